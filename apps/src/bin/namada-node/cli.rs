@@ -38,6 +38,11 @@ pub fn main() -> Result<()> {
                 ledger::rollback(chain_ctx.config.ledger)
                     .wrap_err("Failed to rollback the Namada node")?;
             }
+            cmds::Ledger::DryRunMigrations(_) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                ledger::dry_run_migrations(chain_ctx.config.ledger)
+                    .wrap_err("Failed to dry-run the state migrations")?;
+            }
         },
         cmds::NamadaNode::Config(sub) => match sub {
             cmds::Config::Gen(cmds::ConfigGen) => {
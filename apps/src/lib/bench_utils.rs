@@ -52,7 +52,6 @@ use namada::ibc::core::host::types::path::{
 };
 use namada::ibc::primitives::proto::{Any, Protobuf};
 use namada::ibc::primitives::{Msg, Timestamp as IbcTimestamp};
-use namada::ledger::dry_run_tx;
 use namada::ledger::gas::TxGasMeter;
 use namada::ledger::ibc::storage::{channel_key, connection_key};
 use namada::ledger::native_vp::ibc::get_dummy_header;
@@ -60,6 +59,7 @@ use namada::ledger::queries::{
     Client, EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
 };
 use namada::ledger::storage_api::StorageRead;
+use namada::ledger::{dry_run_tx, estimate_gas};
 use namada::proto::{Code, Data, Section, Signature, Tx};
 use namada::tendermint::Hash;
 use namada::tendermint_rpc::{self};
@@ -710,6 +710,8 @@ impl Client for BenchShell {
 
         if request.path == "/shell/dry_run_tx" {
             dry_run_tx(ctx, &request)
+        } else if request.path == "/shell/estimate_gas" {
+            estimate_gas(ctx, &request)
         } else {
             RPC.handle(ctx, &request)
         }
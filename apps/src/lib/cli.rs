@@ -260,6 +260,7 @@ pub mod cmds {
                 .subcommand(QueryProtocolParameters::def().display_order(5))
                 .subcommand(QueryPgf::def().display_order(5))
                 .subcommand(QueryValidatorState::def().display_order(5))
+                .subcommand(QueryValidatorSetup::def().display_order(5))
                 .subcommand(QueryCommissionRate::def().display_order(5))
                 .subcommand(QueryRewards::def().display_order(5))
                 .subcommand(QueryMetaData::def().display_order(5))
@@ -334,6 +335,8 @@ pub mod cmds {
             let query_pgf = Self::parse_with_ctx(matches, QueryPgf);
             let query_validator_state =
                 Self::parse_with_ctx(matches, QueryValidatorState);
+            let query_validator_setup =
+                Self::parse_with_ctx(matches, QueryValidatorSetup);
             let query_commission =
                 Self::parse_with_ctx(matches, QueryCommissionRate);
             let query_metadata = Self::parse_with_ctx(matches, QueryMetaData);
@@ -385,6 +388,7 @@ pub mod cmds {
                 .or(query_protocol_parameters)
                 .or(query_pgf)
                 .or(query_validator_state)
+                .or(query_validator_setup)
                 .or(query_commission)
                 .or(query_metadata)
                 .or(query_account)
@@ -471,6 +475,7 @@ pub mod cmds {
         QueryProtocolParameters(QueryProtocolParameters),
         QueryPgf(QueryPgf),
         QueryValidatorState(QueryValidatorState),
+        QueryValidatorSetup(QueryValidatorSetup),
         QueryRewards(QueryRewards),
         SignTx(SignTx),
         GenIbcShieldedTransafer(GenIbcShieldedTransafer),
@@ -987,6 +992,7 @@ pub mod cmds {
         Reset(LedgerReset),
         DumpDb(LedgerDumpDb),
         RollBack(LedgerRollBack),
+        DryRunMigrations(LedgerDryRunMigrations),
     }
 
     impl SubCmd for Ledger {
@@ -998,10 +1004,13 @@ pub mod cmds {
                 let reset = SubCmd::parse(matches).map(Self::Reset);
                 let dump_db = SubCmd::parse(matches).map(Self::DumpDb);
                 let rollback = SubCmd::parse(matches).map(Self::RollBack);
+                let dry_run_migrations =
+                    SubCmd::parse(matches).map(Self::DryRunMigrations);
                 let run_until = SubCmd::parse(matches).map(Self::RunUntil);
                 run.or(reset)
                     .or(dump_db)
                     .or(rollback)
+                    .or(dry_run_migrations)
                     .or(run_until)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun(args::LedgerRun {
@@ -1021,6 +1030,7 @@ pub mod cmds {
                 .subcommand(LedgerReset::def())
                 .subcommand(LedgerDumpDb::def())
                 .subcommand(LedgerRollBack::def())
+                .subcommand(LedgerDryRunMigrations::def())
         }
     }
 
@@ -1122,6 +1132,26 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerDryRunMigrations;
+
+    impl SubCmd for LedgerDryRunMigrations {
+        const CMD: &'static str = "dry-run-migrations";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Run the pending migrations of Namada state against the last \
+                 committed state and print the keys they change, without \
+                 persisting anything. The pending migrations are applied \
+                 when the ledger node starts.",
+            )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Config {
         Gen(ConfigGen),
@@ -1786,6 +1816,30 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryValidatorSetup(
+        pub args::QueryValidatorSetup<args::CliTypes>,
+    );
+
+    impl SubCmd for QueryValidatorSetup {
+        const CMD: &'static str = "check-validator-setup";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryValidatorSetup(args::QueryValidatorSetup::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Check if a PoS validator is set up to enter the \
+                     consensus set at the pipeline epoch.",
+                )
+                .add_args::<args::QueryValidatorSetup<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryTransfers(pub args::QueryTransfers<args::CliTypes>);
 
@@ -2983,6 +3037,7 @@ pub mod args {
     pub const ALLOW_DUPLICATE_IP: ArgFlag = flag("allow-duplicate-ip");
     pub const AMOUNT: Arg<token::DenominatedAmount> = arg("amount");
     pub const ARCHIVE_DIR: ArgOpt<PathBuf> = arg_opt("archive-dir");
+    pub const AUTO_WITHDRAW: ArgFlag = flag("auto-withdraw");
     pub const BALANCE_OWNER: ArgOpt<WalletBalanceOwner> = arg_opt("owner");
     pub const BASE_DIR: ArgDefault<PathBuf> = arg_default(
         "base-dir",
@@ -3053,6 +3108,8 @@ pub mod args {
     );
     pub const ETH_SYNC: ArgFlag = flag("sync");
     pub const EXPIRATION_OPT: ArgOpt<DateTimeUtc> = arg_opt("expiration");
+    pub const EXPIRATION_HEIGHT_OPT: ArgOpt<BlockHeight> =
+        arg_opt("expiration-height");
     pub const EMAIL: Arg<String> = arg("email");
     pub const EMAIL_OPT: ArgOpt<String> = EMAIL.opt();
     pub const FEE_UNSHIELD_SPENDING_KEY: ArgOpt<WalletTransferSource> =
@@ -3167,6 +3224,7 @@ pub mod args {
     pub const TRANSFER_SOURCE: Arg<WalletTransferSource> = arg("source");
     pub const TRANSFER_TARGET: Arg<WalletTransferTarget> = arg("target");
     pub const TX_HASH: Arg<String> = arg("tx-hash");
+    pub const TX_MEMO_OPT: ArgOpt<String> = arg_opt("tx-memo");
     pub const THRESHOLD: ArgOpt<u8> = arg_opt("threshold");
     pub const UNSAFE_DONT_ENCRYPT: ArgFlag = flag("unsafe-dont-encrypt");
     pub const UNSAFE_SHOW_SECRET: ArgFlag = flag("unsafe-show-secret");
@@ -4533,6 +4591,7 @@ pub mod args {
                 validator: chain_ctx.get(&self.validator),
                 amount: self.amount,
                 source: self.source.map(|x| chain_ctx.get(&x)),
+                auto_withdraw: self.auto_withdraw,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -4552,12 +4611,14 @@ pub mod args {
                 })
                 .amount;
             let source = SOURCE_OPT.parse(matches);
+            let auto_withdraw = AUTO_WITHDRAW.parse(matches);
             let tx_code_path = PathBuf::from(TX_UNBOND_WASM);
             Self {
                 tx,
                 validator,
                 amount,
                 source,
+                auto_withdraw,
                 tx_code_path,
             }
         }
@@ -4575,6 +4636,10 @@ pub mod args {
                      unbonding from self-bonds, the validator is also the \
                      source.",
                 ))
+                .arg(AUTO_WITHDRAW.def().help(
+                    "Withdraw the unbonded tokens automatically once they \
+                     become withdrawable.",
+                ))
         }
     }
 
@@ -5364,6 +5429,31 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryValidatorSetup<SdkTypes>> for QueryValidatorSetup<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QueryValidatorSetup<SdkTypes> {
+            QueryValidatorSetup::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                validator: ctx.borrow_chain_or_exit().get(&self.validator),
+            }
+        }
+    }
+
+    impl Args for QueryValidatorSetup<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self { query, validator }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(
+                VALIDATOR
+                    .def()
+                    .help("The validator's address whose setup is checked."),
+            )
+        }
+    }
+
     impl CliToSdk<CommissionRateChange<SdkTypes>>
         for CommissionRateChange<CliTypes>
     {
@@ -5949,6 +6039,8 @@ pub mod args {
                 tx_reveal_code_path: self.tx_reveal_code_path,
                 password: self.password,
                 expiration: self.expiration,
+                expiration_height: self.expiration_height,
+                tx_memo: self.tx_memo,
                 chain_id: self
                     .chain_id
                     .or_else(|| Some(ctx.config.ledger.chain_id.clone())),
@@ -6018,6 +6110,18 @@ pub mod args {
                  equivalent:\n2012-12-12T12:12:12Z\n2012-12-12 \
                  12:12:12Z\n2012-  12-12T12:  12:12Z",
             ))
+            .arg(EXPIRATION_HEIGHT_OPT.def().help(
+                "The block height after which the transaction is expected to \
+                 expire. It is converted to an expiration datetime from the \
+                 maximum expected time per block, so the transaction may \
+                 remain valid for some more blocks if they are produced \
+                 faster than that.",
+            ))
+            .arg(TX_MEMO_OPT.def().help(
+                "A memo to attach to the transaction, e.g. the identifier of \
+                 an order that it pays for. The memo is signed along with the \
+                 transaction and it can be at most 256 bytes long.",
+            ))
             .arg(
                 DISPOSABLE_SIGNING_KEY
                     .def()
@@ -6085,6 +6189,8 @@ pub mod args {
             let gas_limit = GAS_LIMIT.parse(matches);
             let wallet_alias_force = WALLET_ALIAS_FORCE.parse(matches);
             let expiration = EXPIRATION_OPT.parse(matches);
+            let expiration_height = EXPIRATION_HEIGHT_OPT.parse(matches);
+            let tx_memo = TX_MEMO_OPT.parse(matches);
             let disposable_signing_key = DISPOSABLE_SIGNING_KEY.parse(matches);
             let signing_keys = SIGNING_KEYS.parse(matches);
             let signatures = SIGNATURES.parse(matches);
@@ -6108,6 +6214,8 @@ pub mod args {
                 fee_unshield,
                 gas_limit,
                 expiration,
+                expiration_height,
+                tx_memo,
                 disposable_signing_key,
                 signing_keys,
                 signatures,
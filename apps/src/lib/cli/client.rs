@@ -394,6 +394,18 @@ impl CliApi {
                         rpc::query_and_print_validator_state(&namada, args)
                             .await;
                     }
+                    Sub::QueryValidatorSetup(QueryValidatorSetup(mut args)) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.query.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_validator_setup(&namada, args)
+                            .await;
+                    }
                     Sub::QueryTransfers(QueryTransfers(mut args)) => {
                         let client = client.unwrap_or_else(|| {
                             C::from_tendermint_address(
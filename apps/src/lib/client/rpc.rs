@@ -48,7 +48,9 @@ use namada::types::storage::{BlockHeight, BlockResults, Epoch, Key, KeySeg};
 use namada::types::token::{Change, MaspDenom};
 use namada::types::{storage, token};
 use namada_sdk::error::{is_pinned_error, Error, PinnedBalanceError};
-use namada_sdk::masp::{Conversions, MaspAmount, MaspChange};
+use namada_sdk::masp::{
+    query_conversion_tree, AssetConversion, Conversions, MaspAmount, MaspChange,
+};
 use namada_sdk::proof_of_stake::types::ValidatorMetaData;
 use namada_sdk::rpc::{
     self, enriched_bonds_and_unbonds, query_epoch, TxResponse,
@@ -1447,16 +1449,23 @@ pub async fn query_protocol_parameters(
         fee_unshielding_descriptions_limit
     );
 
-    let key = param_storage::get_gas_cost_key();
-    let gas_cost_table: BTreeMap<Address, token::Amount> =
-        query_storage_value(context.client(), &key)
-            .await
-            .expect("Parameter should be defined.");
+    let gas_cost_table = rpc::query_fee_gas_costs(context.client())
+        .await
+        .expect("Parameter should be defined.");
     display_line!(context.io(), "{:4}Gas cost table:", "");
     for (token, gas_cost) in gas_cost_table {
         display_line!(context.io(), "{:8}{}: {:?}", "", token, gas_cost);
     }
 
+    let conversion_rates =
+        rpc::query_fee_token_conversion_rates(context.client())
+            .await
+            .expect("Conversion rates should be readable.");
+    display_line!(context.io(), "{:4}Fee token conversion rates:", "");
+    for (token, rate) in conversion_rates {
+        display_line!(context.io(), "{:8}{}: {}", "", token, rate);
+    }
+
     display_line!(context.io(), "PoS parameters");
     let pos_params = query_pos_parameters(context.client()).await;
     display_line!(
@@ -1471,6 +1480,12 @@ pub async fn query_protocol_parameters(
         "",
         pos_params.block_vote_reward
     );
+    display_line!(
+        context.io(),
+        "{:4}Block vote extension reward: {}",
+        "",
+        pos_params.block_vext_reward
+    );
     display_line!(
         context.io(),
         "{:4}Duplicate vote minimum slash rate: {}",
@@ -1574,38 +1589,9 @@ pub async fn query_and_print_unbonds(
     source: &Address,
     validator: &Address,
 ) {
-    let unbonds =
-        query_unbond_with_slashing(context.client(), source, validator).await;
-    let current_epoch = query_epoch(context.client()).await.unwrap();
-
-    let mut total_withdrawable = token::Amount::zero();
-    let mut not_yet_withdrawable = HashMap::<Epoch, token::Amount>::new();
-    for ((_start_epoch, withdraw_epoch), amount) in unbonds.into_iter() {
-        if withdraw_epoch <= current_epoch {
-            total_withdrawable += amount;
-        } else {
-            let withdrawable_amount =
-                not_yet_withdrawable.entry(withdraw_epoch).or_default();
-            *withdrawable_amount += amount;
-        }
-    }
-    if !total_withdrawable.is_zero() {
-        display_line!(
-            context.io(),
-            "Total withdrawable now: {}.",
-            total_withdrawable.to_string_native()
-        );
-    }
-    if !not_yet_withdrawable.is_empty() {
-        display_line!(context.io(), "Current epoch: {current_epoch}.");
-    }
-    for (withdraw_epoch, amount) in not_yet_withdrawable {
-        display_line!(
-            context.io(),
-            "Amount {} withdrawable starting from epoch {withdraw_epoch}.",
-            amount.to_string_native(),
-        );
-    }
+    rpc::query_and_print_unbonds(context, source, validator)
+        .await
+        .unwrap()
 }
 
 pub async fn query_withdrawable_tokens<
@@ -1942,6 +1928,66 @@ pub async fn query_and_print_validator_state(
     }
 }
 
+/// Check and print the setup of a validator at the pipeline epoch
+pub async fn query_and_print_validator_setup(
+    context: &impl Namada,
+    args: args::QueryValidatorSetup,
+) {
+    let validator = args.validator;
+    let report = rpc::check_validator_setup(context.client(), &validator)
+        .await
+        .expect("Validator setup check should succeed.");
+
+    display_line!(
+        context.io(),
+        "Validator {validator} setup at the pipeline epoch {} (current epoch \
+         {}):",
+        report.pipeline_epoch,
+        report.current_epoch,
+    );
+    if let Some(state) = report.pipeline_state {
+        display_line!(context.io(), "{:4}State: {state:?}", "");
+    }
+    display_line!(
+        context.io(),
+        "{:4}Stake: {}",
+        "",
+        report.pipeline_stake.to_string_native()
+    );
+    if let Some(min_consensus_stake) = report.min_consensus_stake {
+        display_line!(
+            context.io(),
+            "{:4}Lowest stake in the full consensus set: {}",
+            "",
+            min_consensus_stake.to_string_native()
+        );
+    }
+    if let Some(CommissionPair {
+        commission_rate,
+        max_commission_change_per_epoch,
+    }) = report.commission.as_ref()
+    {
+        display_line!(
+            context.io(),
+            "{:4}Commission rate: {commission_rate}, max change per epoch: \
+             {max_commission_change_per_epoch}",
+            "",
+        );
+    }
+    if report.is_ready() {
+        display_line!(
+            context.io(),
+            "The validator is set up to enter the consensus set at the \
+             pipeline epoch."
+        );
+    } else {
+        display_line!(context.io(), "Found problems with the setup:");
+        for issue in &report.issues {
+            display_line!(context.io(), "{:4}- {issue}", "");
+        }
+    }
+}
+
 /// Query PoS validator's commission rate information
 pub async fn query_and_print_commission_rate(
     context: &impl Namada,
@@ -2366,18 +2412,21 @@ pub async fn query_conversions(
     let conversions = rpc::query_conversions(context.client())
         .await
         .expect("Conversions should be defined");
-    // Track whether any non-sentinel conversions are found
-    let mut conversions_found = false;
-    for (addr, epoch, amt) in conversions.values() {
-        // If the user has specified any targets, then meet them
-        // If we have a sentinel conversion, then skip printing
-        if matches!(&target_token, Some(target) if target != addr)
-            || matches!(&args.epoch, Some(target) if target != epoch)
-            || amt.is_zero()
-        {
-            continue;
-        }
-        conversions_found = true;
+    // If the user has specified any targets, then meet them
+    let selected = query_conversion_tree(
+        context.client(),
+        target_token.as_ref(),
+        args.epoch,
+    )
+    .await
+    .expect("Conversions should be defined");
+    for AssetConversion {
+        token: addr,
+        epoch,
+        conversion: amt,
+        ..
+    } in &selected
+    {
         // Print the asset to which the conversion applies
         display!(
             context.io(),
@@ -2406,7 +2455,7 @@ pub async fn query_conversions(
         // Allowed conversions are always implicit equations
         display_line!(context.io(), " = 0");
     }
-    if !conversions_found {
+    if selected.is_empty() {
         display_line!(
             context.io(),
             "No conversions found satisfying specified criteria."
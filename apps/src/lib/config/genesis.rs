@@ -1,6 +1,7 @@
 //! The parameters used for the chain's genesis
 
 pub mod chain;
+pub mod cometbft;
 pub mod templates;
 pub mod transactions;
 pub mod utils;
@@ -323,6 +324,7 @@ pub fn make_dev_genesis(
     use namada::types::chain::ChainIdPrefix;
     use namada::types::ethereum_events::EthAddress;
     use namada::types::key::*;
+    use namada::types::voting_power::FractionalVotingPower;
     use namada_sdk::wallet::alias::Alias;
 
     use crate::config::genesis::chain::{finalize, DeriveEstablishedAddress};
@@ -350,6 +352,7 @@ pub fn make_dev_genesis(
     genesis.parameters.eth_bridge_params = Some(templates::EthBridgeParams {
         eth_start_height: Default::default(),
         min_confirmations: Default::default(),
+        vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
         contracts: Contracts {
             native_erc20: wnam(),
             bridge: UpgradeableContract {
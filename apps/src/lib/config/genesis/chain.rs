@@ -342,6 +342,7 @@ impl Finalized {
             tm_votes_per_token,
             block_proposer_reward,
             block_vote_reward,
+            block_vext_reward,
             max_inflation_rate,
             target_staked_ratio,
             duplicate_vote_min_slash_rate,
@@ -350,6 +351,14 @@ impl Finalized {
             validator_stake_threshold,
             liveness_window_check,
             liveness_threshold,
+            past_data_retention_len,
+            validator_sets_retention_len,
+            evidence_max_age_epochs,
+            max_slashed_validators_per_block,
+            validator_deregistration_len,
+            inflation_model,
+            concentration_penalty_threshold,
+            concentration_penalty_rate,
         } = self.parameters.pos_params.clone();
 
         namada::proof_of_stake::parameters::PosParams {
@@ -360,6 +369,7 @@ impl Finalized {
                 tm_votes_per_token,
                 block_proposer_reward,
                 block_vote_reward,
+                block_vext_reward,
                 max_inflation_rate,
                 target_staked_ratio,
                 duplicate_vote_min_slash_rate,
@@ -368,6 +378,14 @@ impl Finalized {
                 validator_stake_threshold,
                 liveness_window_check,
                 liveness_threshold,
+                past_data_retention_len,
+                validator_sets_retention_len,
+                evidence_max_age_epochs,
+                max_slashed_validators_per_block,
+                validator_deregistration_len,
+                inflation_model,
+                concentration_penalty_threshold,
+                concentration_penalty_rate,
             },
             max_proposal_period: self.parameters.gov_params.max_proposal_period,
         }
@@ -407,6 +425,7 @@ impl Finalized {
         if let Some(templates::EthBridgeParams {
             eth_start_height,
             min_confirmations,
+            vext_digest_quorum,
             contracts,
             erc20_whitelist,
         }) = self.parameters.eth_bridge_params.clone()
@@ -414,6 +433,7 @@ impl Finalized {
             Some(namada::ledger::eth_bridge::EthereumBridgeParams {
                 eth_start_height,
                 min_confirmations,
+                vext_digest_quorum,
                 erc20_whitelist,
                 contracts,
             })
@@ -0,0 +1,346 @@
+//! Import of the genesis validator set from the genesis file of an existing
+//! CometBFT (Tendermint) chain, to launch a chain with the same validators.
+//!
+//! The CometBFT genesis only provides the consensus keys of the validators,
+//! so their other keys, addresses and stake must be given in a delegations
+//! TOML file, keyed by the CometBFT address of the validator:
+//!
+//! ```toml
+//! [validators.B2B4A9C3E1F3D5A7B9C1D3E5F7A9B1C3D5E7F9A1]
+//! address = "tnam1..."
+//! tokens = "1000000"
+//! commission_rate = "0.05"
+//! max_commission_rate_change = "0.01"
+//! protocol_key = "tpknam1..."
+//! eth_hot_key = "tpknam1..."
+//! eth_cold_key = "tpknam1..."
+//!
+//! [validators.B2B4A9C3E1F3D5A7B9C1D3E5F7A9B1C3D5E7F9A1.metadata]
+//! email = "validator@example.com"
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use borsh::BorshDeserialize;
+use eyre::{ensure, eyre, Context};
+use namada::core::types::string_encoding::StringEncoded;
+use namada::ledger::pos::types::ValidatorMetaData;
+use namada::ledger::pos::{Dec, GenesisValidator, OwnedPosParams};
+use namada::types::address::Address;
+use namada::types::key::{common, ed25519};
+use namada::types::token::{
+    self, DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES,
+};
+use serde::{Deserialize, Serialize};
+
+use super::utils::read_toml;
+use crate::facade::tendermint::{account, validator, Genesis, PublicKey};
+
+/// The maximum total voting power of a CometBFT validator set
+const MAX_TOTAL_VOTING_POWER: i64 = i64::MAX / 8;
+
+/// The Namada data of the validators imported from a CometBFT genesis
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Delegations {
+    /// The validators keyed by their hex-encoded CometBFT address
+    pub validators: BTreeMap<String, ValidatorDelegation>,
+}
+
+/// The Namada data of a single validator imported from a CometBFT genesis
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidatorDelegation {
+    /// The address of the validator
+    pub address: Address,
+    /// The amount of native tokens bonded to the validator
+    pub tokens: DenominatedAmount,
+    /// Commission rate charged on rewards for delegators (bounded inside
+    /// 0-1)
+    pub commission_rate: Dec,
+    /// Maximum change in commission rate permitted per epoch
+    pub max_commission_rate_change: Dec,
+    pub protocol_key: StringEncoded<common::PublicKey>,
+    pub eth_hot_key: StringEncoded<common::PublicKey>,
+    pub eth_cold_key: StringEncoded<common::PublicKey>,
+    /// Validator metadata
+    pub metadata: ValidatorMetaData,
+}
+
+/// Read the genesis validators from a CometBFT genesis JSON file and a
+/// delegations TOML file
+pub fn read_genesis_validators(
+    cometbft_genesis_path: &Path,
+    delegations_path: &Path,
+    params: &OwnedPosParams,
+) -> eyre::Result<Vec<GenesisValidator>> {
+    let cometbft_genesis =
+        std::fs::read(cometbft_genesis_path).wrap_err_with(|| {
+            format!(
+                "Couldn't read the CometBFT genesis file from {}",
+                cometbft_genesis_path.to_string_lossy()
+            )
+        })?;
+    let cometbft_genesis: Genesis<serde_json::Value> =
+        serde_json::from_slice(&cometbft_genesis).wrap_err_with(|| {
+            format!(
+                "Couldn't parse the CometBFT genesis JSON from {}",
+                cometbft_genesis_path.to_string_lossy()
+            )
+        })?;
+    let delegations: Delegations = read_toml(delegations_path, "Delegations")?;
+    genesis_validators(&cometbft_genesis.validators, delegations, params)
+}
+
+/// Build the genesis validators from the validators of a CometBFT genesis
+/// and their delegations. Checks that every validator has a delegation,
+/// that the keys and addresses of the validators are unique and that their
+/// stake adds up to a valid CometBFT validator set.
+pub fn genesis_validators(
+    cometbft_validators: &[validator::Info],
+    delegations: Delegations,
+    params: &OwnedPosParams,
+) -> eyre::Result<Vec<GenesisValidator>> {
+    let mut delegations = delegations
+        .validators
+        .into_iter()
+        .map(|(id, delegation)| {
+            let id = account::Id::from_str(&id).map_err(|err| {
+                eyre!("Invalid CometBFT validator address {id}: {err}")
+            })?;
+            Ok((id, delegation))
+        })
+        .collect::<eyre::Result<BTreeMap<_, _>>>()?;
+
+    let mut addresses = HashSet::new();
+    let mut keys = HashSet::new();
+    let mut total_stake = token::Amount::zero();
+    let mut total_voting_power: i64 = 0;
+    let mut validators = Vec::with_capacity(cometbft_validators.len());
+    for info in cometbft_validators {
+        let consensus_key =
+            consensus_key(&info.pub_key).wrap_err_with(|| {
+                format!("Invalid consensus key of validator {}", info.address)
+            })?;
+        let ValidatorDelegation {
+            address,
+            tokens,
+            commission_rate,
+            max_commission_rate_change,
+            protocol_key,
+            eth_hot_key,
+            eth_cold_key,
+            metadata,
+        } = delegations.remove(&info.address).ok_or_else(|| {
+            eyre!("Missing the delegation of validator {}", info.address)
+        })?;
+
+        ensure!(
+            addresses.insert(address.clone()),
+            "The address {address} is used by more than one validator"
+        );
+        for key in [
+            &consensus_key,
+            &protocol_key.raw,
+            &eth_hot_key.raw,
+            &eth_cold_key.raw,
+        ] {
+            ensure!(
+                keys.insert(key.clone()),
+                "The key {key} is used more than once by the validators"
+            );
+        }
+        for (name, key) in
+            [("hot", &eth_hot_key.raw), ("cold", &eth_cold_key.raw)]
+        {
+            ensure!(
+                matches!(key, common::PublicKey::Secp256k1(_)),
+                "The Ethereum {name} key of validator {address} must be a \
+                 Secp256k1 key"
+            );
+        }
+
+        let tokens = tokens
+            .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+            .map_err(|err| {
+                eyre!("Invalid amount of tokens of validator {address}: {err}")
+            })?
+            .amount;
+        ensure!(
+            !tokens.is_zero(),
+            "Validator {address} must have a non-zero stake"
+        );
+        total_stake = total_stake.checked_add(tokens).ok_or_else(|| {
+            eyre!("The total stake of the validators overflows")
+        })?;
+        let voting_power = params.tm_votes_per_token
+            * u128::try_from(tokens).map_err(|_| {
+                eyre!("The stake of validator {address} is too large")
+            })?;
+        let voting_power = voting_power
+            .to_uint()
+            .and_then(|power| i64::try_from(power).ok())
+            .ok_or_else(|| {
+                eyre!("The voting power of validator {address} overflows")
+            })?;
+        total_voting_power = total_voting_power
+            .checked_add(voting_power)
+            .filter(|power| *power <= MAX_TOTAL_VOTING_POWER)
+            .ok_or_else(|| {
+                eyre!(
+                    "The total voting power of the validators exceeds the \
+                     CometBFT maximum of {MAX_TOTAL_VOTING_POWER}"
+                )
+            })?;
+        if voting_power != info.power.value() as i64 {
+            tracing::warn!(
+                "The voting power {voting_power} of validator {address} \
+                 doesn't match its power {} in the CometBFT genesis",
+                info.power.value()
+            );
+        }
+
+        validators.push(GenesisValidator {
+            address,
+            tokens,
+            consensus_key,
+            protocol_key: protocol_key.raw,
+            eth_cold_key: eth_cold_key.raw,
+            eth_hot_key: eth_hot_key.raw,
+            commission_rate,
+            max_commission_rate_change,
+            metadata,
+        });
+    }
+
+    if let Some(id) = delegations.keys().next() {
+        return Err(eyre!(
+            "Found a delegation of {id}, which is not a validator of the \
+             CometBFT genesis"
+        ));
+    }
+    ensure!(
+        !validators.is_empty(),
+        "The CometBFT genesis has no validators"
+    );
+    Ok(validators)
+}
+
+/// Convert the consensus key of a CometBFT validator
+fn consensus_key(pub_key: &PublicKey) -> eyre::Result<common::PublicKey> {
+    match pub_key {
+        PublicKey::Ed25519(_) => {
+            let pk = ed25519::PublicKey::try_from_slice(&pub_key.to_bytes())?;
+            Ok(common::PublicKey::Ed25519(pk))
+        }
+        _ => Err(eyre!("The consensus key must be an Ed25519 key")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use namada::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+    use namada::types::key::testing::{gen_keypair, keypair_1, keypair_2};
+    use namada::types::key::{secp256k1, RefTo};
+
+    use super::*;
+    use crate::facade::tendermint::vote;
+
+    /// The CometBFT validators with the given consensus keys
+    fn cometbft_validators(keys: &[common::PublicKey]) -> Vec<validator::Info> {
+        keys.iter()
+            .map(|pk| {
+                validator::Info::new(
+                    pk.clone().into(),
+                    vote::Power::try_from(10_u64).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    /// The delegation of a validator with freshly generated keys
+    fn delegation(address: Address, tokens: u64) -> ValidatorDelegation {
+        let eth_key = || {
+            StringEncoded::new(common::PublicKey::Secp256k1(
+                gen_keypair::<secp256k1::SigScheme>().ref_to(),
+            ))
+        };
+        ValidatorDelegation {
+            address,
+            tokens: DenominatedAmount::native(token::Amount::native_whole(
+                tokens,
+            )),
+            commission_rate: Dec::new(5, 2).unwrap(),
+            max_commission_rate_change: Dec::new(1, 2).unwrap(),
+            protocol_key: StringEncoded::new(common::PublicKey::Ed25519(
+                gen_keypair::<ed25519::SigScheme>().ref_to(),
+            )),
+            eth_hot_key: eth_key(),
+            eth_cold_key: eth_key(),
+            metadata: ValidatorMetaData::default(),
+        }
+    }
+
+    /// Test that the validators of a CometBFT genesis are imported with their
+    /// delegations, which must match the validators one to one
+    #[test]
+    fn test_import_genesis_validators() {
+        let params = OwnedPosParams::default();
+        let consensus_keys = [keypair_1().ref_to(), keypair_2().ref_to()];
+        let cometbft_validators = cometbft_validators(&consensus_keys);
+        let ids: Vec<String> = cometbft_validators
+            .iter()
+            .map(|info| info.address.to_string())
+            .collect();
+
+        let mut delegations = Delegations::default();
+        delegations
+            .validators
+            .insert(ids[0].clone(), delegation(established_address_1(), 100));
+        // A missing delegation
+        assert!(genesis_validators(
+            &cometbft_validators,
+            delegations.clone(),
+            &params
+        )
+        .is_err());
+
+        delegations
+            .validators
+            .insert(ids[1].clone(), delegation(established_address_2(), 200));
+        // Delegations are round-tripped through TOML
+        let delegations: Delegations =
+            toml::from_str(&toml::to_string(&delegations).unwrap()).unwrap();
+        let validators = genesis_validators(
+            &cometbft_validators,
+            delegations.clone(),
+            &params,
+        )
+        .unwrap();
+        assert_eq!(validators.len(), 2);
+        assert_eq!(validators[0].consensus_key, consensus_keys[0]);
+        assert_eq!(validators[0].address, established_address_1());
+        assert_eq!(validators[0].tokens, token::Amount::native_whole(100));
+        assert_eq!(validators[1].consensus_key, consensus_keys[1]);
+        assert_eq!(validators[1].tokens, token::Amount::native_whole(200));
+
+        // A delegation of a validator not in the CometBFT genesis
+        let mut extra = delegations.clone();
+        extra.validators.insert(
+            account::Id::new([0; 20]).to_string(),
+            delegation(established_address_3(), 300),
+        );
+        assert!(
+            genesis_validators(&cometbft_validators, extra, &params).is_err()
+        );
+
+        // The same address used by two validators
+        let mut duplicate = delegations;
+        duplicate.validators.get_mut(&ids[1]).unwrap().address =
+            established_address_1();
+        assert!(genesis_validators(&cometbft_validators, duplicate, &params)
+            .is_err());
+    }
+}
@@ -7,14 +7,17 @@ use std::path::Path;
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada::core::types::{ethereum_structs, token};
 use namada::eth_bridge::parameters::{
-    Contracts, Erc20WhitelistEntry, MinimumConfirmations,
+    default_vext_digest_quorum, Contracts, Erc20WhitelistEntry,
+    MinimumConfirmations,
 };
+use namada::proof_of_stake::inflation::InflationModelParams;
 use namada::types::address::Address;
 use namada::types::chain::ProposalBytes;
 use namada::types::dec::Dec;
 use namada::types::token::{
     Amount, DenominatedAmount, Denomination, NATIVE_MAX_DECIMAL_PLACES,
 };
+use namada::types::voting_power::FractionalVotingPower;
 use serde::{Deserialize, Serialize};
 
 use super::transactions::{self, Transactions};
@@ -388,6 +391,8 @@ pub struct PosParams {
     pub block_proposer_reward: Dec,
     /// Reward for voting on a block.
     pub block_vote_reward: Dec,
+    /// Reward for the Ethereum bridge vote extensions included in a block.
+    pub block_vext_reward: Dec,
     /// Maximum staking APY
     pub max_inflation_rate: Dec,
     /// Target ratio of staked NAM tokens to total NAM tokens
@@ -410,6 +415,30 @@ pub struct PosParams {
     /// The minimum required activity of consensus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The number of epochs, on top of the slash processing delay, for which
+    /// historical PoS data is retained before it gets purged
+    pub past_data_retention_len: u64,
+    /// The number of past epochs for which the validator sets are kept in
+    /// storage (the consensus validator sets are kept for at least the max
+    /// proposal period on top)
+    pub validator_sets_retention_len: u64,
+    /// The maximum age, in epochs, of the evidence of a misbehavior for which
+    /// a validator can still be slashed
+    pub evidence_max_age_epochs: u64,
+    /// The maximum number of slashed validators whose slashes are processed
+    /// in a single block (zero for no limit)
+    pub max_slashed_validators_per_block: u64,
+    /// The number of epochs for which a validator must have had no stake and
+    /// no bonds before it gets deregistered (zero to never deregister)
+    pub validator_deregistration_len: u64,
+    /// The model used to compute the PoS inflation
+    pub inflation_model: InflationModelParams,
+    /// The share of the total consensus stake above which a validator's
+    /// consensus set reward is reduced (one for no reduction)
+    pub concentration_penalty_threshold: Dec,
+    /// The rate at which the consensus set reward of a validator above the
+    /// concentration penalty threshold is reduced
+    pub concentration_penalty_rate: Dec,
 }
 
 #[derive(
@@ -480,6 +509,11 @@ pub struct EthBridgeParams {
     /// Minimum number of confirmations needed to trust an Ethereum branch.
     /// This must be at least one.
     pub min_confirmations: MinimumConfirmations,
+    /// The fraction of the total voting power that the validators who signed
+    /// the vote extensions in a digest must exceed, for a block proposal
+    /// including the digest to be accepted. Defaults to 2/3.
+    #[serde(default = "default_vext_digest_quorum")]
+    pub vext_digest_quorum: FractionalVotingPower,
     /// List of ERC20 token types whitelisted at genesis time.
     pub erc20_whitelist: Vec<Erc20WhitelistEntry>,
     /// The addresses of the Ethereum contracts that need to be directly known
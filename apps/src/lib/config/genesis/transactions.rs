@@ -80,6 +80,8 @@ fn get_tx_args(use_device: bool) -> TxArgs {
         fee_unshield: None,
         gas_limit: Default::default(),
         expiration: None,
+        expiration_height: None,
+        tx_memo: None,
         disposable_signing_key: false,
         chain_id: None,
         signing_keys: vec![],
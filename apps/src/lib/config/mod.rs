@@ -46,6 +46,11 @@ pub struct Config {
 pub struct ValidatorLocalConfig {
     pub accepted_gas_tokens:
         HashMap<namada::types::address::Address, namada::types::token::Amount>,
+    /// The percentage of the block space and gas available to wrapper txs
+    /// that is reserved to PoS and governance txs in the blocks proposed
+    /// by this validator. When not set, defaults to 10%.
+    #[serde(default)]
+    pub priority_block_space_percent: Option<u64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -1,5 +1,7 @@
 pub mod eth_events {
+    use std::collections::BTreeMap;
     use std::fmt::Debug;
+    use std::num::NonZeroU64;
     use std::str::FromStr;
 
     use ethbridge_bridge_events::{
@@ -112,6 +114,34 @@ pub mod eth_events {
             })
         }
 
+        /// Raise the number of confirmations required by this event to the
+        /// highest minimum confirmations configured for the ERC20 tokens
+        /// it transfers, if any of them requires more confirmations.
+        pub fn require_erc20_confirmations(
+            &mut self,
+            erc20_min_confirmations: &BTreeMap<EthAddress, NonZeroU64>,
+        ) {
+            let assets: Vec<&EthAddress> = match &self.event {
+                EthereumEvent::TransfersToNamada { transfers, .. } => {
+                    transfers.iter().map(|transfer| &transfer.asset).collect()
+                }
+                EthereumEvent::TransfersToEthereum { transfers, .. } => {
+                    transfers.iter().map(|transfer| &transfer.asset).collect()
+                }
+                _ => return,
+            };
+            for asset in assets {
+                if let Some(min_confirmations) =
+                    erc20_min_confirmations.get(asset)
+                {
+                    self.confirmations = self
+                        .confirmations
+                        .clone()
+                        .max(min_confirmations.get().into());
+                }
+            }
+        }
+
         /// Check if the minimum number of confirmations has been
         /// reached at the input block height.
         pub fn is_confirmed(&self, height: &Uint256) -> bool {
@@ -15,7 +15,9 @@ use namada::eth_bridge::ethers::providers::{Http, Middleware, Provider};
 use namada::eth_bridge::oracle::config::Config;
 use namada::types::control_flow::time::{Constant, Duration, Instant, Sleep};
 use namada::types::ethereum_events::EthereumEvent;
+use namada::types::time::DateTimeUtc;
 use namada_sdk::eth_bridge::{eth_syncing_status_timeout, SyncStatus};
+use namada_sdk::queries::OracleConnectionState;
 use num256::Uint256;
 use thiserror::Error;
 use tokio::sync::mpsc::error::TryRecvError;
@@ -44,6 +46,11 @@ pub enum Error {
         "Need more confirmations for oracle to continue processing blocks."
     )]
     MoreConfirmations,
+    #[error(
+        "The Ethereum chain was reorganized while the oracle was checking for \
+         events."
+    )]
+    Reorg,
     #[error("The Ethereum oracle timed out")]
     Timeout,
 }
@@ -191,6 +198,8 @@ pub struct Oracle<C = Provider<Http>> {
     sender: BoundedSender<EthereumEvent>,
     /// The most recently processed block is recorded here.
     last_processed_block: last_processed_block::Sender,
+    /// A channel for publishing the health of the oracle.
+    status: status::Sender,
     /// How long the oracle should wait between checking blocks
     backoff: Duration,
     /// How long the oracle should allow the fullnode to be unresponsive
@@ -206,6 +215,7 @@ impl<C: RpcClient> Oracle<C> {
         client_or_url: Either<C, &str>,
         sender: BoundedSender<EthereumEvent>,
         last_processed_block: last_processed_block::Sender,
+        status: status::Sender,
         backoff: Duration,
         ceiling: Duration,
         control: control::Receiver,
@@ -219,10 +229,20 @@ impl<C: RpcClient> Oracle<C> {
             backoff,
             ceiling,
             last_processed_block,
+            status,
             control,
         }
     }
 
+    /// Publish a heartbeat, reporting the given state of the
+    /// connection to the Ethereum full node.
+    fn heartbeat(&self, connection: OracleConnectionState) {
+        self.status.send_modify(|heartbeat| {
+            heartbeat.connection = connection;
+            heartbeat.timestamp = Some(DateTimeUtc::now());
+        });
+    }
+
     /// Send a series of [`EthereumEvent`]s to the Namada
     /// ledger. Returns a boolean indicating that all sent
     /// successfully. If false is returned, the receiver
@@ -256,6 +276,7 @@ impl<C: RpcClient> Oracle<C> {
     /// If the bridge has been deactivated, block here until a new
     /// config is passed that reactivates the bridge
     async fn wait_on_reactivation(&mut self) -> Config {
+        self.heartbeat(OracleConnectionState::Deactivated);
         loop {
             if let Some(Command::UpdateConfig(c)) = self.control.recv().await {
                 if c.active {
@@ -285,6 +306,7 @@ pub fn run_oracle<C: RpcClient>(
     sender: BoundedSender<EthereumEvent>,
     control: control::Receiver,
     last_processed_block: last_processed_block::Sender,
+    status: status::Sender,
     spawner: &mut AbortableSpawner,
 ) -> tokio::task::JoinHandle<()> {
     let url = url.as_ref().to_owned();
@@ -299,6 +321,7 @@ pub fn run_oracle<C: RpcClient>(
                         Either::Right(&url),
                         sender,
                         last_processed_block,
+                        status,
                         DEFAULT_BACKOFF,
                         DEFAULT_CEILING,
                         control,
@@ -377,6 +400,13 @@ pub(crate) async fn try_process_eth_events<C: RpcClient>(
                         block = ?next_block_to_process,
                         "Error while trying to process Ethereum block"
                     );
+                    oracle.heartbeat(match error {
+                        Error::FallenBehind => OracleConnectionState::Syncing,
+                        Error::MoreConfirmations | Error::Reorg => {
+                            OracleConnectionState::Connected
+                        }
+                        _ => OracleConnectionState::Unreachable,
+                    });
                     ProcessEventAction::ContinuePollingEvents
                 } else {
                     tracing::error!(
@@ -384,10 +414,14 @@ pub(crate) async fn try_process_eth_events<C: RpcClient>(
                         block = ?next_block_to_process,
                         "The Ethereum oracle has disconnected"
                     );
+                    oracle.heartbeat(OracleConnectionState::Halted);
                     ProcessEventAction::HaltOracle
                 }
             },
-            |()| ProcessEventAction::ProceedToNextBlock,
+            |()| {
+                oracle.heartbeat(OracleConnectionState::Connected);
+                ProcessEventAction::ProceedToNextBlock
+            },
         )
 }
 
@@ -440,6 +474,7 @@ async fn run_oracle_aux<C: RpcClient>(mut oracle: Oracle<C>) {
         .await;
 
         if hints::unlikely(res.is_err()) {
+            oracle.heartbeat(OracleConnectionState::Halted);
             break;
         }
 
@@ -464,8 +499,7 @@ async fn process_events_in_block<C: RpcClient>(
     oracle: &Oracle<C>,
     config: &Config,
 ) -> Result<(), Error> {
-    let mut queue: Vec<PendingEvent> = vec![];
-    let pending = &mut queue;
+    let mut confirmed_batches = vec![];
     // update the latest block height
 
     let last_processed_block_ref = oracle.last_processed_block.borrow();
@@ -481,6 +515,9 @@ async fn process_events_in_block<C: RpcClient>(
         SyncStatus::Syncing => return Err(Error::FallenBehind),
     }
     .into();
+    oracle.status.send_modify(|heartbeat| {
+        heartbeat.latest_block = Some(latest_block.clone());
+    });
     let minimum_latest_block =
         block_to_process.clone() + config.min_confirmations.into();
     if minimum_latest_block > latest_block {
@@ -534,7 +571,12 @@ async fn process_events_in_block<C: RpcClient>(
                         &log,
                         u64::from(config.min_confirmations).into(),
                     ) {
-                        Ok(event) => Some(event),
+                        Ok(mut event) => {
+                            event.require_erc20_confirmations(
+                                &config.erc20_min_confirmations,
+                            );
+                            Some(event)
+                        }
                         Err(error) => {
                             tracing::error!(
                                 ?error,
@@ -550,29 +592,40 @@ async fn process_events_in_block<C: RpcClient>(
                 })
                 .collect()
         };
-        pending.append(&mut events);
-        if !pending.is_empty() {
-            tracing::info!(
+        // hold off on this block if some of its events transfer ERC20
+        // tokens that require more confirmations than the block has
+        if events
+            .iter()
+            .any(|event| !event.is_confirmed(&latest_block))
+        {
+            tracing::debug!(
                 ?block_to_process,
+                ?latest_block,
                 ?addr,
                 ?sig,
-                pending = pending.len(),
-                "There are Ethereum events pending"
+                "Waiting for Ethereum events transferring ERC20 tokens to \
+                 reach their minimum number of confirmations"
             );
+            return Err(Error::MoreConfirmations);
         }
-        let confirmed = process_queue(&latest_block, pending);
+        let confirmed = process_queue(&latest_block, &mut events);
         if !confirmed.is_empty() {
             tracing::info!(
                 ?block_to_process,
                 ?addr,
                 ?sig,
-                pending = pending.len(),
                 confirmed = confirmed.len(),
                 min_confirmations = ?config.min_confirmations,
                 "Some events that have reached the minimum number of \
                  confirmations and will be sent onwards"
             );
         }
+        confirmed_batches.push((sig, addr, confirmed));
+    }
+    // nothing is sent to the ledger before making sure the block
+    // we just checked hasn't been reorganized in the meantime
+    reconfirm_latest_block(oracle, last_processed_block, &latest_block).await?;
+    for (sig, addr, confirmed) in confirmed_batches {
         if !oracle.send(confirmed).await {
             return Err(Error::Channel(sig.into(), addr));
         }
@@ -580,6 +633,43 @@ async fn process_events_in_block<C: RpcClient>(
     Ok(())
 }
 
+/// Query the latest Ethereum block height once more, after having
+/// checked a block for events, to detect chain reorganizations that
+/// took place in the meantime.
+///
+/// If the chain has been rewound below `latest_block`, the confirmations
+/// of the events we found may no longer hold, or the events may even have
+/// been orphaned, so the block must be checked again from scratch.
+async fn reconfirm_latest_block<C: RpcClient>(
+    oracle: &Oracle<C>,
+    last_processed_block: Option<&ethereum_structs::BlockHeight>,
+    latest_block: &ethereum_structs::BlockHeight,
+) -> Result<(), Error> {
+    let deadline = Instant::now() + oracle.ceiling;
+    let reconfirmed_block: ethereum_structs::BlockHeight = match oracle
+        .client
+        .syncing(last_processed_block, oracle.backoff, deadline)
+        .await?
+    {
+        SyncStatus::AtHeight(height) => height,
+        SyncStatus::Syncing => return Err(Error::FallenBehind),
+    }
+    .into();
+    if &reconfirmed_block < latest_block {
+        tracing::warn!(
+            ?latest_block,
+            ?reconfirmed_block,
+            "The Ethereum chain was reorganized while checking for bridge \
+             events"
+        );
+        oracle.status.send_modify(|heartbeat| {
+            heartbeat.latest_block = Some(reconfirmed_block);
+        });
+        return Err(Error::Reorg);
+    }
+    Ok(())
+}
+
 /// Check which events in the queue have reached their
 /// required number of confirmations and remove them
 /// from the queue of pending events
@@ -616,8 +706,25 @@ pub mod last_processed_block {
     }
 }
 
+pub mod status {
+    //! Functionality to do with publishing the health of the oracle.
+    use namada_sdk::queries::OracleHeartbeat;
+    use tokio::sync::watch;
+
+    pub type Sender = watch::Sender<OracleHeartbeat>;
+    pub type Receiver = watch::Receiver<OracleHeartbeat>;
+
+    /// Construct a [`tokio::sync::watch`] channel to publish the heartbeats
+    /// of the oracle. Until the oracle attempts to process its first block,
+    /// this will hold a default heartbeat, awaiting configuration.
+    pub fn channel() -> (Sender, Receiver) {
+        watch::channel(OracleHeartbeat::default())
+    }
+}
+
 #[cfg(test)]
 mod test_oracle {
+    use std::collections::BTreeMap;
     use std::num::NonZeroU64;
 
     use ethbridge_bridge_events::{TransferToChainFilter, TransferToErcFilter};
@@ -673,6 +780,7 @@ mod test_oracle {
         let (blocks_processed_recv, client) = Web3Client::setup();
         let (eth_sender, eth_receiver) = tokio::sync::mpsc::channel(1000);
         let (last_processed_block_sender, _) = last_processed_block::channel();
+        let (status_sender, _) = status::channel();
         let (control_sender, control_receiver) = control::channel();
         let controller = client.controller();
         TestPackage {
@@ -680,6 +788,7 @@ mod test_oracle {
                 client,
                 sender: eth_sender,
                 last_processed_block: last_processed_block_sender,
+                status: status_sender,
                 // backoff should be short for tests so that they run faster
                 backoff: Duration::from_millis(5),
                 ceiling: DEFAULT_CEILING,
@@ -954,6 +1063,228 @@ mod test_oracle {
         oracle.await.expect("Test failed");
     }
 
+    /// Test that events transferring ERC20 tokens with their own minimum
+    /// number of confirmations are only sent once they reach it
+    #[tokio::test]
+    async fn test_erc20_min_confirmations() {
+        let TestPackage {
+            oracle,
+            mut eth_recv,
+            controller,
+            blocks_processed_recv: _processed,
+            mut control_sender,
+        } = setup();
+        let min_confirmations = 100;
+        let config = Config {
+            min_confirmations: NonZeroU64::try_from(min_confirmations)
+                .expect("Test wasn't set up correctly"),
+            erc20_min_confirmations: BTreeMap::from([(
+                EthAddress([0; 20]),
+                NonZeroU64::try_from(150)
+                    .expect("Test wasn't set up correctly"),
+            )]),
+            ..Config::default()
+        };
+        let oracle =
+            start_with_default_config(oracle, &mut control_sender, config)
+                .await;
+        // Increase height above the configured minimum confirmations
+        controller.apply_cmd(TestCmd::NewHeight(min_confirmations.into()));
+
+        // confirmed after 150 blocks, due to the transferred asset
+        let gas_payer = gen_established_address();
+        let event = TransferToErcFilter {
+            transfers: vec![Erc20Transfer {
+                amount: 0.into(),
+                from: H160([0; 20]),
+                to: H160([1; 20]),
+                data_digest: [0; 32],
+            }],
+            relayer_address: gas_payer.to_string(),
+            nonce: 0.into(),
+        }
+        .get_log();
+        let (sender, seen) = channel();
+        controller.apply_cmd(TestCmd::NewEvent {
+            event_type: event_signature::<TransferToErcFilter>(),
+            log: event,
+            height: 100,
+            seen: sender,
+        });
+
+        // increase block height so the event reaches the bridge's minimum
+        // number of confirmations, but not the asset's
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(200u32)));
+        seen.await.expect("Test failed");
+        let mut time = std::time::Duration::from_secs(1);
+        while time > std::time::Duration::from_millis(10) {
+            assert!(eth_recv.try_recv().is_err());
+            time -= std::time::Duration::from_millis(10);
+        }
+
+        // increase block height so the event is confirmed
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(250u32)));
+        let event = eth_recv.recv().await.expect("Test failed");
+        if let EthereumEvent::TransfersToEthereum { transfers, .. } = event {
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].asset, EthAddress([0; 20]));
+        } else {
+            panic!("Test failed, {:?}", event);
+        }
+
+        drop(eth_recv);
+        oracle.await.expect("Test failed");
+    }
+
+    /// Test that events orphaned by a reorg of the Ethereum chain are
+    /// never sent, while the events replayed on the new fork are sent
+    /// exactly once
+    #[tokio::test]
+    async fn test_reorg_orphans_events() {
+        let TestPackage {
+            oracle,
+            mut eth_recv,
+            controller,
+            blocks_processed_recv: _processed,
+            mut control_sender,
+        } = setup();
+        let min_confirmations = 100;
+        let config = Config {
+            min_confirmations: NonZeroU64::try_from(min_confirmations)
+                .expect("Test wasn't set up correctly"),
+            ..Config::default()
+        };
+        let oracle =
+            start_with_default_config(oracle, &mut control_sender, config)
+                .await;
+        // Increase height above the configured minimum confirmations
+        controller.apply_cmd(TestCmd::NewHeight(min_confirmations.into()));
+
+        // an event that will be orphaned
+        let orphaned_event = TransferToChainFilter {
+            nonce: 0.into(),
+            transfers: vec![],
+            confirmations: 100.into(),
+        }
+        .get_log();
+        let (sender, seen_orphaned) = channel();
+        controller.apply_cmd(TestCmd::NewEvent {
+            event_type: event_signature::<TransferToChainFilter>(),
+            log: orphaned_event,
+            height: 150,
+            seen: sender,
+        });
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(180u32)));
+
+        // rewind the chain below the block of the event, and replay
+        // a different event on the new fork
+        controller.apply_cmd(TestCmd::Reorg { depth: 50 });
+        assert!(seen_orphaned.await.is_err());
+        let replayed_event = TransferToChainFilter {
+            nonce: 1.into(),
+            transfers: vec![],
+            confirmations: 100.into(),
+        }
+        .get_log();
+        let (sender, _recv) = channel();
+        controller.apply_cmd(TestCmd::NewEvent {
+            event_type: event_signature::<TransferToChainFilter>(),
+            log: replayed_event,
+            height: 140,
+            seen: sender,
+        });
+
+        // only the replayed event should be received
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(250u32)));
+        let event = eth_recv.recv().await.expect("Test failed");
+        if let EthereumEvent::TransfersToNamada { nonce, .. } = event {
+            assert_eq!(nonce, 1.into());
+        } else {
+            panic!("Test failed, {:?}", event);
+        }
+        let mut time = std::time::Duration::from_secs(1);
+        while time > std::time::Duration::from_millis(10) {
+            assert!(eth_recv.try_recv().is_err());
+            time -= std::time::Duration::from_millis(10);
+        }
+
+        drop(eth_recv);
+        oracle.await.expect("Test failed");
+    }
+
+    /// Test that events are not sent if the Ethereum chain is reorganized
+    /// while the oracle checks their block, such that they lose some of
+    /// their confirmations, and that they are sent exactly once after
+    /// being confirmed again
+    #[tokio::test]
+    async fn test_reorg_while_checking_block() {
+        let TestPackage {
+            oracle,
+            mut eth_recv,
+            controller,
+            blocks_processed_recv: _processed,
+            mut control_sender,
+        } = setup();
+        let min_confirmations = 100;
+        let config = Config {
+            min_confirmations: NonZeroU64::try_from(min_confirmations)
+                .expect("Test wasn't set up correctly"),
+            ..Config::default()
+        };
+        let oracle =
+            start_with_default_config(oracle, &mut control_sender, config)
+                .await;
+        // Increase height above the configured minimum confirmations
+        controller.apply_cmd(TestCmd::NewHeight(min_confirmations.into()));
+
+        // the chain is rewound by 60 blocks, while the oracle
+        // checks the block of the event
+        controller.apply_cmd(TestCmd::ReorgAtBlock {
+            block: 100,
+            depth: 60,
+        });
+        let new_event = TransferToChainFilter {
+            nonce: 0.into(),
+            transfers: vec![],
+            confirmations: 100.into(),
+        }
+        .get_log();
+        let (sender, seen) = channel();
+        controller.apply_cmd(TestCmd::NewEvent {
+            event_type: event_signature::<TransferToChainFilter>(),
+            log: new_event,
+            height: 100,
+            seen: sender,
+        });
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(200u32)));
+
+        // the event was seen, but it no longer has enough
+        // confirmations to be sent
+        seen.await.expect("Test failed");
+        let mut time = std::time::Duration::from_secs(1);
+        while time > std::time::Duration::from_millis(10) {
+            assert!(eth_recv.try_recv().is_err());
+            time -= std::time::Duration::from_millis(10);
+        }
+
+        // the event is confirmed once again
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(200u32)));
+        let event = eth_recv.recv().await.expect("Test failed");
+        if let EthereumEvent::TransfersToNamada { nonce, .. } = event {
+            assert_eq!(nonce, 0.into());
+        } else {
+            panic!("Test failed, {:?}", event);
+        }
+        let mut time = std::time::Duration::from_secs(1);
+        while time > std::time::Duration::from_millis(10) {
+            assert!(eth_recv.try_recv().is_err());
+            time -= std::time::Duration::from_millis(10);
+        }
+
+        drop(eth_recv);
+        oracle.await.expect("Test failed");
+    }
+
     /// Test that Ethereum blocks are processed in sequence up to the latest
     /// block that has reached the minimum number of confirmations
     #[tokio::test]
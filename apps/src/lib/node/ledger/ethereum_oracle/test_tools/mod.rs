@@ -93,6 +93,24 @@ pub mod mock_web3_client {
             height: u32,
             seen: Sender<()>,
         },
+        /// Rewind the Ethereum chain by the given number of blocks.
+        ///
+        /// Events found in orphaned blocks are forgotten, and events
+        /// that had yet to be seen in those blocks are dropped. Different
+        /// events can be replayed on the new fork with
+        /// [`TestCmd::NewEvent`].
+        Reorg {
+            depth: u32,
+        },
+        /// Rewind the Ethereum chain by the given number of blocks,
+        /// once the oracle checks the given block for events.
+        ///
+        /// This simulates a reorg taking place while the oracle is
+        /// processing a block.
+        ReorgAtBlock {
+            block: u32,
+            depth: u32,
+        },
     }
 
     /// The type of events supported
@@ -121,6 +139,10 @@ pub mod mock_web3_client {
                     height,
                     seen,
                 } => oracle.events.push((ty, log, height, seen)),
+                TestCmd::Reorg { depth } => oracle.reorg(depth),
+                TestCmd::ReorgAtBlock { block, depth } => {
+                    oracle.scheduled_reorg = Some((block.into(), depth))
+                }
             }
         }
     }
@@ -140,8 +162,39 @@ pub mod mock_web3_client {
         active: bool,
         latest_block_height: Uint256,
         events: Vec<(MockEventType, ethabi::RawLog, u32, Sender<()>)>,
+        /// Logs already returned for some block, which are returned again
+        /// if the oracle checks the same block more than once.
+        returned_logs: Vec<(MockEventType, ethabi::RawLog, Uint256)>,
         blocks_processed: UnboundedSender<Uint256>,
         last_block_processed: Option<Uint256>,
+        /// A reorg to be simulated once the oracle checks some block.
+        scheduled_reorg: Option<(Uint256, u32)>,
+    }
+
+    impl Web3ClientInner {
+        /// Rewind the chain by `depth` blocks, orphaning all the
+        /// events above the new latest block height.
+        fn reorg(&mut self, depth: u32) {
+            let depth = Uint256::from(depth);
+            let new_height = if depth > self.latest_block_height {
+                Uint256::default()
+            } else {
+                self.latest_block_height.clone() - depth
+            };
+            tracing::debug!(
+                old_height = ?self.latest_block_height,
+                ?new_height,
+                "Simulating a reorg of the Ethereum chain"
+            );
+            self.returned_logs
+                .retain(|(_, _, block)| *block <= new_height);
+            // dropping the `seen` senders of orphaned events lets tests
+            // assert that they are never seen by the oracle
+            self.events.retain(|(_, _, height, _)| {
+                Uint256::from(*height) <= new_height
+            });
+            self.latest_block_height = new_height;
+        }
     }
 
     #[async_trait(?Send)]
@@ -168,13 +221,37 @@ pub mod mock_web3_client {
             let block_to_check: Uint256 = block.into();
             let mut client = self.0.lock().unwrap();
             if client.active {
-                let mut logs = vec![];
+                if client
+                    .scheduled_reorg
+                    .as_ref()
+                    .map(|(block, _)| *block == block_to_check)
+                    .unwrap_or(false)
+                {
+                    let (_, depth) = client.scheduled_reorg.take().unwrap();
+                    client.reorg(depth);
+                }
+                client
+                    .returned_logs
+                    .retain(|(_, _, block)| *block >= block_to_check);
+                let mut logs: Vec<_> = client
+                    .returned_logs
+                    .iter()
+                    .filter(|(event_ty, _, block)| {
+                        event_ty == ty && *block == block_to_check
+                    })
+                    .map(|(_, log, _)| log.clone())
+                    .collect();
                 let mut events = vec![];
                 std::mem::swap(&mut client.events, &mut events);
                 for (event_ty, log, height, seen) in events.into_iter() {
                     if event_ty == ty && block_to_check >= Uint256::from(height)
                     {
                         seen.send(()).unwrap();
+                        client.returned_logs.push((
+                            event_ty,
+                            log.clone(),
+                            block_to_check.clone(),
+                        ));
                         logs.push(log);
                     } else {
                         client.events.push((event_ty, log, height, seen));
@@ -227,8 +304,10 @@ pub mod mock_web3_client {
                     active: true,
                     latest_block_height: Default::default(),
                     events: vec![],
+                    returned_logs: vec![],
                     blocks_processed: block_processed_send,
                     last_block_processed: None,
+                    scheduled_reorg: None,
                 }))),
             )
         }
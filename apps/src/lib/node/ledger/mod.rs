@@ -25,7 +25,7 @@ use tokio::task;
 use tower::ServiceBuilder;
 
 use self::abortable::AbortableSpawner;
-use self::ethereum_oracle::last_processed_block;
+use self::ethereum_oracle::{last_processed_block, status};
 use self::shell::EthereumOracleChannels;
 use self::shims::abcipp_shim::AbciService;
 use crate::cli::args;
@@ -228,6 +228,11 @@ pub fn rollback(config: config::Ledger) -> Result<(), shell::Error> {
     shell::rollback(config)
 }
 
+/// Dry-run the pending state migrations and report the keys they change
+pub fn dry_run_migrations(config: config::Ledger) -> Result<(), shell::Error> {
+    shell::dry_run_migrations(config)
+}
+
 /// Runs and monitors a few concurrent tasks.
 ///
 /// This includes:
@@ -666,6 +671,7 @@ async fn maybe_start_ethereum_oracle(
         mpsc::channel(config.ethereum_bridge.channel_buffer_size);
     let (last_processed_block_sender, last_processed_block_receiver) =
         last_processed_block::channel();
+    let (status_sender, status_receiver) = status::channel();
     let (control_sender, control_receiver) = oracle::control::channel();
 
     match config.ethereum_bridge.mode {
@@ -675,6 +681,7 @@ async fn maybe_start_ethereum_oracle(
                 eth_sender,
                 control_receiver,
                 last_processed_block_sender,
+                status_sender,
                 spawner,
             );
 
@@ -684,6 +691,7 @@ async fn maybe_start_ethereum_oracle(
                     eth_receiver,
                     control_sender,
                     last_processed_block_receiver,
+                    status_receiver,
                 ),
             }
         }
@@ -734,6 +742,7 @@ async fn maybe_start_ethereum_oracle(
                     eth_receiver,
                     control_sender,
                     last_processed_block_receiver,
+                    status_receiver,
                 ),
             }
         }
@@ -29,6 +29,20 @@
 //! total block space, we roughly divide the Tendermint block
 //! space in 3, for each major type of tx.
 //!
+//! # Priority txs
+//!
+//! When the mempool is congested, e.g. with transfers, the encrypted
+//! txs bin could be entirely filled up by them, starving the PoS and
+//! governance txs (say, a validator unjailing itself) submitted at
+//! the same time. To prevent this, a fraction of the space and gas of
+//! the encrypted txs bin can be reserved for priority txs. Other
+//! encrypted txs may only be allotted the resources left outside of
+//! this reservation, while priority txs may use up the entire bin.
+//!
+//! Since decrypted txs take up as much space as the encrypted txs of
+//! the previous height, the space reserved for priority txs that goes
+//! unused also remains available to the protocol txs of the next block.
+//!
 //! # How gas is allocated
 //!
 //! Gas is only relevant to DKG encrypted txs. Every encrypted tx defines its
@@ -79,12 +93,24 @@ pub enum AllocFailure {
 pub struct BlockResources<'tx> {
     tx: &'tx [u8],
     gas: u64,
+    priority: bool,
 }
 
 impl<'tx> BlockResources<'tx> {
     /// Generates a new block resource instance
     pub fn new(tx: &'tx [u8], gas: u64) -> Self {
-        Self { tx, gas }
+        Self {
+            tx,
+            gas,
+            priority: false,
+        }
+    }
+
+    /// Set whether these resources are required by a priority tx,
+    /// which may use the resources reserved in the encrypted txs bin.
+    pub fn with_priority(mut self, priority: bool) -> Self {
+        self.priority = priority;
+        self
     }
 }
 
@@ -174,6 +200,17 @@ impl<M> BlockAllocator<states::BuildingEncryptedTxBatch<M>> {
             decrypted_txs: TxBin::default(),
         }
     }
+
+    /// Reserve the given fraction of the resources of the encrypted
+    /// txs bin to priority txs.
+    #[inline]
+    pub fn with_priority_reserve(
+        mut self,
+        reserve: threshold::Threshold,
+    ) -> Self {
+        self.encrypted_txs.reserve(reserve);
+        self
+    }
 }
 
 impl<State> BlockAllocator<State> {
@@ -233,9 +270,23 @@ impl<R: Resource> TxBin<R> {
     ///
     /// Signal the caller if the tx requires more resource than its max
     /// allotted.
+    #[inline]
     pub fn try_dump(
         &mut self,
         resource: R::Input<'_>,
+    ) -> Result<(), AllocFailure> {
+        self.try_dump_with_reserve(resource, 0)
+    }
+
+    /// Try to dump a new transaction into this [`TxBin`], leaving
+    /// `reserved` resources unoccupied.
+    ///
+    /// Signal the caller if the tx requires more resource than its max
+    /// allotted.
+    pub fn try_dump_with_reserve(
+        &mut self,
+        resource: R::Input<'_>,
+        reserved: u64,
     ) -> Result<(), AllocFailure> {
         let resource = R::usage_of(resource);
         if resource > self.allotted {
@@ -245,11 +296,12 @@ impl<R: Resource> TxBin<R> {
             });
         }
         let occupied = self.occupied + resource;
-        if occupied <= self.allotted {
+        if occupied + reserved <= self.allotted {
             self.occupied = occupied;
             Ok(())
         } else {
-            let bin_resource_left = self.resource_left();
+            let bin_resource_left =
+                self.resource_left().saturating_sub(reserved);
             Err(AllocFailure::Rejected { bin_resource_left })
         }
     }
@@ -259,6 +311,10 @@ impl<R: Resource> TxBin<R> {
 pub struct EncryptedTxsBins {
     space: TxBin<BlockSpace>,
     gas: TxBin<BlockGas>,
+    /// The space reserved for priority txs, yet to be occupied.
+    reserved_space: u64,
+    /// The gas reserved for priority txs, yet to be occupied.
+    reserved_gas: u64,
 }
 
 impl EncryptedTxsBins {
@@ -267,6 +323,39 @@ impl EncryptedTxsBins {
         Self {
             space: TxBin::init(allotted_space_in_bytes),
             gas: TxBin::init(max_gas),
+            reserved_space: 0,
+            reserved_gas: 0,
+        }
+    }
+
+    /// Reserve the given fraction of the space and gas of these
+    /// bins to priority txs.
+    pub fn reserve(&mut self, reserve: threshold::Threshold) {
+        self.reserved_space = reserve.over(self.space.allotted);
+        self.reserved_gas = reserve.over(self.gas.allotted);
+    }
+
+    /// Try to allocate the resources required by a tx, taking into
+    /// account the resources reserved for priority txs.
+    pub fn try_alloc(
+        &mut self,
+        resources: BlockResources<'_>,
+    ) -> Result<(), AllocFailure> {
+        if resources.priority {
+            self.space.try_dump(resources.tx)?;
+            self.gas.try_dump(resources.gas)?;
+            self.reserved_space = self
+                .reserved_space
+                .saturating_sub(BlockSpace::usage_of(resources.tx));
+            self.reserved_gas = self
+                .reserved_gas
+                .saturating_sub(BlockGas::usage_of(resources.gas));
+            Ok(())
+        } else {
+            self.space
+                .try_dump_with_reserve(resources.tx, self.reserved_space)?;
+            self.gas
+                .try_dump_with_reserve(resources.gas, self.reserved_gas)
         }
     }
 
@@ -304,7 +393,7 @@ pub mod threshold {
 
     impl Threshold {
         /// Return a new [`Threshold`].
-        const fn new(numer: u64, denom: u64) -> Self {
+        pub const fn new(numer: u64, denom: u64) -> Self {
             // constrain ratio to a max of 1
             let numer = if numer > denom { denom } else { numer };
             Self(Ratio::new_raw(numer, denom))
@@ -318,6 +407,10 @@ pub mod threshold {
 
     /// Divide free space in three.
     pub const ONE_THIRD: Threshold = Threshold::new(1, 3);
+
+    /// The default fraction of the encrypted txs bin reserved
+    /// to priority txs.
+    pub const DEFAULT_PRIORITY_RESERVE: Threshold = Threshold::new(1, 10);
 }
 
 #[cfg(test)]
@@ -397,6 +490,62 @@ mod tests {
         );
     }
 
+    /// Check that the resources reserved to priority txs can
+    /// only be occupied by priority txs.
+    #[test]
+    fn test_priority_txs_reserve() {
+        const BLOCK_SIZE: u64 = 60;
+        const BLOCK_GAS: u64 = 1_000;
+
+        // reserve half of the encrypted txs bin to priority txs
+        let mut alloc = BsaWrapperTxs::init(BLOCK_SIZE, BLOCK_GAS)
+            .with_priority_reserve(threshold::Threshold::new(1, 2));
+        assert_eq!(alloc.encrypted_txs.space.allotted, 20);
+
+        // other txs can't occupy the reserved space
+        assert!(alloc.try_alloc(BlockResources::new(&[0; 8], 0)).is_ok());
+        assert_matches!(
+            alloc.try_alloc(BlockResources::new(&[0; 3], 0)),
+            Err(AllocFailure::Rejected {
+                bin_resource_left: 2
+            })
+        );
+
+        // priority txs can
+        assert!(
+            alloc
+                .try_alloc(BlockResources::new(&[0; 6], 0).with_priority(true))
+                .is_ok()
+        );
+
+        // the reserved space left over by priority txs remains reserved
+        assert_matches!(
+            alloc.try_alloc(BlockResources::new(&[0; 3], 0)),
+            Err(AllocFailure::Rejected { .. })
+        );
+        assert!(alloc.try_alloc(BlockResources::new(&[0; 2], 0)).is_ok());
+        assert!(
+            alloc
+                .try_alloc(BlockResources::new(&[0; 4], 0).with_priority(true))
+                .is_ok()
+        );
+
+        // the same goes for gas
+        let mut alloc = BsaWrapperTxs::init(BLOCK_SIZE, BLOCK_GAS)
+            .with_priority_reserve(threshold::Threshold::new(1, 2));
+        assert_matches!(
+            alloc.try_alloc(BlockResources::new(&[0; 1], 501)),
+            Err(AllocFailure::Rejected { .. })
+        );
+        assert!(
+            alloc
+                .try_alloc(
+                    BlockResources::new(&[0; 1], 1_000).with_priority(true)
+                )
+                .is_ok()
+        );
+    }
+
     // Test that we cannot include encrypted txs in a block
     // when the state invariants banish them from inclusion.
     #[test]
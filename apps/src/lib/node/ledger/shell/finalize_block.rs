@@ -1,28 +1,34 @@
 //! Implementation of the `FinalizeBlock` ABCI++ method for the Shell
 
 use data_encoding::HEXUPPER;
-use namada::core::ledger::inflation;
 use namada::core::ledger::masp_conversions::update_allowed_conversions;
 use namada::core::ledger::pgf::ADDRESS as pgf_address;
 use namada::ledger::events::EventType;
 use namada::ledger::gas::{GasMetering, TxGasMeter};
 use namada::ledger::parameters::storage as params_storage;
+use namada::ledger::pos::utils::{
+    PosIoEvent, RewardsEvent, ValidatorStateEvent,
+};
 use namada::ledger::pos::{namada_proof_of_stake, staking_token_address};
 use namada::ledger::protocol;
 use namada::ledger::storage::wl_storage::WriteLogAndStorage;
+use namada::ledger::storage::write_log::StorageModification;
 use namada::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
 use namada::ledger::storage_api::token::credit_tokens;
-use namada::ledger::storage_api::{pgf, StorageRead, StorageWrite};
+use namada::ledger::storage_api::{pgf, StorageRead};
+use namada::proof_of_stake::io_stats::{merge_io_stats, PosIoStats};
+use namada::proof_of_stake::proposer_history::record_block_proposer;
 use namada::proof_of_stake::{
     find_validator_by_raw_hash, read_last_block_proposer_address,
-    read_pos_params, read_total_stake, write_last_block_proposer_address,
+    write_last_block_proposer_address,
 };
 use namada::types::dec::Dec;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::storage::{BlockHash, BlockResults, Epoch, Header};
 use namada::types::transaction::protocol::{
-    ethereum_tx_data_variants, ProtocolTxType,
+    ethereum_tx_data_variants, EthereumTxData, ProtocolTxType,
 };
+use namada::types::transaction::TxErrorPayload;
 use namada::types::vote_extensions::ethereum_events::MultiSignedEthEvent;
 
 use super::governance::execute_governance_proposals;
@@ -61,6 +67,12 @@ where
     ) -> Result<shim::response::FinalizeBlock> {
         let mut response = shim::response::FinalizeBlock::default();
 
+        // Halt before touching the state if governance agreed on an upgrade
+        // at this height
+        self.check_halt_at_height(
+            self.wl_storage.storage.get_last_block_height() + 1,
+        )?;
+
         // Begin the new block and check if a new epoch has begun
         let (height, new_epoch) =
             self.update_state(req.header, req.hash, req.byzantine_validators);
@@ -81,22 +93,35 @@ where
             self.wl_storage.storage.update_epoch_blocks_delay
         );
 
-        // Finalize the transactions' hashes from the previous block
-        for hash in self.wl_storage.storage.iter_replay_protection() {
-            self.wl_storage
-                .write_log
-                .finalize_tx_hash(hash)
-                .expect("Failed tx hashes finalization")
-        }
-
-        let pos_params =
-            namada_proof_of_stake::read_pos_params(&self.wl_storage)?;
+        // Finalize the transactions' hashes from the previous block and prune
+        // the expired ones at the beginning of a new epoch
+        let block_time = self
+            .wl_storage
+            .storage
+            .header
+            .as_ref()
+            .expect("The header of the block must be set")
+            .time;
+        self.wl_storage
+            .finalize_replay_protection(new_epoch, block_time)
+            .expect("Failed tx hashes finalization");
 
         if new_epoch {
             update_allowed_conversions(&mut self.wl_storage)?;
 
             execute_governance_proposals(self, &mut response)?;
+        }
+
+        // Read the PoS parameters only after the governance proposals have
+        // been executed, as they may change them
+        let pos_params =
+            namada_proof_of_stake::read_pos_params(&self.wl_storage)?;
 
+        // The changes of the validators' states made by the protocol and by
+        // the txs in this block, to be emitted as events
+        let mut validator_state_changes = Vec::new();
+
+        if new_epoch {
             // Copy the new_epoch + pipeline_len - 1 validator set into
             // new_epoch + pipeline_len
             namada_proof_of_stake::copy_validator_sets_and_positions(
@@ -106,12 +131,35 @@ where
                 current_epoch + pos_params.pipeline_len,
             )?;
 
+            // Respect any change in the number of validator slots in the
+            // pipeline epoch validator sets
+            let ((), changes) = with_state_changes(
+                &mut self.wl_storage,
+                ValidatorStateChangeCause::Rebalance,
+                |storage| {
+                    namada_proof_of_stake::rebalance_validator_sets(
+                        storage,
+                        &pos_params,
+                        current_epoch,
+                    )
+                },
+            )?;
+            validator_state_changes.extend(changes);
+
             // Compute the total stake of the consensus validator set and record
             // it in storage
             namada_proof_of_stake::compute_and_store_total_consensus_stake(
                 &mut self.wl_storage,
                 current_epoch,
             )?;
+
+            // Snapshot the consensus validator set of the new epoch, so that
+            // it can be queried with a proof
+            namada_proof_of_stake::store_validator_set_snapshot(
+                &mut self.wl_storage,
+                &pos_params,
+                current_epoch,
+            )?;
         }
 
         // Get the actual votes from cometBFT in the preferred format
@@ -121,24 +169,68 @@ where
         // because it potentially needs to be able to read validator state from
         // previous epoch and jailing validator removes the historical state
         if !votes.is_empty() {
+            let vext_signers = Self::bridge_vext_signers(&req.txs);
             self.log_block_rewards(
                 votes.clone(),
+                &vext_signers,
                 height,
                 current_epoch,
                 new_epoch,
             )?;
         }
 
+        // The validator sets may be written to from here on, so the cached
+        // sets must no longer be used until they're populated again
+        self.consensus_set_cache.invalidate();
+
         // Invariant: This has to be applied after
         // `copy_validator_sets_and_positions` and before `self.update_epoch`.
-        self.record_slashes_from_evidence();
+        self.record_slashes_from_evidence(&mut validator_state_changes);
         // Invariant: This has to be applied after
-        // `copy_validator_sets_and_positions` if we're starting a new epoch
+        // `copy_validator_sets_and_positions` if we're starting a new epoch.
+        // Process slashes before inflation as they may affect the rewards in
+        // the current epoch. The processing of slashes may be spread over the
+        // first blocks of the epoch, so it's continued in every block. In that
+        // case, the inflation is still applied in the first block, while the
+        // slashes are in progress. This is fine because the inflation of the
+        // last epoch only depends on the stakes in the last epoch, while the
+        // slashes processed in this epoch only change the stakes from this
+        // epoch onward.
+        let mut pos_io_stats = PosIoStats::new();
+        let slash_processing = self.process_slashes(
+            new_epoch,
+            &mut pos_io_stats,
+            &mut validator_state_changes,
+        );
         if new_epoch {
-            // Invariant: Process slashes before inflation as they may affect
-            // the rewards in the current epoch.
-            self.process_slashes();
-            self.apply_inflation(current_epoch)?;
+            self.apply_inflation(current_epoch, &mut response)?;
+        }
+        // Invariant: Execute the scheduled actions after processing the
+        // slashes, so that the scheduled withdrawals account for them. They're
+        // deferred while the slashes are still being processed.
+        let execute_scheduled_actions = match slash_processing {
+            SlashProcessingStatus::Idle => new_epoch,
+            SlashProcessingStatus::InProgress => false,
+            SlashProcessingStatus::Completed => true,
+        };
+        if execute_scheduled_actions {
+            namada_proof_of_stake::process_scheduled_actions(
+                &mut self.wl_storage,
+                current_epoch,
+            )?;
+            // Rebalance the staking pool once its matured unbonds can be
+            // withdrawn
+            let ((), changes) = with_state_changes(
+                &mut self.wl_storage,
+                ValidatorStateChangeCause::StakeChange,
+                |storage| {
+                    namada_proof_of_stake::staking_pool::rebalance_staking_pool(
+                        storage,
+                        current_epoch,
+                    )
+                },
+            )?;
+            validator_state_changes.extend(changes);
         }
 
         // Consensus set liveness check
@@ -161,18 +253,47 @@ where
                 vote_height,
                 &pos_params,
             )?;
+            // Count the missed votes reported by a quorum of consensus
+            // validators in their liveness vote extensions
+            namada_proof_of_stake::apply_liveness_reports(
+                &mut self.wl_storage,
+                &pos_params,
+                epoch_of_votes,
+                vote_height,
+            )?;
+            // Keep the consensus validators that missed this vote, to be
+            // reported in the next liveness vote extension
+            self.liveness_observations =
+                namada_proof_of_stake::read_consensus_validator_set_addresses(
+                    &self.wl_storage,
+                    epoch_of_votes,
+                )?
+                .into_iter()
+                .filter(|validator| {
+                    !votes
+                        .iter()
+                        .any(|vote| &vote.validator_address == validator)
+                })
+                .collect();
         }
 
         let validator_set_update_epoch =
             self.get_validator_set_update_epoch(current_epoch);
 
         // Jail validators for inactivity
-        namada_proof_of_stake::jail_for_liveness(
+        let ((), changes) = with_state_changes(
             &mut self.wl_storage,
-            &pos_params,
-            current_epoch,
-            validator_set_update_epoch,
+            ValidatorStateChangeCause::Liveness,
+            |storage| {
+                namada_proof_of_stake::jail_for_liveness(
+                    storage,
+                    &pos_params,
+                    current_epoch,
+                    validator_set_update_epoch,
+                )
+            },
         )?;
+        validator_state_changes.extend(changes);
 
         if new_epoch {
             // Prune liveness data from validators that are no longer in the
@@ -181,6 +302,19 @@ where
                 &mut self.wl_storage,
                 current_epoch,
             )?;
+
+            // Prune historical PoS data
+            namada_proof_of_stake::prune_all(
+                &mut self.wl_storage,
+                current_epoch,
+            )?;
+
+            // Deregister the validators that have been without stake for
+            // too long
+            namada_proof_of_stake::deregister_zero_stake_validators(
+                &mut self.wl_storage,
+                current_epoch,
+            )?;
         }
 
         let mut stats = InternalStats::default();
@@ -333,7 +467,10 @@ where
                         ProtocolTxType::BridgePoolVext
                         | ProtocolTxType::BridgePool
                         | ProtocolTxType::ValSetUpdateVext
-                        | ProtocolTxType::ValidatorSetUpdate => (
+                        | ProtocolTxType::ValidatorSetUpdate
+                        | ProtocolTxType::LivenessVext
+                        | ProtocolTxType::Liveness
+                        | ProtocolTxType::ConsensusValidatorSetUpdates => (
                             Event::new_tx_event(&tx, height.0),
                             None,
                             TxGasMeter::new_from_sub_limit(0.into()),
@@ -437,6 +574,10 @@ where
                             if let Some(wrapper) = embedding_wrapper {
                                 self.commit_inner_tx_hash(wrapper);
                             }
+                            self.collect_tx_pos_io_stats(&mut pos_io_stats);
+                            self.collect_tx_validator_state_changes(
+                                &mut validator_state_changes,
+                            );
                         }
                         self.wl_storage.commit_tx();
                         if !tx_event.contains_key("code") {
@@ -536,7 +677,13 @@ where
 
                     tx_event["gas_used"] =
                         tx_gas_meter.get_tx_consumed_gas().to_string();
-                    tx_event["info"] = msg.to_string();
+                    let info = msg.to_string();
+                    // Surface the machine-readable error of a failed tx
+                    if let Some(payload) = TxErrorPayload::from_info(&info) {
+                        tx_event["error"] = serde_json::to_string(&payload)
+                            .expect("Serializing a tx error cannot fail");
+                    }
+                    tx_event["info"] = info;
                     if let EventType::Accepted = tx_event.event_type {
                         // If wrapper, invalid tx error code
                         tx_event["code"] = ErrorCodes::InvalidTx.into();
@@ -567,11 +714,30 @@ where
             self.update_eth_oracle(&changed_keys);
         }
 
+        record_block_proposer(
+            &mut self.wl_storage,
+            height,
+            native_block_proposer_address.clone(),
+        )?;
         write_last_block_proposer_address(
             &mut self.wl_storage,
             native_block_proposer_address,
         )?;
 
+        response
+            .events
+            .extend(PosIoEvent::block_events(&pos_io_stats, height));
+        // Emit the validator state changes made by the txs and the protocol in
+        // this block
+        response.events.extend(
+            validator_state_changes
+                .iter()
+                .map(|change| ValidatorStateEvent::new(change, height).into()),
+        );
+
+        #[cfg(feature = "audit")]
+        self.audit_pos_balance(height);
+
         self.event_log_mut().log_events(response.events.clone());
         tracing::debug!("End finalize_block {height} of epoch {current_epoch}");
 
@@ -614,6 +780,8 @@ where
     /// If a new epoch begins, we update the response to include
     /// changes to the validator sets and consensus parameters
     fn update_epoch(&mut self, response: &mut shim::response::FinalizeBlock) {
+        self.populate_consensus_set_cache()
+            .expect("Must be able to read the consensus validator sets");
         // Apply validator set update
         response.validator_updates = self
             .get_abci_validator_updates(false, |pk, power| {
@@ -630,64 +798,29 @@ where
             .expect("Must be able to update validator set");
     }
 
-    /// Calculate the new inflation rate, mint the new tokens to the PoS
-    /// account, then update the reward products of the validators. This is
-    /// executed while finalizing the first block of a new epoch and is applied
-    /// with respect to the previous epoch.
-    fn apply_inflation(&mut self, current_epoch: Epoch) -> Result<()> {
+    /// Mint the PoS inflation computed with the PoS inflation model, update
+    /// the reward products of the validators, then mint the PGF inflation.
+    /// This is executed while finalizing the first block of a new epoch and is
+    /// applied with respect to the previous epoch. The distribution of the
+    /// inflation is reported in the events of the block.
+    fn apply_inflation(
+        &mut self,
+        current_epoch: Epoch,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
         let last_epoch = current_epoch.prev();
-        // Get input values needed for the PD controller for PoS.
-        // Run the PD controllers to calculate new rates.
-
-        let params = read_pos_params(&self.wl_storage)?;
 
         // Read from Parameters storage
         let epochs_per_year: u64 = self
             .read_storage_key(&params_storage::get_epochs_per_year_key())
             .expect("Epochs per year should exist in storage");
-        let pos_p_gain_nom: Dec = self
-            .read_storage_key(&params_storage::get_pos_gain_p_key())
-            .expect("PoS P-gain factor should exist in storage");
-        let pos_d_gain_nom: Dec = self
-            .read_storage_key(&params_storage::get_pos_gain_d_key())
-            .expect("PoS D-gain factor should exist in storage");
-
-        let pos_last_staked_ratio: Dec = self
-            .read_storage_key(&params_storage::get_staked_ratio_key())
-            .expect("PoS staked ratio should exist in storage");
-        let pos_last_inflation_amount: token::Amount = self
-            .read_storage_key(&params_storage::get_pos_inflation_amount_key())
-            .expect("PoS inflation amount should exist in storage");
-        // Read from PoS storage
+        // The PGF inflation is computed from the supply before the PoS
+        // inflation is minted
         let total_tokens: token::Amount = self
             .read_storage_key(&token::minted_balance_key(
                 &staking_token_address(&self.wl_storage),
             ))
             .expect("Total NAM balance should exist in storage");
-        let pos_locked_supply =
-            read_total_stake(&self.wl_storage, &params, last_epoch)?;
-        let pos_locked_ratio_target = params.target_staked_ratio;
-        let pos_max_inflation_rate = params.max_inflation_rate;
-
-        // Run rewards PD controller
-        let pos_controller = inflation::RewardsController {
-            locked_tokens: pos_locked_supply.raw_amount(),
-            total_tokens: total_tokens.raw_amount(),
-            total_native_tokens: total_tokens.raw_amount(),
-            locked_ratio_target: pos_locked_ratio_target,
-            locked_ratio_last: pos_last_staked_ratio,
-            max_reward_rate: pos_max_inflation_rate,
-            last_inflation_amount: pos_last_inflation_amount.raw_amount(),
-            p_gain_nom: pos_p_gain_nom,
-            d_gain_nom: pos_d_gain_nom,
-            epochs_per_year,
-        };
-
-        // Run the rewards controllers
-        let inflation::ValsToUpdate {
-            locked_ratio,
-            inflation,
-        } = pos_controller.run();
 
         // Get the number of blocks in the last epoch
         let first_block_of_last_epoch = self
@@ -702,28 +835,20 @@ where
 
         let staking_token = staking_token_address(&self.wl_storage);
 
-        let inflation = token::Amount::from_uint(inflation, 0)
-            .expect("Should not fail Uint -> Amount conversion");
-        namada_proof_of_stake::update_rewards_products_and_mint_inflation(
-            &mut self.wl_storage,
-            &params,
-            last_epoch,
-            num_blocks_in_last_epoch,
-            inflation,
-            &staking_token,
-        )
-        .expect(
-            "Must be able to update PoS rewards products and mint inflation",
-        );
-
-        // Write new rewards parameters that will be used for the inflation of
-        // the current new epoch
-        self.wl_storage
-            .write(&params_storage::get_pos_inflation_amount_key(), inflation)
-            .expect("unable to write new reward rate");
-        self.wl_storage
-            .write(&params_storage::get_staked_ratio_key(), locked_ratio)
-            .expect("unable to write new locked ratio");
+        let distribution =
+            namada_proof_of_stake::inflation::compute_and_mint_inflation(
+                &mut self.wl_storage,
+                last_epoch,
+                num_blocks_in_last_epoch,
+            )
+            .expect(
+                "Must be able to update PoS rewards products and mint \
+                 inflation",
+            );
+        response.events.extend(RewardsEvent::distribution_events(
+            &distribution,
+            self.wl_storage.storage.block.height,
+        ));
 
         // Pgf inflation
         let pgf_parameters = pgf::get_parameters(&self.wl_storage)?;
@@ -818,6 +943,7 @@ where
     fn log_block_rewards(
         &mut self,
         votes: Vec<namada_proof_of_stake::types::VoteInfo>,
+        vext_signers: &HashSet<Address>,
         height: BlockHeight,
         current_epoch: Epoch,
         new_epoch: bool,
@@ -829,15 +955,20 @@ where
                 tracing::debug!(
                     "Found last block proposer: {proposer_address}"
                 );
+                let votes_epoch = if new_epoch {
+                    current_epoch.prev()
+                } else {
+                    current_epoch
+                };
+                self.consensus_set_cache
+                    .populate(&self.wl_storage, votes_epoch)?;
                 namada_proof_of_stake::log_block_rewards(
                     &mut self.wl_storage,
-                    if new_epoch {
-                        current_epoch.prev()
-                    } else {
-                        current_epoch
-                    },
+                    votes_epoch,
                     &proposer_address,
                     votes,
+                    vext_signers,
+                    &self.consensus_set_cache,
                 )?;
             }
             None => {
@@ -855,13 +986,145 @@ where
         Ok(())
     }
 
+    // Collect the validators whose Ethereum bridge pool root or validator set
+    // update vote extensions were included in the block. These vote
+    // extensions were made on the last committed block, like the votes that
+    // the PoS rewards are assigned for.
+    fn bridge_vext_signers(
+        txs: &[shim::request::ProcessedTx],
+    ) -> HashSet<Address> {
+        let mut signers = HashSet::new();
+        for processed_tx in txs {
+            if ErrorCodes::from_u32(processed_tx.result.code)
+                != Some(ErrorCodes::Ok)
+            {
+                continue;
+            }
+            let Ok(tx) = Tx::try_from(processed_tx.tx.as_ref()) else {
+                continue;
+            };
+            let Ok(tx_data) = EthereumTxData::try_from(&tx) else {
+                continue;
+            };
+            match tx_data {
+                EthereumTxData::BridgePool(multi_signed) => {
+                    signers.extend(
+                        multi_signed
+                            .into_iter()
+                            .map(|vext| vext.data.validator_addr),
+                    );
+                }
+                EthereumTxData::ValidatorSetUpdate(digest) => {
+                    signers.extend(digest.signatures.into_keys());
+                }
+                EthereumTxData::BridgePoolVext(vext) => {
+                    signers.insert(vext.data.validator_addr);
+                }
+                EthereumTxData::ValSetUpdateVext(vext) => {
+                    signers.insert(vext.data.validator_addr);
+                }
+                _ => {}
+            }
+        }
+        signers
+    }
+
+    // Add the storage I/O of the PoS operations of the last applied tx, which
+    // the tx wrote to temporary storage, to the stats of the block. The stats
+    // are dropped with the rest of the temporary storage when the tx is
+    // committed.
+    fn collect_tx_pos_io_stats(&self, io_stats: &mut PosIoStats) {
+        let (modification, _gas) =
+            self.wl_storage.write_log.read(&pos::io_stats_key());
+        if let Some(StorageModification::Temp { value }) = modification {
+            match PosIoStats::try_from_slice(value) {
+                Ok(tx_io_stats) => merge_io_stats(io_stats, &tx_io_stats),
+                Err(err) => {
+                    tracing::warn!(
+                        "Ignoring invalid PoS storage I/O stats of a tx: {err}"
+                    );
+                }
+            }
+        }
+    }
+
+    // Add the validator state changes made by the last applied tx, which the
+    // tx wrote to temporary storage, to the changes of the block
+    fn collect_tx_validator_state_changes(
+        &self,
+        changes: &mut Vec<ValidatorStateChange>,
+    ) {
+        let (modification, _gas) = self
+            .wl_storage
+            .write_log
+            .read(&pos::validator_state_changes_key());
+        if let Some(StorageModification::Temp { value }) = modification {
+            match Vec::<ValidatorStateChange>::try_from_slice(value) {
+                Ok(tx_changes) => changes.extend(tx_changes),
+                Err(err) => {
+                    tracing::warn!(
+                        "Ignoring invalid validator state changes of a tx: \
+                         {err}"
+                    );
+                }
+            }
+        }
+    }
+
+    // Check that the PoS account balance matches the bonded and unbonded
+    // tokens, less the slashed tokens, plus the unclaimed rewards, and log
+    // the breakdown of the balance on a mismatch.
+    #[cfg(feature = "audit")]
+    fn audit_pos_balance(&self, height: BlockHeight) {
+        let report =
+            match namada_proof_of_stake::audit::audit_pos(&self.wl_storage) {
+                Ok(Some(report)) => report,
+                Ok(None) => {
+                    tracing::debug!(
+                        "Skipping the PoS audit at height {height} while \
+                         slashes are being processed"
+                    );
+                    return;
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to audit the PoS account at height {height}: \
+                         {err}"
+                    );
+                    return;
+                }
+            };
+        match report.expected_balance() {
+            Ok(expected) if expected == report.balance => {
+                tracing::debug!("PoS audit passed at height {height}");
+            }
+            expected => {
+                tracing::error!(
+                    %height,
+                    epoch = %report.epoch,
+                    balance = %report.balance.to_string_native(),
+                    expected = ?expected.map(|a| a.to_string_native()),
+                    bonded = %report.bonded.to_string_native(),
+                    unbonded = %report.unbonded.to_string_native(),
+                    slashed = %report.slashed.to_string_native(),
+                    unclaimed_rewards =
+                        %report.unclaimed_rewards.to_string_native(),
+                    "PoS account balance mismatch"
+                );
+            }
+        }
+    }
+
     // Write the inner tx hash to storage and remove the corresponding wrapper
     // hash since it's redundant (we check the inner tx hash too when validating
     // the wrapper). Requires the wrapper transaction as argument to recover
     // both the hashes.
     fn commit_inner_tx_hash(&mut self, wrapper_tx: Tx) {
         self.wl_storage
-            .write_tx_hash(wrapper_tx.raw_header_hash())
+            .write_tx_hash(
+                wrapper_tx.raw_header_hash(),
+                wrapper_tx.header.expiration,
+            )
             .expect("Error while writing tx hash to storage");
 
         self.wl_storage
@@ -969,11 +1232,11 @@ mod test_finalize_block {
     };
     use namada::proof_of_stake::{
         enqueued_slashes_handle, get_num_consensus_validators,
-        read_consensus_validator_set_addresses_with_stake,
-        read_validator_stake, rewards_accumulator_handle, unjail_validator,
-        validator_consensus_key_handle, validator_rewards_products_handle,
-        validator_slashes_handle, validator_state_handle, write_pos_params,
-        ADDRESS as pos_address,
+        read_consensus_validator_set_addresses_with_stake, read_pos_params,
+        read_total_stake, read_validator_stake, rewards_accumulator_handle,
+        unjail_validator, validator_consensus_key_handle,
+        validator_rewards_products_handle, validator_slashes_handle,
+        validator_state_handle, write_pos_params, ADDRESS as pos_address,
     };
     use namada::proto::{Code, Data, Section, Signature};
     use namada::types::dec::POS_DECIMAL_PRECISION;
@@ -2174,6 +2437,33 @@ mod test_finalize_block {
         assert!(rp1 > rp2);
         assert!(rp2 > rp3);
         assert!(rp3 > rp4);
+
+        // The distribution of the inflation is reported in the event log
+        let inflation_events: Vec<_> = shell
+            .event_log()
+            .iter()
+            .filter(|event| event.event_type == EventType::PosInflation)
+            .collect();
+        assert_eq!(inflation_events.len(), 1);
+        assert_eq!(inflation_events[0]["epoch"], Epoch::default().to_string());
+        assert_eq!(inflation_events[0]["num_validators"], "4");
+        let rewards_products: BTreeMap<String, String> = shell
+            .event_log()
+            .iter()
+            .filter(|event| event.event_type == EventType::PosRewards)
+            .map(|event| {
+                (event["validator"].clone(), event["rewards_product"].clone())
+            })
+            .collect();
+        assert_eq!(
+            rewards_products,
+            BTreeMap::from([
+                (val1.address.to_string(), rp1.to_string()),
+                (val2.address.to_string(), rp2.to_string()),
+                (val3.address.to_string(), rp3.to_string()),
+                (val4.address.to_string(), rp4.to_string()),
+            ])
+        );
     }
 
     /// A unit test for PoS inflationary rewards claiming and querying
@@ -3058,7 +3348,7 @@ mod test_finalize_block {
             shell
                 .wl_storage
                 .storage
-                .write_replay_protection_entry(&mut batch, &hash_subkey)
+                .write_replay_protection_entry(&mut batch, &hash_subkey, &[])
                 .expect("Test failed");
         }
 
@@ -3199,7 +3489,7 @@ mod test_finalize_block {
             shell
                 .wl_storage
                 .storage
-                .write_replay_protection_entry(&mut batch, &hash_subkey)
+                .write_replay_protection_entry(&mut batch, &hash_subkey, &[])
                 .unwrap();
         }
 
@@ -5150,6 +5440,32 @@ mod test_finalize_block {
             assert_eq!(val5_state, ValidatorState::BelowThreshold);
         }
 
+        // The jailing of validator 2 is reported in the event log for all the
+        // epochs up through the pipeline
+        let val2_jailed_epochs: BTreeSet<String> = shell
+            .event_log()
+            .iter()
+            .filter(|event| {
+                event.event_type == EventType::ValidatorState
+                    && event["validator"] == val2.to_string()
+                    && event["cause"] == "liveness"
+            })
+            .map(|event| {
+                assert_eq!(
+                    event["from"],
+                    ValidatorState::Consensus.to_string()
+                );
+                assert_eq!(event["to"], ValidatorState::Jailed.to_string());
+                event["epoch"].clone()
+            })
+            .collect();
+        assert_eq!(
+            val2_jailed_epochs,
+            (1..=params.pipeline_len)
+                .map(|offset| (current_epoch + offset).to_string())
+                .collect()
+        );
+
         // Check the liveness data for validators 2 and 5 (2 should still be
         // there, 5 should be removed)
         for val in &initial_consensus_set {
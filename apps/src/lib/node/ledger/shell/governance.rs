@@ -14,12 +14,11 @@ use namada::core::ledger::pgf::storage::steward::StewardDetail;
 use namada::core::ledger::pgf::ADDRESS;
 use namada::core::ledger::storage_api::governance as gov_api;
 use namada::ledger::governance::utils::ProposalEvent;
-use namada::ledger::pos::BondId;
+use namada::ledger::pos::{BondId, PosQueries};
 use namada::ledger::protocol;
 use namada::ledger::storage::types::encode;
 use namada::ledger::storage::{DBIter, StorageHasher, DB};
-use namada::ledger::storage_api::{pgf, token, StorageWrite};
-use namada::proof_of_stake::parameters::PosParams;
+use namada::ledger::storage_api::{pgf, token, ResultExt, StorageWrite};
 use namada::proof_of_stake::{bond_amount, read_total_stake};
 use namada::proto::{Code, Data};
 use namada::types::address::Address;
@@ -46,12 +45,16 @@ where
 
     for id in std::mem::take(&mut shell.proposal_data) {
         let proposal_funds_key = gov_storage::get_funds_key(id);
+        let proposal_start_epoch_key =
+            gov_storage::get_voting_start_epoch_key(id);
         let proposal_end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
         let proposal_type_key = gov_storage::get_proposal_type_key(id);
         let proposal_author_key = gov_storage::get_author_key(id);
 
         let funds: token::Amount =
             force_read(&shell.wl_storage, &proposal_funds_key)?;
+        let proposal_start_epoch: Epoch =
+            force_read(&shell.wl_storage, &proposal_start_epoch_key)?;
         let proposal_end_epoch: Epoch =
             force_read(&shell.wl_storage, &proposal_end_epoch_key)?;
         let proposal_type: ProposalType =
@@ -61,16 +64,16 @@ where
 
         let is_steward = pgf::is_steward(&shell.wl_storage, &proposal_author)?;
 
+        // Votes are weighted by the stake at the start of the voting period
         let params = read_pos_params(&shell.wl_storage)?;
         let total_voting_power =
-            read_total_stake(&shell.wl_storage, &params, proposal_end_epoch)?;
+            read_total_stake(&shell.wl_storage, &params, proposal_start_epoch)?;
 
         let tally_type = TallyType::from(proposal_type.clone(), is_steward);
         let votes = compute_proposal_votes(
             &shell.wl_storage,
-            &params,
             id,
-            proposal_end_epoch,
+            proposal_start_epoch,
         )?;
         let proposal_result =
             compute_proposal_result(votes, total_voting_power, tally_type);
@@ -199,16 +202,16 @@ where
     Ok(proposals_result)
 }
 
-fn compute_proposal_votes<S>(
-    storage: &S,
-    params: &PosParams,
+fn compute_proposal_votes<D, H>(
+    wl_storage: &WlStorage<D, H>,
     proposal_id: u64,
     epoch: Epoch,
 ) -> storage_api::Result<ProposalVotes>
 where
-    S: StorageRead,
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
 {
-    let votes = gov_api::get_proposal_votes(storage, proposal_id)?;
+    let votes = gov_api::get_proposal_votes(wl_storage, proposal_id)?;
 
     let mut validators_vote: HashMap<Address, TallyVote> = HashMap::default();
     let mut validator_voting_power: HashMap<Address, VotePower> =
@@ -224,8 +227,10 @@ where
             let validator = vote.validator.clone();
             let vote_data = vote.data.clone();
 
-            let validator_stake =
-                read_total_stake(storage, params, epoch).unwrap_or_default();
+            let validator_stake = wl_storage
+                .pos_queries()
+                .get_voting_power_at(&validator, epoch)
+                .into_storage_result()?;
 
             validators_vote.insert(validator.clone(), vote_data.into());
             validator_voting_power.insert(validator, validator_stake);
@@ -239,7 +244,7 @@ where
                 validator: validator.clone(),
             };
             let delegator_stake =
-                bond_amount(storage, &bond_id, epoch).unwrap_or_default();
+                bond_amount(wl_storage, &bond_id, epoch).unwrap_or_default();
 
             delegators_vote.insert(delegator.clone(), vote_data.into());
             delegator_voting_power
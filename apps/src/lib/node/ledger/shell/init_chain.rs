@@ -101,6 +101,8 @@ where
         let parameters = genesis.get_chain_parameters(&self.wasm_dir);
         self.store_wasms(&parameters)?;
         parameters.init_storage(&mut self.wl_storage)?;
+        // A new chain starts with the latest version of the state schema
+        storage::migrations::all().init_state_version(&mut self.wl_storage)?;
 
         // Initialize governance parameters
         let gov_params = genesis.get_gov_params();
@@ -11,11 +11,13 @@ mod governance;
 mod init_chain;
 pub mod prepare_proposal;
 pub mod process_proposal;
+mod protocol_tx_cache;
 pub(super) mod queries;
 mod stats;
 #[cfg(any(test, feature = "testing"))]
 #[allow(dead_code)]
 pub mod testing;
+mod upgrade;
 pub mod utils;
 mod vote_extensions;
 
@@ -25,12 +27,14 @@ use std::mem;
 use std::path::{Path, PathBuf};
 #[allow(unused_imports)]
 use std::rc::Rc;
+use std::sync::Mutex;
 
 use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::transaction::Transaction;
 use namada::core::hints;
 use namada::core::ledger::eth_bridge;
+use namada::core::types::ethereum_structs;
 use namada::ledger::events::log::EventLog;
 use namada::ledger::events::Event;
 use namada::ledger::gas::{Gas, TxGasMeter};
@@ -51,10 +55,21 @@ use namada::ledger::storage::{
 use namada::ledger::storage_api::tx::validate_tx_bytes;
 use namada::ledger::storage_api::{self, StorageRead};
 use namada::ledger::{parameters, pos, protocol};
-use namada::proof_of_stake::{self, process_slashes, read_pos_params, slash};
+use namada::proof_of_stake::io_stats::{with_io_stats, PosIoStats, PosOp};
+use namada::proof_of_stake::state_changes::with_state_changes;
+use namada::proof_of_stake::types::{
+    SlashProcessingStatus, ValidatorStateChange, ValidatorStateChangeCause,
+};
+use namada::proof_of_stake::validator_set_cache::ConsensusValidatorSetCache;
+use namada::proof_of_stake::{
+    self, process_slashes_chunk, read_pos_params, slash,
+};
 use namada::proto::{self, Section, Tx};
 use namada::types::address::Address;
 use namada::types::chain::ChainId;
+use namada::types::consensus_validator_set::{
+    ConsensusValidatorSetUpdates, ConsensusValidatorUpdate,
+};
 use namada::types::ethereum_events::EthereumEvent;
 use namada::types::internal::{ExpiredTx, TxInQueue};
 use namada::types::key::*;
@@ -66,13 +81,16 @@ use namada::types::{address, token};
 use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::{WasmCacheAccess, WasmCacheRwAccess};
 use namada_sdk::eth_bridge::{EthBridgeQueries, EthereumOracleConfig};
+use namada_sdk::queries::EthereumOracleStatus;
 use namada_sdk::tendermint::AppHash;
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
 use thiserror::Error;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
-use super::ethereum_oracle::{self as oracle, last_processed_block};
+use self::protocol_tx_cache::ProtocolTxCache;
+use self::vote_extensions::metrics::VextMetrics;
+use super::ethereum_oracle::{self as oracle, last_processed_block, status};
 use crate::config::{self, genesis, TendermintMode, ValidatorLocalConfig};
 use crate::facade::tendermint::abci::types::{Misbehavior, MisbehaviorKind};
 use crate::facade::tendermint::v0_37::abci::{request, response};
@@ -125,6 +143,12 @@ pub enum Error {
     StorageApi(#[from] storage_api::Error),
     #[error("Transaction replay attempt: {0}")]
     ReplayAttempt(String),
+    #[error("The chain halted at height {0} for an upgrade")]
+    HaltAtHeight(BlockHeight),
+    #[error("Error writing the upgrade marker: {0}")]
+    UpgradeMarker(std::io::Error),
+    #[error("{0}")]
+    Migration(#[from] storage::migrations::Error),
 }
 
 impl From<Error> for TxResult {
@@ -156,6 +180,8 @@ pub enum ErrorCodes {
     FeeError = 12,
     InvalidVoteExtension = 13,
     TooLarge = 14,
+    InvalidValidatorSetUpdate = 15,
+    InvalidLivenessReport = 16,
 }
 
 impl ErrorCodes {
@@ -167,10 +193,21 @@ impl ErrorCodes {
         // to catch potential bugs when adding new codes
         match self {
             Ok | WasmRuntimeError => true,
-            InvalidTx | InvalidSig | InvalidOrder | ExtraTxs
-            | Undecryptable | AllocationError | ReplayTx | InvalidChainId
-            | ExpiredTx | TxGasLimit | FeeError | InvalidVoteExtension
-            | TooLarge => false,
+            InvalidTx
+            | InvalidSig
+            | InvalidOrder
+            | ExtraTxs
+            | Undecryptable
+            | AllocationError
+            | ReplayTx
+            | InvalidChainId
+            | ExpiredTx
+            | TxGasLimit
+            | FeeError
+            | InvalidVoteExtension
+            | TooLarge
+            | InvalidValidatorSetUpdate
+            | InvalidLivenessReport => false,
         }
     }
 }
@@ -223,6 +260,48 @@ pub fn rollback(config: config::Ledger) -> Result<()> {
         .map_err(|e| Error::StorageApi(storage_api::Error::new(e)))
 }
 
+/// Run the pending state migrations against the last committed state and
+/// report the keys they change, without persisting anything
+pub fn dry_run_migrations(config: config::Ledger) -> Result<()> {
+    let chain_dir = config.shell.base_dir.join(config.chain_id.as_str());
+    let genesis = genesis::chain::Finalized::read_toml_files(&chain_dir)
+        .expect("Missing genesis files");
+    let db_path = config.shell.db_dir(&config.chain_id);
+    let mut storage = storage::PersistentStorage::open(
+        db_path,
+        config.chain_id.clone(),
+        genesis.get_native_token().clone(),
+        None,
+        config.shell.storage_read_past_height_limit,
+    );
+    storage
+        .load_last_state()
+        .map_err(|e| Error::StorageApi(storage_api::Error::new(e)))?;
+    let mut wl_storage = WlStorage {
+        storage,
+        write_log: WriteLog::default(),
+    };
+
+    let report = storage::migrations::all().run(&mut wl_storage, true)?;
+    if report.from_version == report.to_version {
+        println!(
+            "The state is at version {}, there are no pending migrations.",
+            report.from_version
+        );
+        return Ok(());
+    }
+    println!(
+        "Migrating the state from version {} to version {} changes {} keys:",
+        report.from_version,
+        report.to_version,
+        report.changed_keys.len()
+    );
+    for key in report.changed_keys {
+        println!("  {key}");
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 #[allow(dead_code, clippy::large_enum_variant)]
 pub(super) enum ShellMode {
@@ -306,6 +385,17 @@ impl ShellMode {
         }
     }
 
+    /// Get the channels to the Ethereum oracle, if this node runs one
+    pub fn eth_oracle_channels(&self) -> Option<&EthereumOracleChannels> {
+        match self {
+            ShellMode::Validator {
+                eth_oracle: Some(eth_oracle),
+                ..
+            } => Some(eth_oracle),
+            _ => None,
+        }
+    }
+
     /// Remove an Ethereum event from the internal queue
     pub fn dequeue_eth_event(&mut self, event: &EthereumEvent) {
         if let ShellMode::Validator {
@@ -414,6 +504,17 @@ where
     pub proposal_data: HashSet<u64>,
     /// Log of events emitted by `FinalizeBlock` ABCI calls.
     event_log: EventLog,
+    /// In-memory cache of the consensus validator sets, populated once per
+    /// block and invalidated whenever the validator sets may be written to
+    consensus_set_cache: ConsensusValidatorSetCache,
+    /// The consensus validators whose votes were missing from the last
+    /// block, to be reported in the liveness vote extension
+    liveness_observations: BTreeSet<Address>,
+    /// Counters of the vote extensions validated and rejected by this node
+    vext_metrics: Mutex<VextMetrics>,
+    /// Hashes of the protocol txs already validated against the last
+    /// committed state
+    protocol_tx_cache: Mutex<ProtocolTxCache>,
 }
 
 /// Channels for communicating with an Ethereum oracle.
@@ -422,6 +523,7 @@ pub struct EthereumOracleChannels {
     ethereum_receiver: EthereumReceiver,
     control_sender: oracle::control::Sender,
     last_processed_block_receiver: last_processed_block::Receiver,
+    status_receiver: status::Receiver,
 }
 
 impl EthereumOracleChannels {
@@ -429,11 +531,26 @@ impl EthereumOracleChannels {
         events_receiver: Receiver<EthereumEvent>,
         control_sender: oracle::control::Sender,
         last_processed_block_receiver: last_processed_block::Receiver,
+        status_receiver: status::Receiver,
     ) -> Self {
         Self {
             ethereum_receiver: EthereumReceiver::new(events_receiver),
             control_sender,
             last_processed_block_receiver,
+            status_receiver,
+        }
+    }
+
+    /// Report the health of the Ethereum oracle, from its latest
+    /// heartbeat and the events queued up for vote extensions.
+    pub fn status(&self) -> EthereumOracleStatus {
+        EthereumOracleStatus {
+            heartbeat: self.status_receiver.borrow().clone(),
+            last_processed_block: self
+                .last_processed_block_receiver
+                .borrow()
+                .clone(),
+            queued_events: self.ethereum_receiver.queue.len() as u64,
         }
     }
 }
@@ -582,7 +699,13 @@ where
             proposal_data: HashSet::new(),
             // TODO: config event log params
             event_log: EventLog::default(),
+            consensus_set_cache: ConsensusValidatorSetCache::default(),
+            liveness_observations: BTreeSet::new(),
+            vext_metrics: Mutex::new(VextMetrics::default()),
+            protocol_tx_cache: Mutex::new(ProtocolTxCache::default()),
         };
+        shell.check_upgrade_marker();
+        shell.migrate_state();
         shell.update_eth_oracle(&Default::default());
         shell
     }
@@ -685,8 +808,12 @@ where
         }
     }
 
-    /// Apply PoS slashes from the evidence
-    fn record_slashes_from_evidence(&mut self) {
+    /// Apply PoS slashes from the evidence. The changes of the validators'
+    /// states that they make are added to the given changes.
+    fn record_slashes_from_evidence(
+        &mut self,
+        state_changes: &mut Vec<ValidatorStateChange>,
+    ) {
         if !self.byzantine_validators.is_empty() {
             let byzantine_validators =
                 mem::take(&mut self.byzantine_validators);
@@ -778,17 +905,26 @@ where
                     evidence_height,
                     current_epoch
                 );
-                if let Err(err) = slash(
+                match with_state_changes(
                     &mut self.wl_storage,
-                    &pos_params,
-                    current_epoch,
-                    evidence_epoch,
-                    evidence_height,
-                    slash_type,
-                    &validator,
-                    validator_set_update_epoch,
+                    ValidatorStateChangeCause::Slash,
+                    |storage| {
+                        slash(
+                            storage,
+                            &pos_params,
+                            current_epoch,
+                            evidence_epoch,
+                            evidence_height,
+                            slash_type,
+                            &validator,
+                            validator_set_update_epoch,
+                        )
+                    },
                 ) {
-                    tracing::error!("Error in slashing: {}", err);
+                    Ok(((), changes)) => state_changes.extend(changes),
+                    Err(err) => {
+                        tracing::error!("Error in slashing: {}", err);
+                    }
                 }
             }
         }
@@ -817,16 +953,44 @@ where
     }
 
     /// Process and apply slashes that have already been recorded for the
-    /// current epoch
-    fn process_slashes(&mut self) {
+    /// current epoch. The processing may be spread over several blocks, in
+    /// which case it's continued in the following blocks. The storage I/O of
+    /// the processing is recorded in the given stats and the changes of the
+    /// validators' states that it makes are added to the given changes.
+    fn process_slashes(
+        &mut self,
+        new_epoch: bool,
+        io_stats: &mut PosIoStats,
+        state_changes: &mut Vec<ValidatorStateChange>,
+    ) -> SlashProcessingStatus {
         let current_epoch = self.wl_storage.storage.block.epoch;
-        if let Err(err) = process_slashes(&mut self.wl_storage, current_epoch) {
-            tracing::error!(
-                "Error while processing slashes queued for epoch {}: {}",
-                current_epoch,
-                err
-            );
-            panic!("Error while processing slashes");
+        match with_state_changes(
+            &mut self.wl_storage,
+            ValidatorStateChangeCause::StakeChange,
+            |storage| {
+                with_io_stats(storage, |storage| {
+                    process_slashes_chunk(storage, current_epoch, new_epoch)
+                })
+            },
+        ) {
+            Ok(((status, io), changes)) => {
+                if status != SlashProcessingStatus::Idle {
+                    io_stats
+                        .entry(PosOp::ProcessSlashes)
+                        .or_default()
+                        .record(&io);
+                }
+                state_changes.extend(changes);
+                status
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Error while processing slashes queued for epoch {}: {}",
+                    current_epoch,
+                    err
+                );
+                panic!("Error while processing slashes");
+            }
         }
     }
 
@@ -837,6 +1001,10 @@ where
             retain_height: tendermint::block::Height::from(0_u32),
             ..Default::default()
         };
+        // checkpoint the Ethereum oracle's progress, such that it gets
+        // persisted along with this block
+        self.bump_last_processed_eth_block();
+
         // commit block's data from write log and store the in DB
         self.wl_storage.commit_block().unwrap_or_else(|e| {
             tracing::error!(
@@ -845,6 +1013,19 @@ where
             )
         });
 
+        // protocol txs must be validated again against the new state
+        self.protocol_tx_cache.lock().unwrap().clear();
+
+        // The validator sets can't change until the next block gets
+        // finalized, so the cache can be populated for the upcoming ABCI calls
+        self.populate_consensus_set_cache().unwrap_or_else(|e| {
+            tracing::error!(
+                "Encountered a storage error while populating the consensus \
+                 validator set cache {:?}",
+                e
+            )
+        });
+
         let root = self.wl_storage.storage.merkle_root();
         tracing::info!(
             "Committed block hash: {}, height: {}",
@@ -853,7 +1034,7 @@ where
         );
         response.data = root.0.to_vec().into();
 
-        self.bump_last_processed_eth_block();
+        self.complete_upgrade();
         self.broadcast_queued_txs();
 
         response
@@ -890,6 +1071,22 @@ where
         }
     }
 
+    /// Return the Ethereum block height from which the oracle should resume
+    /// processing blocks, i.e. the block right after the last one it had
+    /// processed, as checkpointed in storage at commit time.
+    ///
+    /// Returns [`None`] if the oracle has not processed any Ethereum blocks
+    /// yet.
+    fn eth_oracle_resume_height(
+        &self,
+    ) -> Option<ethereum_structs::BlockHeight> {
+        self.wl_storage
+            .storage
+            .ethereum_height
+            .clone()
+            .map(|last_processed| last_processed + 1.into())
+    }
+
     /// Empties all the ledger's queues of transactions to be broadcasted
     /// via CometBFT's P2P network.
     #[inline]
@@ -990,7 +1187,7 @@ where
 
         // Write wrapper hash to WAL
         temp_wl_storage
-            .write_tx_hash(wrapper_hash)
+            .write_tx_hash(wrapper_hash, wrapper.header.expiration)
             .map_err(|e| Error::ReplayAttempt(e.to_string()))
     }
 
@@ -1057,10 +1254,7 @@ where
                 };
 
             let start_block = self
-                .wl_storage
-                .storage
-                .ethereum_height
-                .clone()
+                .eth_oracle_resume_height()
                 .unwrap_or(config.eth_start_height);
             tracing::info!(
                 ?start_block,
@@ -1072,6 +1266,11 @@ where
                 bridge_contract: config.contracts.bridge.address,
                 start_block,
                 active,
+                erc20_min_confirmations: config
+                    .erc20_min_confirmations
+                    .into_iter()
+                    .map(|(asset, confirmations)| (asset, confirmations.into()))
+                    .collect(),
             };
             tracing::info!(
                 ?config,
@@ -1162,6 +1361,18 @@ where
             }
         }
 
+        // Protocol txs already validated against the last committed state
+        // are not validated again
+        if let TxType::Protocol(protocol_tx) = tx.header().tx_type {
+            if self.protocol_tx_cache.lock().unwrap().contains(tx_bytes) {
+                response.log = String::from(VALID_MSG);
+                if let ProtocolTxType::ValSetUpdateVext = protocol_tx.tx {
+                    response.priority = i64::MAX;
+                }
+                return response;
+            }
+        }
+
         // Tx signature check
         let tx_type = match tx.validate_tx() {
             Ok(_) => tx.header(),
@@ -1190,6 +1401,7 @@ where
             };
         }
 
+        let is_protocol_tx = matches!(tx_type.tx_type, TxType::Protocol(_));
         match tx_type.tx_type {
             TxType::Protocol(protocol_tx) => match protocol_tx.tx {
                 ProtocolTxType::EthEventsVext => {
@@ -1270,6 +1482,27 @@ where
                         response.priority = i64::MAX;
                     }
                 }
+                ProtocolTxType::LivenessVext => {
+                    let ext = try_vote_extension!(
+                        "liveness",
+                        response,
+                        ethereum_tx_data_variants::LivenessVext::try_from(&tx),
+                    );
+                    if let Err(err) = self
+                        .validate_liveness_vext_and_get_it_back(
+                            ext,
+                            self.wl_storage.storage.get_last_block_height(),
+                        )
+                    {
+                        response.code = ErrorCodes::InvalidVoteExtension.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Invalid liveness vote extension: \
+                             {err}",
+                        );
+                    } else {
+                        response.log = String::from(VALID_MSG);
+                    }
+                }
                 _ => {
                     response.code = ErrorCodes::InvalidTx.into();
                     response.log = format!(
@@ -1372,6 +1605,9 @@ where
         }
 
         if response.code == ErrorCodes::Ok.into() {
+            if is_protocol_tx {
+                self.protocol_tx_cache.lock().unwrap().insert(tx_bytes);
+            }
             response.log = VALID_MSG.into();
         }
         response
@@ -1539,39 +1775,81 @@ where
             namada_proof_of_stake::read_pos_params(&self.wl_storage)
                 .expect("Could not find the PoS parameters");
 
-        let validator_set_update_fn = if is_genesis {
-            namada_proof_of_stake::genesis_validator_set_tendermint
-        } else {
-            namada_proof_of_stake::validator_set_update_tendermint
+        let convert_update = |update: ValidatorSetUpdate| {
+            let (consensus_key, power) = match update {
+                ValidatorSetUpdate::Consensus(ConsensusValidator {
+                    consensus_key,
+                    bonded_stake,
+                }) => {
+                    let power: i64 = into_tm_voting_power(
+                        pos_params.tm_votes_per_token,
+                        bonded_stake,
+                    );
+                    (consensus_key, power)
+                }
+                ValidatorSetUpdate::Deactivated(consensus_key) => {
+                    // Any validators that have been dropped from the
+                    // consensus set must have voting power set to 0 to
+                    // remove them from the conensus set
+                    let power = 0_i64;
+                    (consensus_key, power)
+                }
+            };
+            validator_conv(consensus_key, power)
         };
 
-        validator_set_update_fn(
-            &self.wl_storage,
-            &pos_params,
-            current_epoch,
-            |update| {
-                let (consensus_key, power) = match update {
-                    ValidatorSetUpdate::Consensus(ConsensusValidator {
-                        consensus_key,
-                        bonded_stake,
-                    }) => {
-                        let power: i64 = into_tm_voting_power(
-                            pos_params.tm_votes_per_token,
-                            bonded_stake,
-                        );
-                        (consensus_key, power)
-                    }
-                    ValidatorSetUpdate::Deactivated(consensus_key) => {
-                        // Any validators that have been dropped from the
-                        // consensus set must have voting power set to 0 to
-                        // remove them from the conensus set
-                        let power = 0_i64;
-                        (consensus_key, power)
-                    }
-                };
-                validator_conv(consensus_key, power)
+        if is_genesis {
+            namada_proof_of_stake::genesis_validator_set_tendermint(
+                &self.wl_storage,
+                &pos_params,
+                current_epoch,
+                convert_update,
+            )
+        } else {
+            namada_proof_of_stake::validator_set_update_tendermint(
+                &self.wl_storage,
+                &pos_params,
+                current_epoch,
+                &self.consensus_set_cache,
+                convert_update,
+            )
+        }
+    }
+
+    /// Get the updates of the consensus validator set that were communicated
+    /// to CometBFT while finalizing the last block, to be recorded on chain by
+    /// a protocol tx in the current block. Returns `None` at any other block.
+    fn get_consensus_validator_set_updates(
+        &self,
+    ) -> storage_api::Result<Option<ConsensusValidatorSetUpdates>> {
+        if self.wl_storage.storage.update_epoch_blocks_delay
+            != Some(EPOCH_SWITCH_BLOCKS_DELAY)
+        {
+            return Ok(None);
+        }
+        let (current_epoch, _gas) = self.wl_storage.storage.get_current_epoch();
+        let updates = self.get_abci_validator_updates(
+            false,
+            |consensus_key, voting_power| ConsensusValidatorUpdate {
+                consensus_key,
+                voting_power,
             },
-        )
+        )?;
+        Ok(Some(ConsensusValidatorSetUpdates {
+            epoch: current_epoch.next(),
+            updates,
+        }))
+    }
+
+    /// Populate the consensus validator set cache with the sets of the
+    /// current and the next epoch, which are the ones read while processing a
+    /// block.
+    fn populate_consensus_set_cache(&mut self) -> storage_api::Result<()> {
+        let (current_epoch, _gas) = self.wl_storage.storage.get_current_epoch();
+        self.consensus_set_cache
+            .populate(&self.wl_storage, current_epoch)?;
+        self.consensus_set_cache
+            .populate(&self.wl_storage, current_epoch.next())
     }
 }
 
@@ -1757,11 +2035,13 @@ mod test_utils {
                 tokio::sync::mpsc::channel(ORACLE_CHANNEL_BUFFER_SIZE);
             let (_, last_processed_block_receiver) =
                 last_processed_block::channel();
+            let (_, status_receiver) = status::channel();
             let (control_sender, control_receiver) = oracle::control::channel();
             let eth_oracle = EthereumOracleChannels::new(
                 eth_receiver,
                 control_sender,
                 last_processed_block_receiver,
+                status_receiver,
             );
             let base_dir = tempdir().unwrap().as_ref().canonicalize().unwrap();
             let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
@@ -2087,10 +2367,12 @@ mod test_utils {
         let (control_sender, _) = oracle::control::channel();
         let (_, last_processed_block_receiver) =
             last_processed_block::channel();
+        let (_, status_receiver) = status::channel();
         let eth_oracle = EthereumOracleChannels::new(
             eth_receiver,
             control_sender,
             last_processed_block_receiver,
+            status_receiver,
         );
         let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
         let tx_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
@@ -2222,10 +2504,12 @@ mod test_utils {
         let (control_sender, _) = oracle::control::channel();
         let (_, last_processed_block_receiver) =
             last_processed_block::channel();
+        let (_, status_receiver) = status::channel();
         let eth_oracle = EthereumOracleChannels::new(
             eth_receiver,
             control_sender,
             last_processed_block_receiver,
+            status_receiver,
         );
         // Reboot the shell and check that the queue was restored from DB
         let shell = Shell::<PersistentDB, PersistentStorageHasher>::new(
@@ -2308,6 +2592,7 @@ mod shell_tests {
 
     use super::*;
     use crate::node::ledger::shell::test_utils;
+    use crate::node::ledger::shell::vote_extensions::metrics::VextKind;
     use crate::wallet;
 
     const GAS_LIMIT_MULTIPLIER: u64 = 100_000;
@@ -2350,6 +2635,23 @@ mod shell_tests {
         assert_eq!(signed_valset_upd.data.signing_epoch, Epoch(0));
     }
 
+    /// Check that the Ethereum oracle is told to resume processing blocks
+    /// right after the last block it had processed, as checkpointed in
+    /// storage.
+    #[test]
+    fn test_eth_oracle_resumes_from_checkpoint() {
+        let (mut shell, _, _, mut control_receiver) =
+            test_utils::setup_at_height(3);
+        while control_receiver.try_recv().is_ok() {}
+
+        shell.wl_storage.storage.ethereum_height = Some(41u64.into());
+        shell.update_eth_oracle(&Default::default());
+
+        let oracle::control::Command::UpdateConfig(config) =
+            control_receiver.try_recv().expect("Test failed");
+        assert_eq!(config.start_block, 42u64.into());
+    }
+
     /// Check that broadcasting expired Ethereum events works
     /// as expected.
     #[test]
@@ -2555,6 +2857,48 @@ mod shell_tests {
         assert_eq!(rsp.code, 0.into());
     }
 
+    /// Test that protocol txs gossiped more than once are only validated
+    /// once per committed block.
+    #[test]
+    fn test_mempool_protocol_tx_replay_cache() {
+        const LAST_HEIGHT: BlockHeight = BlockHeight(3);
+
+        let (mut shell, _recv, _, _) = test_utils::setup_at_height(LAST_HEIGHT);
+
+        let (protocol_key, _) = wallet::defaults::validator_keys();
+        let validator_addr = wallet::defaults::validator_address();
+
+        let ext = ethereum_events::Vext {
+            validator_addr: validator_addr.clone(),
+            block_height: LAST_HEIGHT,
+            ethereum_events: vec![EthereumEvent::TransfersToNamada {
+                nonce: 0u64.into(),
+                transfers: vec![],
+            }],
+        }
+        .sign(&protocol_key);
+        let tx = EthereumTxData::EthEventsVext(ext)
+            .sign(&protocol_key, shell.chain_id.clone())
+            .to_bytes();
+        let validated = |shell: &test_utils::TestShell| {
+            shell.vext_metrics.lock().unwrap().per_validator[&validator_addr]
+                [&VextKind::EthereumEvents]
+                .validated
+        };
+
+        for _ in 0..3 {
+            let rsp = shell.mempool_validate(&tx, Default::default());
+            assert_eq!(rsp.code, 0.into());
+        }
+        assert_eq!(validated(&shell), 1);
+
+        // the cache is cleared upon committing a block
+        shell.commit();
+        let rsp = shell.mempool_validate(&tx, Default::default());
+        assert_eq!(rsp.code, 0.into());
+        assert_eq!(validated(&shell), 2);
+    }
+
     /// Test if Ethereum events validation fails, if the underlying
     /// protocol transaction type is different from the vote extension
     /// contained in the transaction's data field.
@@ -2745,7 +3089,7 @@ mod shell_tests {
         shell
             .wl_storage
             .storage
-            .write_replay_protection_entry(&mut batch, &wrapper_hash_key)
+            .write_replay_protection_entry(&mut batch, &wrapper_hash_key, &[])
             .expect("Test failed");
 
         // Try wrapper tx replay attack
@@ -2786,7 +3130,7 @@ mod shell_tests {
         shell
             .wl_storage
             .storage
-            .write_replay_protection_entry(&mut batch, &inner_hash_key)
+            .write_replay_protection_entry(&mut batch, &inner_hash_key, &[])
             .expect("Test failed");
 
         // Try inner tx replay attack
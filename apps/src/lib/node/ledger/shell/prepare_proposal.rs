@@ -1,31 +1,63 @@
 //! Implementation of the [`RequestPrepareProposal`] ABCI++ method for the Shell
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use namada::core::hints;
 use namada::core::ledger::gas::TxGasMeter;
 use namada::ledger::pos::PosQueries;
 use namada::ledger::protocol::get_fee_unshielding_transaction;
 use namada::ledger::storage::{DBIter, StorageHasher, TempWlStorage, DB};
+use namada::ledger::storage_api::StorageRead;
 use namada::proof_of_stake::find_validator_by_raw_hash;
 use namada::proto::Tx;
 use namada::types::address::Address;
+use namada::types::hash::Hash;
 use namada::types::internal::TxInQueue;
 use namada::types::key::tm_raw_hash_to_string;
+use namada::types::storage::Key;
 use namada::types::time::DateTimeUtc;
+use namada::types::transaction::protocol::EthereumTxData;
 use namada::types::transaction::{DecryptedTx, TxType};
 use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::WasmCacheAccess;
+use namada_sdk::tx::{
+    TX_BOND_WASM, TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
+    TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_INIT_PROPOSAL,
+    TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM, TX_UNBOND_WASM,
+    TX_UNJAIL_VALIDATOR_WASM, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
+};
 
 use super::super::*;
 use super::block_alloc::states::{
-    BuildingDecryptedTxBatch, BuildingProtocolTxBatch,
-    EncryptedTxBatchAllocator, NextState, TryAlloc,
+    BuildingDecryptedTxBatch, BuildingEncryptedTxBatch,
+    BuildingProtocolTxBatch, EncryptedTxBatchAllocator, NextState, TryAlloc,
+    WithEncryptedTxs,
 };
+use super::block_alloc::threshold::{self, Threshold};
 use super::block_alloc::{AllocFailure, BlockAllocator, BlockResources};
+use super::vote_extensions::VextKind;
 use crate::facade::tendermint_proto::google::protobuf::Timestamp;
 use crate::facade::tendermint_proto::v0_37::abci::RequestPrepareProposal;
 use crate::node::ledger::shell::ShellMode;
 use crate::node::ledger::shims::abcipp_shim_types::shim::{response, TxBytes};
 
+/// The PoS and governance txs that are allotted the block space
+/// reserved to priority txs.
+const PRIORITY_TXS: [&str; 12] = [
+    TX_BOND_WASM,
+    TX_UNBOND_WASM,
+    TX_WITHDRAW_WASM,
+    TX_REDELEGATE_WASM,
+    TX_CLAIM_REWARDS_WASM,
+    TX_CHANGE_COMMISSION_WASM,
+    TX_CHANGE_CONSENSUS_KEY_WASM,
+    TX_UNJAIL_VALIDATOR_WASM,
+    TX_DEACTIVATE_VALIDATOR_WASM,
+    TX_REACTIVATE_VALIDATOR_WASM,
+    TX_INIT_PROPOSAL,
+    TX_VOTE_PROPOSAL,
+];
+
 impl<D, H> Shell<D, H>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
@@ -117,12 +149,44 @@ where
                 (&self.wl_storage).into(),
             )
         } else {
+            let alloc: BlockAllocator<
+                BuildingEncryptedTxBatch<WithEncryptedTxs>,
+            > = (&self.wl_storage).into();
             EncryptedTxBatchAllocator::WithEncryptedTxs(
-                (&self.wl_storage).into(),
+                alloc.with_priority_reserve(self.get_priority_reserve()),
             )
         }
     }
 
+    /// Return the fraction of the resources of the encrypted txs bin
+    /// reserved to PoS and governance txs, as configured by this
+    /// validator.
+    fn get_priority_reserve(&self) -> Threshold {
+        match &self.mode {
+            ShellMode::Validator {
+                local_config: Some(local_config),
+                ..
+            } => local_config
+                .priority_block_space_percent
+                .map(|percent| Threshold::new(percent, 100))
+                .unwrap_or(threshold::DEFAULT_PRIORITY_RESERVE),
+            _ => threshold::DEFAULT_PRIORITY_RESERVE,
+        }
+    }
+
+    /// Return the code hashes of the PoS and governance txs, which
+    /// are allotted the block space reserved to priority txs.
+    fn get_priority_tx_code_hashes(&self) -> BTreeSet<Hash> {
+        PRIORITY_TXS
+            .iter()
+            .filter_map(|name| {
+                self.wl_storage
+                    .read(&Key::wasm_code_name(name.to_string()))
+                    .expect("Must be able to read the wasm code hashes")
+            })
+            .collect()
+    }
+
     /// Builds a batch of encrypted transactions, retrieved from
     /// Tendermint's mempool.
     fn build_encrypted_txs(
@@ -141,23 +205,44 @@ where
         let mut temp_wl_storage = TempWlStorage::new(&self.wl_storage.storage);
         let mut vp_wasm_cache = self.vp_wasm_cache.clone();
         let mut tx_wasm_cache = self.tx_wasm_cache.clone();
+        let priority_code_hashes = self.get_priority_tx_code_hashes();
 
-        let txs = txs
+        let (priority_txs, other_txs): (Vec<_>, Vec<_>) = txs
             .iter()
             .filter_map(|tx_bytes| {
-                match self.validate_wrapper_bytes(tx_bytes, block_time, &mut temp_wl_storage, &mut vp_wasm_cache, &mut tx_wasm_cache, block_proposer) {
-                    Ok(gas) => {
+                match self.validate_wrapper_bytes(
+                    tx_bytes,
+                    block_time,
+                    &mut temp_wl_storage,
+                    &mut vp_wasm_cache,
+                    &mut tx_wasm_cache,
+                    block_proposer,
+                ) {
+                    Ok((gas, tx)) => {
                         temp_wl_storage.write_log.commit_tx();
-                        Some((tx_bytes.to_owned(), gas))
-                    },
+                        let priority = tx
+                            .code_sec()
+                            .map(|code| {
+                                priority_code_hashes.contains(&code.code.hash())
+                            })
+                            .unwrap_or(false);
+                        Some((tx_bytes.to_owned(), gas, priority))
+                    }
                     Err(()) => {
                         temp_wl_storage.write_log.drop_tx();
                         None
                     }
                 }
             })
-            .take_while(|(tx_bytes, tx_gas)| {
-                alloc.try_alloc(BlockResources::new(&tx_bytes[..], tx_gas.to_owned()))
+            .partition(|(_, _, priority)| *priority);
+
+        // priority txs are allocated first, so that other txs
+        // can make use of any reserved space they leave over
+        let txs = priority_txs
+            .into_iter()
+            .chain(other_txs)
+            .take_while(|(tx_bytes, tx_gas, priority)| {
+                alloc.try_alloc(BlockResources::new(&tx_bytes[..], tx_gas.to_owned()).with_priority(*priority))
                     .map_or_else(
                         |status| match status {
                             AllocFailure::Rejected { bin_resource_left} => {
@@ -186,14 +271,15 @@ where
                         |()| true,
                     )
             })
-            .map(|(tx, _)| tx)
+            .map(|(tx, _, _)| tx)
             .collect();
         let alloc = alloc.next_state();
 
         (txs, alloc)
     }
 
-    /// Validity checks on a wrapper tx
+    /// Validity checks on a wrapper tx. Returns the gas limit of
+    /// the wrapper, along with the deserialized tx.
     #[allow(clippy::too_many_arguments)]
     fn validate_wrapper_bytes<CA>(
         &self,
@@ -203,7 +289,7 @@ where
         vp_wasm_cache: &mut VpCache<CA>,
         tx_wasm_cache: &mut TxCache<CA>,
         block_proposer: &Address,
-    ) -> Result<u64, ()>
+    ) -> Result<(u64, Tx), ()>
     where
         CA: 'static + WasmCacheAccess + Sync,
     {
@@ -239,7 +325,7 @@ where
                 Some(block_proposer),
                 true,
             ) {
-                Ok(()) => Ok(u64::from(wrapper.gas_limit)),
+                Ok(()) => Ok((u64::from(wrapper.gas_limit), tx)),
                 Err(_) => Err(()),
             }
         } else {
@@ -324,7 +410,15 @@ where
             return vec![];
         }
 
-        let deserialized_iter = self.deserialize_vote_extensions(txs);
+        // the consensus validator set updates are allocated first, so that
+        // they are always recorded in the block following their submission
+        // to CometBFT
+        let deserialized_iter = self
+            .build_consensus_validator_set_updates_tx()
+            .into_iter()
+            .chain(order_vote_extensions(
+                self.deserialize_vote_extensions(txs),
+            ));
         let pos_queries = self.wl_storage.pos_queries();
 
         deserialized_iter.take_while(|tx_bytes|
@@ -367,6 +461,38 @@ where
         )
         .collect()
     }
+
+    /// Builds the protocol tx recording the updates of the consensus
+    /// validator set that were communicated to CometBFT in the last block,
+    /// if any.
+    fn build_consensus_validator_set_updates_tx(&self) -> Option<TxBytes> {
+        let updates = self
+            .get_consensus_validator_set_updates()
+            .expect("Must be able to read the consensus validator sets")?;
+        let protocol_key = self.mode.get_protocol_key()?;
+        let tx = EthereumTxData::ConsensusValidatorSetUpdates(updates)
+            .sign(protocol_key, self.chain_id.clone());
+        Some(tx.to_bytes().into())
+    }
+}
+
+/// Arrange the vote extension protocol txs of a proposal in a canonical
+/// order, dropping duplicates.
+///
+/// Txs are grouped by [`VextKind`], i.e. Ethereum events come first,
+/// followed by Bridge pool roots, validator set updates and liveness
+/// data. Within each group, txs are sorted by the hash of their bytes.
+/// Hence, validators that received the same set of vote extensions
+/// build byte-identical proposals, regardless of the order in which
+/// the vote extensions were gossiped to them, or how many times.
+fn order_vote_extensions(
+    vexts: impl Iterator<Item = (VextKind, TxBytes)>,
+) -> Vec<TxBytes> {
+    vexts
+        .map(|(kind, tx_bytes)| ((kind, Hash::sha256(&tx_bytes)), tx_bytes))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect()
 }
 
 #[cfg(test)]
@@ -382,6 +508,7 @@ mod test_prepare_proposal {
     use namada::ledger::gas::Gas;
     use namada::ledger::pos::PosQueries;
     use namada::ledger::replay_protection;
+    use namada::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
     use namada::proof_of_stake::types::WeightedValidator;
     use namada::proof_of_stake::{
         consensus_validator_set_handle,
@@ -403,8 +530,10 @@ mod test_prepare_proposal {
     use super::*;
     use crate::config::ValidatorLocalConfig;
     use crate::node::ledger::shell::test_utils::{
-        self, gen_keypair, get_pkh_from_address, TestShell,
+        self, gen_keypair, get_pkh_from_address, ProcessProposal, TestError,
+        TestShell,
     };
+    use crate::node::ledger::shell::ErrorCodes;
     use crate::node::ledger::shims::abcipp_shim_types::shim::request::FinalizeBlock;
     use crate::wallet;
 
@@ -1132,6 +1261,7 @@ mod test_prepare_proposal {
                     namada::core::types::address::nam(),
                     Amount::from(1),
                 )]),
+                priority_block_space_percent: None,
             });
         }
 
@@ -1224,6 +1354,7 @@ mod test_prepare_proposal {
                     namada::core::types::address::nam(),
                     Amount::from(100),
                 )]),
+                priority_block_space_percent: None,
             });
         }
 
@@ -1379,6 +1510,57 @@ mod test_prepare_proposal {
         assert!(result.txs.is_empty());
     }
 
+    /// Test that the consensus validator set updates communicated to
+    /// CometBFT in the last block are proposed in a single protocol tx,
+    /// which is accepted by `ProcessProposal` only once.
+    #[test]
+    fn test_prepare_proposal_consensus_validator_set_updates() {
+        const LAST_HEIGHT: BlockHeight = BlockHeight(3);
+
+        let (mut shell, _recv, _, _) = test_utils::setup_at_height(LAST_HEIGHT);
+
+        // no updates are proposed if none were communicated to CometBFT
+        let rsp = shell.prepare_proposal(RequestPrepareProposal::default());
+        assert!(rsp.txs.is_empty());
+
+        shell.wl_storage.storage.update_epoch_blocks_delay =
+            Some(EPOCH_SWITCH_BLOCKS_DELAY);
+        let rsp = shell.prepare_proposal(RequestPrepareProposal::default());
+        assert_eq!(rsp.txs.len(), 1);
+
+        let got = Tx::try_from(&rsp.txs[0][..]).unwrap();
+        let updates = match (&got).try_into().expect("Test failed") {
+            EthereumTxData::ConsensusValidatorSetUpdates(updates) => updates,
+            _ => panic!("Test failed"),
+        };
+        let (current_epoch, _gas) =
+            shell.wl_storage.storage.get_current_epoch();
+        assert_eq!(updates.epoch, current_epoch.next());
+        assert_eq!(
+            Some(updates),
+            shell.get_consensus_validator_set_updates().unwrap()
+        );
+
+        let tx = rsp.txs[0].to_vec();
+        shell
+            .process_proposal(ProcessProposal {
+                txs: vec![tx.clone()],
+            })
+            .expect("Test failed");
+        match shell.process_proposal(ProcessProposal {
+            txs: vec![tx.clone(), tx],
+        }) {
+            Err(TestError::RejectProposal(results)) => {
+                assert_eq!(results[0].result.code, u32::from(ErrorCodes::Ok));
+                assert_eq!(
+                    results[1].result.code,
+                    u32::from(ErrorCodes::ExtraTxs)
+                );
+            }
+            _ => panic!("Test failed"),
+        }
+    }
+
     /// Test that Ethereum events with outdated nonces are
     /// not proposed during `PrepareProposal`.
     #[test]
@@ -1489,4 +1671,45 @@ mod test_prepare_proposal {
             assert_eq!(found_event, event2);
         }
     }
+
+    /// Test that the vote extension protocol txs of a proposal are
+    /// deduplicated, and ordered independently of the order in which
+    /// they were received.
+    #[test]
+    fn test_deterministic_protocol_tx_order() {
+        const LAST_HEIGHT: BlockHeight = BlockHeight(3);
+
+        let (shell, _recv, _, _) = test_utils::setup_at_height(LAST_HEIGHT);
+
+        let (protocol_key, _) = wallet::defaults::validator_keys();
+        let validator_addr = wallet::defaults::validator_address();
+
+        let [tx1, tx2]: [TxBytes; 2] = [10u64, 11u64].map(|nonce| {
+            let ext = ethereum_events::Vext {
+                validator_addr: validator_addr.clone(),
+                block_height: LAST_HEIGHT,
+                ethereum_events: vec![EthereumEvent::TransfersToNamada {
+                    nonce: nonce.into(),
+                    transfers: vec![],
+                }],
+            }
+            .sign(&protocol_key);
+            EthereumTxData::EthEventsVext(ext)
+                .sign(&protocol_key, shell.chain_id.clone())
+                .to_bytes()
+                .into()
+        });
+
+        let proposal = |txs: Vec<TxBytes>| {
+            shell
+                .prepare_proposal(RequestPrepareProposal {
+                    txs,
+                    ..Default::default()
+                })
+                .txs
+        };
+        let proposed = proposal(vec![tx1.clone(), tx2.clone(), tx1.clone()]);
+        assert_eq!(proposed.len(), 2);
+        assert_eq!(proposed, proposal(vec![tx2, tx1]));
+    }
 }
@@ -16,6 +16,8 @@ use namada::types::transaction::protocol::{
 use namada_sdk::eth_bridge::{EthBridgeQueries, SendValsetUpd};
 
 use super::block_alloc::{BlockSpace, EncryptedTxsBins};
+use super::vote_extensions::aggregator::AggregatableVext;
+use super::vote_extensions::VoteExtensionError;
 use super::*;
 use crate::facade::tendermint_proto::v0_37::abci::RequestProcessProposal;
 use crate::node::ledger::shell::block_alloc::{AllocFailure, TxBin};
@@ -39,6 +41,9 @@ pub struct ValidationMeta {
     pub decrypted_queue_has_remaining_txs: bool,
     /// Check if a block has decrypted txs.
     pub has_decrypted_txs: bool,
+    /// Check if a block has the consensus validator set updates protocol
+    /// tx.
+    pub has_consensus_validator_set_updates: bool,
 }
 
 impl<D, H> From<&WlStorage<D, H>> for ValidationMeta
@@ -57,6 +62,7 @@ where
         Self {
             decrypted_queue_has_remaining_txs: false,
             has_decrypted_txs: false,
+            has_consensus_validator_set_updates: false,
             encrypted_txs_bins: encrypted_txs_bin,
             txs_bin,
         }
@@ -210,8 +216,13 @@ where
     /// in the sum of the total voting power of all received vote extensions.
     ///
     /// At least 2/3 of validators by voting power must have included vote
-    /// extensions for this function to consider a proposal valid.
-    fn validate_vexts_in_proposal<I>(&self, mut vote_extensions: I) -> TxResult
+    /// extensions for this function to consider a proposal valid. Otherwise,
+    /// the proposal is rejected with the given error code.
+    fn validate_vexts_in_proposal<I>(
+        &self,
+        mut vote_extensions: I,
+        rejection_code: ErrorCodes,
+    ) -> TxResult
     where
         I: Iterator<Item = Option<namada::types::token::Amount>>,
     {
@@ -222,7 +233,7 @@ where
             }
         } else {
             TxResult {
-                code: ErrorCodes::InvalidVoteExtension.into(),
+                code: rejection_code.into(),
                 info: "Process proposal rejected this proposal because at \
                        least one of the vote extensions included was invalid."
                     .into(),
@@ -230,6 +241,91 @@ where
         }
     }
 
+    /// Checks if all the vote extensions in a digest included in a
+    /// proposal are valid, and if the validators who signed them exceed
+    /// the vote extension digest quorum of the total voting power, such
+    /// that a proposer cannot include under-signed digests.
+    fn validate_vext_digest_in_proposal<T, I>(
+        &self,
+        vote_extensions: I,
+        rejection_code: ErrorCodes,
+    ) -> TxResult
+    where
+        T: AggregatableVext,
+        I: Iterator<
+            Item = std::result::Result<
+                (namada::types::token::Amount, T),
+                VoteExtensionError,
+            >,
+        >,
+    {
+        let mut aggregator = self.new_vext_aggregator();
+        for maybe_ext in vote_extensions {
+            let Ok((voting_power, ext)) = maybe_ext else {
+                return TxResult {
+                    code: rejection_code.into(),
+                    info: "Process proposal rejected this proposal because \
+                           at least one of the vote extensions included was \
+                           invalid."
+                        .into(),
+                };
+            };
+            aggregator.insert(voting_power, ext);
+        }
+        if !aggregator.has_quorum() {
+            return TxResult {
+                code: rejection_code.into(),
+                info: "Process proposal rejected this proposal because the \
+                       vote extensions included in a digest were not signed \
+                       by a quorum of the validators."
+                    .into(),
+            };
+        }
+        TxResult {
+            code: ErrorCodes::Ok.into(),
+            info: "Process proposal accepted this transaction".into(),
+        }
+    }
+
+    /// Checks that the consensus validator set updates included in a
+    /// proposal were signed by the block proposer and are the same as the
+    /// ones that were communicated to CometBFT in the last block.
+    fn validate_consensus_validator_set_updates(
+        &self,
+        tx: &Tx,
+        signer: &common::PublicKey,
+        block_proposer: &Address,
+    ) -> std::result::Result<(), String> {
+        let signed_by_proposer = self
+            .wl_storage
+            .pos_queries()
+            .get_validator_from_protocol_pk(signer, None)
+            .map(|validator| &validator.address == block_proposer)
+            .unwrap_or_default();
+        if !signed_by_proposer {
+            return Err(
+                "the updates were not signed by the block proposer".to_string()
+            );
+        }
+        let updates =
+            ethereum_tx_data_variants::ConsensusValidatorSetUpdates::try_from(
+                tx,
+            )
+            .map_err(|err| err.to_string())?;
+        let expected = self
+            .get_consensus_validator_set_updates()
+            .map_err(|err| err.to_string())?;
+        match expected {
+            Some(expected) if expected == updates => Ok(()),
+            Some(_) => Err("the updates do not match the ones communicated \
+                            to CometBFT in the last block"
+                .to_string()),
+            None => Err("no validator set updates were communicated to \
+                         CometBFT in the last block"
+                .to_string()),
+        }
+    }
+
     /// Checks if the Tx can be deserialized from bytes. Checks the fees and
     /// signatures of the fee payer for a transaction if it is a wrapper tx.
     ///
@@ -297,6 +393,11 @@ where
             };
         }
 
+        // protocol txs validated by `CheckTx` against the last committed
+        // state need not have their signatures verified again
+        let validated_in_mempool =
+            self.protocol_tx_cache.lock().unwrap().contains(tx_bytes);
+
         let maybe_tx = Tx::try_from(tx_bytes).map_or_else(
             |err| {
                 tracing::debug!(
@@ -313,6 +414,9 @@ where
             |tx| {
                 let tx_chain_id = tx.header.chain_id.clone();
                 let tx_expiration = tx.header.expiration;
+                if validated_in_mempool {
+                    return Ok((tx_chain_id, tx_expiration, tx));
+                }
                 if let Err(err) = tx.validate_tx() {
                     // This occurs if the wrapper / protocol tx signature is
                     // invalid
@@ -329,11 +433,13 @@ where
             Err(tx_result) => return tx_result,
         };
 
-        if let Err(err) = tx.validate_tx() {
-            return TxResult {
-                code: ErrorCodes::InvalidSig.into(),
-                info: err.to_string(),
-            };
+        if !validated_in_mempool {
+            if let Err(err) = tx.validate_tx() {
+                return TxResult {
+                    code: ErrorCodes::InvalidSig.into(),
+                    info: err.to_string(),
+                };
+            }
         }
         match tx.header().tx_type {
             // If it is a raw transaction, we do no further validation
@@ -368,6 +474,21 @@ where
                         };
                     }
                 }
+                // vote extensions validated by `CheckTx` are not validated
+                // again, except for validator set updates, which `CheckTx`
+                // validates against the last committed epoch
+                if validated_in_mempool
+                    && !matches!(
+                        protocol_tx.tx,
+                        ProtocolTxType::ValSetUpdateVext
+                    )
+                {
+                    return TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "Process Proposal accepted this transaction"
+                            .into(),
+                    };
+                }
                 match protocol_tx.tx {
                     ProtocolTxType::EthEventsVext => {
                         ethereum_tx_data_variants::EthEventsVext::try_from(&tx)
@@ -448,7 +569,8 @@ where
                         })
                         .unwrap_or_else(|err| {
                             TxResult {
-                                code: ErrorCodes::InvalidVoteExtension.into(),
+                                code: ErrorCodes::InvalidValidatorSetUpdate
+                                    .into(),
                                 info: format!(
                                     "Process proposal rejected this proposal \
                                      because one of the included validator \
@@ -458,6 +580,33 @@ where
                             }
                         })
                     }
+                    ProtocolTxType::LivenessVext => {
+                        ethereum_tx_data_variants::LivenessVext::try_from(&tx)
+                            .map_err(|err| err.to_string())
+                            .and_then(|ext| {
+                                self.validate_liveness_vext_and_get_it_back(
+                                    ext,
+                                    self.wl_storage
+                                        .storage
+                                        .get_last_block_height(),
+                                )
+                                .map(|_| TxResult {
+                                    code: ErrorCodes::Ok.into(),
+                                    info: "Process Proposal accepted this \
+                                           transaction"
+                                        .into(),
+                                })
+                                .map_err(|err| err.to_string())
+                            })
+                            .unwrap_or_else(|err| TxResult {
+                                code: ErrorCodes::InvalidLivenessReport.into(),
+                                info: format!(
+                                    "Process proposal rejected this proposal \
+                                     because one of the included liveness \
+                                     vote extensions was invalid: {err}"
+                                ),
+                            })
+                    }
                     ProtocolTxType::EthereumEvents => {
                         let digest =
                             ethereum_tx_data_variants::EthereumEvents::try_from(
@@ -467,13 +616,13 @@ where
                         let extensions = digest.decompress(
                             self.wl_storage.storage.get_last_block_height(),
                         );
-                        let valid_extensions = self
-                            .validate_eth_events_vext_list(extensions)
-                            .map(|maybe_ext| {
-                                maybe_ext.ok().map(|(power, _)| power)
-                            });
+                        let valid_extensions =
+                            self.validate_eth_events_vext_list(extensions);
 
-                        self.validate_vexts_in_proposal(valid_extensions)
+                        self.validate_vext_digest_in_proposal(
+                            valid_extensions,
+                            ErrorCodes::InvalidVoteExtension,
+                        )
                     }
                     ProtocolTxType::BridgePool => {
                         let digest =
@@ -481,26 +630,39 @@ where
                                 &tx,
                             )
                             .unwrap();
-                        let valid_extensions = self
-                            .validate_bp_roots_vext_list(digest)
-                            .map(|maybe_ext| {
-                                maybe_ext.ok().map(|(power, _)| power)
-                            });
-                        self.validate_vexts_in_proposal(valid_extensions)
+                        let valid_extensions =
+                            self.validate_bp_roots_vext_list(digest);
+                        self.validate_vext_digest_in_proposal(
+                            valid_extensions,
+                            ErrorCodes::InvalidVoteExtension,
+                        )
                     }
                     ProtocolTxType::ValidatorSetUpdate => {
                         let digest =
                             ethereum_tx_data_variants::ValidatorSetUpdate::try_from(
                                 &tx,
-                            )
-                            .unwrap();
+                            );
+                        let digest = match digest {
+                            Ok(digest) => digest,
+                            Err(err) => {
+                                return TxResult {
+                                    code: ErrorCodes::InvalidValidatorSetUpdate
+                                        .into(),
+                                    info: format!(
+                                        "Process proposal rejected a \
+                                         malformed validator set update: {err}"
+                                    ),
+                                };
+                            }
+                        };
                         if !self
                             .wl_storage
                             .ethbridge_queries()
                             .must_send_valset_upd(SendValsetUpd::AtPrevHeight)
                         {
                             return TxResult {
-                                code: ErrorCodes::InvalidVoteExtension.into(),
+                                code: ErrorCodes::InvalidValidatorSetUpdate
+                                    .into(),
                                 info: "Process proposal rejected a validator \
                                        set update vote extension issued at an \
                                        invalid block height"
@@ -517,7 +679,72 @@ where
                                 maybe_ext.ok().map(|(power, _)| power)
                             });
 
-                        self.validate_vexts_in_proposal(valid_extensions)
+                        self.validate_vexts_in_proposal(
+                            valid_extensions,
+                            ErrorCodes::InvalidValidatorSetUpdate,
+                        )
+                    }
+                    ProtocolTxType::Liveness => {
+                        let digest =
+                            ethereum_tx_data_variants::Liveness::try_from(&tx);
+                        let digest = match digest {
+                            Ok(digest) => digest,
+                            Err(err) => {
+                                return TxResult {
+                                    code: ErrorCodes::InvalidLivenessReport
+                                        .into(),
+                                    info: format!(
+                                        "Process proposal rejected a \
+                                         malformed liveness report: {err}"
+                                    ),
+                                };
+                            }
+                        };
+                        let extensions = digest.decompress(
+                            self.wl_storage.storage.get_last_block_height(),
+                        );
+                        let valid_extensions = self
+                            .validate_liveness_vext_list(extensions)
+                            .map(|maybe_ext| {
+                                maybe_ext.ok().map(|(power, _)| power)
+                            });
+
+                        self.validate_vexts_in_proposal(
+                            valid_extensions,
+                            ErrorCodes::InvalidLivenessReport,
+                        )
+                    }
+                    ProtocolTxType::ConsensusValidatorSetUpdates => {
+                        if metadata.has_consensus_validator_set_updates {
+                            return TxResult {
+                                code: ErrorCodes::ExtraTxs.into(),
+                                info: "Process proposal rejected a duplicate \
+                                       consensus validator set updates \
+                                       transaction"
+                                    .into(),
+                            };
+                        }
+                        metadata.has_consensus_validator_set_updates = true;
+                        self.validate_consensus_validator_set_updates(
+                            &tx,
+                            &protocol_tx.pk,
+                            block_proposer,
+                        )
+                        .map(|()| TxResult {
+                            code: ErrorCodes::Ok.into(),
+                            info: "Process Proposal accepted this transaction"
+                                .into(),
+                        })
+                        .unwrap_or_else(|err| {
+                            TxResult {
+                                code: ErrorCodes::InvalidValidatorSetUpdate
+                                    .into(),
+                                info: format!(
+                                    "Process proposal rejected the consensus \
+                                     validator set updates: {err}"
+                                ),
+                            }
+                        })
                     }
                 }
             }
@@ -690,6 +917,7 @@ where
 /// are covered by the e2e tests.
 #[cfg(test)]
 mod test_process_proposal {
+    use namada::core::ledger::eth_bridge::storage::vext_digest_quorum_key;
     use namada::ledger::replay_protection;
     use namada::ledger::storage_api::StorageWrite;
     use namada::proto::{
@@ -701,9 +929,12 @@ mod test_process_proposal {
     use namada::types::time::DateTimeUtc;
     use namada::types::token;
     use namada::types::token::Amount;
-    use namada::types::transaction::protocol::EthereumTxData;
+    use namada::types::transaction::protocol::{EthereumTxData, ProtocolTx};
     use namada::types::transaction::{Fee, WrapperTx};
-    use namada::types::vote_extensions::{bridge_pool_roots, ethereum_events};
+    use namada::types::vote_extensions::{
+        bridge_pool_roots, ethereum_events, liveness,
+    };
+    use namada::types::voting_power::FractionalVotingPower;
 
     use super::*;
     use crate::node::ledger::shell::test_utils::{
@@ -813,6 +1044,45 @@ mod test_process_proposal {
         );
     }
 
+    /// Check that we reject a Bridge pool roots digest whose signers
+    /// don't exceed the vote extension digest quorum.
+    #[test]
+    fn check_rejected_bp_roots_digest_without_quorum() {
+        let (mut shell, _a, _b, _c) =
+            test_utils::setup_with_cfg(test_utils::SetupCfg {
+                last_height: 1u64,
+                num_validators: 2,
+                ..Default::default()
+            });
+        shell.wl_storage.storage.block.height =
+            shell.wl_storage.storage.get_last_block_height();
+        shell.commit();
+        let protocol_key = shell.mode.get_protocol_key().expect("Test failed");
+        // the digest is only signed by one of the two validators,
+        // holding half of the voting power
+        let vote_ext = shell.extend_vote_with_bp_roots().expect("Test failed");
+        let tx = EthereumTxData::BridgePool(vote_ext.into())
+            .sign(protocol_key, shell.chain_id.clone())
+            .to_bytes();
+        assert_eq!(
+            rejected_tx_code(&mut shell, tx.clone()),
+            u32::from(ErrorCodes::InvalidVoteExtension)
+        );
+
+        // lower the quorum below the voting power of the signers
+        shell
+            .wl_storage
+            .write(&vext_digest_quorum_key(), FractionalVotingPower::ONE_THIRD)
+            .expect("Test failed");
+        let request = ProcessProposal { txs: vec![tx] };
+        let [resp]: [ProcessedTx; 1] = shell
+            .process_proposal(request)
+            .expect("Test failed")
+            .try_into()
+            .expect("Test failed");
+        assert_eq!(resp.result.code, u32::from(ErrorCodes::Ok));
+    }
+
     fn check_rejected_eth_events(
         shell: &mut TestShell,
         vote_extension: ethereum_events::SignedVext,
@@ -925,6 +1195,86 @@ mod test_process_proposal {
         check_rejected_eth_events(&mut shell, ext, protocol_key);
     }
 
+    /// Get the error code of the single tx of a rejected proposal
+    fn rejected_tx_code(shell: &mut TestShell, tx: Vec<u8>) -> u32 {
+        let request = ProcessProposal { txs: vec![tx] };
+        match shell.process_proposal(request) {
+            Err(TestError::RejectProposal(resp)) => match resp.as_slice() {
+                [resp] => resp.result.code,
+                _ => panic!("Test failed"),
+            },
+            _ => panic!("Test failed"),
+        }
+    }
+
+    /// Test that PoS-affecting protocol txs whose data can't be decoded are
+    /// rejected with their dedicated error codes, rather than failing later
+    /// in `FinalizeBlock`.
+    #[test]
+    fn test_reject_malformed_pos_protocol_txs() {
+        const LAST_HEIGHT: BlockHeight = BlockHeight(3);
+        let (mut shell, _recv, _, _) = test_utils::setup_at_height(LAST_HEIGHT);
+        let protocol_key = shell.mode.get_protocol_key().expect("Test failed");
+        let protocol_key = protocol_key.clone();
+
+        for (tx_type, expected) in [
+            (
+                ProtocolTxType::ValidatorSetUpdate,
+                ErrorCodes::InvalidValidatorSetUpdate,
+            ),
+            (ProtocolTxType::Liveness, ErrorCodes::InvalidLivenessReport),
+        ] {
+            let mut tx =
+                Tx::from_type(TxType::Protocol(Box::new(ProtocolTx {
+                    pk: protocol_key.ref_to(),
+                    tx: tx_type,
+                })));
+            tx.header.chain_id = shell.chain_id.clone();
+            tx.set_data(Data::new(vec![0xff; 3]));
+            tx.add_section(Section::Signature(Signature::new(
+                tx.sechashes(),
+                [(0, protocol_key.clone())].into_iter().collect(),
+                None,
+            )));
+            assert_eq!(
+                rejected_tx_code(&mut shell, tx.to_bytes()),
+                u32::from(expected)
+            );
+        }
+    }
+
+    /// Test that a liveness vote extension with an invalid signature or
+    /// issued for a future block height is rejected with a dedicated error
+    /// code.
+    #[test]
+    fn test_reject_invalid_liveness_vext() {
+        const LAST_HEIGHT: BlockHeight = BlockHeight(3);
+        let (mut shell, _recv, _, _) = test_utils::setup_at_height(LAST_HEIGHT);
+        let (protocol_key, _) = wallet::defaults::validator_keys();
+        let addr = wallet::defaults::validator_address();
+
+        let mut ext = liveness::Vext::empty(LAST_HEIGHT, addr.clone())
+            .sign(&protocol_key);
+        ext.sig = test_utils::invalidate_signature(ext.sig);
+        let tx = EthereumTxData::LivenessVext(ext)
+            .sign(&protocol_key, shell.chain_id.clone())
+            .to_bytes();
+        assert_eq!(
+            rejected_tx_code(&mut shell, tx),
+            u32::from(ErrorCodes::InvalidLivenessReport)
+        );
+
+        let ext = liveness::Vext::empty(LAST_HEIGHT.next_height(), addr)
+            .sign(&protocol_key);
+        let tx = EthereumTxData::LivenessVext(ext)
+            .sign(&protocol_key, shell.chain_id.clone())
+            .to_bytes();
+        assert_eq!(
+            rejected_tx_code(&mut shell, tx),
+            u32::from(ErrorCodes::InvalidLivenessReport)
+        );
+    }
+
     /// Test that if a wrapper tx is not signed, the block is rejected
     /// by [`process_proposal`].
     #[test]
@@ -1423,7 +1773,7 @@ mod test_process_proposal {
         shell
             .wl_storage
             .storage
-            .write_replay_protection_entry(&mut batch, &hash_key)
+            .write_replay_protection_entry(&mut batch, &hash_key, &[])
             .expect("Test failed");
 
         // Run validation
@@ -1549,7 +1899,7 @@ mod test_process_proposal {
         shell
             .wl_storage
             .storage
-            .write_replay_protection_entry(&mut batch, &hash_key)
+            .write_replay_protection_entry(&mut batch, &hash_key, &[])
             .expect("Test failed");
 
         // Run validation
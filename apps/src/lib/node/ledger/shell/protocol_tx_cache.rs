@@ -0,0 +1,82 @@
+//! In-memory cache of the hashes of recently validated protocol txs.
+//!
+//! The same vote extension protocol tx is usually gossiped to a node by
+//! many of its peers. Since the validity of a protocol tx only depends on
+//! the last committed state, once a tx has been validated, its signatures
+//! need not be verified again until the next block is committed.
+
+use std::num::NonZeroUsize;
+
+use clru::CLruCache;
+use namada::types::hash::Hash;
+
+/// The maximum number of protocol tx hashes kept in a [`ProtocolTxCache`].
+const PROTOCOL_TX_CACHE_CAPACITY: usize = 4096;
+
+/// LRU cache of the hashes of the protocol txs found to be valid against
+/// the last committed state.
+#[derive(Debug)]
+pub struct ProtocolTxCache {
+    hashes: CLruCache<Hash, ()>,
+}
+
+impl Default for ProtocolTxCache {
+    fn default() -> Self {
+        Self::new(
+            NonZeroUsize::new(PROTOCOL_TX_CACHE_CAPACITY)
+                .expect("The protocol tx cache capacity must be non-zero"),
+        )
+    }
+}
+
+impl ProtocolTxCache {
+    /// Create a new cache holding at most `capacity` tx hashes.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            hashes: CLruCache::new(capacity),
+        }
+    }
+
+    /// Check if the protocol tx with the given raw bytes has already
+    /// been validated against the last committed state.
+    pub fn contains(&mut self, tx_bytes: &[u8]) -> bool {
+        self.hashes.get(&Hash::sha256(tx_bytes)).is_some()
+    }
+
+    /// Record that the protocol tx with the given raw bytes is valid
+    /// against the last committed state.
+    pub fn insert(&mut self, tx_bytes: &[u8]) {
+        self.hashes.put(Hash::sha256(tx_bytes), ());
+    }
+
+    /// Forget all cached tx hashes. Must be called whenever a new block
+    /// is committed, as the validity of protocol txs may have changed.
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+}
+
+#[cfg(test)]
+mod test_protocol_tx_cache {
+    use super::*;
+
+    /// Test that the cache evicts the least recently seen tx hashes,
+    /// and that it can be cleared.
+    #[test]
+    fn test_protocol_tx_cache() {
+        let mut cache = ProtocolTxCache::new(NonZeroUsize::new(2).unwrap());
+        cache.insert(b"tx 1");
+        cache.insert(b"tx 2");
+        assert!(cache.contains(b"tx 1"));
+
+        // "tx 2" is the least recently seen tx
+        cache.insert(b"tx 3");
+        assert!(cache.contains(b"tx 1"));
+        assert!(!cache.contains(b"tx 2"));
+        assert!(cache.contains(b"tx 3"));
+
+        cache.clear();
+        assert!(!cache.contains(b"tx 1"));
+        assert!(!cache.contains(b"tx 3"));
+    }
+}
@@ -1,8 +1,8 @@
 //! Shell methods for querying state
 
-use namada::ledger::dry_run_tx;
 use namada::ledger::queries::{RequestCtx, ResponseQuery};
 use namada::ledger::storage_api::token;
+use namada::ledger::{dry_run_tx, estimate_gas};
 use namada::types::address::Address;
 
 use super::*;
@@ -29,6 +29,12 @@ where
         // Invoke the root RPC handler - returns borsh-encoded data on success
         let result = if query.path == "/shell/dry_run_tx" {
             dry_run_tx(ctx, &query)
+        } else if query.path == "/shell/estimate_gas" {
+            estimate_gas(ctx, &query)
+        } else if query.path == "/shell/vext_metrics" {
+            Ok(self.vext_metrics())
+        } else if query.path == "/shell/eth_bridge/oracle/status" {
+            Ok(self.eth_oracle_status())
         } else {
             namada::ledger::queries::handle_path(ctx, &query)
         };
@@ -47,6 +53,29 @@ where
         }
     }
 
+    /// Return a borsh-encoded snapshot of the counters of the vote
+    /// extensions validated and rejected by this node.
+    fn vext_metrics(&self) -> ResponseQuery<Vec<u8>> {
+        let metrics = self.vext_metrics.lock().unwrap().clone();
+        ResponseQuery {
+            data: metrics.serialize_to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Return the borsh-encoded health of the Ethereum oracle of this
+    /// node, or [`None`] if the node does not run an oracle.
+    fn eth_oracle_status(&self) -> ResponseQuery<Vec<u8>> {
+        let status = self
+            .mode
+            .eth_oracle_channels()
+            .map(EthereumOracleChannels::status);
+        ResponseQuery {
+            data: status.serialize_to_vec(),
+            ..Default::default()
+        }
+    }
+
     /// Simple helper function for the ledger to get balances
     /// of the specified token at the specified address
     pub fn get_balance(
@@ -201,4 +230,44 @@ mod test_queries {
             (2, 28, false),
         ],
     }
+
+    /// Test that the shell reports the health of its Ethereum oracle.
+    #[test]
+    fn test_eth_oracle_status() {
+        let (mut shell, _recv, eth_sender, _oracle_control_recv) =
+            test_utils::setup_at_height(0u64);
+        let query_status = |shell: &test_utils::TestShell| {
+            let response = shell.query(request::Query {
+                data: Default::default(),
+                path: "/shell/eth_bridge/oracle/status".into(),
+                height: Default::default(),
+                prove: false,
+            });
+            Option::<EthereumOracleStatus>::try_from_slice(&response.value)
+                .expect("Test failed")
+                .expect("Test failed")
+        };
+
+        // the oracle has not published any heartbeats yet
+        let status = query_status(&shell);
+        assert_eq!(status.heartbeat, Default::default());
+        assert_eq!(status.last_processed_block, None);
+        assert_eq!(status.queued_events, 0);
+
+        // queue up an event for the next vote extension
+        eth_sender
+            .try_send(EthereumEvent::TransfersToNamada {
+                nonce: 0u64.into(),
+                transfers: vec![],
+            })
+            .expect("Test failed");
+        if let ShellMode::Validator {
+            eth_oracle: Some(eth_oracle),
+            ..
+        } = &mut shell.mode
+        {
+            eth_oracle.ethereum_receiver.fill_queue(|_| true);
+        }
+        assert_eq!(query_status(&shell).queued_events, 1);
+    }
 }
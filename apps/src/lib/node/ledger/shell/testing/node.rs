@@ -1,8 +1,10 @@
+use std::collections::{BTreeSet, HashSet};
 use std::future::poll_fn;
 use std::mem::ManuallyDrop;
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::task::Poll;
 
 use color_eyre::eyre::{Report, Result};
@@ -11,14 +13,17 @@ use itertools::Either;
 use lazy_static::lazy_static;
 use namada::core::types::ethereum_structs;
 use namada::eth_bridge::oracle::config::Config as OracleConfig;
-use namada::ledger::dry_run_tx;
-use namada::ledger::events::log::dumb_queries;
+use namada::ledger::events::log::{dumb_queries, EventLog};
+use namada::ledger::events::Event;
+use namada::ledger::parameters::{self, EpochDuration};
 use namada::ledger::queries::{
     EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
 };
+use namada::ledger::storage::write_log::WriteLog;
 use namada::ledger::storage::{
-    LastBlock, Sha256Hasher, EPOCH_SWITCH_BLOCKS_DELAY,
+    LastBlock, Sha256Hasher, Storage, DB, EPOCH_SWITCH_BLOCKS_DELAY,
 };
+use namada::ledger::{dry_run_tx, estimate_gas};
 use namada::proof_of_stake::pos_queries::PosQueries;
 use namada::proof_of_stake::types::WeightedValidator;
 use namada::proof_of_stake::{
@@ -28,14 +33,18 @@ use namada::proof_of_stake::{
 use namada::tendermint::abci::response::Info;
 use namada::tendermint::abci::types::VoteInfo;
 use namada::tendermint_rpc::SimpleRequest;
+use namada::types::address::Address;
 use namada::types::control_flow::time::Duration;
 use namada::types::ethereum_events::EthereumEvent;
 use namada::types::hash::Hash;
 use namada::types::key::tm_consensus_key_raw_hash;
 use namada::types::storage::{BlockHash, BlockHeight, Epoch, Header};
-use namada::types::time::DateTimeUtc;
+use namada::types::time::{self, DateTimeUtc};
 use namada_sdk::queries::Client;
 use num_traits::cast::FromPrimitive;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_core::{OsRng, RngCore};
 use regex::Regex;
 use tokio::sync::mpsc;
 
@@ -48,7 +57,7 @@ use crate::node::ledger::ethereum_oracle::test_tools::mock_web3_client::{
     TestOracle, Web3Client, Web3Controller,
 };
 use crate::node::ledger::ethereum_oracle::{
-    control, last_processed_block, try_process_eth_events,
+    control, last_processed_block, status, try_process_eth_events,
 };
 use crate::node::ledger::shell::testing::utils::TestDir;
 use crate::node::ledger::shell::{ErrorCodes, EthereumOracleChannels, Shell};
@@ -94,6 +103,69 @@ pub struct MockServices {
     /// Mock Ethereum oracle, that processes blocks from Ethereum
     /// in order to find events emitted by a transaction to vote on.
     ethereum_oracle: MockEthOracle,
+    /// The clock timestamping the blocks.
+    clock: MockClock,
+    /// The seeded source of randomness of the node.
+    rng: Mutex<StdRng>,
+}
+
+/// The clock timestamping the blocks of a [`MockNode`].
+#[derive(Debug)]
+pub enum MockClock {
+    /// The current time of the host.
+    Wall,
+    /// A deterministic clock, moving forward by a fixed interval after
+    /// each block.
+    Fixed {
+        /// The time of the next block.
+        next_block_time: Mutex<DateTimeUtc>,
+        /// The time between two consecutive blocks.
+        block_interval: Duration,
+    },
+}
+
+impl MockClock {
+    /// A deterministic clock, starting at the given time.
+    pub fn fixed(start: DateTimeUtc, block_interval: Duration) -> Self {
+        Self::Fixed {
+            next_block_time: Mutex::new(start),
+            block_interval,
+        }
+    }
+
+    /// The time of the next block.
+    fn now(&self) -> DateTimeUtc {
+        match self {
+            Self::Wall => DateTimeUtc::now(),
+            Self::Fixed {
+                next_block_time, ..
+            } => *next_block_time.lock().unwrap(),
+        }
+    }
+
+    /// Set the time of the next block, if the clock is deterministic.
+    fn set(&self, time: DateTimeUtc) {
+        if let Self::Fixed {
+            next_block_time, ..
+        } = self
+        {
+            *next_block_time.lock().unwrap() = time;
+        }
+    }
+
+    /// Move forward to the time of the block after the next one.
+    fn tick(&self) {
+        if let Self::Fixed {
+            next_block_time,
+            block_interval,
+        } = self
+        {
+            let mut next_block_time = next_block_time.lock().unwrap();
+            *next_block_time = *next_block_time
+                + time::Duration::from_std(*block_interval)
+                    .expect("Test failed");
+        }
+    }
 }
 
 /// Actions to be performed by the mock node, as a result
@@ -182,6 +254,12 @@ pub struct MockServicesCfg {
     pub auto_drive_services: bool,
     /// Whether to enable the Ethereum oracle or not.
     pub enable_eth_oracle: bool,
+    /// The clock timestamping the blocks.
+    pub clock: MockClock,
+    /// The seed of the source of randomness of the node. If none is
+    /// given, a random seed is picked and logged, so that a run can still
+    /// be reproduced.
+    pub rng_seed: Option<u64>,
 }
 
 /// Instantiate mock services for a node.
@@ -190,12 +268,14 @@ pub fn mock_services(cfg: MockServicesCfg) -> MockServicesPackage {
     let (eth_sender, eth_receiver) = mpsc::channel(1000);
     let (last_processed_block_sender, last_processed_block_receiver) =
         last_processed_block::channel();
+    let (status_sender, status_receiver) = status::channel();
     let (control_sender, control_receiver) = control::channel();
     let eth_oracle_controller = eth_client.controller();
     let oracle = TestOracle::new(
         Either::Left(eth_client),
         eth_sender.clone(),
         last_processed_block_sender,
+        status_sender,
         Duration::from_millis(5),
         Duration::from_secs(30),
         control_receiver,
@@ -204,8 +284,11 @@ pub fn mock_services(cfg: MockServicesCfg) -> MockServicesPackage {
         eth_receiver,
         control_sender,
         last_processed_block_receiver,
+        status_receiver,
     );
     let (tx_broadcaster, tx_receiver) = mpsc::unbounded_channel();
+    let rng_seed = cfg.rng_seed.unwrap_or_else(|| OsRng.next_u64());
+    tracing::info!("Seeding the mock node's randomness with {rng_seed}");
     let ethereum_oracle = MockEthOracle {
         oracle,
         config: Default::default(),
@@ -216,6 +299,8 @@ pub fn mock_services(cfg: MockServicesCfg) -> MockServicesPackage {
         services: MockServices {
             ethereum_oracle,
             tx_receiver: tokio::sync::Mutex::new(tx_receiver),
+            clock: cfg.clock,
+            rng: Mutex::new(StdRng::seed_from_u64(rng_seed)),
         },
         shell_handlers: MockServiceShellHandlers {
             tx_broadcaster: tx_broadcaster.clone(),
@@ -242,13 +327,53 @@ pub enum NodeResults {
     Failed(ErrorCodes),
 }
 
+/// The shell of a node of the mock network
+pub type MockShell = Shell<storage::PersistentDB, Sha256Hasher>;
+
 pub struct MockNode {
-    pub shell: Arc<Mutex<Shell<storage::PersistentDB, Sha256Hasher>>>,
+    pub shell: Arc<Mutex<MockShell>>,
+    /// The shells of the other nodes of the network, e.g. run by other
+    /// validators. They are sent the same txs and blocks as `shell`, so their
+    /// broadcasters should feed the same mock services.
+    pub peers: Vec<Arc<Mutex<MockShell>>>,
+    /// The consensus validators whose votes are missing from the blocks
+    pub offline_validators: Arc<Mutex<HashSet<Address>>>,
     pub test_dir: ManuallyDrop<TestDir>,
     pub keep_temp: bool,
     pub results: Arc<Mutex<Vec<NodeResults>>>,
     pub services: Arc<MockServices>,
     pub auto_drive_services: bool,
+    /// How far the clock of the node is ahead of the current time
+    pub time_offset: Arc<Mutex<Duration>>,
+}
+
+/// A snapshot of the state of a [`MockNode`], taken with
+/// [`MockNode::snapshot`]. It can be restored any number of times, so that
+/// several test scenarios can start from the same chain.
+pub struct MockNodeSnapshot {
+    /// The snapshots of the shells of the network, starting with `shell`
+    shells: Vec<ShellSnapshot>,
+    /// The results of the txs submitted to the node
+    results: Vec<NodeResults>,
+    /// How far the clock of the node was ahead of the current time
+    time_offset: Duration,
+    /// The time of the next block on the clock of the node
+    next_block_time: DateTimeUtc,
+    /// The consensus validators whose votes were missing from the blocks
+    offline_validators: HashSet<Address>,
+}
+
+/// A snapshot of the state of a [`MockShell`]
+struct ShellSnapshot {
+    /// The directory of a checkpoint of the DB
+    db_dir: PathBuf,
+    /// The storage changes that weren't committed yet
+    write_log: WriteLog,
+    /// The events emitted by the shell
+    event_log: EventLog,
+    byzantine_validators: Vec<tendermint::evidence::Misbehavior>,
+    proposal_data: HashSet<u64>,
+    liveness_observations: BTreeSet<Address>,
 }
 
 impl Drop for MockNode {
@@ -320,16 +445,182 @@ impl MockNode {
         self.shell.lock().unwrap().wl_storage.storage.last_epoch
     }
 
+    /// The shells of all the nodes of the network, starting with `shell`
+    fn shells(&self) -> impl Iterator<Item = &Arc<Mutex<MockShell>>> {
+        std::iter::once(&self.shell).chain(&self.peers)
+    }
+
+    /// The shell of the node run by the given validator, defaulting to
+    /// `shell` if there's none
+    fn validator_shell(&self, validator: &Address) -> &Arc<Mutex<MockShell>> {
+        self.peers
+            .iter()
+            .find(|shell| {
+                shell.lock().unwrap().mode.get_validator_address()
+                    == Some(validator)
+            })
+            .unwrap_or(&self.shell)
+    }
+
+    /// Take the given validator offline, so that its votes are missing from
+    /// the next blocks, e.g. to get it jailed for inactivity
+    pub fn take_offline(&self, validator: Address) {
+        self.offline_validators.lock().unwrap().insert(validator);
+    }
+
+    /// Bring the given validator back online, so that it votes for the next
+    /// blocks
+    pub fn bring_online(&self, validator: &Address) {
+        self.offline_validators.lock().unwrap().remove(validator);
+    }
+
+    /// A new directory for a checkpoint of a DB
+    fn checkpoint_dir(&self) -> PathBuf {
+        static NEXT_CHECKPOINT: AtomicU64 = AtomicU64::new(0);
+        let checkpoint = NEXT_CHECKPOINT.fetch_add(1, Ordering::Relaxed);
+        self.test_dir
+            .path()
+            .join("checkpoints")
+            .join(checkpoint.to_string())
+    }
+
+    /// Take a snapshot of the state of the node, including the storage of
+    /// all the shells of the network, which can be restored with
+    /// [`MockNode::restore`]
+    pub fn snapshot(&self) -> MockNodeSnapshot {
+        let shells = self
+            .shells()
+            .map(|shell| {
+                let locked = shell.lock().unwrap();
+                let db_dir = self.checkpoint_dir();
+                locked
+                    .wl_storage
+                    .storage
+                    .db
+                    .checkpoint(&db_dir)
+                    .expect("Test failed");
+                ShellSnapshot {
+                    db_dir,
+                    write_log: locked.wl_storage.write_log.clone(),
+                    event_log: locked.event_log.clone(),
+                    byzantine_validators: locked.byzantine_validators.clone(),
+                    proposal_data: locked.proposal_data.clone(),
+                    liveness_observations: locked.liveness_observations.clone(),
+                }
+            })
+            .collect();
+        MockNodeSnapshot {
+            shells,
+            results: self.results.lock().unwrap().clone(),
+            time_offset: *self.time_offset.lock().unwrap(),
+            next_block_time: self.services.clock.now(),
+            offline_validators: self.offline_validators.lock().unwrap().clone(),
+        }
+    }
+
+    /// Restore the state of the node from a snapshot taken with
+    /// [`MockNode::snapshot`]. The shells of the network reload their
+    /// storage from copies of the snapshotted DBs, as if they restarted.
+    pub fn restore(&self, snapshot: &MockNodeSnapshot) {
+        assert_eq!(
+            snapshot.shells.len(),
+            self.shells().count(),
+            "The snapshot was taken from another network"
+        );
+        for (shell, shell_snapshot) in self.shells().zip(&snapshot.shells) {
+            // Open a copy of the checkpoint, so that it stays untouched for
+            // the next restorations
+            let db_dir = self.checkpoint_dir();
+            storage::PersistentDB::open(&shell_snapshot.db_dir, None)
+                .checkpoint(&db_dir)
+                .expect("Test failed");
+
+            let mut locked = shell.lock().unwrap();
+            let current = &locked.wl_storage.storage;
+            let mut restored = Storage::open(
+                &db_dir,
+                current.chain_id.clone(),
+                current.native_token.clone(),
+                None,
+                current.storage_read_past_height_limit,
+            );
+            restored.load_last_state().expect("Test failed");
+            locked.wl_storage.storage = restored;
+            locked.wl_storage.write_log = shell_snapshot.write_log.clone();
+            locked.event_log = shell_snapshot.event_log.clone();
+            locked.byzantine_validators =
+                shell_snapshot.byzantine_validators.clone();
+            locked.proposal_data = shell_snapshot.proposal_data.clone();
+            locked.liveness_observations =
+                shell_snapshot.liveness_observations.clone();
+            // Drop the caches populated from the state being replaced
+            locked.consensus_set_cache = Default::default();
+            locked.protocol_tx_cache = Default::default();
+        }
+
+        *self.results.lock().unwrap() = snapshot.results.clone();
+        *self.time_offset.lock().unwrap() = snapshot.time_offset;
+        self.services.clock.set(snapshot.next_block_time);
+        *self.offline_validators.lock().unwrap() =
+            snapshot.offline_validators.clone();
+    }
+
+    /// The time of the next block, i.e. the time of the node's clock moved
+    /// forward by [`MockNode::advance_time`]
+    pub fn block_time(&self) -> DateTimeUtc {
+        let offset = *self.time_offset.lock().unwrap();
+        self.services.clock.now()
+            + time::Duration::from_std(offset).expect("Test failed")
+    }
+
+    /// The seeded source of randomness of the node. Tests should draw their
+    /// random values from it, so that a run can be reproduced from the seed
+    /// given in [`MockServicesCfg`].
+    pub fn rng(&self) -> MutexGuard<'_, StdRng> {
+        self.services.rng.lock().unwrap()
+    }
+
+    /// Move the clock of the node forward by the given duration. The blocks
+    /// committed from now on are timestamped accordingly, so the epochs
+    /// whose minimum duration elapsed in the meantime can end. Note that
+    /// txs expiring before the new time will be rejected.
+    pub fn advance_time(&self, duration: Duration) {
+        *self.time_offset.lock().unwrap() += duration;
+    }
+
+    /// Change the minimum duration of epochs. The current epoch ends once
+    /// both the given number of blocks and time have passed since the last
+    /// committed block, and the following ones last as long.
+    pub fn set_epoch_duration(&self, epoch_duration: EpochDuration) {
+        let block_time = self.block_time();
+        for shell in self.shells() {
+            let mut locked = shell.lock().unwrap();
+            parameters::update_epoch_parameter(
+                &mut locked.wl_storage,
+                &epoch_duration,
+            )
+            .expect("Test failed");
+            locked.wl_storage.commit_tx();
+
+            let storage = &mut locked.wl_storage.storage;
+            storage.next_epoch_min_start_height = storage
+                .get_last_block_height()
+                + epoch_duration.min_num_of_blocks;
+            storage.next_epoch_min_start_time =
+                block_time + epoch_duration.min_duration;
+        }
+    }
+
     pub fn next_epoch(&mut self) -> Epoch {
-        {
-            let mut locked = self.shell.lock().unwrap();
+        let block_time = self.block_time();
+        for shell in self.shells() {
+            let mut locked = shell.lock().unwrap();
 
             let next_epoch_height =
                 locked.wl_storage.storage.get_last_block_height() + 1;
             locked.wl_storage.storage.next_epoch_min_start_height =
                 next_epoch_height;
-            locked.wl_storage.storage.next_epoch_min_start_time =
-                DateTimeUtc::now();
+            locked.wl_storage.storage.next_epoch_min_start_time = block_time;
             let next_epoch_min_start_height =
                 locked.wl_storage.storage.next_epoch_min_start_height;
             if let Some(LastBlock { height, .. }) =
@@ -352,51 +643,92 @@ impl MockNode {
             .0
     }
 
-    /// Get the address of the block proposer and the votes for the block
-    fn prepare_request(&self) -> (Vec<u8>, Vec<VoteInfo>) {
-        let (val1, ck) = {
-            let locked = self.shell.lock().unwrap();
-            let params = locked.wl_storage.pos_queries().get_pos_params();
-            let current_epoch = locked.wl_storage.storage.get_current_epoch().0;
-            let consensus_set: Vec<WeightedValidator> =
-                read_consensus_validator_set_addresses_with_stake(
-                    &locked.wl_storage,
-                    current_epoch,
-                )
-                .unwrap()
-                .into_iter()
-                .collect();
+    /// Get the address and raw hash of the block proposer, i.e. the first
+    /// online consensus validator, and the votes of all the consensus
+    /// validators for the block, where those of offline ones are absent
+    fn prepare_request(&self) -> (Address, Vec<u8>, Vec<VoteInfo>) {
+        let offline_validators =
+            self.offline_validators.lock().unwrap().clone();
+        let locked = self.shell.lock().unwrap();
+        let params = locked.wl_storage.pos_queries().get_pos_params();
+        let current_epoch = locked.wl_storage.storage.get_current_epoch().0;
+        let consensus_set: Vec<WeightedValidator> =
+            read_consensus_validator_set_addresses_with_stake(
+                &locked.wl_storage,
+                current_epoch,
+            )
+            .unwrap()
+            .into_iter()
+            .collect();
 
-            let val1 = consensus_set[0].clone();
-            let ck = validator_consensus_key_handle(&val1.address)
-                .get(&locked.wl_storage, current_epoch, &params)
-                .unwrap()
-                .unwrap();
-            (val1, ck)
-        };
+        let mut proposer = None;
+        let votes = consensus_set
+            .into_iter()
+            .map(|validator| {
+                let ck = validator_consensus_key_handle(&validator.address)
+                    .get(&locked.wl_storage, current_epoch, &params)
+                    .unwrap()
+                    .unwrap();
+                let hash_string = tm_consensus_key_raw_hash(&ck);
+                let pkh = HEXUPPER.decode(hash_string.as_bytes()).unwrap();
+                let online = !offline_validators.contains(&validator.address);
+                if online && proposer.is_none() {
+                    proposer = Some((validator.address.clone(), pkh.clone()));
+                }
+                let sig_info = if online {
+                    tendermint::abci::types::BlockSignatureInfo::LegacySigned
+                } else {
+                    tendermint::abci::types::BlockSignatureInfo::Flag(
+                        tendermint::block::BlockIdFlag::Absent,
+                    )
+                };
+                VoteInfo {
+                    validator: tendermint::abci::types::Validator {
+                        address: pkh.try_into().unwrap(),
+                        power: (u128::try_from(validator.bonded_stake)
+                            .expect("Test failed")
+                            as u64)
+                            .try_into()
+                            .unwrap(),
+                    },
+                    sig_info,
+                }
+            })
+            .collect();
+        let (proposer, pkh) =
+            proposer.expect("All the consensus validators are offline");
 
-        let hash_string = tm_consensus_key_raw_hash(&ck);
-        let pkh1 = HEXUPPER.decode(hash_string.as_bytes()).unwrap();
-        let votes = vec![VoteInfo {
-            validator: tendermint::abci::types::Validator {
-                address: pkh1.clone().try_into().unwrap(),
-                power: (u128::try_from(val1.bonded_stake).expect("Test failed")
-                    as u64)
-                    .try_into()
-                    .unwrap(),
-            },
-            sig_info: tendermint::abci::types::BlockSignatureInfo::LegacySigned,
-        }];
+        (proposer, pkh, votes)
+    }
 
-        (pkh1, votes)
+    /// Finalize and commit the given block on all the nodes of the network,
+    /// checking that they agree on the resulting state. Returns the events
+    /// emitted by `shell`.
+    fn finalize_block_on_all_shells(&self, req: FinalizeBlock) -> Vec<Event> {
+        let mut events = None;
+        let mut app_hash = None;
+        for shell in self.shells() {
+            let mut locked = shell.lock().unwrap();
+            let resp = locked.finalize_block(req.clone()).expect("Test failed");
+            let hash = locked.commit().data;
+            match &app_hash {
+                None => app_hash = Some(hash),
+                Some(expected) => assert_eq!(
+                    &hash, expected,
+                    "The state of a peer diverged from the network"
+                ),
+            }
+            events.get_or_insert(resp.events);
+        }
+        self.services.clock.tick();
+        events.expect("There is at least one shell")
     }
 
     /// Simultaneously call the `FinalizeBlock` and
     /// `Commit` handlers.
     pub fn finalize_and_commit(&self) {
-        let (proposer_address, votes) = self.prepare_request();
-
-        let mut locked = self.shell.lock().unwrap();
+        let (proposer, proposer_address, votes) = self.prepare_request();
+        let block_time = self.block_time();
 
         // build finalize block abci request
         let req = {
@@ -407,7 +739,12 @@ impl MockNode {
                     proposer_address: proposer_address.clone().into(),
                     ..Default::default()
                 };
-                let txs = locked.prepare_proposal(req).txs;
+                let txs = self
+                    .validator_shell(&proposer)
+                    .lock()
+                    .unwrap()
+                    .prepare_proposal(req)
+                    .txs;
 
                 txs.into_iter()
                     .map(|tx| ProcessedTx {
@@ -423,7 +760,7 @@ impl MockNode {
                 hash: BlockHash([0u8; 32]),
                 header: Header {
                     hash: Hash([0; 32]),
-                    time: DateTimeUtc::now(),
+                    time: block_time,
                     next_validators_hash: Hash([0; 32]),
                 },
                 byzantine_validators: vec![],
@@ -433,8 +770,7 @@ impl MockNode {
             }
         };
 
-        locked.finalize_block(req).expect("Test failed");
-        locked.commit();
+        self.finalize_block_on_all_shells(req);
     }
 
     /// Advance to a block height that allows
@@ -457,15 +793,27 @@ impl MockNode {
         // The block space allocator disallows encrypted txs in certain blocks.
         // Advance to block height that allows txs.
         self.advance_to_allowed_block();
-        let (proposer_address, votes) = self.prepare_request();
+        let (_, proposer_address, votes) = self.prepare_request();
+        let block_time = self.block_time();
 
         let req = RequestProcessProposal {
             txs: txs.clone().into_iter().map(|tx| tx.into()).collect(),
             proposer_address: proposer_address.clone().into(),
             ..Default::default()
         };
-        let mut locked = self.shell.lock().unwrap();
-        let (result, tx_results) = locked.process_proposal(req);
+        // Every node of the network must agree on the proposal
+        let mut proposal_results = self
+            .shells()
+            .map(|shell| shell.lock().unwrap().process_proposal(req.clone()));
+        let (result, tx_results) = proposal_results
+            .next()
+            .expect("There is at least one shell");
+        for (peer_result, _) in proposal_results {
+            assert_eq!(
+                peer_result, result,
+                "A peer disagreed on the validity of the proposal"
+            );
+        }
 
         let mut errors: Vec<_> = tx_results
             .iter()
@@ -487,7 +835,7 @@ impl MockNode {
             hash: BlockHash([0u8; 32]),
             header: Header {
                 hash: Hash([0; 32]),
-                time: DateTimeUtc::now(),
+                time: block_time,
                 next_validators_hash: Hash([0; 32]),
             },
             byzantine_validators: vec![],
@@ -504,9 +852,8 @@ impl MockNode {
         };
 
         // process the results
-        let resp = locked.finalize_block(req).unwrap();
-        let mut error_codes = resp
-            .events
+        let mut error_codes = self
+            .finalize_block_on_all_shells(req)
             .into_iter()
             .map(|e| {
                 let code = ErrorCodes::from_u32(
@@ -524,7 +871,6 @@ impl MockNode {
             })
             .collect::<Vec<_>>();
         self.results.lock().unwrap().append(&mut error_codes);
-        locked.commit();
     }
 
     /// Check that applying a tx succeeded.
@@ -598,6 +944,8 @@ impl<'a> Client for &'a MockNode {
         };
         if request.path == "/shell/dry_run_tx" {
             dry_run_tx(ctx, &request)
+        } else if request.path == "/shell/estimate_gas" {
+            estimate_gas(ctx, &request)
         } else {
             rpc.handle(ctx, &request)
         }
@@ -0,0 +1,303 @@
+//! Coordination of the chain upgrades.
+//!
+//! Governance may set the `halt_at_height` protocol parameter to the height of
+//! the first block to be finalized by an upgraded binary, along with the
+//! `upgrade_version` parameter, the version of that binary. The nodes running
+//! any other version stop right before finalizing the block at the halt
+//! height and persist an upgrade marker, recording the height and the version
+//! of the binary that halted, and they refuse to start again until they're
+//! restarted with the agreed version. The upgraded binary finalizes the block
+//! at the halt height and removes the marker once the block has been
+//! committed. On startup at the halt height, it runs the pending migrations
+//! of the state, which get committed along with that block.
+
+use std::path::PathBuf;
+
+use namada::ledger::parameters;
+use namada::ledger::storage::{DBIter, StorageHasher, DB};
+use namada::types::storage::BlockHeight;
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result, Shell};
+use crate::cli::namada_version;
+use crate::node::ledger::storage;
+
+/// The name of the upgrade marker file in the chain directory
+const UPGRADE_MARKER_FILE: &str = "upgrade_marker.toml";
+
+/// The marker persisted by a node that halted the chain for an upgrade
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeMarker {
+    /// The height of the block left to the upgraded binary
+    pub height: BlockHeight,
+    /// The version of the binary that halted the chain
+    pub version: String,
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Get the path of the upgrade marker file
+    fn upgrade_marker_path(&self) -> PathBuf {
+        self.base_dir
+            .join(self.chain_id.as_str())
+            .join(UPGRADE_MARKER_FILE)
+    }
+
+    /// Read the upgrade marker, if any
+    pub fn read_upgrade_marker(&self) -> Option<UpgradeMarker> {
+        let path = self.upgrade_marker_path();
+        let contents = std::fs::read_to_string(&path).ok()?;
+        match toml::from_str(&contents) {
+            Ok(marker) => Some(marker),
+            Err(err) => {
+                tracing::error!(
+                    "Ignoring the invalid upgrade marker at {}: {err}",
+                    path.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// Check if the given height is the halt height agreed by governance and
+    /// this binary is the version of the upgrade
+    fn is_upgrade_height(&self, height: BlockHeight) -> Result<bool> {
+        if parameters::read_halt_at_height(&self.wl_storage)? != Some(height) {
+            return Ok(false);
+        }
+        let upgrade_version =
+            parameters::read_upgrade_version(&self.wl_storage)?;
+        Ok(upgrade_version.as_deref() == Some(namada_version()))
+    }
+
+    /// Check if the chain must halt for an upgrade before finalizing the
+    /// block at the given height. This is the case at the halt height agreed
+    /// by governance, unless this binary is the version of the upgrade. On
+    /// halting, the upgrade marker is persisted and an error is returned.
+    pub(super) fn check_halt_at_height(
+        &self,
+        height: BlockHeight,
+    ) -> Result<()> {
+        if parameters::read_halt_at_height(&self.wl_storage)? != Some(height) {
+            return Ok(());
+        }
+        if self.is_upgrade_height(height)? {
+            tracing::info!(
+                "Finalizing the block at the halt height {height} with the \
+                 upgraded version {}",
+                namada_version()
+            );
+            return Ok(());
+        }
+
+        let marker = UpgradeMarker {
+            height,
+            version: namada_version().to_string(),
+        };
+        let path = self.upgrade_marker_path();
+        if let Some(chain_dir) = path.parent() {
+            std::fs::create_dir_all(chain_dir).map_err(Error::UpgradeMarker)?;
+        }
+        let contents = toml::to_string(&marker)
+            .expect("Serializing the upgrade marker shouldn't fail");
+        std::fs::write(&path, contents).map_err(Error::UpgradeMarker)?;
+        tracing::warn!(
+            "Reached the halt height {height} agreed by governance. The node \
+             must be restarted with the upgraded binary (version {}).",
+            parameters::read_upgrade_version(&self.wl_storage)?
+                .as_deref()
+                .unwrap_or("unknown")
+        );
+        Err(Error::HaltAtHeight(height))
+    }
+
+    /// Refuse to run a binary other than the upgraded one once the chain has
+    /// halted for an upgrade, and remove the upgrade marker left behind once
+    /// the block at the halt height has been committed. This must be called
+    /// on startup, after the last state has been loaded.
+    pub(super) fn check_upgrade_marker(&self) {
+        let last_height = self.wl_storage.storage.get_last_block_height();
+        if let Some(marker) = self.read_upgrade_marker() {
+            if last_height >= marker.height {
+                self.remove_upgrade_marker();
+            }
+        }
+        let next_height = last_height + 1;
+        if let Err(Error::HaltAtHeight(height)) =
+            self.check_halt_at_height(next_height)
+        {
+            panic!(
+                "This binary (version {}) halted the chain at height {height} \
+                 for an upgrade and it must be replaced by the upgraded binary",
+                namada_version()
+            );
+        }
+    }
+
+    /// Run the pending migrations of the state, whose changes get committed
+    /// along with the next block. This must be called on startup, after the
+    /// last state has been loaded.
+    ///
+    /// The migrations only run when the next block is the one at the halt
+    /// height and this binary is the version of the upgrade agreed by
+    /// governance, such that every node migrates the state in the same
+    /// block. Any other binary with pending migrations refuses to start.
+    pub(super) fn migrate_state(&mut self) {
+        // A new chain is initialized with the latest version of the state
+        if self.wl_storage.storage.last_block.is_none() {
+            return;
+        }
+        let migrations = storage::migrations::all();
+        let state_version = parameters::read_state_version(&self.wl_storage)
+            .expect("Reading the state version shouldn't fail")
+            .unwrap_or_default();
+        if state_version >= migrations.latest_version() {
+            return;
+        }
+        let next_height = self.wl_storage.storage.get_last_block_height() + 1;
+        let is_upgrade_height = self
+            .is_upgrade_height(next_height)
+            .expect("Reading the upgrade parameters shouldn't fail");
+        if !is_upgrade_height {
+            panic!(
+                "This binary (version {}) must migrate the state from version \
+                 {state_version} to version {}, which may only happen at the \
+                 halt height agreed by governance for its upgrade. The \
+                 previous binary must be run until then.",
+                namada_version(),
+                migrations.latest_version()
+            );
+        }
+
+        let report = migrations
+            .run(&mut self.wl_storage, false)
+            .unwrap_or_else(|err| {
+                panic!("The state migrations were rolled back: {err}")
+            });
+        tracing::info!(
+            "Migrated the state from version {} to version {}, changing {} \
+             keys",
+            report.from_version,
+            report.to_version,
+            report.changed_keys.len()
+        );
+    }
+
+    /// Remove the upgrade marker once the block at the halt height has been
+    /// committed
+    pub(super) fn complete_upgrade(&self) {
+        let last_height = self.wl_storage.storage.get_last_block_height();
+        if matches!(
+            parameters::read_halt_at_height(&self.wl_storage),
+            Ok(Some(height)) if height == last_height
+        ) {
+            self.remove_upgrade_marker();
+        }
+    }
+
+    /// Remove the upgrade marker file, if any
+    fn remove_upgrade_marker(&self) {
+        let path = self.upgrade_marker_path();
+        if !path.exists() {
+            return;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => tracing::info!("Completed the upgrade of the chain"),
+            Err(err) => tracing::error!(
+                "Failed to remove the upgrade marker at {}: {err}",
+                path.display()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_upgrade {
+    use namada::ledger::storage::LastBlock;
+    use namada::types::storage::BlockHash;
+    use namada::types::time::DateTimeUtc;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+
+    /// Test that the binary halts at the height agreed by governance and
+    /// persists the upgrade marker, unless it is the version of the upgrade
+    /// agreed by governance
+    #[test]
+    fn test_halt_at_height() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let height = shell.wl_storage.storage.get_last_block_height() + 1;
+        shell.check_halt_at_height(height).expect("Test failed");
+        assert_eq!(shell.read_upgrade_marker(), None);
+
+        // Without an agreed version, no binary may finalize the block
+        parameters::update_halt_at_height(&mut shell.wl_storage, height)
+            .expect("Test failed");
+        assert!(matches!(
+            shell.check_halt_at_height(height),
+            Err(Error::HaltAtHeight(halt_height)) if halt_height == height
+        ));
+
+        // The binary of another version halts and persists the marker
+        parameters::update_upgrade_version(&mut shell.wl_storage, "upgraded")
+            .expect("Test failed");
+        assert!(matches!(
+            shell.check_halt_at_height(height),
+            Err(Error::HaltAtHeight(halt_height)) if halt_height == height
+        ));
+        assert_eq!(
+            shell.read_upgrade_marker(),
+            Some(UpgradeMarker {
+                height,
+                version: namada_version().to_string(),
+            })
+        );
+        // Other heights are processed as usual
+        shell.check_halt_at_height(height + 1).expect("Test failed");
+
+        // The upgraded binary finalizes the block at the halt height
+        parameters::update_upgrade_version(
+            &mut shell.wl_storage,
+            namada_version(),
+        )
+        .expect("Test failed");
+        shell.check_halt_at_height(height).expect("Test failed");
+
+        // The marker is removed once the block has been committed
+        shell.wl_storage.storage.last_block = Some(LastBlock {
+            height,
+            hash: BlockHash::default(),
+            time: DateTimeUtc::now(),
+        });
+        shell.complete_upgrade();
+        assert_eq!(shell.read_upgrade_marker(), None);
+    }
+
+    /// Test that the migrations are only allowed at the halt height agreed by
+    /// governance, for the version of the upgrade
+    #[test]
+    fn test_upgrade_height() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let height = shell.wl_storage.storage.get_last_block_height() + 1;
+        assert!(!shell.is_upgrade_height(height).expect("Test failed"));
+
+        parameters::update_halt_at_height(&mut shell.wl_storage, height)
+            .expect("Test failed");
+        assert!(!shell.is_upgrade_height(height).expect("Test failed"));
+
+        parameters::update_upgrade_version(&mut shell.wl_storage, "upgraded")
+            .expect("Test failed");
+        assert!(!shell.is_upgrade_height(height).expect("Test failed"));
+
+        parameters::update_upgrade_version(
+            &mut shell.wl_storage,
+            namada_version(),
+        )
+        .expect("Test failed");
+        assert!(shell.is_upgrade_height(height).expect("Test failed"));
+        assert!(!shell.is_upgrade_height(height + 1).expect("Test failed"));
+    }
+}
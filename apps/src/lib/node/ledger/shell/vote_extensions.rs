@@ -1,14 +1,21 @@
 //! Extend Tendermint votes with Ethereum bridge logic.
 
+pub mod aggregator;
 pub mod bridge_pool_vext;
 pub mod eth_events;
+pub mod liveness;
+pub mod metrics;
 pub mod val_set_update;
 
+use namada::ledger::pos::PosQueries;
+use namada::proof_of_stake::pos_queries;
 use namada::proto::{SignableEthMessage, Signed};
 use namada::types::keccak::keccak_hash;
+use namada::types::storage::Epoch;
 use namada::types::transaction::protocol::EthereumTxData;
 use namada::types::vote_extensions::{
-    bridge_pool_roots, ethereum_events, validator_set_update, VoteExtension,
+    bridge_pool_roots, ethereum_events, liveness, validator_set_update,
+    VoteExtension,
 };
 use namada_sdk::eth_bridge::{EthBridgeQueries, SendValsetUpd};
 
@@ -18,6 +25,20 @@ use crate::node::ledger::shims::abcipp_shim_types::shim::TxBytes;
 /// Message to be passed to `.expect()` calls in this module.
 const VALIDATOR_EXPECT_MSG: &str = "Only validators receive this method call.";
 
+/// The kinds of vote extension protocol txs, in the order in which
+/// they are included in block proposals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VextKind {
+    /// Ethereum events seen by a validator.
+    EthEvents,
+    /// A signature over the Bridge pool merkle root and nonce.
+    BridgePoolRoot,
+    /// A signature over the next validator set.
+    ValidatorSetUpdate,
+    /// The liveness of a validator.
+    Liveness,
+}
+
 /// The error yielded from validating faulty vote extensions in the shell
 #[derive(Error, Debug)]
 pub enum VoteExtensionError {
@@ -61,6 +82,35 @@ pub enum VoteExtensionError {
          not active"
     )]
     EthereumBridgeInactive,
+    #[error(
+        "The liveness vote extension reports a validator that is either not \
+         a consensus validator or the signer of the vote extension"
+    )]
+    InvalidLivenessObservation,
+}
+
+impl VoteExtensionError {
+    /// Return the name of this error's variant, used to label the
+    /// rejection reason of vote extensions in the shell's metrics.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::ValsetUpdProofAvailable => "ValsetUpdProofAvailable",
+            Self::TransfersLenMismatch => "TransfersLenMismatch",
+            Self::InvalidEthEventNonce => "InvalidEthEventNonce",
+            Self::UnexpectedBlockHeight => "UnexpectedBlockHeight",
+            Self::UnexpectedEpoch => "UnexpectedEpoch",
+            Self::HaveDupesOrNonSorted => "HaveDupesOrNonSorted",
+            Self::PubKeyNotInStorage => "PubKeyNotInStorage",
+            Self::VerifySigFailed => "VerifySigFailed",
+            Self::ValidatorMissingFromExtension => {
+                "ValidatorMissingFromExtension"
+            }
+            Self::DivergesFromStorage => "DivergesFromStorage",
+            Self::InvalidBPRootSig => "InvalidBPRootSig",
+            Self::EthereumBridgeInactive => "EthereumBridgeInactive",
+            Self::InvalidLivenessObservation => "InvalidLivenessObservation",
+        }
+    }
 }
 
 impl<D, H> Shell<D, H>
@@ -77,9 +127,44 @@ where
             ethereum_events: self.extend_vote_with_ethereum_events(),
             bridge_pool_root: self.extend_vote_with_bp_roots(),
             validator_set_update: self.extend_vote_with_valset_update(),
+            liveness: self.extend_vote_with_liveness(),
         }
     }
 
+    /// Lookup the stake and protocol key of a consensus validator at the given
+    /// epoch. The cached consensus validator set is used when available,
+    /// otherwise the set is read from storage.
+    pub fn get_consensus_validator_from_address(
+        &self,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> pos_queries::Result<(token::Amount, common::PublicKey)> {
+        if self.consensus_set_cache.get(epoch).is_none() {
+            return self
+                .wl_storage
+                .pos_queries()
+                .get_validator_from_address(validator, Some(epoch));
+        }
+        let bonded_stake = self
+            .consensus_set_cache
+            .find_validator(epoch, validator)
+            .map(|validator| validator.bonded_stake)
+            .ok_or_else(|| {
+                pos_queries::Error::NotValidatorAddress(
+                    validator.clone(),
+                    epoch,
+                )
+            })?;
+        let params = self.wl_storage.pos_queries().get_pos_params();
+        let protocol_pk = proof_of_stake::validator_protocol_key_handle(
+            validator,
+        )
+        .get(&self.wl_storage, epoch, &params)
+        .map_err(pos_queries::Error::Storage)?
+        .expect("Protocol public key should be set in storage after genesis.");
+        Ok((bonded_stake, protocol_pk))
+    }
+
     /// Extend PreCommit votes with [`ethereum_events::Vext`] instances.
     #[inline]
     pub fn extend_vote_with_ethereum_events(
@@ -208,12 +293,44 @@ where
             })
     }
 
+    /// Extend PreCommit votes with [`liveness::Vext`] instances, reporting
+    /// the consensus validators whose votes were missing from the last
+    /// committed block.
+    pub fn extend_vote_with_liveness(&self) -> Option<liveness::SignedVext> {
+        let validator_addr = self
+            .mode
+            .get_validator_address()
+            .expect(VALIDATOR_EXPECT_MSG);
+        let offline_validators: BTreeSet<_> = self
+            .liveness_observations
+            .iter()
+            .filter(|&validator| validator != validator_addr)
+            .cloned()
+            .collect();
+        if offline_validators.is_empty() {
+            return None;
+        }
+        tracing::debug!(
+            ?offline_validators,
+            "Reporting consensus validators observed to be offline"
+        );
+        let ext = liveness::Vext {
+            block_height: self.wl_storage.storage.get_last_block_height(),
+            validator_addr: validator_addr.to_owned(),
+            offline_validators,
+        };
+        let protocol_key =
+            self.mode.get_protocol_key().expect(VALIDATOR_EXPECT_MSG);
+        Some(ext.sign(protocol_key))
+    }
+
     /// Given a slice of [`TxBytes`], return an iterator over the
-    /// ones we could deserialize to vote extension protocol txs.
+    /// ones we could deserialize to vote extension protocol txs,
+    /// along with their kind.
     pub fn deserialize_vote_extensions<'shell>(
         &'shell self,
         txs: &'shell [TxBytes],
-    ) -> impl Iterator<Item = TxBytes> + 'shell {
+    ) -> impl Iterator<Item = (VextKind, TxBytes)> + 'shell {
         txs.iter().filter_map(move |tx_bytes| {
             let tx = match Tx::try_from(tx_bytes.as_ref()) {
                 Ok(tx) => tx,
@@ -227,7 +344,9 @@ where
                 }
             };
             match (&tx).try_into().ok()? {
-                EthereumTxData::BridgePoolVext(_) => Some(tx_bytes.clone()),
+                EthereumTxData::BridgePoolVext(_) => {
+                    Some((VextKind::BridgePoolRoot, tx_bytes.clone()))
+                }
                 EthereumTxData::EthEventsVext(ext) => {
                     // NB: only propose events with at least
                     // one valid nonce
@@ -239,7 +358,7 @@ where
                                 .ethbridge_queries()
                                 .validate_eth_event_nonce(event)
                         })
-                        .then(|| tx_bytes.clone())
+                        .then(|| (VextKind::EthEvents, tx_bytes.clone()))
                 }
                 EthereumTxData::ValSetUpdateVext(ext) => {
                     // only include non-stale validator set updates
@@ -255,7 +374,10 @@ where
                         .wl_storage
                         .ethbridge_queries()
                         .valset_upd_seen(ext.data.signing_epoch.next()))
-                    .then(|| tx_bytes.clone())
+                    .then(|| (VextKind::ValidatorSetUpdate, tx_bytes.clone()))
+                }
+                EthereumTxData::LivenessVext(_) => {
+                    Some((VextKind::Liveness, tx_bytes.clone()))
                 }
                 _ => None,
             }
@@ -272,11 +394,13 @@ pub fn iter_protocol_txs(
         ethereum_events,
         bridge_pool_root,
         validator_set_update,
+        liveness,
     } = ext;
     [
         ethereum_events.map(EthereumTxData::EthEventsVext),
         bridge_pool_root.map(EthereumTxData::BridgePoolVext),
         validator_set_update.map(EthereumTxData::ValSetUpdateVext),
+        liveness.map(EthereumTxData::LivenessVext),
     ]
     .into_iter()
     .flatten()
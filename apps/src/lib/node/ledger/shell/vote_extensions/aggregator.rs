@@ -0,0 +1,241 @@
+//! Aggregation of the valid vote extensions of some kind, along with the
+//! accounting of the voting power of the validators that issued them.
+
+use std::collections::{HashMap, HashSet};
+
+use namada::ledger::pos::PosQueries;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::proto::Signed;
+use namada::types::address::Address;
+use namada::types::storage::BlockHeight;
+use namada::types::token;
+use namada::types::vote_extensions::{bridge_pool_roots, ethereum_events};
+use namada::types::voting_power::FractionalVotingPower;
+use namada_sdk::eth_bridge::EthBridgeQueries;
+
+use super::metrics::VextKind;
+use super::VoteExtensionError;
+use crate::node::ledger::shell::Shell;
+
+/// A function validating a vote extension against the last committed
+/// block height, returning it back along with the voting power of the
+/// validator that issued it.
+pub type ValidateVext<D, H, T> =
+    fn(
+        &Shell<D, H>,
+        T,
+        BlockHeight,
+    ) -> std::result::Result<(token::Amount, T), VoteExtensionError>;
+
+/// A signed vote extension, issued by some validator at some block
+/// height, which can be aggregated by a [`VextAggregator`].
+pub trait AggregatableVext {
+    /// The kind of the vote extension.
+    const KIND: VextKind;
+
+    /// The address of the validator that issued the vote extension.
+    fn validator_addr(&self) -> &Address;
+
+    /// The block height at which the vote extension was issued.
+    fn block_height(&self) -> BlockHeight;
+}
+
+impl AggregatableVext for Signed<ethereum_events::Vext> {
+    const KIND: VextKind = VextKind::EthereumEvents;
+
+    #[inline]
+    fn validator_addr(&self) -> &Address {
+        &self.data.validator_addr
+    }
+
+    #[inline]
+    fn block_height(&self) -> BlockHeight {
+        self.data.block_height
+    }
+}
+
+impl AggregatableVext for Signed<bridge_pool_roots::Vext> {
+    const KIND: VextKind = VextKind::BridgePoolRoot;
+
+    #[inline]
+    fn validator_addr(&self) -> &Address {
+        &self.data.validator_addr
+    }
+
+    #[inline]
+    fn block_height(&self) -> BlockHeight {
+        self.data.block_height
+    }
+}
+
+/// Aggregates valid vote extensions of some kind.
+///
+/// Only the first vote extension issued by some validator at a given
+/// block height is taken into account. The voting power of each validator
+/// whose vote extensions were aggregated is counted once, such that it
+/// can be checked whether a quorum of validators issued the aggregated
+/// vote extensions.
+#[derive(Debug, Clone)]
+pub struct VextAggregator<T> {
+    /// The total voting power of the consensus validators
+    total_voting_power: token::Amount,
+    /// The fraction of the total voting power that must be exceeded
+    /// for the aggregated vote extensions to reach a quorum
+    quorum: FractionalVotingPower,
+    /// The voting power of each validator whose vote extensions were
+    /// aggregated
+    voting_powers: HashMap<Address, token::Amount>,
+    /// The issuing address and block height of each aggregated
+    /// vote extension
+    signers: HashSet<(Address, BlockHeight)>,
+    /// The aggregated vote extensions, in the order they were added
+    vote_extensions: Vec<T>,
+}
+
+impl<T: AggregatableVext> VextAggregator<T> {
+    /// Create an empty [`VextAggregator`], given the total voting power
+    /// of the consensus validators and the fraction of it that must be
+    /// exceeded to reach a quorum.
+    pub fn new(
+        total_voting_power: token::Amount,
+        quorum: FractionalVotingPower,
+    ) -> Self {
+        Self {
+            total_voting_power,
+            quorum,
+            voting_powers: HashMap::new(),
+            signers: HashSet::new(),
+            vote_extensions: vec![],
+        }
+    }
+
+    /// Add a valid vote extension, issued by a validator with the given
+    /// voting power, to the aggregator.
+    ///
+    /// Returns `false`, leaving the aggregator untouched, if a vote
+    /// extension issued by the same validator at the same block height
+    /// had already been added.
+    pub fn insert(&mut self, voting_power: token::Amount, ext: T) -> bool {
+        let validator = ext.validator_addr().clone();
+        if !self.signers.insert((validator.clone(), ext.block_height())) {
+            return false;
+        }
+        self.voting_powers.entry(validator).or_insert(voting_power);
+        self.vote_extensions.push(ext);
+        true
+    }
+
+    /// Return the number of vote extensions aggregated so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vote_extensions.len()
+    }
+
+    /// Check if no vote extensions have been aggregated so far.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vote_extensions.is_empty()
+    }
+
+    /// Return the voting power of the validators whose vote extensions
+    /// were aggregated so far.
+    #[inline]
+    pub fn voting_power(&self) -> token::Amount {
+        self.voting_powers.values().copied().sum()
+    }
+
+    /// Check if the validators whose vote extensions were aggregated
+    /// so far hold more than the quorum of the total voting power.
+    #[inline]
+    pub fn has_quorum(&self) -> bool {
+        self.voting_power() > self.quorum * self.total_voting_power
+    }
+
+    /// Return the aggregated vote extensions, in the order they were added.
+    #[inline]
+    pub fn into_vote_extensions(self) -> Vec<T> {
+        self.vote_extensions
+    }
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Takes an iterator over vote extension instances, and returns
+    /// another iterator. The latter yields the vote extensions that
+    /// `validate` deems valid at the last committed block height, along
+    /// with the voting power of the validators that issued them, or the
+    /// reason why these are invalid, in the form of a `VoteExtensionError`.
+    #[inline]
+    pub fn validate_vext_list<'iter, T: 'iter>(
+        &'iter self,
+        vote_extensions: impl IntoIterator<Item = T> + 'iter,
+        validate: ValidateVext<D, H, T>,
+    ) -> impl Iterator<
+        Item = std::result::Result<(token::Amount, T), VoteExtensionError>,
+    > + 'iter {
+        let last_height = self.wl_storage.storage.get_last_block_height();
+        vote_extensions.into_iter().map(move |vote_extension| {
+            validate(self, vote_extension, last_height)
+        })
+    }
+
+    /// Create an empty [`VextAggregator`], whose quorum is the vote
+    /// extension digest quorum of the Ethereum bridge, out of the total
+    /// voting power of the current epoch.
+    pub fn new_vext_aggregator<T: AggregatableVext>(
+        &self,
+    ) -> VextAggregator<T> {
+        VextAggregator::new(
+            self.wl_storage.pos_queries().get_total_voting_power(None),
+            self.wl_storage.ethbridge_queries().get_vext_digest_quorum(),
+        )
+    }
+
+    /// Aggregates the valid vote extensions in the given list with a
+    /// [`VextAggregator`], filtering out the invalid instances with
+    /// `validate`. Returns `None` if no block has been committed yet.
+    pub fn aggregate_vexts<T: AggregatableVext>(
+        &self,
+        vote_extensions: impl IntoIterator<Item = T>,
+        validate: ValidateVext<D, H, T>,
+    ) -> Option<VextAggregator<T>> {
+        #[allow(clippy::question_mark)]
+        if self.wl_storage.storage.last_block.is_none() {
+            return None;
+        }
+
+        let mut aggregator = self.new_vext_aggregator();
+
+        for (validator_voting_power, vote_extension) in self
+            .validate_vext_list(vote_extensions, validate)
+            .filter_map(|ext| ext.ok())
+        {
+            let validator_addr = vote_extension.validator_addr().clone();
+            let block_height = vote_extension.block_height();
+            if !aggregator.insert(validator_voting_power, vote_extension) {
+                tracing::warn!(
+                    kind = ?T::KIND,
+                    %validator_addr,
+                    ?block_height,
+                    "Dropped duplicate vote extension from validator while \
+                     aggregating vote extensions - maybe private key of \
+                     validator is being used by multiple nodes?"
+                );
+            }
+        }
+
+        tracing::debug!(
+            kind = ?T::KIND,
+            num_vexts = aggregator.len(),
+            voting_power = ?aggregator.voting_power(),
+            total_voting_power = ?aggregator.total_voting_power,
+            has_quorum = aggregator.has_quorum(),
+            "Aggregated vote extensions"
+        );
+        Some(aggregator)
+    }
+}
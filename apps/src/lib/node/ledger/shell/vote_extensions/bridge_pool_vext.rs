@@ -9,6 +9,7 @@ use namada::types::keccak::keccak_hash;
 use namada::types::storage::BlockHeight;
 use namada::types::token;
 
+use super::metrics::VextKind;
 use super::*;
 use crate::node::ledger::shell::Shell;
 
@@ -48,6 +49,26 @@ where
     ) -> std::result::Result<
         (token::Amount, Signed<bridge_pool_roots::Vext>),
         VoteExtensionError,
+    > {
+        let validator = ext.data.validator_addr.clone();
+        let result = self.check_bp_roots_vext(ext, last_height);
+        self.vext_metrics.lock().unwrap().record(
+            VextKind::BridgePoolRoot,
+            &validator,
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    /// Check the validity of a Bridge pool root vote extension, as described
+    /// in [`Self::validate_bp_roots_vext`].
+    fn check_bp_roots_vext(
+        &self,
+        ext: Signed<bridge_pool_roots::Vext>,
+        last_height: BlockHeight,
+    ) -> std::result::Result<
+        (token::Amount, Signed<bridge_pool_roots::Vext>),
+        VoteExtensionError,
     > {
         // NOTE: for ABCI++, we should pass
         // `last_height` here, instead of `ext.data.block_height`
@@ -96,9 +117,7 @@ where
         // get the public key associated with this validator
         let validator = &ext.data.validator_addr;
         let (voting_power, pk) = self
-            .wl_storage
-            .pos_queries()
-            .get_validator_from_address(validator, Some(ext_height_epoch))
+            .get_consensus_validator_from_address(validator, ext_height_epoch)
             .map_err(|err| {
                 tracing::debug!(
                     ?err,
@@ -172,12 +191,10 @@ where
             VoteExtensionError,
         >,
     > + 'iter {
-        vote_extensions.into_iter().map(|vote_extension| {
-            self.validate_bp_roots_vext_and_get_it_back(
-                vote_extension,
-                self.wl_storage.storage.get_last_block_height(),
-            )
-        })
+        self.validate_vext_list(
+            vote_extensions,
+            Self::validate_bp_roots_vext_and_get_it_back,
+        )
     }
 
     /// Takes a list of signed Bridge pool root vote extensions,
@@ -196,6 +213,24 @@ where
                 ext_1.data.validator_addr == ext_2.data.validator_addr
             })
     }
+
+    /// Compresses a set of signed Bridge pool root vote extensions into
+    /// a single [`bridge_pool_roots::MultiSignedVext`], whilst filtering
+    /// invalid [`bridge_pool_roots::SignedVext`] instances in the process.
+    /// Only the first vote extension of each validator at some block
+    /// height is kept.
+    pub fn compress_bp_roots(
+        &self,
+        vote_extensions: Vec<bridge_pool_roots::SignedVext>,
+    ) -> Option<bridge_pool_roots::MultiSignedVext> {
+        let vote_extensions = self.aggregate_vexts(
+            vote_extensions,
+            Self::validate_bp_roots_vext_and_get_it_back,
+        )?;
+        Some(bridge_pool_roots::MultiSignedVext(
+            vote_extensions.into_vote_extensions().into_iter().collect(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -389,6 +424,39 @@ mod test_bp_vote_extensions {
         assert_eq!(valid, vec![vote_ext]);
     }
 
+    /// Test that the Bridge pool root vexts are compressed into a
+    /// single instance per validator, and that the voting power of
+    /// the validators that issued them is accounted for.
+    #[test]
+    fn test_compress_bp_roots() {
+        let (mut shell, _broadcaster, _, _oracle_control_recv) =
+            setup_at_height(1u64);
+        shell.wl_storage.storage.block.height =
+            shell.wl_storage.storage.get_last_block_height();
+        shell.commit();
+        let vote_ext = shell.extend_vote_with_bp_roots().expect("Test failed");
+
+        let aggregator = shell
+            .aggregate_vexts(
+                vec![vote_ext.clone(), vote_ext.clone()],
+                |shell, ext, height| {
+                    shell.validate_bp_roots_vext_and_get_it_back(ext, height)
+                },
+            )
+            .expect("Test failed");
+        assert_eq!(aggregator.len(), 1);
+        assert_eq!(
+            aggregator.voting_power(),
+            shell.wl_storage.pos_queries().get_total_voting_power(None)
+        );
+        assert!(aggregator.has_quorum());
+
+        let digest = shell
+            .compress_bp_roots(vec![vote_ext.clone(), vote_ext.clone()])
+            .expect("Test failed");
+        assert_eq!(digest, bridge_pool_roots::MultiSignedVext::from(vote_ext));
+    }
+
     /// Test that Bridge pool roots signed by a non-validator are rejected
     /// even if the vext is signed by a validator
     #[test]
@@ -1,7 +1,5 @@
 //! Extend Tendermint votes with Ethereum events seen by a quorum of validators.
 
-use std::collections::{BTreeMap, HashMap};
-
 use namada::ledger::pos::PosQueries;
 use namada::ledger::storage::traits::StorageHasher;
 use namada::ledger::storage::{DBIter, DB};
@@ -10,10 +8,11 @@ use namada::types::ethereum_events::EthereumEvent;
 use namada::types::storage::BlockHeight;
 use namada::types::token;
 use namada::types::vote_extensions::ethereum_events::{
-    self, MultiSignedEthEvent,
+    self, EthEventsAggregator,
 };
 use namada_sdk::eth_bridge::EthBridgeQueries;
 
+use super::metrics::VextKind;
 use super::*;
 use crate::node::ledger::shell::{Shell, ShellMode};
 
@@ -52,6 +51,26 @@ where
     ) -> std::result::Result<
         (token::Amount, Signed<ethereum_events::Vext>),
         VoteExtensionError,
+    > {
+        let validator = ext.data.validator_addr.clone();
+        let result = self.check_eth_events_vext(ext, last_height);
+        self.vext_metrics.lock().unwrap().record(
+            VextKind::EthereumEvents,
+            &validator,
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    /// Check the validity of an Ethereum events vote extension, as described
+    /// in [`Self::validate_eth_events_vext`].
+    fn check_eth_events_vext(
+        &self,
+        ext: Signed<ethereum_events::Vext>,
+        last_height: BlockHeight,
+    ) -> std::result::Result<
+        (token::Amount, Signed<ethereum_events::Vext>),
+        VoteExtensionError,
     > {
         // NOTE: for ABCI++, we should pass
         // `last_height` here, instead of `ext.data.block_height`
@@ -99,9 +118,7 @@ where
         // get the public key associated with this validator
         let validator = &ext.data.validator_addr;
         let (voting_power, pk) = self
-            .wl_storage
-            .pos_queries()
-            .get_validator_from_address(validator, Some(ext_height_epoch))
+            .get_consensus_validator_from_address(validator, ext_height_epoch)
             .map_err(|err| {
                 tracing::debug!(
                     ?err,
@@ -203,12 +220,10 @@ where
             VoteExtensionError,
         >,
     > + 'iter {
-        vote_extensions.into_iter().map(|vote_extension| {
-            self.validate_eth_events_vext_and_get_it_back(
-                vote_extension,
-                self.wl_storage.storage.get_last_block_height(),
-            )
-        })
+        self.validate_vext_list(
+            vote_extensions,
+            Self::validate_eth_events_vext_and_get_it_back,
+        )
     }
 
     /// Takes a list of signed Ethereum events vote extensions,
@@ -227,64 +242,35 @@ where
     /// Compresses a set of signed Ethereum events into a single
     /// [`ethereum_events::VextDigest`], whilst filtering invalid
     /// [`Signed<ethereum_events::Vext>`] instances in the process.
+    /// The events are deduplicated on their keccak hash by an
+    /// [`EthEventsAggregator`], and only the first vote extension of
+    /// each validator at some block height is kept by the
+    /// [`super::aggregator::VextAggregator`].
     ///
-    /// When vote extensions are being used, this performs a check
-    /// that at least 2/3 of the validators by voting power have
-    /// included ethereum events in their vote extension.
+    /// A block proposal including the digest is only accepted if
+    /// the validators who signed it exceed the vote extension digest
+    /// quorum of the total voting power.
     pub fn compress_ethereum_events(
         &self,
         vote_extensions: Vec<Signed<ethereum_events::Vext>>,
     ) -> Option<ethereum_events::VextDigest> {
-        #[allow(clippy::question_mark)]
-        if self.wl_storage.storage.last_block.is_none() {
-            return None;
-        }
-
-        let mut event_observers = BTreeMap::new();
-        let mut signatures = HashMap::new();
-
-        for (_validator_voting_power, vote_extension) in
-            self.filter_invalid_eth_events_vexts(vote_extensions)
-        {
-            let validator_addr = vote_extension.data.validator_addr;
-            let block_height = vote_extension.data.block_height;
-
-            // register all ethereum events seen by `validator_addr`
-            for ev in vote_extension.data.ethereum_events {
-                let signers =
-                    event_observers.entry(ev).or_insert_with(BTreeSet::new);
-                signers.insert((validator_addr.clone(), block_height));
-            }
+        let vote_extensions = self.aggregate_vexts(
+            vote_extensions,
+            Self::validate_eth_events_vext_and_get_it_back,
+        )?;
 
-            // register the signature of `validator_addr`
-            let addr = validator_addr.clone();
-            let sig = vote_extension.sig;
-
-            let key = (addr, block_height);
+        let mut aggregator = EthEventsAggregator::new();
+        for vote_extension in vote_extensions.into_vote_extensions() {
             tracing::debug!(
-                ?key,
-                ?sig,
-                ?validator_addr,
+                validator_addr = ?vote_extension.data.validator_addr,
+                block_height = ?vote_extension.data.block_height,
+                sig = ?vote_extension.sig,
                 "Inserting signature into ethereum_events::VextDigest"
             );
-            if let Some(existing_sig) = signatures.insert(key, sig.clone()) {
-                tracing::warn!(
-                    ?sig,
-                    ?existing_sig,
-                    ?validator_addr,
-                    "Overwrote old signature from validator while \
-                     constructing ethereum_events::VextDigest - maybe private \
-                     key of validator is being used by multiple nodes?"
-                );
-            }
+            aggregator.insert(vote_extension);
         }
 
-        let events: Vec<MultiSignedEthEvent> = event_observers
-            .into_iter()
-            .map(|(event, signers)| MultiSignedEthEvent { event, signers })
-            .collect();
-
-        Some(ethereum_events::VextDigest { events, signatures })
+        Some(aggregator.into_digest())
     }
 }
 
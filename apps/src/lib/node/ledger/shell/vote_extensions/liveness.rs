@@ -0,0 +1,331 @@
+//! Extend Tendermint votes with the liveness observations of consensus
+//! validators, used to jail validators for downtime.
+
+use std::collections::{BTreeMap, HashMap};
+
+use namada::ledger::pos::PosQueries;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::proof_of_stake::read_consensus_validator_set_addresses;
+use namada::types::storage::BlockHeight;
+use namada::types::token;
+use namada::types::vote_extensions::liveness;
+
+use super::metrics::VextKind;
+use super::*;
+use crate::node::ledger::shell::Shell;
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Validates a liveness vote extension issued at the provided
+    /// block height.
+    ///
+    /// Checks that at epoch of the provided height:
+    ///  * The inner Namada address corresponds to a consensus validator.
+    ///  * The validator correctly signed the extension.
+    ///  * The validator signed over the correct height inside of the extension.
+    ///  * The validators reported as offline are consensus validators, other
+    ///    than the validator who made the observations.
+    #[inline]
+    #[allow(dead_code)]
+    pub fn validate_liveness_vext(
+        &self,
+        ext: liveness::SignedVext,
+        last_height: BlockHeight,
+    ) -> bool {
+        self.validate_liveness_vext_and_get_it_back(ext, last_height)
+            .is_ok()
+    }
+
+    /// This method behaves exactly like [`Self::validate_liveness_vext`],
+    /// with the added bonus of returning the vote extension back, if it
+    /// is valid.
+    pub fn validate_liveness_vext_and_get_it_back(
+        &self,
+        ext: liveness::SignedVext,
+        last_height: BlockHeight,
+    ) -> std::result::Result<
+        (token::Amount, liveness::SignedVext),
+        VoteExtensionError,
+    > {
+        let validator = ext.data.validator_addr.clone();
+        let result = self.check_liveness_vext(ext, last_height);
+        self.vext_metrics.lock().unwrap().record(
+            VextKind::Liveness,
+            &validator,
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    /// Check the validity of a liveness vote extension, as described in
+    /// [`Self::validate_liveness_vext`].
+    fn check_liveness_vext(
+        &self,
+        ext: liveness::SignedVext,
+        last_height: BlockHeight,
+    ) -> std::result::Result<
+        (token::Amount, liveness::SignedVext),
+        VoteExtensionError,
+    > {
+        let ext_height_epoch = match self
+            .wl_storage
+            .pos_queries()
+            .get_epoch(ext.data.block_height)
+        {
+            Some(epoch) => epoch,
+            _ => {
+                tracing::debug!(
+                    block_height = ?ext.data.block_height,
+                    "The epoch of the liveness vote extension's block height \
+                     should always be known",
+                );
+                return Err(VoteExtensionError::UnexpectedEpoch);
+            }
+        };
+        if ext.data.block_height > last_height {
+            tracing::debug!(
+                ext_height = ?ext.data.block_height,
+                ?last_height,
+                "Liveness vote extension issued for a block height higher \
+                 than the chain's last height."
+            );
+            return Err(VoteExtensionError::UnexpectedBlockHeight);
+        }
+        if ext.data.block_height.0 == 0 {
+            tracing::debug!("Dropping vote extension issued at genesis");
+            return Err(VoteExtensionError::UnexpectedBlockHeight);
+        }
+        let validator = &ext.data.validator_addr;
+        let consensus_validators = read_consensus_validator_set_addresses(
+            &self.wl_storage,
+            ext_height_epoch,
+        )
+        .expect("Reading the consensus validator set shouldn't fail");
+        if let Some(offline_validator) = ext
+            .data
+            .offline_validators
+            .iter()
+            .find(|offline_validator| {
+                *offline_validator == validator
+                    || !consensus_validators.contains(offline_validator)
+            })
+        {
+            tracing::debug!(
+                %validator,
+                %offline_validator,
+                "Found an invalid observation in a liveness vote extension \
+                 from some validator"
+            );
+            return Err(VoteExtensionError::InvalidLivenessObservation);
+        }
+        // get the public key associated with this validator
+        let (voting_power, pk) = self
+            .get_consensus_validator_from_address(validator, ext_height_epoch)
+            .map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    %validator,
+                    "Could not get public key from Storage for some validator, \
+                     while validating liveness vote extension"
+                );
+                VoteExtensionError::PubKeyNotInStorage
+            })?;
+        // verify the signature of the vote extension
+        ext.verify(&pk)
+            .map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    ?ext.sig,
+                    ?pk,
+                    %validator,
+                    "Failed to verify the signature of a liveness vote \
+                     extension issued by some validator"
+                );
+                VoteExtensionError::VerifySigFailed
+            })
+            .map(|_| (voting_power, ext))
+    }
+
+    /// Takes an iterator over liveness vote extension instances,
+    /// and returns another iterator. The latter yields
+    /// valid liveness vote extensions, or the reason why these
+    /// are invalid, in the form of a `VoteExtensionError`.
+    #[inline]
+    pub fn validate_liveness_vext_list<'iter>(
+        &'iter self,
+        vote_extensions: impl IntoIterator<Item = liveness::SignedVext> + 'iter,
+    ) -> impl Iterator<
+        Item = std::result::Result<
+            (token::Amount, liveness::SignedVext),
+            VoteExtensionError,
+        >,
+    > + 'iter {
+        vote_extensions.into_iter().map(|vote_extension| {
+            self.validate_liveness_vext_and_get_it_back(
+                vote_extension,
+                self.wl_storage.storage.get_last_block_height(),
+            )
+        })
+    }
+
+    /// Takes a list of signed liveness vote extensions,
+    /// and filters out invalid instances.
+    #[inline]
+    pub fn filter_invalid_liveness_vexts<'iter>(
+        &'iter self,
+        vote_extensions: impl IntoIterator<Item = liveness::SignedVext> + 'iter,
+    ) -> impl Iterator<Item = (token::Amount, liveness::SignedVext)> + 'iter
+    {
+        self.validate_liveness_vext_list(vote_extensions)
+            .filter_map(|ext| ext.ok())
+    }
+
+    /// Compresses a set of signed liveness observations into a single
+    /// [`liveness::VextDigest`], whilst filtering invalid
+    /// [`liveness::SignedVext`] instances in the process.
+    pub fn compress_liveness_vexts(
+        &self,
+        vote_extensions: Vec<liveness::SignedVext>,
+    ) -> Option<liveness::VextDigest> {
+        #[allow(clippy::question_mark)]
+        if self.wl_storage.storage.last_block.is_none() {
+            return None;
+        }
+
+        let mut observations = BTreeMap::new();
+        let mut signatures = HashMap::new();
+
+        for (_validator_voting_power, vote_extension) in
+            self.filter_invalid_liveness_vexts(vote_extensions)
+        {
+            let validator_addr = vote_extension.data.validator_addr;
+            let block_height = vote_extension.data.block_height;
+
+            // register all the validators seen offline by `validator_addr`
+            for offline_validator in vote_extension.data.offline_validators {
+                let signers = observations
+                    .entry(offline_validator)
+                    .or_insert_with(BTreeSet::new);
+                signers.insert((validator_addr.clone(), block_height));
+            }
+
+            // register the signature of `validator_addr`
+            let sig = vote_extension.sig;
+            let key = (validator_addr.clone(), block_height);
+            if let Some(existing_sig) = signatures.insert(key, sig.clone()) {
+                tracing::warn!(
+                    ?sig,
+                    ?existing_sig,
+                    ?validator_addr,
+                    "Overwrote old signature from validator while \
+                     constructing liveness::VextDigest - maybe private key of \
+                     validator is being used by multiple nodes?"
+                );
+            }
+        }
+
+        Some(liveness::VextDigest {
+            signatures,
+            observations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_vote_extensions {
+    use std::collections::BTreeSet;
+
+    use namada::proof_of_stake::read_consensus_validator_set_addresses;
+    use namada::types::storage::BlockHeight;
+    use namada::types::vote_extensions::liveness;
+
+    use crate::node::ledger::shell::test_utils::{self, SetupCfg};
+    use crate::wallet;
+
+    /// Test that a liveness vote extension reporting another consensus
+    /// validator as offline is accepted
+    #[test]
+    fn test_validate_liveness_vext() {
+        let (shell, _recv, _, _) = test_utils::setup_with_cfg(SetupCfg {
+            last_height: 3u64,
+            num_validators: 2,
+            ..Default::default()
+        });
+        let validator_addr =
+            shell.mode.get_validator_address().unwrap().clone();
+        let protocol_key =
+            shell.mode.get_protocol_key().expect("Test failed").clone();
+        let epoch = shell.wl_storage.storage.get_current_epoch().0;
+        let offline_validators =
+            read_consensus_validator_set_addresses(&shell.wl_storage, epoch)
+                .expect("Test failed")
+                .into_iter()
+                .filter(|validator| validator != &validator_addr)
+                .collect::<BTreeSet<_>>();
+        assert_eq!(offline_validators.len(), 1);
+
+        let ext = liveness::Vext {
+            block_height: shell.wl_storage.storage.get_last_block_height(),
+            validator_addr,
+            offline_validators,
+        }
+        .sign(&protocol_key);
+
+        assert!(shell.validate_liveness_vext(
+            ext.clone(),
+            shell.wl_storage.storage.get_last_block_height(),
+        ));
+
+        let digest = shell
+            .compress_liveness_vexts(vec![ext.clone()])
+            .expect("Test failed");
+        assert_eq!(digest, liveness::VextDigest::singleton(ext));
+    }
+
+    /// Test that liveness vote extensions reporting the signing validator
+    /// itself, or validators outside of the consensus set, are rejected
+    #[test]
+    fn test_reject_invalid_liveness_observations() {
+        let (shell, _recv, _, _) = test_utils::setup_at_height(3u64);
+        let validator_addr =
+            shell.mode.get_validator_address().unwrap().clone();
+        let protocol_key =
+            shell.mode.get_protocol_key().expect("Test failed").clone();
+        let last_height = shell.wl_storage.storage.get_last_block_height();
+
+        for offline_validator in
+            [validator_addr.clone(), wallet::defaults::bertha_address()]
+        {
+            let ext = liveness::Vext {
+                block_height: last_height,
+                validator_addr: validator_addr.clone(),
+                offline_validators: BTreeSet::from([offline_validator]),
+            }
+            .sign(&protocol_key);
+            assert!(!shell.validate_liveness_vext(ext, last_height));
+        }
+    }
+
+    /// Test that liveness vote extensions issued for a block height
+    /// in the future are rejected
+    #[test]
+    fn test_reject_liveness_vext_future_height() {
+        let (shell, _recv, _, _) = test_utils::setup_at_height(3u64);
+        let validator_addr =
+            shell.mode.get_validator_address().unwrap().clone();
+        let protocol_key =
+            shell.mode.get_protocol_key().expect("Test failed").clone();
+        let last_height = shell.wl_storage.storage.get_last_block_height();
+
+        let ext = liveness::Vext::empty(
+            BlockHeight(last_height.0 + 1),
+            validator_addr,
+        )
+        .sign(&protocol_key);
+        assert!(!shell.validate_liveness_vext(ext, last_height));
+    }
+}
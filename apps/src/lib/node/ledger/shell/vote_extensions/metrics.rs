@@ -0,0 +1,148 @@
+//! Counters of validated and rejected vote extensions, which help
+//! operators diagnose why the vote extensions of some validator are
+//! getting dropped.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada::types::address::Address;
+
+use super::VoteExtensionError;
+
+/// The kinds of vote extensions validated by the shell.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum VextKind {
+    /// Ethereum events vote extensions.
+    EthereumEvents,
+    /// Bridge pool root vote extensions.
+    BridgePoolRoot,
+    /// Validator set update vote extensions.
+    ValidatorSetUpdate,
+    /// Liveness vote extensions.
+    Liveness,
+}
+
+/// Counters of the vote extensions of some kind.
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct VextCounters {
+    /// The number of vote extensions that were successfully validated.
+    pub validated: u64,
+    /// The number of rejected vote extensions, indexed by the name of the
+    /// [`VoteExtensionError`] variant they were rejected with.
+    pub rejected: BTreeMap<String, u64>,
+}
+
+impl VextCounters {
+    /// Count the outcome of validating a vote extension.
+    fn record(&mut self, error: Option<&VoteExtensionError>) {
+        match error {
+            None => self.validated += 1,
+            Some(error) => {
+                *self.rejected.entry(error.reason().to_owned()).or_default() +=
+                    1;
+            }
+        }
+    }
+
+    /// The total number of rejected vote extensions.
+    pub fn total_rejected(&self) -> u64 {
+        self.rejected.values().sum()
+    }
+}
+
+/// Metrics of the vote extensions validated by the shell, since the
+/// node started.
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct VextMetrics {
+    /// Counters of each kind of vote extension.
+    pub per_kind: BTreeMap<VextKind, VextCounters>,
+    /// Counters of each kind of vote extension, per validator that
+    /// issued them.
+    pub per_validator: BTreeMap<Address, BTreeMap<VextKind, VextCounters>>,
+}
+
+impl VextMetrics {
+    /// Count the outcome of validating a vote extension of the given kind,
+    /// issued by `validator`.
+    pub fn record(
+        &mut self,
+        kind: VextKind,
+        validator: &Address,
+        error: Option<&VoteExtensionError>,
+    ) {
+        self.per_kind.entry(kind).or_default().record(error);
+        self.per_validator
+            .entry(validator.clone())
+            .or_default()
+            .entry(kind)
+            .or_default()
+            .record(error);
+    }
+}
+
+#[cfg(test)]
+mod test_vext_metrics {
+    use namada::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    use super::*;
+
+    /// Test that validated and rejected vote extensions are counted per
+    /// kind, per validator and per rejection reason.
+    #[test]
+    fn test_record_vext_outcomes() {
+        let validator_1 = established_address_1();
+        let validator_2 = established_address_2();
+        let mut metrics = VextMetrics::default();
+
+        metrics.record(VextKind::EthereumEvents, &validator_1, None);
+        metrics.record(
+            VextKind::EthereumEvents,
+            &validator_1,
+            Some(&VoteExtensionError::VerifySigFailed),
+        );
+        metrics.record(
+            VextKind::EthereumEvents,
+            &validator_2,
+            Some(&VoteExtensionError::VerifySigFailed),
+        );
+        metrics.record(
+            VextKind::BridgePoolRoot,
+            &validator_2,
+            Some(&VoteExtensionError::InvalidBPRootSig),
+        );
+
+        let eth_events = &metrics.per_kind[&VextKind::EthereumEvents];
+        assert_eq!(eth_events.validated, 1);
+        assert_eq!(eth_events.total_rejected(), 2);
+        assert_eq!(eth_events.rejected["VerifySigFailed"], 2);
+
+        let validator_2_counters = &metrics.per_validator[&validator_2];
+        assert_eq!(
+            validator_2_counters[&VextKind::EthereumEvents].validated,
+            0
+        );
+        assert_eq!(
+            validator_2_counters[&VextKind::BridgePoolRoot].rejected
+                ["InvalidBPRootSig"],
+            1
+        );
+        assert!(!metrics.per_kind.contains_key(&VextKind::Liveness));
+    }
+}
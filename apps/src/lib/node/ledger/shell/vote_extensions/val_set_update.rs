@@ -10,6 +10,7 @@ use namada::types::storage::Epoch;
 use namada::types::token;
 use namada::types::vote_extensions::validator_set_update;
 
+use super::metrics::VextKind;
 use super::*;
 use crate::node::ledger::shell::Shell;
 
@@ -59,6 +60,26 @@ where
     ) -> std::result::Result<
         (token::Amount, validator_set_update::SignedVext),
         VoteExtensionError,
+    > {
+        let validator = ext.data.validator_addr.clone();
+        let result = self.check_valset_upd_vext(ext, last_epoch);
+        self.vext_metrics.lock().unwrap().record(
+            VextKind::ValidatorSetUpdate,
+            &validator,
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    /// Check the validity of a validator set update vote extension, as
+    /// described in [`Self::validate_valset_upd_vext`].
+    fn check_valset_upd_vext(
+        &self,
+        ext: validator_set_update::SignedVext,
+        last_epoch: Epoch,
+    ) -> std::result::Result<
+        (token::Amount, validator_set_update::SignedVext),
+        VoteExtensionError,
     > {
         if self.wl_storage.storage.last_block.is_none() {
             tracing::debug!(
@@ -123,9 +144,7 @@ where
         // get the public key associated with this validator
         let validator = &ext.data.validator_addr;
         let (voting_power, _) = self
-            .wl_storage
-            .pos_queries()
-            .get_validator_from_address(validator, Some(signing_epoch))
+            .get_consensus_validator_from_address(validator, signing_epoch)
             .map_err(|err| {
                 tracing::debug!(
                     ?err,
@@ -0,0 +1,403 @@
+//! Migrations of the ledger state across the versions of its schema.
+//!
+//! The version of the state schema is recorded in the protocol parameters.
+//! When a binary that knows about a higher version than the one recorded
+//! starts, it runs the pending migrations in order of their version. The
+//! migrations write through the block write log, so that their changes get
+//! committed along with the next block, which is the block at the halt height
+//! after a coordinated upgrade, and every node ends up with the same state. If
+//! any migration fails, none of the changes is kept.
+
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada::ledger::parameters;
+use namada::ledger::storage::{DBIter, StorageHasher, WlStorage, DB};
+use namada::ledger::storage_api::{self, ResultExt, StorageRead};
+use namada::proof_of_stake::parameters::OwnedPosParams;
+use namada::proof_of_stake::storage::params_key;
+use namada::proof_of_stake::write_pos_params;
+use namada::types::dec::Dec;
+use namada::types::storage::Key;
+use namada::types::token;
+use thiserror::Error;
+
+/// The version of the state schema
+pub type StateVersion = u64;
+
+/// A function migrating the state to a new version of its schema
+pub type MigrationFn<D, H> =
+    fn(&mut WlStorage<D, H>) -> storage_api::Result<()>;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(
+        "The state migration to version {version} ({description}) failed: \
+         {error}"
+    )]
+    Migration {
+        version: StateVersion,
+        description: &'static str,
+        error: storage_api::Error,
+    },
+    #[error("Error reading or writing the state version: {0}")]
+    StateVersion(storage_api::Error),
+}
+
+/// A migration of the state to a new version of its schema
+pub struct Migration<D, H> {
+    /// The version of the state after the migration
+    pub version: StateVersion,
+    /// A short description of the migration, for the logs
+    pub description: &'static str,
+    /// The function applying the migration
+    pub migrate: MigrationFn<D, H>,
+}
+
+/// The outcome of running the pending migrations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// The version of the state before the migrations
+    pub from_version: StateVersion,
+    /// The version of the state after the migrations
+    pub to_version: StateVersion,
+    /// The storage keys changed by the migrations
+    pub changed_keys: BTreeSet<Key>,
+}
+
+/// The ordered migrations of the state
+pub struct MigrationRegistry<D, H> {
+    migrations: Vec<Migration<D, H>>,
+}
+
+impl<D, H> Default for MigrationRegistry<D, H> {
+    fn default() -> Self {
+        Self { migrations: vec![] }
+    }
+}
+
+impl<D, H> MigrationRegistry<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Register the migration to the given version. The migrations must be
+    /// registered in order of strictly increasing versions, starting from 1.
+    pub fn register(
+        mut self,
+        version: StateVersion,
+        description: &'static str,
+        migrate: MigrationFn<D, H>,
+    ) -> Self {
+        assert!(
+            version > self.latest_version(),
+            "The state migration to version {version} must be registered \
+             after the migration to version {}",
+            self.latest_version()
+        );
+        self.migrations.push(Migration {
+            version,
+            description,
+            migrate,
+        });
+        self
+    }
+
+    /// The latest version of the state schema
+    pub fn latest_version(&self) -> StateVersion {
+        self.migrations
+            .last()
+            .map(|migration| migration.version)
+            .unwrap_or_default()
+    }
+
+    /// Record the latest version of the state schema, for a chain
+    /// initialized at genesis
+    pub fn init_state_version(
+        &self,
+        wl_storage: &mut WlStorage<D, H>,
+    ) -> storage_api::Result<()> {
+        parameters::update_state_version(wl_storage, self.latest_version())
+    }
+
+    /// Run the migrations to a higher version than the one of the state, in
+    /// order, and record the latest version. This must be called on startup,
+    /// before any block is processed.
+    ///
+    /// If any migration fails, or on a dry run, the write log is rolled back
+    /// to its state prior to the migrations.
+    pub fn run(
+        &self,
+        wl_storage: &mut WlStorage<D, H>,
+        dry_run: bool,
+    ) -> Result<MigrationReport, Error> {
+        let from_version = parameters::read_state_version(wl_storage)
+            .map_err(Error::StateVersion)?
+            .unwrap_or_default();
+        let mut report = MigrationReport {
+            from_version,
+            to_version: from_version,
+            changed_keys: BTreeSet::new(),
+        };
+        if from_version >= self.latest_version() {
+            return Ok(report);
+        }
+
+        let write_log = wl_storage.write_log.clone();
+        match self.apply(wl_storage, from_version) {
+            Ok(()) => {
+                report.to_version = self.latest_version();
+                report.changed_keys = wl_storage.write_log.get_block_keys();
+                if dry_run {
+                    wl_storage.write_log = write_log;
+                }
+                Ok(report)
+            }
+            Err(err) => {
+                wl_storage.write_log = write_log;
+                Err(err)
+            }
+        }
+    }
+
+    /// Apply the pending migrations to the write log
+    fn apply(
+        &self,
+        wl_storage: &mut WlStorage<D, H>,
+        from_version: StateVersion,
+    ) -> Result<(), Error> {
+        for Migration {
+            version,
+            description,
+            migrate,
+        } in self
+            .migrations
+            .iter()
+            .filter(|migration| migration.version > from_version)
+        {
+            tracing::info!(
+                "Migrating the state to version {version}: {description}"
+            );
+            migrate(wl_storage).map_err(|error| Error::Migration {
+                version: *version,
+                description,
+                error,
+            })?;
+        }
+        parameters::update_state_version(wl_storage, self.latest_version())
+            .map_err(Error::StateVersion)
+    }
+}
+
+/// All the migrations of the state, in order. A migration must be registered
+/// here whenever a change to the ledger breaks the schema of the existing
+/// state, e.g. the re-keying of some storage or a new field in a stored
+/// type.
+pub fn all<D, H>() -> MigrationRegistry<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    MigrationRegistry::default().register(
+        1,
+        "re-encode the PoS parameters with their new fields",
+        migrate_pos_params,
+    )
+}
+
+/// The layout of the PoS parameters prior to the state version 1
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+struct PosParamsV0 {
+    max_validator_slots: u64,
+    pipeline_len: u64,
+    unbonding_len: u64,
+    tm_votes_per_token: Dec,
+    block_proposer_reward: Dec,
+    block_vote_reward: Dec,
+    max_inflation_rate: Dec,
+    target_staked_ratio: Dec,
+    duplicate_vote_min_slash_rate: Dec,
+    light_client_attack_min_slash_rate: Dec,
+    cubic_slashing_window_length: u64,
+    validator_stake_threshold: token::Amount,
+    liveness_window_check: u64,
+    liveness_threshold: Dec,
+}
+
+/// Re-encode the PoS parameters stored in the layout prior to the state
+/// version 1, setting the fields added since to their default values
+fn migrate_pos_params<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+) -> storage_api::Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    let bytes = wl_storage
+        .read_bytes(&params_key())?
+        .ok_or_else(|| storage_api::Error::new_const("Missing PoS params"))?;
+    let PosParamsV0 {
+        max_validator_slots,
+        pipeline_len,
+        unbonding_len,
+        tm_votes_per_token,
+        block_proposer_reward,
+        block_vote_reward,
+        max_inflation_rate,
+        target_staked_ratio,
+        duplicate_vote_min_slash_rate,
+        light_client_attack_min_slash_rate,
+        cubic_slashing_window_length,
+        validator_stake_threshold,
+        liveness_window_check,
+        liveness_threshold,
+    } = PosParamsV0::try_from_slice(&bytes).into_storage_result()?;
+    let defaults = OwnedPosParams::default();
+    let params = OwnedPosParams {
+        max_validator_slots,
+        pipeline_len,
+        unbonding_len,
+        tm_votes_per_token,
+        block_proposer_reward,
+        block_vote_reward,
+        max_inflation_rate,
+        target_staked_ratio,
+        duplicate_vote_min_slash_rate,
+        light_client_attack_min_slash_rate,
+        cubic_slashing_window_length,
+        validator_stake_threshold,
+        liveness_window_check,
+        liveness_threshold,
+        // The evidence must not outlive the unbonding period
+        evidence_max_age_epochs: std::cmp::min(
+            defaults.evidence_max_age_epochs,
+            unbonding_len,
+        ),
+        ..defaults
+    };
+    write_pos_params(wl_storage, &params)
+}
+
+#[cfg(test)]
+mod test_migrations {
+    use borsh_ext::BorshSerializeExt;
+    use namada::ledger::storage::testing::TestWlStorage;
+    use namada::ledger::storage_api::StorageWrite;
+    use namada::types::address;
+    use namada::types::storage::KeySeg;
+
+    use super::*;
+
+    fn test_key() -> Key {
+        Key::from(address::nam().to_db_key())
+            .push(&"migrated".to_owned())
+            .unwrap()
+    }
+
+    fn write_key(wl_storage: &mut TestWlStorage) -> storage_api::Result<()> {
+        wl_storage.write(&test_key(), 1_u64)
+    }
+
+    fn fail(_wl_storage: &mut TestWlStorage) -> storage_api::Result<()> {
+        Err(storage_api::Error::new_const("the migration failed"))
+    }
+
+    /// Test that the pending migrations are applied in order, and that none
+    /// of their changes is kept on a dry run or on failure
+    #[test]
+    fn test_run_migrations() {
+        let mut wl_storage = TestWlStorage::default();
+
+        // A failing migration drops the changes of the previous ones
+        let registry = MigrationRegistry::default()
+            .register(1, "write a key", write_key)
+            .register(2, "fail", fail);
+        assert!(matches!(
+            registry.run(&mut wl_storage, false),
+            Err(Error::Migration { version: 2, .. })
+        ));
+        assert!(!wl_storage.has_key(&test_key()).unwrap());
+        assert_eq!(parameters::read_state_version(&wl_storage).unwrap(), None);
+
+        // A dry run reports the changes without keeping them
+        let registry =
+            MigrationRegistry::default().register(1, "write a key", write_key);
+        let report = registry.run(&mut wl_storage, true).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, 1);
+        assert!(report.changed_keys.contains(&test_key()));
+        assert!(!wl_storage.has_key(&test_key()).unwrap());
+
+        let report = registry.run(&mut wl_storage, false).unwrap();
+        assert_eq!(report.to_version, 1);
+        assert_eq!(wl_storage.read(&test_key()).unwrap(), Some(1_u64));
+        assert_eq!(
+            parameters::read_state_version(&wl_storage).unwrap(),
+            Some(1)
+        );
+
+        // The migrations already applied don't run again
+        let registry = MigrationRegistry::default()
+            .register(1, "fail", fail)
+            .register(2, "write a key", write_key);
+        let report = registry.run(&mut wl_storage, false).unwrap();
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.to_version, 2);
+    }
+
+    /// Test that the PoS parameters stored in the layout prior to the state
+    /// version 1 are re-encoded with the default values of the new fields
+    #[test]
+    fn test_migrate_pos_params() {
+        let mut wl_storage = TestWlStorage::default();
+        let legacy = PosParamsV0 {
+            max_validator_slots: 100,
+            pipeline_len: 2,
+            unbonding_len: 6,
+            tm_votes_per_token: Dec::one(),
+            block_proposer_reward: Dec::new(125, 3).unwrap(),
+            block_vote_reward: Dec::new(1, 1).unwrap(),
+            max_inflation_rate: Dec::new(1, 1).unwrap(),
+            target_staked_ratio: Dec::new(6667, 4).unwrap(),
+            duplicate_vote_min_slash_rate: Dec::new(1, 3).unwrap(),
+            light_client_attack_min_slash_rate: Dec::new(1, 3).unwrap(),
+            cubic_slashing_window_length: 1,
+            validator_stake_threshold: token::Amount::native_whole(1),
+            liveness_window_check: 100,
+            liveness_threshold: Dec::new(9, 1).unwrap(),
+        };
+        wl_storage
+            .write_bytes(&params_key(), legacy.serialize_to_vec())
+            .unwrap();
+        // The legacy layout can't be decoded as the current one
+        assert!(wl_storage.read::<OwnedPosParams>(&params_key()).is_err());
+
+        let report = all().run(&mut wl_storage, false).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert!(report.changed_keys.contains(&params_key()));
+
+        let params: OwnedPosParams =
+            wl_storage.read(&params_key()).unwrap().unwrap();
+        let defaults = OwnedPosParams::default();
+        assert_eq!(params.max_validator_slots, legacy.max_validator_slots);
+        assert_eq!(params.unbonding_len, legacy.unbonding_len);
+        assert_eq!(params.liveness_window_check, legacy.liveness_window_check);
+        assert_eq!(params.liveness_threshold, legacy.liveness_threshold);
+        assert_eq!(
+            params.validator_stake_threshold,
+            legacy.validator_stake_threshold
+        );
+        assert_eq!(params.block_vext_reward, defaults.block_vext_reward);
+        assert_eq!(
+            params.past_data_retention_len,
+            defaults.past_data_retention_len
+        );
+        assert_eq!(
+            params.max_slashed_validators_per_block,
+            defaults.max_slashed_validators_per_block
+        );
+        assert_eq!(params.evidence_max_age_epochs, legacy.unbonding_len);
+        assert_eq!(params.inflation_model, defaults.inflation_model);
+    }
+}
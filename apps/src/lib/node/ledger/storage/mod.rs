@@ -1,6 +1,7 @@
 //! The storage module handles both the current state in-memory and the stored
 //! state in DB.
 
+pub mod migrations;
 mod rocksdb;
 
 use std::fmt;
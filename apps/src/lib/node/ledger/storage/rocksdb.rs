@@ -18,8 +18,15 @@
 //!     - `next_epoch_min_start_height`
 //!     - `next_epoch_min_start_time`
 //!   - `conversion_state`: MASP conversion state
-//! - `subspace`: accounts sub-spaces
+//! - `subspace`: accounts sub-spaces, except for the ones with their own
+//!   column family below
 //!   - `{address}/{dyn}`: any byte data associated with accounts
+//! - `subspace_pos`: the PoS sub-space
+//!   - `{pos_address}/{dyn}`
+//! - `subspace_masp`: the MASP sub-space
+//!   - `{masp_address}/{dyn}`
+//! - `subspace_ibc`: the IBC sub-space
+//!   - `{ibc_address}/{dyn}`
 //! - `diffs`: diffs in account subspaces' key-vals
 //!   - `new/{dyn}`: value set in block height `h`
 //!   - `old/{dyn}`: value from predecessor block height
@@ -37,6 +44,8 @@
 //! - `replay_protection`: hashes of processed tx
 //!     - `all`: the hashes included up to the last block
 //!     - `last`: the hashes included in the last block
+//!     - `expiring/{e}`: the hashes of the txs that have expired by the start
+//!       of epoch `e`
 
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -47,8 +56,10 @@ use std::sync::Mutex;
 use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
+use itertools::structs::KMergeBy;
 use itertools::Either;
 use namada::core::ledger::masp_conversions::ConversionState;
+use namada::core::ledger::replay_protection;
 use namada::core::types::ethereum_structs;
 use namada::eth_bridge::storage::proof::BridgePoolRootProof;
 use namada::ledger::eth_bridge::storage::bridge_pool;
@@ -60,11 +71,12 @@ use namada::ledger::storage::{
     types, BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error,
     MerkleTreeStoresRead, Result, StoreType, DB,
 };
+use namada::types::address::{self, Address};
 use namada::types::ethereum_events::Uint;
 use namada::types::internal::TxQueue;
 use namada::types::storage::{
-    BlockHeight, BlockResults, Epoch, EthEventsQueue, Header, Key, KeySeg,
-    KEY_SEGMENT_SEPARATOR,
+    BlockHeight, BlockResults, DbKeySeg, Epoch, EthEventsQueue, Header, Key,
+    KeySeg, KEY_SEGMENT_SEPARATOR,
 };
 use namada::types::time::DateTimeUtc;
 use rayon::prelude::*;
@@ -87,6 +99,28 @@ const DIFFS_CF: &str = "diffs";
 const STATE_CF: &str = "state";
 const BLOCK_CF: &str = "block";
 const REPLAY_PROTECTION_CF: &str = "replay_protection";
+const POS_SUBSPACE_CF: &str = "subspace_pos";
+const MASP_SUBSPACE_CF: &str = "subspace_masp";
+const IBC_SUBSPACE_CF: &str = "subspace_ibc";
+
+/// The sub-spaces of the subsystems that are kept in their own column family,
+/// apart from the other accounts sub-spaces
+const SUBSYSTEM_SUBSPACES: [(Address, &str); 3] = [
+    (address::POS, POS_SUBSPACE_CF),
+    (address::MASP, MASP_SUBSPACE_CF),
+    (address::IBC, IBC_SUBSPACE_CF),
+];
+
+/// The number of keys moved at once when splitting the sub-spaces of the
+/// subsystems out of the `subspace` column family
+const SPLIT_SUBSPACE_BATCH_SIZE: usize = 10_000;
+
+/// The key of the version of the layout of the column families in the `state`
+/// column family
+const LAYOUT_VERSION_KEY: &str = "layout_version";
+/// The version of the layout of the column families in which the sub-spaces
+/// of the subsystems are split out of the `subspace` column family
+const SPLIT_SUBSPACE_LAYOUT_VERSION: u64 = 1;
 
 /// RocksDB handle
 #[derive(Debug)]
@@ -130,15 +164,7 @@ pub fn open(
     db_opts.set_atomic_flush(true);
 
     let mut cfs = Vec::new();
-    let mut table_opts = BlockBasedOptions::default();
-    table_opts.set_block_size(16 * 1024);
-    table_opts.set_cache_index_and_filter_blocks(true);
-    table_opts.set_pin_l0_filter_and_index_blocks_in_cache(true);
-    if let Some(cache) = cache {
-        table_opts.set_block_cache(cache);
-    }
-    // latest format versions https://github.com/facebook/rocksdb/blob/d1c510baecc1aef758f91f786c4fbee3bc847a63/include/rocksdb/table.h#L394
-    table_opts.set_format_version(5);
+    let table_opts = make_table_opts(cache);
 
     // for subspace (read/update-intensive)
     let mut subspace_cf_opts = Options::default();
@@ -189,9 +215,78 @@ pub fn open(
         replay_protection_cf_opts,
     ));
 
-    rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
+    // The sub-spaces of the subsystems are mostly read by key, so their
+    // tables come with a bloom filter to skip the files without the key
+    let mut filtered_table_opts = make_table_opts(cache);
+    filtered_table_opts.set_bloom_filter(10.0, false);
+
+    // for PoS (read-intensive, hot during block processing)
+    let mut pos_cf_opts = Options::default();
+    // Fast decompression of the hot data
+    pos_cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+    pos_cf_opts.set_level_compaction_dynamic_level_bytes(true);
+    // Prioritize minimizing read amplification
+    pos_cf_opts.set_compaction_style(rocksdb::DBCompactionStyle::Level);
+    pos_cf_opts.set_block_based_table_factory(&filtered_table_opts);
+    cfs.push(ColumnFamilyDescriptor::new(POS_SUBSPACE_CF, pos_cf_opts));
+
+    // for MASP (insert-intensive, with large writes)
+    let mut masp_cf_opts = Options::default();
+    masp_cf_opts.set_compression_type(rocksdb::DBCompressionType::Zstd);
+    masp_cf_opts.set_compression_options(0, 0, 0, 1024 * 1024);
+    // Absorb the large writes in memory before flushing them
+    masp_cf_opts.set_write_buffer_size(128 * 1024 * 1024);
+    // Prioritize minimizing write amplification
+    masp_cf_opts.set_compaction_style(rocksdb::DBCompactionStyle::Universal);
+    masp_cf_opts.set_block_based_table_factory(&filtered_table_opts);
+    cfs.push(ColumnFamilyDescriptor::new(MASP_SUBSPACE_CF, masp_cf_opts));
+
+    // for IBC (read/update-intensive)
+    let mut ibc_cf_opts = Options::default();
+    ibc_cf_opts.set_compression_type(rocksdb::DBCompressionType::Zstd);
+    ibc_cf_opts.set_compression_options(0, 0, 0, 1024 * 1024);
+    ibc_cf_opts.set_level_compaction_dynamic_level_bytes(true);
+    ibc_cf_opts.set_compaction_style(rocksdb::DBCompactionStyle::Level);
+    ibc_cf_opts.set_block_based_table_factory(&filtered_table_opts);
+    cfs.push(ColumnFamilyDescriptor::new(IBC_SUBSPACE_CF, ibc_cf_opts));
+
+    let db = rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
         .map(RocksDB)
-        .map_err(|e| Error::DBError(e.into_string()))
+        .map_err(|e| Error::DBError(e.into_string()))?;
+    db.split_subspace()?;
+    Ok(db)
+}
+
+/// Make the options of the block-based tables of a column family
+fn make_table_opts(cache: Option<&rocksdb::Cache>) -> BlockBasedOptions {
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts.set_block_size(16 * 1024);
+    table_opts.set_cache_index_and_filter_blocks(true);
+    table_opts.set_pin_l0_filter_and_index_blocks_in_cache(true);
+    if let Some(cache) = cache {
+        table_opts.set_block_cache(cache);
+    }
+    // latest format versions https://github.com/facebook/rocksdb/blob/d1c510baecc1aef758f91f786c4fbee3bc847a63/include/rocksdb/table.h#L394
+    table_opts.set_format_version(5);
+    table_opts
+}
+
+/// Get the names of the column families of all the accounts sub-spaces
+fn subspace_cf_names() -> impl Iterator<Item = &'static str> {
+    std::iter::once(SUBSPACE_CF)
+        .chain(SUBSYSTEM_SUBSPACES.iter().map(|(_, cf_name)| *cf_name))
+}
+
+/// Get the name of the column family of the sub-space of the given key
+fn subspace_cf_name(key: &Key) -> &'static str {
+    match key.segments.first() {
+        Some(DbKeySeg::AddressSeg(owner)) => SUBSYSTEM_SUBSPACES
+            .iter()
+            .find(|(address, _)| address == owner)
+            .map(|(_, cf_name)| *cf_name)
+            .unwrap_or(SUBSPACE_CF),
+        _ => SUBSPACE_CF,
+    }
 }
 
 impl Drop for RocksDB {
@@ -207,6 +302,73 @@ impl RocksDB {
             .ok_or(Error::DBError("No {cf_name} column family".to_string()))
     }
 
+    /// Get the column family of the sub-space of the given key
+    fn get_subspace_column_family(&self, key: &Key) -> Result<&ColumnFamily> {
+        self.get_column_family(subspace_cf_name(key))
+    }
+
+    /// Move the keys of the sub-spaces of the subsystems out of the
+    /// `subspace` column family, which used to hold all the accounts
+    /// sub-spaces, into their own column families. The keys are moved in
+    /// batches that are written atomically, such that an interrupted split
+    /// gets resumed the next time the DB is opened. The version of the layout
+    /// is recorded once the split is complete, so that it only runs once.
+    fn split_subspace(&self) -> Result<()> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        let layout_version: u64 = match self
+            .0
+            .get_cf(state_cf, LAYOUT_VERSION_KEY)
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError)?,
+            None => 0,
+        };
+        if layout_version >= SPLIT_SUBSPACE_LAYOUT_VERSION {
+            return Ok(());
+        }
+
+        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        for (address, cf_name) in &SUBSYSTEM_SUBSPACES {
+            let cf = self.get_column_family(cf_name)?;
+            let prefix = format!("{}/", Key::from(address.to_db_key()));
+            let iter = self.0.iterator_cf_opt(
+                subspace_cf,
+                make_iter_read_opts(Some(prefix.clone())),
+                IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+            );
+            let mut batch = WriteBatch::default();
+            let mut moved_keys = 0_usize;
+            for result in iter {
+                let (key, value) =
+                    result.map_err(|e| Error::DBError(e.into_string()))?;
+                batch.put_cf(cf, &key, value);
+                batch.delete_cf(subspace_cf, key);
+                moved_keys += 1;
+                if moved_keys % SPLIT_SUBSPACE_BATCH_SIZE == 0 {
+                    self.0
+                        .write(std::mem::take(&mut batch))
+                        .map_err(|e| Error::DBError(e.into_string()))?;
+                }
+            }
+            self.0
+                .write(batch)
+                .map_err(|e| Error::DBError(e.into_string()))?;
+            if moved_keys > 0 {
+                tracing::info!(
+                    "Moved {moved_keys} keys of the {address} sub-space to \
+                     the {cf_name} column family"
+                );
+            }
+        }
+        self.0
+            .put_cf(
+                state_cf,
+                LAYOUT_VERSION_KEY,
+                types::encode(&SPLIT_SUBSPACE_LAYOUT_VERSION),
+            )
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
     /// Persist the diff of an account subspace key-val under the height where
     /// it was changed.
     fn write_subspace_diff(
@@ -282,6 +444,14 @@ impl RocksDB {
             .map_err(|e| Error::DBError(e.into_string()))
     }
 
+    /// Create a checkpoint of the DB in the given directory, i.e. a
+    /// consistent copy of its current state that can be opened as a DB
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
+        rocksdb::checkpoint::Checkpoint::new(&self.0)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(path))
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
     /// Dump last known block
     pub fn dump_block(
         &self,
@@ -376,10 +546,12 @@ impl RocksDB {
                 .expect("Unable to write to output file");
         } else {
             // Just dump the current subspace
-            let cf = self
-                .get_column_family(SUBSPACE_CF)
-                .expect("Subspace column family should exist");
-            self.dump_it(cf, None, &mut file);
+            for cf_name in subspace_cf_names() {
+                let cf = self
+                    .get_column_family(cf_name)
+                    .expect("Subspace column family should exist");
+                self.dump_it(cf, None, &mut file);
+            }
         }
 
         // replay protection
@@ -419,10 +591,11 @@ impl RocksDB {
         };
 
         let mut buf = BufWriter::new(file);
-        for (key, raw_val, _gas) in PersistentPrefixIterator(
-            PrefixIterator::new(iter, String::default()),
+        for (key, raw_val, _gas) in PersistentPrefixIterator::new(
+            [iter],
             // Empty string to prevent prefix stripping, the prefix is
             // already in the enclosed iterator
+            String::default(),
         ) {
             let val = HEXLOWER.encode(&raw_val);
             let bytes = format!("\"{key}\" = \"{val}\"\n");
@@ -519,7 +692,9 @@ impl RocksDB {
             |(key, _value, _gas)| -> Result<()> {
                 // Restore previous height diff if present, otherwise delete the
                 // subspace key
-                let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+                let subspace_cf = self.get_subspace_column_family(
+                    &Key::parse(&key).map_err(Error::KeyError)?,
+                )?;
                 match self.read_subspace_val_with_height(
                     &Key::from(key.to_db_key()),
                     previous_height,
@@ -546,7 +721,6 @@ impl RocksDB {
         };
         {
             let mut batch_guard = batch.lock().unwrap();
-            let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
             for (key, val, _) in
                 iter_diffs_prefix(self, last_block.height, None, true)
             {
@@ -555,6 +729,7 @@ impl RocksDB {
                 if self.read_subspace_val(&diff_new_key)?.is_none() {
                     // If there is no new value, it has been deleted in this
                     // block and we have to restore it
+                    let subspace_cf = self.get_subspace_column_family(&key)?;
                     batch_guard.put_cf(subspace_cf, key.to_string(), val)
                 }
             }
@@ -570,9 +745,10 @@ impl RocksDB {
                 read_opts,
                 IteratorMode::From(prefix.as_bytes(), Direction::Forward),
             );
-            for (key, _value, _gas) in PersistentPrefixIterator(
+            for (key, _value, _gas) in PersistentPrefixIterator::new(
+                [iter],
                 // Empty prefix string to prevent stripping
-                PrefixIterator::new(iter, String::default()),
+                String::default(),
             ) {
                 batch.delete_cf(cf, key);
             }
@@ -1181,7 +1357,7 @@ impl DB for RocksDB {
     }
 
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
-        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        let subspace_cf = self.get_subspace_column_family(key)?;
         self.0
             .get_cf(subspace_cf, key.to_string())
             .map_err(|e| Error::DBError(e.into_string()))
@@ -1287,7 +1463,7 @@ impl DB for RocksDB {
         key: &Key,
         value: impl AsRef<[u8]>,
     ) -> Result<i64> {
-        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        let subspace_cf = self.get_subspace_column_family(key)?;
         let value = value.as_ref();
         let size_diff = match self
             .0
@@ -1323,7 +1499,7 @@ impl DB for RocksDB {
         height: BlockHeight,
         key: &Key,
     ) -> Result<i64> {
-        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        let subspace_cf = self.get_subspace_column_family(key)?;
 
         // Check the length of previous value, if any
         let prev_len = match self
@@ -1363,7 +1539,7 @@ impl DB for RocksDB {
         value: impl AsRef<[u8]>,
     ) -> Result<i64> {
         let value = value.as_ref();
-        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        let subspace_cf = self.get_subspace_column_family(key)?;
         let size_diff = match self
             .0
             .get_cf(subspace_cf, key.to_string())
@@ -1405,7 +1581,7 @@ impl DB for RocksDB {
         height: BlockHeight,
         key: &Key,
     ) -> Result<i64> {
-        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        let subspace_cf = self.get_subspace_column_family(key)?;
 
         // Check the length of previous value, if any
         let prev_len = match self
@@ -1474,13 +1650,12 @@ impl DB for RocksDB {
         &mut self,
         batch: &mut Self::WriteBatch,
         key: &Key,
+        value: &[u8],
     ) -> Result<()> {
         let replay_protection_cf =
             self.get_column_family(REPLAY_PROTECTION_CF)?;
 
-        batch
-            .0
-            .put_cf(replay_protection_cf, key.to_string(), vec![]);
+        batch.0.put_cf(replay_protection_cf, key.to_string(), value);
 
         Ok(())
     }
@@ -1506,7 +1681,15 @@ impl<'iter> DBIter<'iter> for RocksDB {
         &'iter self,
         prefix: Option<&Key>,
     ) -> PersistentPrefixIterator<'iter> {
-        iter_subspace_prefix(self, prefix)
+        iter_subspace_prefix(self, prefix, None)
+    }
+
+    fn iter_prefix_from(
+        &'iter self,
+        prefix: &Key,
+        start: &Key,
+    ) -> PersistentPrefixIterator<'iter> {
+        iter_subspace_prefix(self, Some(prefix), Some(start))
     }
 
     fn iter_results(&'iter self) -> PersistentPrefixIterator<'iter> {
@@ -1522,7 +1705,7 @@ impl<'iter> DBIter<'iter> for RocksDB {
             read_opts,
             IteratorMode::From(prefix.as_bytes(), Direction::Forward),
         );
-        PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
+        PersistentPrefixIterator::new([iter], db_prefix)
     }
 
     fn iter_old_diffs(
@@ -1546,21 +1729,59 @@ impl<'iter> DBIter<'iter> for RocksDB {
             .get_column_family(REPLAY_PROTECTION_CF)
             .expect("{REPLAY_PROTECTION_CF} column family should exist");
 
-        iter_prefix(self, replay_protection_cf, "last".to_string(), None)
+        iter_prefix(
+            self,
+            [replay_protection_cf],
+            "last".to_string(),
+            None,
+            None,
+        )
+    }
+
+    fn iter_replay_protection_bucket(
+        &'iter self,
+        bucket: Epoch,
+    ) -> Self::PrefixIter {
+        let replay_protection_cf = self
+            .get_column_family(REPLAY_PROTECTION_CF)
+            .expect("{REPLAY_PROTECTION_CF} column family should exist");
+
+        iter_prefix(
+            self,
+            [replay_protection_cf],
+            "".to_string(),
+            Some(format!(
+                "{}/",
+                replay_protection::get_replay_protection_bucket_prefix(bucket)
+            )),
+            None,
+        )
     }
 }
 
 fn iter_subspace_prefix<'iter>(
     db: &'iter RocksDB,
     prefix: Option<&Key>,
+    start: Option<&Key>,
 ) -> PersistentPrefixIterator<'iter> {
-    let subspace_cf = db
-        .get_column_family(SUBSPACE_CF)
-        .expect("{SUBSPACE_CF} column family should exist");
+    // A prefix with an owner address is iterated in the column family of its
+    // sub-space only, otherwise all the sub-spaces are iterated over
+    let subspace_cfs: Vec<_> = match prefix {
+        Some(prefix) if prefix != &Key::default() => {
+            vec![subspace_cf_name(prefix)]
+        }
+        _ => subspace_cf_names().collect(),
+    }
+    .into_iter()
+    .map(|cf_name| {
+        db.get_column_family(cf_name)
+            .expect("Subspace column family should exist")
+    })
+    .collect();
     let db_prefix = "".to_owned();
     iter_prefix(
         db,
-        subspace_cf,
+        subspace_cfs,
         db_prefix,
         prefix.map(|k| {
             if k == &Key::default() {
@@ -1569,6 +1790,7 @@ fn iter_subspace_prefix<'iter>(
                 format!("{k}/")
             }
         }),
+        start.map(|k| k.to_string()),
     )
 }
 
@@ -1591,31 +1813,60 @@ fn iter_diffs_prefix<'a>(
         }
     });
     // get keys without a prefix
-    iter_prefix(db, diffs_cf, db_prefix, prefix)
+    iter_prefix(db, [diffs_cf], db_prefix, prefix, None)
 }
 
+/// Iterate the keys with the given prefix in the given column families,
+/// seeking to the `start` key if any, or else to the prefix
 fn iter_prefix<'a>(
     db: &'a RocksDB,
-    cf: &'a ColumnFamily,
+    cfs: impl IntoIterator<Item = &'a ColumnFamily>,
     db_prefix: String,
     prefix: Option<String>,
+    start: Option<String>,
 ) -> PersistentPrefixIterator<'a> {
-    let read_opts = make_iter_read_opts(prefix.clone());
-    let iter = db.0.iterator_cf_opt(
-        cf,
-        read_opts,
-        IteratorMode::From(
-            prefix.unwrap_or_default().as_bytes(),
-            Direction::Forward,
-        ),
-    );
-    PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
+    let iters = cfs.into_iter().map(|cf| {
+        let read_opts = make_iter_read_opts(prefix.clone());
+        let from = start.as_deref().or(prefix.as_deref()).unwrap_or_default();
+        db.0.iterator_cf_opt(
+            cf,
+            read_opts,
+            IteratorMode::From(from.as_bytes(), Direction::Forward),
+        )
+    });
+    PersistentPrefixIterator::new(iters, db_prefix)
 }
 
+/// A key-val read from a RocksDB iterator
+type DBIterItem = std::result::Result<types::KVBytes, rocksdb::Error>;
+
+/// RocksDB iterators over one or more column families, merged in the order of
+/// their keys
+type MergedDBIterator<'a> =
+    KMergeBy<rocksdb::DBIterator<'a>, fn(&DBIterItem, &DBIterItem) -> bool>;
+
 #[derive(Debug)]
-pub struct PersistentPrefixIterator<'a>(
-    PrefixIterator<rocksdb::DBIterator<'a>>,
-);
+pub struct PersistentPrefixIterator<'a>(PrefixIterator<MergedDBIterator<'a>>);
+
+impl<'a> PersistentPrefixIterator<'a> {
+    /// Iterate over the given RocksDB iterators in the order of their keys
+    fn new(
+        iters: impl IntoIterator<Item = rocksdb::DBIterator<'a>>,
+        db_prefix: String,
+    ) -> Self {
+        let key_order: fn(&DBIterItem, &DBIterItem) -> bool =
+            |a, b| match (a, b) {
+                (Ok((a, _)), Ok((b, _))) => a < b,
+                // Yield the errors first
+                (Err(_), _) => true,
+                (Ok(_), Err(_)) => false,
+            };
+        Self(PrefixIterator::new(
+            itertools::kmerge_by(iters, key_order),
+            db_prefix,
+        ))
+    }
+}
 
 impl<'a> Iterator for PersistentPrefixIterator<'a> {
     type Item = (String, Vec<u8>, u64);
@@ -1911,7 +2162,14 @@ mod test {
             .iter_prefix(Some(&prefix_1))
             .map(|(key, _val, _)| Key::parse(key).unwrap())
             .collect();
-        itertools::assert_equal(keys_1, itered_keys);
+        itertools::assert_equal(keys_1.clone(), itered_keys);
+
+        // The iteration seeks to the start key
+        let itered_keys: Vec<Key> = db
+            .iter_prefix_from(&prefix_1, &keys_1[1])
+            .map(|(key, _val, _)| Key::parse(key).unwrap())
+            .collect();
+        itertools::assert_equal(keys_1[1..].to_vec(), itered_keys);
 
         let itered_keys: Vec<Key> = db
             .iter_prefix(None)
@@ -1920,6 +2178,95 @@ mod test {
         itertools::assert_equal(all_keys, itered_keys);
     }
 
+    /// Test that the sub-spaces of the subsystems are kept in their own column
+    /// families, that they are iterated over in order along with the other
+    /// sub-spaces, and that they get split out of the `subspace` column family
+    /// of a DB written by a previous version
+    #[test]
+    fn test_subspace_column_families() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let pos_key = Key::from(address::POS.to_db_key())
+            .push(&"pos".to_string())
+            .unwrap();
+        let masp_prefix = Key::from(address::MASP.to_db_key());
+        let masp_key = masp_prefix.push(&"masp".to_string()).unwrap();
+        let other_key = Key::parse("other").unwrap();
+
+        let mut batch = RocksDB::batch();
+        for key in [&pos_key, &masp_key, &other_key] {
+            db.batch_write_subspace_val(
+                &mut batch,
+                BlockHeight(1),
+                key,
+                [0_u8],
+            )
+            .unwrap();
+        }
+        db.exec_batch(batch.0).unwrap();
+
+        let subspace_cf = db.get_column_family(SUBSPACE_CF).unwrap();
+        let pos_cf = db.get_column_family(POS_SUBSPACE_CF).unwrap();
+        assert!(db.0.get_cf(pos_cf, pos_key.to_string()).unwrap().is_some());
+        assert!(
+            db.0.get_cf(subspace_cf, pos_key.to_string())
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(db.read_subspace_val(&pos_key).unwrap(), Some(vec![0_u8]));
+
+        let mut all_keys = vec![
+            pos_key.to_string(),
+            masp_key.to_string(),
+            other_key.to_string(),
+        ];
+        all_keys.sort();
+        let itered_keys: Vec<String> =
+            db.iter_prefix(None).map(|(key, _val, _)| key).collect();
+        assert_eq!(itered_keys, all_keys);
+        let itered_keys: Vec<String> = db
+            .iter_prefix(Some(&masp_prefix))
+            .map(|(key, _val, _)| key)
+            .collect();
+        assert_eq!(itered_keys, vec![masp_key.to_string()]);
+
+        // A key written in the `subspace` column family by a previous version
+        // gets moved to its own column family when the DB is opened
+        let ibc_key = Key::from(address::IBC.to_db_key())
+            .push(&"ibc".to_string())
+            .unwrap();
+        db.0.put_cf(subspace_cf, ibc_key.to_string(), [1_u8])
+            .unwrap();
+        let state_cf = db.get_column_family(STATE_CF).unwrap();
+        db.0.delete_cf(state_cf, LAYOUT_VERSION_KEY).unwrap();
+        drop(db);
+        let db = open(dir.path(), None).unwrap();
+        let subspace_cf = db.get_column_family(SUBSPACE_CF).unwrap();
+        assert!(
+            db.0.get_cf(subspace_cf, ibc_key.to_string())
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(db.read_subspace_val(&ibc_key).unwrap(), Some(vec![1_u8]));
+
+        // Once the split is complete, the `subspace` column family is no
+        // longer scanned when the DB is opened
+        let other_ibc_key = Key::from(address::IBC.to_db_key())
+            .push(&"other".to_string())
+            .unwrap();
+        db.0.put_cf(subspace_cf, other_ibc_key.to_string(), [2_u8])
+            .unwrap();
+        drop(db);
+        let db = open(dir.path(), None).unwrap();
+        let subspace_cf = db.get_column_family(SUBSPACE_CF).unwrap();
+        assert!(
+            db.0.get_cf(subspace_cf, other_ibc_key.to_string())
+                .unwrap()
+                .is_some()
+        );
+    }
+
     #[test]
     fn test_rollback() {
         let dir = tempdir().unwrap();
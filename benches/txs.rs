@@ -38,7 +38,7 @@ use namada::types::transaction::governance::{
     InitProposalData, VoteProposalData,
 };
 use namada::types::transaction::pos::{
-    Bond, CommissionChange, ConsensusKeyChange, Redelegation, Withdraw,
+    Bond, CommissionChange, ConsensusKeyChange, Redelegation, Unbond, Withdraw,
 };
 use namada_apps::bench_utils::{
     BenchShell, BenchShieldedCtx, ALBERT_PAYMENT_ADDRESS, ALBERT_SPENDING_KEY,
@@ -182,10 +182,11 @@ fn unbond(c: &mut Criterion) {
     let shell = BenchShell::default();
     let unbond = shell.generate_tx(
         TX_UNBOND_WASM,
-        Bond {
+        Unbond {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(defaults::albert_address()),
+            auto_withdraw: false,
         },
         None,
         None,
@@ -194,10 +195,11 @@ fn unbond(c: &mut Criterion) {
 
     let self_unbond = shell.generate_tx(
         TX_UNBOND_WASM,
-        Bond {
+        Unbond {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: None,
+            auto_withdraw: false,
         },
         None,
         None,
@@ -258,10 +260,11 @@ fn withdraw(c: &mut Criterion) {
                     let unbond_tx = match bench_name {
                         "withdraw" => shell.generate_tx(
                             TX_UNBOND_WASM,
-                            Bond {
+                            Unbond {
                                 validator: defaults::validator_address(),
                                 amount: Amount::native_whole(1000),
                                 source: Some(defaults::albert_address()),
+                                auto_withdraw: false,
                             },
                             None,
                             None,
@@ -269,10 +272,11 @@ fn withdraw(c: &mut Criterion) {
                         ),
                         "self_withdraw" => shell.generate_tx(
                             TX_UNBOND_WASM,
-                            Bond {
+                            Unbond {
                                 validator: defaults::validator_address(),
                                 amount: Amount::native_whole(1000),
                                 source: None,
+                                auto_withdraw: false,
                             },
                             None,
                             None,
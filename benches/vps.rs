@@ -16,7 +16,7 @@ use namada::types::hash::Hash;
 use namada::types::key::ed25519;
 use namada::types::storage::{Key, TxIndex};
 use namada::types::transaction::governance::VoteProposalData;
-use namada::types::transaction::pos::{Bond, CommissionChange};
+use namada::types::transaction::pos::{Bond, CommissionChange, Unbond};
 use namada::vm::wasm::run;
 use namada_apps::bench_utils::{
     generate_foreign_key_tx, BenchShell, TX_BOND_WASM,
@@ -110,10 +110,11 @@ fn vp_user(c: &mut Criterion) {
 
     let pos = shell.generate_tx(
         TX_UNBOND_WASM,
-        Bond {
+        Unbond {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(defaults::albert_address()),
+            auto_withdraw: false,
         },
         None,
         None,
@@ -408,10 +409,11 @@ fn vp_validator(c: &mut Criterion) {
 
     let pos = shell.generate_tx(
         TX_UNBOND_WASM,
-        Bond {
+        Unbond {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: None,
+            auto_withdraw: false,
         },
         None,
         None,
@@ -32,6 +32,7 @@ pub const BRIDGE_POOL_ADDRESS: Address =
 struct Segments {
     signed_root: &'static str,
     bridge_pool_nonce: &'static str,
+    transfer_outcome: &'static str,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -76,6 +77,18 @@ pub fn get_nonce_key() -> Key {
     }
 }
 
+/// Get the storage key for the outcome of a transfer that was
+/// removed from the pool, using its hash
+pub fn get_transfer_outcome_key(hash: &KeccakHash) -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(BRIDGE_POOL_ADDRESS),
+            DbKeySeg::StringSeg(Segments::VALUES.transfer_outcome.into()),
+            hash.to_db_key(),
+        ],
+    }
+}
+
 /// Check if a key belongs to the bridge pools sub-storage
 pub fn is_bridge_pool_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &BRIDGE_POOL_ADDRESS)
@@ -63,6 +63,11 @@ pub fn bridge_contract_key() -> Key {
     get_bridge_contract_address_key_at_addr(PARAM_ADDRESS)
 }
 
+/// Storage key for the quorum of vote extension digests parameter.
+pub fn vext_digest_quorum_key() -> Key {
+    get_vext_digest_quorum_key_at_addr(PARAM_ADDRESS)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
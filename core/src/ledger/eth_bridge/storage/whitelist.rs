@@ -3,14 +3,24 @@
 //! These storage keys should only ever be written to by governance,
 //! or `InitChain`.
 
+use std::collections::BTreeMap;
+use std::num::NonZeroU64;
 use std::str::FromStr;
 
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
 use super::super::ADDRESS as BRIDGE_ADDRESS;
 use super::{prefix as ethbridge_key_prefix, wrapped_erc20s};
+use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
 use crate::types::ethereum_events::EthAddress;
 use crate::types::storage;
 use crate::types::storage::DbKeySeg;
-use crate::types::token::{denom_key, minted_balance_key};
+use crate::types::token::{self, denom_key, minted_balance_key};
+
+/// The key of the content of a governance proposal under which the
+/// proposal carries the [`WhitelistUpdate`]s to apply, encoded as JSON.
+pub const PROPOSAL_CONTENT_KEY: &str = "erc20_whitelist";
 
 mod segments {
     //! Storage key segments under the token whitelist.
@@ -29,6 +39,9 @@ mod segments {
         pub whitelisted: &'static str,
         /// The token cap of an ERC20 asset.
         pub cap: &'static str,
+        /// The minimum number of confirmations of the Ethereum events
+        /// transferring an ERC20 asset.
+        pub min_confirmations: &'static str,
     }
 
     /// All the values of the generated [`Segments`].
@@ -50,6 +63,9 @@ pub enum KeyType {
     WrappedSupply,
     /// The denomination of the ERC20 asset.
     Denomination,
+    /// The minimum number of confirmations an Ethereum event transferring
+    /// the ERC20 asset must reach before it can be acted on.
+    MinConfirmations,
 }
 
 /// Whitelisted ERC20 token storage sub-space.
@@ -60,11 +76,16 @@ pub struct Key {
     pub suffix: KeyType,
 }
 
-/// Return the whitelist storage key sub-space prefix.
-fn whitelist_prefix(asset: &EthAddress) -> storage::Key {
+/// Return the storage key prefix of the whole ERC20 token whitelist.
+pub fn prefix() -> storage::Key {
     ethbridge_key_prefix()
         .push(&segments::MAIN_SEGMENT.to_owned())
         .expect("Should be able to push a storage key segment")
+}
+
+/// Return the whitelist storage key sub-space prefix.
+fn whitelist_prefix(asset: &EthAddress) -> storage::Key {
+    prefix()
         .push(&asset.to_canonical())
         .expect("Should be able to push a storage key segment")
 }
@@ -85,6 +106,9 @@ impl From<&Key> for storage::Key {
             KeyType::Cap => whitelist_prefix(&key.asset)
                 .push(&segments::VALUES.cap.to_owned())
                 .expect("Should be able to push a storage key segment"),
+            KeyType::MinConfirmations => whitelist_prefix(&key.asset)
+                .push(&segments::VALUES.min_confirmations.to_owned())
+                .expect("Should be able to push a storage key segment"),
             KeyType::WrappedSupply => {
                 let token = wrapped_erc20s::token(&key.asset);
                 minted_balance_key(&token)
@@ -98,7 +122,8 @@ impl From<&Key> for storage::Key {
 }
 
 /// Check if some [`storage::Key`] is an Ethereum bridge whitelist key
-/// of type [`KeyType::Cap`] or [`KeyType::Whitelisted`].
+/// of type [`KeyType::Cap`], [`KeyType::Whitelisted`] or
+/// [`KeyType::MinConfirmations`].
 pub fn is_cap_or_whitelisted_key(key: &storage::Key) -> bool {
     match &key.segments[..] {
         [
@@ -116,9 +141,155 @@ pub fn is_cap_or_whitelisted_key(key: &storage::Key) -> bool {
     }
 }
 
+/// If some [`storage::Key`] is an Ethereum bridge whitelist key of type
+/// [`KeyType::Cap`], [`KeyType::Whitelisted`] or
+/// [`KeyType::MinConfirmations`], return the ERC20 asset it refers to.
+pub fn key_asset(key: &storage::Key) -> Option<EthAddress> {
+    if !is_cap_or_whitelisted_key(key) {
+        return None;
+    }
+    match &key.segments[2] {
+        DbKeySeg::StringSeg(asset) => EthAddress::from_str(asset).ok(),
+        DbKeySeg::AddressSeg(_) => None,
+    }
+}
+
+/// If some [`storage::Key`] is an Ethereum bridge whitelist key of type
+/// [`KeyType::MinConfirmations`], return the ERC20 asset it refers to.
+pub fn min_confirmations_key_asset(key: &storage::Key) -> Option<EthAddress> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(s1),
+            DbKeySeg::StringSeg(s2),
+            DbKeySeg::StringSeg(s3),
+            DbKeySeg::StringSeg(s4),
+        ] if s1 == &BRIDGE_ADDRESS
+            && s2 == segments::MAIN_SEGMENT
+            && s4 == segments::VALUES.min_confirmations =>
+        {
+            EthAddress::from_str(s3).ok()
+        }
+        _ => None,
+    }
+}
+
+/// An update of the ERC20 token whitelist, applied by a governance proposal.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum WhitelistUpdate {
+    /// Whitelist an ERC20 asset, or update the cap of an already
+    /// whitelisted one.
+    Add {
+        /// The address of the ERC20 asset.
+        token: EthAddress,
+        /// The token cap of the wrapped asset.
+        cap: token::Amount,
+        /// The denomination of the ERC20 asset.
+        denom: token::Denomination,
+        /// The minimum number of confirmations of the Ethereum events
+        /// transferring the asset, if different from the bridge's default.
+        #[serde(default)]
+        min_confirmations: Option<NonZeroU64>,
+    },
+    /// Remove an ERC20 asset from the whitelist. Its wrapped supply is
+    /// kept, but any further transfer of the asset to Namada mints NUTs.
+    Remove {
+        /// The address of the ERC20 asset.
+        token: EthAddress,
+    },
+}
+
+/// Parse the [`WhitelistUpdate`]s carried by the content of a governance
+/// proposal, if any.
+pub fn updates_from_proposal_content(
+    content: &BTreeMap<String, String>,
+) -> Option<Vec<WhitelistUpdate>> {
+    let updates = content.get(PROPOSAL_CONTENT_KEY)?;
+    serde_json::from_str(updates).ok()
+}
+
+/// Apply some [`WhitelistUpdate`]s to storage.
+///
+/// The denomination of an ERC20 asset can't be changed once set, since
+/// its wrapped supply is expressed in it.
+pub fn apply_updates<S>(
+    storage: &mut S,
+    updates: &[WhitelistUpdate],
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    for update in updates {
+        match update {
+            WhitelistUpdate::Add {
+                token,
+                cap,
+                denom,
+                min_confirmations,
+            } => {
+                let denom_key: storage::Key = Key {
+                    asset: *token,
+                    suffix: KeyType::Denomination,
+                }
+                .into();
+                match storage.read::<token::Denomination>(&denom_key)? {
+                    Some(current) if &current != denom => {
+                        return Err(storage_api::Error::new(format!(
+                            "Cannot change the denomination of the ERC20 \
+                             asset {token} from {} to {}",
+                            current.0, denom.0
+                        )));
+                    }
+                    Some(_) => {}
+                    None => storage.write(&denom_key, denom)?,
+                }
+                let key = Key {
+                    asset: *token,
+                    suffix: KeyType::Whitelisted,
+                };
+                storage.write(&key.into(), true)?;
+                let key = Key {
+                    asset: *token,
+                    suffix: KeyType::Cap,
+                };
+                storage.write(&key.into(), cap)?;
+                let key: storage::Key = Key {
+                    asset: *token,
+                    suffix: KeyType::MinConfirmations,
+                }
+                .into();
+                match min_confirmations {
+                    Some(min_confirmations) => {
+                        storage.write(&key, min_confirmations)?
+                    }
+                    None => storage.delete(&key)?,
+                }
+            }
+            WhitelistUpdate::Remove { token } => {
+                let key = Key {
+                    asset: *token,
+                    suffix: KeyType::Whitelisted,
+                };
+                storage.write(&key.into(), false)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
     use crate::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
 
     /// Test that storage key serialization yields the expected value.
@@ -163,4 +334,79 @@ mod tests {
         };
         assert!(!is_cap_or_whitelisted_key(&unexpected_key));
     }
+
+    /// Test that the ERC20 asset of a minimum confirmations key is
+    /// correctly parsed.
+    #[test]
+    fn test_min_confirmations_key_asset() {
+        let key: storage::Key = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::MinConfirmations,
+        }
+        .into();
+        assert_eq!(
+            min_confirmations_key_asset(&key),
+            Some(DAI_ERC20_ETH_ADDRESS)
+        );
+
+        let cap_key: storage::Key = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::Cap,
+        }
+        .into();
+        assert_eq!(min_confirmations_key_asset(&cap_key), None);
+    }
+
+    /// Test that the whitelist updates carried by a proposal content are
+    /// applied to storage.
+    #[test]
+    fn test_apply_proposal_updates() {
+        let mut storage = TestWlStorage::default();
+        let updates = vec![WhitelistUpdate::Add {
+            token: DAI_ERC20_ETH_ADDRESS,
+            cap: token::Amount::from(1_000),
+            denom: 18.into(),
+            min_confirmations: NonZeroU64::new(50),
+        }];
+        let content = BTreeMap::from([(
+            PROPOSAL_CONTENT_KEY.to_string(),
+            serde_json::to_string(&updates).unwrap(),
+        )]);
+        let parsed = updates_from_proposal_content(&content).unwrap();
+        assert_eq!(parsed, updates);
+        apply_updates(&mut storage, &parsed).unwrap();
+
+        let whitelisted = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::Whitelisted,
+        };
+        assert_eq!(storage.read(&whitelisted.into()).unwrap(), Some(true));
+        let cap = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::Cap,
+        };
+        assert_eq!(
+            storage.read(&cap.into()).unwrap(),
+            Some(token::Amount::from(1_000))
+        );
+
+        // the denomination can't be changed
+        let change_denom = [WhitelistUpdate::Add {
+            token: DAI_ERC20_ETH_ADDRESS,
+            cap: token::Amount::from(1_000),
+            denom: 6.into(),
+            min_confirmations: None,
+        }];
+        assert!(apply_updates(&mut storage, &change_denom).is_err());
+
+        let remove = [WhitelistUpdate::Remove {
+            token: DAI_ERC20_ETH_ADDRESS,
+        }];
+        apply_updates(&mut storage, &remove).unwrap();
+        let whitelisted = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::Whitelisted,
+        };
+        assert_eq!(storage.read(&whitelisted.into()).unwrap(), Some(false));
+    }
 }
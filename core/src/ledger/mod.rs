@@ -12,4 +12,5 @@ pub mod replay_protection;
 pub mod storage;
 pub mod storage_api;
 pub mod tx_env;
+pub mod vesting;
 pub mod vp_env;
@@ -14,6 +14,7 @@ use crate::types::address::{Address, InternalAddress};
 use crate::types::chain::ProposalBytes;
 use crate::types::dec::Dec;
 use crate::types::hash::Hash;
+use crate::types::storage::BlockHeight;
 use crate::types::time::DurationSecs;
 use crate::types::token;
 
@@ -410,7 +411,9 @@ where
         .into_storage_result()
 }
 
-/// Read the cost per unit of gas for the provided token
+/// Read the cost per unit of gas for the provided token. Tokens missing from
+/// the gas cost table are accepted if governance whitelisted them with a
+/// conversion rate to the native token.
 pub fn read_gas_cost<S>(
     storage: &S,
     token: &Address,
@@ -422,7 +425,128 @@ where
         .read(&storage::get_gas_cost_key())?
         .ok_or(ReadError::ParametersMissing)
         .into_storage_result()?;
-    Ok(gas_cost_table.get(token).map(|amount| amount.to_owned()))
+    if let Some(amount) = gas_cost_table.get(token) {
+        return Ok(Some(amount.to_owned()));
+    }
+    let conversion_rates = read_fee_token_conversion_rates(storage)?;
+    let native_token = storage.get_native_token()?;
+    let mut gas_costs =
+        fee_gas_costs(gas_cost_table, &conversion_rates, &native_token);
+    Ok(gas_costs.remove(token))
+}
+
+/// Read the conversion rates of the tokens whitelisted by governance for fee
+/// payment, expressed as the amount of the token (in its base units) charged
+/// per base unit of the native token. Empty if no rate was ever set.
+pub fn read_fee_token_conversion_rates<S>(
+    storage: &S,
+) -> storage_api::Result<BTreeMap<Address, Dec>>
+where
+    S: StorageRead,
+{
+    let key = storage::get_fee_token_conversion_rates_key();
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Read the height at which the chain halts for an upgrade, if governance has
+/// agreed on one. The nodes stop before finalizing the block at that height,
+/// which is left to the upgraded binary.
+pub fn read_halt_at_height<S>(
+    storage: &S,
+) -> storage_api::Result<Option<BlockHeight>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_halt_at_height_key())
+}
+
+/// Update the height at which the chain halts for an upgrade
+pub fn update_halt_at_height<S>(
+    storage: &mut S,
+    height: BlockHeight,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&storage::get_halt_at_height_key(), height)
+}
+
+/// Read the version of the upgraded binary agreed by governance, which is the
+/// only version allowed to finalize the block at the halt height
+pub fn read_upgrade_version<S>(
+    storage: &S,
+) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_upgrade_version_key())
+}
+
+/// Update the version of the upgraded binary
+pub fn update_upgrade_version<S>(
+    storage: &mut S,
+    version: impl Into<String>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&storage::get_upgrade_version_key(), version.into())
+}
+
+/// Read the version of the state schema. The state of a chain that was
+/// initialized before the state versioning was introduced has no version.
+pub fn read_state_version<S>(storage: &S) -> storage_api::Result<Option<u64>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_state_version_key())
+}
+
+/// Update the version of the state schema
+pub fn update_state_version<S>(
+    storage: &mut S,
+    version: u64,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&storage::get_state_version_key(), version)
+}
+
+/// Update the conversion rates of the tokens whitelisted for fee payment
+pub fn update_fee_token_conversion_rates<S>(
+    storage: &mut S,
+    conversion_rates: &BTreeMap<Address, Dec>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_fee_token_conversion_rates_key();
+    storage.write(&key, conversion_rates)
+}
+
+/// Compute the cost per unit of gas of every token accepted for fee payment.
+/// The tokens of the gas cost table keep their own cost, while the tokens
+/// with a positive conversion rate are charged the native token's gas cost
+/// converted at that rate (rounded up).
+pub fn fee_gas_costs(
+    mut gas_cost_table: BTreeMap<Address, Amount>,
+    conversion_rates: &BTreeMap<Address, Dec>,
+    native_token: &Address,
+) -> BTreeMap<Address, Amount> {
+    let native_gas_cost = match gas_cost_table.get(native_token) {
+        Some(amount) => amount.to_owned(),
+        None => return gas_cost_table,
+    };
+    for (token, rate) in conversion_rates {
+        if rate.is_negative() || rate.is_zero() {
+            continue;
+        }
+        gas_cost_table
+            .entry(token.to_owned())
+            .or_insert_with(|| native_gas_cost.mul_ceil(*rate));
+    }
+    gas_cost_table
 }
 
 /// Read all the parameters from storage. Returns the parameters and gas
@@ -576,3 +700,35 @@ where
         fee_unshielding_descriptions_limit,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::types::address::{btc, eth, nam};
+
+    /// Test that whitelisted tokens are charged the native gas cost converted
+    /// at their rate, without overriding the gas cost table
+    #[test]
+    fn test_fee_gas_costs() {
+        let gas_cost_table = BTreeMap::from([
+            (nam(), Amount::from(100)),
+            (eth(), Amount::from(7)),
+        ]);
+        let conversion_rates = BTreeMap::from([
+            (btc(), Dec::from_str("0.025").unwrap()),
+            (eth(), Dec::from_str("2").unwrap()),
+        ]);
+        let gas_costs =
+            fee_gas_costs(gas_cost_table.clone(), &conversion_rates, &nam());
+        assert_eq!(gas_costs.get(&nam()), Some(&Amount::from(100)));
+        assert_eq!(gas_costs.get(&eth()), Some(&Amount::from(7)));
+        assert_eq!(gas_costs.get(&btc()), Some(&Amount::from(3)));
+
+        // Without a native gas cost no rate can be applied
+        let gas_costs =
+            fee_gas_costs(gas_cost_table, &conversion_rates, &btc());
+        assert_eq!(gas_costs.get(&btc()), None);
+    }
+}
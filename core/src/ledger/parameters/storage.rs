@@ -23,6 +23,8 @@ struct Keys {
     native_erc20: &'static str,
     /// Sub-lkey for storing the Ethereum address of the bridge contract.
     bridge_contract_address: &'static str,
+    /// Sub-key for storing the quorum of vote extension digests.
+    vext_digest_quorum: &'static str,
     // ========================================
     // PoS parameters
     // ========================================
@@ -43,9 +45,19 @@ struct Keys {
     max_tx_bytes: &'static str,
     max_block_gas: &'static str,
     minimum_gas_price: &'static str,
+    fee_token_conversion_rates: &'static str,
     fee_unshielding_gas_limit: &'static str,
     fee_unshielding_descriptions_limit: &'static str,
     max_signatures_per_transaction: &'static str,
+    /// Sub-key for storing the height at which the chain halts for an
+    /// upgrade.
+    halt_at_height: &'static str,
+    /// Sub-key for storing the version of the binary that finalizes the block
+    /// at the halt height.
+    upgrade_version: &'static str,
+    /// Sub-key for storing the version of the state schema, which is bumped
+    /// by the state migrations.
+    state_version: &'static str,
 }
 
 /// Returns if the key is a parameter key.
@@ -206,7 +218,29 @@ pub fn get_gas_cost_key() -> Key {
     get_minimum_gas_price_key_at_addr(ADDRESS)
 }
 
+/// Storage key used for the conversion rates of the tokens whitelisted for
+/// fee payment
+pub fn get_fee_token_conversion_rates_key() -> Key {
+    get_fee_token_conversion_rates_key_at_addr(ADDRESS)
+}
+
 /// Storage key used for the max signatures per transaction key
 pub fn get_max_signatures_per_transaction_key() -> Key {
     get_max_signatures_per_transaction_key_at_addr(ADDRESS)
 }
+
+/// Storage key used for the height at which the chain halts for an upgrade
+pub fn get_halt_at_height_key() -> Key {
+    get_halt_at_height_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the version of the binary that finalizes the block at
+/// the halt height
+pub fn get_upgrade_version_key() -> Key {
+    get_upgrade_version_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the version of the state schema
+pub fn get_state_version_key() -> Key {
+    get_state_version_key_at_addr(ADDRESS)
+}
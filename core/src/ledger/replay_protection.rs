@@ -1,10 +1,92 @@
 //! Replay protection storage
+//!
+//! The hashes of the txs included in the last block are kept under the `last`
+//! subkey, with the expiration time of their tx as value, if any. At the
+//! beginning of the following block, they are moved under the `all` subkey.
+//! The hashes of the txs that have an expiration time are also indexed in the
+//! bucket of the first epoch that's guaranteed to start after the txs have
+//! expired, under the `expiring` subkey, so that they can be pruned all at
+//! once at the beginning of that epoch. The hashes of the txs that never
+//! expire are kept forever.
+//!
+//! The entries written before the introduction of the buckets are
+//! intentionally not migrated. Their expiration time was never stored, the tx
+//! bodies aren't kept either, and a tx may have no expiration at all, so
+//! there's no way to tell when replaying one of these txs would become
+//! harmless: indexing them in any bucket could let them be pruned and
+//! replayed. They are therefore handled as the hashes of txs that never
+//! expire: an empty value under the `last` subkey is read as a tx that never
+//! expires and the hashes under the `all` subkey that aren't indexed in any
+//! bucket are never pruned.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
 
 use crate::types::hash::Hash;
-use crate::types::storage::Key;
+use crate::types::storage::{Epoch, Key};
+use crate::types::time::{DateTimeUtc, DurationSecs};
 
 const ERROR_MSG: &str = "Cannot obtain a valid db key";
 
+/// The expiration of a finalized tx hash
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct TxHashExpiration {
+    /// The expiration time of the tx
+    pub time: DateTimeUtc,
+    /// The epoch of the bucket holding the tx hash
+    pub bucket: Epoch,
+}
+
+impl TxHashExpiration {
+    /// Find the bucket of a tx hash whose tx expires at the given time, i.e.
+    /// the first epoch that's guaranteed to start after the expiration time,
+    /// given the current epoch, the minimum start time of the next epoch and
+    /// the minimum duration of an epoch.
+    ///
+    /// The bucket is only a lower bound if the minimum duration of an epoch
+    /// gets shortened later on, so the tx hashes that haven't expired yet
+    /// when their bucket is pruned must be moved to a later bucket.
+    pub fn new(
+        time: DateTimeUtc,
+        current_epoch: Epoch,
+        next_epoch_min_start_time: DateTimeUtc,
+        min_duration: DurationSecs,
+    ) -> Self {
+        let bucket = if time < next_epoch_min_start_time || min_duration.0 == 0
+        {
+            current_epoch.next()
+        } else {
+            // Every epoch after the next one starts at least the minimum
+            // duration after its predecessor
+            let secs = (time.0 - next_epoch_min_start_time.0).num_seconds();
+            current_epoch + 2_u64 + secs as u64 / min_duration.0
+        };
+        Self { time, bucket }
+    }
+}
+
+/// Encode the expiration time of a tx, to be written as the value of its hash
+/// under the `last` subkey
+pub fn encode_last_entry(expiration: Option<DateTimeUtc>) -> Vec<u8> {
+    expiration
+        .map(|time| time.serialize_to_vec())
+        .unwrap_or_default()
+}
+
+/// Decode the expiration time of a tx from the value of its hash under the
+/// `last` subkey. An empty value is a tx that never expires.
+pub fn decode_last_entry(value: &[u8]) -> Option<DateTimeUtc> {
+    if value.is_empty() {
+        return None;
+    }
+    Some(
+        DateTimeUtc::try_from_slice(value)
+            .expect("Failed tx expiration decoding"),
+    )
+}
+
 /// Get the transaction hash key under the `last` subkey
 pub fn get_replay_protection_last_subkey(hash: &Hash) -> Key {
     Key::parse("last")
@@ -21,6 +103,23 @@ pub fn get_replay_protection_all_subkey(hash: &Hash) -> Key {
         .expect(ERROR_MSG)
 }
 
+/// Get the prefix of the bucket of the given epoch under the `expiring`
+/// subkey
+pub fn get_replay_protection_bucket_prefix(bucket: Epoch) -> Key {
+    Key::parse("expiring")
+        .expect(ERROR_MSG)
+        .push(&bucket)
+        .expect(ERROR_MSG)
+}
+
+/// Get the transaction hash key in the bucket of the given epoch under the
+/// `expiring` subkey
+pub fn get_replay_protection_bucket_subkey(bucket: Epoch, hash: &Hash) -> Key {
+    get_replay_protection_bucket_prefix(bucket)
+        .push(&hash.to_string())
+        .expect(ERROR_MSG)
+}
+
 /// Get the full transaction hash key under the `last` subkey
 pub fn get_replay_protection_last_key(hash: &Hash) -> Key {
     Key::parse("replay_protection")
@@ -17,6 +17,7 @@ use super::{
     BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error, Result, DB,
 };
 use crate::ledger::masp_conversions::ConversionState;
+use crate::ledger::replay_protection;
 use crate::ledger::storage::types::{self, KVBytes, PrefixIterator};
 use crate::types::ethereum_events::Uint;
 use crate::types::ethereum_structs;
@@ -601,12 +602,13 @@ impl DB for MockDB {
         &mut self,
         _batch: &mut Self::WriteBatch,
         key: &Key,
+        value: &[u8],
     ) -> Result<()> {
         let key = Key::parse("replay_protection")
             .map_err(Error::KeyError)?
             .join(key);
 
-        match self.0.borrow_mut().insert(key.to_string(), vec![]) {
+        match self.0.borrow_mut().insert(key.to_string(), value.to_vec()) {
             Some(_) => Err(Error::DBError(format!(
                 "Replay protection key {key} already in storage"
             ))),
@@ -706,7 +708,27 @@ impl<'iter> DBIter<'iter> for MockDB {
         let iter = self.0.borrow().clone().into_iter();
         MockPrefixIterator::new(
             MockIterator {
-                prefix: "last".to_string(),
+                prefix: format!("{db_prefix}last/"),
+                iter,
+            },
+            db_prefix,
+        )
+    }
+
+    fn iter_replay_protection_bucket(
+        &'iter self,
+        bucket: Epoch,
+    ) -> Self::PrefixIter {
+        let db_prefix = "replay_protection/".to_owned();
+        let iter = self.0.borrow().clone().into_iter();
+        MockPrefixIterator::new(
+            MockIterator {
+                prefix: format!(
+                    "{db_prefix}{}/",
+                    replay_protection::get_replay_protection_bucket_prefix(
+                        bucket
+                    )
+                ),
                 iter,
             },
             db_prefix,
@@ -33,6 +33,7 @@ pub use crate::ledger::masp_conversions::{
     calculate_masp_rewards, encode_asset_type, ConversionState,
 };
 use crate::ledger::parameters::{self, EpochDuration, Parameters};
+use crate::ledger::replay_protection;
 use crate::ledger::storage::merkle_tree::{
     Error as MerkleTreeError, MerkleRoot,
 };
@@ -370,6 +371,7 @@ pub trait DB: std::fmt::Debug {
         &mut self,
         batch: &mut Self::WriteBatch,
         key: &Key,
+        value: &[u8],
     ) -> Result<()>;
 
     /// Delete a replay protection entry
@@ -393,6 +395,18 @@ pub trait DBIter<'iter> {
     /// ordered by the storage keys.
     fn iter_prefix(&'iter self, prefix: Option<&Key>) -> Self::PrefixIter;
 
+    /// Read account subspace key value pairs with the given prefix from the DB,
+    /// ordered by the storage keys, seeking to the given `start` key. A DB that
+    /// cannot seek may also return the keys ordered before `start`, which
+    /// must then be skipped.
+    fn iter_prefix_from(
+        &'iter self,
+        prefix: &Key,
+        _start: &Key,
+    ) -> Self::PrefixIter {
+        self.iter_prefix(Some(prefix))
+    }
+
     /// Read results subspace key value pairs from the DB
     fn iter_results(&'iter self) -> Self::PrefixIter;
 
@@ -412,6 +426,12 @@ pub trait DBIter<'iter> {
 
     /// Read replay protection storage from the last block
     fn iter_replay_protection(&'iter self) -> Self::PrefixIter;
+
+    /// Read the bucket of the replay protection storage of the given epoch
+    fn iter_replay_protection_bucket(
+        &'iter self,
+        bucket: Epoch,
+    ) -> Self::PrefixIter;
 }
 
 /// Atomic batch write.
@@ -1267,8 +1287,9 @@ where
         &mut self,
         batch: &mut D::WriteBatch,
         key: &Key,
+        value: &[u8],
     ) -> Result<()> {
-        self.db.write_replay_protection_entry(batch, key)
+        self.db.write_replay_protection_entry(batch, key, value)
     }
 
     /// Delete the provided tx hash from storage
@@ -1280,18 +1301,44 @@ where
         self.db.delete_replay_protection_entry(batch, key)
     }
 
-    /// Iterate the replay protection storage from the last block
+    /// Iterate the replay protection storage from the last block, along with
+    /// the expiration time of the txs, if any
     pub fn iter_replay_protection(
         &self,
-    ) -> Box<dyn Iterator<Item = Hash> + '_> {
-        Box::new(self.db.iter_replay_protection().map(|(key, _, _)| {
-            key.rsplit_once('/')
-                .expect("Missing tx hash in storage key")
-                .1
-                .parse()
-                .expect("Failed hash conversion")
+    ) -> Box<dyn Iterator<Item = (Hash, Option<DateTimeUtc>)> + '_> {
+        Box::new(self.db.iter_replay_protection().map(|(key, value, _)| {
+            (
+                parse_replay_protection_hash(&key),
+                replay_protection::decode_last_entry(&value),
+            )
         }))
     }
+
+    /// Iterate the bucket of the replay protection storage of the given
+    /// epoch, along with the expiration time of the txs
+    pub fn iter_replay_protection_bucket(
+        &self,
+        bucket: Epoch,
+    ) -> Box<dyn Iterator<Item = (Hash, DateTimeUtc)> + '_> {
+        Box::new(self.db.iter_replay_protection_bucket(bucket).map(
+            |(key, value, _)| {
+                (
+                    parse_replay_protection_hash(&key),
+                    DateTimeUtc::try_from_slice(&value)
+                        .expect("Failed tx expiration decoding"),
+                )
+            },
+        ))
+    }
+}
+
+/// Parse the tx hash at the end of a replay protection storage key
+fn parse_replay_protection_hash(key: &str) -> Hash {
+    key.rsplit_once('/')
+        .expect("Missing tx hash in storage key")
+        .1
+        .parse()
+        .expect("Failed hash conversion")
 }
 
 impl From<MerkleTreeError> for Error {
@@ -4,6 +4,7 @@ use std::iter::Peekable;
 
 use super::EPOCH_SWITCH_BLOCKS_DELAY;
 use crate::ledger::parameters::EpochDuration;
+use crate::ledger::replay_protection::TxHashExpiration;
 use crate::ledger::storage::write_log::{self, WriteLog};
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, DB};
 use crate::ledger::storage_api::{ResultExt, StorageRead, StorageWrite};
@@ -101,10 +102,12 @@ pub trait WriteLogAndStorage {
     /// borrow checker)
     fn split_borrow(&mut self) -> (&mut WriteLog, &Storage<Self::D, Self::H>);
 
-    /// Write the provided tx hash to storage.
+    /// Write the provided tx hash to storage, along with the expiration time
+    /// of the tx, if any.
     fn write_tx_hash(
         &mut self,
         hash: Hash,
+        expiration: Option<DateTimeUtc>,
     ) -> crate::ledger::storage::write_log::Result<()>;
 }
 
@@ -135,8 +138,9 @@ where
     fn write_tx_hash(
         &mut self,
         hash: Hash,
+        expiration: Option<DateTimeUtc>,
     ) -> crate::ledger::storage::write_log::Result<()> {
-        self.write_log.write_tx_hash(hash)
+        self.write_log.write_tx_hash(hash, expiration)
     }
 }
 
@@ -167,8 +171,9 @@ where
     fn write_tx_hash(
         &mut self,
         hash: Hash,
+        expiration: Option<DateTimeUtc>,
     ) -> crate::ledger::storage::write_log::Result<()> {
-        self.write_log.write_tx_hash(hash)
+        self.write_log.write_tx_hash(hash, expiration)
     }
 }
 
@@ -271,6 +276,62 @@ where
     ) -> crate::ledger::storage::write_log::Result<()> {
         self.write_log.delete_tx_hash(hash)
     }
+
+    /// Finalize the transactions' hashes from the previous block and, at the
+    /// beginning of a new epoch, prune the bucket of the epoch from the hashes
+    /// of the transactions that have expired by the given block time. This
+    /// must be called at the beginning of the block processing, after the
+    /// epoch has been updated and before any other replay protection
+    /// operation is done.
+    pub fn finalize_replay_protection(
+        &mut self,
+        new_epoch: bool,
+        block_time: DateTimeUtc,
+    ) -> crate::ledger::storage::write_log::Result<()> {
+        let EpochDuration { min_duration, .. } =
+            parameters::read_epoch_duration_parameter(self)
+                .expect("Couldn't read epoch duration parameter");
+        let current_epoch = self.storage.block.epoch;
+        let next_epoch_min_start_time = self.storage.next_epoch_min_start_time;
+        let expiration = |time| {
+            TxHashExpiration::new(
+                time,
+                current_epoch,
+                next_epoch_min_start_time,
+                min_duration,
+            )
+        };
+
+        for (hash, time) in self.storage.iter_replay_protection() {
+            self.write_log
+                .finalize_tx_hash(hash, time.map(expiration))?;
+        }
+
+        if new_epoch {
+            let mut pruned = 0_u64;
+            for (hash, time) in
+                self.storage.iter_replay_protection_bucket(current_epoch)
+            {
+                if time < block_time {
+                    self.write_log.prune_tx_hash(hash, current_epoch)?;
+                    pruned += 1;
+                } else {
+                    // The epochs have been shorter than expected, so the
+                    // transaction hasn't expired yet
+                    self.write_log.rebucket_tx_hash(
+                        hash,
+                        current_epoch,
+                        expiration(time),
+                    )?;
+                }
+            }
+            tracing::debug!(
+                "Pruned {pruned} expired transactions' hashes from the replay \
+                 protection storage in epoch {current_epoch}"
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Prefix iterator for [`WlStorage`].
@@ -471,6 +532,23 @@ where
         Ok(iter)
     }
 
+    fn iter_prefix_from<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+        start: &storage::Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        // Only the DB is seeked, the write log's items before the start are
+        // skipped by the caller
+        let storage_iter =
+            self.storage().db.iter_prefix_from(prefix, start).peekable();
+        let write_log_iter =
+            self.write_log().iter_prefix_post(prefix).peekable();
+        Ok(PrefixIter {
+            storage_iter,
+            write_log_iter,
+        })
+    }
+
     fn iter_next<'iter>(
         &'iter self,
         iter: &mut Self::PrefixIter<'iter>,
@@ -565,7 +643,8 @@ mod tests {
     use super::*;
     use crate::ledger::storage::testing::TestWlStorage;
     use crate::types::address::InternalAddress;
-    use crate::types::storage::DbKeySeg;
+    use crate::types::storage::{DbKeySeg, Epoch};
+    use crate::types::time::DurationSecs;
 
     proptest! {
         // Generate arb valid input for `test_prefix_iters_aux`
@@ -681,6 +760,80 @@ mod tests {
         itertools::assert_equal(expected_post, read_post);
     }
 
+    /// Test that the hashes of the expiring txs are pruned with the bucket of
+    /// the first epoch that's guaranteed to start after their expiration, or
+    /// moved to a later bucket if the epochs have been shorter than expected
+    #[test]
+    fn test_replay_protection_buckets() {
+        let mut s = TestWlStorage::default();
+        let min_duration = DurationSecs(100);
+        s.write(
+            &parameters::storage::get_epoch_duration_storage_key(),
+            EpochDuration {
+                min_num_of_blocks: 1,
+                min_duration,
+            },
+        )
+        .unwrap();
+        let start = DateTimeUtc::unix_epoch();
+        s.storage.next_epoch_min_start_time = start + min_duration;
+
+        let expiring = Hash::sha256("expiring".as_bytes());
+        let late = Hash::sha256("late".as_bytes());
+        let permanent = Hash::sha256("permanent".as_bytes());
+        s.write_tx_hash(expiring, Some(start + DurationSecs(50)))
+            .unwrap();
+        s.write_tx_hash(late, Some(start + DurationSecs(250)))
+            .unwrap();
+        s.write_tx_hash(permanent, None).unwrap();
+        s.commit_block().unwrap();
+
+        // Finalize the hashes in the following block
+        s.finalize_replay_protection(false, start + DurationSecs(10))
+            .unwrap();
+        s.commit_block().unwrap();
+        let bucket = |s: &TestWlStorage, epoch: u64| {
+            s.storage
+                .iter_replay_protection_bucket(Epoch(epoch))
+                .map(|(hash, _time)| hash)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bucket(&s, 1), vec![expiring]);
+        assert!(bucket(&s, 2).is_empty());
+        assert_eq!(bucket(&s, 3), vec![late]);
+
+        // The expired tx hash is pruned at the beginning of the next epoch
+        s.storage.block.epoch = Epoch(1);
+        s.storage.next_epoch_min_start_time = start + DurationSecs(200);
+        s.finalize_replay_protection(true, start + DurationSecs(100))
+            .unwrap();
+        s.commit_block().unwrap();
+        assert!(bucket(&s, 1).is_empty());
+        assert!(!s.storage.has_replay_protection_entry(&expiring).unwrap());
+        assert!(s.storage.has_replay_protection_entry(&late).unwrap());
+        assert!(s.storage.has_replay_protection_entry(&permanent).unwrap());
+
+        // The epochs have been shorter than expected, e.g. because of a
+        // change of the minimum duration, so the tx that hasn't expired yet
+        // is moved to a later bucket
+        s.storage.block.epoch = Epoch(3);
+        s.storage.next_epoch_min_start_time = start + DurationSecs(300);
+        s.finalize_replay_protection(true, start + DurationSecs(200))
+            .unwrap();
+        s.commit_block().unwrap();
+        assert!(bucket(&s, 3).is_empty());
+        assert_eq!(bucket(&s, 4), vec![late]);
+        assert!(s.storage.has_replay_protection_entry(&late).unwrap());
+
+        s.storage.block.epoch = Epoch(4);
+        s.finalize_replay_protection(true, start + DurationSecs(300))
+            .unwrap();
+        s.commit_block().unwrap();
+        assert!(bucket(&s, 4).is_empty());
+        assert!(!s.storage.has_replay_protection_entry(&late).unwrap());
+        assert!(s.storage.has_replay_protection_entry(&permanent).unwrap());
+    }
+
     fn apply_to_wl_storage(s: &mut TestWlStorage, kvs: &[KeyVal<i8>]) {
         // Apply writes first
         for (key, val) in kvs {
@@ -3,6 +3,7 @@
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+use borsh_ext::BorshSerializeExt;
 use itertools::Itertools;
 use thiserror::Error;
 
@@ -11,14 +12,17 @@ use crate::ledger::gas::{
     MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_WRITE_GAS_PER_BYTE,
 };
 use crate::ledger::replay_protection::{
-    get_replay_protection_all_subkey, get_replay_protection_last_subkey,
+    encode_last_entry, get_replay_protection_all_subkey,
+    get_replay_protection_bucket_subkey, get_replay_protection_last_subkey,
+    TxHashExpiration,
 };
 use crate::ledger::storage::traits::StorageHasher;
 use crate::ledger::storage::Storage;
 use crate::types::address::{Address, EstablishedAddressGen, InternalAddress};
 use crate::types::hash::Hash;
 use crate::types::ibc::IbcEvent;
-use crate::types::storage;
+use crate::types::storage::{self, Epoch};
+use crate::types::time::DateTimeUtc;
 use crate::types::token::{
     is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
 };
@@ -74,12 +78,17 @@ pub enum StorageModification {
 #[derive(Debug, Clone)]
 /// A replay protection storage modification
 enum ReProtStorageModification {
-    /// Write an entry
-    Write,
+    /// Write an entry, along with the expiration time of its tx, if any
+    Write(Option<DateTimeUtc>),
     /// Delete an entry
     Delete,
-    /// Finalize an entry
-    Finalize,
+    /// Finalize an entry, indexing it in the bucket of its expiration, if any
+    Finalize(Option<TxHashExpiration>),
+    /// Prune an entry of an expired tx from the bucket of the given epoch
+    Prune(Epoch),
+    /// Move an entry of a tx that hasn't expired yet from the bucket of the
+    /// given epoch to the bucket of its expiration
+    Rebucket(Epoch, TxHashExpiration),
 }
 
 /// The write log storage
@@ -405,6 +414,13 @@ impl WriteLog {
             .collect()
     }
 
+    /// Get the storage keys changed and accounts keys initialized in the
+    /// current block. The keys of the current transaction and precommit are
+    /// not included in the result of this function.
+    pub fn get_block_keys(&self) -> BTreeSet<storage::Key> {
+        self.block_write_log.keys().cloned().collect()
+    }
+
     /// Get the storage keys changed in the current transaction (left) and
     /// the addresses of accounts initialized in the current transaction
     /// (right). The first vector excludes keys of validity predicates of
@@ -534,12 +550,13 @@ impl WriteLog {
 
         for (hash, entry) in self.replay_protection.iter() {
             match entry {
-                ReProtStorageModification::Write => storage
+                ReProtStorageModification::Write(expiration) => storage
                     .write_replay_protection_entry(
                         batch,
                         // Can only write tx hashes to the previous block, no
                         // further
                         &get_replay_protection_last_subkey(hash),
+                        &encode_last_entry(*expiration),
                     )
                     .map_err(Error::StorageError)?,
                 ReProtStorageModification::Delete => storage
@@ -550,11 +567,12 @@ impl WriteLog {
                         &get_replay_protection_last_subkey(hash),
                     )
                     .map_err(Error::StorageError)?,
-                ReProtStorageModification::Finalize => {
+                ReProtStorageModification::Finalize(expiration) => {
                     storage
                         .write_replay_protection_entry(
                             batch,
                             &get_replay_protection_all_subkey(hash),
+                            &[],
                         )
                         .map_err(Error::StorageError)?;
                     storage
@@ -562,6 +580,50 @@ impl WriteLog {
                             batch,
                             &get_replay_protection_last_subkey(hash),
                         )
+                        .map_err(Error::StorageError)?;
+                    if let Some(TxHashExpiration { time, bucket }) = expiration
+                    {
+                        storage
+                            .write_replay_protection_entry(
+                                batch,
+                                &get_replay_protection_bucket_subkey(
+                                    *bucket, hash,
+                                ),
+                                &time.serialize_to_vec(),
+                            )
+                            .map_err(Error::StorageError)?
+                    }
+                }
+                ReProtStorageModification::Prune(bucket) => {
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &get_replay_protection_all_subkey(hash),
+                        )
+                        .map_err(Error::StorageError)?;
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &get_replay_protection_bucket_subkey(*bucket, hash),
+                        )
+                        .map_err(Error::StorageError)?
+                }
+                ReProtStorageModification::Rebucket(
+                    from,
+                    TxHashExpiration { time, bucket },
+                ) => {
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &get_replay_protection_bucket_subkey(*from, hash),
+                        )
+                        .map_err(Error::StorageError)?;
+                    storage
+                        .write_replay_protection_entry(
+                            batch,
+                            &get_replay_protection_bucket_subkey(*bucket, hash),
+                            &time.serialize_to_vec(),
+                        )
                         .map_err(Error::StorageError)?
                 }
             }
@@ -669,16 +731,25 @@ impl WriteLog {
     /// Check if the given tx hash has already been processed. Returns `None` if
     /// the key is not known.
     pub fn has_replay_protection_entry(&self, hash: &Hash) -> Option<bool> {
-        self.replay_protection
-            .get(hash)
-            .map(|action| !matches!(action, ReProtStorageModification::Delete))
+        self.replay_protection.get(hash).map(|action| {
+            !matches!(
+                action,
+                ReProtStorageModification::Delete
+                    | ReProtStorageModification::Prune(_)
+            )
+        })
     }
 
-    /// Write the transaction hash
-    pub(crate) fn write_tx_hash(&mut self, hash: Hash) -> Result<()> {
+    /// Write the transaction hash, along with the expiration time of the
+    /// transaction, if any
+    pub(crate) fn write_tx_hash(
+        &mut self,
+        hash: Hash,
+        expiration: Option<DateTimeUtc>,
+    ) -> Result<()> {
         if self
             .replay_protection
-            .insert(hash, ReProtStorageModification::Write)
+            .insert(hash, ReProtStorageModification::Write(expiration))
             .is_some()
         {
             // Cannot write an hash if other requests have already been
@@ -699,7 +770,7 @@ impl WriteLog {
         {
             None => Ok(()),
             // Allow overwriting a previous finalize request
-            Some(ReProtStorageModification::Finalize) => Ok(()),
+            Some(ReProtStorageModification::Finalize(_)) => Ok(()),
             Some(_) =>
             // Cannot delete an hash that still has to be written to
             // storage or has already been deleted
@@ -713,12 +784,17 @@ impl WriteLog {
     }
 
     /// Move the transaction hash of the previous block to the list of all
-    /// blocks. This functions should be called at the beginning of the block
-    /// processing, before any other replay protection operation is done
-    pub fn finalize_tx_hash(&mut self, hash: Hash) -> Result<()> {
+    /// blocks and, if the transaction expires, to the bucket of its
+    /// expiration. This functions should be called at the beginning of the
+    /// block processing, before any other replay protection operation is done
+    pub fn finalize_tx_hash(
+        &mut self,
+        hash: Hash,
+        expiration: Option<TxHashExpiration>,
+    ) -> Result<()> {
         if self
             .replay_protection
-            .insert(hash, ReProtStorageModification::Finalize)
+            .insert(hash, ReProtStorageModification::Finalize(expiration))
             .is_some()
         {
             // Cannot finalize an hash if other requests have already been
@@ -730,6 +806,49 @@ impl WriteLog {
 
         Ok(())
     }
+
+    /// Remove the transaction hash of an expired transaction from the bucket
+    /// of the given epoch and from the list of all blocks. This functions
+    /// should be called at the beginning of the given epoch, after the
+    /// finalization of the hashes of the previous block
+    pub fn prune_tx_hash(&mut self, hash: Hash, bucket: Epoch) -> Result<()> {
+        if self
+            .replay_protection
+            .insert(hash, ReProtStorageModification::Prune(bucket))
+            .is_some()
+        {
+            // Cannot prune an hash if other requests have already been
+            // committed for the same hash
+            return Err(Error::ReplayProtection(format!(
+                "Requested a prune on hash {hash} over a previous request"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Move the transaction hash of a transaction that hasn't expired yet from
+    /// the bucket of the given epoch to the bucket of its expiration
+    pub fn rebucket_tx_hash(
+        &mut self,
+        hash: Hash,
+        from: Epoch,
+        expiration: TxHashExpiration,
+    ) -> Result<()> {
+        if self
+            .replay_protection
+            .insert(hash, ReProtStorageModification::Rebucket(from, expiration))
+            .is_some()
+        {
+            // Cannot move an hash if other requests have already been
+            // committed for the same hash
+            return Err(Error::ReplayProtection(format!(
+                "Requested a rebucket on hash {hash} over a previous request"
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -968,13 +1087,13 @@ mod tests {
 
         // write some replay protection keys
         write_log
-            .write_tx_hash(Hash::sha256("tx1".as_bytes()))
+            .write_tx_hash(Hash::sha256("tx1".as_bytes()), None)
             .unwrap();
         write_log
-            .write_tx_hash(Hash::sha256("tx2".as_bytes()))
+            .write_tx_hash(Hash::sha256("tx2".as_bytes()), None)
             .unwrap();
         write_log
-            .write_tx_hash(Hash::sha256("tx3".as_bytes()))
+            .write_tx_hash(Hash::sha256("tx3".as_bytes()), None)
             .unwrap();
 
         // commit a block
@@ -993,13 +1112,13 @@ mod tests {
 
         // write some replay protection keys
         write_log
-            .write_tx_hash(Hash::sha256("tx4".as_bytes()))
+            .write_tx_hash(Hash::sha256("tx4".as_bytes()), None)
             .unwrap();
         write_log
-            .write_tx_hash(Hash::sha256("tx5".as_bytes()))
+            .write_tx_hash(Hash::sha256("tx5".as_bytes()), None)
             .unwrap();
         write_log
-            .write_tx_hash(Hash::sha256("tx6".as_bytes()))
+            .write_tx_hash(Hash::sha256("tx6".as_bytes()), None)
             .unwrap();
 
         // delete previous hash
@@ -1010,7 +1129,7 @@ mod tests {
         // finalize previous hashes
         for tx in ["tx2", "tx3"] {
             write_log
-                .finalize_tx_hash(Hash::sha256(tx.as_bytes()))
+                .finalize_tx_hash(Hash::sha256(tx.as_bytes()), None)
                 .unwrap();
         }
 
@@ -680,6 +680,30 @@ where
         }))
     }
 
+    /// An iterator visiting the key-value elements from the given key, in the
+    /// order of the storage keys. The storages that can seek skip the
+    /// elements before the given key without reading them.
+    pub fn iter_from<'iter>(
+        &self,
+        storage: &'iter impl StorageRead,
+        start: &K,
+    ) -> Result<impl Iterator<Item = Result<(K, V)>> + 'iter> {
+        let iter = storage_api::iter_prefix_from(
+            storage,
+            &self.get_data_prefix(),
+            &self.get_data_key(start),
+        )?;
+        Ok(iter.map(|key_val_res| {
+            let (key, val) = key_val_res?;
+            let last_key_seg = key
+                .last()
+                .ok_or(ReadError::UnexpectedlyEmptyStorageKey)
+                .into_storage_result()?;
+            let key = K::parse(last_key_seg.raw()).into_storage_result()?;
+            Ok((key, val))
+        }))
+    }
+
     // /// Collect the lazy map into an eager map
     // pub fn collect<M, S>(&self, storage: &S) -> Result<M>
     // where
@@ -755,6 +779,12 @@ mod test {
         assert_eq!(map_it.next().unwrap()?, (key2, val2.clone()));
         drop(map_it);
 
+        // The iteration from a key skips the elements before it
+        let mut map_it = lazy_map.iter_from(&storage, &key2)?;
+        assert_eq!(map_it.next().unwrap()?, (key2, val2.clone()));
+        assert!(map_it.next().is_none());
+        drop(map_it);
+
         assert!(lazy_map.get(&storage, &0)?.is_none());
         assert_eq!(lazy_map.get(&storage, &key)?.unwrap(), val);
         assert_eq!(lazy_map.get(&storage, &key2)?.unwrap(), val2);
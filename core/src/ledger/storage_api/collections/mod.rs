@@ -150,3 +150,150 @@ pub struct ValidationBuilder<Change> {
     /// The accumulator of found changes under the vector
     pub changes: Vec<Change>,
 }
+
+/// Adapters for iterators that yield a [`storage_api::Result`] for each
+/// element, such as the iterators over the lazy collections, which propagate
+/// errors instead of requiring the elements to be unwrapped.
+pub trait TryIterExt<T>:
+    Iterator<Item = storage_api::Result<T>> + Sized
+{
+    /// Keep only the elements for which the fallible `predicate` returns
+    /// `true`. Errors from the iterator and from the predicate are passed
+    /// through.
+    fn try_filter<P>(self, predicate: P) -> TryFilter<Self, P>
+    where
+        P: FnMut(&T) -> storage_api::Result<bool>,
+    {
+        TryFilter {
+            iter: self,
+            predicate,
+        }
+    }
+
+    /// Map the elements with the fallible function `f`. Errors from the
+    /// iterator and from the function are passed through.
+    fn try_map<U, F>(self, f: F) -> TryMap<Self, F>
+    where
+        F: FnMut(T) -> storage_api::Result<U>,
+    {
+        TryMap { iter: self, f }
+    }
+
+    /// Collect the elements into an eager collection, e.g. a `Vec` or a
+    /// `BTreeMap`, stopping at the first error.
+    fn collect_eager<B>(self) -> storage_api::Result<B>
+    where
+        B: FromIterator<T>,
+    {
+        self.collect()
+    }
+}
+
+impl<T, I: Iterator<Item = storage_api::Result<T>>> TryIterExt<T> for I {}
+
+/// An iterator adapter created by [`TryIterExt::try_filter`]
+#[derive(Debug)]
+pub struct TryFilter<I, P> {
+    iter: I,
+    predicate: P,
+}
+
+impl<T, I, P> Iterator for TryFilter<I, P>
+where
+    I: Iterator<Item = storage_api::Result<T>>,
+    P: FnMut(&T) -> storage_api::Result<bool>,
+{
+    type Item = storage_api::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for item in self.iter.by_ref() {
+            match item {
+                Ok(val) => match (self.predicate)(&val) {
+                    Ok(true) => return Some(Ok(val)),
+                    Ok(false) => {}
+                    Err(err) => return Some(Err(err)),
+                },
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+/// An iterator adapter created by [`TryIterExt::try_map`]
+#[derive(Debug)]
+pub struct TryMap<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<T, U, I, F> Iterator for TryMap<I, F>
+where
+    I: Iterator<Item = storage_api::Result<T>>,
+    F: FnMut(T) -> storage_api::Result<U>,
+{
+    type Item = storage_api::Result<U>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|item| item.and_then(&mut self.f))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+
+    #[test]
+    fn test_try_iter_ext() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+
+        let key = storage::Key::parse("test").unwrap();
+        let lazy_map = LazyMap::<u32, u64>::open(key);
+        for (key, val) in [(1, 10), (2, 20), (3, 30), (4, 40)] {
+            lazy_map.insert(&mut storage, key, val)?;
+        }
+
+        // Filter and map the elements and collect them into an eager map
+        let eager: BTreeMap<u32, u64> = lazy_map
+            .iter(&storage)?
+            .try_filter(|(key, _)| Ok(key % 2 == 0))
+            .try_map(|(key, val)| Ok((key, val + 1)))
+            .collect_eager()?;
+        assert_eq!(eager, BTreeMap::from([(2, 21), (4, 41)]));
+
+        // An error from the predicate is propagated
+        let res = lazy_map
+            .iter(&storage)?
+            .try_filter(|(key, _)| {
+                if *key < 3 {
+                    Ok(true)
+                } else {
+                    Err(storage_api::Error::new_const("predicate error"))
+                }
+            })
+            .collect_eager::<Vec<_>>();
+        assert!(res.is_err());
+
+        // An error from the mapping function is propagated
+        let res = lazy_map
+            .iter(&storage)?
+            .try_map(|(key, _)| {
+                if key < 3 {
+                    Ok(key)
+                } else {
+                    Err(storage_api::Error::new_const("map error"))
+                }
+            })
+            .collect_eager::<Vec<_>>();
+        assert!(res.is_err());
+
+        Ok(())
+    }
+}
@@ -90,6 +90,19 @@ impl Error {
             _ => Err(self),
         }
     }
+
+    /// Attempt to get a reference to the inner error as `E` if any, like
+    /// [`Error::downcast`] but without consuming this error.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        match self {
+            Self::Custom(CustomError(b))
+            | Self::CustomWithMessage(_, CustomError(b)) => b.downcast_ref(),
+            Self::SimpleMessage(_) => None,
+        }
+    }
 }
 
 /// A custom error
@@ -10,6 +10,7 @@ pub mod pgf;
 pub mod token;
 pub mod tx;
 pub mod validation;
+pub mod vesting;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
@@ -72,6 +73,21 @@ pub trait StorageRead {
         prefix: &storage::Key,
     ) -> Result<Self::PrefixIter<'iter>>;
 
+    /// Storage prefix iterator ordered by the storage keys, seeking to the
+    /// given `start` key. A storage that cannot seek may also return the keys
+    /// ordered before `start`, so this falls back on [`fn@Self::iter_prefix`]
+    /// by default.
+    ///
+    /// For a more user-friendly iterator API, which skips the keys before
+    /// `start`, use [`fn@iter_prefix_from`] instead.
+    fn iter_prefix_from<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+        _start: &storage::Key,
+    ) -> Result<Self::PrefixIter<'iter>> {
+        self.iter_prefix(prefix)
+    }
+
     /// Storage prefix iterator. It will try to read from the storage.
     fn iter_next<'iter>(
         &'iter self,
@@ -213,6 +229,59 @@ where
     Ok(iter)
 }
 
+/// Iterate Borsh encoded items matching the given prefix, ordered by the
+/// storage keys, from the given `start` key. The storages that can seek skip
+/// the keys ordered before `start` without reading them.
+pub fn iter_prefix_from<'a, T>(
+    storage: &'a impl StorageRead,
+    prefix: &crate::types::storage::Key,
+    start: &crate::types::storage::Key,
+) -> Result<impl Iterator<Item = Result<(storage::Key, T)>> + 'a>
+where
+    T: BorshDeserialize,
+{
+    let iter = storage.iter_prefix_from(prefix, start)?;
+    let start = start.to_string();
+    let iter = itertools::unfold(iter, move |iter| {
+        // The loop is for skipping the keys before the start, which are
+        // returned by the storages that cannot seek
+        loop {
+            match storage.iter_next(iter) {
+                Ok(Some((key, val))) => {
+                    if key < start {
+                        continue;
+                    }
+                    let key =
+                        match storage::Key::parse(key).into_storage_result() {
+                            Ok(key) => key,
+                            Err(err) => {
+                                // Propagate key encoding errors into Iterator's
+                                // Item
+                                return Some(Err(err));
+                            }
+                        };
+                    let val =
+                        match T::try_from_slice(&val).into_storage_result() {
+                            Ok(val) => val,
+                            Err(err) => {
+                                // Propagate val encoding errors into Iterator's
+                                // Item
+                                return Some(Err(err));
+                            }
+                        };
+                    return Some(Ok((key, val)));
+                }
+                Ok(None) => return None,
+                Err(err) => {
+                    // Propagate `iter_next` errors into Iterator's Item
+                    return Some(Err(err));
+                }
+            }
+        }
+    });
+    Ok(iter)
+}
+
 /// Iterate Borsh encoded items matching the given prefix and passing the given
 /// `filter` predicate, ordered by the storage keys.
 ///
@@ -0,0 +1,243 @@
+//! Vesting
+
+use crate::ledger::storage_api::{self, token, StorageRead, StorageWrite};
+use crate::ledger::vesting::{
+    storage as vesting_storage, VestingSchedule, ADDRESS,
+};
+use crate::types::address::Address;
+use crate::types::storage::Epoch;
+use crate::types::token::Amount;
+
+/// Read the vesting schedule of an account, if any
+pub fn read_schedule<S>(
+    storage: &S,
+    owner: &Address,
+) -> storage_api::Result<Option<VestingSchedule>>
+where
+    S: StorageRead,
+{
+    vesting_storage::schedules_handle().get(storage, owner)
+}
+
+/// Check if an account has a vesting schedule
+pub fn is_vesting_account<S>(
+    storage: &S,
+    owner: &Address,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    vesting_storage::schedules_handle().contains(storage, owner)
+}
+
+/// Read the amount of native tokens held by the vesting address on behalf of
+/// an account. This excludes the tokens that the account has delegated.
+pub fn read_locked_amount<S>(
+    storage: &S,
+    owner: &Address,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead,
+{
+    Ok(vesting_storage::locked_handle()
+        .get(storage, owner)?
+        .unwrap_or_default())
+}
+
+/// Give the `owner` account a vesting schedule, funded by transferring its
+/// total amount of native tokens from the `source` to the vesting address.
+pub fn init_vesting<S>(
+    storage: &mut S,
+    source: &Address,
+    owner: &Address,
+    schedule: VestingSchedule,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if is_vesting_account(storage, owner)? {
+        return Err(storage_api::Error::new_const(
+            "The account already has a vesting schedule",
+        ));
+    }
+    let total = match schedule.total() {
+        Some(total) if schedule.is_valid() => total,
+        _ => {
+            return Err(storage_api::Error::new_const(
+                "Invalid vesting schedule",
+            ));
+        }
+    };
+    credit_locked(storage, source, owner, total)?;
+    vesting_storage::schedules_handle().insert(
+        storage,
+        owner.clone(),
+        schedule,
+    )?;
+    Ok(())
+}
+
+/// The amount of native tokens that the `owner` of a vesting account can
+/// claim in the given epoch. Delegated tokens are considered to be locked
+/// first, so that only the held tokens in excess of the amount that is still
+/// locked can be claimed.
+pub fn claimable_amount<S>(
+    storage: &S,
+    owner: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead,
+{
+    let Some(schedule) = read_schedule(storage, owner)? else {
+        return Ok(Amount::zero());
+    };
+    let held = read_locked_amount(storage, owner)?;
+    Ok(held
+        .checked_sub(schedule.locked_at(current_epoch))
+        .unwrap_or_default())
+}
+
+/// Transfer all the claimable native tokens of a vesting account to its
+/// `owner`. Returns the claimed amount.
+pub fn claim_vested<S>(
+    storage: &mut S,
+    owner: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let amount = claimable_amount(storage, owner, current_epoch)?;
+    debit_locked(storage, owner, owner, amount)?;
+    Ok(amount)
+}
+
+/// Transfer native tokens held on behalf of the `owner` vesting account from
+/// the vesting address to the `dest`.
+pub fn debit_locked<S>(
+    storage: &mut S,
+    owner: &Address,
+    dest: &Address,
+    amount: Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let held = read_locked_amount(storage, owner)?;
+    let new_held = held.checked_sub(amount).ok_or_else(|| {
+        storage_api::Error::new_const("Insufficient vesting account balance")
+    })?;
+    let native_token = storage.get_native_token()?;
+    token::transfer(storage, &native_token, &ADDRESS, dest, amount)?;
+    vesting_storage::locked_handle().insert(
+        storage,
+        owner.clone(),
+        new_held,
+    )?;
+    Ok(())
+}
+
+/// Transfer native tokens from the `source` to the vesting address to be held
+/// on behalf of the `owner` vesting account.
+pub fn credit_locked<S>(
+    storage: &mut S,
+    source: &Address,
+    owner: &Address,
+    amount: Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let held = read_locked_amount(storage, owner)?;
+    let new_held = held.checked_add(amount).ok_or_else(|| {
+        storage_api::Error::new_const("Vesting account balance overflow")
+    })?;
+    let native_token = storage.get_native_token()?;
+    token::transfer(storage, &native_token, source, &ADDRESS, amount)?;
+    vesting_storage::locked_handle().insert(
+        storage,
+        owner.clone(),
+        new_held,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::types::address::POS;
+
+    #[test]
+    fn test_vesting_claims() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let native_token = storage.storage.native_token.clone();
+        let funder = established_address_1();
+        let owner = established_address_2();
+        token::credit_tokens(
+            &mut storage,
+            &native_token,
+            &funder,
+            Amount::from(100_u64),
+        )?;
+        let schedule = VestingSchedule {
+            tranches: BTreeMap::from_iter([
+                (Epoch(2), Amount::from(40_u64)),
+                (Epoch(4), Amount::from(60_u64)),
+            ]),
+        };
+        init_vesting(&mut storage, &funder, &owner, schedule.clone())?;
+        assert!(is_vesting_account(&storage, &owner)?);
+        assert_eq!(
+            read_locked_amount(&storage, &owner)?,
+            Amount::from(100_u64)
+        );
+        assert_eq!(
+            token::read_balance(&storage, &native_token, &ADDRESS)?,
+            Amount::from(100_u64)
+        );
+        // A schedule can only be given once
+        assert!(init_vesting(&mut storage, &funder, &owner, schedule).is_err());
+
+        // Nothing can be claimed before the first tranche unlocks
+        assert!(claim_vested(&mut storage, &owner, Epoch(1))?.is_zero());
+        assert_eq!(
+            claim_vested(&mut storage, &owner, Epoch(2))?,
+            Amount::from(40_u64)
+        );
+        assert!(claim_vested(&mut storage, &owner, Epoch(3))?.is_zero());
+
+        // Delegated tokens are counted as locked first
+        debit_locked(&mut storage, &owner, &POS, Amount::from(50_u64))?;
+        assert_eq!(
+            claimable_amount(&storage, &owner, Epoch(4))?,
+            Amount::from(10_u64)
+        );
+        credit_locked(&mut storage, &POS, &owner, Amount::from(50_u64))?;
+        assert_eq!(
+            claim_vested(&mut storage, &owner, Epoch(4))?,
+            Amount::from(60_u64)
+        );
+        assert_eq!(
+            token::read_balance(&storage, &native_token, &owner)?,
+            Amount::from(100_u64)
+        );
+        assert!(
+            debit_locked(&mut storage, &owner, &owner, Amount::from(1_u64))
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! Vesting library code. Tokens of an account with a vesting schedule are
+//! held by the vesting internal address until they unlock, so that they can
+//! be delegated but not transferred.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::Epoch;
+use crate::types::token;
+
+/// Vesting storage
+pub mod storage;
+
+/// The vesting internal address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::Vesting);
+
+/// A vesting schedule of native tokens, made of tranches that each unlock a
+/// given amount at the start of an epoch.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct VestingSchedule {
+    /// The amounts unlocked at the start of each epoch
+    pub tranches: BTreeMap<Epoch, token::Amount>,
+}
+
+impl VestingSchedule {
+    /// Check that the schedule has at least one tranche, that none of its
+    /// tranches are empty and that its total doesn't overflow.
+    pub fn is_valid(&self) -> bool {
+        !self.tranches.is_empty()
+            && self.tranches.values().all(|amount| !amount.is_zero())
+            && self.total().is_some()
+    }
+
+    /// The total amount of tokens vested by the schedule, if it doesn't
+    /// overflow
+    pub fn total(&self) -> Option<token::Amount> {
+        self.tranches
+            .values()
+            .try_fold(token::Amount::zero(), |acc, amount| {
+                acc.checked_add(*amount)
+            })
+    }
+
+    /// The amount of tokens that are still locked in the given epoch
+    pub fn locked_at(&self, epoch: Epoch) -> token::Amount {
+        self.tranches
+            .range(epoch.next()..)
+            .fold(token::Amount::zero(), |acc, (_, amount)| acc + *amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vesting_schedule() {
+        let schedule = VestingSchedule {
+            tranches: BTreeMap::from_iter([
+                (Epoch(2), token::Amount::from(10_u64)),
+                (Epoch(5), token::Amount::from(30_u64)),
+            ]),
+        };
+        assert!(schedule.is_valid());
+        assert_eq!(schedule.total(), Some(token::Amount::from(40_u64)));
+        assert_eq!(schedule.locked_at(Epoch(0)), token::Amount::from(40_u64));
+        assert_eq!(schedule.locked_at(Epoch(2)), token::Amount::from(30_u64));
+        assert_eq!(schedule.locked_at(Epoch(4)), token::Amount::from(30_u64));
+        assert_eq!(schedule.locked_at(Epoch(5)), token::Amount::zero());
+
+        assert!(!VestingSchedule::default().is_valid());
+        let empty_tranche = VestingSchedule {
+            tranches: BTreeMap::from_iter([(Epoch(1), token::Amount::zero())]),
+        };
+        assert!(!empty_tranche.is_valid());
+    }
+}
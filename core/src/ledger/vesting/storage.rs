@@ -0,0 +1,82 @@
+use namada_macros::StorageKeys;
+
+use super::{VestingSchedule, ADDRESS};
+use crate::ledger::storage_api::collections::{
+    lazy_map, LazyCollection, LazyMap,
+};
+use crate::types::address::Address;
+use crate::types::storage::{DbKeySeg, Key};
+use crate::types::token;
+
+/// Storage keys for vesting internal address.
+#[derive(StorageKeys)]
+struct Keys {
+    schedules: &'static str,
+    locked: &'static str,
+}
+
+/// Obtain a storage key prefix for the vesting schedules
+pub fn schedules_key_prefix() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.schedules.to_string()),
+        ],
+    }
+}
+
+/// LazyMap handler for the vesting schedules of accounts
+pub fn schedules_handle() -> LazyMap<Address, VestingSchedule> {
+    LazyMap::open(schedules_key_prefix())
+}
+
+/// Check if the given storage key is a vesting schedule key. If it is,
+/// returns the owner of the vesting account.
+pub fn is_schedule_key(key: &Key) -> Option<&Address> {
+    is_owner_data_key(key, Keys::VALUES.schedules)
+}
+
+/// Obtain a storage key prefix for the amounts of tokens held on behalf of
+/// the vesting accounts
+pub fn locked_key_prefix() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.locked.to_string()),
+        ],
+    }
+}
+
+/// LazyMap handler for the amounts of tokens held on behalf of the vesting
+/// accounts
+pub fn locked_handle() -> LazyMap<Address, token::Amount> {
+    LazyMap::open(locked_key_prefix())
+}
+
+/// Check if the given storage key is a key of the amount held on behalf of a
+/// vesting account. If it is, returns the owner of the vesting account.
+pub fn is_locked_key(key: &Key) -> Option<&Address> {
+    is_owner_data_key(key, Keys::VALUES.locked)
+}
+
+/// Check if key is inside vesting address space
+pub fn is_vesting_key(key: &Key) -> bool {
+    matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
+}
+
+fn is_owner_data_key<'a>(key: &'a Key, map: &str) -> Option<&'a Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(vesting),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(owner),
+        ] if vesting.eq(&ADDRESS)
+            && prefix.as_str() == map
+            && data.as_str() == lazy_map::DATA_SUBKEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
@@ -5,9 +5,9 @@ mod types;
 
 pub use types::{
     standalone_signature, verify_standalone_sig, Code, Commitment,
-    CompressedSignature, Data, Error, Header, MaspBuilder, Section,
+    CompressedSignature, Data, Error, Header, MaspBuilder, Memo, Section,
     SerializeWithBorsh, Signable, SignableEthMessage, Signature,
-    SignatureIndex, Signed, Signer, Tx, TxError,
+    SignatureIndex, Signed, Signer, Tx, TxError, MAX_MEMO_LEN,
 };
 
 #[cfg(test)]
@@ -30,4 +30,50 @@ mod tests {
         let tx_from_bytes = Tx::decode(&tx_from_hex[..]).unwrap();
         assert_eq!(tx, tx_from_bytes);
     }
+
+    /// Test that the memo of a tx is bounded, signed along with the raw header
+    /// and that a tx can carry at most one memo
+    #[test]
+    fn test_tx_memo() {
+        use crate::types::account::AccountPublicKeysMap;
+        use crate::types::chain::ChainId;
+        use crate::types::key::testing::keypair_1;
+        use crate::types::key::RefTo;
+
+        assert!(Memo::new(vec![0; MAX_MEMO_LEN + 1]).is_none());
+        let memo = Memo::new("order-42").unwrap();
+        assert_eq!(memo.to_string().parse::<Memo>().unwrap(), memo);
+
+        let keypair = keypair_1();
+        let mut tx = types::Tx::new(ChainId::default(), None);
+        tx.add_data(42u64);
+        tx.add_memo(memo.clone());
+        tx.sign_raw(
+            vec![keypair.clone()],
+            AccountPublicKeysMap::from_iter([keypair.ref_to()]),
+            None,
+        );
+        assert_eq!(tx.memo(), Some(memo.clone()));
+        assert!(tx.validate_tx().is_ok());
+        tx.verify_signature(&keypair.ref_to(), &[tx.raw_header_hash()])
+            .unwrap();
+
+        // the signature doesn't hold anymore if the memo is replaced
+        let mut tampered = tx.clone();
+        tampered.add_memo(Memo::new("order-43").unwrap());
+        assert!(
+            tampered
+                .verify_signature(
+                    &keypair.ref_to(),
+                    &[tampered.raw_header_hash()]
+                )
+                .is_err()
+        );
+
+        tampered.add_section(Section::Memo(memo));
+        assert!(matches!(
+            tampered.validate_tx(),
+            Err(TxError::InvalidMemo(_))
+        ));
+    }
 }
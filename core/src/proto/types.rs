@@ -2,8 +2,10 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
+use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::str::FromStr;
 
 use borsh::schema::{add_definition, Declaration, Definition};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
@@ -280,6 +282,61 @@ impl Data {
     }
 }
 
+/// The maximum length in bytes of the memo of a transaction
+pub const MAX_MEMO_LEN: usize = 256;
+
+/// A section carrying a memo attached to a transaction, e.g. the identifier
+/// of an order that a transfer pays for. The memo is signed along with the
+/// raw header of the transaction.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct Memo {
+    pub memo: Vec<u8>,
+}
+
+impl Memo {
+    /// Make a new memo section with the given bytes, if they are no longer
+    /// than [`MAX_MEMO_LEN`]
+    pub fn new(memo: impl Into<Vec<u8>>) -> Option<Self> {
+        let memo = memo.into();
+        (memo.len() <= MAX_MEMO_LEN).then_some(Self { memo })
+    }
+
+    /// Hash this memo section
+    pub fn hash<'a>(&self, hasher: &'a mut Sha256) -> &'a mut Sha256 {
+        hasher.update(self.serialize_to_vec());
+        hasher
+    }
+}
+
+impl Display for Memo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", HEXUPPER.encode(&self.memo))
+    }
+}
+
+impl FromStr for Memo {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let memo = HEXUPPER
+            .decode(s.as_bytes())
+            .map_err(|err| err.to_string())?;
+        Self::new(memo).ok_or_else(|| {
+            format!("The memo is longer than {MAX_MEMO_LEN} bytes")
+        })
+    }
+}
+
 /// Error representing the case where the supplied code has incorrect hash
 pub struct CommitmentError;
 
@@ -838,6 +895,8 @@ pub enum Section {
     MaspBuilder(MaspBuilder),
     /// Wrap a header with a section for the purposes of computing hashes
     Header(Header),
+    /// A memo attached to the transaction
+    Memo(Memo),
 }
 
 impl Section {
@@ -860,6 +919,7 @@ impl Section {
                 hasher
             }
             Self::Header(header) => header.hash(hasher),
+            Self::Memo(memo) => memo.hash(hasher),
         }
     }
 
@@ -950,6 +1010,15 @@ impl Section {
             None
         }
     }
+
+    /// Extract the memo from this section if possible
+    pub fn memo(&self) -> Option<Memo> {
+        if let Self::Memo(data) = self {
+            Some(data.clone())
+        } else {
+            None
+        }
+    }
 }
 
 /// A Namada transaction header indicating where transaction subcomponents can
@@ -1036,6 +1105,8 @@ pub enum TxError {
     SigError(String),
     #[error("Failed to deserialize Tx: {0}")]
     Deserialization(String),
+    #[error("Invalid memo: {0}")]
+    InvalidMemo(String),
 }
 
 /// A Namada transaction is represented as a header followed by a series of
@@ -1134,6 +1205,19 @@ impl Tx {
         Section::Header(raw_header).get_hash()
     }
 
+    /// Get the hashes signed by the signers of the inner transaction, i.e. the
+    /// hash of the raw header and that of the memo, if any
+    pub fn raw_sechashes(&self) -> Vec<crate::types::hash::Hash> {
+        let mut hashes = vec![self.raw_header_hash()];
+        hashes.extend(
+            self.sections
+                .iter()
+                .filter(|section| matches!(section, Section::Memo(_)))
+                .map(Section::get_hash),
+        );
+        hashes
+    }
+
     /// Get hashes of all the sections in this transaction
     pub fn sechashes(&self) -> Vec<crate::types::hash::Hash> {
         let mut hashes = vec![self.header_hash()];
@@ -1235,6 +1319,20 @@ impl Tx {
         }
     }
 
+    /// Get the memo attached to this transaction, if any
+    pub fn memo(&self) -> Option<Memo> {
+        self.sections.iter().find_map(Section::memo)
+    }
+
+    /// Attach a memo to this transaction, replacing any previous one. The memo
+    /// must be attached before the transaction is signed.
+    pub fn add_memo(&mut self, memo: Memo) -> &mut Self {
+        self.sections
+            .retain(|section| !matches!(section, Section::Memo(_)));
+        self.add_section(Section::Memo(memo));
+        self
+    }
+
     /// Convert this transaction into protobufs
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = vec![];
@@ -1347,7 +1445,7 @@ impl Tx {
         public_keys_index_map: &AccountPublicKeysMap,
         signer: Option<Address>,
     ) -> Vec<SignatureIndex> {
-        let targets = vec![self.raw_header_hash()];
+        let targets = self.raw_sechashes();
         let mut signatures = Vec::new();
         let section = Signature::new(
             targets,
@@ -1393,9 +1491,29 @@ impl Tx {
     /// the Tx and verify it is of the appropriate form. This means
     /// 1. The wrapper tx is indeed signed
     /// 2. The signature is valid
+    ///
+    /// Whatever its type, the Tx must carry at most one memo, of at most
+    /// [`MAX_MEMO_LEN`] bytes.
     pub fn validate_tx(
         &self,
     ) -> std::result::Result<Option<&Signature>, TxError> {
+        let mut memos =
+            self.sections.iter().filter_map(|section| match section {
+                Section::Memo(memo) => Some(memo),
+                _ => None,
+            });
+        if let Some(memo) = memos.next() {
+            if memo.memo.len() > MAX_MEMO_LEN {
+                return Err(TxError::InvalidMemo(format!(
+                    "the memo is longer than {MAX_MEMO_LEN} bytes"
+                )));
+            }
+            if memos.next().is_some() {
+                return Err(TxError::InvalidMemo(
+                    "the transaction carries more than one memo".to_string(),
+                ));
+            }
+        }
         match &self.header.tx_type {
             // verify signature and extract signed data
             TxType::Wrapper(wrapper) => self
@@ -1571,8 +1689,9 @@ impl Tx {
         account_public_keys_map: AccountPublicKeysMap,
         signer: Option<Address>,
     ) -> &mut Self {
-        // The inner tx signer signs the Decrypted version of the Header
-        let hashes = vec![self.raw_header_hash()];
+        // The inner tx signer signs the Decrypted version of the Header,
+        // along with the memo
+        let hashes = self.raw_sechashes();
         self.protocol_filter();
 
         let secret_keys = if signer.is_some() {
@@ -1596,7 +1715,7 @@ impl Tx {
     ) -> &mut Self {
         self.protocol_filter();
         let mut pk_section = Signature {
-            targets: vec![self.raw_header_hash()],
+            targets: self.raw_sechashes(),
             signatures: BTreeMap::new(),
             signer: Signer::PubKeys(vec![]),
         };
@@ -1607,7 +1726,7 @@ impl Tx {
                 // Add the signature under the given multisig address
                 let section =
                     sections.entry(addr.clone()).or_insert_with(|| Signature {
-                        targets: vec![self.raw_header_hash()],
+                        targets: self.raw_sechashes(),
                         signatures: BTreeMap::new(),
                         signer: Signer::Address(addr.clone()),
                     });
@@ -124,6 +124,12 @@ impl From<raw::Address<'_, raw::Validated>> for Address {
                 InternalAddress::IbcToken(IbcTokenHash(*raw_addr.data())),
             ),
             raw::Discriminant::Masp => Address::Internal(InternalAddress::Masp),
+            raw::Discriminant::Vesting => {
+                Address::Internal(InternalAddress::Vesting)
+            }
+            raw::Discriminant::StakingPool => {
+                Address::Internal(InternalAddress::StakingPool)
+            }
         }
     }
 }
@@ -218,6 +224,16 @@ impl<'addr> From<&'addr Address> for raw::Address<'addr, raw::Validated> {
                     .validate()
                     .expect("This raw address is valid")
             }
+            Address::Internal(InternalAddress::Vesting) => {
+                raw::Address::from_discriminant(raw::Discriminant::Vesting)
+                    .validate()
+                    .expect("This raw address is valid")
+            }
+            Address::Internal(InternalAddress::StakingPool) => {
+                raw::Address::from_discriminant(raw::Discriminant::StakingPool)
+                    .validate()
+                    .expect("This raw address is valid")
+            }
         }
     }
 }
@@ -543,6 +559,10 @@ pub enum InternalAddress {
     Pgf,
     /// Masp
     Masp,
+    /// Vesting accounts' locked tokens
+    Vesting,
+    /// Pooled staking
+    StakingPool,
 }
 
 impl Display for InternalAddress {
@@ -564,6 +584,8 @@ impl Display for InternalAddress {
                 Self::Multitoken => "Multitoken".to_string(),
                 Self::Pgf => "PublicGoodFundings".to_string(),
                 Self::Masp => "MASP".to_string(),
+                Self::Vesting => "Vesting".to_string(),
+                Self::StakingPool => "StakingPool".to_string(),
             }
         )
     }
@@ -579,6 +601,8 @@ impl InternalAddress {
             "bridgepool" => Some(InternalAddress::EthBridgePool),
             "governance" => Some(InternalAddress::Governance),
             "masp" => Some(InternalAddress::Masp),
+            "vesting" => Some(InternalAddress::Vesting),
+            "stakingpool" => Some(InternalAddress::StakingPool),
             _ => None,
         }
     }
@@ -864,6 +888,8 @@ pub mod testing {
             InternalAddress::Nut(_) => {}
             InternalAddress::Pgf => {}
             InternalAddress::Masp => {}
+            InternalAddress::Vesting => {}
+            InternalAddress::StakingPool => {}
             InternalAddress::Multitoken => {} /* Add new addresses in the
                                                * `prop_oneof` below. */
         };
@@ -881,6 +907,8 @@ pub mod testing {
             Just(InternalAddress::Multitoken),
             Just(InternalAddress::Pgf),
             Just(InternalAddress::Masp),
+            Just(InternalAddress::Vesting),
+            Just(InternalAddress::StakingPool),
         ]
     }
 
@@ -65,6 +65,10 @@ pub enum Discriminant {
     IbcToken = 13,
     /// MASP raw address.
     Masp = 14,
+    /// Vesting raw address.
+    Vesting = 15,
+    /// Staking pool raw address.
+    StakingPool = 16,
 }
 
 /// Raw address representation.
@@ -0,0 +1,36 @@
+//! Contains types necessary for recording on chain the updates of the
+//! consensus validator set communicated to CometBFT.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::types::key::common;
+use crate::types::storage::Epoch;
+
+/// An update of the voting power of a consensus validator, as communicated to
+/// CometBFT. A voting power of zero removes the validator from the consensus
+/// validator set.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ConsensusValidatorUpdate {
+    /// The validator's consensus key
+    pub consensus_key: common::PublicKey,
+    /// The validator's new CometBFT voting power
+    pub voting_power: i64,
+}
+
+/// All the updates of the consensus validator set that take effect at the
+/// start of an epoch.
+///
+/// These are included in a block as a single protocol transaction by the
+/// proposer of the block following the one in which the updates were
+/// communicated to CometBFT, such that they can be replayed from the chain.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ConsensusValidatorSetUpdates {
+    /// The epoch in which the updates take effect
+    pub epoch: Epoch,
+    /// The updates of the consensus validators
+    pub updates: Vec<ConsensusValidatorUpdate>,
+}
@@ -158,6 +158,11 @@ impl Dec {
         Dec(self.0 + other.0)
     }
 
+    /// Do addition of two [`Dec`]s. Return `None` if overflow.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.0.checked_add(&other.0).map(Dec)
+    }
+
     /// Do multiply two [`Dec`]s. Return `None` if overflow.
     /// This methods will overflow incorretly if both arguments are greater than
     /// 128bit.
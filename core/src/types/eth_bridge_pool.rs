@@ -15,7 +15,7 @@ use crate::types::ethereum_events::{
     EthAddress, TransferToEthereum as TransferToEthereumEvent,
 };
 use crate::types::hash::Hash as HashDigest;
-use crate::types::storage::{DbKeySeg, Key};
+use crate::types::storage::{BlockHeight, DbKeySeg, Key};
 use crate::types::token::Amount;
 
 /// A version used in our Ethereuem smart contracts
@@ -293,6 +293,29 @@ pub struct GasFee {
     pub token: Address,
 }
 
+/// The outcome of a transfer to Ethereum, once it has been removed
+/// from the Bridge pool.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub enum TransferToEthereumOutcome {
+    /// The transfer was relayed and confirmed on Ethereum, and removed
+    /// from the pool at the given height.
+    Relayed(BlockHeight),
+    /// The transfer timed out in the pool, and was refunded at the
+    /// given height.
+    Expired(BlockHeight),
+}
+
 #[cfg(test)]
 mod test_eth_bridge_pool_types {
     use super::*;
@@ -3,6 +3,7 @@
 //! on Ethereum.
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Display;
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use data_encoding::HEXUPPER;
@@ -103,6 +104,14 @@ impl TryFrom<&str> for KeccakHash {
     }
 }
 
+impl FromStr for KeccakHash {
+    type Err = TryFromError;
+
+    fn from_str(s: &str) -> Result<Self, TryFromError> {
+        s.try_into()
+    }
+}
+
 impl AsRef<[u8]> for KeccakHash {
     fn as_ref(&self) -> &[u8] {
         &self.0
@@ -3,6 +3,7 @@
 pub mod account;
 pub mod address;
 pub mod chain;
+pub mod consensus_validator_set;
 pub mod dec;
 pub mod eth_abi;
 pub mod eth_bridge_pool;
@@ -13,10 +13,12 @@ pub mod pgf;
 pub mod pos;
 /// transaction protocols made by validators
 pub mod protocol;
+/// txs to manage vesting accounts
+pub mod vesting;
 /// wrapper txs with encrypted payloads
 pub mod wrapper;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
@@ -172,6 +174,8 @@ pub enum TxSentinel {
     OutOfGas,
     /// Found invalid commtiment to one of the transaction's sections
     InvalidCommitment,
+    /// The transaction failed with the given error
+    Failed(TxErrorPayload),
 }
 
 impl TxSentinel {
@@ -184,6 +188,82 @@ impl TxSentinel {
     pub fn set_invalid_commitment(&mut self) {
         *self = Self::InvalidCommitment
     }
+
+    /// Set the sentinel for a transaction that failed with the given error,
+    /// unless it already signals an event requiring special replay
+    /// protection handling
+    pub fn set_failed(&mut self, payload: TxErrorPayload) {
+        if let Self::None | Self::Failed(_) = self {
+            *self = Self::Failed(payload)
+        }
+    }
+}
+
+/// The tag preceding the JSON encoding of a [`TxErrorPayload`] in the info
+/// string of a tx result
+pub const TX_ERROR_PAYLOAD_TAG: &str = "tx_error_payload=";
+
+/// A machine-readable description of the error that made a transaction fail,
+/// so that clients can tell which error occurred and with which values
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TxErrorPayload {
+    /// The kind of the error, e.g. `UnbondError::UnbondAmountGreaterThanBond`
+    pub kind: String,
+    /// The human-readable error message
+    pub message: String,
+    /// The values involved in the error, by name
+    pub details: BTreeMap<String, String>,
+}
+
+impl TxErrorPayload {
+    /// Describe an error of the given kind, without any details
+    pub fn new(kind: impl Into<String>, message: impl ToString) -> Self {
+        Self {
+            kind: kind.into(),
+            message: message.to_string(),
+            details: BTreeMap::new(),
+        }
+    }
+
+    /// Add a named value to the details of the error
+    pub fn with_detail(
+        mut self,
+        name: impl Into<String>,
+        value: impl ToString,
+    ) -> Self {
+        self.details.insert(name.into(), value.to_string());
+        self
+    }
+
+    /// Find the payload embedded in the given info string of a tx result,
+    /// if any
+    pub fn from_info(info: &str) -> Option<Self> {
+        let (_, encoded) = info.split_once(TX_ERROR_PAYLOAD_TAG)?;
+        // The payload may be followed by more text
+        serde_json::Deserializer::from_str(encoded)
+            .into_iter()
+            .next()?
+            .ok()
+    }
+}
+
+/// Displays the message, followed by the tagged JSON encoding of the payload
+/// that [`TxErrorPayload::from_info`] can recover from any string it is
+/// embedded in
+impl fmt::Display for TxErrorPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoded = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{} ({}{})", self.message, TX_ERROR_PAYLOAD_TAG, encoded)
+    }
 }
 
 #[cfg(test)]
@@ -322,6 +402,41 @@ mod test_process_tx {
     }
 }
 
+#[cfg(test)]
+mod test_tx_error_payload {
+    use super::*;
+
+    /// Test that a payload can be recovered from an info string embedding
+    /// its display
+    #[test]
+    fn test_tx_error_payload_from_info() {
+        let payload = TxErrorPayload::new(
+            "UnbondError::UnbondAmountGreaterThanBond",
+            "Trying to withdraw more tokens",
+        )
+        .with_detail("amount", 20)
+        .with_detail("bond_amount", 10);
+        let info = format!("Failed running wasm with: {payload}. Gas used: 5");
+        assert_eq!(TxErrorPayload::from_info(&info), Some(payload));
+        assert_eq!(TxErrorPayload::from_info("Transaction is valid."), None);
+    }
+
+    /// Test that a failure doesn't override an event requiring special
+    /// replay protection handling
+    #[test]
+    fn test_sentinel_set_failed() {
+        let payload = TxErrorPayload::new("Other", "error");
+        let mut sentinel = TxSentinel::default();
+        sentinel.set_failed(payload.clone());
+        assert!(matches!(sentinel, TxSentinel::Failed(_)));
+
+        let mut sentinel = TxSentinel::default();
+        sentinel.set_invalid_commitment();
+        sentinel.set_failed(payload);
+        assert!(matches!(sentinel, TxSentinel::InvalidCommitment));
+    }
+}
+
 /// Test that process_tx correctly identifies a DecryptedTx
 /// with some unsigned data and returns an identical copy
 #[test]
@@ -71,7 +71,30 @@ pub struct Bond {
 }
 
 /// An unbond of a bond.
-pub type Unbond = Bond;
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Unbond {
+    /// Validator address
+    pub validator: Address,
+    /// The amount of tokens
+    pub amount: token::Amount,
+    /// Source address for unbonding from delegations. For unbonding from
+    /// self-bonds, the validator is also the source.
+    pub source: Option<Address>,
+    /// Withdraw the unbonded tokens automatically once they become
+    /// withdrawable
+    pub auto_withdraw: bool,
+}
 
 /// A withdrawal of an unbond.
 #[derive(
@@ -206,3 +229,77 @@ pub struct ConsensusKeyChange {
     /// The new consensus key
     pub consensus_key: common::PublicKey,
 }
+
+/// A change to the validator's Ethereum bridge hot and cold keys.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct EthKeysChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new Ethereum bridge hot key
+    pub eth_hot_key: secp256k1::PublicKey,
+    /// The new Ethereum bridge cold key
+    pub eth_cold_key: secp256k1::PublicKey,
+}
+
+/// A change to the validator's operator key.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct OperatorKeyChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new operator key, or `None` to remove the current one
+    pub operator_key: Option<common::PublicKey>,
+}
+
+/// A deposit of tokens into the PoS staking pool.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct PoolDeposit {
+    /// The depositor's address
+    pub owner: Address,
+    /// The amount of tokens
+    pub amount: token::Amount,
+}
+
+/// A redemption of PoS staking pool shares.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct PoolRedeem {
+    /// The depositor's address
+    pub owner: Address,
+    /// The number of redeemed shares
+    pub shares: token::Amount,
+}
@@ -7,10 +7,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::proto::{Data, Section, Signature, Tx, TxError};
 use crate::types::chain::ChainId;
+use crate::types::consensus_validator_set;
 use crate::types::key::*;
 use crate::types::transaction::{Digest, Sha256, TxType};
 use crate::types::vote_extensions::{
-    bridge_pool_roots, ethereum_events, validator_set_update,
+    bridge_pool_roots, ethereum_events, liveness, validator_set_update,
 };
 
 #[derive(
@@ -131,6 +132,15 @@ ethereum_tx_data_declare! {
         BridgePoolVext(bridge_pool_roots::SignedVext),
         /// Validator set update signed by some validator
         ValSetUpdateVext(validator_set_update::SignedVext),
+        /// Liveness observations contained in vote extensions that
+        /// are compressed before being included on chain
+        Liveness(liveness::VextDigest),
+        /// Liveness observations made by some validator
+        LivenessVext(liveness::SignedVext),
+        /// Updates of the consensus validator set communicated to CometBFT
+        ConsensusValidatorSetUpdates(
+            consensus_validator_set::ConsensusValidatorSetUpdates
+        ),
     }
 }
 
@@ -192,6 +202,9 @@ impl EthereumTxData {
             EthEventsVext,
             BridgePoolVext,
             ValSetUpdateVext,
+            Liveness,
+            LivenessVext,
+            ConsensusValidatorSetUpdates,
         }
     }
 
@@ -225,6 +238,18 @@ impl EthereumTxData {
                 BorshDeserialize::try_from_slice(data)
                     .map(EthereumTxData::ValSetUpdateVext)
             },
+            ProtocolTxType::Liveness => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::Liveness)
+            },
+            ProtocolTxType::LivenessVext => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::LivenessVext)
+            },
+            ProtocolTxType::ConsensusValidatorSetUpdates => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::ConsensusValidatorSetUpdates)
+            },
         };
         deserialize(data)
             .map_err(|err| TxError::Deserialization(err.to_string()))
@@ -257,6 +282,13 @@ pub enum ProtocolTxType {
     BridgePoolVext,
     /// Validator set update signed by some validator
     ValSetUpdateVext,
+    /// Liveness observations contained in vote extensions that
+    /// are compressed before being included on chain
+    Liveness,
+    /// Liveness observations made by some validator
+    LivenessVext,
+    /// Updates of the consensus validator set communicated to CometBFT
+    ConsensusValidatorSetUpdates,
 }
 
 impl ProtocolTxType {
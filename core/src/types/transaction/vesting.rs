@@ -0,0 +1,25 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::vesting::VestingSchedule;
+use crate::types::address::Address;
+
+/// A tx data type to give an account a vesting schedule
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct InitVesting {
+    /// The address funding the vesting schedule
+    pub source: Address,
+    /// The owner of the vesting account
+    pub owner: Address,
+    /// The vesting schedule
+    pub schedule: VestingSchedule,
+}
@@ -2,6 +2,7 @@
 
 pub mod bridge_pool_roots;
 pub mod ethereum_events;
+pub mod liveness;
 pub mod validator_set_update;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
@@ -20,4 +21,7 @@ pub struct VoteExtension {
     pub bridge_pool_root: Option<bridge_pool_roots::SignedVext>,
     /// Vote extension data related with validator set updates.
     pub validator_set_update: Option<validator_set_update::SignedVext>,
+    /// Vote extension data related with the liveness of consensus
+    /// validators.
+    pub liveness: Option<liveness::SignedVext>,
 }
@@ -1,13 +1,16 @@
 //! Contains types necessary for processing Ethereum events
 //! in vote extensions.
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
 
 use crate::proto::Signed;
 use crate::types::address::Address;
 use crate::types::ethereum_events::EthereumEvent;
+use crate::types::keccak::{keccak_hash, KeccakHash};
 use crate::types::key::common::{self, Signature};
 use crate::types::storage::BlockHeight;
 
@@ -136,6 +139,77 @@ impl VextDigest {
     }
 }
 
+/// Aggregates signed [`Vext`] instances into a [`VextDigest`].
+///
+/// Ethereum events are keyed on the keccak hash of their Borsh encoding,
+/// such that each distinct event is stored once, regardless of how many
+/// validators reported it. Only the first [`Vext`] issued by some validator
+/// at a given block height is taken into account.
+#[derive(Debug, Clone, Default)]
+pub struct EthEventsAggregator {
+    /// The signatures, signing address, and signing block height
+    /// of each aggregated [`Vext`]
+    signatures: HashMap<(Address, BlockHeight), Signature>,
+    /// The aggregated events, keyed on their keccak hash
+    events: BTreeMap<KeccakHash, MultiSignedEthEvent>,
+}
+
+impl EthEventsAggregator {
+    /// Create an empty [`EthEventsAggregator`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signed [`Vext`] to the aggregator.
+    ///
+    /// Returns `false`, leaving the aggregator untouched, if a [`Vext`]
+    /// signed by the same validator at the same block height had
+    /// already been added.
+    pub fn insert(&mut self, ext: Signed<Vext>) -> bool {
+        let signer = (ext.data.validator_addr, ext.data.block_height);
+        match self.signatures.entry(signer.clone()) {
+            Entry::Occupied(_) => return false,
+            Entry::Vacant(entry) => {
+                entry.insert(ext.sig);
+            }
+        }
+        for event in ext.data.ethereum_events {
+            let hash = keccak_hash(event.serialize_to_vec());
+            self.events
+                .entry(hash)
+                .or_insert_with(|| MultiSignedEthEvent {
+                    event,
+                    signers: BTreeSet::new(),
+                })
+                .signers
+                .insert(signer.clone());
+        }
+        true
+    }
+
+    /// Return the number of [`Vext`] instances aggregated so far.
+    #[inline]
+    pub fn num_signatures(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Return the number of distinct Ethereum events aggregated so far.
+    #[inline]
+    pub fn num_events(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Build a [`VextDigest`] from the aggregated [`Vext`] instances.
+    /// The events in the digest are ordered by their keccak hash.
+    pub fn into_digest(self) -> VextDigest {
+        VextDigest {
+            signatures: self.signatures,
+            events: self.events.into_values().collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,4 +342,112 @@ mod tests {
             }
         }
     }
+
+    /// Test that the same Ethereum events reported by several validators
+    /// are only aggregated once
+    #[test]
+    fn test_aggregator_dedupes_events() {
+        let sk_1 = key::testing::keypair_1();
+        let sk_2 = key::testing::keypair_2();
+        let validator_1 = address::testing::established_address_1();
+        let validator_2 = address::testing::established_address_2();
+        let height = BlockHeight(123);
+
+        let ev_1 = EthereumEvent::TransfersToNamada {
+            nonce: 1u64.into(),
+            transfers: vec![],
+        };
+        let ev_2 = EthereumEvent::TransfersToNamada {
+            nonce: 2u64.into(),
+            transfers: vec![],
+        };
+
+        let mut aggregator = EthEventsAggregator::new();
+        let ext_1 = Vext {
+            block_height: height,
+            validator_addr: validator_1.clone(),
+            ethereum_events: vec![ev_1.clone(), ev_2.clone()],
+        };
+        let ext_2 = Vext {
+            block_height: height,
+            validator_addr: validator_2.clone(),
+            ethereum_events: vec![ev_1.clone()],
+        };
+        assert!(aggregator.insert(ext_1.sign(&sk_1)));
+        assert!(aggregator.insert(ext_2.sign(&sk_2)));
+        assert_eq!(aggregator.num_signatures(), 2);
+        assert_eq!(aggregator.num_events(), 2);
+
+        let digest = aggregator.into_digest();
+        let signers_of = |event: &EthereumEvent| {
+            digest
+                .events
+                .iter()
+                .find(|ev| &ev.event == event)
+                .map(|ev| ev.signers.clone())
+                .expect("Test failed")
+        };
+        assert_eq!(
+            signers_of(&ev_1),
+            BTreeSet::from([
+                (validator_1.clone(), height),
+                (validator_2, height),
+            ])
+        );
+        assert_eq!(signers_of(&ev_2), BTreeSet::from([(validator_1, height)]));
+    }
+
+    /// Test that only the first vote extension of some validator at a
+    /// given block height is aggregated
+    #[test]
+    fn test_aggregator_dedupes_signatures() {
+        let sk = key::testing::keypair_1();
+        let validator = address::testing::established_address_1();
+        let height = BlockHeight(123);
+
+        let ev_1 = EthereumEvent::TransfersToNamada {
+            nonce: 1u64.into(),
+            transfers: vec![],
+        };
+        let ev_2 = EthereumEvent::TransfersToNamada {
+            nonce: 2u64.into(),
+            transfers: vec![],
+        };
+
+        let mut aggregator = EthEventsAggregator::new();
+        let first = Vext {
+            block_height: height,
+            validator_addr: validator.clone(),
+            ethereum_events: vec![ev_1],
+        }
+        .sign(&sk);
+        let second = Vext {
+            block_height: height,
+            validator_addr: validator.clone(),
+            ethereum_events: vec![ev_2.clone()],
+        }
+        .sign(&sk);
+        assert!(aggregator.insert(first.clone()));
+        assert!(!aggregator.insert(second));
+        assert_eq!(aggregator.num_signatures(), 1);
+        assert_eq!(aggregator.num_events(), 1);
+
+        // the same validator may still vote at another block height
+        let other_height = Vext {
+            block_height: BlockHeight(height.0 + 1),
+            validator_addr: validator,
+            ethereum_events: vec![ev_2],
+        }
+        .sign(&sk);
+        assert!(aggregator.insert(other_height.clone()));
+        assert_eq!(aggregator.num_signatures(), 2);
+        assert_eq!(aggregator.num_events(), 2);
+
+        // the digest decompresses back into the aggregated extensions
+        let mut decompressed = aggregator
+            .into_digest()
+            .decompress(BlockHeight(height.0 + 1));
+        decompressed.sort_by_key(|ext| ext.data.block_height);
+        assert_eq!(decompressed, vec![first, other_height]);
+    }
 }
@@ -0,0 +1,207 @@
+//! Contains types necessary for processing the liveness observations of
+//! consensus validators in vote extensions.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::proto::Signed;
+use crate::types::address::Address;
+use crate::types::key::common::{self, Signature};
+use crate::types::storage::BlockHeight;
+
+/// Type alias for a [`LivenessVext`].
+pub type Vext = LivenessVext;
+
+/// Represents a [`Vext`] signed by some validator, with
+/// a Namada protocol key.
+pub type SignedVext = Signed<Vext>;
+
+/// Represents the set of consensus validators observed to be offline
+/// by some validator.
+///
+/// This struct will be created and signed over by each consensus validator,
+/// to be included as a vote extension at the end of a Tendermint PreCommit
+/// phase.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct LivenessVext {
+    /// The block height for which this [`Vext`] was made.
+    pub block_height: BlockHeight,
+    /// The address of the validator that made the observations.
+    pub validator_addr: Address,
+    /// The consensus validators whose vote was missing from the
+    /// last block committed by this validator.
+    pub offline_validators: BTreeSet<Address>,
+}
+
+impl Vext {
+    /// Creates a [`Vext`] without any liveness observations.
+    pub fn empty(block_height: BlockHeight, validator_addr: Address) -> Self {
+        Self {
+            block_height,
+            validator_addr,
+            offline_validators: BTreeSet::new(),
+        }
+    }
+
+    /// Sign a [`Vext`] with a validator's `signing_key`,
+    /// and return the signed data.
+    pub fn sign(self, signing_key: &common::SecretKey) -> Signed<Self> {
+        Signed::new(signing_key, self)
+    }
+}
+
+/// Type alias for a [`LivenessVextDigest`].
+pub type VextDigest = LivenessVextDigest;
+
+/// Compresses a set of signed [`Vext`] instances, to save
+/// space on a block.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct LivenessVextDigest {
+    /// The signatures, signing address, and signing block height
+    /// of each [`Vext`]
+    pub signatures: HashMap<(Address, BlockHeight), Signature>,
+    /// The validators reported as offline, mapped to the validators
+    /// (and block heights) that reported them
+    pub observations: BTreeMap<Address, BTreeSet<(Address, BlockHeight)>>,
+}
+
+impl VextDigest {
+    /// Build a singleton [`VextDigest`], from the provided [`Vext`].
+    #[inline]
+    pub fn singleton(ext: Signed<Vext>) -> VextDigest {
+        let signer = (ext.data.validator_addr.clone(), ext.data.block_height);
+        VextDigest {
+            observations: ext
+                .data
+                .offline_validators
+                .into_iter()
+                .map(|offline| (offline, BTreeSet::from([signer.clone()])))
+                .collect(),
+            signatures: HashMap::from([(signer, ext.sig)]),
+        }
+    }
+
+    /// Decompresses a set of signed [`Vext`] instances.
+    pub fn decompress(self, _: BlockHeight) -> Vec<Signed<Vext>> {
+        let VextDigest {
+            signatures,
+            observations,
+        } = self;
+
+        let mut extensions = vec![];
+
+        for (validator, sig) in signatures.into_iter() {
+            let mut ext = Vext::empty(validator.1, validator.0.clone());
+
+            for (offline, signers) in observations.iter() {
+                if signers.contains(&validator) {
+                    ext.offline_validators.insert(offline.clone());
+                }
+            }
+
+            let signed = Signed::new_from(ext, sig);
+            extensions.push(signed);
+        }
+        extensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+    use crate::types::key;
+    use crate::types::key::RefTo;
+
+    /// Test decompression of a set of liveness observations
+    #[test]
+    fn test_decompress_liveness_observations() {
+        let sk_1 = key::testing::keypair_1();
+        let sk_2 = key::testing::keypair_2();
+
+        let last_block_height = BlockHeight(123);
+
+        let validator_1 = address::testing::established_address_1();
+        let validator_2 = address::testing::established_address_2();
+        let offline_1 = address::testing::established_address_3();
+        let offline_2 = address::testing::established_address_4();
+
+        // v1 saw both validators offline, while v2 only saw one of them
+        let ext_1 = Signed::new(&sk_1, {
+            let mut ext = Vext::empty(last_block_height, validator_1.clone());
+            ext.offline_validators.insert(offline_1.clone());
+            ext.offline_validators.insert(offline_2.clone());
+            ext
+        });
+        let ext_2 = Signed::new(&sk_2, {
+            let mut ext = Vext::empty(last_block_height, validator_2.clone());
+            ext.offline_validators.insert(offline_1.clone());
+            ext
+        });
+
+        let ext = vec![ext_1, ext_2];
+
+        let signatures: HashMap<_, _> = [
+            ((validator_1.clone(), last_block_height), ext[0].sig.clone()),
+            ((validator_2.clone(), last_block_height), ext[1].sig.clone()),
+        ]
+        .into_iter()
+        .collect();
+        let observations = BTreeMap::from([
+            (
+                offline_1,
+                BTreeSet::from([
+                    (validator_1.clone(), last_block_height),
+                    (validator_2, last_block_height),
+                ]),
+            ),
+            (
+                offline_2,
+                BTreeSet::from([(validator_1.clone(), last_block_height)]),
+            ),
+        ]);
+
+        let digest = VextDigest {
+            signatures,
+            observations,
+        };
+
+        let decompressed = digest.decompress(last_block_height);
+
+        assert_eq!(decompressed.len(), ext.len());
+        for vext in decompressed.into_iter() {
+            assert!(ext.contains(&vext));
+            if vext.data.validator_addr == validator_1 {
+                assert!(vext.verify(&sk_1.ref_to()).is_ok())
+            } else {
+                assert!(vext.verify(&sk_2.ref_to()).is_ok())
+            }
+        }
+    }
+
+    /// Test that a singleton digest decompresses back into its
+    /// original [`Vext`]
+    #[test]
+    fn test_liveness_singleton_roundtrip() {
+        let sk = key::testing::keypair_1();
+        let ext = Signed::new(&sk, {
+            let mut ext = Vext::empty(
+                BlockHeight(1),
+                address::testing::established_address_1(),
+            );
+            ext.offline_validators
+                .insert(address::testing::established_address_2());
+            ext
+        });
+
+        let decompressed =
+            VextDigest::singleton(ext.clone()).decompress(BlockHeight(1));
+
+        assert_eq!(decompressed, vec![ext]);
+    }
+}
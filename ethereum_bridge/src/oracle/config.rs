@@ -1,4 +1,5 @@
 //! Configuration for an oracle.
+use std::collections::BTreeMap;
 use std::num::NonZeroU64;
 
 use namada_core::types::ethereum_events::EthAddress;
@@ -16,6 +17,10 @@ pub struct Config {
     pub start_block: ethereum_structs::BlockHeight,
     /// The status of the Ethereum bridge (active / inactive)
     pub active: bool,
+    /// The minimum number of block confirmations the events transferring
+    /// some ERC20 token must have before being sent to the ledger, for the
+    /// tokens that require more than `min_confirmations`.
+    pub erc20_min_confirmations: BTreeMap<EthAddress, NonZeroU64>,
 }
 
 // TODO: this production Default implementation is temporary, there should be no
@@ -29,6 +34,7 @@ impl std::default::Default for Config {
             bridge_contract: EthAddress([0; 20]),
             start_block: 0.into(),
             active: true,
+            erc20_min_confirmations: BTreeMap::new(),
         }
     }
 }
@@ -1,4 +1,5 @@
 //! Parameters for configuring the Ethereum bridge
+use std::collections::BTreeMap;
 use std::num::NonZeroU64;
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -7,11 +8,12 @@ use namada_core::ledger::eth_bridge::storage::whitelist;
 use namada_core::ledger::storage;
 use namada_core::ledger::storage::types::encode;
 use namada_core::ledger::storage::WlStorage;
-use namada_core::ledger::storage_api::{StorageRead, StorageWrite};
+use namada_core::ledger::storage_api::{self, StorageRead, StorageWrite};
 use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::ethereum_structs;
 use namada_core::types::storage::Key;
 use namada_core::types::token::{DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES};
+use namada_core::types::voting_power::FractionalVotingPower;
 use serde::{Deserialize, Serialize};
 
 use crate::storage::eth_bridge_queries::{
@@ -36,6 +38,21 @@ pub struct Erc20WhitelistEntry {
     pub token_address: EthAddress,
     /// The token cap of the whitelisted ERC20 token.
     pub token_cap: DenominatedAmount,
+    /// The minimum number of confirmations the Ethereum events transferring
+    /// this token must reach, if different from the bridge's default.
+    #[serde(default)]
+    pub min_confirmations: Option<MinimumConfirmations>,
+}
+
+impl From<Erc20WhitelistEntry> for whitelist::WhitelistUpdate {
+    fn from(entry: Erc20WhitelistEntry) -> Self {
+        Self::Add {
+            token: entry.token_address,
+            cap: entry.token_cap.amount,
+            denom: entry.token_cap.denom,
+            min_confirmations: entry.min_confirmations.map(NonZeroU64::from),
+        }
+    }
 }
 
 /// Represents a configuration value for the minimum number of
@@ -156,6 +173,11 @@ pub struct EthereumBridgeParams {
     /// Minimum number of confirmations needed to trust an Ethereum branch.
     /// This must be at least one.
     pub min_confirmations: MinimumConfirmations,
+    /// The fraction of the total voting power that the validators who signed
+    /// the vote extensions in a digest must exceed, for a block proposal
+    /// including the digest to be accepted. Defaults to 2/3.
+    #[serde(default = "default_vext_digest_quorum")]
+    pub vext_digest_quorum: FractionalVotingPower,
     /// List of ERC20 token types whitelisted at genesis time.
     pub erc20_whitelist: Vec<Erc20WhitelistEntry>,
     /// The addresses of the Ethereum contracts that need to be directly known
@@ -163,6 +185,11 @@ pub struct EthereumBridgeParams {
     pub contracts: Contracts,
 }
 
+/// The default quorum of vote extension digests.
+pub fn default_vext_digest_quorum() -> FractionalVotingPower {
+    FractionalVotingPower::TWO_THIRDS
+}
+
 impl EthereumBridgeParams {
     /// Initialize the Ethereum bridge parameters in storage.
     ///
@@ -177,6 +204,7 @@ impl EthereumBridgeParams {
             erc20_whitelist,
             eth_start_height,
             min_confirmations,
+            vext_digest_quorum,
             contracts:
                 Contracts {
                     native_erc20,
@@ -188,6 +216,7 @@ impl EthereumBridgeParams {
         let native_erc20_key = bridge_storage::native_erc20_key();
         let bridge_contract_key = bridge_storage::bridge_contract_key();
         let eth_start_height_key = bridge_storage::eth_start_height_key();
+        let vext_digest_quorum_key = bridge_storage::vext_digest_quorum_key();
         wl_storage
             .write_bytes(
                 &active_key,
@@ -206,9 +235,13 @@ impl EthereumBridgeParams {
         wl_storage
             .write_bytes(&eth_start_height_key, encode(eth_start_height))
             .unwrap();
+        wl_storage
+            .write_bytes(&vext_digest_quorum_key, encode(vext_digest_quorum))
+            .unwrap();
         for Erc20WhitelistEntry {
             token_address: addr,
             token_cap: DenominatedAmount { amount: cap, denom },
+            min_confirmations: token_min_confirmations,
         } in erc20_whitelist
         {
             if addr == native_erc20
@@ -240,6 +273,17 @@ impl EthereumBridgeParams {
             }
             .into();
             wl_storage.write_bytes(&key, encode(denom)).unwrap();
+
+            if let Some(token_min_confirmations) = token_min_confirmations {
+                let key = whitelist::Key {
+                    asset: *addr,
+                    suffix: whitelist::KeyType::MinConfirmations,
+                }
+                .into();
+                wl_storage
+                    .write_bytes(&key, encode(token_min_confirmations))
+                    .unwrap();
+            }
         }
         // Initialize the storage for the Ethereum Bridge VP.
         vp::init_storage(wl_storage);
@@ -260,6 +304,10 @@ pub struct EthereumOracleConfig {
     /// The addresses of the Ethereum contracts that need to be directly known
     /// by validators.
     pub contracts: Contracts,
+    /// Minimum number of confirmations needed to trust the Ethereum events
+    /// transferring some whitelisted ERC20 token, for the tokens that
+    /// override the bridge's default.
+    pub erc20_min_confirmations: BTreeMap<EthAddress, MinimumConfirmations>,
 }
 
 impl From<EthereumBridgeParams> for EthereumOracleConfig {
@@ -268,12 +316,20 @@ impl From<EthereumBridgeParams> for EthereumOracleConfig {
             eth_start_height,
             min_confirmations,
             contracts,
+            erc20_whitelist,
             ..
         } = config;
+        let erc20_min_confirmations = erc20_whitelist
+            .into_iter()
+            .filter_map(|entry| {
+                Some((entry.token_address, entry.min_confirmations?))
+            })
+            .collect();
         Self {
             eth_start_height,
             min_confirmations,
             contracts,
+            erc20_min_confirmations,
         }
     }
 }
@@ -311,6 +367,13 @@ impl EthereumOracleConfig {
         let native_erc20 = must_read_key(wl_storage, &native_erc20_key);
         let bridge_contract = must_read_key(wl_storage, &bridge_contract_key);
         let eth_start_height = must_read_key(wl_storage, &eth_start_height_key);
+        let erc20_min_confirmations = read_erc20_min_confirmations(wl_storage)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Could not read the minimum confirmations of the \
+                     whitelisted ERC20 tokens: {err:?}"
+                )
+            });
 
         Some(Self {
             eth_start_height,
@@ -319,10 +382,35 @@ impl EthereumOracleConfig {
                 native_erc20,
                 bridge: bridge_contract,
             },
+            erc20_min_confirmations,
         })
     }
 }
 
+/// Read the minimum number of confirmations of each whitelisted ERC20 token
+/// that overrides the bridge's default.
+pub fn read_erc20_min_confirmations<S>(
+    storage: &S,
+) -> storage_api::Result<BTreeMap<EthAddress, MinimumConfirmations>>
+where
+    S: StorageRead,
+{
+    storage_api::iter_prefix_bytes(storage, &whitelist::prefix())?
+        .filter_map(|entry| {
+            let (key, bytes) = match entry {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err)),
+            };
+            let asset = whitelist::min_confirmations_key_asset(&key)?;
+            Some(
+                MinimumConfirmations::try_from_slice(&bytes)
+                    .map(|min_confirmations| (asset, min_confirmations))
+                    .map_err(storage_api::Error::new),
+            )
+        })
+        .collect()
+}
+
 /// Get the Ethereum address for wNam from storage, if possible
 pub fn read_native_erc20_address<S>(storage: &S) -> Result<EthAddress>
 where
@@ -371,6 +459,7 @@ mod tests {
     use eyre::Result;
     use namada_core::ledger::storage::testing::TestWlStorage;
     use namada_core::types::ethereum_events::EthAddress;
+    use namada_core::types::token::Amount;
 
     use super::*;
     use crate::parameters::{
@@ -387,6 +476,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: MinimumConfirmations::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
             contracts: Contracts {
                 native_erc20: EthAddress([42; 20]),
                 bridge: UpgradeableContract {
@@ -409,6 +499,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: MinimumConfirmations::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
             contracts: Contracts {
                 native_erc20: EthAddress([42; 20]),
                 bridge: UpgradeableContract {
@@ -425,6 +516,47 @@ mod tests {
         assert_eq!(config, read);
     }
 
+    /// Test that the minimum confirmations of whitelisted ERC20 tokens are
+    /// written to storage, and read back into the oracle config.
+    #[test]
+    fn test_ethereum_bridge_config_erc20_min_confirmations() {
+        let mut wl_storage = TestWlStorage::default();
+        let min_confirmations =
+            MinimumConfirmations::from(NonZeroU64::new(150).unwrap());
+        let config = EthereumBridgeParams {
+            erc20_whitelist: vec![
+                Erc20WhitelistEntry {
+                    token_address: EthAddress([1; 20]),
+                    token_cap: Amount::max().native_denominated(),
+                    min_confirmations: Some(min_confirmations),
+                },
+                Erc20WhitelistEntry {
+                    token_address: EthAddress([2; 20]),
+                    token_cap: Amount::max().native_denominated(),
+                    min_confirmations: None,
+                },
+            ],
+            eth_start_height: Default::default(),
+            min_confirmations: MinimumConfirmations::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
+            contracts: Contracts {
+                native_erc20: EthAddress([42; 20]),
+                bridge: UpgradeableContract {
+                    address: EthAddress([23; 20]),
+                    version: ContractVersion::default(),
+                },
+            },
+        };
+        config.init_storage(&mut wl_storage);
+
+        let read = EthereumOracleConfig::read(&wl_storage).unwrap();
+        assert_eq!(
+            read.erc20_min_confirmations,
+            BTreeMap::from([(EthAddress([1; 20]), min_confirmations)])
+        );
+        assert_eq!(read, EthereumOracleConfig::from(config));
+    }
+
     #[test]
     fn test_ethereum_bridge_config_uninitialized() {
         let wl_storage = TestWlStorage::default();
@@ -441,6 +573,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: MinimumConfirmations::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
             contracts: Contracts {
                 native_erc20: EthAddress([42; 20]),
                 bridge: UpgradeableContract {
@@ -7,7 +7,8 @@ use borsh::BorshDeserialize;
 use eyre::{Result, WrapErr};
 use namada_core::hints;
 use namada_core::ledger::eth_bridge::storage::bridge_pool::{
-    get_nonce_key, is_pending_transfer_key, BRIDGE_POOL_ADDRESS,
+    get_nonce_key, get_transfer_outcome_key, is_pending_transfer_key,
+    BRIDGE_POOL_ADDRESS,
 };
 use namada_core::ledger::eth_bridge::storage::{
     self as bridge_storage, wrapped_erc20s,
@@ -18,8 +19,9 @@ use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, WlStorage, DB};
 use namada_core::ledger::storage_api::{StorageRead, StorageWrite};
 use namada_core::types::address::Address;
+use namada_core::types::eth_abi::Encode;
 use namada_core::types::eth_bridge_pool::{
-    PendingTransfer, TransferToEthereumKind,
+    PendingTransfer, TransferToEthereumKind, TransferToEthereumOutcome,
 };
 use namada_core::types::ethereum_events::{
     EthAddress, EthereumEvent, TransferToEthereum, TransferToNamada,
@@ -359,8 +361,15 @@ where
             balance.spend(&pending_transfer.gas_fee.amount);
         })?;
         wl_storage.delete(&key)?;
+        // record the outcome of the transfer, for clients tracking it
+        let outcome_key =
+            get_transfer_outcome_key(&pending_transfer.keccak256());
+        let relayed_at =
+            TransferToEthereumOutcome::Relayed(wl_storage.storage.block.height);
+        wl_storage.write(&outcome_key, relayed_at)?;
         _ = pending_keys.remove(&key);
         _ = changed_keys.insert(key);
+        _ = changed_keys.insert(outcome_key);
         _ = changed_keys.insert(pool_balance_key);
         _ = changed_keys.insert(relayer_rewards_key);
     }
@@ -430,6 +439,13 @@ where
     wl_storage.delete(&key)?;
     _ = changed_keys.insert(key);
 
+    // Record that the transfer has expired
+    let outcome_key = get_transfer_outcome_key(&transfer.keccak256());
+    let expired_at =
+        TransferToEthereumOutcome::Expired(wl_storage.storage.block.height);
+    wl_storage.write(&outcome_key, expired_at)?;
+    _ = changed_keys.insert(outcome_key);
+
     Ok(changed_keys)
 }
 
@@ -1060,6 +1076,20 @@ mod tests {
         assert!(changed_keys.remove(&pool_nam_balance_key));
         assert!(changed_keys.remove(&pool_erc_balance_key));
         assert!(changed_keys.remove(&get_nonce_key()));
+        for transfer in &pending_transfers {
+            let outcome_key = get_transfer_outcome_key(&transfer.keccak256());
+            assert!(changed_keys.remove(&outcome_key));
+            let outcome: TransferToEthereumOutcome = wl_storage
+                .read(&outcome_key)
+                .expect("Test failed")
+                .expect("Test failed");
+            assert_eq!(
+                outcome,
+                TransferToEthereumOutcome::Relayed(
+                    wl_storage.storage.block.height
+                )
+            );
+        }
         assert!(changed_keys.iter().all(|k| pending_keys.contains(k)));
 
         let prefix = BRIDGE_POOL_ADDRESS.to_db_key().into();
@@ -1068,8 +1098,9 @@ mod tests {
                 .iter_prefix(&prefix)
                 .expect("Test failed")
                 .count(),
-            // NOTE: we should have one write -- the bridge pool nonce update
-            1
+            // NOTE: we should have one write for the bridge pool nonce
+            // update, plus one for the outcome of each relayed transfer
+            1 + pending_transfers.len()
         );
         let relayer_nam_balance = Amount::try_from_slice(
             &wl_storage
@@ -1171,9 +1202,23 @@ mod tests {
                 .expect("Test failed")
                 .count(),
             // NOTE: we should have two writes -- one of them being
-            // the bridge pool nonce update
-            2
+            // the bridge pool nonce update -- plus one for the outcome
+            // of each expired transfer
+            2 + pending_transfers.len()
         );
+        for transfer in &pending_transfers {
+            let outcome_key = get_transfer_outcome_key(&transfer.keccak256());
+            let outcome: TransferToEthereumOutcome = wl_storage
+                .read(&outcome_key)
+                .expect("Test failed")
+                .expect("Test failed");
+            assert_eq!(
+                outcome,
+                TransferToEthereumOutcome::Expired(
+                    wl_storage.storage.block.height
+                )
+            );
+        }
 
         // Check the gas fee
         let expected = pending_transfers
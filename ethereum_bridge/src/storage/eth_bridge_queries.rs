@@ -1,7 +1,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::hints;
 use namada_core::ledger::eth_bridge::storage::{
-    active_key, bridge_pool, whitelist,
+    active_key, bridge_pool, vext_digest_quorum_key, whitelist,
 };
 use namada_core::ledger::storage;
 use namada_core::ledger::storage::{StoreType, WlStorage};
@@ -182,6 +182,17 @@ where
         }
     }
 
+    /// Get the fraction of the total voting power that the validators
+    /// who signed the vote extensions in a digest must exceed, for the
+    /// digest to be included in a block. Defaults to 2/3, if the
+    /// parameter has not been written to storage.
+    pub fn get_vext_digest_quorum(self) -> FractionalVotingPower {
+        self.wl_storage
+            .read(&vext_digest_quorum_key())
+            .expect("Reading the vote extension digest quorum shouldn't fail.")
+            .unwrap_or(FractionalVotingPower::TWO_THIRDS)
+    }
+
     /// Get the nonce of the next transfers to Namada event to be processed.
     pub fn get_next_nam_transfers_nonce(self) -> Uint {
         self.wl_storage
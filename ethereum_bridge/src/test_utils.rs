@@ -15,6 +15,7 @@ use namada_core::types::keccak::KeccakHash;
 use namada_core::types::key::{self, protocol_pk_key, RefTo};
 use namada_core::types::storage::{BlockHeight, Key};
 use namada_core::types::token;
+use namada_core::types::voting_power::FractionalVotingPower;
 use namada_proof_of_stake::parameters::OwnedPosParams;
 use namada_proof_of_stake::pos_queries::PosQueries;
 use namada_proof_of_stake::types::GenesisValidator;
@@ -110,6 +111,7 @@ pub fn bootstrap_ethereum_bridge(
             // be violated is if we construct values
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
+        vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
         }),
         contracts: Contracts {
             native_erc20: wnam(),
@@ -80,6 +80,7 @@ pub fn transaction(attr: TokenStream, input: TokenStream) -> TokenStream {
 
             if let Err(err) = #ident(&mut ctx, tx_data) {
                 namada_tx_prelude::debug_log!("Transaction error: {}", err);
+                namada_tx_prelude::set_tx_error(&err);
                 // crash the transaction to abort
                 panic!();
             }
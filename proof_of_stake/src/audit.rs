@@ -0,0 +1,201 @@
+//! PoS token accounting audit. With the `audit` feature, the ledger checks at
+//! the end of every block that the PoS account's balance of the staking token
+//! matches the tokens that the account owes to bond holders, i.e. the bonded
+//! and unbonded-but-not-withdrawn tokens, less the slashed tokens, plus the
+//! rewards that have not been claimed yet.
+
+use namada_core::ledger::storage_api::{self, token, StorageRead};
+use namada_core::types::storage::Epoch;
+use namada_core::types::token::Amount;
+
+use crate::{
+    bonds_and_unbonds, is_slash_processing_in_progress, query_reward_tokens,
+    staking_token_address, ArithmeticError, ADDRESS,
+};
+
+/// The breakdown of the PoS account's balance computed by [`audit_pos`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosAuditReport {
+    /// The epoch in which the audit was done
+    pub epoch: Epoch,
+    /// The actual balance of the staking token in the PoS account
+    pub balance: Amount,
+    /// The sum of all the bonds
+    pub bonded: Amount,
+    /// The sum of all the unbonds that have not been withdrawn yet
+    pub unbonded: Amount,
+    /// The sum of the tokens slashed from the bonds and unbonds
+    pub slashed: Amount,
+    /// The sum of the rewards that have not been claimed yet
+    pub unclaimed_rewards: Amount,
+}
+
+impl PosAuditReport {
+    /// The balance that the PoS account is expected to hold
+    pub fn expected_balance(&self) -> Result<Amount, ArithmeticError> {
+        self.bonded
+            .checked_add(self.unbonded)
+            .and_then(|owed| owed.checked_add(self.unclaimed_rewards))
+            .ok_or(ArithmeticError::Overflow("PoS audit expected balance"))?
+            .checked_sub(self.slashed)
+            .ok_or(ArithmeticError::Underflow("PoS audit expected balance"))
+    }
+
+    /// Check if the actual balance matches the expected balance
+    pub fn is_balanced(&self) -> Result<bool, ArithmeticError> {
+        Ok(self.balance == self.expected_balance()?)
+    }
+}
+
+/// Compute the breakdown of the PoS account's balance in the current epoch.
+/// Returns `None` while slashes are being processed, as the bonds and unbonds
+/// of the validators that are yet to be processed are not slashed yet.
+pub fn audit_pos<S>(storage: &S) -> storage_api::Result<Option<PosAuditReport>>
+where
+    S: StorageRead,
+{
+    if is_slash_processing_in_progress(storage)? {
+        return Ok(None);
+    }
+    let epoch = storage.get_block_epoch()?;
+    let staking_token = staking_token_address(storage);
+    let balance = token::read_balance(storage, &staking_token, &ADDRESS)?;
+
+    let mut bonded = Amount::zero();
+    let mut unbonded = Amount::zero();
+    let mut slashed = Amount::zero();
+    let mut unclaimed_rewards = Amount::zero();
+    for (bond_id, details) in bonds_and_unbonds(storage, None, None)? {
+        for bond in details.bonds {
+            bonded = checked_sum(bonded, bond.amount, "PoS audit bonded")?;
+            slashed = checked_sum(
+                slashed,
+                bond.slashed_amount.unwrap_or_default(),
+                "PoS audit slashed",
+            )?;
+        }
+        for unbond in details.unbonds {
+            unbonded =
+                checked_sum(unbonded, unbond.amount, "PoS audit unbonded")?;
+            slashed = checked_sum(
+                slashed,
+                unbond.slashed_amount.unwrap_or_default(),
+                "PoS audit slashed",
+            )?;
+        }
+        let rewards = query_reward_tokens(
+            storage,
+            Some(&bond_id.source),
+            &bond_id.validator,
+            epoch,
+        )?;
+        unclaimed_rewards =
+            checked_sum(unclaimed_rewards, rewards, "PoS audit rewards")?;
+    }
+
+    Ok(Some(PosAuditReport {
+        epoch,
+        balance,
+        bonded,
+        unbonded,
+        slashed,
+        unclaimed_rewards,
+    }))
+}
+
+fn checked_sum(
+    acc: Amount,
+    amount: Amount,
+    context: &'static str,
+) -> Result<Amount, ArithmeticError> {
+    acc.checked_add(amount)
+        .ok_or(ArithmeticError::Overflow(context))
+}
+
+#[cfg(test)]
+mod test_audit {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::ledger::storage_api::token::credit_tokens;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada_core::types::dec::Dec;
+    use namada_core::types::key::testing::{keypair_1, keypair_2};
+    use namada_core::types::key::{self, RefTo};
+
+    use super::*;
+    use crate::parameters::OwnedPosParams;
+    use crate::test_utils::test_init_genesis;
+    use crate::types::GenesisValidator;
+    use crate::{bond_tokens, unbond_tokens};
+
+    /// Test that the audit balances after bonding and unbonding and catches
+    /// tokens credited to the PoS account outside of a bond
+    #[test]
+    fn test_audit_pos() {
+        let mut s = TestWlStorage::default();
+        let validator = established_address_1();
+        let genesis_validator = GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::native_whole(1_000),
+            consensus_key: keypair_1().ref_to(),
+            protocol_key: keypair_2().ref_to(),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(1, 1)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+        };
+        test_init_genesis(
+            &mut s,
+            OwnedPosParams::default(),
+            [genesis_validator].into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+
+        let report = audit_pos(&s).unwrap().unwrap();
+        assert_eq!(report.bonded, token::Amount::native_whole(1_000));
+        assert!(report.is_balanced().unwrap());
+
+        let delegator = established_address_2();
+        let staking_token = staking_token_address(&s);
+        let amount = token::Amount::native_whole(100);
+        credit_tokens(&mut s, &staking_token, &delegator, amount).unwrap();
+        let current_epoch = s.storage.block.epoch;
+        bond_tokens(
+            &mut s,
+            Some(&delegator),
+            &validator,
+            amount,
+            current_epoch,
+            None,
+        )
+        .unwrap();
+        unbond_tokens(
+            &mut s,
+            Some(&delegator),
+            &validator,
+            token::Amount::native_whole(40),
+            current_epoch,
+            false,
+        )
+        .unwrap();
+
+        let report = audit_pos(&s).unwrap().unwrap();
+        assert_eq!(report.bonded, token::Amount::native_whole(1_060));
+        assert_eq!(report.unbonded, token::Amount::native_whole(40));
+        assert!(report.is_balanced().unwrap());
+
+        credit_tokens(&mut s, &staking_token, &ADDRESS, amount).unwrap();
+        let report = audit_pos(&s).unwrap().unwrap();
+        assert!(!report.is_balanced().unwrap());
+    }
+}
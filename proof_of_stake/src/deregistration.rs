@@ -0,0 +1,364 @@
+//! Deregistration of the validators that have had no stake and no bonds for
+//! longer than the `validator_deregistration_len` parameter. Without it, every
+//! validator that has ever been registered, e.g. by the operators that churn
+//! through a testnet, would keep its data in storage forever.
+
+use std::collections::{BTreeSet, HashSet};
+
+use namada_core::ledger::storage_api::{self, StorageRead, StorageWrite};
+use namada_core::types::address::Address;
+use namada_core::types::key::{
+    common, protocol_pk_key, tm_consensus_key_raw_hash,
+};
+use namada_core::types::storage::Epoch;
+
+use crate::parameters::PosParams;
+use crate::storage::{
+    bonds_prefix, is_bond_key, is_unbond_key, unbonds_prefix,
+    validator_address_raw_hash_key, validator_prefix,
+};
+use crate::types::ValidatorState;
+use crate::{
+    below_threshold_validator_set_handle, find_all_enqueued_slashes,
+    find_validator_by_raw_hash, read_all_validator_addresses, read_pos_params,
+    read_validator_stake, validator_addresses_handle,
+    validator_consensus_key_handle, validator_slashes_handle,
+    validator_state_handle, zero_stake_validators_handle,
+};
+
+/// Track the validators that have no stake and deregister the ones that have
+/// had no stake for longer than the `validator_deregistration_len` parameter
+/// and that have no bonds, no unbonds and no enqueued slashes left. This must
+/// be applied at an epoch transition, after the validator sets have been
+/// copied into the pipeline epoch. Returns the deregistered validators.
+///
+/// A deregistered validator is removed from the set of all validators and
+/// from the below-threshold validator set from the current epoch onwards, and
+/// its keys, commission, state, stake deltas, metadata and slashes older than
+/// the evidence window are purged. Its consensus keys stay in the set of used
+/// consensus keys, so that they can never be reused by another validator.
+pub fn deregister_zero_stake_validators<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Vec<Address>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    if params.validator_deregistration_len == 0 {
+        return Ok(vec![]);
+    }
+
+    let zero_stake_validators = zero_stake_validators_handle();
+    let mut due = BTreeSet::new();
+    let validators: BTreeSet<Address> =
+        read_all_validator_addresses(storage, current_epoch)?
+            .into_iter()
+            .collect();
+    for validator in validators {
+        let without_stake =
+            is_without_stake(storage, &params, &validator, current_epoch)?;
+        let since = zero_stake_validators.get(storage, &validator)?;
+        match (without_stake, since) {
+            (true, None) => {
+                zero_stake_validators.insert(
+                    storage,
+                    validator,
+                    current_epoch,
+                )?;
+            }
+            (true, Some(since)) => {
+                if since + params.validator_deregistration_len < current_epoch {
+                    due.insert(validator);
+                }
+            }
+            (false, Some(_)) => {
+                zero_stake_validators.remove(storage, &validator)?;
+            }
+            (false, None) => {}
+        }
+    }
+    if due.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // The validators that still have some bonds or unbonds, e.g. fully
+    // slashed bonds or unbonds that haven't been withdrawn yet, or some slashes
+    // to be processed are kept, as their data is still needed
+    let mut in_use = HashSet::<Address>::new();
+    for result in storage_api::iter_prefix_bytes(storage, &bonds_prefix())? {
+        let (key, _val_bytes) = result?;
+        if let Some((bond_id, _start)) = is_bond_key(&key) {
+            in_use.insert(bond_id.validator);
+        }
+    }
+    for result in storage_api::iter_prefix_bytes(storage, &unbonds_prefix())? {
+        let (key, _val_bytes) = result?;
+        if let Some((bond_id, _start, _withdraw)) = is_unbond_key(&key) {
+            in_use.insert(bond_id.validator);
+        }
+    }
+    in_use.extend(
+        find_all_enqueued_slashes(storage, current_epoch.prev())?.into_keys(),
+    );
+
+    let mut deregistered = vec![];
+    for validator in due {
+        if in_use.contains(&validator) {
+            continue;
+        }
+        deregister_validator(storage, &params, &validator, current_epoch)?;
+        deregistered.push(validator);
+    }
+    Ok(deregistered)
+}
+
+/// Check if the validator has no stake in any epoch from the current epoch up
+/// to the pipeline epoch. A validator in the consensus or below-capacity sets
+/// has a position in the set, so it's never considered without stake, which
+/// may only happen if the validator stake threshold is zero.
+fn is_without_stake<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
+        if !read_validator_stake(storage, params, validator, epoch)?.is_zero() {
+            return Ok(false);
+        }
+        let state =
+            validator_state_handle(validator).get(storage, epoch, params)?;
+        if matches!(
+            state,
+            Some(ValidatorState::Consensus | ValidatorState::BelowCapacity)
+        ) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Remove the validator from the validator sets and purge its data
+fn deregister_validator<S>(
+    storage: &mut S,
+    params: &PosParams,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::info!(
+        "Deregistering validator {validator} in epoch {current_epoch}, as it \
+         has had no stake for more than {} epochs",
+        params.validator_deregistration_len
+    );
+
+    for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
+        validator_addresses_handle()
+            .at(&epoch)
+            .remove(storage, validator)?;
+        below_threshold_validator_set_handle()
+            .at(&epoch)
+            .remove(storage, validator)?;
+    }
+
+    // The look-ups of the validator's address by its consensus keys
+    let consensus_keys = validator_consensus_key_handle(validator)
+        .get_data_handler()
+        .iter(storage)?
+        .map(|res| res.map(|(_epoch, key)| key))
+        .collect::<storage_api::Result<Vec<common::PublicKey>>>()?;
+    for consensus_key in consensus_keys {
+        let raw_hash = tm_consensus_key_raw_hash(&consensus_key);
+        if find_validator_by_raw_hash(storage, &raw_hash)?.as_ref()
+            == Some(validator)
+        {
+            storage.delete(&validator_address_raw_hash_key(raw_hash))?;
+        }
+    }
+
+    // All the data under the validator's prefix and its protocol key
+    for prefix in [validator_prefix(validator), protocol_pk_key(validator)] {
+        let keys = storage_api::iter_prefix_bytes(storage, &prefix)?
+            .map(|res| res.map(|(key, _val_bytes)| key))
+            .collect::<storage_api::Result<Vec<_>>>()?;
+        for key in keys {
+            storage.delete(&key)?;
+        }
+    }
+
+    // The slashes that are older than the evidence window
+    let slashes = validator_slashes_handle(validator);
+    let mut kept = vec![];
+    while let Some(slash) = slashes.pop(storage)? {
+        if slash.epoch + params.evidence_max_age_epochs >= current_epoch {
+            kept.push(slash);
+        }
+    }
+    for slash in kept.into_iter().rev() {
+        slashes.push(storage, slash)?;
+    }
+
+    zero_stake_validators_handle().remove(storage, validator)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_deregistration {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada_core::types::dec::Dec;
+    use namada_core::types::key::{self, RefTo};
+    use namada_core::types::token;
+
+    use super::*;
+    use crate::parameters::OwnedPosParams;
+    use crate::test_utils::test_init_genesis;
+    use crate::types::GenesisValidator;
+    use crate::{
+        compute_and_store_total_consensus_stake,
+        copy_validator_sets_and_positions, is_validator, unbond_tokens,
+        withdraw_tokens,
+    };
+
+    /// Test that a validator that has unbonded all its stake is deregistered
+    /// only once it's been without stake for longer than the deregistration
+    /// length and its unbonds have been withdrawn
+    #[test]
+    fn test_deregister_zero_stake_validators() {
+        let mut s = TestWlStorage::default();
+        let params = OwnedPosParams {
+            unbonding_len: 4,
+            evidence_max_age_epochs: 2,
+            validator_deregistration_len: 3,
+            ..Default::default()
+        };
+        let validator = established_address_1();
+        let leaving_validator = established_address_2();
+        let tokens = token::Amount::native_whole(1_000);
+        let genesis_validator = |address: Address| GenesisValidator {
+            address,
+            tokens,
+            consensus_key: key::common::PublicKey::Ed25519(
+                key::testing::gen_keypair::<key::ed25519::SigScheme>().ref_to(),
+            ),
+            protocol_key: key::common::PublicKey::Ed25519(
+                key::testing::gen_keypair::<key::ed25519::SigScheme>().ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(1, 1)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+        };
+        let leaving_genesis_validator =
+            genesis_validator(leaving_validator.clone());
+        let leaving_raw_hash =
+            tm_consensus_key_raw_hash(&leaving_genesis_validator.consensus_key);
+        let params = test_init_genesis(
+            &mut s,
+            params,
+            [
+                genesis_validator(validator.clone()),
+                leaving_genesis_validator,
+            ]
+            .into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+
+        // Unbond all of the leaving validator's stake
+        let mut current_epoch = s.storage.block.epoch;
+        unbond_tokens(
+            &mut s,
+            None,
+            &leaving_validator,
+            tokens,
+            current_epoch,
+            false,
+        )
+        .unwrap();
+        let withdrawable_epoch =
+            current_epoch + params.withdrawable_epoch_offset();
+
+        let advance_epoch = |s: &mut TestWlStorage| {
+            s.storage.block.epoch = s.storage.block.epoch.next();
+            let current_epoch = s.storage.block.epoch;
+            compute_and_store_total_consensus_stake(s, current_epoch).unwrap();
+            copy_validator_sets_and_positions(
+                s,
+                &params,
+                current_epoch,
+                current_epoch + params.pipeline_len,
+            )
+            .unwrap();
+            let deregistered =
+                deregister_zero_stake_validators(s, current_epoch).unwrap();
+            (current_epoch, deregistered)
+        };
+
+        // The validator is without stake from the pipeline epoch, but it's
+        // kept until its unbond is withdrawn
+        while current_epoch < withdrawable_epoch {
+            let (epoch, deregistered) = advance_epoch(&mut s);
+            assert!(deregistered.is_empty());
+            current_epoch = epoch;
+        }
+        assert!(is_validator(&s, &leaving_validator).unwrap());
+        assert_eq!(
+            zero_stake_validators_handle()
+                .get(&s, &leaving_validator)
+                .unwrap(),
+            Some(Epoch(params.pipeline_len))
+        );
+
+        withdraw_tokens(&mut s, None, &leaving_validator, current_epoch)
+            .unwrap();
+        let (current_epoch, deregistered) = advance_epoch(&mut s);
+        assert_eq!(deregistered, vec![leaving_validator.clone()]);
+
+        assert!(!is_validator(&s, &leaving_validator).unwrap());
+        assert!(
+            find_validator_by_raw_hash(&s, &leaving_raw_hash)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            zero_stake_validators_handle()
+                .get(&s, &leaving_validator)
+                .unwrap()
+                .is_none()
+        );
+        for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
+            let validators = read_all_validator_addresses(&s, epoch).unwrap();
+            assert!(!validators.contains(&leaving_validator));
+            assert!(validators.contains(&validator));
+            assert!(
+                !below_threshold_validator_set_handle()
+                    .at(&epoch)
+                    .contains(&s, &leaving_validator)
+                    .unwrap()
+            );
+        }
+        assert!(
+            validator_state_handle(&leaving_validator)
+                .get(&s, current_epoch, &params)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! [`Epoched`] and [`EpochedDelta`] are structures for data that is set for
 //! future (and possibly past) epochs.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::{cmp, ops};
@@ -53,7 +53,7 @@ impl<Data, FutureEpochs, PastEpochs, SON>
     Epoched<Data, FutureEpochs, PastEpochs, SON>
 where
     FutureEpochs: EpochOffset,
-    PastEpochs: EpochOffset,
+    PastEpochs: PruningStrategy,
 {
     /// Open the handle
     pub fn open(key: storage::Key) -> Self {
@@ -70,7 +70,7 @@ where
 impl<Data, FutureEpochs, PastEpochs> Epoched<Data, FutureEpochs, PastEpochs>
 where
     FutureEpochs: EpochOffset,
-    PastEpochs: EpochOffset,
+    PastEpochs: PruningStrategy,
     Data: BorshSerialize + BorshDeserialize + 'static + Debug,
 {
     /// Initialize new epoched data. Sets the head to the given value.
@@ -110,23 +110,24 @@ where
                 // Epoch can be a lot greater than the epoch where
                 // a value is recorded, we check the upper bound
                 // epoch of the LazyMap data
-                let mut epoch = std::cmp::min(epoch, future_most_epoch);
-                loop {
-                    let res = data_handler.get(storage, &epoch)?;
-                    match res {
-                        Some(_) => return Ok(res),
-                        None => {
-                            if epoch.0 > 0
-                                && epoch
-                                    > Self::sub_past_epochs(params, last_update)
-                            {
-                                epoch = Epoch(epoch.0 - 1);
-                            } else {
-                                return Ok(None);
-                            }
-                        }
-                    }
+                let epoch = std::cmp::min(epoch, future_most_epoch);
+                let oldest_epoch = std::cmp::min(
+                    Self::sub_past_epochs(params, last_update),
+                    epoch,
+                );
+                // The most recent value recorded in the range is the last one
+                // of the ordered iteration
+                let mut value = None;
+                for res in iter_data_in_range(
+                    &data_handler,
+                    storage,
+                    oldest_epoch,
+                    epoch,
+                )? {
+                    let (_epoch, data) = res?;
+                    value = Some(data);
                 }
+                Ok(value)
             }
         }
     }
@@ -182,9 +183,8 @@ where
         if let (Some(last_update), Some(oldest_epoch)) =
             (last_update, oldest_epoch)
         {
-            let oldest_to_keep = current_epoch
-                .checked_sub(PastEpochs::value(params))
-                .unwrap_or_default();
+            let oldest_to_keep =
+                PastEpochs::oldest_epoch_to_keep(params, current_epoch);
             if oldest_epoch < oldest_to_keep {
                 let diff = u64::from(oldest_to_keep - oldest_epoch);
                 // Go through the epochs before the expected oldest epoch and
@@ -256,7 +256,8 @@ where
         storage.read(&key)
     }
 
-    fn get_data_handler(&self) -> LazyMap<Epoch, Data> {
+    /// Get handle to the raw LazyMap data
+    pub fn get_data_handler(&self) -> LazyMap<Epoch, Data> {
         let key = self
             .storage_prefix
             .push(&LAZY_MAP_SUB_KEY.to_owned())
@@ -265,9 +266,7 @@ where
     }
 
     fn sub_past_epochs(params: &PosParams, epoch: Epoch) -> Epoch {
-        epoch
-            .checked_sub(PastEpochs::value(params))
-            .unwrap_or_default()
+        PastEpochs::oldest_epoch_to_keep(params, epoch)
     }
 
     fn get_oldest_epoch_storage_key(&self) -> storage::Key {
@@ -304,7 +303,7 @@ impl<Data, FutureEpochs, PastEpochs>
     Epoched<Data, FutureEpochs, PastEpochs, collections::Nested>
 where
     FutureEpochs: EpochOffset,
-    PastEpochs: EpochOffset,
+    PastEpochs: PruningStrategy,
     Data: LazyCollection + Debug,
 {
     /// Get the inner LazyCollection value by the outer key
@@ -396,9 +395,7 @@ where
     }
 
     fn sub_past_epochs(params: &PosParams, epoch: Epoch) -> Epoch {
-        epoch
-            .checked_sub(PastEpochs::value(params))
-            .unwrap_or_default()
+        PastEpochs::oldest_epoch_to_keep(params, epoch)
     }
 
     /// Update data by removing old epochs
@@ -416,9 +413,8 @@ where
         if let (Some(last_update), Some(oldest_epoch)) =
             (last_update, oldest_epoch)
         {
-            let oldest_to_keep = current_epoch
-                .checked_sub(PastEpochs::value(params))
-                .unwrap_or_default();
+            let oldest_to_keep =
+                PastEpochs::oldest_epoch_to_keep(params, current_epoch);
             if oldest_epoch < oldest_to_keep {
                 let diff = u64::from(oldest_to_keep - oldest_epoch);
                 // Go through the epochs before the expected oldest epoch and
@@ -463,7 +459,7 @@ impl<Data, FutureEpochs, PastEpochs>
     EpochedDelta<Data, FutureEpochs, PastEpochs>
 where
     FutureEpochs: EpochOffset,
-    PastEpochs: EpochOffset,
+    PastEpochs: PruningStrategy,
     Data: BorshSerialize
         + BorshDeserialize
         + ops::Add<Output = Data>
@@ -534,14 +530,16 @@ where
                 let epoch = std::cmp::min(epoch, future_most_epoch);
 
                 let mut sum: Option<Data> = None;
-                for ep in (start_epoch.0)..=(epoch.0) {
-                    if let Some(delta) =
-                        data_handler.get(storage, &Epoch(ep))?
-                    {
-                        match sum.as_mut() {
-                            Some(sum) => *sum += delta,
-                            None => sum = Some(delta),
-                        }
+                for res in iter_data_in_range(
+                    &data_handler,
+                    storage,
+                    start_epoch,
+                    epoch,
+                )? {
+                    let (_epoch, delta) = res?;
+                    match sum.as_mut() {
+                        Some(sum) => *sum += delta,
+                        None => sum = Some(delta),
                     }
                 }
                 Ok(sum)
@@ -549,6 +547,51 @@ where
         }
     }
 
+    /// Get the sums of the delta values up through each epoch of the given
+    /// inclusive range, reading every delta only once. The epochs whose deltas
+    /// have been pruned are not included.
+    pub fn get_sums_in_range<S>(
+        &self,
+        storage: &S,
+        from_epoch: Epoch,
+        to_epoch: Epoch,
+        params: &PosParams,
+    ) -> storage_api::Result<BTreeMap<Epoch, Data>>
+    where
+        S: StorageRead,
+        Data: Clone + Default,
+    {
+        let mut sums = BTreeMap::new();
+        let last_update = match self.get_last_update(storage)? {
+            Some(last_update) => last_update,
+            None => return Ok(sums),
+        };
+        let data_handler = self.get_data_handler();
+        let start_epoch = Self::sub_past_epochs(params, last_update);
+        let future_most_epoch = last_update + FutureEpochs::value(params);
+
+        // There are no deltas past the future-most epoch
+        let mut deltas: BTreeMap<Epoch, Data> = iter_data_in_range(
+            &data_handler,
+            storage,
+            start_epoch,
+            cmp::min(to_epoch, future_most_epoch),
+        )?
+        .collect::<storage_api::Result<_>>()?;
+
+        let mut sum = Data::default();
+        for ep in (start_epoch.0)..=(to_epoch.0) {
+            let epoch = Epoch(ep);
+            if let Some(delta) = deltas.remove(&epoch) {
+                sum += delta;
+            }
+            if epoch >= from_epoch {
+                sums.insert(epoch, sum.clone());
+            }
+        }
+        Ok(sums)
+    }
+
     /// Initialize or add a value to the current delta value at the given epoch
     /// offset.
     pub fn add<S>(
@@ -605,7 +648,7 @@ where
     /// Update the data associated with epochs to trim historical data, if
     /// needed. Any value with epoch before the oldest epoch to be kept is
     /// added to the value at the oldest stored epoch that is kept.
-    fn update_data<S>(
+    pub fn update_data<S>(
         &self,
         storage: &mut S,
         params: &PosParams,
@@ -619,9 +662,8 @@ where
         if let (Some(last_update), Some(oldest_epoch)) =
             (last_update, oldest_epoch)
         {
-            let oldest_to_keep = current_epoch
-                .checked_sub(PastEpochs::value(params))
-                .unwrap_or_default();
+            let oldest_to_keep =
+                PastEpochs::oldest_epoch_to_keep(params, current_epoch);
             if oldest_epoch < oldest_to_keep {
                 let diff = u64::from(oldest_to_keep - oldest_epoch);
                 // Go through the epochs before the expected oldest epoch and
@@ -722,9 +764,7 @@ where
     }
 
     fn sub_past_epochs(params: &PosParams, epoch: Epoch) -> Epoch {
-        epoch
-            .checked_sub(PastEpochs::value(params))
-            .unwrap_or_default()
+        PastEpochs::oldest_epoch_to_keep(params, epoch)
     }
 
     fn get_oldest_epoch_storage_key(&self) -> storage::Key {
@@ -757,6 +797,27 @@ where
     }
 }
 
+/// Iterate the data of an epoched lazy map recorded in the given inclusive
+/// range of epochs, in ascending order of the epochs. The epoch key segments
+/// are encoded in an order-preserving way, so this is a single prefix
+/// iteration that seeks to the start of the range and stops past its end,
+/// instead of a look-up for every epoch of the range.
+fn iter_data_in_range<'iter, Data>(
+    data_handler: &LazyMap<Epoch, Data>,
+    storage: &'iter impl StorageRead,
+    start: Epoch,
+    end: Epoch,
+) -> storage_api::Result<
+    impl Iterator<Item = storage_api::Result<(Epoch, Data)>> + 'iter,
+>
+where
+    Data: BorshSerialize + BorshDeserialize + 'static + Debug,
+{
+    Ok(data_handler
+        .iter_from(storage, &start)?
+        .take_while(move |res| !matches!(res, Ok((epoch, _)) if *epoch > end)))
+}
+
 /// Zero offset
 #[derive(
     Debug,
@@ -1041,6 +1102,53 @@ impl EpochOffset for OffsetMaxProposalPeriodOrSlashProcessingLenPlus {
     }
 }
 
+/// Offset at the validator sets retention length.
+#[derive(
+    Debug,
+    Clone,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub struct OffsetValidatorSetsRetentionLen;
+impl EpochOffset for OffsetValidatorSetsRetentionLen {
+    fn value(params: &PosParams) -> u64 {
+        params.validator_sets_retention_len
+    }
+
+    fn dyn_offset() -> DynEpochOffset {
+        DynEpochOffset::ValidatorSetsRetentionLen
+    }
+}
+
+/// Offset at the max proposal period, plus the validator sets retention
+/// length.
+#[derive(
+    Debug,
+    Clone,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub struct OffsetConsensusValidatorSetsRetentionLen;
+impl EpochOffset for OffsetConsensusValidatorSetsRetentionLen {
+    fn value(params: &PosParams) -> u64 {
+        params.max_proposal_period + params.validator_sets_retention_len
+    }
+
+    fn dyn_offset() -> DynEpochOffset {
+        DynEpochOffset::ConsensusValidatorSetsRetentionLen
+    }
+}
+
 /// Offset length dynamic choice.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DynEpochOffset {
@@ -1070,6 +1178,11 @@ pub enum DynEpochOffset {
     /// Offset at the larger of max proposal period or slash processing delay,
     /// plus the default num past epochs
     MaxProposalPeriodOrSlashProcessingLenPlus,
+    /// Offset at the validator sets retention length
+    ValidatorSetsRetentionLen,
+    /// Offset at the max proposal period plus the validator sets retention
+    /// length
+    ConsensusValidatorSetsRetentionLen,
     /// Offset of the max u64 value
     MaxU64,
 }
@@ -1085,6 +1198,54 @@ pub trait EpochOffset:
     fn dyn_offset() -> DynEpochOffset;
 }
 
+/// Retention policy of the historical data of an epoched data structure. The
+/// data older than the oldest epoch to keep is trimmed whenever the structure
+/// is updated or pruned.
+pub trait PruningStrategy {
+    /// Find the oldest epoch whose data must be kept at the given current
+    /// epoch.
+    fn oldest_epoch_to_keep(params: &PosParams, current_epoch: Epoch) -> Epoch;
+}
+
+/// Keep the data of the last `N` epochs, where `N` is the value of the given
+/// offset.
+#[derive(Debug, Clone)]
+pub struct KeepLastN<Offset> {
+    offset: PhantomData<Offset>,
+}
+impl<Offset> PruningStrategy for KeepLastN<Offset>
+where
+    Offset: EpochOffset,
+{
+    fn oldest_epoch_to_keep(params: &PosParams, current_epoch: Epoch) -> Epoch {
+        current_epoch
+            .checked_sub(Offset::value(params))
+            .unwrap_or_default()
+    }
+}
+
+/// Keep the data for as long as it may still affect the processing of
+/// slashes, as given by [`PosParams::oldest_epoch_to_retain`].
+#[derive(Debug, Clone)]
+pub struct KeepUntilProcessed;
+impl PruningStrategy for KeepUntilProcessed {
+    fn oldest_epoch_to_keep(params: &PosParams, current_epoch: Epoch) -> Epoch {
+        params.oldest_epoch_to_retain(current_epoch)
+    }
+}
+
+/// Never prune the data.
+#[derive(Debug, Clone)]
+pub struct NeverPrune;
+impl PruningStrategy for NeverPrune {
+    fn oldest_epoch_to_keep(
+        _params: &PosParams,
+        _current_epoch: Epoch,
+    ) -> Epoch {
+        Epoch::default()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use namada_core::ledger::storage::testing::TestWlStorage;
@@ -1101,10 +1262,11 @@ mod test {
         let mut s = init_storage()?;
 
         let key_prefix = storage::Key::parse("test").unwrap();
-        let epoched =
-            Epoched::<u64, OffsetPipelineLen, OffsetPipelineLen>::open(
-                key_prefix,
-            );
+        let epoched = Epoched::<
+            u64,
+            OffsetPipelineLen,
+            KeepLastN<OffsetPipelineLen>,
+        >::open(key_prefix);
         let data_handler = epoched.get_data_handler();
         assert!(epoched.get_last_update(&s)?.is_none());
         assert!(epoched.get_oldest_epoch(&s)?.is_none());
@@ -1173,7 +1335,7 @@ mod test {
 
         let key_prefix = storage::Key::parse("test").unwrap();
         let epoched =
-            Epoched::<u64, OffsetPipelineLen, OffsetMaxU64>::open(key_prefix);
+            Epoched::<u64, OffsetPipelineLen, NeverPrune>::open(key_prefix);
         let data_handler = epoched.get_data_handler();
         assert!(epoched.get_last_update(&s)?.is_none());
         assert!(epoched.get_oldest_epoch(&s)?.is_none());
@@ -1240,10 +1402,11 @@ mod test {
         let mut s = init_storage()?;
 
         let key_prefix = storage::Key::parse("test").unwrap();
-        let epoched =
-            EpochedDelta::<u64, OffsetPipelineLen, OffsetPipelineLen>::open(
-                key_prefix,
-            );
+        let epoched = EpochedDelta::<
+            u64,
+            OffsetPipelineLen,
+            KeepLastN<OffsetPipelineLen>,
+        >::open(key_prefix);
         let data_handler = epoched.get_data_handler();
         assert!(epoched.get_last_update(&s)?.is_none());
         assert!(epoched.get_oldest_epoch(&s)?.is_none());
@@ -1314,10 +1477,9 @@ mod test {
 
         // Nothing should ever get trimmed
         let key_prefix = storage::Key::parse("test").unwrap();
-        let epoched =
-            EpochedDelta::<u64, OffsetPipelineLen, OffsetMaxU64>::open(
-                key_prefix,
-            );
+        let epoched = EpochedDelta::<u64, OffsetPipelineLen, NeverPrune>::open(
+            key_prefix,
+        );
         let data_handler = epoched.get_data_handler();
         assert!(epoched.get_last_update(&s)?.is_none());
         assert!(epoched.get_oldest_epoch(&s)?.is_none());
@@ -1390,9 +1552,94 @@ mod test {
         assert_eq!(data_handler.get(&s, &Epoch(9))?, None);
         assert_eq!(data_handler.get(&s, &Epoch(10))?, Some(21));
 
+        // The sums in a range match the sums read one epoch at a time
+        let params = read_pos_params(&s)?;
+        let sums =
+            epoched.get_sums_in_range(&s, Epoch(2), Epoch(20), &params)?;
+        assert_eq!(
+            sums.keys().copied().collect::<Vec<_>>(),
+            Epoch(2).iter_range(19).collect::<Vec<_>>()
+        );
+        assert_eq!(sums[&Epoch(2)], 6);
+        assert_eq!(sums[&Epoch(9)], 15);
+        assert_eq!(sums[&Epoch(20)], 36);
+        for (epoch, sum) in sums {
+            assert_eq!(epoched.get_sum(&s, epoch, &params)?, Some(sum));
+        }
+
         Ok(())
     }
 
+    /// Test the look-ups of sparse data recorded at epochs far apart, whose
+    /// keys are ordered across multiple bytes of the encoded epochs
+    #[test]
+    fn test_epoched_sparse_lookups() -> storage_api::Result<()> {
+        let mut s = init_storage()?;
+        let params = read_pos_params(&s)?;
+
+        let epoched = Epoched::<u64, OffsetPipelineLen, NeverPrune>::open(
+            storage::Key::parse("test").unwrap(),
+        );
+        epoched.init_at_genesis(&mut s, 1, Epoch(0))?;
+        epoched.set(&mut s, 2, Epoch(300), 0)?;
+        epoched.set(&mut s, 3, Epoch(1_000), 0)?;
+        assert_eq!(epoched.get(&s, Epoch(0), &params)?, Some(1));
+        assert_eq!(epoched.get(&s, Epoch(299), &params)?, Some(1));
+        assert_eq!(epoched.get(&s, Epoch(300), &params)?, Some(2));
+        assert_eq!(epoched.get(&s, Epoch(999), &params)?, Some(2));
+        assert_eq!(epoched.get(&s, Epoch(1_000), &params)?, Some(3));
+        assert_eq!(epoched.get(&s, Epoch(5_000), &params)?, Some(3));
+
+        let epoched = EpochedDelta::<u64, OffsetPipelineLen, NeverPrune>::open(
+            storage::Key::parse("test_delta").unwrap(),
+        );
+        epoched.init_at_genesis(&mut s, 1, Epoch(0))?;
+        epoched.add(&mut s, 2, Epoch(300), 0)?;
+        epoched.add(&mut s, 4, Epoch(1_000), 0)?;
+        assert_eq!(epoched.get_sum(&s, Epoch(0), &params)?, Some(1));
+        assert_eq!(epoched.get_sum(&s, Epoch(299), &params)?, Some(1));
+        assert_eq!(epoched.get_sum(&s, Epoch(300), &params)?, Some(3));
+        assert_eq!(epoched.get_sum(&s, Epoch(999), &params)?, Some(3));
+        assert_eq!(epoched.get_sum(&s, Epoch(1_000), &params)?, Some(7));
+        assert_eq!(epoched.get_sum(&s, Epoch(5_000), &params)?, Some(7));
+
+        let sums =
+            epoched.get_sums_in_range(&s, Epoch(299), Epoch(1_001), &params)?;
+        assert_eq!(sums[&Epoch(299)], 1);
+        assert_eq!(sums[&Epoch(300)], 3);
+        assert_eq!(sums[&Epoch(1_001)], 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pruning_strategies() {
+        let params = PosParams::default();
+        let epoch = Epoch(100);
+
+        assert_eq!(
+            KeepLastN::<OffsetPipelineLen>::oldest_epoch_to_keep(
+                &params, epoch
+            ),
+            epoch - params.pipeline_len
+        );
+        assert_eq!(
+            KeepLastN::<OffsetPipelineLen>::oldest_epoch_to_keep(
+                &params,
+                Epoch::default()
+            ),
+            Epoch::default()
+        );
+        assert_eq!(
+            KeepUntilProcessed::oldest_epoch_to_keep(&params, epoch),
+            params.oldest_epoch_to_retain(epoch)
+        );
+        assert_eq!(
+            NeverPrune::oldest_epoch_to_keep(&params, epoch),
+            Epoch::default()
+        );
+    }
+
     fn init_storage() -> storage_api::Result<TestWlStorage> {
         let mut s = TestWlStorage::default();
         let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
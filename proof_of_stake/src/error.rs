@@ -5,8 +5,10 @@ use namada_core::ledger::storage_api;
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::storage::Epoch;
+use namada_core::types::transaction::TxErrorPayload;
 use thiserror::Error;
 
+use crate::parameters::ParamError;
 use crate::rewards;
 use crate::types::{BondId, ValidatorState};
 
@@ -17,6 +19,13 @@ pub enum GenesisError {
     VotingPowerOverflow(TryFromIntError),
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum WriteParamsError {
+    #[error("Invalid PoS parameters: {0:?}")]
+    InvalidParams(Vec<ParamError>),
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum InflationError {
@@ -55,7 +64,7 @@ pub enum UnbondError {
     #[error("No bond could be found")]
     NoBondFound,
     #[error(
-        "Trying to withdraw more tokens ({0}) than the amount bonded ({0})"
+        "Trying to withdraw more tokens ({0}) than the amount bonded ({1})"
     )]
     UnbondAmountGreaterThanBond(String, String),
     #[error("No bonds found for the validator {0}")]
@@ -75,6 +84,10 @@ pub enum WithdrawError {
     NoUnbondFound(BondId),
     #[error("No unbond may be withdrawn yet for {0}")]
     NoWithdrawableUnbond(BondId),
+    #[error(
+        "No unbond may be withdrawn while slashes are still being processed"
+    )]
+    SlashProcessingInProgress,
 }
 
 #[allow(missing_docs)]
@@ -90,6 +103,15 @@ pub enum SlashError {
     VotingPowerOverflow(TryFromIntError),
     #[error("Unexpected negative stake {0} for validator {1}")]
     NegativeStake(i128, Address),
+    #[error("Unknown slash type: {0}")]
+    UnknownSlashType(String),
+    #[error("Unknown slash status: {0}")]
+    UnknownSlashStatus(String),
+    #[error(
+        "Evidence from epoch {0} is too old to be slashed for in epoch {1}, \
+         the maximum evidence age is {2} epochs"
+    )]
+    EvidenceTooOld(Epoch, Epoch, u64),
 }
 
 #[allow(missing_docs)]
@@ -177,6 +199,59 @@ pub enum ConsensusKeyChangeError {
     MustBeEd25519,
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ConsensusKeyReservationError {
+    #[error("The consensus key is required to be an Ed25519 key")]
+    MustBeEd25519,
+    #[error("The consensus key is already used by a validator")]
+    AlreadyUsed,
+    #[error("The consensus key is reserved by {0} until the end of epoch {1}")]
+    ReservedByOther(Address, Epoch),
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum EthKeysChangeError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error("The Ethereum {0} key is required to be a Secp256k1 key")]
+    MustBeSecp256k1(&'static str),
+    #[error("The Ethereum hot and cold keys must be different")]
+    HotEqCold,
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum OperatorKeyChangeError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum StakingPoolError {
+    #[error(
+        "The staking pool shares of {owner} are lower than the redeemed \
+         {shares}"
+    )]
+    InsufficientShares { owner: Address, shares: String },
+    #[error(
+        "The staking pool doesn't have enough unbondable tokens to redeem \
+         {0}"
+    )]
+    InsufficientBonds(String),
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ArithmeticError {
+    #[error("Overflow while updating the {0}")]
+    Overflow(&'static str),
+    #[error("Underflow while updating the {0}")]
+    Underflow(&'static str),
+}
+
 impl From<BecomeValidatorError> for storage_api::Error {
     fn from(err: BecomeValidatorError) -> Self {
         Self::new(err)
@@ -248,3 +323,155 @@ impl From<ConsensusKeyChangeError> for storage_api::Error {
         Self::new(err)
     }
 }
+
+impl From<ConsensusKeyReservationError> for storage_api::Error {
+    fn from(err: ConsensusKeyReservationError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<EthKeysChangeError> for storage_api::Error {
+    fn from(err: EthKeysChangeError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<OperatorKeyChangeError> for storage_api::Error {
+    fn from(err: OperatorKeyChangeError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<StakingPoolError> for storage_api::Error {
+    fn from(err: StakingPoolError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<ArithmeticError> for storage_api::Error {
+    fn from(err: ArithmeticError) -> Self {
+        Self::new(err)
+    }
+}
+
+/// Describe the given error with a machine-readable payload, if it is one of
+/// the PoS errors that a transaction may fail with
+pub fn tx_error_payload(err: &storage_api::Error) -> Option<TxErrorPayload> {
+    if let Some(err) = err.downcast_ref::<UnbondError>() {
+        let payload = payload("UnbondError", err);
+        return Some(match err {
+            UnbondError::UnbondAmountGreaterThanBond(amount, bond_amount) => {
+                payload
+                    .with_detail("amount", amount)
+                    .with_detail("bond_amount", bond_amount)
+            }
+            UnbondError::ValidatorHasNoBonds(validator)
+            | UnbondError::ValidatorHasNoVotingPower(validator)
+            | UnbondError::ValidatorIsFrozen(validator) => {
+                payload.with_detail("validator", validator)
+            }
+            UnbondError::NoBondFound | UnbondError::VotingPowerOverflow(_) => {
+                payload
+            }
+        });
+    }
+    if let Some(err) = err.downcast_ref::<WithdrawError>() {
+        let (WithdrawError::NoUnbondFound(bond_id)
+        | WithdrawError::NoWithdrawableUnbond(bond_id)) = err;
+        return Some(
+            payload("WithdrawError", err)
+                .with_detail("source", &bond_id.source)
+                .with_detail("validator", &bond_id.validator),
+        );
+    }
+    if let Some(err) = err.downcast_ref::<UnjailValidatorError>() {
+        let payload = payload("UnjailValidatorError", err);
+        return Some(match err {
+            UnjailValidatorError::NotAValidator(validator) => {
+                payload.with_detail("validator", validator)
+            }
+            UnjailValidatorError::NotJailed(validator, epoch) => payload
+                .with_detail("validator", validator)
+                .with_detail("epoch", epoch),
+            UnjailValidatorError::NotEligible(
+                validator,
+                eligible_epoch,
+                current_epoch,
+            ) => payload
+                .with_detail("validator", validator)
+                .with_detail("eligible_epoch", eligible_epoch)
+                .with_detail("current_epoch", current_epoch),
+        });
+    }
+
+    macro_rules! try_downcast {
+        ($($ty:ident),* $(,)?) => {
+            $(
+                if let Some(err) = err.downcast_ref::<$ty>() {
+                    return Some(payload(stringify!($ty), err));
+                }
+            )*
+        };
+    }
+    try_downcast!(
+        BecomeValidatorError,
+        BondError,
+        CommissionRateChangeError,
+        RedelegationError,
+        DeactivationError,
+        ReactivationError,
+        MetadataError,
+        ConsensusKeyChangeError,
+        ConsensusKeyReservationError,
+        EthKeysChangeError,
+        OperatorKeyChangeError,
+        StakingPoolError,
+        ArithmeticError,
+    );
+    None
+}
+
+/// Describe a PoS error of the given type, with a kind named after its
+/// variant
+fn payload<E>(ty: &str, err: &E) -> TxErrorPayload
+where
+    E: std::error::Error + std::fmt::Debug,
+{
+    let debug = format!("{err:?}");
+    let variant = debug
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or_default();
+    TxErrorPayload::new(format!("{ty}::{variant}"), err)
+}
+
+#[cfg(test)]
+mod test_error {
+    use namada_core::types::address::testing::established_address_1;
+
+    use super::*;
+
+    /// Test that the PoS errors are described with their kind and values
+    #[test]
+    fn test_tx_error_payload() {
+        let err =
+            storage_api::Error::from(UnbondError::UnbondAmountGreaterThanBond(
+                "20".to_string(),
+                "10".to_string(),
+            ));
+        let payload = tx_error_payload(&err).unwrap();
+        assert_eq!(payload.kind, "UnbondError::UnbondAmountGreaterThanBond");
+        assert_eq!(payload.details["amount"], "20");
+        assert_eq!(payload.details["bond_amount"], "10");
+
+        let validator = established_address_1();
+        let err =
+            storage_api::Error::from(BondError::InactiveValidator(validator));
+        let payload = tx_error_payload(&err).unwrap();
+        assert_eq!(payload.kind, "BondError::InactiveValidator");
+        assert!(payload.details.is_empty());
+
+        let err = storage_api::Error::new_const("some error");
+        assert_eq!(tx_error_payload(&err), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Export of the whole PoS state at some epoch. The export is meant to be
+//! inspected by auditors and diffed between nodes when investigating a
+//! consensus split, so it only contains ordered collections and its Borsh
+//! encoding is the same on every node with the same state.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+
+use crate::parameters::PosParams;
+use crate::storage::{
+    bonds_prefix, is_bond_key, is_unbond_key, unbonds_prefix,
+};
+use crate::types::{BondId, Slash, ValidatorState, WeightedValidator};
+use crate::{
+    find_all_enqueued_slashes, find_validator_slashes,
+    read_all_validator_addresses,
+    read_below_capacity_validator_set_addresses_with_stake,
+    read_below_threshold_validator_set_addresses,
+    read_consensus_validator_set_addresses_with_stake, total_deltas_handle,
+    validator_deltas_handle, PosQueryCtx,
+};
+
+/// The PoS state at some epoch, as exported by [`export_pos_state`]
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct PosStateExport {
+    /// The epoch of the export
+    pub epoch: Epoch,
+    /// The PoS parameters
+    pub params: PosParams,
+    /// The total stake of all the validators in the epoch
+    pub total_stake: token::Amount,
+    /// The raw total stake deltas
+    pub total_deltas: BTreeMap<Epoch, token::Change>,
+    /// The consensus validator set in the epoch
+    pub consensus_set: BTreeSet<WeightedValidator>,
+    /// The below-capacity validator set in the epoch
+    pub below_capacity_set: BTreeSet<WeightedValidator>,
+    /// The below-threshold validator set in the epoch
+    pub below_threshold_set: BTreeSet<Address>,
+    /// The state of every validator known in the epoch
+    pub validators: BTreeMap<Address, ValidatorExport>,
+    /// The raw bond deltas, keyed by their start epochs
+    pub bonds: BTreeMap<BondId, BTreeMap<Epoch, token::Amount>>,
+    /// The raw unbonds, keyed by their start and withdrawable epochs
+    pub unbonds: BTreeMap<BondId, BTreeMap<(Epoch, Epoch), token::Amount>>,
+    /// The slashes enqueued to be processed after the epoch, keyed by their
+    /// processing epochs
+    pub enqueued_slashes: BTreeMap<Address, BTreeMap<Epoch, Vec<Slash>>>,
+}
+
+/// The state of a single validator in a [`PosStateExport`]
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct ValidatorExport {
+    /// The state of the validator in the epoch
+    pub state: Option<ValidatorState>,
+    /// The stake of the validator in the epoch
+    pub stake: token::Amount,
+    /// The raw stake deltas of the validator
+    pub deltas: BTreeMap<Epoch, token::Change>,
+    /// The commission rate of the validator in the epoch
+    pub commission_rate: Option<Dec>,
+    /// The max commission rate change per epoch of the validator
+    pub max_commission_rate_change: Option<Dec>,
+    /// The processed slashes of the validator
+    pub slashes: Vec<Slash>,
+}
+
+/// Export the whole PoS state at the given epoch. The epoched data is read at
+/// the given epoch, while the raw deltas, bonds, unbonds and slashes are
+/// exported as they are currently stored.
+pub fn export_pos_state<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<PosStateExport>
+where
+    S: StorageRead,
+{
+    let pos = PosQueryCtx::new(storage)?;
+
+    let mut validators = BTreeMap::new();
+    for validator in read_all_validator_addresses(storage, epoch)? {
+        let commission = pos.validator_commission(&validator, epoch)?;
+        let export = ValidatorExport {
+            state: pos.validator_state(&validator, epoch)?,
+            stake: pos.read_validator_stake(&validator, epoch)?,
+            deltas: validator_deltas_handle(&validator)
+                .get_data_handler()
+                .iter(storage)?
+                .collect::<storage_api::Result<_>>()?,
+            commission_rate: commission
+                .as_ref()
+                .map(|commission| commission.commission_rate),
+            max_commission_rate_change: commission
+                .map(|commission| commission.max_commission_change_per_epoch),
+            slashes: find_validator_slashes(storage, &validator)?,
+        };
+        validators.insert(validator, export);
+    }
+
+    let mut bonds = BTreeMap::<BondId, BTreeMap<Epoch, token::Amount>>::new();
+    for result in storage_api::iter_prefix_bytes(storage, &bonds_prefix())? {
+        let (key, val_bytes) = result?;
+        // The prefix also matches the `last_update` of the epoched bonds
+        if let Some((bond_id, start)) = is_bond_key(&key) {
+            let amount = token::Amount::try_from_slice(&val_bytes)
+                .map_err(storage_api::Error::new)?;
+            bonds.entry(bond_id).or_default().insert(start, amount);
+        }
+    }
+
+    let mut unbonds =
+        BTreeMap::<BondId, BTreeMap<(Epoch, Epoch), token::Amount>>::new();
+    for result in storage_api::iter_prefix_bytes(storage, &unbonds_prefix())? {
+        let (key, val_bytes) = result?;
+        if let Some((bond_id, start, withdraw)) = is_unbond_key(&key) {
+            let amount = token::Amount::try_from_slice(&val_bytes)
+                .map_err(storage_api::Error::new)?;
+            unbonds
+                .entry(bond_id)
+                .or_default()
+                .insert((start, withdraw), amount);
+        }
+    }
+
+    Ok(PosStateExport {
+        epoch,
+        total_stake: pos.read_total_stake(epoch)?,
+        total_deltas: total_deltas_handle()
+            .get_data_handler()
+            .iter(storage)?
+            .collect::<storage_api::Result<_>>()?,
+        consensus_set: read_consensus_validator_set_addresses_with_stake(
+            storage, epoch,
+        )?,
+        below_capacity_set:
+            read_below_capacity_validator_set_addresses_with_stake(
+                storage, epoch,
+            )?,
+        below_threshold_set: read_below_threshold_validator_set_addresses(
+            storage, epoch,
+        )?
+        .into_iter()
+        .collect(),
+        validators,
+        bonds,
+        unbonds,
+        enqueued_slashes: find_all_enqueued_slashes(storage, epoch)?
+            .into_iter()
+            .collect(),
+        params: pos.params().clone(),
+    })
+}
+
+#[cfg(test)]
+mod test_export {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::types::address::testing::established_address_1;
+    use namada_core::types::key::testing::{keypair_1, keypair_2};
+    use namada_core::types::key::{self, RefTo};
+
+    use super::*;
+    use crate::parameters::OwnedPosParams;
+    use crate::test_utils::test_init_genesis;
+    use crate::types::GenesisValidator;
+
+    /// Test that the export contains the genesis validator and its self-bond
+    /// and that exporting the same state twice gives the same encoding
+    #[test]
+    fn test_export_pos_state() {
+        let mut s = TestWlStorage::default();
+        let validator = established_address_1();
+        let tokens = token::Amount::native_whole(1_000);
+        let genesis_validator = GenesisValidator {
+            address: validator.clone(),
+            tokens,
+            consensus_key: keypair_1().ref_to(),
+            protocol_key: keypair_2().ref_to(),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(1, 1)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+        };
+        test_init_genesis(
+            &mut s,
+            OwnedPosParams::default(),
+            [genesis_validator].into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+
+        let export = export_pos_state(&s, Epoch::default()).unwrap();
+        assert_eq!(export.total_stake, tokens);
+        assert_eq!(export.consensus_set.len(), 1);
+        assert!(export.below_capacity_set.is_empty());
+        assert!(export.below_threshold_set.is_empty());
+
+        let validator_export = export.validators.get(&validator).unwrap();
+        assert_eq!(validator_export.stake, tokens);
+        assert_eq!(validator_export.state, Some(ValidatorState::Consensus));
+        assert!(validator_export.slashes.is_empty());
+
+        let self_bond = BondId {
+            source: validator.clone(),
+            validator,
+        };
+        let bonded: token::Amount = export
+            .bonds
+            .get(&self_bond)
+            .unwrap()
+            .values()
+            .copied()
+            .sum();
+        assert_eq!(bonded, tokens);
+        assert!(export.unbonds.is_empty());
+
+        let again = export_pos_state(&s, Epoch::default()).unwrap();
+        assert_eq!(
+            borsh::to_vec(&export).unwrap(),
+            borsh::to_vec(&again).unwrap()
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! PoS inflation. The amount of the staking token minted as rewards for the
+//! last epoch is computed by the [`InflationModel`] chosen with the
+//! `inflation_model` PoS parameter and then distributed to the validators
+//! with [`update_rewards_products_and_mint_inflation`].
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::inflation::{RewardsController, ValsToUpdate};
+use namada_core::ledger::parameters::storage as params_storage;
+use namada_core::ledger::storage_api::{
+    self, token as token_api, OptionExt, StorageRead, StorageWrite,
+};
+use namada_core::types::dec::Dec;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use serde::{Deserialize, Serialize};
+
+use crate::rewards::InflationDistribution;
+use crate::{
+    read_pos_params, read_total_stake, staking_token_address,
+    update_rewards_products_and_mint_inflation,
+};
+
+/// The inputs of an [`InflationModel`], read at the beginning of an epoch for
+/// the inflation of the last epoch
+#[derive(Debug, Clone)]
+pub struct InflationInputs {
+    /// The total supply of the staking token
+    pub total_supply: token::Amount,
+    /// The total stake in the last epoch
+    pub locked_supply: token::Amount,
+    /// The ratio of the total stake to the total supply computed for the
+    /// inflation of the epoch before the last one
+    pub last_locked_ratio: Dec,
+    /// The inflation minted for the epoch before the last one
+    pub last_inflation_amount: token::Amount,
+    /// The number of epochs per year
+    pub epochs_per_year: u64,
+    /// The target ratio of the total stake to the total supply
+    pub target_staked_ratio: Dec,
+    /// The maximum annual inflation rate
+    pub max_inflation_rate: Dec,
+    /// The nominal proportional gain of the PD controller
+    pub p_gain_nom: Dec,
+    /// The nominal derivative gain of the PD controller
+    pub d_gain_nom: Dec,
+}
+
+impl InflationInputs {
+    /// The ratio of the total stake to the total supply. When there is no
+    /// supply, the ratio is one.
+    pub fn locked_ratio(&self) -> Dec {
+        if self.total_supply.is_zero() {
+            Dec::one()
+        } else {
+            Dec::from(self.locked_supply) / Dec::from(self.total_supply)
+        }
+    }
+}
+
+/// The output of an [`InflationModel`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InflationOutput {
+    /// The ratio of the total stake to the total supply, which is recorded
+    /// for the next run of the model
+    pub locked_ratio: Dec,
+    /// The inflation to be minted for the last epoch
+    pub inflation: token::Amount,
+}
+
+/// A model of the PoS inflation
+pub trait InflationModel {
+    /// Compute the inflation to be minted for the last epoch
+    fn compute_inflation(&self, inputs: &InflationInputs) -> InflationOutput;
+}
+
+/// A PD controller that adjusts the inflation to bring the ratio of the total
+/// stake to the total supply to the target staked ratio, without exceeding
+/// the maximum inflation rate
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct PdController;
+
+impl InflationModel for PdController {
+    fn compute_inflation(&self, inputs: &InflationInputs) -> InflationOutput {
+        let controller = RewardsController {
+            locked_tokens: inputs.locked_supply.raw_amount(),
+            total_tokens: inputs.total_supply.raw_amount(),
+            total_native_tokens: inputs.total_supply.raw_amount(),
+            locked_ratio_target: inputs.target_staked_ratio,
+            locked_ratio_last: inputs.last_locked_ratio,
+            max_reward_rate: inputs.max_inflation_rate,
+            last_inflation_amount: inputs.last_inflation_amount.raw_amount(),
+            p_gain_nom: inputs.p_gain_nom,
+            d_gain_nom: inputs.d_gain_nom,
+            epochs_per_year: inputs.epochs_per_year,
+        };
+        let ValsToUpdate {
+            locked_ratio,
+            inflation,
+        } = controller.run();
+        InflationOutput {
+            locked_ratio,
+            inflation: token::Amount::from_uint(inflation, 0)
+                .expect("Should not fail Uint -> Amount conversion"),
+        }
+    }
+}
+
+/// A fixed annual inflation rate of the total supply
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct FixedRate {
+    /// The annual inflation rate
+    pub rate: Dec,
+}
+
+impl InflationModel for FixedRate {
+    fn compute_inflation(&self, inputs: &InflationInputs) -> InflationOutput {
+        InflationOutput {
+            locked_ratio: inputs.locked_ratio(),
+            inflation: epoch_inflation(
+                inputs.total_supply,
+                self.rate,
+                inputs.epochs_per_year,
+            ),
+        }
+    }
+}
+
+/// A fixed annual inflation rate of the total supply, until the total supply
+/// reaches a maximum
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct CappedSupply {
+    /// The annual inflation rate
+    pub rate: Dec,
+    /// The maximum total supply of the staking token
+    pub max_supply: token::Amount,
+}
+
+impl InflationModel for CappedSupply {
+    fn compute_inflation(&self, inputs: &InflationInputs) -> InflationOutput {
+        let inflation = epoch_inflation(
+            inputs.total_supply,
+            self.rate,
+            inputs.epochs_per_year,
+        );
+        let headroom = self
+            .max_supply
+            .checked_sub(inputs.total_supply)
+            .unwrap_or_default();
+        InflationOutput {
+            locked_ratio: inputs.locked_ratio(),
+            inflation: std::cmp::min(inflation, headroom),
+        }
+    }
+}
+
+/// The per-epoch inflation of the total supply at the given annual rate
+fn epoch_inflation(
+    total_supply: token::Amount,
+    annual_rate: Dec,
+    epochs_per_year: u64,
+) -> token::Amount {
+    if epochs_per_year == 0 {
+        return token::Amount::zero();
+    }
+    let inflation =
+        Dec::from(total_supply) * annual_rate / Dec::from(epochs_per_year);
+    token::Amount::from(inflation)
+}
+
+/// The inflation model chosen with the `inflation_model` PoS parameter
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InflationModelParams {
+    /// The [`PdController`] model
+    #[default]
+    PdController,
+    /// The [`FixedRate`] model
+    FixedRate(FixedRate),
+    /// The [`CappedSupply`] model
+    CappedSupply(CappedSupply),
+}
+
+impl InflationModelParams {
+    /// The annual inflation rate of the fixed-rate models
+    pub fn fixed_rate(&self) -> Option<Dec> {
+        match self {
+            Self::PdController => None,
+            Self::FixedRate(FixedRate { rate })
+            | Self::CappedSupply(CappedSupply { rate, .. }) => Some(*rate),
+        }
+    }
+}
+
+impl InflationModel for InflationModelParams {
+    fn compute_inflation(&self, inputs: &InflationInputs) -> InflationOutput {
+        match self {
+            Self::PdController => PdController.compute_inflation(inputs),
+            Self::FixedRate(model) => model.compute_inflation(inputs),
+            Self::CappedSupply(model) => model.compute_inflation(inputs),
+        }
+    }
+}
+
+/// Read the inputs of the inflation model for the last epoch
+pub fn read_inflation_inputs<S>(
+    storage: &S,
+    last_epoch: Epoch,
+) -> storage_api::Result<InflationInputs>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let epochs_per_year: u64 = storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Epochs per year parameter should exist in storage")?;
+    let p_gain_nom: Dec = storage
+        .read(&params_storage::get_pos_gain_p_key())?
+        .ok_or_err_msg("PoS P-gain factor should exist in storage")?;
+    let d_gain_nom: Dec = storage
+        .read(&params_storage::get_pos_gain_d_key())?
+        .ok_or_err_msg("PoS D-gain factor should exist in storage")?;
+    let last_locked_ratio: Dec = storage
+        .read(&params_storage::get_staked_ratio_key())?
+        .ok_or_err_msg("PoS staked ratio should exist in storage")?;
+    let last_inflation_amount: token::Amount = storage
+        .read(&params_storage::get_pos_inflation_amount_key())?
+        .ok_or_err_msg("PoS inflation amount should exist in storage")?;
+    let staking_token = staking_token_address(storage);
+    let total_supply = token_api::read_total_supply(storage, &staking_token)?;
+    let locked_supply = read_total_stake(storage, &params, last_epoch)?;
+    Ok(InflationInputs {
+        total_supply,
+        locked_supply,
+        last_locked_ratio,
+        last_inflation_amount,
+        epochs_per_year,
+        target_staked_ratio: params.target_staked_ratio,
+        max_inflation_rate: params.max_inflation_rate,
+        p_gain_nom,
+        d_gain_nom,
+    })
+}
+
+/// Compute the PoS inflation for the last epoch with the inflation model of
+/// the PoS parameters, mint it and distribute it to the validators that
+/// earned rewards in the last epoch. The inflation and the staked ratio are
+/// recorded as the inputs of the next run of the model. This must be applied
+/// in the first block of a new epoch. Returns the distribution of the
+/// inflation, so that it can be reported.
+pub fn compute_and_mint_inflation<S>(
+    storage: &mut S,
+    last_epoch: Epoch,
+    num_blocks_in_last_epoch: u64,
+) -> storage_api::Result<InflationDistribution>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    let inputs = read_inflation_inputs(storage, last_epoch)?;
+    let InflationOutput {
+        locked_ratio,
+        inflation,
+    } = params.inflation_model.compute_inflation(&inputs);
+
+    let staking_token = staking_token_address(storage);
+    let distribution = update_rewards_products_and_mint_inflation(
+        storage,
+        &params,
+        last_epoch,
+        num_blocks_in_last_epoch,
+        inflation,
+        &staking_token,
+    )?;
+
+    storage
+        .write(&params_storage::get_pos_inflation_amount_key(), inflation)?;
+    storage.write(&params_storage::get_staked_ratio_key(), locked_ratio)?;
+
+    Ok(distribution)
+}
+
+#[cfg(test)]
+mod test_inflation {
+    use super::*;
+
+    fn inputs() -> InflationInputs {
+        InflationInputs {
+            total_supply: token::Amount::native_whole(1_000_000),
+            locked_supply: token::Amount::native_whole(500_000),
+            last_locked_ratio: Dec::new(5, 1).expect("Test failed"),
+            last_inflation_amount: token::Amount::zero(),
+            epochs_per_year: 100,
+            target_staked_ratio: Dec::new(6667, 4).expect("Test failed"),
+            max_inflation_rate: Dec::new(1, 1).expect("Test failed"),
+            p_gain_nom: Dec::new(25, 2).expect("Test failed"),
+            d_gain_nom: Dec::new(25, 2).expect("Test failed"),
+        }
+    }
+
+    /// Test the inflation computed by the different models
+    #[test]
+    fn test_inflation_models() {
+        let inputs = inputs();
+        let half = Dec::new(5, 1).expect("Test failed");
+
+        // The PD controller increases the inflation towards the max rate, as
+        // the staked ratio is below the target
+        let pd = InflationModelParams::PdController.compute_inflation(&inputs);
+        let max_inflation = token::Amount::native_whole(1_000);
+        assert_eq!(pd.locked_ratio, half);
+        assert!(!pd.inflation.is_zero());
+        assert!(pd.inflation <= max_inflation);
+
+        // 5% a year over 100 epochs
+        let rate = Dec::new(5, 2).expect("Test failed");
+        let fixed = InflationModelParams::FixedRate(FixedRate { rate })
+            .compute_inflation(&inputs);
+        assert_eq!(
+            fixed,
+            InflationOutput {
+                locked_ratio: half,
+                inflation: token::Amount::native_whole(500),
+            }
+        );
+
+        // The inflation is capped by the supply left below the max supply
+        let capped = |max_supply| {
+            InflationModelParams::CappedSupply(CappedSupply {
+                rate,
+                max_supply,
+            })
+            .compute_inflation(&inputs)
+            .inflation
+        };
+        assert_eq!(
+            capped(token::Amount::native_whole(2_000_000)),
+            token::Amount::native_whole(500)
+        );
+        assert_eq!(
+            capped(token::Amount::native_whole(1_000_100)),
+            token::Amount::native_whole(100)
+        );
+        assert_eq!(
+            capped(token::Amount::native_whole(1_000_000)),
+            token::Amount::zero()
+        );
+        assert_eq!(
+            capped(token::Amount::native_whole(1)),
+            token::Amount::zero()
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Storage I/O accounting of the PoS operations. The storage accesses of an
+//! operation are counted by running it on an [`IoCounter`] wrapping the
+//! storage. The counts are used to calibrate the gas costs of the PoS txs and
+//! to spot the inputs that make an operation unusually expensive, such as
+//! many small bonds that are unbonded all at once.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage_api::{self, StorageRead, StorageWrite};
+use namada_core::types::address::Address;
+use namada_core::types::storage::{
+    self, BlockHash, BlockHeight, Epoch, Header, TxIndex,
+};
+
+/// The PoS operations whose storage I/O is accounted
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum PosOp {
+    /// Bond or delegation
+    Bond,
+    /// Unbond of a bond or a delegation
+    Unbond,
+    /// Redelegation from one validator to another
+    Redelegate,
+    /// Withdrawal of unbonded tokens
+    Withdraw,
+    /// Processing of the slashes enqueued for the current epoch
+    ProcessSlashes,
+}
+
+impl Display for PosOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PosOp::Bond => write!(f, "bond"),
+            PosOp::Unbond => write!(f, "unbond"),
+            PosOp::Redelegate => write!(f, "redelegate"),
+            PosOp::Withdraw => write!(f, "withdraw"),
+            PosOp::ProcessSlashes => write!(f, "process_slashes"),
+        }
+    }
+}
+
+/// Storage accesses counted by an [`IoCounter`]
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct StorageIoStats {
+    /// Number of reads, including the `has_key` checks
+    pub reads: u64,
+    /// Total length of the values read
+    pub bytes_read: u64,
+    /// Number of prefix iterators opened
+    pub iters: u64,
+    /// Number of steps of the prefix iterators
+    pub iter_steps: u64,
+    /// Number of writes
+    pub writes: u64,
+    /// Total length of the values written
+    pub bytes_written: u64,
+    /// Number of deletions
+    pub deletes: u64,
+}
+
+impl StorageIoStats {
+    /// Add the counts of `other` to these
+    pub fn add(&mut self, other: &StorageIoStats) {
+        self.reads = self.reads.saturating_add(other.reads);
+        self.bytes_read = self.bytes_read.saturating_add(other.bytes_read);
+        self.iters = self.iters.saturating_add(other.iters);
+        self.iter_steps = self.iter_steps.saturating_add(other.iter_steps);
+        self.writes = self.writes.saturating_add(other.writes);
+        self.bytes_written =
+            self.bytes_written.saturating_add(other.bytes_written);
+        self.deletes = self.deletes.saturating_add(other.deletes);
+    }
+
+    /// The number of reads, iterator steps, writes and deletions
+    pub fn accesses(&self) -> u64 {
+        self.reads
+            .saturating_add(self.iter_steps)
+            .saturating_add(self.writes)
+            .saturating_add(self.deletes)
+    }
+}
+
+/// The storage I/O of all the calls of a PoS operation
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct PosOpIoStats {
+    /// Number of calls of the operation
+    pub calls: u64,
+    /// Storage accesses of all the calls
+    pub io: StorageIoStats,
+    /// Storage accesses of the most expensive call, by the number of reads
+    /// and writes
+    pub max_call: StorageIoStats,
+}
+
+impl PosOpIoStats {
+    /// Record the storage accesses of a call of the operation
+    pub fn record(&mut self, io: &StorageIoStats) {
+        self.merge(&PosOpIoStats {
+            calls: 1,
+            io: *io,
+            max_call: *io,
+        })
+    }
+
+    /// Merge the stats of other calls of the same operation
+    pub fn merge(&mut self, other: &PosOpIoStats) {
+        self.calls = self.calls.saturating_add(other.calls);
+        self.io.add(&other.io);
+        if other.max_call.accesses() > self.max_call.accesses() {
+            self.max_call = other.max_call;
+        }
+    }
+}
+
+/// The storage I/O of the PoS operations, e.g. of a tx or of a block
+pub type PosIoStats = BTreeMap<PosOp, PosOpIoStats>;
+
+/// Merge the stats of `other` into `stats`
+pub fn merge_io_stats(stats: &mut PosIoStats, other: &PosIoStats) {
+    for (op, op_stats) in other {
+        stats.entry(*op).or_default().merge(op_stats);
+    }
+}
+
+/// Run a PoS operation on the given storage, counting its storage accesses
+pub fn with_io_stats<S, T, F>(
+    storage: &mut S,
+    op: F,
+) -> storage_api::Result<(T, StorageIoStats)>
+where
+    S: StorageRead + StorageWrite,
+    F: FnOnce(&mut IoCounter<'_, S>) -> storage_api::Result<T>,
+{
+    let mut counter = IoCounter::new(storage);
+    let res = op(&mut counter)?;
+    Ok((res, counter.stats()))
+}
+
+/// A storage wrapper that counts the storage accesses
+#[derive(Debug)]
+pub struct IoCounter<'a, S> {
+    inner: &'a mut S,
+    reads: Cell<u64>,
+    bytes_read: Cell<u64>,
+    iters: Cell<u64>,
+    iter_steps: Cell<u64>,
+    writes: u64,
+    bytes_written: u64,
+    deletes: u64,
+}
+
+impl<'a, S> IoCounter<'a, S> {
+    /// Wrap the storage to count its accesses
+    pub fn new(inner: &'a mut S) -> Self {
+        Self {
+            inner,
+            reads: Cell::new(0),
+            bytes_read: Cell::new(0),
+            iters: Cell::new(0),
+            iter_steps: Cell::new(0),
+            writes: 0,
+            bytes_written: 0,
+            deletes: 0,
+        }
+    }
+
+    /// The storage accesses counted so far
+    pub fn stats(&self) -> StorageIoStats {
+        StorageIoStats {
+            reads: self.reads.get(),
+            bytes_read: self.bytes_read.get(),
+            iters: self.iters.get(),
+            iter_steps: self.iter_steps.get(),
+            writes: self.writes,
+            bytes_written: self.bytes_written,
+            deletes: self.deletes,
+        }
+    }
+
+    fn count(counter: &Cell<u64>, by: u64) {
+        counter.set(counter.get().saturating_add(by));
+    }
+}
+
+impl<S> StorageRead for IoCounter<'_, S>
+where
+    S: StorageRead,
+{
+    type PrefixIter<'iter> = S::PrefixIter<'iter>
+    where
+        Self: 'iter;
+
+    fn read_bytes(
+        &self,
+        key: &storage::Key,
+    ) -> storage_api::Result<Option<Vec<u8>>> {
+        let value = self.inner.read_bytes(key)?;
+        Self::count(&self.reads, 1);
+        if let Some(value) = value.as_ref() {
+            Self::count(&self.bytes_read, value.len() as u64);
+        }
+        Ok(value)
+    }
+
+    fn has_key(&self, key: &storage::Key) -> storage_api::Result<bool> {
+        Self::count(&self.reads, 1);
+        self.inner.has_key(key)
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        Self::count(&self.iters, 1);
+        self.inner.iter_prefix(prefix)
+    }
+
+    fn iter_prefix_from<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+        start: &storage::Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        Self::count(&self.iters, 1);
+        self.inner.iter_prefix_from(prefix, start)
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        iter: &mut Self::PrefixIter<'iter>,
+    ) -> storage_api::Result<Option<(String, Vec<u8>)>> {
+        let next = self.inner.iter_next(iter)?;
+        if let Some((_key, value)) = next.as_ref() {
+            Self::count(&self.iter_steps, 1);
+            Self::count(&self.bytes_read, value.len() as u64);
+        }
+        Ok(next)
+    }
+
+    fn get_chain_id(&self) -> storage_api::Result<String> {
+        self.inner.get_chain_id()
+    }
+
+    fn get_block_height(&self) -> storage_api::Result<BlockHeight> {
+        self.inner.get_block_height()
+    }
+
+    fn get_block_header(
+        &self,
+        height: BlockHeight,
+    ) -> storage_api::Result<Option<Header>> {
+        self.inner.get_block_header(height)
+    }
+
+    fn get_block_hash(&self) -> storage_api::Result<BlockHash> {
+        self.inner.get_block_hash()
+    }
+
+    fn get_block_epoch(&self) -> storage_api::Result<Epoch> {
+        self.inner.get_block_epoch()
+    }
+
+    fn get_tx_index(&self) -> storage_api::Result<TxIndex> {
+        self.inner.get_tx_index()
+    }
+
+    fn get_native_token(&self) -> storage_api::Result<Address> {
+        self.inner.get_native_token()
+    }
+}
+
+impl<S> StorageWrite for IoCounter<'_, S>
+where
+    S: StorageWrite,
+{
+    fn write_bytes(
+        &mut self,
+        key: &storage::Key,
+        val: impl AsRef<[u8]>,
+    ) -> storage_api::Result<()> {
+        let val = val.as_ref();
+        self.writes = self.writes.saturating_add(1);
+        self.bytes_written =
+            self.bytes_written.saturating_add(val.len() as u64);
+        self.inner.write_bytes(key, val)
+    }
+
+    fn delete(&mut self, key: &storage::Key) -> storage_api::Result<()> {
+        self.deletes = self.deletes.saturating_add(1);
+        self.inner.delete(key)
+    }
+}
+
+#[cfg(test)]
+mod test_io_stats {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::ledger::storage_api::token::{self, credit_tokens};
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada_core::types::dec::Dec;
+    use namada_core::types::key::testing::{keypair_1, keypair_2};
+    use namada_core::types::key::{self, RefTo};
+
+    use super::*;
+    use crate::parameters::OwnedPosParams;
+    use crate::test_utils::test_init_genesis;
+    use crate::types::GenesisValidator;
+    use crate::{bond_tokens, staking_token_address};
+
+    /// Test that the storage accesses of repeated bonds are counted and
+    /// merged
+    #[test]
+    fn test_io_counter() {
+        let mut s = TestWlStorage::default();
+        let validator = established_address_1();
+        let genesis_validator = GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::native_whole(1_000),
+            consensus_key: keypair_1().ref_to(),
+            protocol_key: keypair_2().ref_to(),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(1, 1)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+        };
+        test_init_genesis(
+            &mut s,
+            OwnedPosParams::default(),
+            [genesis_validator].into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+        let delegator = established_address_2();
+        let staking_token = staking_token_address(&s);
+        let amount = token::Amount::native_whole(10);
+        credit_tokens(&mut s, &staking_token, &delegator, amount).unwrap();
+
+        let mut stats = PosIoStats::new();
+        for _ in 0..2 {
+            let ((), io) = with_io_stats(&mut s, |storage| {
+                bond_tokens(
+                    storage,
+                    Some(&delegator),
+                    &validator,
+                    token::Amount::native_whole(1),
+                    Epoch::default(),
+                    None,
+                )
+            })
+            .unwrap();
+            assert!(io.reads > 0);
+            assert!(io.writes > 0);
+            assert!(io.bytes_written > 0);
+            stats.entry(PosOp::Bond).or_default().record(&io);
+        }
+
+        let mut block_stats = PosIoStats::new();
+        merge_io_stats(&mut block_stats, &stats);
+        merge_io_stats(&mut block_stats, &stats);
+        let bond_stats = block_stats[&PosOp::Bond];
+        assert_eq!(bond_stats.calls, 4);
+        assert_eq!(bond_stats.io.writes, 2 * stats[&PosOp::Bond].io.writes);
+        assert!(bond_stats.max_call.accesses() <= bond_stats.io.accesses());
+    }
+}
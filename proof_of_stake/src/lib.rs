@@ -6,13 +6,27 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
 
+#[cfg(any(test, feature = "audit"))]
+pub mod audit;
+pub mod deregistration;
 pub mod epoched;
+pub mod export;
+pub mod inflation;
+pub mod io_stats;
 pub mod parameters;
 pub mod pos_queries;
+pub mod proposer_history;
+pub mod query_ctx;
 pub mod rewards;
+pub mod sampling;
+pub mod setup_check;
+pub mod staking_pool;
+pub mod state_changes;
 pub mod storage;
 pub mod types;
-// pub mod validation;
+pub mod validation;
+pub mod validator_set_cache;
+pub mod write_batch;
 
 mod error;
 #[cfg(test)]
@@ -22,14 +36,19 @@ use core::fmt::Debug;
 use std::cmp::{self, Reverse};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+pub use deregistration::deregister_zero_stake_validators;
+use epoched::PruningStrategy;
 pub use error::*;
 use namada_core::ledger::storage_api::collections::lazy_map::{
     Collectable, LazyMap, NestedMap, NestedSubKey, SubKey,
 };
-use namada_core::ledger::storage_api::collections::{LazyCollection, LazySet};
+use namada_core::ledger::storage_api::collections::{
+    lazy_set, LazyCollection, LazySet, TryIterExt,
+};
 use namada_core::ledger::storage_api::{
-    self, governance, token, ResultExt, StorageRead, StorageWrite,
+    self, governance, token, vesting, OptionExt, ResultExt, StorageRead,
+    StorageWrite,
 };
 use namada_core::types::address::{self, Address, InternalAddress};
 use namada_core::types::dec::Dec;
@@ -38,37 +57,54 @@ use namada_core::types::key::{
 };
 use namada_core::types::storage::BlockHeight;
 pub use namada_core::types::storage::{Epoch, Key, KeySeg};
-use once_cell::unsync::Lazy;
 pub use parameters::{OwnedPosParams, PosParams};
-use rewards::PosRewardsCalculator;
+pub use query_ctx::PosQueryCtx;
+use rewards::{
+    consensus_reward_fractions, InflationDistribution, PosRewardsCalculator,
+    ValidatorRewards,
+};
 use storage::{
-    bonds_for_source_prefix, bonds_prefix, consensus_keys_key,
-    get_validator_address_from_bond, is_bond_key, is_unbond_key,
-    is_validator_slashes_key, last_block_proposer_key,
+    bonds_for_source_prefix, bonds_prefix, consensus_key_reservations_key,
+    consensus_keys_key, get_validator_address_from_bond, is_bond_key,
+    is_unbond_key, is_validator_slashes_key, last_block_proposer_key,
     last_pos_reward_claim_epoch_key, params_key, rewards_counter_key,
-    slashes_prefix, unbonds_for_source_prefix, unbonds_prefix,
-    validator_address_raw_hash_key, validator_description_key,
-    validator_discord_key, validator_email_key, validator_last_slash_key,
-    validator_max_commission_rate_change_key, validator_website_key,
+    scheduled_actions_key, slash_processing_cursor_key, slashes_prefix,
+    unbonds_for_source_prefix, unbonds_prefix, validator_address_raw_hash_key,
+    validator_description_key, validator_discord_key, validator_email_key,
+    validator_last_slash_key, validator_max_commission_rate_change_key,
+    validator_operator_key_key, validator_website_key,
 };
 use types::{
     into_tm_voting_power, BelowCapacityValidatorSet,
-    BelowCapacityValidatorSets, BondDetails, BondId, Bonds,
-    BondsAndUnbondsDetail, BondsAndUnbondsDetails, CommissionRates,
+    BelowCapacityValidatorSets, BelowThresholdValidatorSets, BondAction,
+    BondActionSimulation, BondAmountCheckpoint, BondAmountCheckpoints,
+    BondDetails, BondId, Bonds, BondsAndUnbondsDetail, BondsAndUnbondsDetails,
+    CommissionRateChange, CommissionRateHistory, CommissionRates,
+    ConsensusKeyReservations, ConsensusKeyReservationsPruning,
     ConsensusValidator, ConsensusValidatorSet, ConsensusValidatorSets,
-    DelegatorRedelegatedBonded, DelegatorRedelegatedUnbonded,
-    EagerRedelegatedBondsMap, EpochedSlashes, IncomingRedelegations,
-    LivenessMissedVotes, LivenessSumMissedVotes, OutgoingRedelegations,
-    Position, RedelegatedBondsOrUnbonds, RedelegatedTokens,
-    ReverseOrdTokenAmount, RewardsAccumulator, RewardsProducts, Slash,
-    SlashType, SlashedAmount, Slashes, TotalConsensusStakes, TotalDeltas,
-    TotalRedelegatedBonded, TotalRedelegatedUnbonded, UnbondDetails, Unbonds,
-    ValidatorAddresses, ValidatorConsensusKeys, ValidatorDeltas,
-    ValidatorEthColdKeys, ValidatorEthHotKeys, ValidatorMetaData,
-    ValidatorPositionAddresses, ValidatorProtocolKeys, ValidatorSetPositions,
-    ValidatorSetUpdate, ValidatorState, ValidatorStates,
-    ValidatorTotalUnbonded, VoteInfo, WeightedValidator,
+    CumulativeSlashRates, DelegatorRedelegatedBonded,
+    DelegatorRedelegatedUnbonded, EagerRedelegatedBondsMap, EpochedSlashes,
+    IncomingRedelegations, IncomingRedelegationsPruning, InflationAmounts,
+    LivenessMissedVotes, LivenessReports, LivenessSumMissedVotes,
+    OutgoingRedelegations, OutgoingRedelegationsPruning, Position,
+    ProposerHistory, QueuedUnbond, RedelegatedBondsOrUnbonds,
+    RedelegatedTokens, RedelegationEligibility, ReverseOrdTokenAmount,
+    RewardsAccumulator, RewardsProducts, ScheduledAction, ScheduledActions,
+    Slash, SlashEstimate, SlashFilter, SlashProcessingCursor,
+    SlashProcessingStatus, SlashRecord, SlashStatus, SlashType, SlashedAmount,
+    Slashes, TotalConsensusStakes, TotalDeltas, TotalRedelegatedBonded,
+    TotalRedelegatedUnbonded, TotalRedelegatedUnbondedPruning, UnbondDetails,
+    Unbonds, ValidatorAddresses, ValidatorConsensusKeys, ValidatorDeltas,
+    ValidatorEthColdKeys, ValidatorEthHotKeys, ValidatorKeys,
+    ValidatorMetaData, ValidatorPositionAddresses, ValidatorProtocolKeys,
+    ValidatorSetDiff, ValidatorSetPositions, ValidatorSetSnapshot,
+    ValidatorSetUpdate, ValidatorSnapshot, ValidatorState,
+    ValidatorStateTransition, ValidatorStates, ValidatorTotalUnbonded,
+    ValidatorTotalUnbondedPruning, VoteInfo, WeightedValidator,
+    ZeroStakeValidators,
 };
+use validator_set_cache::ConsensusValidatorSetCache;
+use write_batch::AmountWriteBatch;
 
 /// Address of the PoS account implemented as a native VP
 pub const ADDRESS: Address = Address::Internal(InternalAddress::PoS);
@@ -77,6 +113,10 @@ pub const ADDRESS: Address = Address::Internal(InternalAddress::PoS);
 pub const SLASH_POOL_ADDRESS: Address =
     Address::Internal(InternalAddress::PosSlashPool);
 
+/// Address of the PoS staking pool account
+pub const STAKING_POOL_ADDRESS: Address =
+    Address::Internal(InternalAddress::StakingPool);
+
 /// Address of the staking token (i.e. the native token)
 pub fn staking_token_address(storage: &impl StorageRead) -> Address {
     storage
@@ -96,6 +136,12 @@ pub fn below_capacity_validator_set_handle() -> BelowCapacityValidatorSets {
     BelowCapacityValidatorSets::open(key)
 }
 
+/// Get the storage handle to the epoched below-threshold validator set
+pub fn below_threshold_validator_set_handle() -> BelowThresholdValidatorSets {
+    let key = storage::below_threshold_validator_set_key();
+    BelowThresholdValidatorSets::open(key)
+}
+
 /// Get the storage handle to a PoS validator's consensus key (used for
 /// signing block votes).
 pub fn validator_consensus_key_handle(
@@ -159,6 +205,19 @@ pub fn validator_addresses_handle() -> ValidatorAddresses {
     ValidatorAddresses::open(key)
 }
 
+/// Get the storage handle to the validators that have had no stake since some
+/// epoch
+pub fn zero_stake_validators_handle() -> ZeroStakeValidators {
+    let key = storage::zero_stake_validators_key();
+    ZeroStakeValidators::open(key)
+}
+
+/// Get the storage handle to the proposers of the last blocks
+pub fn proposer_history_handle() -> ProposerHistory {
+    let key = storage::proposer_history_key();
+    ProposerHistory::open(key)
+}
+
 /// Get the storage handle to a PoS validator's commission rate
 pub fn validator_commission_rate_handle(
     validator: &Address,
@@ -167,6 +226,14 @@ pub fn validator_commission_rate_handle(
     CommissionRates::open(key)
 }
 
+/// Get the storage handle to a PoS validator's commission rate changes history
+pub fn validator_commission_history_handle(
+    validator: &Address,
+) -> CommissionRateHistory {
+    let key = storage::validator_commission_history_key(validator);
+    CommissionRateHistory::open(key)
+}
+
 /// Get the storage handle to a bond, which is dynamically updated with when
 /// unbonding
 pub fn bond_handle(source: &Address, validator: &Address) -> Bonds {
@@ -178,6 +245,19 @@ pub fn bond_handle(source: &Address, validator: &Address) -> Bonds {
     Bonds::open(key)
 }
 
+/// Get the storage handle to the checkpoints of a bond amount
+pub fn bond_amount_checkpoint_handle(
+    source: &Address,
+    validator: &Address,
+) -> BondAmountCheckpoints {
+    let bond_id = BondId {
+        source: source.clone(),
+        validator: validator.clone(),
+    };
+    let key = storage::bond_amount_checkpoint_key(&bond_id);
+    BondAmountCheckpoints::open(key)
+}
+
 /// Get the storage handle to a validator's total bonds, which are not updated
 /// due to unbonding
 pub fn total_bonded_handle(validator: &Address) -> Bonds {
@@ -213,6 +293,15 @@ pub fn validator_slashes_handle(validator: &Address) -> Slashes {
     Slashes::open(key)
 }
 
+/// Get the storage handle to the cumulative rates of a PoS validator's
+/// processed slashes
+pub fn validator_cumulative_slash_rate_handle(
+    validator: &Address,
+) -> CumulativeSlashRates {
+    let key = storage::validator_cumulative_slash_rate_key(validator);
+    CumulativeSlashRates::open(key)
+}
+
 /// Get the storage handle to list of all slashes to be processed and ultimately
 /// placed in the `validator_slashes_handle`
 pub fn enqueued_slashes_handle() -> EpochedSlashes {
@@ -235,6 +324,12 @@ pub fn validator_rewards_products_handle(
     RewardsProducts::open(key)
 }
 
+/// Get the storage handle to the PoS inflation amounts applied in each epoch
+pub fn inflation_amounts_handle() -> InflationAmounts {
+    let key = storage::inflation_amounts_key();
+    InflationAmounts::open(key)
+}
+
 /// Get the storage handle to a validator's incoming redelegations
 pub fn validator_incoming_redelegations_handle(
     validator: &Address,
@@ -295,6 +390,13 @@ pub fn liveness_sum_missed_votes_handle() -> LivenessSumMissedVotes {
     LivenessSumMissedVotes::open(key)
 }
 
+/// Get the storage handle to the liveness reports received from vote
+/// extensions
+pub fn liveness_reports_handle() -> LivenessReports {
+    let key = storage::liveness_reports_key();
+    LivenessReports::open(key)
+}
+
 /// Init genesis. Requires that the governance parameters are initialized.
 pub fn init_genesis<S>(
     storage: &mut S,
@@ -309,6 +411,7 @@ where
 
     consensus_validator_set_handle().init(storage, current_epoch)?;
     below_capacity_validator_set_handle().init(storage, current_epoch)?;
+    below_threshold_validator_set_handle().init(storage, current_epoch)?;
     validator_set_positions_handle().init(storage, current_epoch)?;
     validator_addresses_handle().init(storage, current_epoch)?;
     tracing::debug!("Finished genesis");
@@ -316,7 +419,7 @@ where
 }
 
 /// Copies the validator sets into all epochs up through the pipeline epoch at
-/// genesis.
+/// genesis and stores the snapshot of the genesis consensus validator set.
 pub fn copy_genesis_validator_sets<S>(
     storage: &mut S,
     params: &OwnedPosParams,
@@ -336,7 +439,8 @@ where
             epoch,
         )?;
     }
-    Ok(())
+
+    store_validator_set_snapshot(storage, &params, current_epoch)
 }
 
 /// Read PoS parameters
@@ -367,7 +471,7 @@ where
     })
 }
 
-/// Write PoS parameters
+/// Write PoS parameters. Fails if the parameters are not valid.
 pub fn write_pos_params<S>(
     storage: &mut S,
     params: &OwnedPosParams,
@@ -375,6 +479,10 @@ pub fn write_pos_params<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    if let Err(errors) = parameters::validate(params) {
+        return Err(WriteParamsError::InvalidParams(errors))
+            .into_storage_result();
+    }
     let key = params_key();
     storage.write(&key, params)
 }
@@ -416,6 +524,47 @@ where
     storage.read(&key)
 }
 
+/// Read all the commission rate changes of a PoS validator, from the oldest
+/// to the most recent one.
+pub fn read_commission_history<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<Vec<CommissionRateChange>>
+where
+    S: StorageRead,
+{
+    validator_commission_history_handle(validator)
+        .iter(storage)?
+        .collect()
+}
+
+/// Read the cumulative commission rate change of a PoS validator from the
+/// changes submitted in the current epoch, which take effect at the given
+/// pipeline epoch.
+pub fn read_commission_rate_change_in_epoch<S>(
+    storage: &S,
+    validator: &Address,
+    pipeline_epoch: Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let history = validator_commission_history_handle(validator);
+    let mut cumulative_change = Dec::zero();
+    // The history is ordered by epoch, so it's enough to go back until the
+    // first change from another epoch
+    for index in (0..history.len(storage)?).rev() {
+        let change = history
+            .get(storage, index)?
+            .expect("The commission rate change must exist");
+        if change.epoch != pipeline_epoch {
+            break;
+        }
+        cumulative_change += change.new_rate.abs_diff(&change.old_rate);
+    }
+    Ok(cumulative_change)
+}
+
 /// Write PoS validator's max commission rate change.
 pub fn write_validator_max_commission_rate_change<S>(
     storage: &mut S,
@@ -513,6 +662,30 @@ where
     Ok(amount)
 }
 
+/// Read PoS validator's stake in every epoch of the given inclusive range in a
+/// single pass over its stake deltas, instead of one read per epoch. The epochs
+/// whose stake deltas have been pruned are not included.
+pub fn read_validator_stake_range<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    from_epoch: namada_core::types::storage::Epoch,
+    to_epoch: namada_core::types::storage::Epoch,
+) -> storage_api::Result<BTreeMap<Epoch, token::Amount>>
+where
+    S: StorageRead,
+{
+    let sums = validator_deltas_handle(validator)
+        .get_sums_in_range(storage, from_epoch, to_epoch, params)?;
+    Ok(sums
+        .into_iter()
+        .map(|(epoch, change)| {
+            debug_assert!(change.non_negative());
+            (epoch, token::Amount::from_change(change))
+        })
+        .collect())
+}
+
 /// Add or remove PoS validator's stake delta value
 pub fn update_validator_deltas<S>(
     storage: &mut S,
@@ -532,13 +705,66 @@ where
         .unwrap_or_default();
     handle.set(
         storage,
-        val.checked_add(&delta)
-            .expect("Validator deltas updated amount should not overflow"),
+        checked_change_add(val, delta, "validator deltas")?,
         current_epoch,
         offset,
     )
 }
 
+/// Add a signed `delta` to a `token::Change` value, returning a typed
+/// overflow or underflow error instead of wrapping or panicking. The
+/// `context` names the value being updated for the error message.
+pub fn checked_change_add(
+    value: token::Change,
+    delta: token::Change,
+    context: &'static str,
+) -> Result<token::Change, ArithmeticError> {
+    value.checked_add(&delta).ok_or(if delta.non_negative() {
+        ArithmeticError::Overflow(context)
+    } else {
+        ArithmeticError::Underflow(context)
+    })
+}
+
+/// Add two token amounts, returning a typed overflow error instead of
+/// panicking. The `context` names the value being updated for the error
+/// message.
+pub fn checked_amount_add(
+    value: token::Amount,
+    delta: token::Amount,
+    context: &'static str,
+) -> Result<token::Amount, ArithmeticError> {
+    value
+        .checked_add(delta)
+        .ok_or(ArithmeticError::Overflow(context))
+}
+
+/// Subtract a token amount from another, returning a typed underflow error
+/// instead of panicking. The `context` names the value being updated for the
+/// error message.
+pub fn checked_amount_sub(
+    value: token::Amount,
+    delta: token::Amount,
+    context: &'static str,
+) -> Result<token::Amount, ArithmeticError> {
+    value
+        .checked_sub(delta)
+        .ok_or(ArithmeticError::Underflow(context))
+}
+
+/// Sum token amounts, returning a typed overflow error instead of panicking.
+/// The `context` names the value being computed for the error message.
+fn checked_amount_sum(
+    amounts: impl IntoIterator<Item = storage_api::Result<token::Amount>>,
+    context: &'static str,
+) -> storage_api::Result<token::Amount> {
+    amounts
+        .into_iter()
+        .try_fold(token::Amount::zero(), |acc, amount| {
+            Ok(checked_amount_add(acc, amount?, context)?)
+        })
+}
+
 /// Read PoS total stake (sum of deltas).
 pub fn read_total_stake<S>(
     storage: &S,
@@ -559,6 +785,88 @@ where
     Ok(amnt)
 }
 
+/// Read PoS total stake in every epoch of the given inclusive range in a
+/// single pass over the total deltas, like [`read_validator_stake_range`].
+pub fn read_total_stake_range<S>(
+    storage: &S,
+    params: &PosParams,
+    from_epoch: namada_core::types::storage::Epoch,
+    to_epoch: namada_core::types::storage::Epoch,
+) -> storage_api::Result<BTreeMap<Epoch, token::Amount>>
+where
+    S: StorageRead,
+{
+    let sums = total_deltas_handle()
+        .get_sums_in_range(storage, from_epoch, to_epoch, params)?;
+    Ok(sums
+        .into_iter()
+        .map(|(epoch, change)| {
+            debug_assert!(change.non_negative());
+            (epoch, token::Amount::from_change(change))
+        })
+        .collect())
+}
+
+/// Read the ratio of the PoS total stake to the total supply of the staking
+/// token in the given epoch. When there is no supply, the ratio is one, as in
+/// the PoS inflation controller.
+pub fn read_bonded_ratio<S>(
+    storage: &S,
+    epoch: namada_core::types::storage::Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let total_stake = read_total_stake(storage, &params, epoch)?;
+    let staking_token = staking_token_address(storage);
+    let total_supply = token::read_total_supply(storage, &staking_token)?;
+    if total_supply.is_zero() {
+        return Ok(Dec::one());
+    }
+    Dec::from(total_stake)
+        .trunc_div(&Dec::from(total_supply))
+        .ok_or_err_msg("Overflow while computing the PoS bonded ratio")
+}
+
+/// Read the PoS inflation amount that was applied for the rewards of the
+/// given epoch, if any.
+pub fn read_inflation_amount<S>(
+    storage: &S,
+    epoch: namada_core::types::storage::Epoch,
+) -> storage_api::Result<Option<token::Amount>>
+where
+    S: StorageRead,
+{
+    inflation_amounts_handle().get(storage, &epoch)
+}
+
+/// Read the recipient of the inflation tokens left-over from the rounding of
+/// the rewards. Defaults to the governance address when not set.
+pub fn read_rewards_dust_recipient<S>(
+    storage: &S,
+) -> storage_api::Result<Address>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read(&storage::rewards_dust_recipient_key())?
+        .unwrap_or(address::GOV))
+}
+
+/// Write the recipient of the inflation tokens left-over from the rounding of
+/// the rewards. Outside of genesis, this may only be changed by an accepted
+/// governance proposal.
+pub fn write_rewards_dust_recipient<S>(
+    storage: &mut S,
+    recipient: &Address,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite,
+{
+    storage.write(&storage::rewards_dust_recipient_key(), recipient)
+}
+
 /// Read all addresses from consensus validator set.
 pub fn read_consensus_validator_set_addresses<S>(
     storage: &S,
@@ -597,18 +905,10 @@ pub fn read_below_threshold_validator_set_addresses<S>(
 where
     S: StorageRead,
 {
-    let params = read_pos_params(storage)?;
-    Ok(validator_addresses_handle()
+    below_threshold_validator_set_handle()
         .at(&epoch)
         .iter(storage)?
-        .map(Result::unwrap)
-        .filter(|address| {
-            matches!(
-                validator_state_handle(address).get(storage, epoch, &params),
-                Ok(Some(ValidatorState::BelowThreshold))
-            )
-        })
-        .collect())
+        .collect()
 }
 
 /// Read all addresses from consensus validator set with their stake.
@@ -699,6 +999,44 @@ where
         .collect()
 }
 
+/// Read the consensus, eth hot, eth cold and protocol keys of all the known
+/// validators in the given epoch.
+pub fn read_all_validator_keys<S>(
+    storage: &S,
+    epoch: namada_core::types::storage::Epoch,
+) -> storage_api::Result<HashMap<Address, ValidatorKeys>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    read_all_validator_addresses(storage, epoch)?
+        .into_iter()
+        .map(|validator| {
+            let missing_key = |key_name: &str| {
+                storage_api::Error::new(format!(
+                    "Missing {key_name} key of validator {validator} in \
+                     epoch {epoch}"
+                ))
+            };
+            let keys = ValidatorKeys {
+                consensus_key: validator_consensus_key_handle(&validator)
+                    .get(storage, epoch, &params)?
+                    .ok_or_else(|| missing_key("consensus"))?,
+                eth_hot_key: validator_eth_hot_key_handle(&validator)
+                    .get(storage, epoch, &params)?
+                    .ok_or_else(|| missing_key("eth hot"))?,
+                eth_cold_key: validator_eth_cold_key_handle(&validator)
+                    .get(storage, epoch, &params)?
+                    .ok_or_else(|| missing_key("eth cold"))?,
+                protocol_key: validator_protocol_key_handle(&validator)
+                    .get(storage, epoch, &params)?
+                    .ok_or_else(|| missing_key("protocol"))?,
+            };
+            Ok((validator, keys))
+        })
+        .collect()
+}
+
 /// Update PoS total deltas.
 /// Note: for EpochedDelta, write the value to change storage by
 pub fn update_total_deltas<S>(
@@ -718,8 +1056,7 @@ where
         .unwrap_or_default();
     handle.set(
         storage,
-        val.checked_add(&delta)
-            .expect("Total deltas updated amount should not overflow"),
+        checked_change_add(val, delta, "total deltas")?,
         current_epoch,
         offset,
     )
@@ -813,8 +1150,13 @@ where
     let source = source.unwrap_or(validator);
     tracing::debug!("Source {source} --> Validator {validator}");
 
-    let staking_token = staking_token_address(storage);
-    token::transfer(storage, &staking_token, source, &ADDRESS, amount)?;
+    if vesting::is_vesting_account(storage, source)? {
+        // Vesting accounts delegate the tokens held by the vesting address
+        vesting::debit_locked(storage, source, &ADDRESS, amount)?;
+    } else {
+        let staking_token = staking_token_address(storage);
+        token::transfer(storage, &staking_token, source, &ADDRESS, amount)?;
+    }
 
     let params = read_pos_params(storage)?;
     let offset = offset_opt.unwrap_or(params.pipeline_len);
@@ -880,6 +1222,45 @@ where
         offset_opt,
     )?;
 
+    update_bond_amount_checkpoints(
+        storage,
+        &params,
+        &BondId {
+            source: source.clone(),
+            validator: validator.clone(),
+        },
+        current_epoch,
+        offset_epoch,
+    )?;
+
+    Ok(())
+}
+
+/// Set the state of a validator from the given epoch and offset, keeping the
+/// below-threshold validator set at the same epoch in sync with it
+fn set_validator_state<S>(
+    storage: &mut S,
+    validator: &Address,
+    state: ValidatorState,
+    current_epoch: Epoch,
+    offset: u64,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    validator_state_handle(validator).set(
+        storage,
+        state,
+        current_epoch,
+        offset,
+    )?;
+    let below_threshold =
+        below_threshold_validator_set_handle().at(&(current_epoch + offset));
+    if state == ValidatorState::BelowThreshold {
+        below_threshold.insert(storage, validator.clone())?;
+    } else {
+        below_threshold.remove(storage, validator)?;
+    }
     Ok(())
 }
 
@@ -904,8 +1285,9 @@ where
         get_num_consensus_validators(storage, target_epoch)?;
 
     if stake < params.validator_stake_threshold {
-        validator_state_handle(address).set(
+        set_validator_state(
             storage,
+            address,
             ValidatorState::BelowThreshold,
             current_epoch,
             offset,
@@ -917,8 +1299,9 @@ where
             &target_epoch,
             address,
         )?;
-        validator_state_handle(address).set(
+        set_validator_state(
             storage,
+            address,
             ValidatorState::Consensus,
             current_epoch,
             offset,
@@ -949,8 +1332,9 @@ where
                 &target_epoch,
                 &removed,
             )?;
-            validator_state_handle(&removed).set(
+            set_validator_state(
                 storage,
+                &removed,
                 ValidatorState::BelowCapacity,
                 current_epoch,
                 offset,
@@ -963,8 +1347,9 @@ where
                 address,
             )?;
             // Update and set the validator states
-            validator_state_handle(address).set(
+            set_validator_state(
                 storage,
+                address,
                 ValidatorState::Consensus,
                 current_epoch,
                 offset,
@@ -977,8 +1362,9 @@ where
                 &target_epoch,
                 address,
             )?;
-            validator_state_handle(address).set(
+            set_validator_state(
                 storage,
+                address,
                 ValidatorState::BelowCapacity,
                 current_epoch,
                 offset,
@@ -1018,10 +1404,11 @@ where
 
     let tokens_pre = read_validator_stake(storage, params, validator, epoch)?;
 
-    let tokens_post = tokens_pre
-        .change()
-        .checked_add(&token_change)
-        .expect("Post-validator set update token amount has overflowed");
+    let tokens_post = checked_change_add(
+        tokens_pre.change(),
+        token_change,
+        "validator set stake",
+    )?;
     debug_assert!(tokens_post.non_negative());
     let tokens_post = token::Amount::from_change(tokens_post);
 
@@ -1067,8 +1454,9 @@ where
                     "Demoting this validator to the below-threshold set"
                 );
                 // Set the validator state as below-threshold
-                validator_state_handle(validator).set(
+                set_validator_state(
                     storage,
+                    validator,
                     ValidatorState::BelowThreshold,
                     current_epoch,
                     offset,
@@ -1104,8 +1492,9 @@ where
                         &epoch,
                         &removed_max_below_capacity,
                     )?;
-                    validator_state_handle(&removed_max_below_capacity).set(
+                    set_validator_state(
                         storage,
+                        &removed_max_below_capacity,
                         ValidatorState::Consensus,
                         current_epoch,
                         offset,
@@ -1139,8 +1528,9 @@ where
                     &epoch,
                     &removed_max_below_capacity,
                 )?;
-                validator_state_handle(&removed_max_below_capacity).set(
+                set_validator_state(
                     storage,
+                    &removed_max_below_capacity,
                     ValidatorState::Consensus,
                     current_epoch,
                     offset,
@@ -1153,8 +1543,9 @@ where
                     &epoch,
                     validator,
                 )?;
-                validator_state_handle(validator).set(
+                set_validator_state(
                     storage,
+                    validator,
                     ValidatorState::BelowCapacity,
                     current_epoch,
                     offset,
@@ -1214,8 +1605,9 @@ where
                     &epoch,
                     validator,
                 )?;
-                validator_state_handle(validator).set(
+                set_validator_state(
                     storage,
+                    validator,
                     ValidatorState::BelowCapacity,
                     current_epoch,
                     offset,
@@ -1226,8 +1618,9 @@ where
                     "Demoting this validator to the below-threshold set"
                 );
 
-                validator_state_handle(validator).set(
+                set_validator_state(
                     storage,
+                    validator,
                     ValidatorState::BelowThreshold,
                     current_epoch,
                     offset,
@@ -1261,8 +1654,9 @@ where
                 &epoch,
                 validator,
             )?;
-            validator_state_handle(validator).set(
+            set_validator_state(
                 storage,
+                validator,
                 ValidatorState::Consensus,
                 current_epoch,
                 offset,
@@ -1303,8 +1697,9 @@ where
                     &epoch,
                     validator,
                 )?;
-                validator_state_handle(validator).set(
+                set_validator_state(
                     storage,
+                    validator,
                     ValidatorState::BelowCapacity,
                     current_epoch,
                     offset,
@@ -1349,8 +1744,9 @@ where
         &offset_epoch,
         &removed_min_consensus,
     )?;
-    validator_state_handle(&removed_min_consensus).set(
+    set_validator_state(
         storage,
+        &removed_min_consensus,
         ValidatorState::BelowCapacity,
         current_epoch,
         offset,
@@ -1363,8 +1759,9 @@ where
         &offset_epoch,
         validator,
     )?;
-    validator_state_handle(validator).set(
+    set_validator_state(
         storage,
+        validator,
         ValidatorState::Consensus,
         current_epoch,
         offset,
@@ -1444,6 +1841,22 @@ where
     consensus_validator_set.update_data(storage, params, current_epoch)?;
     below_capacity_validator_set.update_data(storage, params, current_epoch)?;
 
+    // Copy the below-threshold validator set
+    let below_threshold_validator_set = below_threshold_validator_set_handle();
+    let below_threshold = below_threshold_validator_set
+        .at(&prev_epoch)
+        .iter(storage)?
+        .collect::<storage_api::Result<Vec<Address>>>()?;
+    let new_below_threshold = below_threshold_validator_set.at(&target_epoch);
+    for validator in below_threshold {
+        new_below_threshold.insert(storage, validator)?;
+    }
+    below_threshold_validator_set.update_data(
+        storage,
+        params,
+        current_epoch,
+    )?;
+
     // Copy validator positions
     let mut positions = HashMap::<Address, Position>::default();
     let validator_set_positions_handle = validator_set_positions_handle();
@@ -1489,6 +1902,107 @@ where
     Ok(())
 }
 
+/// Rebalance the consensus and below-capacity validator sets at the pipeline
+/// epoch to respect the `max_validator_slots` parameter, which may have been
+/// changed since the sets were last updated. When the number of slots shrank,
+/// the lowest-stake consensus validators are demoted to the below-capacity
+/// set. When it grew, the highest-stake below-capacity validators are promoted
+/// to the consensus set.
+///
+/// This must be applied at an epoch transition, after the validator sets have
+/// been copied into the pipeline epoch with
+/// `copy_validator_sets_and_positions`.
+pub fn rebalance_validator_sets<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let offset = params.pipeline_len;
+    let target_epoch = current_epoch + offset;
+    let consensus_set = consensus_validator_set_handle().at(&target_epoch);
+    let below_capacity_set =
+        below_capacity_validator_set_handle().at(&target_epoch);
+    let mut num_consensus_validators =
+        get_num_consensus_validators(storage, target_epoch)?;
+
+    // Demote the excess consensus validators
+    while num_consensus_validators > params.max_validator_slots {
+        let min_consensus_amount =
+            get_min_consensus_validator_amount(&consensus_set, storage)?;
+        let consensus_vals_min = consensus_set.at(&min_consensus_amount);
+        let last_position = find_last_position(&consensus_vals_min, storage)?
+            .expect("There must be at least 1 consensus validator");
+        let demoted = consensus_vals_min
+            .remove(storage, &last_position)?
+            .expect("There must be at least 1 consensus validator");
+        tracing::info!(
+            "Demoting validator {demoted} to the below-capacity set at epoch \
+             {target_epoch}, because the number of validator slots decreased \
+             to {}",
+            params.max_validator_slots
+        );
+        insert_validator_into_set(
+            &below_capacity_set.at(&min_consensus_amount.into()),
+            storage,
+            &target_epoch,
+            &demoted,
+        )?;
+        set_validator_state(
+            storage,
+            &demoted,
+            ValidatorState::BelowCapacity,
+            current_epoch,
+            offset,
+        )?;
+        num_consensus_validators -= 1;
+    }
+
+    // Promote the greatest below-capacity validators into the free slots
+    while num_consensus_validators < params.max_validator_slots {
+        let max_below_capacity_amount =
+            match get_max_below_capacity_validator_amount(
+                &below_capacity_set,
+                storage,
+            )? {
+                Some(amount) => amount,
+                None => break,
+            };
+        let below_capacity_vals_max =
+            below_capacity_set.at(&max_below_capacity_amount.into());
+        let first_position =
+            find_first_position(&below_capacity_vals_max, storage)?
+                .expect("There must be at least 1 below-capacity validator");
+        let promoted = below_capacity_vals_max
+            .remove(storage, &first_position)?
+            .expect("There must be at least 1 below-capacity validator");
+        tracing::info!(
+            "Promoting validator {promoted} to the consensus set at epoch \
+             {target_epoch}, because the number of validator slots increased \
+             to {}",
+            params.max_validator_slots
+        );
+        insert_validator_into_set(
+            &consensus_set.at(&max_below_capacity_amount),
+            storage,
+            &target_epoch,
+            &promoted,
+        )?;
+        set_validator_state(
+            storage,
+            &promoted,
+            ValidatorState::Consensus,
+            current_epoch,
+            offset,
+        )?;
+        num_consensus_validators += 1;
+    }
+
+    Ok(())
+}
+
 /// Compute total validator stake for the current epoch
 fn compute_total_consensus_stake<S>(
     storage: &S,
@@ -1509,9 +2023,11 @@ where
                 },
                 _validator,
             ) = entry?;
-            Ok(acc.checked_add(amount).expect(
-                "Total consensus stake computation should not overflow.",
-            ))
+            acc.checked_add(amount).ok_or_else(|| {
+                storage_api::Error::from(ArithmeticError::Overflow(
+                    "total consensus stake",
+                ))
+            })
         })
 }
 
@@ -1532,24 +2048,95 @@ where
     total_consensus_stake_key_handle().set(storage, total, epoch, 0)
 }
 
-/// Read the position of the validator in the subset of validators that have the
-/// same bonded stake. This information is held in its own epoched structure in
-/// addition to being inside the validator sets.
-fn read_validator_set_position<S>(
+/// Compute a snapshot of the consensus validator set of the given epoch with
+/// the validators' stake and consensus keys.
+pub fn compute_validator_set_snapshot<S>(
     storage: &S,
-    validator: &Address,
+    params: &PosParams,
     epoch: Epoch,
-    _params: &PosParams,
-) -> storage_api::Result<Option<Position>>
+) -> storage_api::Result<ValidatorSetSnapshot>
 where
     S: StorageRead,
 {
-    let handle = validator_set_positions_handle();
-    handle.get_data_handler().at(&epoch).get(storage, validator)
+    let consensus_set =
+        read_consensus_validator_set_addresses_with_stake(storage, epoch)?;
+    let mut validators = Vec::with_capacity(consensus_set.len());
+    // Iterate from the highest stake
+    for WeightedValidator {
+        bonded_stake,
+        address,
+    } in consensus_set.into_iter().rev()
+    {
+        let consensus_key = validator_consensus_key_handle(&address)
+            .get(storage, epoch, params)?
+            .expect("Consensus validator must have a consensus key");
+        validators.push(ValidatorSnapshot {
+            address,
+            bonded_stake,
+            consensus_key,
+        });
+    }
+    Ok(ValidatorSetSnapshot { epoch, validators })
 }
 
-/// Find the first (lowest) position in a validator set if it is not empty
-fn find_first_position<S>(
+/// Store a snapshot of the consensus validator set of the given epoch under a
+/// deterministic storage key, from which it can be queried with a proof. This
+/// is done at the beginning of every epoch, after which the validator set of
+/// the epoch can no longer change. Snapshots older than the withdrawable epoch
+/// offset are removed.
+pub fn store_validator_set_snapshot<S>(
+    storage: &mut S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let snapshot = compute_validator_set_snapshot(storage, params, epoch)?;
+    tracing::debug!(
+        "Validator set snapshot for epoch {epoch} with commitment {}",
+        snapshot.commitment()
+    );
+    storage.write(&storage::validator_set_snapshot_key(epoch), snapshot)?;
+
+    if let Some(old_epoch) =
+        epoch.checked_sub(params.withdrawable_epoch_offset() + 1)
+    {
+        storage.delete(&storage::validator_set_snapshot_key(old_epoch))?;
+    }
+    Ok(())
+}
+
+/// Read the stored snapshot of the consensus validator set of the given epoch,
+/// if any.
+pub fn read_validator_set_snapshot<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<Option<ValidatorSetSnapshot>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::validator_set_snapshot_key(epoch))
+}
+
+/// Read the position of the validator in the subset of validators that have the
+/// same bonded stake. This information is held in its own epoched structure in
+/// addition to being inside the validator sets.
+fn read_validator_set_position<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+    _params: &PosParams,
+) -> storage_api::Result<Option<Position>>
+where
+    S: StorageRead,
+{
+    let handle = validator_set_positions_handle();
+    handle.get_data_handler().at(&epoch).get(storage, validator)
+}
+
+/// Find the first (lowest) position in a validator set if it is not empty
+fn find_first_position<S>(
     handle: &ValidatorPositionAddresses,
     storage: &S,
 ) -> storage_api::Result<Option<Position>>
@@ -1672,7 +2259,9 @@ struct BondAndUnbondUpdates {
 
 /// Temp: In quint this is from `ResultUnbondTx` field `resultSlashing: {sum:
 /// int, epochMap: Epoch -> int}`
-#[derive(Debug, Default)]
+#[derive(
+    Debug, Clone, Default, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
 pub struct ResultSlashing {
     /// The token amount unbonded from the validator stake after accounting for
     /// slashes
@@ -1694,6 +2283,35 @@ pub fn unbond_tokens<S>(
     current_epoch: Epoch,
     is_redelegation: bool,
 ) -> storage_api::Result<ResultSlashing>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut batch = AmountWriteBatch::default();
+    let result = unbond_tokens_batched(
+        storage,
+        &mut batch,
+        source,
+        validator,
+        amount,
+        current_epoch,
+        is_redelegation,
+    )?;
+    batch.flush(storage)?;
+    Ok(result)
+}
+
+/// Unbond tokens, accumulating the updates of the unbonded and redelegated
+/// amounts in the given `batch` instead of writing them directly. The caller
+/// is responsible for flushing the batch.
+fn unbond_tokens_batched<S>(
+    storage: &mut S,
+    batch: &mut AmountWriteBatch,
+    source: Option<&Address>,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+    is_redelegation: bool,
+) -> storage_api::Result<ResultSlashing>
 where
     S: StorageRead + StorageWrite,
 {
@@ -1778,7 +2396,11 @@ where
                     storage,
                     &redelegated_bonds.at(&bond_epoch),
                     bond_epoch,
-                    cur_bond_amount - new_bond_amount,
+                    checked_amount_sub(
+                        cur_bond_amount,
+                        new_bond_amount,
+                        "redelegated bond amount to unbond",
+                    )?,
                 )?
             } else {
                 ModifiedRedelegation::default()
@@ -1839,10 +2461,10 @@ where
     // Update the unbonds in storage using the eager map computed above
     if !is_redelegation {
         for (start_epoch, &unbond_amount) in new_unbonds_map.iter() {
-            unbonds.at(start_epoch).update(
-                storage,
-                withdrawable_epoch,
-                |cur_val| cur_val.unwrap_or_default() + unbond_amount,
+            batch.add(
+                &unbonds.at(start_epoch),
+                &withdrawable_epoch,
+                unbond_amount,
             )?;
         }
     }
@@ -1895,11 +2517,11 @@ where
             for (src_validator, redelegated_unbonds) in unbonds {
                 let redelegated_unbonded =
                     this_redelegated_unbonded.at(src_validator);
-                for (&redelegation_epoch, &change) in redelegated_unbonds {
-                    redelegated_unbonded.update(
-                        storage,
+                for (redelegation_epoch, &change) in redelegated_unbonds {
+                    batch.add(
+                        &redelegated_unbonded,
                         redelegation_epoch,
-                        |current| current.unwrap_or_default() + change,
+                        change,
                     )?;
                 }
             }
@@ -1911,13 +2533,9 @@ where
     // Update the validator's total bonded and unbonded amounts
     let total_bonded = total_bonded_handle(validator).get_data_handler();
     let total_unbonded = total_unbonded_handle(validator).at(&pipeline_epoch);
-    for (&start_epoch, &amount) in &new_unbonds_map {
-        total_bonded.update(storage, start_epoch, |current| {
-            current.unwrap_or_default() - amount
-        })?;
-        total_unbonded.update(storage, start_epoch, |current| {
-            current.unwrap_or_default() + amount
-        })?;
+    for (start_epoch, &amount) in &new_unbonds_map {
+        batch.sub(&total_bonded, start_epoch, amount)?;
+        batch.add(&total_unbonded, start_epoch, amount)?;
     }
 
     let total_redelegated_bonded =
@@ -1931,22 +2549,14 @@ where
                 let bonded_sub_map = total_redelegated_bonded
                     .at(redelegation_start_epoch)
                     .at(src_validator);
-                bonded_sub_map.update(
-                    storage,
-                    *bond_start_epoch,
-                    |current| current.unwrap_or_default() - *change,
-                )?;
+                batch.sub(&bonded_sub_map, bond_start_epoch, *change)?;
 
                 // total redelegated unbonded
                 let unbonded_sub_map = total_redelegated_unbonded
                     .at(&pipeline_epoch)
                     .at(redelegation_start_epoch)
                     .at(src_validator);
-                unbonded_sub_map.update(
-                    storage,
-                    *bond_start_epoch,
-                    |current| current.unwrap_or_default() + *change,
-                )?;
+                batch.add(&unbonded_sub_map, bond_start_epoch, *change)?;
             }
         }
     }
@@ -2077,6 +2687,17 @@ where
         add_rewards_to_counter(storage, source, validator, rewards)?;
     }
 
+    update_bond_amount_checkpoints(
+        storage,
+        &params,
+        &BondId {
+            source: source.clone(),
+            validator: validator.clone(),
+        },
+        current_epoch,
+        pipeline_epoch,
+    )?;
+
     Ok(result_slashing)
 }
 
@@ -2096,7 +2717,7 @@ fn fold_and_slash_redelegated_bonds<S>(
     start_epoch: Epoch,
     list_slashes: &[Slash],
     slash_epoch_filter: impl Fn(Epoch) -> bool,
-) -> FoldRedelegatedBondsResult
+) -> storage_api::Result<FoldRedelegatedBondsResult>
 where
     S: StorageRead,
 {
@@ -2107,32 +2728,37 @@ where
             let mut merged: Vec<Slash> =
             // Look-up slashes for this validator ...
                 validator_slashes_handle(src_validator)
-                    .iter(storage)
-                    .unwrap()
-                    .map(Result::unwrap)
-                    .filter(|slash| {
-                        params.in_redelegation_slashing_window(
+                    .iter(storage)?
+                    .try_filter(|slash| {
+                        Ok(params.in_redelegation_slashing_window(
                             slash.epoch,
                             params.redelegation_start_epoch_from_end(
                                 start_epoch,
                             ),
                             start_epoch,
                         ) && *bond_start <= slash.epoch
-                            && slash_epoch_filter(slash.epoch)
+                            && slash_epoch_filter(slash.epoch))
                     })
                     // ... and add `list_slashes`
-                    .chain(list_slashes.iter().cloned())
-                    .collect();
+                    .chain(list_slashes.iter().cloned().map(Ok))
+                    .collect_eager()?;
 
             // Sort slashes by epoch
             merged.sort_by(|s1, s2| s1.epoch.partial_cmp(&s2.epoch).unwrap());
 
-            result.total_redelegated += change;
-            result.total_after_slashing +=
-                apply_list_slashes(params, &merged, change);
+            result.total_redelegated = checked_amount_add(
+                result.total_redelegated,
+                change,
+                "total redelegated amount",
+            )?;
+            result.total_after_slashing = checked_amount_add(
+                result.total_after_slashing,
+                apply_list_slashes(params, &merged, change),
+                "total redelegated amount after slashing",
+            )?;
         }
     }
-    result
+    Ok(result)
 }
 
 /// Computes how much remains from an amount of tokens after applying a list of
@@ -2260,7 +2886,11 @@ where
             },
             amount,
         ) = rb?;
-        total_redelegated += amount;
+        total_redelegated = checked_amount_add(
+            total_redelegated,
+            amount,
+            "total redelegated amount",
+        )?;
         src_validators.insert(src_validator);
     }
 
@@ -2278,13 +2908,10 @@ where
             break;
         }
         let rbonds = redelegated_bonds.at(&src_validator);
-        let total_src_val_amount = rbonds
-            .iter(storage)?
-            .map(|res| {
-                let (_, amount) = res?;
-                Ok(amount)
-            })
-            .sum::<storage_api::Result<token::Amount>>()?;
+        let total_src_val_amount = checked_amount_sum(
+            rbonds.iter(storage)?.try_map(|(_, amount)| Ok(amount)),
+            "redelegated amount from the source validator",
+        )?;
 
         // TODO: move this into the `if total_redelegated <= remaining` branch
         // below, then we don't have to remove it in `fn
@@ -2297,7 +2924,11 @@ where
             .validators_to_remove
             .insert(src_validator.clone());
         if total_src_val_amount <= remaining {
-            remaining -= total_src_val_amount;
+            remaining = checked_amount_sub(
+                remaining,
+                total_src_val_amount,
+                "remaining redelegated amount to unbond",
+            )?;
         } else {
             let bonds_to_remove =
                 find_bonds_to_remove(storage, &rbonds, remaining)?;
@@ -2430,14 +3061,16 @@ where
         epochs
             .iter()
             .cloned()
-            .filter(|e| redelegated_bonds.contains(storage, e).unwrap())
-            .collect::<BTreeSet<Epoch>>()
+            .map(Ok)
+            .try_filter(|e| redelegated_bonds.contains(storage, e))
+            .collect_eager::<BTreeSet<Epoch>>()?
     } else {
         epochs_to_remove
             .iter()
             .cloned()
-            .filter(|e| redelegated_bonds.contains(storage, e).unwrap())
-            .collect::<BTreeSet<Epoch>>()
+            .map(Ok)
+            .try_filter(|e| redelegated_bonds.contains(storage, e))
+            .collect_eager::<BTreeSet<Epoch>>()?
     };
     debug_assert!(
         modified
@@ -2468,20 +3101,20 @@ where
                 .unwrap_or(true)
                 || modified.validators_to_remove.is_empty()
             {
-                for res in redelegated_bonds.at(&start).iter(storage).unwrap() {
+                for res in redelegated_bonds.at(&start).iter(storage)? {
                     let (
                         NestedSubKey::Data {
                             key: validator,
                             nested_sub_key: SubKey::Data(epoch),
                         },
                         amount,
-                    ) = res.unwrap();
+                    ) = res?;
                     rbonds
                         .entry(validator.clone())
                         .or_default()
                         .insert(epoch, amount);
                 }
-                (start, rbonds)
+                Ok((start, rbonds))
             } else {
                 for src_validator in &modified.validators_to_remove {
                     if modified
@@ -2492,8 +3125,8 @@ where
                     {
                         let raw_bonds =
                             redelegated_bonds.at(&start).at(src_validator);
-                        for res in raw_bonds.iter(storage).unwrap() {
-                            let (bond_epoch, bond_amount) = res.unwrap();
+                        for res in raw_bonds.iter(storage)? {
+                            let (bond_epoch, bond_amount) = res?;
                             rbonds
                                 .entry(src_validator.clone())
                                 .or_default()
@@ -2504,8 +3137,7 @@ where
                             let cur_redel_bond_amount = redelegated_bonds
                                 .at(&start)
                                 .at(src_validator)
-                                .get(storage, bond_start)
-                                .unwrap()
+                                .get(storage, bond_start)?
                                 .unwrap_or_default();
                             let raw_bonds = rbonds
                                 .entry(src_validator.clone())
@@ -2521,22 +3153,22 @@ where
                             } else {
                                 raw_bonds.insert(
                                     *bond_start,
-                                    cur_redel_bond_amount
-                                        - modified
-                                            .new_amount
-                                            // Safe unwrap - it shouldn't
-                                            // get to
-                                            // this if it's None
-                                            .unwrap(),
+                                    checked_amount_sub(
+                                        cur_redel_bond_amount,
+                                        // Safe unwrap - it shouldn't get to
+                                        // this if it's None
+                                        modified.new_amount.unwrap(),
+                                        "redelegated unbond amount",
+                                    )?,
                                 );
                             }
                         }
                     }
                 }
-                (start, rbonds)
+                Ok((start, rbonds))
             }
         })
-        .collect();
+        .collect_eager()?;
 
     Ok(new_redelegated_unbonds)
 }
@@ -2578,10 +3210,12 @@ fn get_slashed_amount(
         });
     }
 
-    let total_computed_amounts = computed_amounts
-        .into_iter()
-        .map(|slashed| slashed.amount)
-        .sum();
+    let total_computed_amounts = checked_amount_sum(
+        computed_amounts
+            .into_iter()
+            .map(|slashed| Ok(slashed.amount)),
+        "total slashed amount",
+    )?;
 
     let final_amount = updated_amount
         .checked_sub(total_computed_amounts)
@@ -2620,7 +3254,7 @@ where
                 start_epoch,
                 &list_slashes,
                 |_| true,
-            )
+            )?
         } else {
             FoldRedelegatedBondsResult::default()
         };
@@ -2632,10 +3266,17 @@ where
         let after_not_redelegated =
             apply_list_slashes(params, &list_slashes, total_not_redelegated);
         // `val amountAfterSlashing`
-        let amount_after_slashing =
-            after_not_redelegated + result_fold.total_after_slashing;
+        let amount_after_slashing = checked_amount_add(
+            after_not_redelegated,
+            result_fold.total_after_slashing,
+            "unbond amount after slashing",
+        )?;
         // Accumulation step
-        result_slashing.sum += amount_after_slashing;
+        result_slashing.sum = checked_amount_add(
+            result_slashing.sum,
+            amount_after_slashing,
+            "total unbond amount after slashing",
+        )?;
         result_slashing
             .epoch_map
             .insert(start_epoch, amount_after_slashing);
@@ -2688,19 +3329,30 @@ where
             *start_epoch,
             &list_slashes,
             |_| true,
-        );
+        )?;
 
         // Unbond amount that didn't come from a redelegation
-        let total_not_redelegated = *amount - result_fold.total_redelegated;
+        let total_not_redelegated = checked_amount_sub(
+            *amount,
+            result_fold.total_redelegated,
+            "unbond amount not redelegated",
+        )?;
         // Find how much remains after slashing non-redelegated amount
         let after_not_redelegated =
             apply_list_slashes(params, &list_slashes, total_not_redelegated);
 
         // Add back the unbond and redelegated unbond amount after slashing
-        let amount_after_slashing =
-            after_not_redelegated + result_fold.total_after_slashing;
+        let amount_after_slashing = checked_amount_add(
+            after_not_redelegated,
+            result_fold.total_after_slashing,
+            "unbond amount after slashing",
+        )?;
 
-        result_slashing.sum += amount_after_slashing;
+        result_slashing.sum = checked_amount_add(
+            result_slashing.sum,
+            amount_after_slashing,
+            "total unbond amount after slashing",
+        )?;
         result_slashing
             .epoch_map
             .insert(*start_epoch, amount_after_slashing);
@@ -2780,7 +3432,14 @@ where
         ));
     }
 
-    // This will fail if the key is already being used
+    // This will fail if the key is reserved by another account or if it's
+    // already being used
+    claim_consensus_key_reservation(
+        storage,
+        address,
+        consensus_key,
+        current_epoch,
+    )?;
     try_insert_consensus_key(storage, consensus_key)?;
 
     let pipeline_epoch = current_epoch + offset;
@@ -2837,8 +3496,9 @@ where
 
     // The validator's stake at initialization is 0, so its state is immediately
     // below-threshold
-    validator_state_handle(address).set(
+    set_validator_state(
         storage,
+        address,
         ValidatorState::BelowThreshold,
         current_epoch,
         offset,
@@ -2877,6 +3537,12 @@ where
     }
 
     // Check for uniqueness of the consensus key
+    claim_consensus_key_reservation(
+        storage,
+        validator,
+        consensus_key,
+        current_epoch,
+    )?;
     try_insert_consensus_key(storage, consensus_key)?;
 
     // Set the new consensus key at the pipeline epoch
@@ -2894,6 +3560,96 @@ where
     Ok(())
 }
 
+/// Ethereum bridge hot and cold keys change for a validator. The new keys take
+/// effect at the pipeline epoch. The transaction applying this change must be
+/// authorized by the validator's current Ethereum cold key.
+///
+/// The validator set update vote extension is signed for the next epoch's
+/// consensus set at the start of every epoch, so the bridge contracts learn
+/// the new keys with the update for the pipeline epoch.
+pub fn change_validator_eth_keys<S>(
+    storage: &mut S,
+    validator: &Address,
+    eth_hot_key: &common::PublicKey,
+    eth_cold_key: &common::PublicKey,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!("Changing Ethereum keys for validator {}", validator);
+
+    if !is_validator(storage, validator)? {
+        return Err(EthKeysChangeError::NotAValidator(validator.clone()).into());
+    }
+    // Require that the new keys are Secp256k1 keys, as they are used to sign
+    // messages for the Ethereum bridge contracts
+    if !matches!(eth_hot_key, common::PublicKey::Secp256k1(_)) {
+        return Err(EthKeysChangeError::MustBeSecp256k1("hot").into());
+    }
+    if !matches!(eth_cold_key, common::PublicKey::Secp256k1(_)) {
+        return Err(EthKeysChangeError::MustBeSecp256k1("cold").into());
+    }
+    if eth_hot_key == eth_cold_key {
+        return Err(EthKeysChangeError::HotEqCold.into());
+    }
+
+    // Set the new keys at the pipeline epoch
+    let params = read_pos_params(storage)?;
+    validator_eth_hot_key_handle(validator).set(
+        storage,
+        eth_hot_key.clone(),
+        current_epoch,
+        params.pipeline_len,
+    )?;
+    validator_eth_cold_key_handle(validator).set(
+        storage,
+        eth_cold_key.clone(),
+        current_epoch,
+        params.pipeline_len,
+    )?;
+
+    Ok(())
+}
+
+/// Read the validator's operator key, if any. A tx signed with the operator
+/// key may change the validator's commission rate and metadata and unjail the
+/// validator, on behalf of the validator's account keys.
+pub fn read_validator_operator_key<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<Option<common::PublicKey>>
+where
+    S: StorageRead,
+{
+    storage.read(&validator_operator_key_key(validator))
+}
+
+/// Set the validator's operator key, or remove it when `None`. The change
+/// takes effect immediately. The transaction applying this change must be
+/// authorized by the validator's account keys.
+pub fn change_validator_operator_key<S>(
+    storage: &mut S,
+    validator: &Address,
+    operator_key: Option<&common::PublicKey>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!("Changing the operator key of validator {}", validator);
+
+    if !is_validator(storage, validator)? {
+        return Err(
+            OperatorKeyChangeError::NotAValidator(validator.clone()).into()
+        );
+    }
+    let key = validator_operator_key_key(validator);
+    match operator_key {
+        Some(operator_key) => storage.write(&key, operator_key),
+        None => storage.delete(&key),
+    }
+}
+
 /// Withdraw tokens from those that have been unbonded from proof-of-stake
 pub fn withdraw_tokens<S>(
     storage: &mut S,
@@ -2923,6 +3679,11 @@ where
         .into());
     }
 
+    // The unbonds may be affected by slashes that are not applied yet
+    if is_slash_processing_in_progress(storage)? {
+        return Err(WithdrawError::SlashProcessingInProgress.into());
+    }
+
     let mut unbonds_and_redelegated_unbonds: BTreeMap<
         (Epoch, Epoch),
         (token::Amount, EagerRedelegatedBondsMap),
@@ -2950,23 +3711,12 @@ where
             continue;
         }
 
-        let mut eager_redelegated_unbonds = EagerRedelegatedBondsMap::default();
-        let matching_redelegated_unbonds =
-            redelegated_unbonds.at(&start_epoch).at(&withdraw_epoch);
-        for ub in matching_redelegated_unbonds.iter(storage)? {
-            let (
-                NestedSubKey::Data {
-                    key: address,
-                    nested_sub_key: SubKey::Data(epoch),
-                },
-                amount,
-            ) = ub?;
-            eager_redelegated_unbonds
-                .entry(address)
-                .or_default()
-                .entry(epoch)
-                .or_insert(amount);
-        }
+        let eager_redelegated_unbonds = read_eager_redelegated_unbonds(
+            storage,
+            &redelegated_unbonds,
+            start_epoch,
+            withdraw_epoch,
+        )?;
 
         unbonds_and_redelegated_unbonds.insert(
             (start_epoch, withdraw_epoch),
@@ -3010,15 +3760,20 @@ where
         }
     }
 
-    // Transfer the withdrawable tokens from the PoS address back to the source
-    let staking_token = staking_token_address(storage);
-    token::transfer(
-        storage,
-        &staking_token,
-        &ADDRESS,
-        source,
-        withdrawable_amount,
-    )?;
+    // Transfer the withdrawable tokens from the PoS address back to the
+    // source, or to the vesting address for vesting accounts
+    if vesting::is_vesting_account(storage, source)? {
+        vesting::credit_locked(storage, &ADDRESS, source, withdrawable_amount)?;
+    } else {
+        let staking_token = staking_token_address(storage);
+        token::transfer(
+            storage,
+            &staking_token,
+            &ADDRESS,
+            source,
+            withdrawable_amount,
+        )?;
+    }
 
     // TODO: Transfer the slashed tokens from the PoS address to the Slash Pool
     // address
@@ -3033,84 +3788,298 @@ where
     Ok(withdrawable_amount)
 }
 
-/// Change the commission rate of a validator
-pub fn change_validator_commission_rate<S>(
+/// Get the storage handle to the actions scheduled at epoch transitions
+pub fn scheduled_actions_handle() -> ScheduledActions {
+    let key = scheduled_actions_key();
+    ScheduledActions::open(key)
+}
+
+/// Schedule the tokens unbonded from a bond in the current epoch to be
+/// withdrawn automatically as soon as they become withdrawable, so that the
+/// owner doesn't have to submit a withdrawal. Returns the epoch in which the
+/// withdrawal will be executed.
+pub fn schedule_withdrawal<S>(
     storage: &mut S,
+    source: Option<&Address>,
     validator: &Address,
-    new_rate: Dec,
     current_epoch: Epoch,
-) -> storage_api::Result<()>
+) -> storage_api::Result<Epoch>
 where
     S: StorageRead + StorageWrite,
 {
-    if new_rate.is_negative() {
-        return Err(CommissionRateChangeError::NegativeRate(
-            new_rate,
-            validator.clone(),
-        )
-        .into());
-    }
-
-    if new_rate > Dec::one() {
-        return Err(CommissionRateChangeError::LargerThanOne(
-            new_rate,
-            validator.clone(),
-        )
-        .into());
-    }
-
-    let max_change =
-        read_validator_max_commission_rate_change(storage, validator)?;
-    if max_change.is_none() {
-        return Err(CommissionRateChangeError::NoMaxSetInStorage(
-            validator.clone(),
-        )
-        .into());
-    }
-
     let params = read_pos_params(storage)?;
-    let commission_handle = validator_commission_rate_handle(validator);
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-
-    let rate_at_pipeline = commission_handle
-        .get(storage, pipeline_epoch, &params)?
-        .expect("Could not find a rate in given epoch");
-    if new_rate == rate_at_pipeline {
-        return Ok(());
-    }
-    let rate_before_pipeline = commission_handle
-        .get(storage, pipeline_epoch.prev(), &params)?
-        .expect("Could not find a rate in given epoch");
-
-    let change_from_prev = new_rate.abs_diff(&rate_before_pipeline);
-    if change_from_prev > max_change.unwrap() {
-        return Err(CommissionRateChangeError::RateChangeTooLarge(
-            change_from_prev,
-            validator.clone(),
-        )
-        .into());
-    }
+    let source = source.unwrap_or(validator);
+    let withdrawable_epoch = current_epoch + params.withdrawable_epoch_offset();
+    tracing::debug!(
+        "Scheduling a withdrawal of unbonds from {validator} to {source} in \
+         epoch {withdrawable_epoch}"
+    );
 
-    commission_handle.set(storage, new_rate, current_epoch, params.pipeline_len)
+    let action = ScheduledAction::Withdraw(BondId {
+        source: source.clone(),
+        validator: validator.clone(),
+    });
+    scheduled_actions_handle()
+        .at(&withdrawable_epoch)
+        .push(storage, action)?;
+    Ok(withdrawable_epoch)
 }
 
-/// Check if the given consensus key is already being used to ensure uniqueness.
-///
-/// If it's not being used, it will be inserted into the set that's being used
-/// for this. If it's already used, this will return an Error.
-pub fn try_insert_consensus_key<S>(
+/// Execute the actions scheduled for the current epoch, and any past epochs
+/// whose actions were deferred, and remove them from the queue. This must be
+/// called at the beginning of an epoch, after the slashes have been processed
+/// so that the withdrawals account for them.
+pub fn process_scheduled_actions<S>(
     storage: &mut S,
-    consensus_key: &common::PublicKey,
+    current_epoch: Epoch,
 ) -> storage_api::Result<()>
 where
     S: StorageRead + StorageWrite,
 {
-    let key = consensus_keys_key();
-    LazySet::open(key).try_insert(storage, consensus_key.clone())
-}
+    let scheduled_actions = scheduled_actions_handle();
+    let actions = scheduled_actions
+        .iter(storage)?
+        .try_filter(|(NestedSubKey::Data { key: epoch, .. }, _)| {
+            Ok(*epoch <= current_epoch)
+        })
+        .try_map(|(NestedSubKey::Data { key: epoch, .. }, action)| {
+            Ok((epoch, action))
+        })
+        .collect_eager::<Vec<_>>()?;
+    if actions.is_empty() {
+        return Ok(());
+    }
+    tracing::debug!(
+        "Processing {} scheduled actions at the beginning of epoch \
+         {current_epoch}",
+        actions.len()
+    );
 
-/// Get the unique set of consensus keys in storage
-pub fn get_consensus_key_set<S>(
+    let mut epochs = BTreeSet::new();
+    for (epoch, action) in actions {
+        epochs.insert(epoch);
+        match action {
+            ScheduledAction::Withdraw(BondId { source, validator }) => {
+                // The unbonds may have already been withdrawn by the owner
+                if unbond_handle(&source, &validator).is_empty(storage)? {
+                    tracing::debug!(
+                        "Nothing left to withdraw from {validator} to {source}"
+                    );
+                    continue;
+                }
+                let amount = withdraw_tokens(
+                    storage,
+                    Some(&source),
+                    &validator,
+                    current_epoch,
+                )?;
+                tracing::debug!(
+                    "Withdrew {} from {validator} to {source}",
+                    amount.to_string_native()
+                );
+            }
+        }
+    }
+
+    for epoch in epochs {
+        scheduled_actions.remove_all(storage, &epoch)?;
+    }
+    Ok(())
+}
+
+/// Read the redelegated unbonds matching an unbond of the given start and
+/// withdraw epochs into an eager map
+fn read_eager_redelegated_unbonds<S>(
+    storage: &S,
+    redelegated_unbonds: &NestedMap<Epoch, RedelegatedBondsOrUnbonds>,
+    start_epoch: Epoch,
+    withdraw_epoch: Epoch,
+) -> storage_api::Result<EagerRedelegatedBondsMap>
+where
+    S: StorageRead,
+{
+    let mut eager_redelegated_unbonds = EagerRedelegatedBondsMap::default();
+    let matching_redelegated_unbonds =
+        redelegated_unbonds.at(&start_epoch).at(&withdraw_epoch);
+    for ub in matching_redelegated_unbonds.iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: address,
+                nested_sub_key: SubKey::Data(epoch),
+            },
+            amount,
+        ) = ub?;
+        eager_redelegated_unbonds
+            .entry(address)
+            .or_default()
+            .entry(epoch)
+            .or_insert(amount);
+    }
+    Ok(eager_redelegated_unbonds)
+}
+
+/// Find the unbonding queue of a bond, i.e. all the unbonds of the given
+/// `source` (or self-bonds when `None`) from the `validator`, each with the
+/// number of epochs left until it can be withdrawn as of `current_epoch` and
+/// the amount that can be withdrawn after the application of slashes. Note
+/// that slashes processed after `current_epoch` may further reduce the
+/// withdrawable amount of unbonds that are not yet withdrawable. The unbonds
+/// are ordered by their start and then withdraw epochs.
+pub fn find_withdrawable_unbonds<S>(
+    storage: &S,
+    source: Option<&Address>,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<Vec<QueuedUnbond>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let source = source.unwrap_or(validator);
+
+    let redelegated_unbonds =
+        delegator_redelegated_unbonds_handle(source).at(validator);
+    let slashes = find_validator_slashes(storage, validator)?;
+
+    let mut unbonds = BTreeMap::new();
+    for unbond in unbond_handle(source, validator).iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: start_epoch,
+                nested_sub_key: SubKey::Data(withdraw_epoch),
+            },
+            amount,
+        ) = unbond?;
+        let eager_redelegated_unbonds = read_eager_redelegated_unbonds(
+            storage,
+            &redelegated_unbonds,
+            start_epoch,
+            withdraw_epoch,
+        )?;
+        unbonds.insert(
+            (start_epoch, withdraw_epoch),
+            (amount, eager_redelegated_unbonds),
+        );
+    }
+
+    unbonds
+        .into_iter()
+        .map(|((start_epoch, withdraw_epoch), (amount, redelegated))| {
+            let unbond = BTreeMap::from([(
+                (start_epoch, withdraw_epoch),
+                (amount, redelegated),
+            )]);
+            let withdrawable_amount = compute_amount_after_slashing_withdraw(
+                storage,
+                &params,
+                &unbond,
+                slashes.clone(),
+            )?
+            .sum;
+            Ok(QueuedUnbond {
+                start_epoch,
+                withdraw_epoch,
+                epochs_until_withdrawable: withdraw_epoch
+                    .0
+                    .saturating_sub(current_epoch.0),
+                amount,
+                withdrawable_amount,
+            })
+        })
+        .collect()
+}
+
+/// Change the commission rate of a validator
+pub fn change_validator_commission_rate<S>(
+    storage: &mut S,
+    validator: &Address,
+    new_rate: Dec,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if new_rate.is_negative() {
+        return Err(CommissionRateChangeError::NegativeRate(
+            new_rate,
+            validator.clone(),
+        )
+        .into());
+    }
+
+    if new_rate > Dec::one() {
+        return Err(CommissionRateChangeError::LargerThanOne(
+            new_rate,
+            validator.clone(),
+        )
+        .into());
+    }
+
+    let max_change =
+        read_validator_max_commission_rate_change(storage, validator)?;
+    if max_change.is_none() {
+        return Err(CommissionRateChangeError::NoMaxSetInStorage(
+            validator.clone(),
+        )
+        .into());
+    }
+
+    let params = read_pos_params(storage)?;
+    let commission_handle = validator_commission_rate_handle(validator);
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let rate_at_pipeline = commission_handle
+        .get(storage, pipeline_epoch, &params)?
+        .expect("Could not find a rate in given epoch");
+    if new_rate == rate_at_pipeline {
+        return Ok(());
+    }
+
+    // All the changes submitted in the current epoch take effect at the same
+    // pipeline epoch, so the maximum change applies to their cumulative change
+    let change_in_epoch = read_commission_rate_change_in_epoch(
+        storage,
+        validator,
+        pipeline_epoch,
+    )?;
+    let cumulative_change =
+        change_in_epoch + new_rate.abs_diff(&rate_at_pipeline);
+    if cumulative_change > max_change.unwrap() {
+        return Err(CommissionRateChangeError::RateChangeTooLarge(
+            cumulative_change,
+            validator.clone(),
+        )
+        .into());
+    }
+
+    validator_commission_history_handle(validator).push(
+        storage,
+        CommissionRateChange {
+            epoch: pipeline_epoch,
+            old_rate: rate_at_pipeline,
+            new_rate,
+        },
+    )?;
+    commission_handle.set(storage, new_rate, current_epoch, params.pipeline_len)
+}
+
+/// Check if the given consensus key is already being used to ensure uniqueness.
+///
+/// If it's not being used, it will be inserted into the set that's being used
+/// for this. If it's already used, this will return an Error.
+pub fn try_insert_consensus_key<S>(
+    storage: &mut S,
+    consensus_key: &common::PublicKey,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = consensus_keys_key();
+    LazySet::open(key).try_insert(storage, consensus_key.clone())
+}
+
+/// Get the unique set of consensus keys in storage
+pub fn get_consensus_key_set<S>(
     storage: &S,
 ) -> storage_api::Result<BTreeSet<common::PublicKey>>
 where
@@ -3118,7 +4087,7 @@ where
 {
     let key = consensus_keys_key();
     let lazy_set = LazySet::<common::PublicKey>::open(key);
-    Ok(lazy_set.iter(storage)?.map(Result::unwrap).collect())
+    lazy_set.iter(storage)?.collect_eager()
 }
 
 /// Check if the given consensus key is already being used to ensure uniqueness.
@@ -3134,6 +4103,112 @@ where
     handle.contains(storage, consensus_key)
 }
 
+/// Get the storage handle to the consensus keys reservations
+pub fn consensus_key_reservations_handle() -> ConsensusKeyReservations {
+    let key = consensus_key_reservations_key();
+    ConsensusKeyReservations::open(key)
+}
+
+/// Find an unexpired reservation of the given consensus key. Returns the
+/// address of the reserving account and the epoch in which the key was
+/// reserved.
+pub fn find_consensus_key_reservation<S>(
+    storage: &S,
+    consensus_key: &common::PublicKey,
+    current_epoch: Epoch,
+) -> storage_api::Result<Option<(Address, Epoch)>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let oldest_epoch = ConsensusKeyReservationsPruning::oldest_epoch_to_keep(
+        &params,
+        current_epoch,
+    );
+    let reservations = consensus_key_reservations_handle();
+    for epoch in Epoch::iter_bounds_inclusive(oldest_epoch, current_epoch) {
+        if let Some(owner) =
+            reservations.at(&epoch).get(storage, consensus_key)?
+        {
+            return Ok(Some((owner, epoch)));
+        }
+    }
+    Ok(None)
+}
+
+/// Reserve an unused consensus key for the given account, ahead of it
+/// becoming a validator. While the reservation is active, only the reserving
+/// account can start using the key, which prevents anyone else from taking it
+/// over between the reservation and the [`become_validator`] transaction.
+///
+/// The reservation expires after the pipeline length. Reserving the same key
+/// again from the same account renews the reservation.
+pub fn reserve_consensus_key<S>(
+    storage: &mut S,
+    owner: &Address,
+    consensus_key: &common::PublicKey,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!("Reserving a consensus key for {owner}");
+
+    // Consensus keys are required to be Ed25519 keys
+    match consensus_key {
+        common::PublicKey::Ed25519(_) => {}
+        common::PublicKey::Secp256k1(_) => {
+            return Err(ConsensusKeyReservationError::MustBeEd25519.into());
+        }
+    }
+
+    if is_consensus_key_used(storage, consensus_key)? {
+        return Err(ConsensusKeyReservationError::AlreadyUsed.into());
+    }
+
+    // Replaces the owner's previous reservation, if any
+    claim_consensus_key_reservation(
+        storage,
+        owner,
+        consensus_key,
+        current_epoch,
+    )?;
+    consensus_key_reservations_handle()
+        .at(&current_epoch)
+        .insert(storage, consensus_key.clone(), owner.clone())?;
+    Ok(())
+}
+
+/// Check that the given consensus key is not reserved by an account other than
+/// the `claimant` and release the claimant's own reservation, if any, as the
+/// key is about to be used.
+fn claim_consensus_key_reservation<S>(
+    storage: &mut S,
+    claimant: &Address,
+    consensus_key: &common::PublicKey,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if let Some((reserved_by, reserved_at)) =
+        find_consensus_key_reservation(storage, consensus_key, current_epoch)?
+    {
+        if &reserved_by != claimant {
+            let params = read_pos_params(storage)?;
+            return Err(ConsensusKeyReservationError::ReservedByOther(
+                reserved_by,
+                reserved_at + params.pipeline_len,
+            )
+            .into());
+        }
+        consensus_key_reservations_handle()
+            .at(&reserved_at)
+            .remove(storage, consensus_key)?;
+    }
+    Ok(())
+}
+
 /// Get the total bond amount, including slashes, for a given bond ID and epoch.
 /// Returns the bond amount after slashing. For future epochs the value is
 /// subject to change.
@@ -3145,7 +4220,126 @@ pub fn bond_amount<S>(
 where
     S: StorageRead,
 {
+    let _span = tracing::debug_span!(
+        "bond_amount",
+        source = %bond_id.source,
+        validator = %bond_id.validator,
+        %epoch
+    )
+    .entered();
     let params = read_pos_params(storage)?;
+    if let Some(amount) =
+        read_bond_amount_checkpoint(storage, &params, bond_id, epoch)?
+    {
+        tracing::trace!("Found a bond amount checkpoint");
+        return Ok(amount);
+    }
+    tracing::trace!("Computing the bond amount from the bonds history");
+    compute_bond_amount(storage, &params, bond_id, epoch)
+}
+
+/// Read the bond amount at the given epoch from the latest checkpoint at or
+/// before it. Returns `None` if there is no such checkpoint within the data
+/// retention horizon or if it is stale because another of the validator's
+/// slashes has been applied since it was computed.
+fn read_bond_amount_checkpoint<S>(
+    storage: &S,
+    params: &PosParams,
+    bond_id: &BondId,
+    epoch: Epoch,
+) -> storage_api::Result<Option<token::Amount>>
+where
+    S: StorageRead,
+{
+    let checkpoint =
+        bond_amount_checkpoint_handle(&bond_id.source, &bond_id.validator)
+            .get(storage, epoch, params)?;
+    match checkpoint {
+        Some(checkpoint)
+            if checkpoint.last_slash
+                == read_validator_last_applied_slash(
+                    storage,
+                    &bond_id.validator,
+                )? =>
+        {
+            Ok(Some(checkpoint.amount))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Read the last of the validator's slashes that has been applied, if any
+fn read_validator_last_applied_slash<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<Option<Slash>>
+where
+    S: StorageRead,
+{
+    let slashes = validator_slashes_handle(validator);
+    match slashes.len(storage)?.checked_sub(1) {
+        Some(last) => slashes.get(storage, last),
+        None => Ok(None),
+    }
+}
+
+/// Record a checkpoint of a bond amount after a change to the bond that takes
+/// effect at the given epoch, and recompute the later checkpoints, which must
+/// also include the change. The later checkpoints may only be recorded up to
+/// the pipeline epoch. The checkpoints older than the data retention horizon
+/// are pruned.
+fn update_bond_amount_checkpoints<S>(
+    storage: &mut S,
+    params: &PosParams,
+    bond_id: &BondId,
+    current_epoch: Epoch,
+    epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let checkpoints =
+        bond_amount_checkpoint_handle(&bond_id.source, &bond_id.validator);
+    let data_handler = checkpoints.get_data_handler();
+    let mut to_update = vec![epoch];
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    for checkpoint_epoch in
+        Epoch::iter_bounds_inclusive(epoch.next(), pipeline_epoch)
+    {
+        if data_handler.get(storage, &checkpoint_epoch)?.is_some() {
+            to_update.push(checkpoint_epoch);
+        }
+    }
+    let last_slash =
+        read_validator_last_applied_slash(storage, &bond_id.validator)?;
+    for checkpoint_epoch in to_update {
+        let amount =
+            compute_bond_amount(storage, params, bond_id, checkpoint_epoch)?;
+        checkpoints.set(
+            storage,
+            BondAmountCheckpoint {
+                amount,
+                last_slash: last_slash.clone(),
+            },
+            current_epoch,
+            u64::from(checkpoint_epoch - current_epoch),
+        )?;
+    }
+    Ok(())
+}
+
+/// Compute the remaining bond amount at the given epoch from all the bonds,
+/// unbonds, redelegations and slashes. Prefer [`bond_amount`], which uses the
+/// checkpoints whenever possible.
+fn compute_bond_amount<S>(
+    storage: &S,
+    params: &PosParams,
+    bond_id: &BondId,
+    epoch: Epoch,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
     // Outer key is the start epoch used to calculate slashes. The inner
     // keys are discarded after applying slashes.
     let mut amounts: BTreeMap<Epoch, token::Amount> = BTreeMap::default();
@@ -3248,7 +4442,17 @@ where
         }
     }
 
-    if !amounts.is_empty() {
+    #[cfg(feature = "verbose-bond-amount")]
+    tracing::debug!(
+        "Bond amounts by start epoch before slashing: {amounts:#?}"
+    );
+
+    // The slashes only need to be applied if any have been processed by the
+    // given epoch
+    if !amounts.is_empty()
+        && !read_validator_cumulative_slash(storage, &bond_id.validator, epoch)?
+            .is_zero()
+    {
         let slashes = find_validator_slashes(storage, &bond_id.validator)?;
 
         // Apply slashes
@@ -3266,8 +4470,13 @@ where
                 .cloned()
                 .collect::<Vec<_>>();
 
-            *amount = apply_list_slashes(&params, &list_slashes, *amount);
+            *amount = apply_list_slashes(params, &list_slashes, *amount);
         }
+
+        #[cfg(feature = "verbose-bond-amount")]
+        tracing::debug!(
+            "Bond amounts by start epoch after slashing: {amounts:#?}"
+        );
     }
 
     Ok(amounts.values().cloned().sum())
@@ -3350,7 +4559,7 @@ where
                     start,
                     &list_slashes,
                     slash_epoch_filter,
-                );
+                )?;
 
                 let total_not_redelegated =
                     *amount - result_fold.total_redelegated;
@@ -3409,13 +4618,97 @@ where
     .collect()
 }
 
+/// Compute the changes in the consensus validator set from `from_epoch` to
+/// `to_epoch`. Validators that stayed in the set with the same bonded stake and
+/// consensus key are not included.
+///
+/// The result is deterministic: the added and updated validators come first,
+/// in the order of the `to_epoch` set, followed by the removed validators, in
+/// the order of the `from_epoch` set. The sets are read from the given `cache`,
+/// falling back to storage for any epoch that is not cached.
+pub fn compute_validator_set_diff<S>(
+    storage: &S,
+    params: &PosParams,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+    cache: &ConsensusValidatorSetCache,
+) -> storage_api::Result<Vec<ValidatorSetDiff>>
+where
+    S: StorageRead,
+{
+    let from_set = cache.read(storage, from_epoch)?;
+    let to_set = cache.read(storage, to_epoch)?;
+    let prev_stakes = from_set
+        .iter()
+        .map(|validator| (&validator.address, validator.bonded_stake))
+        .collect::<HashMap<_, _>>();
+    let new_addresses = to_set
+        .iter()
+        .map(|validator| &validator.address)
+        .collect::<HashSet<_>>();
+
+    let read_consensus_key = |address: &Address, epoch: Epoch| {
+        validator_consensus_key_handle(address)
+            .get(storage, epoch, params)?
+            .ok_or_else(|| {
+                storage_api::Error::new(format!(
+                    "Missing consensus key of validator {address} in epoch \
+                     {epoch}"
+                ))
+            })
+    };
+
+    let mut diff = vec![];
+    for validator in to_set.iter() {
+        let address = &validator.address;
+        let consensus_key = read_consensus_key(address, to_epoch)?;
+        match prev_stakes.get(address) {
+            None => diff.push(ValidatorSetDiff::Added {
+                address: address.clone(),
+                consensus_key,
+                bonded_stake: validator.bonded_stake,
+            }),
+            Some(&prev_bonded_stake) => {
+                let prev_consensus_key =
+                    read_consensus_key(address, from_epoch)?;
+                if prev_bonded_stake != validator.bonded_stake
+                    || prev_consensus_key != consensus_key
+                {
+                    diff.push(ValidatorSetDiff::Updated {
+                        address: address.clone(),
+                        consensus_key,
+                        prev_consensus_key,
+                        bonded_stake: validator.bonded_stake,
+                        prev_bonded_stake,
+                    })
+                }
+            }
+        }
+    }
+    for validator in from_set.iter() {
+        let address = &validator.address;
+        if !new_addresses.contains(address) {
+            diff.push(ValidatorSetDiff::Removed {
+                address: address.clone(),
+                prev_consensus_key: read_consensus_key(address, from_epoch)?,
+                prev_bonded_stake: validator.bonded_stake,
+            })
+        }
+    }
+    Ok(diff)
+}
+
 /// Communicate imminent validator set updates to Tendermint. This function is
 /// called two blocks before the start of a new epoch because Tendermint
 /// validator updates become active two blocks after the updates are submitted.
+///
+/// The consensus validator sets are read from the given `cache`, falling back
+/// to storage for any epoch that is not cached.
 pub fn validator_set_update_tendermint<S, T>(
     storage: &S,
     params: &PosParams,
     current_epoch: Epoch,
+    cache: &ConsensusValidatorSetCache,
     f: impl FnMut(ValidatorSetUpdate) -> T,
 ) -> storage_api::Result<Vec<T>>
 where
@@ -3426,190 +4719,91 @@ where
     // give Tendermint updates for the next epoch
     let next_epoch = current_epoch.next();
 
-    let new_consensus_validator_handle =
-        consensus_validator_set_handle().at(&next_epoch);
-    let prev_consensus_validator_handle =
-        consensus_validator_set_handle().at(&current_epoch);
-
-    let new_consensus_validators = new_consensus_validator_handle
-        .iter(storage)?
-        .map(|validator| {
-            let (
-                NestedSubKey::Data {
-                    key: new_stake,
-                    nested_sub_key: _,
-                },
-                address,
-            ) = validator.unwrap();
-
-            tracing::debug!(
-                "Consensus validator address {address}, stake {}",
-                new_stake.to_string_native()
-            );
-
-            let new_consensus_key = validator_consensus_key_handle(&address)
-                .get(storage, next_epoch, params)
-                .unwrap()
-                .unwrap();
-
-            let old_consensus_key = validator_consensus_key_handle(&address)
-                .get(storage, current_epoch, params)
-                .unwrap();
-
-            // Check if the validator was consensus in the previous epoch with
-            // the same stake. If so, no updated is needed.
-            // Look up previous state and prev and current voting powers
-            if !prev_consensus_validator_handle.is_empty(storage).unwrap() {
-                let prev_state = validator_state_handle(&address)
-                    .get(storage, current_epoch, params)
-                    .unwrap();
-                let prev_tm_voting_power = Lazy::new(|| {
-                    let prev_validator_stake = read_validator_stake(
-                        storage,
-                        params,
-                        &address,
-                        current_epoch,
-                    )
-                    .unwrap();
-                    into_tm_voting_power(
-                        params.tm_votes_per_token,
-                        prev_validator_stake,
-                    )
-                });
-                let new_tm_voting_power = Lazy::new(|| {
-                    into_tm_voting_power(params.tm_votes_per_token, new_stake)
-                });
-
-                // If it was in `Consensus` before and voting power has not
-                // changed, skip the update
-                if matches!(prev_state, Some(ValidatorState::Consensus))
-                    && *prev_tm_voting_power == *new_tm_voting_power
-                {
-                    if old_consensus_key.as_ref().unwrap() == &new_consensus_key
-                    {
-                        tracing::debug!(
-                            "skipping validator update, {address} is in \
-                             consensus set but voting power hasn't changed"
-                        );
-                        return vec![];
-                    } else {
-                        return vec![
-                            ValidatorSetUpdate::Consensus(ConsensusValidator {
-                                consensus_key: new_consensus_key,
-                                bonded_stake: new_stake,
-                            }),
-                            ValidatorSetUpdate::Deactivated(
-                                old_consensus_key.unwrap(),
-                            ),
-                        ];
-                    }
-                }
-                // If both previous and current voting powers are 0, and the
-                // validator_stake_threshold is 0, skip update
-                if params.validator_stake_threshold.is_zero()
-                    && *prev_tm_voting_power == 0
-                    && *new_tm_voting_power == 0
-                {
-                    tracing::info!(
-                        "skipping validator update, {address} is in consensus \
-                         set but without voting power"
-                    );
-                    return vec![];
-                }
+    let diff = compute_validator_set_diff(
+        storage,
+        params,
+        current_epoch,
+        next_epoch,
+        cache,
+    )?;
+    let voting_power =
+        |stake| into_tm_voting_power(params.tm_votes_per_token, stake);
+    // If the validator stake threshold is 0, validators without voting power
+    // may be in the consensus set, but they are not known to Tendermint
+    let skip_without_voting_power = params.validator_stake_threshold.is_zero();
+
+    let updates = diff.into_iter().flat_map(|entry| match entry {
+        ValidatorSetDiff::Added {
+            address,
+            consensus_key,
+            bonded_stake,
+        } => {
+            if skip_without_voting_power && voting_power(bonded_stake) == 0 {
+                tracing::info!(
+                    "skipping validator update, {address} is in consensus set \
+                     but without voting power"
+                );
+                return vec![];
             }
-
             tracing::debug!(
                 "{address} consensus key {}",
-                new_consensus_key.tm_raw_hash()
+                consensus_key.tm_raw_hash()
             );
-
-            if old_consensus_key.as_ref() == Some(&new_consensus_key)
-                || old_consensus_key.is_none()
-            {
-                vec![ValidatorSetUpdate::Consensus(ConsensusValidator {
-                    consensus_key: new_consensus_key,
-                    bonded_stake: new_stake,
-                })]
-            } else {
-                vec![
+            vec![ValidatorSetUpdate::Consensus(ConsensusValidator {
+                consensus_key,
+                bonded_stake,
+            })]
+        }
+        ValidatorSetDiff::Updated {
+            address,
+            consensus_key,
+            prev_consensus_key,
+            bonded_stake,
+            prev_bonded_stake,
+        } => {
+            if consensus_key != prev_consensus_key {
+                tracing::debug!(
+                    "{address} consensus key changed to {}",
+                    consensus_key.tm_raw_hash()
+                );
+                return vec![
                     ValidatorSetUpdate::Consensus(ConsensusValidator {
-                        consensus_key: new_consensus_key,
-                        bonded_stake: new_stake,
+                        consensus_key,
+                        bonded_stake,
                     }),
-                    ValidatorSetUpdate::Deactivated(old_consensus_key.unwrap()),
-                ]
+                    ValidatorSetUpdate::Deactivated(prev_consensus_key),
+                ];
             }
-        });
-
-    let prev_consensus_validators = prev_consensus_validator_handle
-        .iter(storage)?
-        .map(|validator| {
-            let (
-                NestedSubKey::Data {
-                    key: _prev_stake,
-                    nested_sub_key: _,
-                },
-                address,
-            ) = validator.unwrap();
-
-            let new_state = validator_state_handle(&address)
-                .get(storage, next_epoch, params)
-                .unwrap();
-
-            let prev_tm_voting_power = Lazy::new(|| {
-                let prev_validator_stake = read_validator_stake(
-                    storage,
-                    params,
-                    &address,
-                    current_epoch,
-                )
-                .unwrap();
-                into_tm_voting_power(
-                    params.tm_votes_per_token,
-                    prev_validator_stake,
-                )
-            });
-
-            let old_consensus_key = validator_consensus_key_handle(&address)
-                .get(storage, current_epoch, params)
-                .unwrap()
-                .unwrap();
-
-            // If the validator is still in the Consensus set, we accounted for
-            // it in the `new_consensus_validators` iterator above
-            if matches!(new_state, Some(ValidatorState::Consensus)) {
+            if voting_power(prev_bonded_stake) == voting_power(bonded_stake) {
+                tracing::debug!(
+                    "skipping validator update, {address} is in consensus set \
+                     but voting power hasn't changed"
+                );
                 return vec![];
-            } else if params.validator_stake_threshold.is_zero()
-                && *prev_tm_voting_power == 0
+            }
+            vec![ValidatorSetUpdate::Consensus(ConsensusValidator {
+                consensus_key,
+                bonded_stake,
+            })]
+        }
+        ValidatorSetDiff::Removed {
+            address,
+            prev_consensus_key,
+            prev_bonded_stake,
+        } => {
+            if skip_without_voting_power && voting_power(prev_bonded_stake) == 0
             {
-                // If the new state is not Consensus but its prev voting power
-                // was 0 and the stake threshold is 0, we can also skip the
-                // update
                 tracing::info!(
                     "skipping validator update, {address} is in consensus set \
                      but without voting power"
                 );
                 return vec![];
             }
+            vec![ValidatorSetUpdate::Deactivated(prev_consensus_key)]
+        }
+    });
 
-            // The remaining validators were previously Consensus but no longer
-            // are, so they must be deactivated
-            let consensus_key = validator_consensus_key_handle(&address)
-                .get(storage, next_epoch, params)
-                .unwrap()
-                .unwrap();
-            tracing::debug!(
-                "{address} consensus key {}",
-                consensus_key.tm_raw_hash()
-            );
-            vec![ValidatorSetUpdate::Deactivated(old_consensus_key)]
-        });
-
-    Ok(new_consensus_validators
-        .chain(prev_consensus_validators)
-        .flatten()
-        .map(f)
-        .collect())
+    Ok(updates.map(f).collect())
 }
 
 /// Find all validators to which a given bond `owner` (or source) has a
@@ -3692,6 +4886,61 @@ where
     validator_slashes_handle(validator).iter(storage)?.collect()
 }
 
+/// Read the cumulative rate of a validator's slashes processed at or before the
+/// given epoch, i.e. the fraction of a bond that contributed to the
+/// validator's stake since before any of its infractions that has been slashed
+/// by the given epoch. Returns zero if no slash has been processed yet.
+pub fn read_validator_cumulative_slash<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let mut rate = Dec::zero();
+    let rates = validator_cumulative_slash_rate_handle(validator);
+    for res in rates.iter(storage)? {
+        let (processing_epoch, cumulative_rate) = res?;
+        if processing_epoch > epoch {
+            break;
+        }
+        rate = cumulative_rate;
+    }
+    Ok(rate)
+}
+
+/// Update the cumulative slash rate of a validator with the slashes processed
+/// in the current epoch. A slash is applied to the part of the stake that has
+/// not been slashed by the slashes processed at or before its infraction
+/// epoch, consistently with `compute_slashable_amount`.
+fn update_validator_cumulative_slash<S>(
+    storage: &mut S,
+    validator: &Address,
+    new_slashes: &[Slash],
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut cumulative_rate =
+        read_validator_cumulative_slash(storage, validator, current_epoch)?;
+    for slash in new_slashes {
+        let slashable_fraction = Dec::one()
+            - read_validator_cumulative_slash(storage, validator, slash.epoch)?;
+        cumulative_rate = cmp::min(
+            Dec::one(),
+            cumulative_rate + slashable_fraction * slash.rate,
+        );
+    }
+    validator_cumulative_slash_rate_handle(validator).insert(
+        storage,
+        current_epoch,
+        cumulative_rate,
+    )?;
+    Ok(())
+}
+
 /// Find raw bond deltas for the given source and validator address.
 pub fn find_bonds<S>(
     storage: &S,
@@ -3742,16 +4991,7 @@ pub fn bonds_and_unbonds<S>(
 where
     S: StorageRead,
 {
-    let params = read_pos_params(storage)?;
-
-    match (source.clone(), validator.clone()) {
-        (Some(source), Some(validator)) => {
-            find_bonds_and_unbonds_details(storage, &params, source, validator)
-        }
-        _ => {
-            get_multiple_bonds_and_unbonds(storage, &params, source, validator)
-        }
-    }
+    PosQueryCtx::new(storage)?.bonds_and_unbonds(source, validator)
 }
 
 /// Collect the details of all of the enqueued slashes to be processed in future
@@ -3787,43 +5027,110 @@ where
             .or_default();
         slashes.push(slash);
     }
-    Ok(enqueued)
+    Ok(enqueued)
+}
+
+/// Find all slashes and the associated validators in the PoS system
+pub fn find_all_slashes<S>(
+    storage: &S,
+) -> storage_api::Result<HashMap<Address, Vec<Slash>>>
+where
+    S: StorageRead,
+{
+    let filter = SlashFilter {
+        status: Some(SlashStatus::Processed),
+        ..Default::default()
+    };
+    let mut slashes: HashMap<Address, Vec<Slash>> = HashMap::new();
+    // The current epoch only affects the enqueued slashes
+    for record in iter_slashes(storage, filter, Epoch::default())? {
+        let SlashRecord {
+            validator, slash, ..
+        } = record?;
+        slashes.entry(validator).or_default().push(slash);
+    }
+    Ok(slashes)
 }
 
-/// Find all slashes and the associated validators in the PoS system
-pub fn find_all_slashes<S>(
-    storage: &S,
-) -> storage_api::Result<HashMap<Address, Vec<Slash>>>
+/// Iterate the slashes that match the given filter. The processed slashes
+/// are streamed from storage in the order of the validators' addresses,
+/// followed by the slashes enqueued to be processed after the given current
+/// epoch, in the order of their processing epochs. The enqueued slashes only
+/// span a few epochs, so they are read eagerly.
+pub fn iter_slashes<'iter, S>(
+    storage: &'iter S,
+    filter: SlashFilter,
+    current_epoch: Epoch,
+) -> storage_api::Result<
+    impl Iterator<Item = storage_api::Result<SlashRecord>> + 'iter,
+>
 where
     S: StorageRead,
 {
-    let mut slashes: HashMap<Address, Vec<Slash>> = HashMap::new();
-    let slashes_iter = storage_api::iter_prefix_bytes(
-        storage,
-        &slashes_prefix(),
-    )?
-    .filter_map(|result| {
-        if let Ok((key, val_bytes)) = result {
-            if let Some(validator) = is_validator_slashes_key(&key) {
-                let slash: Slash =
-                    BorshDeserialize::try_from_slice(&val_bytes).ok()?;
-                return Some((validator, slash));
+    let processed: Box<
+        dyn Iterator<Item = storage_api::Result<(Address, Slash)>> + 'iter,
+    > = if !filter.includes_status(SlashStatus::Processed) {
+        Box::new(std::iter::empty())
+    } else if let Some(validator) = filter.validator.clone() {
+        let slashes = validator_slashes_handle(&validator).iter(storage)?;
+        Box::new(slashes.map(move |slash| Ok((validator.clone(), slash?))))
+    } else {
+        let slashes =
+            storage_api::iter_prefix_bytes(storage, &slashes_prefix())?;
+        Box::new(slashes.filter_map(|res| {
+            let (key, val_bytes) = match res {
+                Ok(key_val) => key_val,
+                Err(err) => return Some(Err(err)),
+            };
+            let validator = is_validator_slashes_key(&key)?;
+            Some(
+                Slash::try_from_slice(&val_bytes)
+                    .into_storage_result()
+                    .map(|slash| (validator, slash)),
+            )
+        }))
+    };
+
+    let mut enqueued = Vec::new();
+    if filter.includes_status(SlashStatus::Enqueued) {
+        for res in enqueued_slashes_handle().get_data_handler().iter(storage)? {
+            let (
+                NestedSubKey::Data {
+                    key: processing_epoch,
+                    nested_sub_key:
+                        NestedSubKey::Data {
+                            key: validator,
+                            nested_sub_key: _,
+                        },
+                },
+                slash,
+            ) = res?;
+            if processing_epoch > current_epoch
+                && filter.matches(&validator, &slash)
+            {
+                enqueued.push(Ok(SlashRecord {
+                    validator,
+                    status: SlashStatus::Enqueued,
+                    processing_epoch: Some(processing_epoch),
+                    slash,
+                }));
             }
         }
-        None
-    });
+    }
 
-    slashes_iter.for_each(|(address, slash)| match slashes.get(&address) {
-        Some(vec) => {
-            let mut vec = vec.clone();
-            vec.push(slash);
-            slashes.insert(address, vec);
-        }
-        None => {
-            slashes.insert(address, vec![slash]);
-        }
-    });
-    Ok(slashes)
+    let processed = processed
+        .try_filter(move |(validator, slash)| {
+            Ok(filter.matches(validator, slash))
+        })
+        .try_map(|(validator, slash)| {
+            Ok(SlashRecord {
+                validator,
+                status: SlashStatus::Processed,
+                processing_epoch: None,
+                slash,
+            })
+        });
+    Ok(processed.chain(enqueued))
 }
 
 fn get_multiple_bonds_and_unbonds<S>(
@@ -4127,11 +5434,18 @@ fn make_unbond_details(
 /// Tally a running sum of the fraction of rewards owed to each validator in
 /// the consensus set. This is used to keep track of the rewards due to each
 /// consensus validator over the lifetime of an epoch.
+///
+/// The `vext_signers` are the validators whose Ethereum bridge pool root or
+/// validator set update vote extensions were included in the block. They
+/// share the [`PosParams::block_vext_reward`] fraction of the rewards in
+/// proportion to their stake.
 pub fn log_block_rewards<S>(
     storage: &mut S,
     epoch: impl Into<Epoch>,
     proposer_address: &Address,
     votes: Vec<VoteInfo>,
+    vext_signers: &HashSet<Address>,
+    cache: &ConsensusValidatorSetCache,
 ) -> storage_api::Result<()>
 where
     S: StorageRead + StorageWrite,
@@ -4141,7 +5455,6 @@ where
 
     let epoch: Epoch = epoch.into();
     let params = read_pos_params(storage)?;
-    let consensus_validators = consensus_validator_set_handle().at(&epoch);
 
     // Get total stake of the consensus validator set
     let total_consensus_stake =
@@ -4188,12 +5501,24 @@ where
         total_signing_stake += stake_from_deltas;
     }
 
+    // Get the combined stake of the consensus validators that contributed
+    // Ethereum bridge vote extensions
+    let consensus_validators = cache.read(storage, epoch)?;
+    let total_vext_stake = consensus_validators
+        .iter()
+        .filter(|validator| vext_signers.contains(&validator.address))
+        .fold(token::Amount::zero(), |acc, validator| {
+            acc + validator.bonded_stake
+        });
+
     // Get the block rewards coefficients (proposing, signing/voting,
-    // consensus set status)
+    // contributing vote extensions, consensus set status)
     let rewards_calculator = PosRewardsCalculator {
         proposer_reward: params.block_proposer_reward,
         signer_reward: params.block_vote_reward,
+        vext_reward: params.block_vext_reward,
         signing_stake: total_signing_stake,
+        vext_stake: total_vext_stake,
         total_stake: total_consensus_stake,
     };
     let coeffs = rewards_calculator
@@ -4211,17 +5536,19 @@ where
 
     // Compute the fractional block rewards for each consensus validator and
     // update the reward accumulators
-    let consensus_stake_unscaled: Dec = total_consensus_stake.into();
+    let consensus_fracs = consensus_reward_fractions(
+        &params,
+        consensus_validators.iter().map(|validator| {
+            (validator.address.clone(), validator.bonded_stake)
+        }),
+        total_consensus_stake,
+    );
     let signing_stake_unscaled: Dec = total_signing_stake.into();
+    let vext_stake_unscaled: Dec = total_vext_stake.into();
     let mut values: HashMap<Address, Dec> = HashMap::new();
-    for validator in consensus_validators.iter(storage)? {
-        let (
-            NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: _,
-            },
-            address,
-        ) = validator?;
+    for validator in consensus_validators.iter() {
+        let address = validator.address.clone();
+        let stake = validator.bonded_stake;
 
         if stake.is_zero() {
             continue;
@@ -4243,33 +5570,45 @@ where
             let signing_frac = stake_unscaled / signing_stake_unscaled;
             rewards_frac += coeffs.signer_coeff * signing_frac;
         }
+        // Ethereum bridge vote extensions reward
+        if vext_signers.contains(&address) {
+            let vext_frac = stake_unscaled / vext_stake_unscaled;
+            rewards_frac += coeffs.vext_coeff * vext_frac;
+        }
         // Consensus validator reward
         rewards_frac += coeffs.active_val_coeff
-            * (stake_unscaled / consensus_stake_unscaled);
+            * consensus_fracs.get(&address).copied().unwrap_or_default();
 
         // To be added to the rewards accumulator
         values.insert(address, rewards_frac);
     }
+    let rewards_accumulator = rewards_accumulator_handle();
     for (address, value) in values.into_iter() {
         // Update the rewards accumulator
-        rewards_accumulator_handle().update(storage, address, |prev| {
-            prev.unwrap_or_default() + value
-        })?;
+        let prev = rewards_accumulator
+            .get(storage, &address)?
+            .unwrap_or_default();
+        let new = prev
+            .checked_add(&value)
+            .ok_or(ArithmeticError::Overflow("rewards accumulator"))?;
+        rewards_accumulator.insert(storage, address, new)?;
     }
 
     Ok(())
 }
 
-#[derive(Clone, Debug)]
-struct Rewards {
-    product: Dec,
-    commissions: token::Amount,
-}
-
 /// Update validator and delegators rewards products and mint the inflation
 /// tokens into the PoS account.
-/// Any left-over inflation tokens from rounding error of the sum of the
-/// rewards is given to the governance address.
+///
+/// The validators' shares of the inflation are computed from their rewards
+/// accumulators with a fixed denominator, so that their sum never exceeds the
+/// inflation. The rounding errors of the shares, of the commissions and of the
+/// rewards products are accounted for explicitly: only the tokens that can be
+/// claimed from the rewards products and the commissions are minted into the
+/// PoS account and the left-over dust is minted into the rewards dust
+/// recipient (see [`reconcile_rewards_dust`]), so that the minted tokens
+/// exactly equal the inflation.
+/// Returns the distribution of the inflation, so that it can be reported.
 pub fn update_rewards_products_and_mint_inflation<S>(
     storage: &mut S,
     params: &PosParams,
@@ -4277,27 +5616,40 @@ pub fn update_rewards_products_and_mint_inflation<S>(
     num_blocks_in_last_epoch: u64,
     inflation: token::Amount,
     staking_token: &Address,
-) -> storage_api::Result<()>
+) -> storage_api::Result<InflationDistribution>
 where
     S: StorageRead + StorageWrite,
 {
-    // Read the rewards accumulator and calculate the new rewards products
-    // for the previous epoch
-    let mut reward_tokens_remaining = inflation;
-    let mut new_rewards_products: HashMap<Address, Rewards> = HashMap::new();
-    let mut accumulators_sum = Dec::zero();
-    for acc in rewards_accumulator_handle().iter(storage)? {
-        let (validator, value) = acc?;
-        accumulators_sum += value;
-
+    // Read the rewards accumulators
+    let accumulators = rewards_accumulator_handle()
+        .iter(storage)?
+        .collect_eager::<Vec<_>>()?;
+    let accumulators_sum =
+        accumulators
+            .iter()
+            .try_fold(Dec::zero(), |acc, (_, value)| {
+                acc.checked_add(value).ok_or(ArithmeticError::Overflow(
+                    "rewards accumulators sum",
+                ))
+            })?;
+    // The fixed denominator of the validators' shares of the inflation. The
+    // sum of the accumulators is used instead of the number of blocks in the
+    // unexpected case that it's greater, so that the shares never add up to
+    // more than the inflation.
+    let denominator =
+        cmp::max(Dec::from(num_blocks_in_last_epoch), accumulators_sum);
+
+    // Calculate the new rewards products for the previous epoch
+    let mut pos_reward_tokens = token::Amount::zero();
+    let mut new_rewards_products: Vec<ValidatorRewards> = Vec::new();
+    for (validator, value) in accumulators {
         // Get reward token amount for this validator
         let fractional_claim = value / num_blocks_in_last_epoch;
-        let reward_tokens = fractional_claim * inflation;
+        let reward_tokens = rewards_share(inflation, value, denominator)?;
 
         // Get validator stake at the last epoch
-        let stake = Dec::from(read_validator_stake(
-            storage, params, &validator, last_epoch,
-        )?);
+        let stake =
+            read_validator_stake(storage, params, &validator, last_epoch)?;
 
         let commission_rate = validator_commission_rate_handle(&validator)
             .get(storage, last_epoch, params)?
@@ -4308,40 +5660,48 @@ where
         // a single product, we're also taking out commission on validator's
         // self-bonds, but it is then included in the rewards claimable by the
         // validator so they get it back.
-        let product =
-            (Dec::one() - commission_rate) * Dec::from(reward_tokens) / stake;
+        let product = (Dec::one() - commission_rate) * Dec::from(reward_tokens)
+            / Dec::from(stake);
 
         // Tally the commission tokens earned by the validator.
-        // TODO: think abt Dec rounding and if `new_product` should be used
-        // instead of `reward_tokens`
         let commissions = commission_rate * reward_tokens;
 
-        new_rewards_products.insert(
+        // The tokens that can be claimed from the product and the commissions
+        // are rounded down, the rest is the rounding dust
+        let claimable = commissions
+            .checked_add(product * stake)
+            .ok_or(ArithmeticError::Overflow("claimable rewards"))?;
+        let dust = reward_tokens
+            .checked_sub(claimable)
+            .ok_or(ArithmeticError::Underflow("rewards rounding dust"))?;
+
+        new_rewards_products.push(ValidatorRewards {
             validator,
-            Rewards {
-                product,
-                commissions,
-            },
-        );
+            reward_fraction: fractional_claim,
+            reward_tokens,
+            product,
+            commissions,
+            dust,
+        });
 
-        reward_tokens_remaining -= reward_tokens;
+        pos_reward_tokens = pos_reward_tokens
+            .checked_add(claimable)
+            .ok_or(ArithmeticError::Overflow("PoS reward tokens"))?;
     }
-    for (
+    for ValidatorRewards {
         validator,
-        Rewards {
-            product,
-            commissions,
-        },
-    ) in new_rewards_products
+        product,
+        commissions,
+        ..
+    } in &new_rewards_products
     {
-        validator_rewards_products_handle(&validator)
-            .insert(storage, last_epoch, product)?;
+        validator_rewards_products_handle(validator)
+            .insert(storage, last_epoch, *product)?;
         // The commissions belong to the validator
-        add_rewards_to_counter(storage, &validator, &validator, commissions)?;
+        add_rewards_to_counter(storage, validator, validator, *commissions)?;
     }
 
     // Mint tokens to the PoS account for the last epoch's inflation
-    let pos_reward_tokens = inflation - reward_tokens_remaining;
     tracing::info!(
         "Minting tokens for PoS rewards distribution into the PoS account. \
          Amount: {}. Total inflation: {}, number of blocks in the last epoch: \
@@ -4357,19 +5717,16 @@ where
         pos_reward_tokens,
     )?;
 
-    if reward_tokens_remaining > token::Amount::zero() {
-        tracing::info!(
-            "Minting tokens remaining from PoS rewards distribution into the \
-             Governance account. Amount: {}.",
-            reward_tokens_remaining.to_string_native()
-        );
-        token::credit_tokens(
-            storage,
-            staking_token,
-            &address::GOV,
-            reward_tokens_remaining,
-        )?;
-    }
+    // Mint the rest of the inflation into the dust recipient
+    let (dust_recipient, dust_tokens) = reconcile_rewards_dust(
+        storage,
+        staking_token,
+        inflation,
+        pos_reward_tokens,
+    )?;
+
+    // Record the inflation applied for the last epoch
+    inflation_amounts_handle().insert(storage, last_epoch, inflation)?;
 
     // Clear validator rewards accumulators
     storage.delete_prefix(
@@ -4377,7 +5734,62 @@ where
         &storage::consensus_validator_rewards_accumulator_key(),
     )?;
 
-    Ok(())
+    Ok(InflationDistribution {
+        epoch: last_epoch,
+        num_blocks: num_blocks_in_last_epoch,
+        inflation,
+        pos_reward_tokens,
+        dust_recipient,
+        dust_tokens,
+        validators: new_rewards_products,
+    })
+}
+
+/// Get the share of the inflation attributed to a rewards accumulator
+/// `value`, rounded down. It's computed in integers, with the accumulator and
+/// the `denominator` both scaled to the fixed precision of [`Dec`].
+fn rewards_share(
+    inflation: token::Amount,
+    value: Dec,
+    denominator: Dec,
+) -> storage_api::Result<token::Amount> {
+    if value.is_negative() || denominator.is_zero() {
+        return Ok(token::Amount::zero());
+    }
+    let share = inflation
+        .raw_amount()
+        .checked_mul(value.abs())
+        .ok_or(ArithmeticError::Overflow("rewards share"))?
+        / denominator.abs();
+    token::Amount::from_uint(share, 0).into_storage_result()
+}
+
+/// Mint the difference between the `inflation` and the `distributed` rewards
+/// tokens, left-over from rounding, into the rewards dust recipient (see
+/// [`read_rewards_dust_recipient`]). Fails if more tokens were distributed
+/// than the inflation. Returns the dust recipient and the minted dust.
+pub fn reconcile_rewards_dust<S>(
+    storage: &mut S,
+    staking_token: &Address,
+    inflation: token::Amount,
+    distributed: token::Amount,
+) -> storage_api::Result<(Address, token::Amount)>
+where
+    S: StorageRead + StorageWrite,
+{
+    let dust = inflation
+        .checked_sub(distributed)
+        .ok_or(ArithmeticError::Underflow("rewards rounding dust"))?;
+    let recipient = read_rewards_dust_recipient(storage)?;
+    if !dust.is_zero() {
+        tracing::info!(
+            "Minting tokens remaining from PoS rewards distribution into \
+             {recipient}. Amount: {}.",
+            dust.to_string_native()
+        );
+        token::credit_tokens(storage, staking_token, &recipient, dust)?;
+    }
+    Ok((recipient, dust))
 }
 
 /// Calculate the cubic slashing rate using all slashes within a window around
@@ -4388,6 +5800,26 @@ pub fn compute_cubic_slash_rate<S>(
     params: &PosParams,
     infraction_epoch: Epoch,
 ) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    compute_cubic_slash_rate_with_infraction(
+        storage,
+        params,
+        infraction_epoch,
+        None,
+    )
+}
+
+/// Calculate the cubic slashing rate like [`compute_cubic_slash_rate`], but
+/// with the stake of the given validator counted as infracting in the
+/// infraction epoch, as if it had a slash enqueued for it.
+fn compute_cubic_slash_rate_with_infraction<S>(
+    storage: &S,
+    params: &PosParams,
+    infraction_epoch: Epoch,
+    pending_infraction: Option<&Address>,
+) -> storage_api::Result<Dec>
 where
     S: StorageRead,
 {
@@ -4409,7 +5841,7 @@ where
         );
         let processing_epoch = epoch + params.slash_processing_epoch_offset();
         let slashes = enqueued_slashes_handle().at(&processing_epoch);
-        let infracting_stake = slashes.iter(storage)?.fold(
+        let mut infracting_stake = slashes.iter(storage)?.fold(
             Ok(Dec::zero()),
             |acc: storage_api::Result<Dec>, res| {
                 let acc = acc?;
@@ -4429,6 +5861,13 @@ where
                 Ok(acc + Dec::from(validator_stake))
             },
         )?;
+        if let Some(validator) = pending_infraction {
+            if epoch == infraction_epoch {
+                let validator_stake =
+                    read_validator_stake(storage, params, validator, epoch)?;
+                infracting_stake += Dec::from(validator_stake);
+            }
+        }
         sum_vp_fraction += infracting_stake / consensus_stake;
     }
     let cubic_rate =
@@ -4437,9 +5876,200 @@ where
     Ok(cubic_rate)
 }
 
+/// Estimate the slash that an infraction of the given validator committed in
+/// the infraction epoch would incur once processed, considering the slashes
+/// that are already enqueued. Nothing is written to storage, so this can be
+/// used to simulate an infraction before it's processed.
+///
+/// The slashed amount is estimated from the validator's stake at the
+/// infraction epoch, so it doesn't account for any stake that may be
+/// unbonded or redelegated before the slash is processed.
+pub fn simulate_slash<S>(
+    storage: &S,
+    validator: &Address,
+    infraction_epoch: Epoch,
+    slash_type: SlashType,
+) -> storage_api::Result<SlashEstimate>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let processing_epoch =
+        infraction_epoch + params.slash_processing_epoch_offset();
+
+    let enqueued_slash_types = enqueued_slashes_handle()
+        .at(&processing_epoch)
+        .at(validator)
+        .iter(storage)?
+        .map(|slash| slash.map(|slash| slash.r#type))
+        .collect::<storage_api::Result<Vec<_>>>()?;
+    // The stake of a validator that already has a slash enqueued for the
+    // infraction epoch is already counted as infracting
+    let pending_infraction =
+        enqueued_slash_types.is_empty().then_some(validator);
+    let cubic_slash_rate = compute_cubic_slash_rate_with_infraction(
+        storage,
+        &params,
+        infraction_epoch,
+        pending_infraction,
+    )?;
+
+    // Sum up the rates of all the slashes of the validator that would be
+    // processed together, as is done in `process_slashes`
+    let slash_rate = enqueued_slash_types
+        .into_iter()
+        .chain(std::iter::once(slash_type))
+        .fold(Dec::zero(), |acc, slash_type| {
+            let slash_rate = cmp::min(
+                Dec::one(),
+                cmp::max(slash_type.get_slash_rate(&params), cubic_slash_rate),
+            );
+            cmp::min(Dec::one(), acc + slash_rate)
+        });
+
+    let stake =
+        read_validator_stake(storage, &params, validator, infraction_epoch)?;
+    Ok(SlashEstimate {
+        processing_epoch,
+        cubic_slash_rate,
+        slash_rate,
+        slashed_amount: slash_rate * stake,
+    })
+}
+
+/// Simulate a bond, unbond or redelegation submitted in the current epoch and
+/// report its outcome at the pipeline epoch. The action is applied to the
+/// given storage, which must therefore be a temporary storage whose changes
+/// are discarded.
+pub fn simulate_bond_action<S>(
+    storage: &mut S,
+    action: &BondAction,
+    current_epoch: Epoch,
+) -> storage_api::Result<BondActionSimulation>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let validators = match action {
+        BondAction::Bond { validator, .. }
+        | BondAction::Unbond { validator, .. } => vec![validator.clone()],
+        BondAction::Redelegate {
+            src_validator,
+            dest_validator,
+            ..
+        } => vec![src_validator.clone(), dest_validator.clone()],
+    };
+    let read_stakes = |storage: &S| {
+        validators
+            .iter()
+            .map(|validator| {
+                let stake = read_validator_stake(
+                    storage,
+                    &params,
+                    validator,
+                    pipeline_epoch,
+                )?;
+                Ok((validator.clone(), stake))
+            })
+            .collect::<storage_api::Result<BTreeMap<_, _>>>()
+    };
+    let read_states = |storage: &S| {
+        read_all_validator_addresses(storage, pipeline_epoch)?
+            .into_iter()
+            .map(|validator| {
+                let state = validator_state_handle(&validator).get(
+                    storage,
+                    pipeline_epoch,
+                    &params,
+                )?;
+                Ok((validator, state))
+            })
+            .collect::<storage_api::Result<BTreeMap<_, _>>>()
+    };
+    let stakes_before = read_stakes(storage)?;
+    let states_before = read_states(storage)?;
+
+    let result_slashing = match action {
+        BondAction::Bond {
+            source,
+            validator,
+            amount,
+        } => {
+            bond_tokens(
+                storage,
+                source.as_ref(),
+                validator,
+                *amount,
+                current_epoch,
+                None,
+            )?;
+            None
+        }
+        BondAction::Unbond {
+            source,
+            validator,
+            amount,
+        } => Some(unbond_tokens(
+            storage,
+            source.as_ref(),
+            validator,
+            *amount,
+            current_epoch,
+            false,
+        )?),
+        BondAction::Redelegate {
+            delegator,
+            src_validator,
+            dest_validator,
+            amount,
+        } => {
+            redelegate_tokens(
+                storage,
+                delegator,
+                src_validator,
+                dest_validator,
+                current_epoch,
+                *amount,
+            )?;
+            None
+        }
+    };
+
+    let mut stakes_after = read_stakes(storage)?;
+    let stake_changes = stakes_before
+        .into_iter()
+        .map(|(validator, before)| {
+            let after = stakes_after.remove(&validator).unwrap_or_default();
+            (validator, (before, after))
+        })
+        .collect();
+    let state_transitions = read_states(storage)?
+        .into_iter()
+        .filter_map(|(validator, to)| {
+            let from = states_before.get(&validator).copied().flatten();
+            (from != to).then_some(ValidatorStateTransition {
+                validator,
+                from,
+                to,
+            })
+        })
+        .collect();
+
+    Ok(BondActionSimulation {
+        pipeline_epoch,
+        result_slashing,
+        stake_changes,
+        state_transitions,
+    })
+}
+
 /// Record a slash for a misbehavior that has been received from Tendermint and
 /// then jail the validator, removing it from the validator set. The slash rate
-/// will be computed at a later epoch.
+/// will be computed at a later epoch. Evidence older than the
+/// `evidence_max_age_epochs` parameter is rejected with
+/// [`SlashError::EvidenceTooOld`].
 #[allow(clippy::too_many_arguments)]
 pub fn slash<S>(
     storage: &mut S,
@@ -4454,6 +6084,19 @@ pub fn slash<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    // Reject stale evidence, which may concern stake that has already been
+    // withdrawn
+    if current_epoch.0.saturating_sub(evidence_epoch.0)
+        > params.evidence_max_age_epochs
+    {
+        return Err(SlashError::EvidenceTooOld(
+            evidence_epoch,
+            current_epoch,
+            params.evidence_max_age_epochs,
+        ))
+        .into_storage_result();
+    }
+
     let evidence_block_height: u64 = evidence_block_height.into();
     let slash = Slash {
         epoch: evidence_epoch,
@@ -4496,22 +6139,121 @@ where
     Ok(())
 }
 
-/// Process enqueued slashes that were discovered earlier. This function is
-/// called upon a new epoch. The final slash rate considering according to the
-/// cubic slashing rate is computed. Then, each slash is recorded in storage
-/// along with its computed rate, and stake is deducted from the affected
-/// validators.
-pub fn process_slashes<S>(
+/// Process enqueued slashes that were discovered earlier. This function is
+/// called upon a new epoch. The final slash rate considering according to the
+/// cubic slashing rate is computed. Then, each slash is recorded in storage
+/// along with its computed rate, and stake is deducted from the affected
+/// validators.
+pub fn process_slashes<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    if let Some(mut cursor) =
+        start_slash_processing(storage, &params, current_epoch)?
+    {
+        process_slashed_validators(storage, &params, &mut cursor, None)?;
+        apply_processed_slashes(storage, &params, cursor)?;
+    }
+    Ok(())
+}
+
+/// Process enqueued slashes like [`process_slashes`], but spread the
+/// processing of the slashed validators over several blocks when there are
+/// more of them than the `max_slashed_validators_per_block` parameter allows.
+/// This function must be called in every block, with `is_new_epoch` set in
+/// the first block of an epoch. The progress is persisted in storage between
+/// the blocks and the slashes are applied once all the slashed validators are
+/// processed. Any slashes of the previous epoch that are still being processed
+/// at the beginning of a new epoch are processed and applied first.
+///
+/// While the slashes are being processed, the slashed validators' stake is
+/// not yet updated and withdrawals are rejected, so that none of the unbonds
+/// can be withdrawn without accounting for the slashes. The slashed validators
+/// also remain frozen (see [`is_validator_frozen`]), so that their bonds can't
+/// be unbonded or redelegated before the slashes are applied.
+pub fn process_slashes_chunk<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+    is_new_epoch: bool,
+) -> storage_api::Result<SlashProcessingStatus>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    let cursor_key = slash_processing_cursor_key();
+
+    let mut cursor = if is_new_epoch {
+        if let Some(mut cursor) =
+            storage.read::<SlashProcessingCursor>(&cursor_key)?
+        {
+            tracing::warn!(
+                "Slashes processed since epoch {} weren't completed before \
+                 the epoch {current_epoch}, completing them now",
+                cursor.epoch
+            );
+            process_slashed_validators(storage, &params, &mut cursor, None)?;
+            apply_processed_slashes(storage, &params, cursor)?;
+            storage.delete(&cursor_key)?;
+        }
+        match start_slash_processing(storage, &params, current_epoch)? {
+            Some(cursor) => cursor,
+            None => return Ok(SlashProcessingStatus::Idle),
+        }
+    } else {
+        match storage.read::<SlashProcessingCursor>(&cursor_key)? {
+            Some(cursor) => cursor,
+            None => return Ok(SlashProcessingStatus::Idle),
+        }
+    };
+
+    let max_validators = match params.max_slashed_validators_per_block {
+        0 => None,
+        max => Some(max),
+    };
+    process_slashed_validators(storage, &params, &mut cursor, max_validators)?;
+
+    if cursor.is_done() {
+        apply_processed_slashes(storage, &params, cursor)?;
+        storage.delete(&cursor_key)?;
+        Ok(SlashProcessingStatus::Completed)
+    } else {
+        tracing::debug!(
+            "{} slashed validators remain to be processed",
+            cursor.pending.len()
+        );
+        storage.write(&cursor_key, cursor)?;
+        Ok(SlashProcessingStatus::InProgress)
+    }
+}
+
+/// Returns whether the processing of some slashes is spread over several
+/// blocks and hasn't been completed yet.
+pub fn is_slash_processing_in_progress<S>(
+    storage: &S,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    storage.has_key(&slash_processing_cursor_key())
+}
+
+/// Compute the final rates of the slashes enqueued for processing in the
+/// current epoch and collect the slashed validators to be processed. Returns
+/// `None` if there are no slashes to process.
+fn start_slash_processing<S>(
     storage: &mut S,
+    params: &PosParams,
     current_epoch: Epoch,
-) -> storage_api::Result<()>
+) -> storage_api::Result<Option<SlashProcessingCursor>>
 where
     S: StorageRead + StorageWrite,
 {
-    let params = read_pos_params(storage)?;
-
     if current_epoch.0 < params.slash_processing_epoch_offset() {
-        return Ok(());
+        return Ok(None);
     }
     let infraction_epoch =
         current_epoch - params.slash_processing_epoch_offset();
@@ -4519,7 +6261,7 @@ where
     // Slashes to be processed in the current epoch
     let enqueued_slashes = enqueued_slashes_handle().at(&current_epoch);
     if enqueued_slashes.is_empty(storage)? {
-        return Ok(());
+        return Ok(None);
     }
     tracing::debug!(
         "Processing slashes at the beginning of epoch {} (committed in epoch \
@@ -4530,12 +6272,13 @@ where
 
     // Compute the cubic slash rate
     let cubic_slash_rate =
-        compute_cubic_slash_rate(storage, &params, infraction_epoch)?;
+        compute_cubic_slash_rate(storage, params, infraction_epoch)?;
 
     // Collect the enqueued slashes and update their rates
     let mut eager_validator_slashes: BTreeMap<Address, Vec<Slash>> =
         BTreeMap::new();
-    let mut eager_validator_slash_rates: HashMap<Address, Dec> = HashMap::new();
+    let mut eager_validator_slash_rates: BTreeMap<Address, Dec> =
+        BTreeMap::new();
 
     // `slashPerValidator` and `slashesMap` while also updating in storage
     for enqueued_slash in enqueued_slashes.iter(storage)? {
@@ -4551,7 +6294,7 @@ where
         let slash_rate = cmp::min(
             Dec::one(),
             cmp::max(
-                enqueued_slash.r#type.get_slash_rate(&params),
+                enqueued_slash.r#type.get_slash_rate(params),
                 cubic_slash_rate,
             ),
         );
@@ -4572,33 +6315,85 @@ where
     }
 
     // Update the epochs of enqueued slashes in storage
-    enqueued_slashes_handle().update_data(storage, &params, current_epoch)?;
+    enqueued_slashes_handle().update_data(storage, params, current_epoch)?;
+
+    Ok(Some(SlashProcessingCursor {
+        epoch: current_epoch,
+        slashes: eager_validator_slashes,
+        pending: eager_validator_slash_rates,
+        slashed_amounts: BTreeMap::new(),
+    }))
+}
 
+/// Process the pending slashed validators of the cursor, up to the given
+/// maximum number of validators, if any.
+fn process_slashed_validators<S>(
+    storage: &mut S,
+    params: &PosParams,
+    cursor: &mut SlashProcessingCursor,
+    max_validators: Option<u64>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut processed = 0_u64;
     // `resultSlashing`
-    let mut map_validator_slash: EagerRedelegatedBondsMap = BTreeMap::new();
-    for (validator, slash_rate) in eager_validator_slash_rates {
+    while let Some((validator, slash_rate)) = cursor.pending.pop_first() {
         process_validator_slash(
             storage,
-            &params,
+            params,
             &validator,
             slash_rate,
-            current_epoch,
-            &mut map_validator_slash,
+            cursor.epoch,
+            &mut cursor.slashed_amounts,
         )?;
+        processed += 1;
+        if max_validators.map_or(false, |max| processed >= max) {
+            break;
+        }
     }
+    Ok(())
+}
+
+/// Write the processed slashes of the cursor into storage and deduct the
+/// slashed amounts from the affected validators' stake.
+fn apply_processed_slashes<S>(
+    storage: &mut S,
+    params: &PosParams,
+    cursor: SlashProcessingCursor,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let SlashProcessingCursor {
+        epoch: current_epoch,
+        slashes: eager_validator_slashes,
+        pending,
+        slashed_amounts: map_validator_slash,
+    } = cursor;
+    debug_assert!(pending.is_empty());
     tracing::debug!("Slashed amounts for validators: {map_validator_slash:#?}");
 
     // Now update the remaining parts of storage
 
     // Write slashes themselves into storage
     for (validator, slashes) in eager_validator_slashes {
+        update_validator_cumulative_slash(
+            storage,
+            &validator,
+            &slashes,
+            current_epoch,
+        )?;
         let validator_slashes = validator_slashes_handle(&validator);
         for slash in slashes {
             validator_slashes.push(storage, slash)?;
         }
     }
 
-    // Update the validator stakes
+    // Update the validator stakes. The total deltas changes of all the slashed
+    // validators are accumulated and written once per epoch.
+    let mut total_deltas_changes: BTreeMap<Epoch, token::Change> =
+        BTreeMap::new();
     for (validator, slash_amounts) in map_validator_slash {
         let mut slash_acc = token::Amount::zero();
 
@@ -4606,12 +6401,12 @@ where
         // validator stake before we make any changes to it
         for (&epoch, &slash_amount) in &slash_amounts {
             let state = validator_state_handle(&validator)
-                .get(storage, epoch, &params)?
+                .get(storage, epoch, params)?
                 .unwrap();
             if state != ValidatorState::Jailed {
                 update_validator_set(
                     storage,
-                    &params,
+                    params,
                     &validator,
                     -slash_amount.change(),
                     epoch,
@@ -4621,23 +6416,26 @@ where
         }
         // Then update validator and total deltas
         for (epoch, slash_amount) in slash_amounts {
-            let slash_delta = slash_amount - slash_acc;
-            slash_acc += slash_delta;
+            let slash_delta = slash_amount
+                .checked_sub(slash_acc)
+                .ok_or(ArithmeticError::Underflow("slashed stake delta"))?;
+            slash_acc = slash_acc
+                .checked_add(slash_delta)
+                .ok_or(ArithmeticError::Overflow("slashed stake delta"))?;
 
             update_validator_deltas(
                 storage,
-                &params,
+                params,
                 &validator,
                 -slash_delta.change(),
                 epoch,
                 Some(0),
             )?;
-            update_total_deltas(
-                storage,
-                &params,
+            let total_change = total_deltas_changes.entry(epoch).or_default();
+            *total_change = checked_change_add(
+                *total_change,
                 -slash_delta.change(),
-                epoch,
-                Some(0),
+                "total deltas",
             )?;
         }
 
@@ -4645,6 +6443,9 @@ where
         // Possibly make the `unbonded` LazyMaps epoched so that it is done
         // automatically?
     }
+    for (epoch, change) in total_deltas_changes {
+        update_total_deltas(storage, params, change, epoch, Some(0))?;
+    }
 
     Ok(())
 }
@@ -4846,7 +6647,7 @@ where
     // as they won't be jailed
     let set_update_epoch = current_epoch.next();
 
-    let mut init_tot_unbonded =
+    let mut init_tot_unbonded = checked_amount_sum(
         Epoch::iter_bounds_inclusive(infraction_epoch.next(), set_update_epoch)
             .map(|epoch| {
                 let redelegated_unbonded = total_redelegated_unbonded
@@ -4856,8 +6657,9 @@ where
                     .get(storage, &bond_start)?
                     .unwrap_or_default();
                 Ok(redelegated_unbonded)
-            })
-            .sum::<storage_api::Result<token::Amount>>()?;
+            }),
+        "total redelegated unbonded amount",
+    )?;
 
     for epoch in Epoch::iter_range(set_update_epoch, params.pipeline_len) {
         let updated_total_unbonded = {
@@ -4867,14 +6669,17 @@ where
                 .at(src_validator)
                 .get(storage, &bond_start)?
                 .unwrap_or_default();
-            init_tot_unbonded + redelegated_unbonded
+            checked_amount_add(
+                init_tot_unbonded,
+                redelegated_unbonded,
+                "total redelegated unbonded amount",
+            )?
         };
 
         let list_slashes = slashes
             .iter(storage)?
-            .map(Result::unwrap)
-            .filter(|slash| {
-                params.in_redelegation_slashing_window(
+            .try_filter(|slash| {
+                Ok(params.in_redelegation_slashing_window(
                     slash.epoch,
                     params.redelegation_start_epoch_from_end(redel_bond_start),
                     redel_bond_start,
@@ -4886,9 +6691,9 @@ where
                     // epoch, we're also taking slashes that were processed in
                     // the infraction epoch as they would still be processed
                     // before any infraction occurred.
-                        <= infraction_epoch
+                        <= infraction_epoch)
             })
-            .collect::<Vec<_>>();
+            .collect_eager::<Vec<_>>()?;
 
         let slashable_amount = amount
             .checked_sub(updated_total_unbonded)
@@ -4900,15 +6705,14 @@ where
 
         let list_slashes = slashes
             .iter(storage)?
-            .map(Result::unwrap)
-            .filter(|slash| {
-                params.in_redelegation_slashing_window(
+            .try_filter(|slash| {
+                Ok(params.in_redelegation_slashing_window(
                     slash.epoch,
                     params.redelegation_start_epoch_from_end(redel_bond_start),
                     redel_bond_start,
-                ) && bond_start <= slash.epoch
+                ) && bond_start <= slash.epoch)
             })
-            .collect::<Vec<_>>();
+            .collect_eager::<Vec<_>>()?;
 
         let slashable_stake =
             apply_list_slashes(params, &list_slashes, slashable_amount)
@@ -4918,7 +6722,11 @@ where
         let to_slash = cmp::min(slashed, slashable_stake);
         if !to_slash.is_zero() {
             let map_value = slashed_amounts.entry(epoch).or_default();
-            *map_value += to_slash;
+            *map_value = checked_amount_add(
+                *map_value,
+                to_slash,
+                "slashed redelegation amount",
+            )?;
         }
     }
 
@@ -4964,28 +6772,24 @@ where
     let mut tot_bonds = total_bonded
         .get_data_handler()
         .iter(storage)?
-        .map(Result::unwrap)
-        .filter(|&(epoch, bonded)| {
-            epoch <= infraction_epoch && bonded > 0.into()
+        .try_filter(|&(epoch, bonded)| {
+            Ok(epoch <= infraction_epoch && bonded > 0.into())
         })
-        .collect::<BTreeMap<_, _>>();
+        .collect_eager::<BTreeMap<_, _>>()?;
 
     let mut redelegated_bonds = tot_bonds
         .keys()
-        .filter(|&epoch| {
-            !total_redelegated_bonded
-                .at(epoch)
-                .is_empty(storage)
-                .unwrap()
+        .copied()
+        .map(Ok)
+        .try_filter(|epoch| {
+            Ok(!total_redelegated_bonded.at(epoch).is_empty(storage)?)
         })
-        .map(|epoch| {
-            let tot_redel_bonded = total_redelegated_bonded
-                .at(epoch)
-                .collect_map(storage)
-                .unwrap();
-            (*epoch, tot_redel_bonded)
+        .try_map(|epoch| {
+            let tot_redel_bonded =
+                total_redelegated_bonded.at(&epoch).collect_map(storage)?;
+            Ok((epoch, tot_redel_bonded))
         })
-        .collect::<BTreeMap<_, _>>();
+        .collect_eager::<BTreeMap<_, _>>()?;
 
     let mut sum = token::Amount::zero();
 
@@ -4993,10 +6797,9 @@ where
         .iter_range(params.pipeline_len)
         .collect::<Vec<_>>();
     for epoch in eps.into_iter().rev() {
-        let amount = tot_bonds.iter().fold(
-            token::Amount::zero(),
-            |acc, (bond_start, bond_amount)| {
-                acc + compute_slash_bond_at_epoch(
+        let amount = checked_amount_sum(
+            tot_bonds.iter().map(|(bond_start, bond_amount)| {
+                compute_slash_bond_at_epoch(
                     storage,
                     params,
                     validator,
@@ -5007,43 +6810,41 @@ where
                     redelegated_bonds.get(bond_start),
                     slash_rate,
                 )
-                .unwrap()
-            },
-        );
+            }),
+            "slashed amount",
+        )?;
 
         let new_bonds = total_unbonded.at(&epoch);
         tot_bonds = new_bonds
-            .collect_map(storage)
-            .unwrap()
+            .collect_map(storage)?
             .into_iter()
             .filter(|(ep, _)| *ep <= infraction_epoch)
             .collect::<BTreeMap<_, _>>();
 
         let new_redelegated_bonds = tot_bonds
             .keys()
-            .filter(|&ep| {
-                !total_redelegated_unbonded.at(ep).is_empty(storage).unwrap()
+            .copied()
+            .map(Ok)
+            .try_filter(|ep| {
+                Ok(!total_redelegated_unbonded.at(ep).is_empty(storage)?)
             })
-            .map(|ep| {
-                (
-                    *ep,
-                    total_redelegated_unbonded
-                        .at(&epoch)
-                        .at(ep)
-                        .collect_map(storage)
-                        .unwrap(),
-                )
+            .try_map(|ep| {
+                let tot_redel_unbonded = total_redelegated_unbonded
+                    .at(&epoch)
+                    .at(&ep)
+                    .collect_map(storage)?;
+                Ok((ep, tot_redel_unbonded))
             })
-            .collect::<BTreeMap<_, _>>();
+            .collect_eager::<BTreeMap<_, _>>()?;
 
         redelegated_bonds = new_redelegated_bonds;
 
         // `newSum`
-        sum += amount;
+        sum = checked_amount_add(sum, amount, "slashed amount")?;
 
         // `newSlashesMap`
         let cur = slashed_amounts.entry(epoch).or_default();
-        *cur += sum;
+        *cur = checked_amount_add(*cur, sum, "slashed amount")?;
     }
     // Hack - should this be done differently? (think this is safe)
     let pipeline_epoch = current_epoch + params.pipeline_len;
@@ -5076,12 +6877,12 @@ where
 {
     let list_slashes = validator_slashes_handle(validator)
         .iter(storage)?
-        .map(Result::unwrap)
-        .filter(|slash| {
-            start <= slash.epoch
-                && slash.epoch + params.slash_processing_epoch_offset() <= epoch
+        .try_filter(|slash| {
+            let processed_at =
+                slash.epoch + params.slash_processing_epoch_offset();
+            Ok(start <= slash.epoch && processed_at <= epoch)
         })
-        .collect::<Vec<_>>();
+        .collect_eager::<Vec<_>>()?;
 
     let slash_epoch_filter =
         |e: Epoch| e + params.slash_processing_epoch_offset() <= epoch;
@@ -5097,6 +6898,7 @@ where
                 slash_epoch_filter,
             )
         })
+        .transpose()?
         .unwrap_or_default();
 
     let total_not_redelegated = amount - result_fold.total_redelegated;
@@ -5209,7 +7011,9 @@ where
 
 /// Check if a validator is frozen. A validator is frozen until after all of its
 /// enqueued slashes have been processed, i.e. until `unbonding_len + 1 +
-/// cubic_slashing_window_length` epochs after its most recent infraction epoch.
+/// cubic_slashing_window_length` epochs after its most recent infraction epoch,
+/// and, when the processing is spread over several blocks, until the
+/// processed slashes have been applied.
 pub fn is_validator_frozen<S>(
     storage: &S,
     validator: &Address,
@@ -5222,12 +7026,17 @@ where
     let last_infraction_epoch =
         read_validator_last_slash_epoch(storage, validator)?;
     if let Some(last_epoch) = last_infraction_epoch {
-        let is_frozen =
-            current_epoch < last_epoch + params.slash_processing_epoch_offset();
-        Ok(is_frozen)
-    } else {
-        Ok(false)
+        if current_epoch < last_epoch + params.slash_processing_epoch_offset() {
+            return Ok(true);
+        }
+        // The slashes of the validator may still be being processed
+        if let Some(cursor) = storage
+            .read::<SlashProcessingCursor>(&slash_processing_cursor_key())?
+        {
+            return Ok(cursor.slashes.contains_key(validator));
+        }
     }
+    Ok(false)
 }
 
 /// Find the total amount of tokens staked at the given `epoch`,
@@ -5270,6 +7079,46 @@ where
     Ok(slashes)
 }
 
+/// Check whether the given delegator may redelegate its bonds from the source
+/// validator in the current epoch and if not, find the earliest epoch from
+/// which it may.
+///
+/// Chained redelegations are forbidden. A redelegation is "chained" if:
+/// 1. the source validator holds bonded tokens that themselves were
+/// redelegated to the src validator
+/// 2. given the latest epoch at which the most recently redelegated tokens
+/// started contributing to the src validator's voting power, these tokens
+/// can still be slashed
+pub fn can_redelegate<S>(
+    storage: &S,
+    delegator: &Address,
+    src_validator: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<RedelegationEligibility>
+where
+    S: StorageRead,
+{
+    let src_redel_end_epoch =
+        validator_incoming_redelegations_handle(src_validator)
+            .get(storage, delegator)?;
+    let end_epoch = match src_redel_end_epoch {
+        Some(end_epoch) => end_epoch,
+        None => return Ok(RedelegationEligibility::Eligible),
+    };
+    let params = read_pos_params(storage)?;
+    let last_contrib_epoch = end_epoch.prev();
+    // Once the source validator's slashes that would cause slash on
+    // redelegation are outdated (would have to be processed before or on
+    // start of the current epoch), the redelegation can be redelegated again
+    let eligible_epoch =
+        last_contrib_epoch + params.slash_processing_epoch_offset();
+    if eligible_epoch <= current_epoch {
+        Ok(RedelegationEligibility::Eligible)
+    } else {
+        Ok(RedelegationEligibility::ChainedUntil(eligible_epoch))
+    }
+}
+
 /// Redelegate bonded tokens from a source validator to a destination validator
 pub fn redelegate_tokens<S>(
     storage: &mut S,
@@ -5315,37 +7164,25 @@ where
         );
     }
 
+    // Forbid chained redelegations
+    if let RedelegationEligibility::ChainedUntil(_) =
+        can_redelegate(storage, delegator, src_validator, current_epoch)?
+    {
+        return Err(RedelegationError::IsChainedRedelegation.into());
+    }
+
     let params = read_pos_params(storage)?;
     let pipeline_epoch = current_epoch + params.pipeline_len;
-    let src_redel_end_epoch =
-        validator_incoming_redelegations_handle(src_validator)
-            .get(storage, delegator)?;
 
-    // Forbid chained redelegations. A redelegation is "chained" if:
-    // 1. the source validator holds bonded tokens that themselves were
-    // redelegated to the src validator
-    // 2. given the latest epoch at which the most recently redelegated tokens
-    // started contributing to the src validator's voting power, these tokens
-    // cannot be slashed anymore
-    let is_not_chained = if let Some(end_epoch) = src_redel_end_epoch {
-        let last_contrib_epoch = end_epoch.prev();
-        // If the source validator's slashes that would cause slash on
-        // redelegation are now outdated (would have to be processed before or
-        // on start of the current epoch), the redelegation can be redelegated
-        // again
-        last_contrib_epoch + params.slash_processing_epoch_offset()
-            <= current_epoch
-    } else {
-        true
-    };
-    if !is_not_chained {
-        return Err(RedelegationError::IsChainedRedelegation.into());
-    }
+    // The amount updates of both the unbond from the src validator and the
+    // redelegation to the dest validator are written at once
+    let mut batch = AmountWriteBatch::default();
 
     // Unbond the redelegated tokens from the src validator.
     // `resultUnbond` in quint
-    let result_unbond = unbond_tokens(
+    let result_unbond = unbond_tokens_batched(
         storage,
+        &mut batch,
         Some(delegator),
         src_validator,
         amount,
@@ -5368,10 +7205,8 @@ where
         .at(dest_validator)
         .at(&pipeline_epoch)
         .at(src_validator);
-    for (&epoch, &unbonded_amount) in result_unbond.epoch_map.iter() {
-        redelegated_bonds.update(storage, epoch, |current| {
-            current.unwrap_or_default() + unbonded_amount
-        })?;
+    for (epoch, &unbonded_amount) in result_unbond.epoch_map.iter() {
+        batch.add(&redelegated_bonds, epoch, unbonded_amount)?;
     }
 
     if tracing::level_enabled!(tracing::Level::DEBUG) {
@@ -5411,10 +7246,10 @@ where
         validator_outgoing_redelegations_handle(src_validator)
             .at(dest_validator);
     for (start, &unbonded_amount) in result_unbond.epoch_map.iter() {
-        outgoing_redelegations.at(start).update(
-            storage,
-            current_epoch,
-            |current| current.unwrap_or_default() + unbonded_amount,
+        batch.add(
+            &outgoing_redelegations.at(start),
+            &current_epoch,
+            unbonded_amount,
         )?;
     }
 
@@ -5423,11 +7258,10 @@ where
         validator_total_redelegated_bonded_handle(dest_validator)
             .at(&pipeline_epoch)
             .at(src_validator);
-    for (&epoch, &amount) in &result_unbond.epoch_map {
-        dest_total_redelegated_bonded.update(storage, epoch, |current| {
-            current.unwrap_or_default() + amount
-        })?;
+    for (epoch, &amount) in &result_unbond.epoch_map {
+        batch.add(&dest_total_redelegated_bonded, epoch, amount)?;
     }
+    batch.flush(storage)?;
 
     // Set the epoch of the validator incoming redelegation from this delegator
     let dest_incoming_redelegations =
@@ -5475,6 +7309,17 @@ where
         None,
     )?;
 
+    update_bond_amount_checkpoints(
+        storage,
+        &params,
+        &BondId {
+            source: delegator.clone(),
+            validator: dest_validator.clone(),
+        },
+        current_epoch,
+        pipeline_epoch,
+    )?;
+
     Ok(())
 }
 
@@ -5552,8 +7397,9 @@ where
     }
 
     // Set the state to inactive
-    validator_state_handle(validator).set(
+    set_validator_state(
         storage,
+        validator,
         ValidatorState::Inactive,
         current_epoch,
         params.pipeline_len,
@@ -5610,8 +7456,9 @@ where
             &target_epoch,
             &removed_max_below_capacity,
         )?;
-        validator_state_handle(&removed_max_below_capacity).set(
+        set_validator_state(
             storage,
+            &removed_max_below_capacity,
             ValidatorState::Consensus,
             target_epoch,
             0,
@@ -5663,8 +7510,9 @@ where
             last_slash_epoch + params.slash_processing_epoch_offset();
         if current_epoch < eligible_epoch {
             // The validator should be set back to jailed
-            validator_state_handle(validator).set(
+            set_validator_state(
                 storage,
+                validator,
                 ValidatorState::Jailed,
                 pipeline_epoch,
                 0,
@@ -5802,6 +7650,124 @@ where
     Ok(())
 }
 
+/// Record a liveness report made by a consensus validator in its vote
+/// extension, listing the consensus validators it observed to be offline.
+/// Returns the storage keys that were changed.
+pub fn record_liveness_report<S>(
+    storage: &mut S,
+    reporter: &Address,
+    offline_validators: &BTreeSet<Address>,
+) -> storage_api::Result<BTreeSet<Key>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let liveness_reports = liveness_reports_handle();
+    let mut changed_keys = BTreeSet::new();
+    for offline_validator in offline_validators {
+        if offline_validator == reporter {
+            continue;
+        }
+        let reporters = liveness_reports.at(offline_validator);
+        reporters.insert(storage, reporter.clone())?;
+        changed_keys.insert(reporters.get_key(reporter));
+    }
+    Ok(changed_keys)
+}
+
+/// Aggregate the liveness reports received from vote extensions. A
+/// consensus validator reported as offline by validators holding more than
+/// 2/3 of the consensus stake is recorded as having missed the vote at
+/// `votes_height`, unless it's already been recorded by
+/// [`record_liveness_data`]. The reports are cleared afterwards, so that
+/// every quorum of reports is only counted once.
+pub fn apply_liveness_reports<S>(
+    storage: &mut S,
+    params: &PosParams,
+    votes_epoch: Epoch,
+    votes_height: BlockHeight,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let liveness_reports = liveness_reports_handle();
+    let reports = liveness_reports
+        .iter(storage)?
+        .map(|entry| {
+            let (
+                NestedSubKey::Data {
+                    key,
+                    nested_sub_key: lazy_set::SubKey::Data(reporter),
+                },
+                (),
+            ) = entry?;
+            Ok((key, reporter))
+        })
+        .collect::<storage_api::Result<Vec<(Address, Address)>>>()?;
+    if reports.is_empty() {
+        return Ok(());
+    }
+
+    let consensus_validators =
+        read_consensus_validator_set_addresses(storage, votes_epoch)?;
+    let total_consensus_stake =
+        get_total_consensus_stake(storage, votes_epoch, params)?;
+    let quorum = total_consensus_stake * (2_u64, 3_u64);
+
+    let mut reported_stakes: BTreeMap<Address, token::Amount> = BTreeMap::new();
+    for (offline_validator, reporter) in reports {
+        // Only the reports made by consensus validators about other
+        // consensus validators are taken into account
+        if !consensus_validators.contains(&offline_validator)
+            || !consensus_validators.contains(&reporter)
+        {
+            continue;
+        }
+        let reporter_stake =
+            read_validator_stake(storage, params, &reporter, votes_epoch)?;
+        let reported_stake =
+            reported_stakes.entry(offline_validator).or_default();
+        *reported_stake = reported_stake
+            .checked_add(reporter_stake)
+            .ok_or(ArithmeticError::Overflow("liveness reports stake"))?;
+    }
+
+    let liveness_missed_votes = liveness_missed_votes_handle();
+    let liveness_sum_missed_votes = liveness_sum_missed_votes_handle();
+    for (offline_validator, reported_stake) in reported_stakes {
+        if reported_stake <= quorum {
+            continue;
+        }
+        let missed_votes = liveness_missed_votes.at(&offline_validator);
+        if missed_votes.contains(storage, &votes_height.0)? {
+            continue;
+        }
+        tracing::debug!(
+            "Recording a missed vote at height {votes_height} for validator \
+             {offline_validator} reported as offline"
+        );
+        missed_votes.insert(storage, votes_height.0)?;
+        liveness_sum_missed_votes.update(
+            storage,
+            offline_validator,
+            |missed_votes| missed_votes.unwrap_or_default() + 1,
+        )?;
+    }
+
+    // Clear the applied reports
+    let reported_validators = liveness_reports
+        .iter(storage)?
+        .map(|entry| {
+            let (NestedSubKey::Data { key, .. }, ()) = entry?;
+            Ok(key)
+        })
+        .collect::<storage_api::Result<BTreeSet<Address>>>()?;
+    for validator in &reported_validators {
+        liveness_reports.remove_all(storage, validator)?;
+    }
+
+    Ok(())
+}
+
 /// Jail validators who failed to match the liveness threshold
 pub fn jail_for_liveness<S>(
     storage: &mut S,
@@ -5858,6 +7824,178 @@ where
     Ok(())
 }
 
+/// Prune historical PoS data at an epoch change. Every pruned structure
+/// declares its retention with a [`PruningStrategy`]: the epoched
+/// deltas and enqueued slashes keep the data of their last epochs, while the
+/// validators' total unbonded amounts and redelegation records are kept for as
+/// long as they may affect slashing. The validators' slashes are never pruned.
+///
+/// The rewards accumulator doesn't need to be pruned here, as it's cleared at
+/// the end of every epoch in [`update_rewards_products_and_mint_inflation`].
+pub fn prune_all<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    tracing::debug!("Pruning PoS data in epoch {current_epoch}");
+
+    // Trim the epoched data
+    enqueued_slashes_handle().update_data(storage, &params, current_epoch)?;
+    total_deltas_handle().update_data(storage, &params, current_epoch)?;
+
+    // Expired consensus key reservations
+    let reservations = consensus_key_reservations_handle();
+    let reservations_oldest =
+        ConsensusKeyReservationsPruning::oldest_epoch_to_keep(
+            &params,
+            current_epoch,
+        );
+    let epochs_to_purge = reservations
+        .iter(storage)?
+        .map(|res| {
+            let (
+                NestedSubKey::Data {
+                    key: epoch,
+                    nested_sub_key: _,
+                },
+                _,
+            ) = res?;
+            Ok(epoch)
+        })
+        .collect::<storage_api::Result<BTreeSet<_>>>()?;
+    for epoch in epochs_to_purge.range(..reservations_oldest) {
+        reservations.remove_all(storage, epoch)?;
+    }
+
+    let total_unbonded_oldest =
+        ValidatorTotalUnbondedPruning::oldest_epoch_to_keep(
+            &params,
+            current_epoch,
+        );
+    let redelegated_unbonded_oldest =
+        TotalRedelegatedUnbondedPruning::oldest_epoch_to_keep(
+            &params,
+            current_epoch,
+        );
+    let outgoing_redelegations_oldest =
+        OutgoingRedelegationsPruning::oldest_epoch_to_keep(
+            &params,
+            current_epoch,
+        );
+    let incoming_redelegations_oldest =
+        IncomingRedelegationsPruning::oldest_epoch_to_keep(
+            &params,
+            current_epoch,
+        );
+
+    for validator in read_all_validator_addresses(storage, current_epoch)? {
+        validator_deltas_handle(&validator).update_data(
+            storage,
+            &params,
+            current_epoch,
+        )?;
+
+        // Total unbonded and total redelegated unbonded, both keyed by the
+        // epoch in which the unbond took effect
+        let total_unbonded = total_unbonded_handle(&validator);
+        let epochs_to_purge = total_unbonded
+            .iter(storage)?
+            .map(|res| {
+                let (
+                    NestedSubKey::Data {
+                        key: epoch,
+                        nested_sub_key: _,
+                    },
+                    _,
+                ) = res?;
+                Ok(epoch)
+            })
+            .collect::<storage_api::Result<BTreeSet<_>>>()?;
+        for epoch in epochs_to_purge.range(..total_unbonded_oldest) {
+            total_unbonded.remove_all(storage, epoch)?;
+        }
+
+        let total_redelegated_unbonded =
+            validator_total_redelegated_unbonded_handle(&validator);
+        let epochs_to_purge = total_redelegated_unbonded
+            .iter(storage)?
+            .map(|res| {
+                let (
+                    NestedSubKey::Data {
+                        key: epoch,
+                        nested_sub_key: _,
+                    },
+                    _,
+                ) = res?;
+                Ok(epoch)
+            })
+            .collect::<storage_api::Result<BTreeSet<_>>>()?;
+        for epoch in epochs_to_purge.range(..redelegated_unbonded_oldest) {
+            total_redelegated_unbonded.remove_all(storage, epoch)?;
+        }
+
+        // Outgoing redelegations that stopped contributing to the source
+        // validator's stake before the horizon
+        let outgoing_redelegations =
+            validator_outgoing_redelegations_handle(&validator);
+        let redelegations_to_purge = outgoing_redelegations
+            .iter(storage)?
+            .filter_map(|res| {
+                let (
+                    NestedSubKey::Data {
+                        key: dest_validator,
+                        nested_sub_key:
+                            NestedSubKey::Data {
+                                key: bond_start,
+                                nested_sub_key: SubKey::Data(redel_start),
+                            },
+                    },
+                    _amount,
+                ) = match res {
+                    Ok(res) => res,
+                    Err(err) => return Some(Err(err)),
+                };
+                let redel_end =
+                    params.redelegation_end_epoch_from_start(redel_start);
+                (redel_end < outgoing_redelegations_oldest).then_some(Ok((
+                    dest_validator,
+                    bond_start,
+                    redel_start,
+                )))
+            })
+            .collect::<storage_api::Result<Vec<_>>>()?;
+        for (dest_validator, bond_start, redel_start) in redelegations_to_purge
+        {
+            outgoing_redelegations
+                .at(&dest_validator)
+                .at(&bond_start)
+                .remove(storage, &redel_start)?;
+        }
+
+        // Incoming redelegations that can no longer be considered chained
+        let incoming_redelegations =
+            validator_incoming_redelegations_handle(&validator);
+        let delegators_to_purge = incoming_redelegations
+            .iter(storage)?
+            .filter_map(|res| match res {
+                Ok((delegator, redel_end)) => (redel_end
+                    + params.slash_processing_epoch_offset()
+                    < incoming_redelegations_oldest)
+                    .then_some(Ok(delegator)),
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<storage_api::Result<Vec<_>>>()?;
+        for delegator in delegators_to_purge {
+            incoming_redelegations.remove(storage, &delegator)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// PoS related utility functions to help set up tests.
 pub mod test_utils {
@@ -6401,8 +8539,9 @@ where
                             &epoch,
                             &max_bc_validator,
                         )?;
-                        validator_state_handle(&max_bc_validator).set(
+                        set_validator_state(
                             storage,
+                            &max_bc_validator,
                             ValidatorState::Consensus,
                             current_epoch,
                             params.pipeline_len,
@@ -6457,8 +8596,9 @@ where
     let start_offset = validator_set_update_epoch.0 - current_epoch.0;
     // Set the validator state as `Jailed` thru the pipeline epoch
     for offset in start_offset..=params.pipeline_len {
-        validator_state_handle(validator).set(
+        set_validator_state(
             storage,
+            validator,
             ValidatorState::Jailed,
             current_epoch,
             offset,
@@ -8,6 +8,8 @@ use namada_core::types::token;
 use namada_core::types::uint::Uint;
 use thiserror::Error;
 
+use crate::inflation::InflationModelParams;
+
 /// Proof-of-Stake system parameters. This includes parameters that are used in
 /// PoS but are read from other accounts storage (governance).
 #[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
@@ -42,6 +44,9 @@ pub struct OwnedPosParams {
     /// Amount of tokens rewarded to each validator that voted on a block
     /// proposal
     pub block_vote_reward: Dec,
+    /// Amount of tokens rewarded to the validators whose Ethereum bridge pool
+    /// root or validator set update vote extensions were included in a block
+    pub block_vext_reward: Dec,
     /// Maximum staking rewards rate per annum
     pub max_inflation_rate: Dec,
     /// Target ratio of staked NAM tokens to total NAM tokens
@@ -64,6 +69,41 @@ pub struct OwnedPosParams {
     /// The minimum required activity of consesus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The number of epochs, on top of the slash processing delay, for which
+    /// historical PoS data (total unbonded amounts, redelegation records and
+    /// enqueued slashes) is retained before it gets purged
+    pub past_data_retention_len: u64,
+    /// The number of past epochs for which the validator sets and the
+    /// validators' positions in them are kept in storage. The consensus
+    /// validator sets are kept for at least the max proposal period on top.
+    /// The value must be greater or equal to
+    /// [`MIN_VALIDATOR_SETS_RETENTION_LEN`].
+    pub validator_sets_retention_len: u64,
+    /// The maximum age, in epochs, of the evidence of a misbehavior for which
+    /// a validator can still be slashed. Older evidence is rejected. The value
+    /// must be lower or equal to `unbonding_len`.
+    pub evidence_max_age_epochs: u64,
+    /// The maximum number of slashed validators whose slashes are processed
+    /// in a single block. When there are more slashed validators, their
+    /// processing is spread over the first blocks of the epoch. Zero means
+    /// that all the slashes are processed at the beginning of the epoch.
+    pub max_slashed_validators_per_block: u64,
+    /// The number of epochs for which a validator must have had no stake and
+    /// no bonds before it gets deregistered and its data is purged from
+    /// storage. Zero means that validators are never deregistered. A non-zero
+    /// value must be greater than `evidence_max_age_epochs`.
+    pub validator_deregistration_len: u64,
+    /// The model used to compute the PoS inflation minted for every epoch
+    pub inflation_model: InflationModelParams,
+    /// The share of the total consensus stake above which a validator's
+    /// reward for being in the consensus set is reduced, to incentivize
+    /// delegators to spread their stake. One means no reduction.
+    pub concentration_penalty_threshold: Dec,
+    /// The rate at which the consensus set reward of a validator above the
+    /// `concentration_penalty_threshold` is reduced, relative to its share of
+    /// the stake above the threshold. The reduction is redistributed to the
+    /// validators below the threshold.
+    pub concentration_penalty_rate: Dec,
 }
 
 impl Default for PosParams {
@@ -88,6 +128,7 @@ impl Default for OwnedPosParams {
             tm_votes_per_token: Dec::one(),
             block_proposer_reward: Dec::new(125, 3).expect("Test failed"),
             block_vote_reward: Dec::new(1, 1).expect("Test failed"),
+            block_vext_reward: Dec::new(1, 2).expect("Test failed"),
             // PoS inflation of 10%
             max_inflation_rate: Dec::new(1, 1).expect("Test failed"),
             // target staked ratio of 2/3
@@ -101,13 +142,21 @@ impl Default for OwnedPosParams {
             validator_stake_threshold: token::Amount::native_whole(1_u64),
             liveness_window_check: 10_000,
             liveness_threshold: Dec::new(9, 1).expect("Test failed"),
+            past_data_retention_len: 2,
+            validator_sets_retention_len: 2,
+            evidence_max_age_epochs: 21,
+            max_slashed_validators_per_block: 0,
+            validator_deregistration_len: 0,
+            inflation_model: InflationModelParams::PdController,
+            concentration_penalty_threshold: Dec::one(),
+            concentration_penalty_rate: Dec::zero(),
         }
     }
 }
 
 #[allow(missing_docs)]
-#[derive(Error, Debug)]
-pub enum ValidationError {
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
     #[error(
         "Maximum total voting power is too large: got {0}, expected at most \
          {MAX_TOTAL_VOTING_POWER}"
@@ -115,6 +164,8 @@ pub enum ValidationError {
     TotalVotingPowerTooLarge(Uint),
     #[error("Votes per token cannot be greater than 1, got {0}")]
     VotesPerTokenGreaterThanOne(Dec),
+    #[error("Max validator slots must be > 0")]
+    MaxValidatorSlotsIsZero,
     #[error("Pipeline length must be >= 2, got {0}")]
     PipelineLenTooShort(u64),
     #[error(
@@ -122,8 +173,40 @@ pub enum ValidationError {
          pipeline: {1}"
     )]
     UnbondingLenTooShort(u64, u64),
+    #[error("Liveness window check must be > 0")]
+    LivenessWindowCheckIsZero,
+    #[error("Liveness threshold must be between 0 and 1, got {0}")]
+    LivenessThresholdOutOfRange(Dec),
+    #[error(
+        "Validator sets retention length must be >= \
+         {MIN_VALIDATOR_SETS_RETENTION_LEN}, got {0}"
+    )]
+    ValidatorSetsRetentionLenTooShort(u64),
+    #[error(
+        "Evidence max age must be <= unbonding length. Got evidence max age: \
+         {0}, unbonding: {1}"
+    )]
+    EvidenceMaxAgeTooLong(u64, u64),
+    #[error(
+        "Validator deregistration length must be 0 or > evidence max age. \
+         Got deregistration length: {0}, evidence max age: {1}"
+    )]
+    ValidatorDeregistrationLenTooShort(u64, u64),
+    #[error("The {0} must be between 0 and 1, got {1}")]
+    RateOutOfRange(&'static str, Dec),
+    #[error(
+        "Validator stake threshold must be <= the maximum token amount, got \
+         {}",
+        .0.to_string_native()
+    )]
+    ValidatorStakeThresholdTooLarge(token::Amount),
 }
 
+/// The minimum number of past epochs for which the validator sets must be
+/// kept, as the sets of the previous epochs are needed to compute the
+/// validator set updates and to process the evidence of the last blocks
+pub const MIN_VALIDATOR_SETS_RETENTION_LEN: u64 = 2;
+
 /// The number of fundamental units per whole token of the native staking token
 pub const TOKENS_PER_NAM: u64 = 1_000_000;
 
@@ -137,16 +220,19 @@ impl OwnedPosParams {
     /// Validate PoS parameters values. Returns an empty list if the values are
     /// valid.
     #[must_use]
-    pub fn validate(&self) -> Vec<ValidationError> {
+    pub fn validate(&self) -> Vec<ParamError> {
         let mut errors = vec![];
 
+        if self.max_validator_slots == 0 {
+            errors.push(ParamError::MaxValidatorSlotsIsZero);
+        }
+
         if self.pipeline_len < 2 {
-            errors
-                .push(ValidationError::PipelineLenTooShort(self.pipeline_len));
+            errors.push(ParamError::PipelineLenTooShort(self.pipeline_len));
         }
 
         if self.pipeline_len >= self.unbonding_len {
-            errors.push(ValidationError::UnbondingLenTooShort(
+            errors.push(ParamError::UnbondingLenTooShort(
                 self.unbonding_len,
                 self.pipeline_len,
             ))
@@ -162,23 +248,106 @@ impl OwnedPosParams {
         match i64::try_from(max_total_voting_power) {
             Ok(max_total_voting_power_i64) => {
                 if max_total_voting_power_i64 > MAX_TOTAL_VOTING_POWER {
-                    errors.push(ValidationError::TotalVotingPowerTooLarge(
+                    errors.push(ParamError::TotalVotingPowerTooLarge(
                         max_total_voting_power,
                     ))
                 }
             }
-            Err(_) => errors.push(ValidationError::TotalVotingPowerTooLarge(
+            Err(_) => errors.push(ParamError::TotalVotingPowerTooLarge(
                 max_total_voting_power,
             )),
         }
 
         // Check that there is no more than 1 vote per token
         if self.tm_votes_per_token > Dec::one() {
-            errors.push(ValidationError::VotesPerTokenGreaterThanOne(
+            errors.push(ParamError::VotesPerTokenGreaterThanOne(
                 self.tm_votes_per_token,
             ))
         }
 
+        // Check that the liveness window is not empty and the liveness
+        // threshold is a valid fraction
+        if self.liveness_window_check == 0 {
+            errors.push(ParamError::LivenessWindowCheckIsZero)
+        }
+        if self.liveness_threshold.is_negative()
+            || self.liveness_threshold > Dec::one()
+        {
+            errors.push(ParamError::LivenessThresholdOutOfRange(
+                self.liveness_threshold,
+            ))
+        }
+
+        // Check that all the rates are valid fractions
+        for (name, rate) in [
+            ("block proposer reward", self.block_proposer_reward),
+            ("block vote reward", self.block_vote_reward),
+            ("block vote extension reward", self.block_vext_reward),
+            ("max inflation rate", self.max_inflation_rate),
+            ("target staked ratio", self.target_staked_ratio),
+            (
+                "duplicate vote min slash rate",
+                self.duplicate_vote_min_slash_rate,
+            ),
+            (
+                "light client attack min slash rate",
+                self.light_client_attack_min_slash_rate,
+            ),
+            (
+                "concentration penalty threshold",
+                self.concentration_penalty_threshold,
+            ),
+            (
+                "concentration penalty rate",
+                self.concentration_penalty_rate,
+            ),
+        ] {
+            if rate.is_negative() || rate > Dec::one() {
+                errors.push(ParamError::RateOutOfRange(name, rate))
+            }
+        }
+        if let Some(rate) = self.inflation_model.fixed_rate() {
+            if rate.is_negative() || rate > Dec::one() {
+                errors.push(ParamError::RateOutOfRange(
+                    "inflation model rate",
+                    rate,
+                ))
+            }
+        }
+
+        if self.validator_stake_threshold
+            > token::Amount::native_whole(TOKEN_MAX_AMOUNT)
+        {
+            errors.push(ParamError::ValidatorStakeThresholdTooLarge(
+                self.validator_stake_threshold,
+            ))
+        }
+
+        if self.validator_sets_retention_len < MIN_VALIDATOR_SETS_RETENTION_LEN
+        {
+            errors.push(ParamError::ValidatorSetsRetentionLenTooShort(
+                self.validator_sets_retention_len,
+            ))
+        }
+
+        if self.evidence_max_age_epochs > self.unbonding_len {
+            errors.push(ParamError::EvidenceMaxAgeTooLong(
+                self.evidence_max_age_epochs,
+                self.unbonding_len,
+            ))
+        }
+
+        // A validator must not be deregistered while there may still be
+        // valid evidence of its misbehavior
+        if self.validator_deregistration_len != 0
+            && self.validator_deregistration_len <= self.evidence_max_age_epochs
+        {
+            errors.push(ParamError::ValidatorDeregistrationLenTooShort(
+                self.validator_deregistration_len,
+                self.evidence_max_age_epochs,
+            ))
+        }
+
         errors
     }
 
@@ -194,6 +363,15 @@ impl OwnedPosParams {
         self.unbonding_len + self.cubic_slashing_window_length + 1
     }
 
+    /// Get the oldest epoch of historical PoS data that must be kept in
+    /// storage at the given current epoch. Any data older than this epoch can
+    /// no longer affect slashing and may be purged.
+    pub fn oldest_epoch_to_retain(&self, current_epoch: Epoch) -> Epoch {
+        current_epoch.sub_or_default(Epoch(
+            self.slash_processing_epoch_offset() + self.past_data_retention_len,
+        ))
+    }
+
     /// Get the first and the last epoch of a cubic slash window.
     pub fn cubic_slash_epoch_window(
         &self,
@@ -247,6 +425,17 @@ impl OwnedPosParams {
     }
 }
 
+/// Validate PoS parameters values, returning all the violated invariants if
+/// the values are not valid.
+pub fn validate(params: &OwnedPosParams) -> Result<(), Vec<ParamError>> {
+    let errors = params.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 impl std::ops::Deref for PosParams {
     type Target = OwnedPosParams;
 
@@ -275,6 +464,115 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_validate_liveness_params() {
+        let params = OwnedPosParams {
+            liveness_window_check: 0,
+            liveness_threshold: Dec::new(11, 1).expect("Test failed"),
+            ..Default::default()
+        };
+        let errors = params.validate();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], ParamError::LivenessWindowCheckIsZero));
+        assert!(matches!(
+            errors[1],
+            ParamError::LivenessThresholdOutOfRange(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_validator_sets_retention_len() {
+        let params = OwnedPosParams {
+            validator_sets_retention_len: MIN_VALIDATOR_SETS_RETENTION_LEN - 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.validate(),
+            vec![ParamError::ValidatorSetsRetentionLenTooShort(
+                MIN_VALIDATOR_SETS_RETENTION_LEN - 1
+            )]
+        );
+
+        let params = OwnedPosParams {
+            validator_sets_retention_len: MIN_VALIDATOR_SETS_RETENTION_LEN + 10,
+            ..Default::default()
+        };
+        assert!(params.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_evidence_max_age_epochs() {
+        let params = OwnedPosParams {
+            unbonding_len: 4,
+            evidence_max_age_epochs: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.validate(),
+            vec![ParamError::EvidenceMaxAgeTooLong(5, 4)]
+        );
+
+        let params = OwnedPosParams {
+            unbonding_len: 4,
+            evidence_max_age_epochs: 4,
+            ..Default::default()
+        };
+        assert!(params.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_validator_deregistration_len() {
+        let params = OwnedPosParams {
+            evidence_max_age_epochs: 4,
+            validator_deregistration_len: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.validate(),
+            vec![ParamError::ValidatorDeregistrationLenTooShort(4, 4)]
+        );
+
+        for validator_deregistration_len in [0, 5] {
+            let params = OwnedPosParams {
+                evidence_max_age_epochs: 4,
+                validator_deregistration_len,
+                ..Default::default()
+            };
+            assert!(params.validate().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_validate_rates_and_limits() {
+        assert_eq!(validate(&OwnedPosParams::default()), Ok(()));
+
+        let params = OwnedPosParams {
+            max_validator_slots: 0,
+            max_inflation_rate: Dec::new(11, 1).expect("Test failed"),
+            duplicate_vote_min_slash_rate: -Dec::new(1, 3)
+                .expect("Test failed"),
+            validator_stake_threshold: token::Amount::native_whole(
+                TOKEN_MAX_AMOUNT,
+            ) + token::Amount::from(1_u64),
+            ..Default::default()
+        };
+        let errors = validate(&params).unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(errors[0], ParamError::MaxValidatorSlotsIsZero));
+        assert!(matches!(
+            errors[1],
+            ParamError::RateOutOfRange("max inflation rate", _)
+        ));
+        assert!(matches!(
+            errors[2],
+            ParamError::RateOutOfRange("duplicate vote min slash rate", _)
+        ));
+        assert!(matches!(
+            errors[3],
+            ParamError::ValidatorStakeThresholdTooLarge(_)
+        ));
+    }
 }
 
 /// Testing helpers
@@ -300,6 +598,7 @@ pub mod testing {
                 pipeline_len,
                 unbonding_len,
                 tm_votes_per_token: Dec::new(tm_votes_per_token, 4).expect("Test failed"),
+                evidence_max_age_epochs: unbonding_len,
                 // The rest of the parameters that are not being used in the PoS
                 // VP are constant for now
                 ..Default::default()
@@ -1,6 +1,8 @@
 //! Storage API for querying data about Proof-of-stake related
 //! data. This includes validator and epoch related data.
 
+use std::collections::{BTreeMap, HashMap};
+
 use namada_core::ledger::parameters::storage::get_max_proposal_bytes_key;
 use namada_core::ledger::storage::WlStorage;
 use namada_core::ledger::storage_api::collections::lazy_map::NestedSubKey;
@@ -11,10 +13,14 @@ use namada_core::types::storage::{BlockHeight, Epoch};
 use namada_core::types::{key, token};
 use thiserror::Error;
 
+use crate::epoched::{
+    EpochOffset, OffsetMaxProposalPeriodOrSlashProcessingLenPlus,
+};
 use crate::types::WeightedValidator;
 use crate::{
-    consensus_validator_set_handle, find_validator_by_raw_hash,
-    get_total_consensus_stake, read_pos_params, validator_eth_cold_key_handle,
+    consensus_validator_set_handle, find_delegations,
+    find_validator_by_raw_hash, get_total_consensus_stake, is_validator,
+    read_pos_params, read_validator_stake, validator_eth_cold_key_handle,
     validator_eth_hot_key_handle, ConsensusValidatorSet, PosParams,
 };
 
@@ -44,11 +50,59 @@ pub enum Error {
         "The public key hash '{0}' does not belong to a validator in storage"
     )]
     NotValidatorKeyHash(String),
+    /// The stake data of the requested epoch is no longer retained in
+    /// storage.
+    #[error(
+        "The stake at epoch {0} is no longer retained in storage, the oldest \
+         retained epoch is {1}"
+    )]
+    EpochNotRetained(Epoch, Epoch),
 }
 
 /// Result type returned by [`PosQueries`] operations.
 pub type Result<T> = ::std::result::Result<T, Error>;
 
+/// The number of epochs whose consensus validators are kept indexed by
+/// protocol key in a [`ProtocolKeyCache`].
+const PROTOCOL_KEY_CACHE_EPOCHS: usize = 2;
+
+/// In-memory index of the consensus validators of the most recently looked
+/// up epochs, by their protocol keys. The index of some epoch is built the
+/// first time a validator is looked up at that epoch, by
+/// [`PosQueriesHook::get_validator_from_protocol_pk_cached`].
+///
+/// The cache must be invalidated whenever the consensus validator sets or
+/// the protocol keys of the cached epochs may have been written to.
+#[derive(Debug, Clone, Default)]
+pub struct ProtocolKeyCache {
+    epochs: BTreeMap<Epoch, HashMap<key::common::PublicKey, WeightedValidator>>,
+}
+
+impl ProtocolKeyCache {
+    /// Check if the consensus validators of the given epoch are cached.
+    pub fn contains_epoch(&self, epoch: Epoch) -> bool {
+        self.epochs.contains_key(&epoch)
+    }
+
+    /// Drop all the cached indices.
+    pub fn invalidate(&mut self) {
+        self.epochs.clear();
+    }
+
+    /// Cache the index of the consensus validators of the given epoch,
+    /// evicting the index of the oldest cached epoch if the cache is full.
+    fn insert(
+        &mut self,
+        epoch: Epoch,
+        index: HashMap<key::common::PublicKey, WeightedValidator>,
+    ) {
+        self.epochs.insert(epoch, index);
+        while self.epochs.len() > PROTOCOL_KEY_CACHE_EPOCHS {
+            self.epochs.pop_first();
+        }
+    }
+}
+
 /// Methods used to query blockchain proof-of-stake related state,
 /// such as the current set of consensus validators.
 pub trait PosQueries {
@@ -137,6 +191,35 @@ where
             .unwrap_or_default()
     }
 
+    /// Lookup the voting power of an address at the given epoch, derived from
+    /// its bonded stake. The voting power of a validator is its total bonded
+    /// stake, including delegations, while the voting power of any other
+    /// address is the sum of its delegations. Only epochs whose stake data is
+    /// still retained in storage can be queried.
+    pub fn get_voting_power_at(
+        self,
+        address: &Address,
+        epoch: Epoch,
+    ) -> Result<token::Amount> {
+        let params = self.get_pos_params();
+        let current_epoch = self.wl_storage.storage.get_current_epoch().0;
+        let oldest_epoch = current_epoch.sub_or_default(Epoch(
+            OffsetMaxProposalPeriodOrSlashProcessingLenPlus::value(&params),
+        ));
+        if epoch < oldest_epoch {
+            return Err(Error::EpochNotRetained(epoch, oldest_epoch));
+        }
+        if is_validator(self.wl_storage, address).map_err(Error::Storage)? {
+            read_validator_stake(self.wl_storage, &params, address, epoch)
+                .map_err(Error::Storage)
+        } else {
+            let delegations =
+                find_delegations(self.wl_storage, address, &epoch)
+                    .map_err(Error::Storage)?;
+            Ok(delegations.into_values().sum())
+        }
+    }
+
     /// Lookup data about a validator from their protocol signing key.
     pub fn get_validator_from_protocol_pk(
         self,
@@ -160,6 +243,49 @@ where
             .ok_or_else(|| Error::NotValidatorKey(pk.to_string(), epoch))
     }
 
+    /// Lookup data about a validator from their protocol signing key, like
+    /// [`Self::get_validator_from_protocol_pk`], but using an index of the
+    /// consensus validators of the given epoch by protocol key, which is
+    /// built on first use and kept in `cache`.
+    pub fn get_validator_from_protocol_pk_cached(
+        self,
+        pk: &key::common::PublicKey,
+        epoch: Option<Epoch>,
+        cache: &mut ProtocolKeyCache,
+    ) -> Result<WeightedValidator> {
+        let epoch = epoch
+            .unwrap_or_else(|| self.wl_storage.storage.get_current_epoch().0);
+        if !cache.contains_epoch(epoch) {
+            let index = self.index_validators_by_protocol_pk(epoch)?;
+            cache.insert(epoch, index);
+        }
+        cache.epochs[&epoch]
+            .get(pk)
+            .cloned()
+            .ok_or_else(|| Error::NotValidatorKey(pk.to_string(), epoch))
+    }
+
+    /// Index the consensus validators of the given epoch by their protocol
+    /// signing keys.
+    fn index_validators_by_protocol_pk(
+        self,
+        epoch: Epoch,
+    ) -> Result<HashMap<key::common::PublicKey, WeightedValidator>> {
+        let params = self.get_pos_params();
+        self.get_consensus_validators(Some(epoch))
+            .iter()
+            .filter_map(|validator| {
+                let protocol_keys =
+                    crate::validator_protocol_key_handle(&validator.address);
+                match protocol_keys.get(self.wl_storage, epoch, &params) {
+                    Ok(Some(key)) => Some(Ok((key, validator))),
+                    Ok(None) => None,
+                    Err(err) => Some(Err(Error::Storage(err))),
+                }
+            })
+            .collect()
+    }
+
     /// Lookup data about a validator from their address.
     pub fn get_validator_from_address(
         self,
@@ -0,0 +1,178 @@
+//! History of the block proposers. The proposer of every block is recorded in
+//! a ring buffer of the last [`PROPOSER_HISTORY_LEN`] blocks, so that the
+//! frequency with which the validators propose blocks can be compared with
+//! their stake, e.g. to detect anomalies in the weighting of the proposer
+//! selection or to audit the proposer rewards.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage_api::{self, StorageRead, StorageWrite};
+use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
+use namada_core::types::storage::BlockHeight;
+
+use crate::proposer_history_handle;
+use crate::types::ProposerRecord;
+
+/// The number of the last blocks whose proposers are kept in the history
+pub const PROPOSER_HISTORY_LEN: u64 = 10_000;
+
+/// The statistics of the block proposers over a window of block heights,
+/// computed by [`proposer_stats`]
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct ProposerStats {
+    /// The number of blocks in the window whose proposer is recorded. This
+    /// is less than the length of the window if the window reaches past the
+    /// start of the history.
+    pub recorded_blocks: u64,
+    /// The number of blocks proposed by each validator in the window
+    pub proposed_blocks: BTreeMap<Address, u64>,
+}
+
+impl ProposerStats {
+    /// The fraction of the recorded blocks in the window proposed by the
+    /// given validator
+    pub fn frequency(&self, validator: &Address) -> Dec {
+        if self.recorded_blocks == 0 {
+            return Dec::zero();
+        }
+        let proposed = self
+            .proposed_blocks
+            .get(validator)
+            .copied()
+            .unwrap_or_default();
+        Dec::from(proposed) / Dec::from(self.recorded_blocks)
+    }
+}
+
+/// Record the proposer of the block at the given height, overwriting the
+/// record of the block [`PROPOSER_HISTORY_LEN`] blocks before it
+pub fn record_block_proposer<S>(
+    storage: &mut S,
+    height: BlockHeight,
+    proposer: Address,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    proposer_history_handle().insert(
+        storage,
+        height.0 % PROPOSER_HISTORY_LEN,
+        ProposerRecord { height, proposer },
+    )?;
+    Ok(())
+}
+
+/// Read the proposer of the block at the given height, if it's still in the
+/// history
+pub fn read_block_proposer<S>(
+    storage: &S,
+    height: BlockHeight,
+) -> storage_api::Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    let record = proposer_history_handle()
+        .get(storage, &(height.0 % PROPOSER_HISTORY_LEN))?;
+    Ok(record
+        .filter(|record| record.height == height)
+        .map(|record| record.proposer))
+}
+
+/// Count the blocks proposed by each validator from `from_height` up to and
+/// including `to_height`. Only the blocks that are still in the history are
+/// counted.
+pub fn proposer_stats<S>(
+    storage: &S,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+) -> storage_api::Result<ProposerStats>
+where
+    S: StorageRead,
+{
+    let mut stats = ProposerStats::default();
+    if from_height > to_height {
+        return Ok(stats);
+    }
+    // The whole history is read with a single prefix iteration, which is
+    // cheaper than looking up the heights in the window one by one
+    for result in proposer_history_handle().iter(storage)? {
+        let (_slot, ProposerRecord { height, proposer }) = result?;
+        if height < from_height || height > to_height {
+            continue;
+        }
+        stats.recorded_blocks += 1;
+        *stats.proposed_blocks.entry(proposer).or_default() += 1;
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod test_proposer_history {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    use super::*;
+
+    /// Test that the proposers are kept for the last blocks only and that the
+    /// stats count the blocks in the window
+    #[test]
+    fn test_proposer_history() {
+        let mut s = TestWlStorage::default();
+        let validator_1 = established_address_1();
+        let validator_2 = established_address_2();
+
+        // The first validator proposes every third block
+        let last_height = PROPOSER_HISTORY_LEN + 99;
+        for height in 1..=last_height {
+            let proposer = if height % 3 == 0 {
+                validator_1.clone()
+            } else {
+                validator_2.clone()
+            };
+            record_block_proposer(&mut s, BlockHeight(height), proposer)
+                .unwrap();
+        }
+
+        // The oldest blocks have been overwritten
+        assert_eq!(read_block_proposer(&s, BlockHeight(99)).unwrap(), None);
+        assert_eq!(
+            read_block_proposer(&s, BlockHeight(102)).unwrap(),
+            Some(validator_1.clone())
+        );
+        assert_eq!(
+            read_block_proposer(&s, BlockHeight(last_height)).unwrap(),
+            Some(validator_2.clone())
+        );
+
+        let stats = proposer_stats(
+            &s,
+            BlockHeight(last_height - 299),
+            BlockHeight(last_height),
+        )
+        .unwrap();
+        assert_eq!(stats.recorded_blocks, 300);
+        assert_eq!(stats.proposed_blocks[&validator_1], 100);
+        assert_eq!(stats.proposed_blocks[&validator_2], 200);
+        assert_eq!(
+            stats.frequency(&validator_1),
+            Dec::one() / Dec::from(3_u64)
+        );
+
+        // A window reaching past the start of the history only counts the
+        // recorded blocks
+        let stats =
+            proposer_stats(&s, BlockHeight(1), BlockHeight(last_height))
+                .unwrap();
+        assert_eq!(stats.recorded_blocks, PROPOSER_HISTORY_LEN);
+        assert_eq!(
+            stats.proposed_blocks.values().sum::<u64>(),
+            PROPOSER_HISTORY_LEN
+        );
+    }
+}
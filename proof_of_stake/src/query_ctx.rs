@@ -0,0 +1,163 @@
+//! Read-only context for PoS queries. The context reads the PoS parameters
+//! once on construction, so that a query made of many reads, e.g. of the
+//! stakes of all the validators, doesn't read and decode them again for every
+//! one of them.
+
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+
+use crate::parameters::PosParams;
+use crate::types::{
+    BondId, BondsAndUnbondsDetails, CommissionPair, TotalDeltas, ValidatorState,
+};
+use crate::{
+    compute_bond_amount, find_bonds_and_unbonds_details,
+    get_multiple_bonds_and_unbonds, read_bond_amount_checkpoint,
+    read_pos_params, read_validator_max_commission_rate_change,
+    read_validator_stake, total_deltas_handle,
+    validator_commission_rate_handle, validator_state_handle,
+};
+
+/// A read-only context for PoS queries over some storage, with the PoS
+/// parameters and the commonly used handles loaded once. The context only
+/// holds a shared reference to the storage, so the handlers serving
+/// concurrent queries can each make their own context over the same storage.
+pub struct PosQueryCtx<'a, S> {
+    storage: &'a S,
+    params: PosParams,
+    total_deltas: TotalDeltas,
+}
+
+impl<'a, S> PosQueryCtx<'a, S>
+where
+    S: StorageRead,
+{
+    /// Make a new context, reading the PoS parameters from the storage
+    pub fn new(storage: &'a S) -> storage_api::Result<Self> {
+        let params = read_pos_params(storage)?;
+        Ok(Self::with_params(storage, params))
+    }
+
+    /// Make a new context with the PoS parameters that have already been read
+    /// from the storage
+    pub fn with_params(storage: &'a S, params: PosParams) -> Self {
+        Self {
+            storage,
+            params,
+            total_deltas: total_deltas_handle(),
+        }
+    }
+
+    /// The storage of the context
+    pub fn storage(&self) -> &'a S {
+        self.storage
+    }
+
+    /// The PoS parameters
+    pub fn params(&self) -> &PosParams {
+        &self.params
+    }
+
+    /// Read the stake of a validator at the given epoch. For non-validators
+    /// and validators with `0` stake, this returns `token::Amount::zero()`.
+    pub fn read_validator_stake(
+        &self,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> storage_api::Result<token::Amount> {
+        read_validator_stake(self.storage, &self.params, validator, epoch)
+    }
+
+    /// Read the total stake of all the validators at the given epoch
+    pub fn read_total_stake(
+        &self,
+        epoch: Epoch,
+    ) -> storage_api::Result<token::Amount> {
+        let amount = self
+            .total_deltas
+            .get_sum(self.storage, epoch, &self.params)?
+            .map(|change| {
+                debug_assert!(change.non_negative());
+                token::Amount::from_change(change)
+            })
+            .unwrap_or_default();
+        Ok(amount)
+    }
+
+    /// Read the state of a validator at the given epoch
+    pub fn validator_state(
+        &self,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> storage_api::Result<Option<ValidatorState>> {
+        validator_state_handle(validator).get(self.storage, epoch, &self.params)
+    }
+
+    /// Read the commission rate of a validator at the given epoch, together
+    /// with its max commission rate change per epoch
+    pub fn validator_commission(
+        &self,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> storage_api::Result<Option<CommissionPair>> {
+        let commission_rate = validator_commission_rate_handle(validator).get(
+            self.storage,
+            epoch,
+            &self.params,
+        )?;
+        let max_commission_change_per_epoch =
+            read_validator_max_commission_rate_change(self.storage, validator)?;
+        Ok(commission_rate.zip(max_commission_change_per_epoch).map(
+            |(commission_rate, max_commission_change_per_epoch)| {
+                CommissionPair {
+                    commission_rate,
+                    max_commission_change_per_epoch,
+                }
+            },
+        ))
+    }
+
+    /// Get the bond amount, after slashing, of the given bond at the given
+    /// epoch. For future epochs the value is subject to change.
+    pub fn bond_amount(
+        &self,
+        bond_id: &BondId,
+        epoch: Epoch,
+    ) -> storage_api::Result<token::Amount> {
+        if let Some(amount) = read_bond_amount_checkpoint(
+            self.storage,
+            &self.params,
+            bond_id,
+            epoch,
+        )? {
+            return Ok(amount);
+        }
+        compute_bond_amount(self.storage, &self.params, bond_id, epoch)
+    }
+
+    /// Collect the details of all bonds and unbonds that match the source and
+    /// validator arguments. If either source or validator is `None`, then
+    /// grab the information for all sources or validators, respectively.
+    pub fn bonds_and_unbonds(
+        &self,
+        source: Option<Address>,
+        validator: Option<Address>,
+    ) -> storage_api::Result<BondsAndUnbondsDetails> {
+        match (source, validator) {
+            (Some(source), Some(validator)) => find_bonds_and_unbonds_details(
+                self.storage,
+                &self.params,
+                source,
+                validator,
+            ),
+            (source, validator) => get_multiple_bonds_and_unbonds(
+                self.storage,
+                &self.params,
+                source,
+                validator,
+            ),
+        }
+    }
+}
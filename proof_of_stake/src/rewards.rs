@@ -1,10 +1,24 @@
 //! PoS rewards distribution.
 
+use std::collections::HashMap;
+
+use namada_core::ledger::parameters::storage as params_storage;
+use namada_core::ledger::storage_api::{self, OptionExt, StorageRead};
+use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
+use namada_core::types::storage::Epoch;
 use namada_core::types::token::Amount;
 use namada_core::types::uint::{Uint, I256};
 use thiserror::Error;
 
+use crate::parameters::PosParams;
+use crate::types::ValidatorState;
+use crate::{
+    read_consensus_validator_set_addresses_with_stake, read_pos_params,
+    total_consensus_stake_key_handle, validator_commission_rate_handle,
+    validator_state_handle,
+};
+
 /// This is equal to 0.01.
 const MIN_PROPOSER_REWARD: Dec =
     Dec(I256(Uint([10000000000u64, 0u64, 0u64, 0u64])));
@@ -27,15 +41,56 @@ pub enum RewardsError {
     CoeffsNotSet,
 }
 
-/// Holds coefficients for the three different ways to get PoS rewards
+/// Holds coefficients for the four different ways to get PoS rewards
 #[derive(Debug, Copy, Clone)]
 #[allow(missing_docs)]
 pub struct PosRewards {
     pub proposer_coeff: Dec,
     pub signer_coeff: Dec,
+    pub vext_coeff: Dec,
     pub active_val_coeff: Dec,
 }
 
+/// The rewards of a validator for the last epoch, derived from its rewards
+/// accumulator
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorRewards {
+    /// The validator address
+    pub validator: Address,
+    /// The validator's fraction of the inflation, i.e. its rewards
+    /// accumulator divided by the number of blocks in the epoch
+    pub reward_fraction: Dec,
+    /// The inflation tokens attributed to the validator
+    pub reward_tokens: Amount,
+    /// The new rewards product of the validator for the epoch
+    pub product: Dec,
+    /// The commissions earned by the validator
+    pub commissions: Amount,
+    /// The reward tokens that can't be claimed because of the rounding of
+    /// the commissions and of the rewards product
+    pub dust: Amount,
+}
+
+/// The distribution of the PoS inflation minted for the last epoch
+#[derive(Debug, Clone, PartialEq)]
+pub struct InflationDistribution {
+    /// The epoch for which the inflation was minted
+    pub epoch: Epoch,
+    /// The number of blocks in the epoch
+    pub num_blocks: u64,
+    /// The total PoS inflation for the epoch
+    pub inflation: Amount,
+    /// The inflation tokens minted into the PoS account
+    pub pos_reward_tokens: Amount,
+    /// The recipient of the inflation tokens left-over from rounding
+    pub dust_recipient: Address,
+    /// The inflation tokens left-over from rounding, minted into the
+    /// `dust_recipient`
+    pub dust_tokens: Amount,
+    /// The rewards of every validator with a rewards accumulator
+    pub validators: Vec<ValidatorRewards>,
+}
+
 /// Holds relevant PoS parameters and is used to calculate the coefficients for
 /// the rewards
 #[derive(Debug, Copy, Clone)]
@@ -44,8 +99,14 @@ pub struct PosRewardsCalculator {
     pub proposer_reward: Dec,
     /// Rewards fraction that goes to the block signers
     pub signer_reward: Dec,
+    /// Rewards fraction that goes to the validators whose Ethereum bridge
+    /// vote extensions were included in the block
+    pub vext_reward: Dec,
     /// Total stake of validators who signed the block
     pub signing_stake: Amount,
+    /// Total stake of validators whose Ethereum bridge vote extensions were
+    /// included in the block
+    pub vext_stake: Amount,
     /// Total stake of the whole consensus set
     pub total_stake: Amount,
 }
@@ -60,7 +121,9 @@ impl PosRewardsCalculator {
         let Self {
             proposer_reward,
             signer_reward,
+            vext_reward,
             signing_stake,
+            vext_stake,
             total_stake,
         } = *self;
 
@@ -77,11 +140,20 @@ impl PosRewardsCalculator {
                 / Dec::from(total_stake)
                 + MIN_PROPOSER_REWARD;
         let signer_coeff = signer_reward;
-        let active_val_coeff = Dec::one() - proposer_coeff - signer_coeff;
+        // If no vote extensions were included, their share of the rewards goes
+        // to the consensus validators
+        let vext_coeff = if vext_stake.is_zero() {
+            Dec::zero()
+        } else {
+            vext_reward
+        };
+        let active_val_coeff =
+            Dec::one() - proposer_coeff - signer_coeff - vext_coeff;
 
         let coeffs = PosRewards {
             proposer_coeff,
             signer_coeff,
+            vext_coeff,
             active_val_coeff,
         };
 
@@ -99,3 +171,166 @@ impl PosRewardsCalculator {
             / 3u64
     }
 }
+
+/// Compute the fractions of the consensus set reward, i.e. of the
+/// `active_val_coeff` portion of the block rewards, of the given consensus
+/// validators with their stake.
+///
+/// Without a concentration penalty, a validator's fraction is its share of the
+/// total consensus stake. Otherwise, the fraction of a validator whose share
+/// is above the `concentration_penalty_threshold` is reduced by the
+/// `concentration_penalty_rate` of its share above the threshold and the
+/// reduction is redistributed to the other validators, pro rata to their
+/// stake, so that the fractions still add up to one. If every validator is
+/// above the threshold, there's no one to redistribute to and no penalty is
+/// applied.
+pub fn consensus_reward_fractions(
+    params: &PosParams,
+    validators: impl IntoIterator<Item = (Address, Amount)>,
+    total_stake: Amount,
+) -> HashMap<Address, Dec> {
+    if total_stake.is_zero() {
+        return HashMap::new();
+    }
+    let total_stake = Dec::from(total_stake);
+    let shares: Vec<(Address, Dec)> = validators
+        .into_iter()
+        .filter(|(_address, stake)| !stake.is_zero())
+        .map(|(address, stake)| (address, Dec::from(stake) / total_stake))
+        .collect();
+
+    let threshold = params.concentration_penalty_threshold;
+    let rate = params.concentration_penalty_rate;
+    let mut total_penalty = Dec::zero();
+    let mut unpenalized_share = Dec::zero();
+    for (_address, share) in shares.iter() {
+        if *share > threshold {
+            total_penalty += rate * (*share - threshold);
+        } else {
+            unpenalized_share += *share;
+        }
+    }
+    if total_penalty.is_zero() || unpenalized_share.is_zero() {
+        return shares.into_iter().collect();
+    }
+
+    shares
+        .into_iter()
+        .map(|(address, share)| {
+            let frac = if share > threshold {
+                share - rate * (share - threshold)
+            } else {
+                share + total_penalty * share / unpenalized_share
+            };
+            (address, frac)
+        })
+        .collect()
+}
+
+/// Estimate the nominal annual rate of return of staking with the consensus
+/// validators in the given epoch, net of the validators' commissions. The
+/// estimate assumes that the last PoS inflation amount is minted in every
+/// epoch of the year and that the total consensus stake doesn't change.
+///
+/// The commission is the average commission rate of the consensus
+/// validators, weighted by their stake.
+pub fn estimate_staking_apr<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let gross_apr = estimate_gross_staking_apr(storage, &params, epoch)?;
+    if gross_apr.is_zero() {
+        return Ok(gross_apr);
+    }
+
+    let mut total_stake = Dec::zero();
+    let mut weighted_commission = Dec::zero();
+    for validator in
+        read_consensus_validator_set_addresses_with_stake(storage, epoch)?
+    {
+        let commission_rate =
+            read_commission_rate(storage, &params, &validator.address, epoch)?;
+        let stake = Dec::from(validator.bonded_stake);
+        weighted_commission += commission_rate * stake;
+        total_stake += stake;
+    }
+    if total_stake.is_zero() {
+        return Ok(Dec::zero());
+    }
+    let avg_commission_rate = weighted_commission / total_stake;
+    Ok(gross_apr * (Dec::one() - avg_commission_rate))
+}
+
+/// Estimate the nominal annual rate of return of staking with the given
+/// validator in the given epoch, net of its commission. Only consensus
+/// validators receive rewards, so the estimate is zero for a validator that
+/// is not in the consensus set. See [`estimate_staking_apr`] for the
+/// assumptions made in the estimate.
+pub fn estimate_validator_staking_apr<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let state =
+        validator_state_handle(validator).get(storage, epoch, &params)?;
+    if state != Some(ValidatorState::Consensus) {
+        return Ok(Dec::zero());
+    }
+    let gross_apr = estimate_gross_staking_apr(storage, &params, epoch)?;
+    let commission_rate =
+        read_commission_rate(storage, &params, validator, epoch)?;
+    Ok(gross_apr * (Dec::one() - commission_rate))
+}
+
+/// Estimate the annual rate of return of the consensus stake in the given
+/// epoch, before commissions.
+fn estimate_gross_staking_apr<S>(
+    storage: &S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let epochs_per_year: u64 = storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Epochs per year parameter should exist in storage")?;
+    let last_inflation_amount: Amount = storage
+        .read(&params_storage::get_pos_inflation_amount_key())?
+        .ok_or_err_msg("PoS inflation amount should exist in storage")?;
+    let total_consensus_stake = total_consensus_stake_key_handle()
+        .get(storage, epoch, params)?
+        .ok_or_err_msg("Total consensus stake should exist in storage")?;
+    if total_consensus_stake.is_zero() {
+        return Ok(Dec::zero());
+    }
+    let annual_inflation = Dec::from(last_inflation_amount)
+        .checked_mul(&Dec::from(epochs_per_year))
+        .ok_or_err_msg("Overflow while estimating the annual PoS inflation")?;
+    annual_inflation
+        .trunc_div(&Dec::from(total_consensus_stake))
+        .ok_or_err_msg("Overflow while estimating the staking APR")
+}
+
+/// Read a validator's commission rate in the given epoch.
+fn read_commission_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    validator_commission_rate_handle(validator)
+        .get(storage, epoch, params)?
+        .ok_or_err_msg("Validator commission rate should exist in storage")
+}
@@ -0,0 +1,67 @@
+//! Stake-weighted sampling of validators, to be used as a building block for
+//! the selection of committees or of validators assigned to some duty.
+
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::hash::Hash;
+use namada_core::types::storage::Epoch;
+use namada_core::types::uint::Uint;
+
+use crate::read_consensus_validator_set_addresses_with_stake;
+use crate::types::WeightedValidator;
+
+/// Sample up to `count` distinct consensus validators of the given epoch, each
+/// draw picking one of the remaining validators with a probability
+/// proportional to its bonded stake.
+///
+/// The sampling is deterministic: the randomness is derived from the `seed`,
+/// which should be a value agreed on chain (e.g. the hash of a committed
+/// block), such that every node obtains the same validators, in the same
+/// order. Fewer than `count` validators are returned if there are not enough
+/// consensus validators with a non-zero stake.
+pub fn sample_validators<S>(
+    storage: &S,
+    epoch: Epoch,
+    seed: &Hash,
+    count: usize,
+) -> storage_api::Result<Vec<WeightedValidator>>
+where
+    S: StorageRead,
+{
+    // The validators are ordered by their stake and address, so the
+    // candidates are in the same order on every node
+    let mut candidates: Vec<WeightedValidator> =
+        read_consensus_validator_set_addresses_with_stake(storage, epoch)?
+            .into_iter()
+            .filter(|validator| !validator.bonded_stake.is_zero())
+            .collect();
+    let mut total_stake = candidates
+        .iter()
+        .fold(Uint::zero(), |acc, v| acc + v.bonded_stake.raw_amount());
+
+    let mut sampled = Vec::with_capacity(count.min(candidates.len()));
+    for draw in 0..count as u64 {
+        if candidates.is_empty() {
+            break;
+        }
+        let point = sample_point(seed, draw) % total_stake;
+        let mut cumulative_stake = Uint::zero();
+        let index = candidates
+            .iter()
+            .position(|validator| {
+                cumulative_stake += validator.bonded_stake.raw_amount();
+                point < cumulative_stake
+            })
+            .expect("The sampled point must be below the total stake");
+        let validator = candidates.remove(index);
+        total_stake -= validator.bonded_stake.raw_amount();
+        sampled.push(validator);
+    }
+    Ok(sampled)
+}
+
+/// Derive the pseudo-random value of the given draw from the seed.
+fn sample_point(seed: &Hash, draw: u64) -> Uint {
+    let mut bytes = seed.0.to_vec();
+    bytes.extend_from_slice(&draw.to_be_bytes());
+    Uint::from_big_endian(&Hash::sha256(bytes).0)
+}
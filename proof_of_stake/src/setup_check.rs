@@ -0,0 +1,368 @@
+//! Pre-flight check of a validator's setup. New operators can use it to find
+//! out before the pipeline epoch whether their validator is set up to enter
+//! the consensus validator set and if not, why.
+
+use std::fmt::Display;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
+use namada_core::types::key::tm_consensus_key_raw_hash;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+
+use crate::types::{CommissionPair, ValidatorState};
+use crate::{
+    find_validator_by_raw_hash, is_validator, is_validator_frozen,
+    read_consensus_validator_set_addresses_with_stake,
+    validator_consensus_key_handle, validator_eth_cold_key_handle,
+    validator_eth_hot_key_handle, PosQueryCtx,
+};
+
+/// A problem with a validator's setup found by [`check_validator_setup`]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum ValidatorSetupIssue {
+    /// The address is not a validator
+    NotValidator,
+    /// The validator has no consensus key at the pipeline epoch
+    MissingConsensusKey,
+    /// The validator's consensus key is registered to another validator
+    ConsensusKeyNotUnique(Address),
+    /// The validator has no Ethereum hot key at the pipeline epoch
+    MissingEthHotKey,
+    /// The validator has no Ethereum cold key at the pipeline epoch
+    MissingEthColdKey,
+    /// The validator's stake at the pipeline epoch is below the validator
+    /// stake threshold
+    StakeBelowThreshold {
+        /// The validator's stake at the pipeline epoch
+        stake: token::Amount,
+        /// The validator stake threshold
+        threshold: token::Amount,
+    },
+    /// The validator's stake at the pipeline epoch is too low to enter the
+    /// full consensus validator set
+    StakeBelowConsensusSet {
+        /// The validator's stake at the pipeline epoch
+        stake: token::Amount,
+        /// The lowest stake in the consensus validator set at the pipeline
+        /// epoch
+        min_consensus_stake: token::Amount,
+    },
+    /// The validator has no commission rate at the pipeline epoch
+    MissingCommission,
+    /// The validator's commission rate is not within `[0, 1]`
+    InvalidCommissionRate(Dec),
+    /// The validator's max commission rate change is not within `[0, 1]`
+    InvalidMaxCommissionRateChange(Dec),
+    /// The validator is jailed at the pipeline epoch
+    Jailed,
+    /// The validator is deactivated at the pipeline epoch
+    Inactive,
+    /// The validator is frozen while its slashes are being processed
+    Frozen,
+}
+
+impl Display for ValidatorSetupIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotValidator => write!(f, "The address is not a validator"),
+            Self::MissingConsensusKey => {
+                write!(f, "The validator has no consensus key")
+            }
+            Self::ConsensusKeyNotUnique(owner) => write!(
+                f,
+                "The validator's consensus key is registered to validator \
+                 {owner}"
+            ),
+            Self::MissingEthHotKey => {
+                write!(f, "The validator has no Ethereum hot key")
+            }
+            Self::MissingEthColdKey => {
+                write!(f, "The validator has no Ethereum cold key")
+            }
+            Self::StakeBelowThreshold { stake, threshold } => write!(
+                f,
+                "The validator's stake {} is below the validator stake \
+                 threshold {}",
+                stake.to_string_native(),
+                threshold.to_string_native()
+            ),
+            Self::StakeBelowConsensusSet {
+                stake,
+                min_consensus_stake,
+            } => write!(
+                f,
+                "The validator's stake {} is not above the lowest stake {} in \
+                 the full consensus validator set",
+                stake.to_string_native(),
+                min_consensus_stake.to_string_native()
+            ),
+            Self::MissingCommission => {
+                write!(f, "The validator has no commission rate")
+            }
+            Self::InvalidCommissionRate(rate) => write!(
+                f,
+                "The validator's commission rate {rate} is not between 0 and 1"
+            ),
+            Self::InvalidMaxCommissionRateChange(change) => write!(
+                f,
+                "The validator's max commission rate change {change} is not \
+                 between 0 and 1"
+            ),
+            Self::Jailed => write!(f, "The validator is jailed"),
+            Self::Inactive => write!(f, "The validator is deactivated"),
+            Self::Frozen => write!(
+                f,
+                "The validator is frozen while its slashes are being processed"
+            ),
+        }
+    }
+}
+
+/// The report of a validator's setup made by [`check_validator_setup`]
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct ValidatorSetupReport {
+    /// The checked validator's address
+    pub validator: Address,
+    /// The epoch in which the check was done
+    pub current_epoch: Epoch,
+    /// The pipeline epoch at which the setup was checked
+    pub pipeline_epoch: Epoch,
+    /// The validator's state at the pipeline epoch
+    pub pipeline_state: Option<ValidatorState>,
+    /// The validator's stake at the pipeline epoch
+    pub pipeline_stake: token::Amount,
+    /// The lowest stake in the consensus validator set at the pipeline
+    /// epoch, if the set is full
+    pub min_consensus_stake: Option<token::Amount>,
+    /// The validator's commission at the pipeline epoch
+    pub commission: Option<CommissionPair>,
+    /// The problems found with the validator's setup
+    pub issues: Vec<ValidatorSetupIssue>,
+}
+
+impl ValidatorSetupReport {
+    /// Check if no problems were found with the validator's setup, i.e. the
+    /// validator will be in the consensus validator set at the pipeline
+    /// epoch, unless the validator sets change before then
+    pub fn is_ready(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check the setup of a validator at the pipeline epoch: that its consensus
+/// key is registered and unique, its Ethereum keys are present, its stake is
+/// high enough to enter the consensus validator set, its commission is sane
+/// and it's neither jailed, deactivated nor frozen.
+pub fn check_validator_setup<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<ValidatorSetupReport>
+where
+    S: StorageRead,
+{
+    let pos = PosQueryCtx::new(storage)?;
+    let params = pos.params();
+    let current_epoch = storage.get_block_epoch()?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let mut report = ValidatorSetupReport {
+        validator: validator.clone(),
+        current_epoch,
+        pipeline_epoch,
+        pipeline_state: None,
+        pipeline_stake: token::Amount::zero(),
+        min_consensus_stake: None,
+        commission: None,
+        issues: vec![],
+    };
+    if !is_validator(storage, validator)? {
+        report.issues.push(ValidatorSetupIssue::NotValidator);
+        return Ok(report);
+    }
+    let issues = &mut report.issues;
+
+    match validator_consensus_key_handle(validator).get(
+        storage,
+        pipeline_epoch,
+        params,
+    )? {
+        Some(consensus_key) => {
+            let raw_hash = tm_consensus_key_raw_hash(&consensus_key);
+            match find_validator_by_raw_hash(storage, raw_hash)? {
+                Some(owner) if &owner != validator => issues
+                    .push(ValidatorSetupIssue::ConsensusKeyNotUnique(owner)),
+                _ => {}
+            }
+        }
+        None => issues.push(ValidatorSetupIssue::MissingConsensusKey),
+    }
+    if validator_eth_hot_key_handle(validator)
+        .get(storage, pipeline_epoch, params)?
+        .is_none()
+    {
+        issues.push(ValidatorSetupIssue::MissingEthHotKey);
+    }
+    if validator_eth_cold_key_handle(validator)
+        .get(storage, pipeline_epoch, params)?
+        .is_none()
+    {
+        issues.push(ValidatorSetupIssue::MissingEthColdKey);
+    }
+
+    let stake = pos.read_validator_stake(validator, pipeline_epoch)?;
+    report.pipeline_stake = stake;
+    if stake < params.validator_stake_threshold {
+        issues.push(ValidatorSetupIssue::StakeBelowThreshold {
+            stake,
+            threshold: params.validator_stake_threshold,
+        });
+    }
+    let consensus_set = read_consensus_validator_set_addresses_with_stake(
+        storage,
+        pipeline_epoch,
+    )?;
+    // The set is ordered by the validators' stakes
+    if consensus_set.len() as u64 >= params.max_validator_slots {
+        report.min_consensus_stake = consensus_set
+            .first()
+            .map(|validator| validator.bonded_stake);
+    }
+
+    report.pipeline_state = pos.validator_state(validator, pipeline_epoch)?;
+    match report.pipeline_state {
+        Some(ValidatorState::BelowCapacity) => {
+            issues.push(ValidatorSetupIssue::StakeBelowConsensusSet {
+                stake,
+                min_consensus_stake: report
+                    .min_consensus_stake
+                    .unwrap_or_default(),
+            })
+        }
+        Some(ValidatorState::Jailed) => {
+            issues.push(ValidatorSetupIssue::Jailed)
+        }
+        Some(ValidatorState::Inactive) => {
+            issues.push(ValidatorSetupIssue::Inactive)
+        }
+        Some(ValidatorState::Consensus)
+        | Some(ValidatorState::BelowThreshold)
+        | None => {}
+    }
+    if is_validator_frozen(storage, validator, current_epoch, params)? {
+        issues.push(ValidatorSetupIssue::Frozen);
+    }
+
+    report.commission = pos.validator_commission(validator, pipeline_epoch)?;
+    match &report.commission {
+        Some(CommissionPair {
+            commission_rate,
+            max_commission_change_per_epoch,
+        }) => {
+            if !is_rate_sane(commission_rate) {
+                issues.push(ValidatorSetupIssue::InvalidCommissionRate(
+                    *commission_rate,
+                ));
+            }
+            if !is_rate_sane(max_commission_change_per_epoch) {
+                issues.push(
+                    ValidatorSetupIssue::InvalidMaxCommissionRateChange(
+                        *max_commission_change_per_epoch,
+                    ),
+                );
+            }
+        }
+        None => issues.push(ValidatorSetupIssue::MissingCommission),
+    }
+
+    Ok(report)
+}
+
+fn is_rate_sane(rate: &Dec) -> bool {
+    !rate.is_negative() && *rate <= Dec::one()
+}
+
+#[cfg(test)]
+mod test_setup_check {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+    use namada_core::types::key::testing::keypair_2;
+    use namada_core::types::key::{self, RefTo};
+
+    use super::*;
+    use crate::parameters::OwnedPosParams;
+    use crate::test_utils::test_init_genesis;
+    use crate::types::GenesisValidator;
+
+    /// Test the setup check of a genesis validator, a non-validator and a
+    /// validator with a stake below the threshold
+    #[test]
+    fn test_check_validator_setup() {
+        let mut s = TestWlStorage::default();
+        let params = OwnedPosParams::default();
+        let threshold = params.validator_stake_threshold;
+        let validator = established_address_1();
+        let low_stake_validator = established_address_2();
+        let genesis_validator =
+            |address: Address, tokens: token::Amount| GenesisValidator {
+                address,
+                tokens,
+                consensus_key: key::common::PublicKey::Ed25519(
+                    key::testing::gen_keypair::<key::ed25519::SigScheme>()
+                        .ref_to(),
+                ),
+                protocol_key: keypair_2().ref_to(),
+                eth_cold_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                eth_hot_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+                max_commission_rate_change: Dec::new(1, 1)
+                    .expect("Dec creation failed"),
+                metadata: Default::default(),
+            };
+        test_init_genesis(
+            &mut s,
+            params,
+            [
+                genesis_validator(
+                    validator.clone(),
+                    token::Amount::native_whole(1_000),
+                ),
+                genesis_validator(
+                    low_stake_validator.clone(),
+                    token::Amount::from_u64(1),
+                ),
+            ]
+            .into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+
+        let report = check_validator_setup(&s, &validator).unwrap();
+        assert!(report.is_ready(), "{:?}", report.issues);
+        assert_eq!(report.pipeline_state, Some(ValidatorState::Consensus));
+        assert_eq!(report.pipeline_stake, token::Amount::native_whole(1_000));
+
+        let non_validator = established_address_3();
+        let report = check_validator_setup(&s, &non_validator).unwrap();
+        assert_eq!(report.issues, vec![ValidatorSetupIssue::NotValidator]);
+
+        let report = check_validator_setup(&s, &low_stake_validator).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![ValidatorSetupIssue::StakeBelowThreshold {
+                stake: token::Amount::from_u64(1),
+                threshold,
+            }]
+        );
+    }
+}
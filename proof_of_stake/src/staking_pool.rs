@@ -0,0 +1,594 @@
+//! PoS staking pool. The pool is an internal account that accepts deposits of
+//! the staking token in exchange for pool shares and spreads its bonds across
+//! a list of validators set by governance. The pool's bonds are rebalanced and
+//! its rewards restaked at the beginning of every epoch. Redeemed shares are
+//! paid from the pool's idle tokens, if there are any, and otherwise become
+//! pooled unbonds that can be withdrawn once the pool's unbonds mature.
+
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet};
+
+use namada_core::ledger::storage_api::collections::LazyCollection;
+use namada_core::ledger::storage_api::{
+    self, token, StorageRead, StorageWrite,
+};
+use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token::Amount;
+
+use crate::storage::{
+    is_unbond_key, staking_pool_reserved_key, staking_pool_shares_key,
+    staking_pool_total_shares_key, staking_pool_unbonds_key,
+    staking_pool_validators_key, unbonds_for_source_prefix,
+};
+use crate::types::{
+    RedelegationEligibility, StakingPoolShares, StakingPoolUnbonds,
+    StakingPoolValidators,
+};
+use crate::{
+    bond_tokens, can_redelegate, claim_reward_tokens, find_delegations,
+    is_validator, is_validator_frozen, query_reward_tokens, read_pos_params,
+    redelegate_tokens, staking_token_address, unbond_tokens, withdraw_tokens,
+    ArithmeticError, StakingPoolError, STAKING_POOL_ADDRESS,
+};
+
+/// Get the storage handle to the validators that the staking pool delegates
+/// to
+pub fn staking_pool_validators_handle() -> StakingPoolValidators {
+    StakingPoolValidators::open(staking_pool_validators_key())
+}
+
+/// Get the storage handle to the staking pool shares of the depositors
+pub fn staking_pool_shares_handle() -> StakingPoolShares {
+    StakingPoolShares::open(staking_pool_shares_key())
+}
+
+/// Get the storage handle to the pooled unbonds of the depositors
+pub fn staking_pool_unbonds_handle() -> StakingPoolUnbonds {
+    StakingPoolUnbonds::open(staking_pool_unbonds_key())
+}
+
+/// Read the total number of staking pool shares
+pub fn read_staking_pool_total_shares<S>(
+    storage: &S,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead,
+{
+    let total = storage.read(&staking_pool_total_shares_key())?;
+    Ok(total.unwrap_or_default())
+}
+
+/// Read the amount of the staking pool's tokens that are owed to the pooled
+/// unbonds
+pub fn read_staking_pool_reserved<S>(storage: &S) -> storage_api::Result<Amount>
+where
+    S: StorageRead,
+{
+    let reserved = storage.read(&staking_pool_reserved_key())?;
+    Ok(reserved.unwrap_or_default())
+}
+
+/// Read the validators that the staking pool delegates to
+pub fn read_staking_pool_validators<S>(
+    storage: &S,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    S: StorageRead,
+{
+    staking_pool_validators_handle().iter(storage)?.collect()
+}
+
+/// Replace the validators that the staking pool delegates to. The pool's
+/// bonds are moved to the new validators by the next rebalancing.
+pub fn write_staking_pool_validators<S>(
+    storage: &mut S,
+    validators: &BTreeSet<Address>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let handle = staking_pool_validators_handle();
+    for validator in read_staking_pool_validators(storage)? {
+        if !validators.contains(&validator) {
+            handle.remove(storage, &validator)?;
+        }
+    }
+    for validator in validators {
+        handle.insert(storage, validator.clone())?;
+    }
+    Ok(())
+}
+
+/// Read the pooled unbonds of a depositor, keyed by the epoch from which they
+/// can be withdrawn
+pub fn read_staking_pool_unbonds<S>(
+    storage: &S,
+    owner: &Address,
+) -> storage_api::Result<BTreeMap<Epoch, Amount>>
+where
+    S: StorageRead,
+{
+    staking_pool_unbonds_handle()
+        .at(owner)
+        .iter(storage)?
+        .collect()
+}
+
+/// The value of the staking pool that backs its shares. This is the sum of
+/// the pool's idle tokens, its bonds at the pipeline epoch, its unbonding
+/// tokens and its unclaimed rewards, less the tokens that are owed to the
+/// pooled unbonds. The rewards are accounted for before they're claimed by
+/// the rebalancing, so that the share price doesn't jump at its epoch.
+pub fn staking_pool_value<S>(
+    storage: &S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let staking_token = staking_token_address(storage);
+
+    let mut value =
+        token::read_balance(storage, &staking_token, &STAKING_POOL_ADDRESS)?;
+    let delegations =
+        find_delegations(storage, &STAKING_POOL_ADDRESS, &pipeline_epoch)?;
+    for (validator, amount) in delegations {
+        let rewards = query_reward_tokens(
+            storage,
+            Some(&STAKING_POOL_ADDRESS),
+            &validator,
+            current_epoch,
+        )?;
+        value = value
+            .checked_add(amount)
+            .and_then(|value| value.checked_add(rewards))
+            .ok_or(ArithmeticError::Overflow("staking pool value"))?;
+    }
+    let unbonds = storage_api::iter_prefix::<Amount>(
+        storage,
+        &unbonds_for_source_prefix(&STAKING_POOL_ADDRESS),
+    )?;
+    for unbond in unbonds {
+        let (key, amount) = unbond?;
+        if is_unbond_key(&key).is_some() {
+            value = value
+                .checked_add(amount)
+                .ok_or(ArithmeticError::Overflow("staking pool value"))?;
+        }
+    }
+
+    let reserved = read_staking_pool_reserved(storage)?;
+    Ok(value.checked_sub(reserved).unwrap_or_default())
+}
+
+/// The value of a single staking pool share in the staking token. Returns
+/// `None` if no shares have been issued yet.
+pub fn staking_pool_share_price<S>(
+    storage: &S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Option<Dec>>
+where
+    S: StorageRead,
+{
+    let total_shares = read_staking_pool_total_shares(storage)?;
+    if total_shares.is_zero() {
+        return Ok(None);
+    }
+    let value = staking_pool_value(storage, current_epoch)?;
+    Ok(Dec::from(value).trunc_div(&Dec::from(total_shares)))
+}
+
+/// Deposit tokens of the `owner` into the staking pool in exchange for pool
+/// shares at the current share price. The deposited tokens are bonded by the
+/// next rebalancing. Returns the number of issued shares.
+pub fn deposit_into_staking_pool<S>(
+    storage: &mut S,
+    owner: &Address,
+    amount: Amount,
+    current_epoch: Epoch,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    if amount.is_zero() {
+        return Ok(Amount::zero());
+    }
+    let total_shares = read_staking_pool_total_shares(storage)?;
+    let value = staking_pool_value(storage, current_epoch)?;
+    // The first deposit, or a deposit into a pool that has lost all of its
+    // value, is issued shares 1:1
+    let shares = if total_shares.is_zero() || value.is_zero() {
+        amount
+    } else {
+        mul_div(amount, total_shares, value)?
+    };
+
+    let staking_token = staking_token_address(storage);
+    token::transfer(
+        storage,
+        &staking_token,
+        owner,
+        &STAKING_POOL_ADDRESS,
+        amount,
+    )?;
+
+    let handle = staking_pool_shares_handle();
+    let owner_shares = handle.get(storage, owner)?.unwrap_or_default();
+    let owner_shares = owner_shares
+        .checked_add(shares)
+        .ok_or(ArithmeticError::Overflow("staking pool shares"))?;
+    handle.insert(storage, owner.clone(), owner_shares)?;
+    let total_shares = total_shares
+        .checked_add(shares)
+        .ok_or(ArithmeticError::Overflow("staking pool shares"))?;
+    storage.write(&staking_pool_total_shares_key(), total_shares)?;
+
+    tracing::debug!(
+        "{owner} deposited {} into the staking pool for {} shares",
+        amount.to_string_native(),
+        shares.to_string_native()
+    );
+    Ok(shares)
+}
+
+/// Redeem staking pool shares of the `owner` at the current share price. The
+/// redeemed value is paid from the pool's idle tokens as far as possible and
+/// the rest is unbonded from the pool's validators into a pooled unbond of the
+/// owner. Returns the redeemed value.
+pub fn redeem_staking_pool_shares<S>(
+    storage: &mut S,
+    owner: &Address,
+    shares: Amount,
+    current_epoch: Epoch,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    if shares.is_zero() {
+        return Ok(Amount::zero());
+    }
+    let handle = staking_pool_shares_handle();
+    let owner_shares = handle.get(storage, owner)?.unwrap_or_default();
+    let remaining_shares =
+        owner_shares.checked_sub(shares).ok_or_else(|| {
+            StakingPoolError::InsufficientShares {
+                owner: owner.clone(),
+                shares: shares.to_string_native(),
+            }
+        })?;
+    let total_shares = read_staking_pool_total_shares(storage)?;
+    let value = mul_div(
+        shares,
+        staking_pool_value(storage, current_epoch)?,
+        total_shares,
+    )?;
+
+    // Burn the redeemed shares
+    if remaining_shares.is_zero() {
+        handle.remove(storage, owner)?;
+    } else {
+        handle.insert(storage, owner.clone(), remaining_shares)?;
+    }
+    let total_shares = total_shares
+        .checked_sub(shares)
+        .ok_or(ArithmeticError::Underflow("staking pool shares"))?;
+    storage.write(&staking_pool_total_shares_key(), total_shares)?;
+
+    // Pay as much as possible from the idle tokens
+    let staking_token = staking_token_address(storage);
+    let balance =
+        token::read_balance(storage, &staking_token, &STAKING_POOL_ADDRESS)?;
+    let reserved = read_staking_pool_reserved(storage)?;
+    let idle = balance.checked_sub(reserved).unwrap_or_default();
+    let paid = cmp::min(value, idle);
+    token::transfer(
+        storage,
+        &staking_token,
+        &STAKING_POOL_ADDRESS,
+        owner,
+        paid,
+    )?;
+
+    let remaining = value - paid;
+    if remaining.is_zero() {
+        return Ok(value);
+    }
+
+    // Unbond the rest, starting from the largest delegations
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let mut delegations: Vec<(Address, Amount)> =
+        find_delegations(storage, &STAKING_POOL_ADDRESS, &pipeline_epoch)?
+            .into_iter()
+            .collect();
+    delegations.sort_by(|(addr_a, amount_a), (addr_b, amount_b)| {
+        amount_b.cmp(amount_a).then_with(|| addr_a.cmp(addr_b))
+    });
+    let mut to_unbond = remaining;
+    for (validator, bonded) in delegations {
+        if to_unbond.is_zero() {
+            break;
+        }
+        if bonded.is_zero()
+            || is_validator_frozen(storage, &validator, current_epoch, &params)?
+        {
+            continue;
+        }
+        let amount = cmp::min(bonded, to_unbond);
+        unbond_tokens(
+            storage,
+            Some(&STAKING_POOL_ADDRESS),
+            &validator,
+            amount,
+            current_epoch,
+            false,
+        )?;
+        to_unbond = to_unbond - amount;
+    }
+    if !to_unbond.is_zero() {
+        return Err(StakingPoolError::InsufficientBonds(
+            value.to_string_native(),
+        )
+        .into());
+    }
+
+    // Record the pooled unbond, withdrawable together with the pool's unbonds
+    let withdrawable_epoch = current_epoch + params.withdrawable_epoch_offset();
+    let unbonds = staking_pool_unbonds_handle().at(owner);
+    let unbonded = unbonds
+        .get(storage, &withdrawable_epoch)?
+        .unwrap_or_default()
+        .checked_add(remaining)
+        .ok_or(ArithmeticError::Overflow("staking pool unbond"))?;
+    unbonds.insert(storage, withdrawable_epoch, unbonded)?;
+    let reserved = reserved
+        .checked_add(remaining)
+        .ok_or(ArithmeticError::Overflow("staking pool reserved amount"))?;
+    storage.write(&staking_pool_reserved_key(), reserved)?;
+
+    tracing::debug!(
+        "{owner} redeemed {} staking pool shares for {}, of which {} is \
+         withdrawable from epoch {withdrawable_epoch}",
+        shares.to_string_native(),
+        value.to_string_native(),
+        remaining.to_string_native()
+    );
+    Ok(value)
+}
+
+/// Withdraw the matured pooled unbonds of the `owner`. If the pool's unbonds
+/// were slashed, the payout is capped at the pool's balance. Returns the
+/// withdrawn amount.
+pub fn withdraw_from_staking_pool<S>(
+    storage: &mut S,
+    owner: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    withdraw_matured_pool_unbonds(storage, current_epoch)?;
+
+    let unbonds = staking_pool_unbonds_handle().at(owner);
+    let mut matured = Vec::new();
+    let mut claimed = Amount::zero();
+    for unbond in unbonds.iter(storage)? {
+        let (withdrawable_epoch, amount) = unbond?;
+        if withdrawable_epoch > current_epoch {
+            continue;
+        }
+        matured.push(withdrawable_epoch);
+        claimed = claimed
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow("staking pool withdrawal"))?;
+    }
+    if matured.is_empty() {
+        return Ok(Amount::zero());
+    }
+    for epoch in matured {
+        unbonds.remove(storage, &epoch)?;
+    }
+    let reserved = read_staking_pool_reserved(storage)?
+        .checked_sub(claimed)
+        .ok_or(ArithmeticError::Underflow("staking pool reserved amount"))?;
+    storage.write(&staking_pool_reserved_key(), reserved)?;
+
+    let staking_token = staking_token_address(storage);
+    let balance =
+        token::read_balance(storage, &staking_token, &STAKING_POOL_ADDRESS)?;
+    let paid = cmp::min(claimed, balance);
+    token::transfer(
+        storage,
+        &staking_token,
+        &STAKING_POOL_ADDRESS,
+        owner,
+        paid,
+    )?;
+    tracing::debug!(
+        "{owner} withdrew {} from the staking pool",
+        paid.to_string_native()
+    );
+    Ok(paid)
+}
+
+/// Rebalance the staking pool at the beginning of an epoch. The pool's matured
+/// unbonds are withdrawn and its rewards claimed, the bonds of validators that
+/// are no longer listed are redelegated to the listed validator with the
+/// lowest delegation from the pool and the idle tokens are bonded evenly
+/// across the listed validators. A validator for which any of these actions
+/// fails is logged and skipped, leaving its tokens to the next rebalancing.
+pub fn rebalance_staking_pool<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut validators = BTreeSet::new();
+    for validator in read_staking_pool_validators(storage)? {
+        if is_validator(storage, &validator)? {
+            validators.insert(validator);
+        }
+    }
+
+    withdraw_matured_pool_unbonds(storage, current_epoch)?;
+
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let delegations: BTreeMap<Address, Amount> =
+        find_delegations(storage, &STAKING_POOL_ADDRESS, &pipeline_epoch)?
+            .into_iter()
+            .collect();
+    for validator in delegations.keys() {
+        if let Err(err) = claim_reward_tokens(
+            storage,
+            Some(&STAKING_POOL_ADDRESS),
+            validator,
+            current_epoch,
+        ) {
+            tracing::error!(
+                "Failed to claim the staking pool's rewards from {validator}: \
+                 {err}"
+            );
+        }
+    }
+    if validators.is_empty() {
+        return Ok(());
+    }
+
+    // Move the bonds of the delisted validators
+    let mut stakes: BTreeMap<Address, Amount> = validators
+        .iter()
+        .map(|validator| {
+            let stake = delegations.get(validator).copied().unwrap_or_default();
+            (validator.clone(), stake)
+        })
+        .collect();
+    for (validator, amount) in delegations {
+        if validators.contains(&validator)
+            || amount.is_zero()
+            || is_validator_frozen(storage, &validator, current_epoch, &params)?
+            || can_redelegate(
+                storage,
+                &STAKING_POOL_ADDRESS,
+                &validator,
+                current_epoch,
+            )? != RedelegationEligibility::Eligible
+        {
+            continue;
+        }
+        let (dest_validator, stake) = stakes
+            .iter_mut()
+            .min_by_key(|(_, stake)| **stake)
+            .expect("The staking pool validators cannot be empty");
+        if let Err(err) = redelegate_tokens(
+            storage,
+            &STAKING_POOL_ADDRESS,
+            &validator,
+            dest_validator,
+            current_epoch,
+            amount,
+        ) {
+            tracing::error!(
+                "Failed to redelegate the staking pool's bond from \
+                 {validator} to {dest_validator}: {err}"
+            );
+            continue;
+        }
+        *stake = stake
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow("staking pool stake"))?;
+    }
+
+    // Bond the idle tokens
+    let staking_token = staking_token_address(storage);
+    let balance =
+        token::read_balance(storage, &staking_token, &STAKING_POOL_ADDRESS)?;
+    let reserved = read_staking_pool_reserved(storage)?;
+    let idle = balance.checked_sub(reserved).unwrap_or_default();
+    if idle.is_zero() {
+        return Ok(());
+    }
+    let num_validators = Amount::from(validators.len() as u64);
+    let per_validator = idle
+        .checked_div(num_validators)
+        .expect("The staking pool validators cannot be empty");
+    // The remainder of the division goes to the first validator
+    let mut remainder = idle
+        - per_validator
+            .checked_mul(num_validators)
+            .expect("The product cannot exceed the idle amount");
+    for validator in validators {
+        let amount = per_validator + remainder;
+        remainder = Amount::zero();
+        if amount.is_zero() {
+            continue;
+        }
+        if let Err(err) = bond_tokens(
+            storage,
+            Some(&STAKING_POOL_ADDRESS),
+            &validator,
+            amount,
+            current_epoch,
+            None,
+        ) {
+            tracing::error!(
+                "Failed to bond the staking pool's tokens to {validator}: \
+                 {err}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Withdraw the staking pool's unbonds that have matured by the current epoch.
+/// A validator from which the withdrawal fails is logged and skipped.
+fn withdraw_matured_pool_unbonds<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut validators = BTreeSet::new();
+    let unbonds = storage_api::iter_prefix::<Amount>(
+        storage,
+        &unbonds_for_source_prefix(&STAKING_POOL_ADDRESS),
+    )?;
+    for unbond in unbonds {
+        let (key, _amount) = unbond?;
+        if let Some((bond_id, _start, withdraw_epoch)) = is_unbond_key(&key) {
+            if withdraw_epoch <= current_epoch {
+                validators.insert(bond_id.validator);
+            }
+        }
+    }
+    for validator in validators {
+        if let Err(err) = withdraw_tokens(
+            storage,
+            Some(&STAKING_POOL_ADDRESS),
+            &validator,
+            current_epoch,
+        ) {
+            tracing::error!(
+                "Failed to withdraw the staking pool's unbonds from \
+                 {validator}: {err}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Compute `a * b / c`, rounded down
+fn mul_div(a: Amount, b: Amount, c: Amount) -> storage_api::Result<Amount> {
+    let product = a
+        .checked_mul(b)
+        .ok_or(ArithmeticError::Overflow("staking pool share amount"))?;
+    Ok(product
+        .checked_div(c)
+        .ok_or(ArithmeticError::Underflow("staking pool share amount"))?)
+}
@@ -0,0 +1,200 @@
+//! Recording of the changes of the validators' states. The state changes made
+//! by a PoS operation are recorded by running it on a [`StateChangeRecorder`]
+//! wrapping the storage, which observes the writes of the validators' states.
+//! The recorded changes are kept in memory, for the ledger to emit them as
+//! events at the end of the block.
+
+use borsh::BorshDeserialize;
+use namada_core::ledger::storage_api::{
+    self, ResultExt, StorageRead, StorageWrite,
+};
+use namada_core::types::address::Address;
+use namada_core::types::storage::{
+    self, BlockHash, BlockHeight, Epoch, Header, TxIndex,
+};
+
+use crate::parameters::PosParams;
+use crate::storage::is_validator_state_key;
+use crate::types::{
+    ValidatorState, ValidatorStateChange, ValidatorStateChangeCause,
+};
+use crate::{read_pos_params, validator_state_handle};
+
+/// Run a PoS operation on the given storage, recording the changes of the
+/// validators' states that it makes with the given cause
+pub fn with_state_changes<S, T, F>(
+    storage: &mut S,
+    cause: ValidatorStateChangeCause,
+    op: F,
+) -> storage_api::Result<(T, Vec<ValidatorStateChange>)>
+where
+    S: StorageRead + StorageWrite,
+    F: FnOnce(&mut StateChangeRecorder<'_, S>) -> storage_api::Result<T>,
+{
+    let mut recorder = StateChangeRecorder::new(storage, cause)?;
+    let res = op(&mut recorder)?;
+    Ok((res, recorder.changes))
+}
+
+/// A storage wrapper that records the changes of the validators' states
+#[derive(Debug)]
+pub struct StateChangeRecorder<'a, S> {
+    inner: &'a mut S,
+    params: PosParams,
+    current_epoch: Epoch,
+    cause: ValidatorStateChangeCause,
+    changes: Vec<ValidatorStateChange>,
+}
+
+impl<'a, S> StateChangeRecorder<'a, S>
+where
+    S: StorageRead,
+{
+    /// Wrap the storage to record the changes of the validators' states made
+    /// in the current epoch with the given cause
+    pub fn new(
+        inner: &'a mut S,
+        cause: ValidatorStateChangeCause,
+    ) -> storage_api::Result<Self> {
+        let params = read_pos_params(&*inner)?;
+        let current_epoch = inner.get_block_epoch()?;
+        Ok(Self {
+            inner,
+            params,
+            current_epoch,
+            cause,
+            changes: Vec::new(),
+        })
+    }
+
+    /// Find the change of a validator's state made by writing the given value
+    /// at the given epoch, if any. The writes to the past epochs are only ever
+    /// made by the pruning of the states and aren't changes.
+    fn find_change(
+        &self,
+        validator: &Address,
+        epoch: Epoch,
+        val: &[u8],
+    ) -> storage_api::Result<Option<ValidatorStateChange>> {
+        if epoch < self.current_epoch {
+            return Ok(None);
+        }
+        let to = ValidatorState::try_from_slice(val).into_storage_result()?;
+        let from = validator_state_handle(validator).get(
+            &*self.inner,
+            epoch,
+            &self.params,
+        )?;
+        if from == Some(to) {
+            return Ok(None);
+        }
+        Ok(Some(ValidatorStateChange {
+            validator: validator.clone(),
+            epoch,
+            from,
+            to,
+            cause: self.cause,
+        }))
+    }
+}
+
+impl<S> StorageRead for StateChangeRecorder<'_, S>
+where
+    S: StorageRead,
+{
+    type PrefixIter<'iter> = S::PrefixIter<'iter>
+    where
+        Self: 'iter;
+
+    fn read_bytes(
+        &self,
+        key: &storage::Key,
+    ) -> storage_api::Result<Option<Vec<u8>>> {
+        self.inner.read_bytes(key)
+    }
+
+    fn has_key(&self, key: &storage::Key) -> storage_api::Result<bool> {
+        self.inner.has_key(key)
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        self.inner.iter_prefix(prefix)
+    }
+
+    fn iter_prefix_from<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+        start: &storage::Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        self.inner.iter_prefix_from(prefix, start)
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        iter: &mut Self::PrefixIter<'iter>,
+    ) -> storage_api::Result<Option<(String, Vec<u8>)>> {
+        self.inner.iter_next(iter)
+    }
+
+    fn get_chain_id(&self) -> storage_api::Result<String> {
+        self.inner.get_chain_id()
+    }
+
+    fn get_block_height(&self) -> storage_api::Result<BlockHeight> {
+        self.inner.get_block_height()
+    }
+
+    fn get_block_header(
+        &self,
+        height: BlockHeight,
+    ) -> storage_api::Result<Option<Header>> {
+        self.inner.get_block_header(height)
+    }
+
+    fn get_block_hash(&self) -> storage_api::Result<BlockHash> {
+        self.inner.get_block_hash()
+    }
+
+    fn get_block_epoch(&self) -> storage_api::Result<Epoch> {
+        self.inner.get_block_epoch()
+    }
+
+    fn get_tx_index(&self) -> storage_api::Result<TxIndex> {
+        self.inner.get_tx_index()
+    }
+
+    fn get_native_token(&self) -> storage_api::Result<Address> {
+        self.inner.get_native_token()
+    }
+}
+
+impl<S> StorageWrite for StateChangeRecorder<'_, S>
+where
+    S: StorageRead + StorageWrite,
+{
+    fn write_bytes(
+        &mut self,
+        key: &storage::Key,
+        val: impl AsRef<[u8]>,
+    ) -> storage_api::Result<()> {
+        let val = val.as_ref();
+        let change = match is_validator_state_key(key) {
+            Some((validator, epoch)) => {
+                self.find_change(validator, epoch, val)?
+            }
+            None => None,
+        };
+        self.inner.write_bytes(key, val)?;
+        if let Some(change) = change {
+            self.changes.push(change);
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &storage::Key) -> storage_api::Result<()> {
+        self.inner.delete(key)
+    }
+}
@@ -16,9 +16,11 @@ const VALIDATOR_ADDRESS_RAW_HASH: &str = "address_raw_hash";
 const VALIDATOR_CONSENSUS_KEY_STORAGE_KEY: &str = "consensus_key";
 const VALIDATOR_ETH_COLD_KEY_STORAGE_KEY: &str = "eth_cold_key";
 const VALIDATOR_ETH_HOT_KEY_STORAGE_KEY: &str = "eth_hot_key";
+const VALIDATOR_OPERATOR_KEY_STORAGE_KEY: &str = "operator_key";
 const VALIDATOR_STATE_STORAGE_KEY: &str = "state";
 const VALIDATOR_DELTAS_STORAGE_KEY: &str = "deltas";
 const VALIDATOR_COMMISSION_RATE_STORAGE_KEY: &str = "commission_rate";
+const VALIDATOR_COMMISSION_HISTORY_STORAGE_KEY: &str = "commission_history";
 const VALIDATOR_MAX_COMMISSION_CHANGE_STORAGE_KEY: &str =
     "max_commission_rate_change";
 const VALIDATOR_REWARDS_PRODUCT_KEY: &str = "validator_rewards_product";
@@ -27,22 +29,40 @@ const VALIDATOR_LAST_KNOWN_PRODUCT_EPOCH_KEY: &str =
 const SLASHES_PREFIX: &str = "slash";
 const ENQUEUED_SLASHES_KEY: &str = "enqueued_slashes";
 const VALIDATOR_LAST_SLASH_EPOCH: &str = "last_slash_epoch";
+const VALIDATOR_CUMULATIVE_SLASH_RATE_KEY: &str = "cumulative_slash_rate";
 const BOND_STORAGE_KEY: &str = "bond";
+const BOND_AMOUNT_CHECKPOINT_STORAGE_KEY: &str = "bond_amount_checkpoint";
 const UNBOND_STORAGE_KEY: &str = "unbond";
 const VALIDATOR_TOTAL_BONDED_STORAGE_KEY: &str = "total_bonded";
 const VALIDATOR_TOTAL_UNBONDED_STORAGE_KEY: &str = "total_unbonded";
 const VALIDATOR_SETS_STORAGE_PREFIX: &str = "validator_sets";
 const CONSENSUS_VALIDATOR_SET_STORAGE_KEY: &str = "consensus";
 const BELOW_CAPACITY_VALIDATOR_SET_STORAGE_KEY: &str = "below_capacity";
+const BELOW_THRESHOLD_VALIDATOR_SET_STORAGE_KEY: &str = "below_threshold";
 const TOTAL_CONSENSUS_STAKE_STORAGE_KEY: &str = "total_consensus_stake";
 const TOTAL_DELTAS_STORAGE_KEY: &str = "total_deltas";
 const VALIDATOR_SET_POSITIONS_KEY: &str = "validator_set_positions";
 const CONSENSUS_KEYS: &str = "consensus_keys";
+const CONSENSUS_KEY_RESERVATIONS: &str = "consensus_key_reservations";
+const SCHEDULED_ACTIONS: &str = "scheduled_actions";
+const SLASH_PROCESSING_CURSOR_KEY: &str = "slash_processing_cursor";
+const IO_STATS_KEY: &str = "io_stats";
+const VALIDATOR_STATE_CHANGES_KEY: &str = "validator_state_changes";
+const ZERO_STAKE_VALIDATORS_KEY: &str = "zero_stake_validators";
+const STAKING_POOL_PREFIX: &str = "staking_pool";
+const STAKING_POOL_VALIDATORS_KEY: &str = "validators";
+const STAKING_POOL_SHARES_KEY: &str = "shares";
+const STAKING_POOL_TOTAL_SHARES_KEY: &str = "total_shares";
+const STAKING_POOL_RESERVED_KEY: &str = "reserved";
+const STAKING_POOL_UNBONDS_KEY: &str = "unbonds";
 const LAST_BLOCK_PROPOSER_STORAGE_KEY: &str = "last_block_proposer";
+const PROPOSER_HISTORY_STORAGE_KEY: &str = "proposer_history";
 const CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY: &str =
     "validator_rewards_accumulator";
 const LAST_REWARD_CLAIM_EPOCH: &str = "last_reward_claim_epoch";
 const REWARDS_COUNTER_KEY: &str = "validator_rewards_commissions";
+const INFLATION_AMOUNTS_KEY: &str = "inflation_amounts";
+const REWARDS_DUST_RECIPIENT_KEY: &str = "rewards_dust_recipient";
 const VALIDATOR_INCOMING_REDELEGATIONS_KEY: &str = "incoming_redelegations";
 const VALIDATOR_OUTGOING_REDELEGATIONS_KEY: &str = "outgoing_redelegations";
 const VALIDATOR_TOTAL_REDELEGATED_BONDED_KEY: &str = "total_redelegated_bonded";
@@ -55,8 +75,10 @@ const VALIDATOR_DESCRIPTION_KEY: &str = "description";
 const VALIDATOR_WEBSITE_KEY: &str = "website";
 const VALIDATOR_DISCORD_KEY: &str = "discord_handle";
 const LIVENESS_PREFIX: &str = "liveness";
+const VALIDATOR_SET_SNAPSHOT_KEY: &str = "validator_set_snapshot";
 const LIVENESS_MISSED_VOTES: &str = "missed_votes";
 const LIVENESS_MISSED_VOTES_SUM: &str = "sum_missed_votes";
+const LIVENESS_REPORTS: &str = "reports";
 
 /// Is the given key a PoS storage key?
 pub fn is_pos_key(key: &Key) -> bool {
@@ -79,7 +101,7 @@ pub fn is_params_key(key: &Key) -> bool {
 }
 
 /// Storage key prefix for validator data.
-fn validator_prefix(validator: &Address) -> Key {
+pub fn validator_prefix(validator: &Address) -> Key {
     Key::from(ADDRESS.to_db_key())
         .push(&VALIDATOR_STORAGE_PREFIX.to_owned())
         .expect("Cannot obtain a storage key")
@@ -187,6 +209,31 @@ pub fn is_validator_eth_hot_key_key(key: &Key) -> Option<&Address> {
     }
 }
 
+/// Storage key for validator's operator key.
+pub fn validator_operator_key_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_OPERATOR_KEY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for validator's operator key?
+pub fn is_validator_operator_key_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(validator),
+            DbKeySeg::StringSeg(key),
+        ] if addr == &ADDRESS
+            && prefix == VALIDATOR_STORAGE_PREFIX
+            && key == VALIDATOR_OPERATOR_KEY_STORAGE_KEY =>
+        {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for validator's commission rate.
 pub fn validator_commission_rate_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -221,6 +268,13 @@ pub fn is_validator_commission_rate_key(
     }
 }
 
+/// Storage key for validator's commission rate changes history.
+pub fn validator_commission_history_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_COMMISSION_HISTORY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key for validator's maximum commission rate change per epoch.
 pub fn validator_max_commission_rate_change_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -522,6 +576,13 @@ pub fn validator_last_slash_key(validator: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for the cumulative rates of a validator's processed slashes
+pub fn validator_cumulative_slash_rate_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_CUMULATIVE_SLASH_RATE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key prefix for all bonds.
 pub fn bonds_prefix() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -543,6 +604,18 @@ pub fn bond_key(bond_id: &BondId) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for the checkpoints of a bond amount with the given ID (source
+/// and validator).
+pub fn bond_amount_checkpoint_key(bond_id: &BondId) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BOND_AMOUNT_CHECKPOINT_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&bond_id.source.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&bond_id.validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Is storage key for a bond? Returns the bond ID and bond start epoch if so.
 pub fn is_bond_key(key: &Key) -> Option<(BondId, Epoch)> {
     if key.segments.len() >= 7 {
@@ -671,6 +744,13 @@ pub fn below_capacity_validator_set_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for below-threshold validator set
+pub fn below_threshold_validator_set_key() -> Key {
+    validator_sets_prefix()
+        .push(&BELOW_THRESHOLD_VALIDATOR_SET_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Is storage key for the consensus validator set?
 pub fn is_consensus_validator_set_key(key: &Key) -> bool {
     matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key), DbKeySeg::StringSeg(set_type), DbKeySeg::StringSeg(lazy_map), DbKeySeg::StringSeg(data), DbKeySeg::StringSeg(_epoch), DbKeySeg::StringSeg(_), DbKeySeg::StringSeg(_amount), DbKeySeg::StringSeg(_), DbKeySeg::StringSeg(_position)] if addr == &ADDRESS && key == VALIDATOR_SETS_STORAGE_PREFIX && set_type == CONSENSUS_VALIDATOR_SET_STORAGE_KEY && lazy_map == LAZY_MAP_SUB_KEY && data == lazy_map::DATA_SUBKEY)
@@ -735,6 +815,13 @@ pub fn is_last_block_proposer_key(key: &Key) -> bool {
     matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == LAST_BLOCK_PROPOSER_STORAGE_KEY)
 }
 
+/// Storage key for the ring buffer of the proposers of the last blocks.
+pub fn proposer_history_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PROPOSER_HISTORY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key for the consensus validator set rewards accumulator.
 pub fn consensus_validator_rewards_accumulator_key() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -751,6 +838,26 @@ pub fn is_consensus_validator_set_accumulator_key(key: &Key) -> bool {
             && key == CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY)
 }
 
+/// Storage key for the PoS inflation amounts applied in each epoch.
+pub fn inflation_amounts_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&INFLATION_AMOUNTS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the recipient of the inflation tokens left-over from the
+/// rounding of the rewards.
+pub fn rewards_dust_recipient_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&REWARDS_DUST_RECIPIENT_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the recipient of the rewards dust?
+pub fn is_rewards_dust_recipient_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == REWARDS_DUST_RECIPIENT_KEY)
+}
+
 /// Storage prefix for epoch at which an account last claimed PoS inflationary
 /// rewards.
 pub fn last_pos_reward_claim_epoch_prefix() -> Key {
@@ -772,6 +879,24 @@ pub fn last_pos_reward_claim_epoch_key(
         .expect("Cannot obtain a storage key")
 }
 
+/// Is storage key for epoch at which an account last claimed PoS inflationary
+/// rewards? Returns the delegator and validator addresses if it is.
+pub fn is_last_pos_reward_claim_epoch_key(
+    key: &Key,
+) -> Option<(&Address, &Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(delegator),
+            DbKeySeg::AddressSeg(validator),
+        ] if addr == &ADDRESS && prefix == LAST_REWARD_CLAIM_EPOCH => {
+            Some((delegator, validator))
+        }
+        _ => None,
+    }
+}
+
 /// Get validator address from bond key
 pub fn get_validator_address_from_bond(key: &Key) -> Option<Address> {
     match key.get_at(3) {
@@ -802,6 +927,150 @@ pub fn is_consensus_keys_key(key: &Key) -> bool {
     matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == CONSENSUS_KEYS)
 }
 
+/// Storage key for the consensus keys reservations.
+pub fn consensus_key_reservations_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&CONSENSUS_KEY_RESERVATIONS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the actions scheduled at epoch transitions.
+pub fn scheduled_actions_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&SCHEDULED_ACTIONS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the progress of the slashes processed over several blocks.
+pub fn slash_processing_cursor_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&SLASH_PROCESSING_CURSOR_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the validators that have had no stake since some epoch.
+pub fn zero_stake_validators_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&ZERO_STAKE_VALIDATORS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the storage I/O of the PoS operations of a tx. The stats
+/// are written to temporary storage, so they're never persisted.
+pub fn io_stats_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&IO_STATS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the validator state changes made by a tx. The changes are
+/// written to temporary storage, so they're never persisted.
+pub fn validator_state_changes_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VALIDATOR_STATE_CHANGES_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key prefix for the staking pool's data.
+fn staking_pool_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&STAKING_POOL_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the staking pool's data?
+pub fn is_staking_pool_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), ..] if addr == &ADDRESS && prefix == STAKING_POOL_PREFIX)
+}
+
+/// Storage key for the set of validators that the staking pool delegates to.
+pub fn staking_pool_validators_key() -> Key {
+    staking_pool_prefix()
+        .push(&STAKING_POOL_VALIDATORS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the set of validators that the staking pool delegates
+/// to?
+pub fn is_staking_pool_validators_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(key), ..] if addr == &ADDRESS && prefix == STAKING_POOL_PREFIX && key == STAKING_POOL_VALIDATORS_KEY)
+}
+
+/// Storage key for the staking pool shares of the pool's depositors.
+pub fn staking_pool_shares_key() -> Key {
+    staking_pool_prefix()
+        .push(&STAKING_POOL_SHARES_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the staking pool shares of a depositor? Returns the
+/// depositor's address if it is.
+pub fn is_staking_pool_shares_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(key),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(owner),
+        ] if addr == &ADDRESS
+            && prefix == STAKING_POOL_PREFIX
+            && key == STAKING_POOL_SHARES_KEY
+            && data == lazy_map::DATA_SUBKEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Storage key for the total number of staking pool shares.
+pub fn staking_pool_total_shares_key() -> Key {
+    staking_pool_prefix()
+        .push(&STAKING_POOL_TOTAL_SHARES_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the amount of the staking pool's tokens that are owed to
+/// the pooled unbonds.
+pub fn staking_pool_reserved_key() -> Key {
+    staking_pool_prefix()
+        .push(&STAKING_POOL_RESERVED_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the pooled unbonds of the staking pool's depositors.
+pub fn staking_pool_unbonds_key() -> Key {
+    staking_pool_prefix()
+        .push(&STAKING_POOL_UNBONDS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for a pooled unbond? Returns the depositor's address and
+/// the withdrawable epoch if it is.
+pub fn is_staking_pool_unbond_key(key: &Key) -> Option<(&Address, Epoch)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(key),
+            DbKeySeg::StringSeg(data_1),
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(data_2),
+            DbKeySeg::StringSeg(epoch),
+        ] if addr == &ADDRESS
+            && prefix == STAKING_POOL_PREFIX
+            && key == STAKING_POOL_UNBONDS_KEY
+            && data_1 == lazy_map::DATA_SUBKEY
+            && data_2 == lazy_map::DATA_SUBKEY =>
+        {
+            let epoch = Epoch::parse(epoch.clone()).ok()?;
+            Some((owner, epoch))
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for a validator's email
 pub fn validator_email_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -850,3 +1119,35 @@ pub fn liveness_sum_missed_votes_key() -> Key {
         .push(&LIVENESS_MISSED_VOTES_SUM.to_owned())
         .expect("Cannot obtain a storage key")
 }
+
+/// Storage key for the liveness reports received from vote extensions.
+pub fn liveness_reports_key() -> Key {
+    liveness_data_prefix()
+        .push(&LIVENESS_REPORTS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the snapshot of the consensus validator set of the given
+/// epoch.
+pub fn validator_set_snapshot_key(epoch: Epoch) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VALIDATOR_SET_SNAPSHOT_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&epoch)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the snapshot of the consensus validator set of an
+/// epoch? Returns the epoch of the snapshot if so.
+pub fn is_validator_set_snapshot_key(key: &Key) -> Option<Epoch> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(epoch),
+        ] if addr == &ADDRESS && prefix == VALIDATOR_SET_SNAPSHOT_KEY => {
+            Epoch::parse(epoch.clone()).ok()
+        }
+        _ => None,
+    }
+}
@@ -4,31 +4,40 @@ mod state_machine;
 mod state_machine_v2;
 mod utils;
 
+use std::cell::Cell;
 use std::cmp::{max, min};
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::ops::{Deref, Range};
 use std::str::FromStr;
 
 use assert_matches::assert_matches;
+use namada_core::ledger::parameters::storage as params_storage;
 use namada_core::ledger::storage::testing::TestWlStorage;
+use namada_core::ledger::storage::TempWlStorage;
 use namada_core::ledger::storage_api::collections::lazy_map::{
     self, Collectable, NestedMap,
 };
 use namada_core::ledger::storage_api::collections::LazyCollection;
 use namada_core::ledger::storage_api::token::{credit_tokens, read_balance};
-use namada_core::ledger::storage_api::StorageRead;
+use namada_core::ledger::storage_api::{
+    self, vesting, StorageRead, StorageWrite,
+};
+use namada_core::ledger::vesting::VestingSchedule;
 use namada_core::types::address::testing::{
     address_from_simple_seed, arb_established_address, established_address_1,
     established_address_2, established_address_3,
 };
 use namada_core::types::address::{Address, EstablishedAddressGen};
 use namada_core::types::dec::Dec;
+use namada_core::types::hash::Hash;
 use namada_core::types::key::common::{PublicKey, SecretKey};
 use namada_core::types::key::testing::{
     arb_common_keypair, common_sk_from_simple_seed, gen_keypair,
 };
 use namada_core::types::key::RefTo;
-use namada_core::types::storage::{BlockHeight, Epoch, Key};
+use namada_core::types::storage::{
+    BlockHash, BlockHeight, Epoch, Header, Key, TxIndex,
+};
 use namada_core::types::token::testing::arb_amount_non_zero_ceiled;
 use namada_core::types::token::NATIVE_MAX_DECIMAL_PLACES;
 use namada_core::types::{address, key, token};
@@ -38,47 +47,83 @@ use proptest::test_runner::Config;
 // `tracing` logs from tests
 use test_log::test;
 
-use crate::epoched::DEFAULT_NUM_PAST_EPOCHS;
-use crate::parameters::testing::arb_pos_params;
+use crate::epoched::{
+    EpochOffset, OffsetMaxProposalPeriodOrSlashProcessingLenPlus,
+};
+use crate::parameters::testing::{arb_pos_params, arb_rate};
 use crate::parameters::{OwnedPosParams, PosParams};
-use crate::rewards::PosRewardsCalculator;
+use crate::pos_queries::{
+    Error as PosQueriesError, PosQueries, ProtocolKeyCache,
+};
+use crate::rewards::{
+    consensus_reward_fractions, estimate_staking_apr,
+    estimate_validator_staking_apr, PosRewardsCalculator,
+};
+use crate::sampling::sample_validators;
+use crate::state_changes::with_state_changes;
 use crate::test_utils::test_init_genesis;
 use crate::types::{
-    into_tm_voting_power, BondDetails, BondId, BondsAndUnbondsDetails,
-    ConsensusValidator, EagerRedelegatedBondsMap, GenesisValidator, Position,
-    RedelegatedTokens, ReverseOrdTokenAmount, Slash, SlashType, UnbondDetails,
-    ValidatorSetUpdate, ValidatorState, VoteInfo, WeightedValidator,
+    into_tm_voting_power, BondAction, BondDetails, BondId,
+    BondsAndUnbondsDetails, CommissionRateChange, ConsensusValidator,
+    EagerRedelegatedBondsMap, GenesisValidator, Position, QueuedUnbond,
+    RedelegatedTokens, RedelegationEligibility, ReverseOrdTokenAmount, Slash,
+    SlashFilter, SlashProcessingStatus, SlashRecord, SlashStatus, SlashType,
+    UnbondDetails, ValidatorKeys, ValidatorSetDiff, ValidatorSetUpdate,
+    ValidatorState, ValidatorStateChange, ValidatorStateChangeCause,
+    ValidatorStateTransition, VoteInfo, WeightedValidator,
 };
+use crate::validator_set_cache::ConsensusValidatorSetCache;
 use crate::{
-    apply_list_slashes, become_validator, below_capacity_validator_set_handle,
-    bond_handle, bond_tokens, bonds_and_unbonds, change_consensus_key,
-    compute_amount_after_slashing_unbond,
+    apply_list_slashes, apply_liveness_reports, become_validator,
+    below_capacity_validator_set_handle, bond_amount_checkpoint_handle,
+    bond_handle, bond_tokens, bonds_and_unbonds, can_redelegate,
+    change_consensus_key, change_validator_commission_rate,
+    change_validator_eth_keys, checked_amount_add, checked_amount_sub,
+    checked_change_add, compute_amount_after_slashing_unbond,
     compute_amount_after_slashing_withdraw,
     compute_and_store_total_consensus_stake, compute_bond_at_epoch,
     compute_modified_redelegation, compute_new_redelegated_unbonds,
     compute_slash_bond_at_epoch, compute_slashable_amount,
+    compute_validator_set_diff, consensus_key_reservations_handle,
     consensus_validator_set_handle, copy_validator_sets_and_positions,
     delegator_redelegated_bonds_handle, delegator_redelegated_unbonds_handle,
-    find_bonds_to_remove, find_validator_by_raw_hash,
-    fold_and_slash_redelegated_bonds, get_consensus_key_set,
-    get_num_consensus_validators, insert_validator_into_validator_set,
-    is_validator, process_slashes,
+    enqueued_slashes_handle, find_all_slashes, find_bonds_to_remove,
+    find_consensus_key_reservation, find_validator_by_raw_hash,
+    find_withdrawable_unbonds, fold_and_slash_redelegated_bonds,
+    get_consensus_key_set, get_num_consensus_validators,
+    get_total_consensus_stake, insert_validator_into_validator_set,
+    is_consensus_key_used, is_slash_processing_in_progress, is_validator,
+    is_validator_frozen, iter_slashes, liveness_missed_votes_handle,
+    liveness_reports_handle, liveness_sum_missed_votes_handle,
+    process_scheduled_actions, process_slashes, process_slashes_chunk,
+    read_all_validator_keys,
     read_below_capacity_validator_set_addresses_with_stake,
-    read_below_threshold_validator_set_addresses,
-    read_consensus_validator_set_addresses_with_stake, read_total_stake,
-    read_validator_deltas_value, read_validator_stake, slash,
-    slash_redelegation, slash_validator, slash_validator_redelegation,
-    staking_token_address, total_bonded_handle, total_deltas_handle,
+    read_below_threshold_validator_set_addresses, read_bonded_ratio,
+    read_commission_history, read_commission_rate_change_in_epoch,
+    read_consensus_validator_set_addresses,
+    read_consensus_validator_set_addresses_with_stake, read_inflation_amount,
+    read_rewards_dust_recipient, read_total_stake, read_total_stake_range,
+    read_validator_cumulative_slash, read_validator_deltas_value,
+    read_validator_set_snapshot, read_validator_stake,
+    read_validator_stake_range, rebalance_validator_sets,
+    reconcile_rewards_dust, record_liveness_report, redelegate_tokens,
+    reserve_consensus_key, schedule_withdrawal, scheduled_actions_handle,
+    simulate_bond_action, simulate_slash, slash, slash_redelegation,
+    slash_validator, slash_validator_redelegation, staking_token_address,
+    store_validator_set_snapshot, total_bonded_handle, total_deltas_handle,
     total_unbonded_handle, unbond_handle, unbond_tokens, unjail_validator,
     update_validator_deltas, update_validator_set,
-    validator_consensus_key_handle, validator_incoming_redelegations_handle,
+    validator_commission_rate_handle, validator_consensus_key_handle,
+    validator_incoming_redelegations_handle,
     validator_outgoing_redelegations_handle, validator_set_positions_handle,
     validator_set_update_tendermint, validator_slashes_handle,
     validator_state_handle, validator_total_redelegated_bonded_handle,
     validator_total_redelegated_unbonded_handle, withdraw_tokens,
-    write_pos_params, write_validator_address_raw_hash, BecomeValidator,
+    write_pos_params, write_rewards_dust_recipient,
+    write_validator_address_raw_hash, ArithmeticError, BecomeValidator,
+    CommissionRateChangeError, ConsensusKeyReservationError,
     EagerRedelegatedUnbonds, FoldRedelegatedBondsResult, ModifiedRedelegation,
-    RedelegationError,
+    RedelegationError, SlashError, UnbondError,
 };
 
 proptest! {
@@ -231,6 +276,38 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_simulate_slash_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_simulate_slash(
+
+    genesis_validators in arb_genesis_validators(4..5, None),
+
+    ) {
+        test_simulate_slash_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_find_withdrawable_unbonds_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_find_withdrawable_unbonds(
+
+    genesis_validators in arb_genesis_validators(4..5, None),
+
+    ) {
+        test_find_withdrawable_unbonds_aux(genesis_validators)
+    }
+}
+
 proptest! {
     // Generate arb valid input for `test_unslashed_bond_amount_aux`
     #![proptest_config(Config {
@@ -279,6 +356,38 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_log_vext_rewards_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_log_vext_rewards(
+
+    genesis_validators in arb_genesis_validators(2..5, None),
+
+    ) {
+        test_log_vext_rewards_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_concentration_penalty_aux`
+    #![proptest_config(Config {
+        cases: 100,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_concentration_penalty(
+        stakes in prop::collection::vec(0..1_000_000_u64, 1..20),
+        threshold in arb_rate(),
+        rate in arb_rate(),
+    ) {
+        test_concentration_penalty_aux(stakes, threshold, rate)
+    }
+}
+
 proptest! {
     // Generate arb valid input for `test_update_rewards_products_aux`
     #![proptest_config(Config {
@@ -294,6 +403,21 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_rewards_dust_reconciliation_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_rewards_dust_reconciliation(
+        genesis_validators in arb_genesis_validators(1..10, None),
+
+    ) {
+        test_rewards_dust_reconciliation_aux(genesis_validators)
+    }
+}
+
 proptest! {
     // Generate arb valid input for `test_consensus_key_change`
     #![proptest_config(Config {
@@ -326,891 +450,513 @@ proptest! {
     }
 }
 
-fn arb_params_and_genesis_validators(
-    num_max_validator_slots: Option<u64>,
-    val_size: Range<usize>,
-) -> impl Strategy<Value = (OwnedPosParams, Vec<GenesisValidator>)> {
-    let params = arb_pos_params(num_max_validator_slots);
-    params.prop_flat_map(move |params| {
-        let validators = arb_genesis_validators(
-            val_size.clone(),
-            Some(params.validator_stake_threshold),
-        );
-        (Just(params), validators)
-    })
+proptest! {
+    // Generate arb valid input for `test_purge_old_epoched_data`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_purge_old_epoched_data(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_purge_old_epoched_data_aux(genesis_validators)
+    }
 }
 
-fn test_slashes_with_unbonding_params()
--> impl Strategy<Value = (OwnedPosParams, Vec<GenesisValidator>, u64)> {
-    let params = arb_pos_params(Some(5));
-    params.prop_flat_map(|params| {
-        let unbond_delay = 0..(params.slash_processing_epoch_offset() * 2);
-        // Must have at least 4 validators so we can slash one and the cubic
-        // slash rate will be less than 100%
-        let validators = arb_genesis_validators(4..10, None);
-        (Just(params), validators, unbond_delay)
-    })
+proptest! {
+    // Generate arb valid input for `test_compute_validator_set_diff`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_compute_validator_set_diff(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_compute_validator_set_diff_aux(genesis_validators)
+    }
 }
 
-/// Test genesis initialization
-fn test_test_init_genesis_aux(
-    params: OwnedPosParams,
-    start_epoch: Epoch,
-    mut validators: Vec<GenesisValidator>,
-) {
-    println!(
-        "Test inputs: {params:?}, {start_epoch}, genesis validators: \
-         {validators:#?}"
-    );
-    let mut s = TestWlStorage::default();
-    s.storage.block.epoch = start_epoch;
+proptest! {
+    // Generate arb valid input for `test_rebalance_validator_sets`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_rebalance_validator_sets(
 
-    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
-    let params = test_init_genesis(
-        &mut s,
-        params,
-        validators.clone().into_iter(),
-        start_epoch,
-    )
-    .unwrap();
+    genesis_validators in arb_genesis_validators(4..10, None),
 
-    let mut bond_details = bonds_and_unbonds(&s, None, None).unwrap();
-    assert!(bond_details.iter().all(|(_id, details)| {
-        details.unbonds.is_empty() && details.slashes.is_empty()
-    }));
+    ) {
+        test_rebalance_validator_sets_aux(genesis_validators)
+    }
+}
 
-    for (i, validator) in validators.into_iter().enumerate() {
-        let addr = &validator.address;
-        let self_bonds = bond_details
-            .remove(&BondId {
-                source: addr.clone(),
-                validator: addr.clone(),
-            })
-            .unwrap();
-        assert_eq!(self_bonds.bonds.len(), 1);
-        assert_eq!(
-            self_bonds.bonds[0],
-            BondDetails {
-                start: start_epoch,
-                amount: validator.tokens,
-                slashed_amount: None,
-            }
-        );
+proptest! {
+    // Generate arb valid input for `test_estimate_staking_apr`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_estimate_staking_apr(
 
-        let state = validator_state_handle(&validator.address)
-            .get(&s, start_epoch, &params)
-            .unwrap();
-        if (i as u64) < params.max_validator_slots
-            && validator.tokens >= params.validator_stake_threshold
-        {
-            // should be in consensus set
-            let handle = consensus_validator_set_handle().at(&start_epoch);
-            assert!(handle.at(&validator.tokens).iter(&s).unwrap().any(
-                |result| {
-                    let (_pos, addr) = result.unwrap();
-                    addr == validator.address
-                }
-            ));
-            assert_eq!(state, Some(ValidatorState::Consensus));
-        } else if validator.tokens >= params.validator_stake_threshold {
-            // Should be in below-capacity set if its tokens are greater than
-            // `validator_stake_threshold`
-            let handle = below_capacity_validator_set_handle().at(&start_epoch);
-            assert!(handle.at(&validator.tokens.into()).iter(&s).unwrap().any(
-                |result| {
-                    let (_pos, addr) = result.unwrap();
-                    addr == validator.address
-                }
-            ));
-            assert_eq!(state, Some(ValidatorState::BelowCapacity));
-        } else {
-            // Should be in below-threshold
-            let bt_addresses =
-                read_below_threshold_validator_set_addresses(&s, start_epoch)
-                    .unwrap();
-            assert!(
-                bt_addresses
-                    .into_iter()
-                    .any(|addr| { addr == validator.address })
-            );
-            assert_eq!(state, Some(ValidatorState::BelowThreshold));
-        }
+    genesis_validators in arb_genesis_validators(2..3, None),
+
+    ) {
+        test_estimate_staking_apr_aux(genesis_validators)
     }
 }
 
-/// Test bonding
-/// NOTE: copy validator sets each time we advance the epoch
-fn test_bonds_aux(params: OwnedPosParams, validators: Vec<GenesisValidator>) {
-    // This can be useful for debugging:
-    // params.pipeline_len = 2;
-    // params.unbonding_len = 4;
-    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
-    let mut s = TestWlStorage::default();
+proptest! {
+    // Generate arb valid input for `test_bond_amount_query_cost`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_bond_amount_query_cost(
 
-    // Genesis
-    let start_epoch = s.storage.block.epoch;
-    let mut current_epoch = s.storage.block.epoch;
-    let params = test_init_genesis(
-        &mut s,
-        params,
-        validators.clone().into_iter(),
-        current_epoch,
-    )
-    .unwrap();
-    s.commit_block().unwrap();
+    genesis_validators in arb_genesis_validators(1..2, None),
 
-    // Advance to epoch 1
-    current_epoch = advance_epoch(&mut s, &params);
-    let self_bond_epoch = current_epoch;
+    ) {
+        test_bond_amount_query_cost_aux(genesis_validators)
+    }
+}
 
-    let validator = validators.first().unwrap();
+proptest! {
+    // Generate arb valid input for `test_read_all_validator_keys`
+    #![proptest_config(Config {
+        cases: 5,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_read_all_validator_keys(
 
-    // Read some data before submitting bond
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-    let staking_token = staking_token_address(&s);
-    let pos_balance_pre = s
-        .read::<token::Amount>(&token::balance_key(
-            &staking_token,
-            &super::ADDRESS,
-        ))
-        .unwrap()
-        .unwrap_or_default();
-    let total_stake_before =
-        read_total_stake(&s, &params, pipeline_epoch).unwrap();
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Self-bond
-    let amount_self_bond = token::Amount::from_uint(100_500_000, 0).unwrap();
-    credit_tokens(&mut s, &staking_token, &validator.address, amount_self_bond)
-        .unwrap();
-    bond_tokens(
-        &mut s,
-        None,
-        &validator.address,
-        amount_self_bond,
-        current_epoch,
-        None,
-    )
-    .unwrap();
+    ) {
+        test_read_all_validator_keys_aux(genesis_validators)
+    }
+}
 
-    // Check the bond delta
-    let self_bond = bond_handle(&validator.address, &validator.address);
-    let delta = self_bond.get_delta_val(&s, pipeline_epoch).unwrap();
-    assert_eq!(delta, Some(amount_self_bond));
+proptest! {
+    // Generate arb valid input for `test_validator_set_snapshot_aux`
+    #![proptest_config(Config {
+        cases: 5,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_validator_set_snapshot(
 
-    // Check the validator in the validator set
-    let set =
-        read_consensus_validator_set_addresses_with_stake(&s, pipeline_epoch)
-            .unwrap();
-    assert!(set.into_iter().any(
-        |WeightedValidator {
-             bonded_stake,
-             address,
-         }| {
-            address == validator.address
-                && bonded_stake == validator.tokens + amount_self_bond
-        }
-    ));
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    let val_deltas =
-        read_validator_deltas_value(&s, &validator.address, &pipeline_epoch)
-            .unwrap();
-    assert_eq!(val_deltas, Some(amount_self_bond.change()));
+    ) {
+        test_validator_set_snapshot_aux(genesis_validators)
+    }
+}
 
-    let total_deltas_handle = total_deltas_handle();
-    assert_eq!(
-        current_epoch,
-        total_deltas_handle.get_last_update(&s).unwrap().unwrap()
-    );
-    let total_stake_after =
-        read_total_stake(&s, &params, pipeline_epoch).unwrap();
-    assert_eq!(total_stake_before + amount_self_bond, total_stake_after);
+proptest! {
+    // Generate arb valid input for `test_validator_sets_retention_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_validator_sets_retention(
 
-    // Check bond details after self-bond
-    let self_bond_id = BondId {
-        source: validator.address.clone(),
-        validator: validator.address.clone(),
-    };
-    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
-        println!("Check index {ix}");
-        let details = bond_details.get(&self_bond_id).unwrap();
-        assert_eq!(
-            details.bonds.len(),
-            2,
-            "Contains genesis and newly added self-bond"
-        );
-        dbg!(&details.bonds);
-        assert_eq!(
-            details.bonds[0],
-            BondDetails {
-                start: start_epoch,
-                amount: validator.tokens,
-                slashed_amount: None
-            },
-        );
-        assert_eq!(
-            details.bonds[1],
-            BondDetails {
-                start: pipeline_epoch,
-                amount: amount_self_bond,
-                slashed_amount: None
-            },
-        );
-    };
-    // Try to call it with different combinations of owner/validator args
-    check_bond_details(0, bonds_and_unbonds(&s, None, None).unwrap());
-    check_bond_details(
-        1,
-        bonds_and_unbonds(&s, Some(validator.address.clone()), None).unwrap(),
-    );
-    check_bond_details(
-        2,
-        bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
-    );
-    check_bond_details(
-        3,
-        bonds_and_unbonds(
-            &s,
-            Some(validator.address.clone()),
-            Some(validator.address.clone()),
-        )
-        .unwrap(),
-    );
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Get a non-validating account with tokens
-    let delegator = address::testing::gen_implicit_address();
-    let amount_del = token::Amount::from_uint(201_000_000, 0).unwrap();
-    credit_tokens(&mut s, &staking_token, &delegator, amount_del).unwrap();
-    let balance_key = token::balance_key(&staking_token, &delegator);
-    let balance = s
-        .read::<token::Amount>(&balance_key)
-        .unwrap()
-        .unwrap_or_default();
-    assert_eq!(balance, amount_del);
+    ) {
+        test_validator_sets_retention_aux(genesis_validators)
+    }
+}
 
-    // Advance to epoch 3
-    advance_epoch(&mut s, &params);
-    current_epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = current_epoch + params.pipeline_len;
+proptest! {
+    // Generate arb valid input for `test_read_bonded_ratio_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_read_bonded_ratio(
 
-    // Delegation
-    let delegation_epoch = current_epoch;
-    bond_tokens(
-        &mut s,
-        Some(&delegator),
-        &validator.address,
-        amount_del,
-        current_epoch,
-        None,
-    )
-    .unwrap();
-    let val_stake_pre = read_validator_stake(
-        &s,
-        &params,
-        &validator.address,
-        pipeline_epoch.prev(),
-    )
-    .unwrap();
-    let val_stake_post =
-        read_validator_stake(&s, &params, &validator.address, pipeline_epoch)
-            .unwrap();
-    assert_eq!(validator.tokens + amount_self_bond, val_stake_pre);
-    assert_eq!(
-        validator.tokens + amount_self_bond + amount_del,
-        val_stake_post
-    );
-    let delegation = bond_handle(&delegator, &validator.address);
-    assert_eq!(
-        delegation
-            .get_sum(&s, pipeline_epoch.prev(), &params)
-            .unwrap()
-            .unwrap_or_default(),
-        token::Amount::zero()
-    );
-    assert_eq!(
-        delegation
-            .get_sum(&s, pipeline_epoch, &params)
-            .unwrap()
-            .unwrap_or_default(),
-        amount_del
-    );
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Check delegation bonds details after delegation
-    let delegation_bond_id = BondId {
-        source: delegator.clone(),
-        validator: validator.address.clone(),
-    };
-    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
-        println!("Check index {ix}");
-        assert_eq!(bond_details.len(), 1);
-        let details = bond_details.get(&delegation_bond_id).unwrap();
-        assert_eq!(details.bonds.len(), 1,);
-        dbg!(&details.bonds);
-        assert_eq!(
-            details.bonds[0],
-            BondDetails {
-                start: pipeline_epoch,
-                amount: amount_del,
-                slashed_amount: None
-            },
-        );
-    };
-    // Try to call it with different combinations of owner/validator args
-    check_bond_details(
-        0,
-        bonds_and_unbonds(&s, Some(delegator.clone()), None).unwrap(),
-    );
-    check_bond_details(
-        1,
-        bonds_and_unbonds(
-            &s,
-            Some(delegator.clone()),
-            Some(validator.address.clone()),
-        )
-        .unwrap(),
-    );
+    ) {
+        test_read_bonded_ratio_aux(genesis_validators)
+    }
+}
 
-    // Check all bond details (self-bonds and delegation)
-    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
-        println!("Check index {ix}");
-        let self_bond_details = bond_details.get(&self_bond_id).unwrap();
-        let delegation_details = bond_details.get(&delegation_bond_id).unwrap();
-        assert_eq!(
-            self_bond_details.bonds.len(),
-            2,
-            "Contains genesis and newly added self-bond"
-        );
-        assert_eq!(
-            self_bond_details.bonds[0],
-            BondDetails {
-                start: start_epoch,
-                amount: validator.tokens,
-                slashed_amount: None
-            },
-        );
-        assert_eq!(self_bond_details.bonds[1].amount, amount_self_bond);
-        assert_eq!(
-            delegation_details.bonds[0],
-            BondDetails {
-                start: pipeline_epoch,
-                amount: amount_del,
-                slashed_amount: None
-            },
-        );
-    };
-    // Try to call it with different combinations of owner/validator args
-    check_bond_details(0, bonds_and_unbonds(&s, None, None).unwrap());
-    check_bond_details(
-        1,
-        bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
-    );
+proptest! {
+    // Generate arb valid input for `test_slash_stale_evidence_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_slash_stale_evidence(
 
-    // Advance to epoch 5
-    for _ in 0..2 {
-        current_epoch = advance_epoch(&mut s, &params);
+    genesis_validators in arb_genesis_validators(1..5, None),
+
+    ) {
+        test_slash_stale_evidence_aux(genesis_validators)
     }
-    let pipeline_epoch = current_epoch + params.pipeline_len;
+}
 
-    // Unbond the self-bond with an amount that will remove all of the self-bond
-    // executed after genesis and some of the genesis bond
-    let amount_self_unbond: token::Amount =
-        amount_self_bond + (validator.tokens / 2);
-    // When the difference is 0, only the non-genesis self-bond is unbonded
-    let unbonded_genesis_self_bond =
-        amount_self_unbond - amount_self_bond != token::Amount::zero();
-    dbg!(
-        amount_self_unbond,
-        amount_self_bond,
-        unbonded_genesis_self_bond
-    );
-    let self_unbond_epoch = s.storage.block.epoch;
+proptest! {
+    // Generate arb valid input for `test_get_voting_power_at_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_get_voting_power_at(
 
-    unbond_tokens(
-        &mut s,
-        None,
-        &validator.address,
-        amount_self_unbond,
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    let val_stake_pre = read_validator_stake(
-        &s,
-        &params,
-        &validator.address,
-        pipeline_epoch.prev(),
-    )
-    .unwrap();
+    ) {
+        test_get_voting_power_at_aux(genesis_validators)
+    }
+}
 
-    let val_stake_post =
-        read_validator_stake(&s, &params, &validator.address, pipeline_epoch)
-            .unwrap();
+proptest! {
+    // Generate arb valid input for `test_sample_validators_aux`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_sample_validators(
 
-    let val_delta =
-        read_validator_deltas_value(&s, &validator.address, &pipeline_epoch)
-            .unwrap();
-    let unbond = unbond_handle(&validator.address, &validator.address);
+    genesis_validators in arb_genesis_validators(1..10, None),
 
-    assert_eq!(val_delta, Some(-amount_self_unbond.change()));
-    assert_eq!(
-        unbond
-            .at(&Epoch::default())
-            .get(
-                &s,
-                &(pipeline_epoch
-                    + params.unbonding_len
-                    + params.cubic_slashing_window_length)
-            )
-            .unwrap(),
-        if unbonded_genesis_self_bond {
-            Some(amount_self_unbond - amount_self_bond)
-        } else {
-            None
-        }
-    );
-    assert_eq!(
-        unbond
-            .at(&(self_bond_epoch + params.pipeline_len))
-            .get(
-                &s,
-                &(pipeline_epoch
-                    + params.unbonding_len
-                    + params.cubic_slashing_window_length)
-            )
-            .unwrap(),
-        Some(amount_self_bond)
-    );
-    assert_eq!(
-        val_stake_pre,
-        validator.tokens + amount_self_bond + amount_del
-    );
-    assert_eq!(
-        val_stake_post,
-        validator.tokens + amount_self_bond + amount_del - amount_self_unbond
-    );
+    ) {
+        test_sample_validators_aux(genesis_validators)
+    }
+}
 
-    // Check all bond and unbond details (self-bonds and delegation)
-    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
-        println!("Check index {ix}");
-        dbg!(&bond_details);
-        assert_eq!(bond_details.len(), 2);
-        let self_bond_details = bond_details.get(&self_bond_id).unwrap();
-        let delegation_details = bond_details.get(&delegation_bond_id).unwrap();
-        assert_eq!(
-            self_bond_details.bonds.len(),
-            1,
-            "Contains only part of the genesis bond now"
-        );
-        assert_eq!(
-            self_bond_details.bonds[0],
-            BondDetails {
-                start: start_epoch,
-                amount: validator.tokens + amount_self_bond
-                    - amount_self_unbond,
-                slashed_amount: None
-            },
-        );
-        assert_eq!(
-            delegation_details.bonds[0],
-            BondDetails {
-                start: delegation_epoch + params.pipeline_len,
-                amount: amount_del,
-                slashed_amount: None
-            },
-        );
-        assert_eq!(
-            self_bond_details.unbonds.len(),
-            if unbonded_genesis_self_bond { 2 } else { 1 },
-            "Contains a full unbond of the last self-bond and an unbond from \
-             the genesis bond"
-        );
-        if unbonded_genesis_self_bond {
-            assert_eq!(
-                self_bond_details.unbonds[0],
-                UnbondDetails {
-                    start: start_epoch,
-                    withdraw: self_unbond_epoch
-                        + params.pipeline_len
-                        + params.unbonding_len
-                        + params.cubic_slashing_window_length,
-                    amount: amount_self_unbond - amount_self_bond,
-                    slashed_amount: None
-                }
-            );
-        }
-        assert_eq!(
-            self_bond_details.unbonds[usize::from(unbonded_genesis_self_bond)],
-            UnbondDetails {
-                start: self_bond_epoch + params.pipeline_len,
-                withdraw: self_unbond_epoch
-                    + params.pipeline_len
-                    + params.unbonding_len
-                    + params.cubic_slashing_window_length,
-                amount: amount_self_bond,
-                slashed_amount: None
-            }
-        );
-    };
-    check_bond_details(
-        0,
-        bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
-    );
+proptest! {
+    // Generate arb valid input for `test_sample_validators_by_stake_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_sample_validators_by_stake(
 
-    // Unbond delegation
-    let amount_undel = token::Amount::from_uint(1_000_000, 0).unwrap();
-    unbond_tokens(
-        &mut s,
-        Some(&delegator),
-        &validator.address,
-        amount_undel,
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    genesis_validators in arb_genesis_validators(2..3, None),
 
-    let val_stake_pre = read_validator_stake(
-        &s,
-        &params,
-        &validator.address,
-        pipeline_epoch.prev(),
-    )
-    .unwrap();
-    let val_stake_post =
-        read_validator_stake(&s, &params, &validator.address, pipeline_epoch)
-            .unwrap();
-    let val_delta =
-        read_validator_deltas_value(&s, &validator.address, &pipeline_epoch)
-            .unwrap();
-    let unbond = unbond_handle(&delegator, &validator.address);
+    ) {
+        test_sample_validators_by_stake_aux(genesis_validators)
+    }
+}
 
-    assert_eq!(
-        val_delta,
-        Some(-(amount_self_unbond + amount_undel).change())
-    );
-    assert_eq!(
-        unbond
-            .at(&(delegation_epoch + params.pipeline_len))
-            .get(
-                &s,
-                &(pipeline_epoch
-                    + params.unbonding_len
-                    + params.cubic_slashing_window_length)
-            )
-            .unwrap(),
-        Some(amount_undel)
-    );
-    assert_eq!(
-        val_stake_pre,
-        validator.tokens + amount_self_bond + amount_del
-    );
-    assert_eq!(
-        val_stake_post,
-        validator.tokens + amount_self_bond - amount_self_unbond + amount_del
-            - amount_undel
-    );
+proptest! {
+    // Generate arb valid input for `test_read_stake_range_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_read_stake_range(
 
-    let withdrawable_offset = params.unbonding_len
-        + params.pipeline_len
-        + params.cubic_slashing_window_length;
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Advance to withdrawable epoch
-    for _ in 0..withdrawable_offset {
-        current_epoch = advance_epoch(&mut s, &params);
+    ) {
+        test_read_stake_range_aux(genesis_validators)
     }
+}
 
-    dbg!(current_epoch);
-
-    let pos_balance = s
-        .read::<token::Amount>(&token::balance_key(
-            &staking_token,
-            &super::ADDRESS,
-        ))
-        .unwrap();
+proptest! {
+    // Generate arb valid input for `test_reserve_consensus_key_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_reserve_consensus_key(
 
-    assert_eq!(
-        Some(pos_balance_pre + amount_self_bond + amount_del),
-        pos_balance
-    );
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Withdraw the self-unbond
-    withdraw_tokens(&mut s, None, &validator.address, current_epoch).unwrap();
-    let unbond = unbond_handle(&validator.address, &validator.address);
-    let unbond_iter = unbond.iter(&s).unwrap().next();
-    assert!(unbond_iter.is_none());
+    ) {
+        test_reserve_consensus_key_aux(genesis_validators)
+    }
+}
 
-    let pos_balance = s
-        .read::<token::Amount>(&token::balance_key(
-            &staking_token,
-            &super::ADDRESS,
-        ))
-        .unwrap();
-    assert_eq!(
-        Some(
-            pos_balance_pre + amount_self_bond - amount_self_unbond
-                + amount_del
-        ),
-        pos_balance
-    );
+proptest! {
+    // Generate arb valid input for `test_simulate_bond_action_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_simulate_bond_action(
 
-    // Withdraw the delegation unbond
-    withdraw_tokens(
-        &mut s,
-        Some(&delegator),
-        &validator.address,
-        current_epoch,
-    )
-    .unwrap();
-    let unbond = unbond_handle(&delegator, &validator.address);
-    let unbond_iter = unbond.iter(&s).unwrap().next();
-    assert!(unbond_iter.is_none());
+    genesis_validators in arb_genesis_validators(2..5, None),
 
-    let pos_balance = s
-        .read::<token::Amount>(&token::balance_key(
-            &staking_token,
-            &super::ADDRESS,
-        ))
-        .unwrap();
-    assert_eq!(
-        Some(
-            pos_balance_pre + amount_self_bond - amount_self_unbond
-                + amount_del
-                - amount_undel
-        ),
-        pos_balance
-    );
+    ) {
+        test_simulate_bond_action_aux(genesis_validators)
+    }
 }
 
-/// Test validator initialization.
-fn test_become_validator_aux(
-    params: OwnedPosParams,
-    new_validator: Address,
-    new_validator_consensus_key: SecretKey,
-    validators: Vec<GenesisValidator>,
-) {
-    println!(
-        "Test inputs: {params:?}, new validator: {new_validator}, genesis \
-         validators: {validators:#?}"
-    );
+proptest! {
+    // Generate arb valid input for `test_scheduled_withdrawal_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_scheduled_withdrawal(
 
-    let mut s = TestWlStorage::default();
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Genesis
-    let mut current_epoch = s.storage.block.epoch;
-    let params = test_init_genesis(
-        &mut s,
-        params,
-        validators.clone().into_iter(),
-        current_epoch,
-    )
-    .unwrap();
-    s.commit_block().unwrap();
+    ) {
+        test_scheduled_withdrawal_aux(genesis_validators)
+    }
+}
 
-    // Advance to epoch 1
-    current_epoch = advance_epoch(&mut s, &params);
+proptest! {
+    // Generate arb valid input for `test_commission_history_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_commission_history(
 
-    let num_consensus_before =
-        get_num_consensus_validators(&s, current_epoch + params.pipeline_len)
-            .unwrap();
-    let num_validators_over_thresh = validators
-        .iter()
-        .filter(|validator| {
-            validator.tokens >= params.validator_stake_threshold
-        })
-        .count();
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    assert_eq!(
-        min(
-            num_validators_over_thresh as u64,
-            params.max_validator_slots
-        ),
-        num_consensus_before
-    );
-    assert!(!is_validator(&s, &new_validator).unwrap());
+    ) {
+        test_commission_history_aux(genesis_validators)
+    }
+}
 
-    // Credit the `new_validator` account
-    let staking_token = staking_token_address(&s);
-    let amount = token::Amount::from_uint(100_500_000, 0).unwrap();
-    // Credit twice the amount as we're gonna bond it in delegation first, then
-    // self-bond
-    credit_tokens(&mut s, &staking_token, &new_validator, amount * 2).unwrap();
+proptest! {
+    // Generate arb valid input for `test_commission_rate_change_cap_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_commission_rate_change_cap(
 
-    // Add a delegation from `new_validator` to `genesis_validator`
-    let genesis_validator = &validators.first().unwrap().address;
-    bond_tokens(
-        &mut s,
-        Some(&new_validator),
-        genesis_validator,
-        amount,
-        current_epoch,
-        None,
-    )
-    .unwrap();
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    let consensus_key = new_validator_consensus_key.to_public();
-    let protocol_sk = common_sk_from_simple_seed(0);
-    let protocol_key = protocol_sk.to_public();
-    let eth_hot_key = key::common::PublicKey::Secp256k1(
-        key::testing::gen_keypair::<key::secp256k1::SigScheme>().ref_to(),
-    );
-    let eth_cold_key = key::common::PublicKey::Secp256k1(
-        key::testing::gen_keypair::<key::secp256k1::SigScheme>().ref_to(),
-    );
+    ) {
+        test_commission_rate_change_cap_aux(genesis_validators)
+    }
+}
 
-    // Try to become a validator - it should fail as there is a delegation
-    let result = become_validator(
-        &mut s,
-        BecomeValidator {
-            params: &params,
-            address: &new_validator,
-            consensus_key: &consensus_key,
-            protocol_key: &protocol_key,
-            eth_cold_key: &eth_cold_key,
-            eth_hot_key: &eth_hot_key,
-            current_epoch,
-            commission_rate: Dec::new(5, 2).expect("Dec creation failed"),
-            max_commission_rate_change: Dec::new(5, 2)
-                .expect("Dec creation failed"),
-            metadata: Default::default(),
-            offset_opt: None,
-        },
-    );
-    assert!(result.is_err());
-    assert!(!is_validator(&s, &new_validator).unwrap());
+proptest! {
+    // Generate arb valid input for `test_chunked_slash_processing_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_chunked_slash_processing(
 
-    // Unbond the delegation
-    unbond_tokens(
-        &mut s,
-        Some(&new_validator),
-        genesis_validator,
-        amount,
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    genesis_validators in arb_genesis_validators(2..5, None),
 
-    // Try to become a validator account again - it should pass now
-    become_validator(
-        &mut s,
-        BecomeValidator {
-            params: &params,
-            address: &new_validator,
-            consensus_key: &consensus_key,
-            protocol_key: &protocol_key,
-            eth_cold_key: &eth_cold_key,
-            eth_hot_key: &eth_hot_key,
-            current_epoch,
-            commission_rate: Dec::new(5, 2).expect("Dec creation failed"),
-            max_commission_rate_change: Dec::new(5, 2)
-                .expect("Dec creation failed"),
-            metadata: Default::default(),
-            offset_opt: None,
-        },
-    )
-    .unwrap();
-    assert!(is_validator(&s, &new_validator).unwrap());
+    ) {
+        test_chunked_slash_processing_aux(genesis_validators)
+    }
+}
 
-    let num_consensus_after =
-        get_num_consensus_validators(&s, current_epoch + params.pipeline_len)
-            .unwrap();
-    // The new validator is initialized with no stake and thus is in the
-    // below-threshold set
-    assert_eq!(num_consensus_before, num_consensus_after);
+proptest! {
+    // Generate arb valid input for `test_vesting_account_delegation_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_vesting_account_delegation(
 
-    // Advance to epoch 2
-    current_epoch = advance_epoch(&mut s, &params);
+    genesis_validators in arb_genesis_validators(1..5, None),
 
-    // Self-bond to the new validator
-    bond_tokens(&mut s, None, &new_validator, amount, current_epoch, None)
-        .unwrap();
+    ) {
+        test_vesting_account_delegation_aux(genesis_validators)
+    }
+}
 
-    // Check the bond delta
-    let bond_handle = bond_handle(&new_validator, &new_validator);
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-    let delta = bond_handle.get_delta_val(&s, pipeline_epoch).unwrap();
-    assert_eq!(delta, Some(amount));
+proptest! {
+    // Generate arb valid input for `test_validator_from_protocol_pk_cached`
+    #![proptest_config(Config {
+        cases: 5,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_validator_from_protocol_pk_cached(
 
-    // Check the validator in the validator set -
-    // If the consensus validator slots are full and all the genesis validators
-    // have stake GTE the new validator's self-bond amount, the validator should
-    // be added to the below-capacity set, or the consensus otherwise
-    if params.max_validator_slots <= validators.len() as u64
-        && validators
-            .iter()
-            .all(|validator| validator.tokens >= amount)
-    {
-        let set = read_below_capacity_validator_set_addresses_with_stake(
-            &s,
-            pipeline_epoch,
-        )
-        .unwrap();
-        assert!(set.into_iter().any(
-            |WeightedValidator {
-                 bonded_stake,
-                 address,
-             }| {
-                address == new_validator && bonded_stake == amount
-            }
-        ));
-    } else {
-        let set = read_consensus_validator_set_addresses_with_stake(
-            &s,
-            pipeline_epoch,
-        )
-        .unwrap();
-        assert!(set.into_iter().any(
-            |WeightedValidator {
-                 bonded_stake,
-                 address,
-             }| {
-                address == new_validator && bonded_stake == amount
-            }
-        ));
+    genesis_validators in arb_genesis_validators(1..5, None),
+
+    ) {
+        test_validator_from_protocol_pk_cached_aux(genesis_validators)
     }
+}
 
-    // Advance to epoch 3
-    current_epoch = advance_epoch(&mut s, &params);
+proptest! {
+    // Generate arb valid input for `test_change_validator_eth_keys`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_change_validator_eth_keys(
 
-    // Unbond the self-bond
-    unbond_tokens(&mut s, None, &new_validator, amount, current_epoch, false)
-        .unwrap();
+    genesis_validators in arb_genesis_validators(1..2, None),
 
-    let withdrawable_offset = params.unbonding_len + params.pipeline_len;
+    ) {
+        test_change_validator_eth_keys_aux(genesis_validators)
+    }
+}
 
-    // Advance to withdrawable epoch
-    for _ in 0..withdrawable_offset {
-        current_epoch = advance_epoch(&mut s, &params);
+proptest! {
+    // Generate arb valid input for `test_liveness_reports_aux`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_liveness_reports(
+
+    genesis_validators in arb_genesis_validators(2..6, None),
+
+    ) {
+        test_liveness_reports_aux(genesis_validators)
     }
+}
 
-    // Withdraw the self-bond
-    withdraw_tokens(&mut s, None, &new_validator, current_epoch).unwrap();
+fn arb_params_and_genesis_validators(
+    num_max_validator_slots: Option<u64>,
+    val_size: Range<usize>,
+) -> impl Strategy<Value = (OwnedPosParams, Vec<GenesisValidator>)> {
+    let params = arb_pos_params(num_max_validator_slots);
+    params.prop_flat_map(move |params| {
+        let validators = arb_genesis_validators(
+            val_size.clone(),
+            Some(params.validator_stake_threshold),
+        );
+        (Just(params), validators)
+    })
 }
 
-fn test_slashes_with_unbonding_aux(
-    mut params: OwnedPosParams,
-    validators: Vec<GenesisValidator>,
-    unbond_delay: u64,
+fn test_slashes_with_unbonding_params()
+-> impl Strategy<Value = (OwnedPosParams, Vec<GenesisValidator>, u64)> {
+    let params = arb_pos_params(Some(5));
+    params.prop_flat_map(|params| {
+        let unbond_delay = 0..(params.slash_processing_epoch_offset() * 2);
+        // Must have at least 4 validators so we can slash one and the cubic
+        // slash rate will be less than 100%
+        let validators = arb_genesis_validators(4..10, None);
+        (Just(params), validators, unbond_delay)
+    })
+}
+
+/// Test genesis initialization
+fn test_test_init_genesis_aux(
+    params: OwnedPosParams,
+    start_epoch: Epoch,
+    mut validators: Vec<GenesisValidator>,
 ) {
-    // This can be useful for debugging:
-    params.pipeline_len = 2;
-    params.unbonding_len = 4;
-    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    println!(
+        "Test inputs: {params:?}, {start_epoch}, genesis validators: \
+         {validators:#?}"
+    );
     let mut s = TestWlStorage::default();
+    s.storage.block.epoch = start_epoch;
 
-    // Find the validator with the least stake to avoid the cubic slash rate
-    // going to 100%
-    let validator =
-        itertools::Itertools::sorted_by_key(validators.iter(), |v| v.tokens)
-            .next()
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        start_epoch,
+    )
+    .unwrap();
+
+    let mut bond_details = bonds_and_unbonds(&s, None, None).unwrap();
+    assert!(bond_details.iter().all(|(_id, details)| {
+        details.unbonds.is_empty() && details.slashes.is_empty()
+    }));
+
+    for (i, validator) in validators.into_iter().enumerate() {
+        let addr = &validator.address;
+        let self_bonds = bond_details
+            .remove(&BondId {
+                source: addr.clone(),
+                validator: addr.clone(),
+            })
             .unwrap();
-    let val_addr = &validator.address;
-    let val_tokens = validator.tokens;
-    println!(
-        "Validator that will misbehave addr {val_addr}, tokens {}",
-        val_tokens.to_string_native()
-    );
+        assert_eq!(self_bonds.bonds.len(), 1);
+        assert_eq!(
+            self_bonds.bonds[0],
+            BondDetails {
+                start: start_epoch,
+                amount: validator.tokens,
+                slashed_amount: None,
+            }
+        );
+
+        let state = validator_state_handle(&validator.address)
+            .get(&s, start_epoch, &params)
+            .unwrap();
+        if (i as u64) < params.max_validator_slots
+            && validator.tokens >= params.validator_stake_threshold
+        {
+            // should be in consensus set
+            let handle = consensus_validator_set_handle().at(&start_epoch);
+            assert!(handle.at(&validator.tokens).iter(&s).unwrap().any(
+                |result| {
+                    let (_pos, addr) = result.unwrap();
+                    addr == validator.address
+                }
+            ));
+            assert_eq!(state, Some(ValidatorState::Consensus));
+        } else if validator.tokens >= params.validator_stake_threshold {
+            // Should be in below-capacity set if its tokens are greater than
+            // `validator_stake_threshold`
+            let handle = below_capacity_validator_set_handle().at(&start_epoch);
+            assert!(handle.at(&validator.tokens.into()).iter(&s).unwrap().any(
+                |result| {
+                    let (_pos, addr) = result.unwrap();
+                    addr == validator.address
+                }
+            ));
+            assert_eq!(state, Some(ValidatorState::BelowCapacity));
+        } else {
+            // Should be in below-threshold
+            let bt_addresses =
+                read_below_threshold_validator_set_addresses(&s, start_epoch)
+                    .unwrap();
+            assert!(
+                bt_addresses
+                    .into_iter()
+                    .any(|addr| { addr == validator.address })
+            );
+            assert_eq!(state, Some(ValidatorState::BelowThreshold));
+        }
+    }
+}
+
+/// Test bonding
+/// NOTE: copy validator sets each time we advance the epoch
+fn test_bonds_aux(params: OwnedPosParams, validators: Vec<GenesisValidator>) {
+    // This can be useful for debugging:
+    // params.pipeline_len = 2;
+    // params.unbonding_len = 4;
+    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    let mut s = TestWlStorage::default();
 
     // Genesis
-    // let start_epoch = s.storage.block.epoch;
+    let start_epoch = s.storage.block.epoch;
     let mut current_epoch = s.storage.block.epoch;
     let params = test_init_genesis(
         &mut s,
@@ -1221,832 +967,1000 @@ fn test_slashes_with_unbonding_aux(
     .unwrap();
     s.commit_block().unwrap();
 
+    // Advance to epoch 1
     current_epoch = advance_epoch(&mut s, &params);
-    super::process_slashes(&mut s, current_epoch).unwrap();
-
-    // Discover first slash
-    let slash_0_evidence_epoch = current_epoch;
-    // let slash_0_processing_epoch =
-    //     slash_0_evidence_epoch + params.slash_processing_epoch_offset();
-    let evidence_block_height = BlockHeight(0); // doesn't matter for slashing logic
-    let slash_0_type = SlashType::DuplicateVote;
-    slash(
-        &mut s,
-        &params,
-        current_epoch,
-        slash_0_evidence_epoch,
-        evidence_block_height,
-        slash_0_type,
-        val_addr,
-        current_epoch.next(),
-    )
-    .unwrap();
+    let self_bond_epoch = current_epoch;
 
-    // Advance to an epoch in which we can unbond
-    let unfreeze_epoch =
-        slash_0_evidence_epoch + params.slash_processing_epoch_offset();
-    while current_epoch < unfreeze_epoch {
-        current_epoch = advance_epoch(&mut s, &params);
-        super::process_slashes(&mut s, current_epoch).unwrap();
-    }
+    let validator = validators.first().unwrap();
 
-    // Advance more epochs randomly from the generated delay
-    for _ in 0..unbond_delay {
-        current_epoch = advance_epoch(&mut s, &params);
-    }
+    // Read some data before submitting bond
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let staking_token = staking_token_address(&s);
+    let pos_balance_pre = s
+        .read::<token::Amount>(&token::balance_key(
+            &staking_token,
+            &super::ADDRESS,
+        ))
+        .unwrap()
+        .unwrap_or_default();
+    let total_stake_before =
+        read_total_stake(&s, &params, pipeline_epoch).unwrap();
 
-    // Unbond half of the tokens
-    let unbond_amount = Dec::new(5, 1).unwrap() * val_tokens;
-    println!("Going to unbond {}", unbond_amount.to_string_native());
-    let unbond_epoch = current_epoch;
-    unbond_tokens(&mut s, None, val_addr, unbond_amount, unbond_epoch, false)
+    // Self-bond
+    let amount_self_bond = token::Amount::from_uint(100_500_000, 0).unwrap();
+    credit_tokens(&mut s, &staking_token, &validator.address, amount_self_bond)
         .unwrap();
-
-    // Discover second slash
-    let slash_1_evidence_epoch = current_epoch;
-    // Ensure that both slashes happen before `unbond_epoch + pipeline`
-    let _slash_1_processing_epoch =
-        slash_1_evidence_epoch + params.slash_processing_epoch_offset();
-    let evidence_block_height = BlockHeight(0); // doesn't matter for slashing logic
-    let slash_1_type = SlashType::DuplicateVote;
-    slash(
+    bond_tokens(
         &mut s,
-        &params,
+        None,
+        &validator.address,
+        amount_self_bond,
         current_epoch,
-        slash_1_evidence_epoch,
-        evidence_block_height,
-        slash_1_type,
-        val_addr,
-        current_epoch.next(),
+        None,
     )
     .unwrap();
 
-    // Advance to an epoch in which we can withdraw
-    let withdraw_epoch = unbond_epoch + params.withdrawable_epoch_offset();
-    while current_epoch < withdraw_epoch {
-        current_epoch = advance_epoch(&mut s, &params);
-        super::process_slashes(&mut s, current_epoch).unwrap();
-    }
-    let token = staking_token_address(&s);
-    let val_balance_pre = read_balance(&s, &token, val_addr).unwrap();
-
-    let bond_id = BondId {
-        source: val_addr.clone(),
-        validator: val_addr.clone(),
-    };
-    let binding =
-        super::bonds_and_unbonds(&s, None, Some(val_addr.clone())).unwrap();
-    let details = binding.get(&bond_id).unwrap();
-    let exp_withdraw_from_details = details.unbonds[0].amount
-        - details.unbonds[0].slashed_amount.unwrap_or_default();
-
-    withdraw_tokens(&mut s, None, val_addr, current_epoch).unwrap();
+    // Check the bond delta
+    let self_bond = bond_handle(&validator.address, &validator.address);
+    let delta = self_bond.get_delta_val(&s, pipeline_epoch).unwrap();
+    assert_eq!(delta, Some(amount_self_bond));
 
-    let val_balance_post = read_balance(&s, &token, val_addr).unwrap();
-    let withdrawn_tokens = val_balance_post - val_balance_pre;
-    println!("Withdrew {} tokens", withdrawn_tokens.to_string_native());
+    // Check the validator in the validator set
+    let set =
+        read_consensus_validator_set_addresses_with_stake(&s, pipeline_epoch)
+            .unwrap();
+    assert!(set.into_iter().any(
+        |WeightedValidator {
+             bonded_stake,
+             address,
+         }| {
+            address == validator.address
+                && bonded_stake == validator.tokens + amount_self_bond
+        }
+    ));
 
-    assert_eq!(exp_withdraw_from_details, withdrawn_tokens);
+    let val_deltas =
+        read_validator_deltas_value(&s, &validator.address, &pipeline_epoch)
+            .unwrap();
+    assert_eq!(val_deltas, Some(amount_self_bond.change()));
 
-    let slash_rate_0 = validator_slashes_handle(val_addr)
-        .get(&s, 0)
-        .unwrap()
-        .unwrap()
-        .rate;
-    let slash_rate_1 = validator_slashes_handle(val_addr)
-        .get(&s, 1)
-        .unwrap()
-        .unwrap()
-        .rate;
-    println!("Slash 0 rate {slash_rate_0}, slash 1 rate {slash_rate_1}");
+    let total_deltas_handle = total_deltas_handle();
+    assert_eq!(
+        current_epoch,
+        total_deltas_handle.get_last_update(&s).unwrap().unwrap()
+    );
+    let total_stake_after =
+        read_total_stake(&s, &params, pipeline_epoch).unwrap();
+    assert_eq!(total_stake_before + amount_self_bond, total_stake_after);
 
-    let expected_withdrawn_amount = Dec::from(
-        (Dec::one() - slash_rate_1)
-            * (Dec::one() - slash_rate_0)
-            * unbond_amount,
+    // Check bond details after self-bond
+    let self_bond_id = BondId {
+        source: validator.address.clone(),
+        validator: validator.address.clone(),
+    };
+    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
+        println!("Check index {ix}");
+        let details = bond_details.get(&self_bond_id).unwrap();
+        assert_eq!(
+            details.bonds.len(),
+            2,
+            "Contains genesis and newly added self-bond"
+        );
+        dbg!(&details.bonds);
+        assert_eq!(
+            details.bonds[0],
+            BondDetails {
+                start: start_epoch,
+                amount: validator.tokens,
+                slashed_amount: None
+            },
+        );
+        assert_eq!(
+            details.bonds[1],
+            BondDetails {
+                start: pipeline_epoch,
+                amount: amount_self_bond,
+                slashed_amount: None
+            },
+        );
+    };
+    // Try to call it with different combinations of owner/validator args
+    check_bond_details(0, bonds_and_unbonds(&s, None, None).unwrap());
+    check_bond_details(
+        1,
+        bonds_and_unbonds(&s, Some(validator.address.clone()), None).unwrap(),
     );
-    // Allow some rounding error, 1 NAMNAM per each slash
-    let rounding_error_tolerance =
-        Dec::new(2, NATIVE_MAX_DECIMAL_PLACES).unwrap();
-    assert!(
-        dbg!(expected_withdrawn_amount.abs_diff(&Dec::from(withdrawn_tokens)))
-            <= rounding_error_tolerance
+    check_bond_details(
+        2,
+        bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
+    );
+    check_bond_details(
+        3,
+        bonds_and_unbonds(
+            &s,
+            Some(validator.address.clone()),
+            Some(validator.address.clone()),
+        )
+        .unwrap(),
     );
 
-    // TODO: finish once implemented
-    // let slash_0 = decimal_mult_amount(slash_rate_0, val_tokens);
-    // let slash_1 = decimal_mult_amount(slash_rate_1, val_tokens - slash_0);
-    // let expected_slash_pool = slash_0 + slash_1;
-    // let slash_pool_balance =
-    //     read_balance(&s, &token, &SLASH_POOL_ADDRESS).unwrap();
-    // assert_eq!(expected_slash_pool, slash_pool_balance);
-}
+    // Get a non-validating account with tokens
+    let delegator = address::testing::gen_implicit_address();
+    let amount_del = token::Amount::from_uint(201_000_000, 0).unwrap();
+    credit_tokens(&mut s, &staking_token, &delegator, amount_del).unwrap();
+    let balance_key = token::balance_key(&staking_token, &delegator);
+    let balance = s
+        .read::<token::Amount>(&balance_key)
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(balance, amount_del);
 
-#[test]
-fn test_validator_raw_hash() {
-    let mut storage = TestWlStorage::default();
-    let address = address::testing::established_address_1();
-    let consensus_sk = key::testing::keypair_1();
-    let consensus_pk = consensus_sk.to_public();
-    let expected_raw_hash = key::tm_consensus_key_raw_hash(&consensus_pk);
+    // Advance to epoch 3
+    advance_epoch(&mut s, &params);
+    current_epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = current_epoch + params.pipeline_len;
 
-    assert!(
-        find_validator_by_raw_hash(&storage, &expected_raw_hash)
+    // Delegation
+    let delegation_epoch = current_epoch;
+    bond_tokens(
+        &mut s,
+        Some(&delegator),
+        &validator.address,
+        amount_del,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    let val_stake_pre = read_validator_stake(
+        &s,
+        &params,
+        &validator.address,
+        pipeline_epoch.prev(),
+    )
+    .unwrap();
+    let val_stake_post =
+        read_validator_stake(&s, &params, &validator.address, pipeline_epoch)
+            .unwrap();
+    assert_eq!(validator.tokens + amount_self_bond, val_stake_pre);
+    assert_eq!(
+        validator.tokens + amount_self_bond + amount_del,
+        val_stake_post
+    );
+    let delegation = bond_handle(&delegator, &validator.address);
+    assert_eq!(
+        delegation
+            .get_sum(&s, pipeline_epoch.prev(), &params)
             .unwrap()
-            .is_none()
+            .unwrap_or_default(),
+        token::Amount::zero()
+    );
+    assert_eq!(
+        delegation
+            .get_sum(&s, pipeline_epoch, &params)
+            .unwrap()
+            .unwrap_or_default(),
+        amount_del
     );
-    write_validator_address_raw_hash(&mut storage, &address, &consensus_pk)
-        .unwrap();
-    let found =
-        find_validator_by_raw_hash(&storage, &expected_raw_hash).unwrap();
-    assert_eq!(found, Some(address));
-}
 
-#[test]
-fn test_validator_sets() {
-    let mut s = TestWlStorage::default();
-    // Only 3 consensus validator slots
-    let params = OwnedPosParams {
-        max_validator_slots: 3,
-        ..Default::default()
+    // Check delegation bonds details after delegation
+    let delegation_bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.address.clone(),
     };
-    let addr_seed = "seed";
-    let mut address_gen = EstablishedAddressGen::new(addr_seed);
-    let mut sk_seed = 0;
-    let mut gen_validator = || {
-        let res = (
-            address_gen.generate_address(addr_seed),
-            key::testing::common_sk_from_simple_seed(sk_seed).to_public(),
+    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
+        println!("Check index {ix}");
+        assert_eq!(bond_details.len(), 1);
+        let details = bond_details.get(&delegation_bond_id).unwrap();
+        assert_eq!(details.bonds.len(), 1,);
+        dbg!(&details.bonds);
+        assert_eq!(
+            details.bonds[0],
+            BondDetails {
+                start: pipeline_epoch,
+                amount: amount_del,
+                slashed_amount: None
+            },
         );
-        // bump the sk seed
-        sk_seed += 1;
-        res
     };
+    // Try to call it with different combinations of owner/validator args
+    check_bond_details(
+        0,
+        bonds_and_unbonds(&s, Some(delegator.clone()), None).unwrap(),
+    );
+    check_bond_details(
+        1,
+        bonds_and_unbonds(
+            &s,
+            Some(delegator.clone()),
+            Some(validator.address.clone()),
+        )
+        .unwrap(),
+    );
 
-    // Create genesis validators
-    let ((val1, pk1), stake1) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val2, pk2), stake2) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val3, pk3), stake3) =
-        (gen_validator(), token::Amount::native_whole(10));
-    let ((val4, pk4), stake4) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val5, pk5), stake5) =
-        (gen_validator(), token::Amount::native_whole(100));
-    let ((val6, pk6), stake6) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val7, pk7), stake7) =
-        (gen_validator(), token::Amount::native_whole(1));
-    println!("\nval1: {val1}, {pk1}, {}", stake1.to_string_native());
-    println!("val2: {val2}, {pk2}, {}", stake2.to_string_native());
-    println!("val3: {val3}, {pk3}, {}", stake3.to_string_native());
-    println!("val4: {val4}, {pk4}, {}", stake4.to_string_native());
-    println!("val5: {val5}, {pk5}, {}", stake5.to_string_native());
-    println!("val6: {val6}, {pk6}, {}", stake6.to_string_native());
-    println!("val7: {val7}, {pk7}, {}", stake7.to_string_native());
+    // Check all bond details (self-bonds and delegation)
+    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
+        println!("Check index {ix}");
+        let self_bond_details = bond_details.get(&self_bond_id).unwrap();
+        let delegation_details = bond_details.get(&delegation_bond_id).unwrap();
+        assert_eq!(
+            self_bond_details.bonds.len(),
+            2,
+            "Contains genesis and newly added self-bond"
+        );
+        assert_eq!(
+            self_bond_details.bonds[0],
+            BondDetails {
+                start: start_epoch,
+                amount: validator.tokens,
+                slashed_amount: None
+            },
+        );
+        assert_eq!(self_bond_details.bonds[1].amount, amount_self_bond);
+        assert_eq!(
+            delegation_details.bonds[0],
+            BondDetails {
+                start: pipeline_epoch,
+                amount: amount_del,
+                slashed_amount: None
+            },
+        );
+    };
+    // Try to call it with different combinations of owner/validator args
+    check_bond_details(0, bonds_and_unbonds(&s, None, None).unwrap());
+    check_bond_details(
+        1,
+        bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
+    );
 
-    let start_epoch = Epoch::default();
-    let epoch = start_epoch;
+    // Advance to epoch 5
+    for _ in 0..2 {
+        current_epoch = advance_epoch(&mut s, &params);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
 
-    let protocol_sk_1 = common_sk_from_simple_seed(0);
-    let protocol_sk_2 = common_sk_from_simple_seed(1);
+    // Unbond the self-bond with an amount that will remove all of the self-bond
+    // executed after genesis and some of the genesis bond
+    let amount_self_unbond: token::Amount =
+        amount_self_bond + (validator.tokens / 2);
+    // When the difference is 0, only the non-genesis self-bond is unbonded
+    let unbonded_genesis_self_bond =
+        amount_self_unbond - amount_self_bond != token::Amount::zero();
+    dbg!(
+        amount_self_unbond,
+        amount_self_bond,
+        unbonded_genesis_self_bond
+    );
+    let self_unbond_epoch = s.storage.block.epoch;
 
-    let params = test_init_genesis(
+    unbond_tokens(
         &mut s,
-        params,
-        [
-            GenesisValidator {
-                address: val1.clone(),
-                tokens: stake1,
-                consensus_key: pk1.clone(),
-                protocol_key: protocol_sk_1.to_public(),
-                eth_hot_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                eth_cold_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
-                max_commission_rate_change: Dec::new(1, 1)
-                    .expect("Dec creation failed"),
-                metadata: Default::default(),
-            },
-            GenesisValidator {
-                address: val2.clone(),
-                tokens: stake2,
-                consensus_key: pk2.clone(),
-                protocol_key: protocol_sk_2.to_public(),
-                eth_hot_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                eth_cold_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
-                max_commission_rate_change: Dec::new(1, 1)
-                    .expect("Dec creation failed"),
-                metadata: Default::default(),
-            },
-        ]
-        .into_iter(),
-        epoch,
+        None,
+        &validator.address,
+        amount_self_unbond,
+        current_epoch,
+        false,
     )
     .unwrap();
 
-    // A helper to insert a non-genesis validator
-    let insert_validator = |s: &mut TestWlStorage,
-                            addr,
-                            pk: &PublicKey,
-                            stake: token::Amount,
-                            epoch: Epoch| {
-        insert_validator_into_validator_set(
-            s,
-            &params,
-            addr,
-            stake,
-            epoch,
-            params.pipeline_len,
-        )
-        .unwrap();
+    let val_stake_pre = read_validator_stake(
+        &s,
+        &params,
+        &validator.address,
+        pipeline_epoch.prev(),
+    )
+    .unwrap();
 
-        update_validator_deltas(s, &params, addr, stake.change(), epoch, None)
+    let val_stake_post =
+        read_validator_stake(&s, &params, &validator.address, pipeline_epoch)
             .unwrap();
 
-        // Set their consensus key (needed for
-        // `validator_set_update_tendermint` fn)
-        validator_consensus_key_handle(addr)
-            .set(s, pk.clone(), epoch, params.pipeline_len)
+    let val_delta =
+        read_validator_deltas_value(&s, &validator.address, &pipeline_epoch)
             .unwrap();
-    };
-
-    // Advance to EPOCH 1
-    //
-    // We cannot call `get_tendermint_set_updates` for the genesis state as
-    // `validator_set_update_tendermint` is only called 2 blocks before the
-    // start of an epoch and so we need to give it a predecessor epoch (see
-    // `get_tendermint_set_updates`), which we cannot have on the first
-    // epoch. In any way, the initial validator set is given to Tendermint
-    // from InitChain, so `validator_set_update_tendermint` is
-    // not being used for it.
-    let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
-
-    // Insert another validator with the greater stake 10 NAM
-    insert_validator(&mut s, &val3, &pk3, stake3, epoch);
-    // Insert validator with stake 1 NAM
-    insert_validator(&mut s, &val4, &pk4, stake4, epoch);
-
-    // Validator `val3` and `val4` will be added at pipeline offset (2) - epoch
-    // 3
-    let val3_and_4_epoch = pipeline_epoch;
-
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
-
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val1 && stake == &stake1 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-
-    // Check tendermint validator set updates - there should be none
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert!(tm_updates.is_empty());
-
-    // Advance to EPOCH 2
-    let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
-
-    // Insert another validator with a greater stake still 1000 NAM. It should
-    // replace 2nd consensus validator with stake 1, which should become
-    // below-capacity
-    insert_validator(&mut s, &val5, &pk5, stake5, epoch);
-    // Validator `val5` will be added at pipeline offset (2) - epoch 4
-    let val5_epoch = pipeline_epoch;
+    let unbond = unbond_handle(&validator.address, &validator.address);
 
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    assert_eq!(val_delta, Some(-amount_self_unbond.change()));
+    assert_eq!(
+        unbond
+            .at(&Epoch::default())
+            .get(
+                &s,
+                &(pipeline_epoch
+                    + params.unbonding_len
+                    + params.cubic_slashing_window_length)
+            )
+            .unwrap(),
+        if unbonded_genesis_self_bond {
+            Some(amount_self_unbond - amount_self_bond)
+        } else {
+            None
+        }
+    );
+    assert_eq!(
+        unbond
+            .at(&(self_bond_epoch + params.pipeline_len))
+            .get(
+                &s,
+                &(pipeline_epoch
+                    + params.unbonding_len
+                    + params.cubic_slashing_window_length)
+            )
+            .unwrap(),
+        Some(amount_self_bond)
+    );
+    assert_eq!(
+        val_stake_pre,
+        validator.tokens + amount_self_bond + amount_del
+    );
+    assert_eq!(
+        val_stake_post,
+        validator.tokens + amount_self_bond + amount_del - amount_self_unbond
+    );
 
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val1 && stake == &stake1 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
-    ));
-
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    // Check all bond and unbond details (self-bonds and delegation)
+    let check_bond_details = |ix, bond_details: BondsAndUnbondsDetails| {
+        println!("Check index {ix}");
+        dbg!(&bond_details);
+        assert_eq!(bond_details.len(), 2);
+        let self_bond_details = bond_details.get(&self_bond_id).unwrap();
+        let delegation_details = bond_details.get(&delegation_bond_id).unwrap();
+        assert_eq!(
+            self_bond_details.bonds.len(),
+            1,
+            "Contains only part of the genesis bond now"
+        );
+        assert_eq!(
+            self_bond_details.bonds[0],
+            BondDetails {
+                start: start_epoch,
+                amount: validator.tokens + amount_self_bond
+                    - amount_self_unbond,
+                slashed_amount: None
+            },
+        );
+        assert_eq!(
+            delegation_details.bonds[0],
+            BondDetails {
+                start: delegation_epoch + params.pipeline_len,
+                amount: amount_del,
+                slashed_amount: None
+            },
+        );
+        assert_eq!(
+            self_bond_details.unbonds.len(),
+            if unbonded_genesis_self_bond { 2 } else { 1 },
+            "Contains a full unbond of the last self-bond and an unbond from \
+             the genesis bond"
+        );
+        if unbonded_genesis_self_bond {
+            assert_eq!(
+                self_bond_details.unbonds[0],
+                UnbondDetails {
+                    start: start_epoch,
+                    withdraw: self_unbond_epoch
+                        + params.pipeline_len
+                        + params.unbonding_len
+                        + params.cubic_slashing_window_length,
+                    amount: amount_self_unbond - amount_self_bond,
+                    slashed_amount: None
+                }
+            );
+        }
+        assert_eq!(
+            self_bond_details.unbonds[usize::from(unbonded_genesis_self_bond)],
+            UnbondDetails {
+                start: self_bond_epoch + params.pipeline_len,
+                withdraw: self_unbond_epoch
+                    + params.pipeline_len
+                    + params.unbonding_len
+                    + params.cubic_slashing_window_length,
+                amount: amount_self_bond,
+                slashed_amount: None
+            }
+        );
+    };
+    check_bond_details(
+        0,
+        bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
+    );
 
-    assert_eq!(below_capacity_vals.len(), 2);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
+    // Unbond delegation
+    let amount_undel = token::Amount::from_uint(1_000_000, 0).unwrap();
+    unbond_tokens(
+        &mut s,
+        Some(&delegator),
+        &validator.address,
+        amount_undel,
+        current_epoch,
+        false,
+    )
+    .unwrap();
 
-    // Advance to EPOCH 3
-    let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
+    let val_stake_pre = read_validator_stake(
+        &s,
+        &params,
+        &validator.address,
+        pipeline_epoch.prev(),
+    )
+    .unwrap();
+    let val_stake_post =
+        read_validator_stake(&s, &params, &validator.address, pipeline_epoch)
+            .unwrap();
+    let val_delta =
+        read_validator_deltas_value(&s, &validator.address, &pipeline_epoch)
+            .unwrap();
+    let unbond = unbond_handle(&delegator, &validator.address);
 
-    // Check tendermint validator set updates
     assert_eq!(
-        val3_and_4_epoch, epoch,
-        "val3 and val4 are in the validator sets now"
+        val_delta,
+        Some(-(amount_self_unbond + amount_undel).change())
     );
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    // `val4` is newly added below-capacity, must be skipped in updated in TM
-    assert_eq!(tm_updates.len(), 1);
     assert_eq!(
-        tm_updates[0],
-        ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk3,
-            bonded_stake: stake3,
-        })
+        unbond
+            .at(&(delegation_epoch + params.pipeline_len))
+            .get(
+                &s,
+                &(pipeline_epoch
+                    + params.unbonding_len
+                    + params.cubic_slashing_window_length)
+            )
+            .unwrap(),
+        Some(amount_undel)
+    );
+    assert_eq!(
+        val_stake_pre,
+        validator.tokens + amount_self_bond + amount_del
+    );
+    assert_eq!(
+        val_stake_post,
+        validator.tokens + amount_self_bond - amount_self_unbond + amount_del
+            - amount_undel
     );
 
-    // Insert another validator with a stake 1 NAM. It should be added to the
-    // below-capacity set
-    insert_validator(&mut s, &val6, &pk6, stake6, epoch);
-    // Validator `val6` will be added at pipeline offset (2) - epoch 5
-    let val6_epoch = pipeline_epoch;
+    let withdrawable_offset = params.unbonding_len
+        + params.pipeline_len
+        + params.cubic_slashing_window_length;
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    // Advance to withdrawable epoch
+    for _ in 0..withdrawable_offset {
+        current_epoch = advance_epoch(&mut s, &params);
+    }
 
-    assert_eq!(below_capacity_vals.len(), 3);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(2)
-    ));
+    dbg!(current_epoch);
 
-    // Advance to EPOCH 4
-    let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
+    let pos_balance = s
+        .read::<token::Amount>(&token::balance_key(
+            &staking_token,
+            &super::ADDRESS,
+        ))
+        .unwrap();
 
-    // Check tendermint validator set updates
-    assert_eq!(val5_epoch, epoch, "val5 is in the validator sets now");
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert_eq!(tm_updates.len(), 2);
     assert_eq!(
-        tm_updates[0],
-        ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk5,
-            bonded_stake: stake5,
-        })
+        Some(pos_balance_pre + amount_self_bond + amount_del),
+        pos_balance
     );
-    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk2));
 
-    // Unbond some stake from val1, it should be be swapped with the greatest
-    // below-capacity validator val2 into the below-capacity set. The stake of
-    // val1 will go below 1 NAM, which is the validator_stake_threshold, so it
-    // will enter the below-threshold validator set.
-    let unbond = token::Amount::from_uint(500_000, 0).unwrap();
-    let stake1 = stake1 - unbond;
-    println!("val1 {val1} new stake {}", stake1.to_string_native());
-    // Because `update_validator_set` and `update_validator_deltas` are
-    // effective from pipeline offset, we use pipeline epoch for the rest of the
-    // checks
-    update_validator_set(&mut s, &params, &val1, -unbond.change(), epoch, None)
-        .unwrap();
-    update_validator_deltas(
-        &mut s,
-        &params,
-        &val1,
-        -unbond.change(),
-        epoch,
-        None,
+    // Withdraw the self-unbond
+    withdraw_tokens(&mut s, None, &validator.address, current_epoch).unwrap();
+    let unbond = unbond_handle(&validator.address, &validator.address);
+    let unbond_iter = unbond.iter(&s).unwrap().next();
+    assert!(unbond_iter.is_none());
+
+    let pos_balance = s
+        .read::<token::Amount>(&token::balance_key(
+            &staking_token,
+            &super::ADDRESS,
+        ))
+        .unwrap();
+    assert_eq!(
+        Some(
+            pos_balance_pre + amount_self_bond - amount_self_unbond
+                + amount_del
+        ),
+        pos_balance
+    );
+
+    // Withdraw the delegation unbond
+    withdraw_tokens(
+        &mut s,
+        Some(&delegator),
+        &validator.address,
+        current_epoch,
     )
     .unwrap();
-    // Epoch 6
-    let val1_unbond_epoch = pipeline_epoch;
+    let unbond = unbond_handle(&delegator, &validator.address);
+    let unbond_iter = unbond.iter(&s).unwrap().next();
+    assert!(unbond_iter.is_none());
 
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    let pos_balance = s
+        .read::<token::Amount>(&token::balance_key(
+            &staking_token,
+            &super::ADDRESS,
+        ))
+        .unwrap();
+    assert_eq!(
+        Some(
+            pos_balance_pre + amount_self_bond - amount_self_unbond
+                + amount_del
+                - amount_undel
+        ),
+        pos_balance
+    );
+}
 
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
-    ));
+/// Test validator initialization.
+fn test_become_validator_aux(
+    params: OwnedPosParams,
+    new_validator: Address,
+    new_validator_consensus_key: SecretKey,
+    validators: Vec<GenesisValidator>,
+) {
+    println!(
+        "Test inputs: {params:?}, new validator: {new_validator}, genesis \
+         validators: {validators:#?}"
+    );
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    let mut s = TestWlStorage::default();
 
-    assert_eq!(below_capacity_vals.len(), 2);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(2)
-    ));
+    // Genesis
+    let mut current_epoch = s.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
 
-    let below_threshold_vals =
-        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
-            .unwrap()
-            .into_iter()
-            .collect::<Vec<_>>();
+    // Advance to epoch 1
+    current_epoch = advance_epoch(&mut s, &params);
 
-    assert_eq!(below_threshold_vals.len(), 1);
-    assert_eq!(&below_threshold_vals[0], &val1);
+    let num_consensus_before =
+        get_num_consensus_validators(&s, current_epoch + params.pipeline_len)
+            .unwrap();
+    let num_validators_over_thresh = validators
+        .iter()
+        .filter(|validator| {
+            validator.tokens >= params.validator_stake_threshold
+        })
+        .count();
 
-    // Advance to EPOCH 5
-    let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
+    assert_eq!(
+        min(
+            num_validators_over_thresh as u64,
+            params.max_validator_slots
+        ),
+        num_consensus_before
+    );
+    assert!(!is_validator(&s, &new_validator).unwrap());
 
-    // Check tendermint validator set updates
-    assert_eq!(val6_epoch, epoch, "val6 is in the validator sets now");
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert!(tm_updates.is_empty());
+    // Credit the `new_validator` account
+    let staking_token = staking_token_address(&s);
+    let amount = token::Amount::from_uint(100_500_000, 0).unwrap();
+    // Credit twice the amount as we're gonna bond it in delegation first, then
+    // self-bond
+    credit_tokens(&mut s, &staking_token, &new_validator, amount * 2).unwrap();
 
-    // Insert another validator with stake 1 - it should be added to below
-    // capacity set
-    insert_validator(&mut s, &val7, &pk7, stake7, epoch);
-    // Epoch 7
-    let val7_epoch = pipeline_epoch;
+    // Add a delegation from `new_validator` to `genesis_validator`
+    let genesis_validator = &validators.first().unwrap().address;
+    bond_tokens(
+        &mut s,
+        Some(&new_validator),
+        genesis_validator,
+        amount,
+        current_epoch,
+        None,
+    )
+    .unwrap();
 
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    let consensus_key = new_validator_consensus_key.to_public();
+    let protocol_sk = common_sk_from_simple_seed(0);
+    let protocol_key = protocol_sk.to_public();
+    let eth_hot_key = key::common::PublicKey::Secp256k1(
+        key::testing::gen_keypair::<key::secp256k1::SigScheme>().ref_to(),
+    );
+    let eth_cold_key = key::common::PublicKey::Secp256k1(
+        key::testing::gen_keypair::<key::secp256k1::SigScheme>().ref_to(),
+    );
 
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
-    ));
+    // Try to become a validator - it should fail as there is a delegation
+    let result = become_validator(
+        &mut s,
+        BecomeValidator {
+            params: &params,
+            address: &new_validator,
+            consensus_key: &consensus_key,
+            protocol_key: &protocol_key,
+            eth_cold_key: &eth_cold_key,
+            eth_hot_key: &eth_hot_key,
+            current_epoch,
+            commission_rate: Dec::new(5, 2).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(5, 2)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+            offset_opt: None,
+        },
+    );
+    assert!(result.is_err());
+    assert!(!is_validator(&s, &new_validator).unwrap());
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    // Unbond the delegation
+    unbond_tokens(
+        &mut s,
+        Some(&new_validator),
+        genesis_validator,
+        amount,
+        current_epoch,
+        false,
+    )
+    .unwrap();
 
-    assert_eq!(below_capacity_vals.len(), 3);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(2)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[2],
-        (
-            lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-            },
-            address
-        )
-        if address == &val7 && stake == &stake7 && *position == Position(3)
-    ));
+    // Try to become a validator account again - it should pass now
+    become_validator(
+        &mut s,
+        BecomeValidator {
+            params: &params,
+            address: &new_validator,
+            consensus_key: &consensus_key,
+            protocol_key: &protocol_key,
+            eth_cold_key: &eth_cold_key,
+            eth_hot_key: &eth_hot_key,
+            current_epoch,
+            commission_rate: Dec::new(5, 2).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(5, 2)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+            offset_opt: None,
+        },
+    )
+    .unwrap();
+    assert!(is_validator(&s, &new_validator).unwrap());
 
-    let below_threshold_vals =
-        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+    let num_consensus_after =
+        get_num_consensus_validators(&s, current_epoch + params.pipeline_len)
+            .unwrap();
+    // The new validator is initialized with no stake and thus is in the
+    // below-threshold set
+    assert_eq!(num_consensus_before, num_consensus_after);
+    assert!(
+        read_below_threshold_validator_set_addresses(
+            &s,
+            current_epoch + params.pipeline_len
+        )
+        .unwrap()
+        .contains(&new_validator)
+    );
+    assert!(
+        !read_below_threshold_validator_set_addresses(&s, current_epoch)
             .unwrap()
-            .into_iter()
-            .collect::<Vec<_>>();
+            .contains(&new_validator)
+    );
 
-    assert_eq!(below_threshold_vals.len(), 1);
-    assert_eq!(&below_threshold_vals[0], &val1);
+    // Advance to epoch 2
+    current_epoch = advance_epoch(&mut s, &params);
 
-    // Advance to EPOCH 6
-    let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
+    // Self-bond to the new validator
+    bond_tokens(&mut s, None, &new_validator, amount, current_epoch, None)
+        .unwrap();
 
-    // Check tendermint validator set updates
-    assert_eq!(val1_unbond_epoch, epoch, "val1's unbond is applied now");
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert_eq!(tm_updates.len(), 2);
-    assert_eq!(
-        tm_updates[0],
-        ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk4.clone(),
-            bonded_stake: stake4,
-        })
-    );
-    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk1));
+    // Check the bond delta
+    let bond_handle = bond_handle(&new_validator, &new_validator);
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let delta = bond_handle.get_delta_val(&s, pipeline_epoch).unwrap();
+    assert_eq!(delta, Some(amount));
 
-    // Bond some stake to val6, it should be be swapped with the lowest
-    // consensus validator val2 into the consensus set
-    let bond = token::Amount::from_uint(500_000, 0).unwrap();
-    let stake6 = stake6 + bond;
-    println!("val6 {val6} new stake {}", stake6.to_string_native());
-    update_validator_set(&mut s, &params, &val6, bond.change(), epoch, None)
+    // Check the validator in the validator set -
+    // If the consensus validator slots are full and all the genesis validators
+    // have stake GTE the new validator's self-bond amount, the validator should
+    // be added to the below-capacity set, or the consensus otherwise
+    if params.max_validator_slots <= validators.len() as u64
+        && validators
+            .iter()
+            .all(|validator| validator.tokens >= amount)
+    {
+        let set = read_below_capacity_validator_set_addresses_with_stake(
+            &s,
+            pipeline_epoch,
+        )
         .unwrap();
-    update_validator_deltas(&mut s, &params, &val6, bond.change(), epoch, None)
+        assert!(set.into_iter().any(
+            |WeightedValidator {
+                 bonded_stake,
+                 address,
+             }| {
+                address == new_validator && bonded_stake == amount
+            }
+        ));
+    } else {
+        let set = read_consensus_validator_set_addresses_with_stake(
+            &s,
+            pipeline_epoch,
+        )
         .unwrap();
-    let val6_bond_epoch = pipeline_epoch;
+        assert!(set.into_iter().any(
+            |WeightedValidator {
+                 bonded_stake,
+                 address,
+             }| {
+                address == new_validator && bonded_stake == amount
+            }
+        ));
+    }
+    // With the self-bond above the threshold, the validator leaves the
+    // below-threshold set
+    assert!(
+        !read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+            .unwrap()
+            .contains(&new_validator)
+    );
 
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    // Advance to epoch 3
+    current_epoch = advance_epoch(&mut s, &params);
 
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
-    ));
+    // Unbond the self-bond
+    unbond_tokens(&mut s, None, &new_validator, amount, current_epoch, false)
+        .unwrap();
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
+    // Without any stake, the validator is back in the below-threshold set
+    assert!(
+        read_below_threshold_validator_set_addresses(
+            &s,
+            current_epoch + params.pipeline_len
+        )
         .unwrap()
-        .map(Result::unwrap)
-        .collect();
+        .contains(&new_validator)
+    );
 
-    assert_eq!(below_capacity_vals.len(), 3);
-    dbg!(&below_capacity_vals);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val7 && stake == &stake7 && *position == Position(3)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[2],
-        (
-            lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-            },
-            address
-        )
-        if address == &val4 && stake == &stake4 && *position == Position(4)
-    ));
+    let withdrawable_offset = params.unbonding_len + params.pipeline_len;
 
-    let below_threshold_vals =
-        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
-            .unwrap()
-            .into_iter()
-            .collect::<Vec<_>>();
+    // Advance to withdrawable epoch
+    for _ in 0..withdrawable_offset {
+        current_epoch = advance_epoch(&mut s, &params);
+    }
 
-    assert_eq!(below_threshold_vals.len(), 1);
-    assert_eq!(&below_threshold_vals[0], &val1);
+    // Withdraw the self-bond
+    withdraw_tokens(&mut s, None, &new_validator, current_epoch).unwrap();
+}
 
-    // Advance to EPOCH 7
-    let epoch = advance_epoch(&mut s, &params);
-    assert_eq!(val7_epoch, epoch, "val6 is in the validator sets now");
+fn test_slashes_with_unbonding_aux(
+    mut params: OwnedPosParams,
+    validators: Vec<GenesisValidator>,
+    unbond_delay: u64,
+) {
+    // This can be useful for debugging:
+    params.pipeline_len = 2;
+    params.unbonding_len = 4;
+    params.evidence_max_age_epochs = 4;
+    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    let mut s = TestWlStorage::default();
 
-    // Check tendermint validator set updates
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert!(tm_updates.is_empty());
+    // Find the validator with the least stake to avoid the cubic slash rate
+    // going to 100%
+    let validator =
+        itertools::Itertools::sorted_by_key(validators.iter(), |v| v.tokens)
+            .next()
+            .unwrap();
+    let val_addr = &validator.address;
+    let val_tokens = validator.tokens;
+    println!(
+        "Validator that will misbehave addr {val_addr}, tokens {}",
+        val_tokens.to_string_native()
+    );
 
-    // Advance to EPOCH 8
-    let epoch = advance_epoch(&mut s, &params);
+    // Genesis
+    // let start_epoch = s.storage.block.epoch;
+    let mut current_epoch = s.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
 
-    // Check tendermint validator set updates
-    assert_eq!(val6_bond_epoch, epoch, "val5's bond is applied now");
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    dbg!(&tm_updates);
-    assert_eq!(tm_updates.len(), 2);
-    assert_eq!(
-        tm_updates[0],
-        ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk6,
-            bonded_stake: stake6,
-        })
-    );
-    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk4));
+    current_epoch = advance_epoch(&mut s, &params);
+    super::process_slashes(&mut s, current_epoch).unwrap();
 
-    // Check that the below-capacity validator set was purged for the old epochs
-    // but that the consensus_validator_set was not
-    let last_epoch = epoch;
-    for e in Epoch::iter_bounds_inclusive(
-        start_epoch,
-        last_epoch
-            .sub_or_default(Epoch(DEFAULT_NUM_PAST_EPOCHS))
-            .sub_or_default(Epoch(1)),
-    ) {
-        assert!(
-            !consensus_validator_set_handle()
-                .at(&e)
-                .is_empty(&s)
-                .unwrap()
-        );
-        assert!(
-            below_capacity_validator_set_handle()
-                .at(&e)
-                .is_empty(&s)
-                .unwrap()
-        );
+    // Discover first slash
+    let slash_0_evidence_epoch = current_epoch;
+    // let slash_0_processing_epoch =
+    //     slash_0_evidence_epoch + params.slash_processing_epoch_offset();
+    let evidence_block_height = BlockHeight(0); // doesn't matter for slashing logic
+    let slash_0_type = SlashType::DuplicateVote;
+    slash(
+        &mut s,
+        &params,
+        current_epoch,
+        slash_0_evidence_epoch,
+        evidence_block_height,
+        slash_0_type,
+        val_addr,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    // Advance to an epoch in which we can unbond
+    let unfreeze_epoch =
+        slash_0_evidence_epoch + params.slash_processing_epoch_offset();
+    while current_epoch < unfreeze_epoch {
+        current_epoch = advance_epoch(&mut s, &params);
+        super::process_slashes(&mut s, current_epoch).unwrap();
+    }
+
+    // Advance more epochs randomly from the generated delay
+    for _ in 0..unbond_delay {
+        current_epoch = advance_epoch(&mut s, &params);
+    }
+
+    // Unbond half of the tokens
+    let unbond_amount = Dec::new(5, 1).unwrap() * val_tokens;
+    println!("Going to unbond {}", unbond_amount.to_string_native());
+    let unbond_epoch = current_epoch;
+    unbond_tokens(&mut s, None, val_addr, unbond_amount, unbond_epoch, false)
+        .unwrap();
+
+    // Discover second slash
+    let slash_1_evidence_epoch = current_epoch;
+    // Ensure that both slashes happen before `unbond_epoch + pipeline`
+    let _slash_1_processing_epoch =
+        slash_1_evidence_epoch + params.slash_processing_epoch_offset();
+    let evidence_block_height = BlockHeight(0); // doesn't matter for slashing logic
+    let slash_1_type = SlashType::DuplicateVote;
+    slash(
+        &mut s,
+        &params,
+        current_epoch,
+        slash_1_evidence_epoch,
+        evidence_block_height,
+        slash_1_type,
+        val_addr,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    // Advance to an epoch in which we can withdraw
+    let withdraw_epoch = unbond_epoch + params.withdrawable_epoch_offset();
+    while current_epoch < withdraw_epoch {
+        current_epoch = advance_epoch(&mut s, &params);
+        super::process_slashes(&mut s, current_epoch).unwrap();
     }
+    let token = staking_token_address(&s);
+    let val_balance_pre = read_balance(&s, &token, val_addr).unwrap();
+
+    let bond_id = BondId {
+        source: val_addr.clone(),
+        validator: val_addr.clone(),
+    };
+    let binding =
+        super::bonds_and_unbonds(&s, None, Some(val_addr.clone())).unwrap();
+    let details = binding.get(&bond_id).unwrap();
+    let exp_withdraw_from_details = details.unbonds[0].amount
+        - details.unbonds[0].slashed_amount.unwrap_or_default();
+
+    withdraw_tokens(&mut s, None, val_addr, current_epoch).unwrap();
+
+    let val_balance_post = read_balance(&s, &token, val_addr).unwrap();
+    let withdrawn_tokens = val_balance_post - val_balance_pre;
+    println!("Withdrew {} tokens", withdrawn_tokens.to_string_native());
+
+    assert_eq!(exp_withdraw_from_details, withdrawn_tokens);
+
+    let slash_rate_0 = validator_slashes_handle(val_addr)
+        .get(&s, 0)
+        .unwrap()
+        .unwrap()
+        .rate;
+    let slash_rate_1 = validator_slashes_handle(val_addr)
+        .get(&s, 1)
+        .unwrap()
+        .unwrap()
+        .rate;
+    println!("Slash 0 rate {slash_rate_0}, slash 1 rate {slash_rate_1}");
+
+    let expected_withdrawn_amount = Dec::from(
+        (Dec::one() - slash_rate_1)
+            * (Dec::one() - slash_rate_0)
+            * unbond_amount,
+    );
+    // Allow some rounding error, 1 NAMNAM per each slash
+    let rounding_error_tolerance =
+        Dec::new(2, NATIVE_MAX_DECIMAL_PLACES).unwrap();
+    assert!(
+        dbg!(expected_withdrawn_amount.abs_diff(&Dec::from(withdrawn_tokens)))
+            <= rounding_error_tolerance
+    );
+
+    // TODO: finish once implemented
+    // let slash_0 = decimal_mult_amount(slash_rate_0, val_tokens);
+    // let slash_1 = decimal_mult_amount(slash_rate_1, val_tokens - slash_0);
+    // let expected_slash_pool = slash_0 + slash_1;
+    // let slash_pool_balance =
+    //     read_balance(&s, &token, &SLASH_POOL_ADDRESS).unwrap();
+    // assert_eq!(expected_slash_pool, slash_pool_balance);
 }
 
-/// When a consensus set validator with 0 voting power adds a bond in the same
-/// epoch as another below-capacity set validator with 0 power, but who adds
-/// more bonds than the validator who is in the consensus set, they get swapped
-/// in the sets. But if both of their new voting powers are still 0 after
-/// bonding, the newly below-capacity validator must not be given to tendermint
-/// with 0 voting power, because it wasn't it its set before
 #[test]
-fn test_validator_sets_swap() {
+fn test_validator_raw_hash() {
+    let mut storage = TestWlStorage::default();
+    let address = address::testing::established_address_1();
+    let consensus_sk = key::testing::keypair_1();
+    let consensus_pk = consensus_sk.to_public();
+    let expected_raw_hash = key::tm_consensus_key_raw_hash(&consensus_pk);
+
+    assert!(
+        find_validator_by_raw_hash(&storage, &expected_raw_hash)
+            .unwrap()
+            .is_none()
+    );
+    write_validator_address_raw_hash(&mut storage, &address, &consensus_pk)
+        .unwrap();
+    let found =
+        find_validator_by_raw_hash(&storage, &expected_raw_hash).unwrap();
+    assert_eq!(found, Some(address));
+}
+
+#[test]
+fn test_checked_delta_updates() {
+    let max = token::Change::maximum();
+    let one = token::Change::from(1_i64);
+    assert_eq!(checked_change_add(max, -one, "test").unwrap(), max - one);
+    assert!(matches!(
+        checked_change_add(max, one, "test"),
+        Err(ArithmeticError::Overflow("test"))
+    ));
+    assert!(matches!(
+        checked_change_add(-max, -max, "test"),
+        Err(ArithmeticError::Underflow("test"))
+    ));
+    let one_amount = token::Amount::from(1_u64);
+    assert!(matches!(
+        checked_amount_add(token::Amount::max(), one_amount, "test"),
+        Err(ArithmeticError::Overflow("test"))
+    ));
+    assert!(matches!(
+        checked_amount_sub(token::Amount::zero(), one_amount, "test"),
+        Err(ArithmeticError::Underflow("test"))
+    ));
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+    let validator = address::testing::established_address_1();
+    let epoch = Epoch::default();
+    update_validator_deltas(
+        &mut storage,
+        &params,
+        &validator,
+        max,
+        epoch,
+        Some(0),
+    )
+    .unwrap();
+    // A malformed delta must not wrap the stored value
+    let res = update_validator_deltas(
+        &mut storage,
+        &params,
+        &validator,
+        one,
+        epoch,
+        Some(0),
+    );
+    assert!(res.is_err());
+    assert_eq!(
+        read_validator_deltas_value(&storage, &validator, &epoch).unwrap(),
+        Some(max)
+    );
+}
+
+#[test]
+fn test_validator_sets() {
     let mut s = TestWlStorage::default();
-    // Only 2 consensus validator slots
+    // Only 3 consensus validator slots
     let params = OwnedPosParams {
-        max_validator_slots: 2,
-        // Set the stake threshold to 0 so no validators are in the
-        // below-threshold set
-        validator_stake_threshold: token::Amount::zero(),
-        // Set 0.1 votes per token
-        tm_votes_per_token: Dec::new(1, 1).expect("Dec creation failed"),
+        max_validator_slots: 3,
         ..Default::default()
     };
-
     let addr_seed = "seed";
     let mut address_gen = EstablishedAddressGen::new(addr_seed);
     let mut sk_seed = 0;
@@ -2060,20 +1974,31 @@ fn test_validator_sets_swap() {
         res
     };
 
-    // Start with two genesis validators, one with 1 voting power and other 0
-    let epoch = Epoch::default();
-    // 1M voting power
+    // Create genesis validators
     let ((val1, pk1), stake1) =
-        (gen_validator(), token::Amount::native_whole(10));
-    // 0 voting power
+        (gen_validator(), token::Amount::native_whole(1));
     let ((val2, pk2), stake2) =
-        (gen_validator(), token::Amount::from_uint(5, 0).unwrap());
-    // 0 voting power
+        (gen_validator(), token::Amount::native_whole(1));
     let ((val3, pk3), stake3) =
-        (gen_validator(), token::Amount::from_uint(5, 0).unwrap());
-    println!("val1: {val1}, {pk1}, {}", stake1.to_string_native());
+        (gen_validator(), token::Amount::native_whole(10));
+    let ((val4, pk4), stake4) =
+        (gen_validator(), token::Amount::native_whole(1));
+    let ((val5, pk5), stake5) =
+        (gen_validator(), token::Amount::native_whole(100));
+    let ((val6, pk6), stake6) =
+        (gen_validator(), token::Amount::native_whole(1));
+    let ((val7, pk7), stake7) =
+        (gen_validator(), token::Amount::native_whole(1));
+    println!("\nval1: {val1}, {pk1}, {}", stake1.to_string_native());
     println!("val2: {val2}, {pk2}, {}", stake2.to_string_native());
     println!("val3: {val3}, {pk3}, {}", stake3.to_string_native());
+    println!("val4: {val4}, {pk4}, {}", stake4.to_string_native());
+    println!("val5: {val5}, {pk5}, {}", stake5.to_string_native());
+    println!("val6: {val6}, {pk6}, {}", stake6.to_string_native());
+    println!("val7: {val7}, {pk7}, {}", stake7.to_string_native());
+
+    let start_epoch = Epoch::default();
+    let epoch = start_epoch;
 
     let protocol_sk_1 = common_sk_from_simple_seed(0);
     let protocol_sk_2 = common_sk_from_simple_seed(1);
@@ -2083,9 +2008,9 @@ fn test_validator_sets_swap() {
         params,
         [
             GenesisValidator {
-                address: val1,
+                address: val1.clone(),
                 tokens: stake1,
-                consensus_key: pk1,
+                consensus_key: pk1.clone(),
                 protocol_key: protocol_sk_1.to_public(),
                 eth_hot_key: key::common::PublicKey::Secp256k1(
                     key::testing::gen_keypair::<key::secp256k1::SigScheme>()
@@ -2103,7 +2028,7 @@ fn test_validator_sets_swap() {
             GenesisValidator {
                 address: val2.clone(),
                 tokens: stake2,
-                consensus_key: pk2,
+                consensus_key: pk2.clone(),
                 protocol_key: protocol_sk_2.to_public(),
                 eth_hot_key: key::common::PublicKey::Secp256k1(
                     key::testing::gen_keypair::<key::secp256k1::SigScheme>()
@@ -2151,109 +2076,144 @@ fn test_validator_sets_swap() {
     };
 
     // Advance to EPOCH 1
+    //
+    // We cannot call `get_tendermint_set_updates` for the genesis state as
+    // `validator_set_update_tendermint` is only called 2 blocks before the
+    // start of an epoch and so we need to give it a predecessor epoch (see
+    // `get_tendermint_set_updates`), which we cannot have on the first
+    // epoch. In any way, the initial validator set is given to Tendermint
+    // from InitChain, so `validator_set_update_tendermint` is
+    // not being used for it.
     let epoch = advance_epoch(&mut s, &params);
     let pipeline_epoch = epoch + params.pipeline_len;
 
-    // Insert another validator with 0 voting power
+    // Insert another validator with the greater stake 10 NAM
     insert_validator(&mut s, &val3, &pk3, stake3, epoch);
+    // Insert validator with stake 1 NAM
+    insert_validator(&mut s, &val4, &pk4, stake4, epoch);
 
-    assert_eq!(stake2, stake3);
-
-    // Add 2 bonds, one for val2 and greater one for val3
-    let bonds_epoch_1 = pipeline_epoch;
-    let bond2 = token::Amount::from_uint(1, 0).unwrap();
-    let stake2 = stake2 + bond2;
-    let bond3 = token::Amount::from_uint(4, 0).unwrap();
-    let stake3 = stake3 + bond3;
+    // Validator `val3` and `val4` will be added at pipeline offset (2) - epoch
+    // 3
+    let val3_and_4_epoch = pipeline_epoch;
 
-    assert!(stake2 < stake3);
-    assert_eq!(into_tm_voting_power(params.tm_votes_per_token, stake2), 0);
-    assert_eq!(into_tm_voting_power(params.tm_votes_per_token, stake3), 0);
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    update_validator_set(&mut s, &params, &val2, bond2.change(), epoch, None)
-        .unwrap();
-    update_validator_deltas(
-        &mut s,
-        &params,
-        &val2,
-        bond2.change(),
-        epoch,
-        None,
-    )
-    .unwrap();
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val1 && stake == &stake1 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
 
-    update_validator_set(&mut s, &params, &val3, bond3.change(), epoch, None)
-        .unwrap();
-    update_validator_deltas(
-        &mut s,
-        &params,
-        &val3,
-        bond3.change(),
-        epoch,
-        None,
-    )
-    .unwrap();
+    // Check tendermint validator set updates - there should be none
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert!(tm_updates.is_empty());
 
     // Advance to EPOCH 2
     let epoch = advance_epoch(&mut s, &params);
     let pipeline_epoch = epoch + params.pipeline_len;
 
-    // Add 2 more bonds, same amount for `val2` and val3`
-    let bonds_epoch_2 = pipeline_epoch;
-    let bonds = token::Amount::native_whole(1);
-    let stake2 = stake2 + bonds;
-    let stake3 = stake3 + bonds;
-    assert!(stake2 < stake3);
-    assert_eq!(
-        into_tm_voting_power(params.tm_votes_per_token, stake2),
-        into_tm_voting_power(params.tm_votes_per_token, stake3)
-    );
+    // Insert another validator with a greater stake still 1000 NAM. It should
+    // replace 2nd consensus validator with stake 1, which should become
+    // below-capacity
+    insert_validator(&mut s, &val5, &pk5, stake5, epoch);
+    // Validator `val5` will be added at pipeline offset (2) - epoch 4
+    let val5_epoch = pipeline_epoch;
 
-    update_validator_set(&mut s, &params, &val2, bonds.change(), epoch, None)
-        .unwrap();
-    update_validator_deltas(
-        &mut s,
-        &params,
-        &val2,
-        bonds.change(),
-        epoch,
-        None,
-    )
-    .unwrap();
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    update_validator_set(&mut s, &params, &val3, bonds.change(), epoch, None)
-        .unwrap();
-    update_validator_deltas(
-        &mut s,
-        &params,
-        &val3,
-        bonds.change(),
-        epoch,
-        None,
-    )
-    .unwrap();
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val1 && stake == &stake1 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
+    ));
 
-    // Advance to EPOCH 3
-    let epoch = advance_epoch(&mut s, &params);
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    // Check tendermint validator set updates
-    assert_eq!(bonds_epoch_1, epoch);
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    // `val2` must not be given to tendermint - even though it was in the
-    // consensus set, its voting power was 0, so it wasn't in TM set before the
-    // bond
-    assert!(tm_updates.is_empty());
+    assert_eq!(below_capacity_vals.len(), 2);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
 
-    // Advance to EPOCH 4
+    // Advance to EPOCH 3
     let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
 
     // Check tendermint validator set updates
-    assert_eq!(bonds_epoch_2, epoch);
+    assert_eq!(
+        val3_and_4_epoch, epoch,
+        "val3 and val4 are in the validator sets now"
+    );
     let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    dbg!(&tm_updates);
+    // `val4` is newly added below-capacity, must be skipped in updated in TM
     assert_eq!(tm_updates.len(), 1);
-    // `val2` must not be given to tendermint as it was and still is below
-    // capacity
     assert_eq!(
         tm_updates[0],
         ValidatorSetUpdate::Consensus(ConsensusValidator {
@@ -2261,2178 +2221,5229 @@ fn test_validator_sets_swap() {
             bonded_stake: stake3,
         })
     );
-}
 
-fn get_tendermint_set_updates(
-    s: &TestWlStorage,
-    params: &PosParams,
-    Epoch(epoch): Epoch,
-) -> Vec<ValidatorSetUpdate> {
-    // Because the `validator_set_update_tendermint` is called 2 blocks before
-    // the start of a new epoch, it expects to receive the epoch that is before
-    // the start of a new one too and so we give it the predecessor of the
-    // current epoch here to actually get the update for the current epoch.
-    let epoch = Epoch(epoch - 1);
-    validator_set_update_tendermint(s, params, epoch, |update| update).unwrap()
-}
+    // Insert another validator with a stake 1 NAM. It should be added to the
+    // below-capacity set
+    insert_validator(&mut s, &val6, &pk6, stake6, epoch);
+    // Validator `val6` will be added at pipeline offset (2) - epoch 5
+    let val6_epoch = pipeline_epoch;
 
-/// Advance to the next epoch. Returns the new epoch.
-fn advance_epoch(s: &mut TestWlStorage, params: &PosParams) -> Epoch {
-    s.storage.block.epoch = s.storage.block.epoch.next();
-    let current_epoch = s.storage.block.epoch;
-    compute_and_store_total_consensus_stake(s, current_epoch).unwrap();
-    copy_validator_sets_and_positions(
-        s,
-        params,
-        current_epoch,
-        current_epoch + params.pipeline_len,
-    )
-    .unwrap();
-    // purge_validator_sets_for_old_epoch(s, current_epoch).unwrap();
-    // process_slashes(s, current_epoch).unwrap();
-    // dbg!(current_epoch);
-    current_epoch
-}
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-fn arb_genesis_validators(
-    size: Range<usize>,
-    threshold: Option<token::Amount>,
-) -> impl Strategy<Value = Vec<GenesisValidator>> {
-    let threshold = threshold
-        .unwrap_or_else(|| PosParams::default().validator_stake_threshold);
-    let tokens: Vec<_> = (0..size.end)
-        .map(|ix| {
-            if ix == 0 {
-                // Make sure that at least one validator has at least a stake
-                // greater or equal to the threshold to avoid having an empty
-                // consensus set.
-                threshold.raw_amount().as_u64()..=10_000_000_u64
-            } else {
-                1..=10_000_000_u64
-            }
-            .prop_map(token::Amount::from)
-        })
-        .collect();
-    (size, tokens)
-        .prop_map(|(size, token_amounts)| {
-            // use unique seeds to generate validators' address and consensus
-            // key
-            let seeds = (0_u64..).take(size);
-            seeds
-                .zip(token_amounts)
-                .map(|(seed, tokens)| {
-                    let address = address_from_simple_seed(seed);
-                    let consensus_sk = common_sk_from_simple_seed(seed);
-                    let consensus_key = consensus_sk.to_public();
-
-                    let protocol_sk = common_sk_from_simple_seed(seed);
-                    let protocol_key = protocol_sk.to_public();
+    assert_eq!(below_capacity_vals.len(), 3);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(2)
+    ));
 
-                    let eth_hot_key = key::common::PublicKey::Secp256k1(
-                        key::testing::gen_keypair::<key::secp256k1::SigScheme>(
-                        )
-                        .ref_to(),
-                    );
-                    let eth_cold_key = key::common::PublicKey::Secp256k1(
-                        key::testing::gen_keypair::<key::secp256k1::SigScheme>(
-                        )
-                        .ref_to(),
-                    );
+    // Advance to EPOCH 4
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
 
-                    let commission_rate = Dec::new(5, 2).expect("Test failed");
-                    let max_commission_rate_change =
-                        Dec::new(1, 2).expect("Test failed");
-                    GenesisValidator {
-                        address,
-                        tokens,
-                        consensus_key,
-                        protocol_key,
-                        eth_hot_key,
-                        eth_cold_key,
-                        commission_rate,
-                        max_commission_rate_change,
-                        metadata: Default::default(),
-                    }
-                })
-                .collect()
+    // Check tendermint validator set updates
+    assert_eq!(val5_epoch, epoch, "val5 is in the validator sets now");
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert_eq!(tm_updates.len(), 2);
+    assert_eq!(
+        tm_updates[0],
+        ValidatorSetUpdate::Consensus(ConsensusValidator {
+            consensus_key: pk5,
+            bonded_stake: stake5,
         })
-        .prop_filter(
-            "Must have at least one genesis validator with stake above the \
-             provided threshold, if any.",
-            move |gen_vals: &Vec<GenesisValidator>| {
-                gen_vals.iter().any(|val| val.tokens >= threshold)
-            },
-        )
-}
-
-fn test_unjail_validator_aux(
-    params: OwnedPosParams,
-    mut validators: Vec<GenesisValidator>,
-) {
-    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
-    let mut s = TestWlStorage::default();
-
-    // Find the validator with the most stake and 100x his stake to keep the
-    // cubic slash rate small
-    let num_vals = validators.len();
-    validators.sort_by_key(|a| a.tokens);
-    validators[num_vals - 1].tokens = 100 * validators[num_vals - 1].tokens;
-
-    // Get second highest stake validator tomisbehave
-    let val_addr = &validators[num_vals - 2].address;
-    let val_tokens = validators[num_vals - 2].tokens;
-    println!(
-        "Validator that will misbehave addr {val_addr}, tokens {}",
-        val_tokens.to_string_native()
     );
+    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk2));
 
-    // Genesis
-    let mut current_epoch = s.storage.block.epoch;
-    let params = test_init_genesis(
+    // Unbond some stake from val1, it should be be swapped with the greatest
+    // below-capacity validator val2 into the below-capacity set. The stake of
+    // val1 will go below 1 NAM, which is the validator_stake_threshold, so it
+    // will enter the below-threshold validator set.
+    let unbond = token::Amount::from_uint(500_000, 0).unwrap();
+    let stake1 = stake1 - unbond;
+    println!("val1 {val1} new stake {}", stake1.to_string_native());
+    // Because `update_validator_set` and `update_validator_deltas` are
+    // effective from pipeline offset, we use pipeline epoch for the rest of the
+    // checks
+    update_validator_set(&mut s, &params, &val1, -unbond.change(), epoch, None)
+        .unwrap();
+    update_validator_deltas(
         &mut s,
-        params,
-        validators.clone().into_iter(),
-        current_epoch,
+        &params,
+        &val1,
+        -unbond.change(),
+        epoch,
+        None,
     )
     .unwrap();
-    s.commit_block().unwrap();
+    // Epoch 6
+    let val1_unbond_epoch = pipeline_epoch;
 
-    current_epoch = advance_epoch(&mut s, &params);
-    super::process_slashes(&mut s, current_epoch).unwrap();
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    // Discover first slash
-    let slash_0_evidence_epoch = current_epoch;
-    let evidence_block_height = BlockHeight(0); // doesn't matter for slashing logic
-    let slash_0_type = SlashType::DuplicateVote;
-    slash(
-        &mut s,
-        &params,
-        current_epoch,
-        slash_0_evidence_epoch,
-        evidence_block_height,
-        slash_0_type,
-        val_addr,
-        current_epoch.next(),
-    )
-    .unwrap();
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
+    ));
 
-    assert_eq!(
-        validator_state_handle(val_addr)
-            .get(&s, current_epoch, &params)
-            .unwrap(),
-        Some(ValidatorState::Consensus)
-    );
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    for epoch in Epoch::iter_bounds_inclusive(
-        current_epoch.next(),
-        current_epoch + params.pipeline_len,
-    ) {
-        // Check the validator state
-        assert_eq!(
-            validator_state_handle(val_addr)
-                .get(&s, epoch, &params)
-                .unwrap(),
-            Some(ValidatorState::Jailed)
-        );
-        // Check the validator set positions
-        assert!(
-            validator_set_positions_handle()
-                .at(&epoch)
-                .get(&s, val_addr)
-                .unwrap()
-                .is_none(),
-        );
-    }
+    assert_eq!(below_capacity_vals.len(), 2);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(2)
+    ));
 
-    // Advance past an epoch in which we can unbond
-    let unfreeze_epoch =
-        slash_0_evidence_epoch + params.slash_processing_epoch_offset();
-    while current_epoch < unfreeze_epoch + 4u64 {
-        current_epoch = advance_epoch(&mut s, &params);
-        super::process_slashes(&mut s, current_epoch).unwrap();
-    }
+    let below_threshold_vals =
+        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
 
-    // Unjail the validator
-    unjail_validator(&mut s, val_addr, current_epoch).unwrap();
+    assert_eq!(below_threshold_vals.len(), 1);
+    assert_eq!(&below_threshold_vals[0], &val1);
 
-    // Check the validator state
-    for epoch in
-        Epoch::iter_bounds_inclusive(current_epoch, current_epoch.next())
-    {
-        assert_eq!(
-            validator_state_handle(val_addr)
-                .get(&s, epoch, &params)
-                .unwrap(),
-            Some(ValidatorState::Jailed)
-        );
-    }
-
-    assert_eq!(
-        validator_state_handle(val_addr)
-            .get(&s, current_epoch + params.pipeline_len, &params)
-            .unwrap(),
-        Some(ValidatorState::Consensus)
-    );
-    assert!(
-        validator_set_positions_handle()
-            .at(&(current_epoch + params.pipeline_len))
-            .get(&s, val_addr)
-            .unwrap()
-            .is_some(),
-    );
+    // Advance to EPOCH 5
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
 
-    // Advance another epoch
-    current_epoch = advance_epoch(&mut s, &params);
-    super::process_slashes(&mut s, current_epoch).unwrap();
+    // Check tendermint validator set updates
+    assert_eq!(val6_epoch, epoch, "val6 is in the validator sets now");
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert!(tm_updates.is_empty());
 
-    let second_att = unjail_validator(&mut s, val_addr, current_epoch);
-    assert!(second_att.is_err());
-}
+    // Insert another validator with stake 1 - it should be added to below
+    // capacity set
+    insert_validator(&mut s, &val7, &pk7, stake7, epoch);
+    // Epoch 7
+    let val7_epoch = pipeline_epoch;
 
-/// `iterateBondsUpToAmountTest`
-#[test]
-fn test_find_bonds_to_remove() {
-    let mut storage = TestWlStorage::default();
-    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
-    gov_params.init_storage(&mut storage).unwrap();
-    write_pos_params(&mut storage, &OwnedPosParams::default()).unwrap();
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    let source = established_address_1();
-    let validator = established_address_2();
-    let bond_handle = bond_handle(&source, &validator);
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
+    ));
 
-    let (e1, e2, e6) = (Epoch(1), Epoch(2), Epoch(6));
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    bond_handle
-        .set(&mut storage, token::Amount::from(5), e1, 0)
-        .unwrap();
-    bond_handle
-        .set(&mut storage, token::Amount::from(3), e2, 0)
-        .unwrap();
-    bond_handle
-        .set(&mut storage, token::Amount::from(8), e6, 0)
-        .unwrap();
+    assert_eq!(below_capacity_vals.len(), 3);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(2)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[2],
+        (
+            lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+            },
+            address
+        )
+        if address == &val7 && stake == &stake7 && *position == Position(3)
+    ));
 
-    // Test 1
-    let bonds_for_removal = find_bonds_to_remove(
-        &storage,
-        &bond_handle.get_data_handler(),
-        token::Amount::from(8),
-    )
-    .unwrap();
-    assert_eq!(
-        bonds_for_removal.epochs,
-        vec![e6].into_iter().collect::<BTreeSet<Epoch>>()
-    );
-    assert!(bonds_for_removal.new_entry.is_none());
+    let below_threshold_vals =
+        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
 
-    // Test 2
-    let bonds_for_removal = find_bonds_to_remove(
-        &storage,
-        &bond_handle.get_data_handler(),
-        token::Amount::from(10),
-    )
-    .unwrap();
-    assert_eq!(
-        bonds_for_removal.epochs,
-        vec![e6].into_iter().collect::<BTreeSet<Epoch>>()
-    );
-    assert_eq!(
-        bonds_for_removal.new_entry,
-        Some((Epoch(2), token::Amount::from(1)))
-    );
+    assert_eq!(below_threshold_vals.len(), 1);
+    assert_eq!(&below_threshold_vals[0], &val1);
 
-    // Test 3
-    let bonds_for_removal = find_bonds_to_remove(
-        &storage,
-        &bond_handle.get_data_handler(),
-        token::Amount::from(11),
-    )
-    .unwrap();
-    assert_eq!(
-        bonds_for_removal.epochs,
-        vec![e6, e2].into_iter().collect::<BTreeSet<Epoch>>()
-    );
-    assert!(bonds_for_removal.new_entry.is_none());
+    // Advance to EPOCH 6
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
 
-    // Test 4
-    let bonds_for_removal = find_bonds_to_remove(
-        &storage,
-        &bond_handle.get_data_handler(),
-        token::Amount::from(12),
-    )
-    .unwrap();
-    assert_eq!(
-        bonds_for_removal.epochs,
-        vec![e6, e2].into_iter().collect::<BTreeSet<Epoch>>()
-    );
+    // Check tendermint validator set updates
+    assert_eq!(val1_unbond_epoch, epoch, "val1's unbond is applied now");
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert_eq!(tm_updates.len(), 2);
     assert_eq!(
-        bonds_for_removal.new_entry,
-        Some((Epoch(1), token::Amount::from(4)))
+        tm_updates[0],
+        ValidatorSetUpdate::Consensus(ConsensusValidator {
+            consensus_key: pk4.clone(),
+            bonded_stake: stake4,
+        })
     );
-}
-
-/// `computeModifiedRedelegationTest`
-#[test]
-fn test_compute_modified_redelegation() {
-    let mut storage = TestWlStorage::default();
-    let validator1 = established_address_1();
-    let validator2 = established_address_2();
-    let owner = established_address_3();
-    let outer_epoch = Epoch(0);
-
-    let mut alice = validator1.clone();
-    let mut bob = validator2.clone();
-
-    // Ensure a ranking order of alice > bob
-    if bob > alice {
-        alice = validator2;
-        bob = validator1;
-    }
-    println!("\n\nalice = {}\nbob   = {}\n", &alice, &bob);
+    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk1));
 
-    // Fill redelegated bonds in storage
-    let redelegated_bonds_map = delegator_redelegated_bonds_handle(&owner)
-        .at(&alice)
-        .at(&outer_epoch);
-    redelegated_bonds_map
-        .at(&alice)
-        .insert(&mut storage, Epoch(2), token::Amount::from(6))
-        .unwrap();
-    redelegated_bonds_map
-        .at(&alice)
-        .insert(&mut storage, Epoch(4), token::Amount::from(7))
-        .unwrap();
-    redelegated_bonds_map
-        .at(&bob)
-        .insert(&mut storage, Epoch(1), token::Amount::from(5))
+    // Bond some stake to val6, it should be be swapped with the lowest
+    // consensus validator val2 into the consensus set
+    let bond = token::Amount::from_uint(500_000, 0).unwrap();
+    let stake6 = stake6 + bond;
+    println!("val6 {val6} new stake {}", stake6.to_string_native());
+    update_validator_set(&mut s, &params, &val6, bond.change(), epoch, None)
         .unwrap();
-    redelegated_bonds_map
-        .at(&bob)
-        .insert(&mut storage, Epoch(4), token::Amount::from(7))
+    update_validator_deltas(&mut s, &params, &val6, bond.change(), epoch, None)
         .unwrap();
+    let val6_bond_epoch = pipeline_epoch;
 
-    // Test cases 1 and 2
-    let mr1 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        token::Amount::from(25),
-    )
-    .unwrap();
-    let mr2 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        token::Amount::from(30),
-    )
-    .unwrap();
-
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        ..Default::default()
-    };
-
-    assert_eq!(mr1, exp_mr);
-    assert_eq!(mr2, exp_mr);
-
-    // Test case 3
-    let mr3 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        token::Amount::from(7),
-    )
-    .unwrap();
-
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        validators_to_remove: BTreeSet::from_iter([bob.clone()]),
-        validator_to_modify: Some(bob.clone()),
-        epochs_to_remove: BTreeSet::from_iter([Epoch(4)]),
-        ..Default::default()
-    };
-    assert_eq!(mr3, exp_mr);
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    // Test case 4
-    let mr4 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        token::Amount::from(8),
-    )
-    .unwrap();
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
+    ));
 
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        validators_to_remove: BTreeSet::from_iter([bob.clone()]),
-        validator_to_modify: Some(bob.clone()),
-        epochs_to_remove: BTreeSet::from_iter([Epoch(1), Epoch(4)]),
-        epoch_to_modify: Some(Epoch(1)),
-        new_amount: Some(4.into()),
-    };
-    assert_eq!(mr4, exp_mr);
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    // Test case 5
-    let mr5 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        12.into(),
-    )
-    .unwrap();
+    assert_eq!(below_capacity_vals.len(), 3);
+    dbg!(&below_capacity_vals);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val7 && stake == &stake7 && *position == Position(3)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[2],
+        (
+            lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+            },
+            address
+        )
+        if address == &val4 && stake == &stake4 && *position == Position(4)
+    ));
 
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        validators_to_remove: BTreeSet::from_iter([bob.clone()]),
-        ..Default::default()
-    };
-    assert_eq!(mr5, exp_mr);
+    let below_threshold_vals =
+        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
 
-    // Test case 6
-    let mr6 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        14.into(),
-    )
-    .unwrap();
+    assert_eq!(below_threshold_vals.len(), 1);
+    assert_eq!(&below_threshold_vals[0], &val1);
 
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
-        validator_to_modify: Some(alice.clone()),
-        epochs_to_remove: BTreeSet::from_iter([Epoch(4)]),
-        epoch_to_modify: Some(Epoch(4)),
-        new_amount: Some(5.into()),
-    };
-    assert_eq!(mr6, exp_mr);
+    // Advance to EPOCH 7
+    let epoch = advance_epoch(&mut s, &params);
+    assert_eq!(val7_epoch, epoch, "val6 is in the validator sets now");
 
-    // Test case 7
-    let mr7 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        19.into(),
-    )
-    .unwrap();
+    // Check tendermint validator set updates
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert!(tm_updates.is_empty());
 
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
-        validator_to_modify: Some(alice.clone()),
-        epochs_to_remove: BTreeSet::from_iter([Epoch(4)]),
-        ..Default::default()
-    };
-    assert_eq!(mr7, exp_mr);
+    // Advance to EPOCH 8
+    let epoch = advance_epoch(&mut s, &params);
 
-    // Test case 8
-    let mr8 = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map,
-        Epoch(5),
-        21.into(),
-    )
-    .unwrap();
+    // Check tendermint validator set updates
+    assert_eq!(val6_bond_epoch, epoch, "val5's bond is applied now");
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    dbg!(&tm_updates);
+    assert_eq!(tm_updates.len(), 2);
+    assert_eq!(
+        tm_updates[0],
+        ValidatorSetUpdate::Consensus(ConsensusValidator {
+            consensus_key: pk6,
+            bonded_stake: stake6,
+        })
+    );
+    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk4));
 
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(5)),
-        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob]),
-        validator_to_modify: Some(alice),
-        epochs_to_remove: BTreeSet::from_iter([Epoch(2), Epoch(4)]),
-        epoch_to_modify: Some(Epoch(2)),
-        new_amount: Some(4.into()),
-    };
-    assert_eq!(mr8, exp_mr);
+    // Check that the below-capacity validator set was purged for the old epochs
+    // but that the consensus_validator_set was not
+    let last_epoch = epoch;
+    for e in Epoch::iter_bounds_inclusive(
+        start_epoch,
+        last_epoch
+            .sub_or_default(Epoch(params.validator_sets_retention_len))
+            .sub_or_default(Epoch(1)),
+    ) {
+        assert!(
+            !consensus_validator_set_handle()
+                .at(&e)
+                .is_empty(&s)
+                .unwrap()
+        );
+        assert!(
+            below_capacity_validator_set_handle()
+                .at(&e)
+                .is_empty(&s)
+                .unwrap()
+        );
+    }
 }
 
-/// `computeBondAtEpochTest`
+/// When a consensus set validator with 0 voting power adds a bond in the same
+/// epoch as another below-capacity set validator with 0 power, but who adds
+/// more bonds than the validator who is in the consensus set, they get swapped
+/// in the sets. But if both of their new voting powers are still 0 after
+/// bonding, the newly below-capacity validator must not be given to tendermint
+/// with 0 voting power, because it wasn't it its set before
 #[test]
-fn test_compute_bond_at_epoch() {
-    let mut storage = TestWlStorage::default();
+fn test_validator_sets_swap() {
+    let mut s = TestWlStorage::default();
+    // Only 2 consensus validator slots
     let params = OwnedPosParams {
-        pipeline_len: 2,
-        unbonding_len: 4,
-        cubic_slashing_window_length: 1,
+        max_validator_slots: 2,
+        // Set the stake threshold to 0 so no validators are in the
+        // below-threshold set
+        validator_stake_threshold: token::Amount::zero(),
+        // Set 0.1 votes per token
+        tm_votes_per_token: Dec::new(1, 1).expect("Dec creation failed"),
         ..Default::default()
     };
-    let alice = established_address_1();
-    let bob = established_address_2();
 
-    // Test 1
-    let res = compute_bond_at_epoch(
-        &storage,
-        &params,
-        &bob,
-        12.into(),
-        3.into(),
-        23.into(),
-        Some(&Default::default()),
-    )
-    .unwrap();
+    let addr_seed = "seed";
+    let mut address_gen = EstablishedAddressGen::new(addr_seed);
+    let mut sk_seed = 0;
+    let mut gen_validator = || {
+        let res = (
+            address_gen.generate_address(addr_seed),
+            key::testing::common_sk_from_simple_seed(sk_seed).to_public(),
+        );
+        // bump the sk seed
+        sk_seed += 1;
+        res
+    };
 
-    pretty_assertions::assert_eq!(res, 23.into());
+    // Start with two genesis validators, one with 1 voting power and other 0
+    let epoch = Epoch::default();
+    // 1M voting power
+    let ((val1, pk1), stake1) =
+        (gen_validator(), token::Amount::native_whole(10));
+    // 0 voting power
+    let ((val2, pk2), stake2) =
+        (gen_validator(), token::Amount::from_uint(5, 0).unwrap());
+    // 0 voting power
+    let ((val3, pk3), stake3) =
+        (gen_validator(), token::Amount::from_uint(5, 0).unwrap());
+    println!("val1: {val1}, {pk1}, {}", stake1.to_string_native());
+    println!("val2: {val2}, {pk2}, {}", stake2.to_string_native());
+    println!("val3: {val3}, {pk3}, {}", stake3.to_string_native());
 
-    // Test 2
-    validator_slashes_handle(&bob)
-        .push(
-            &mut storage,
-            Slash {
-                epoch: 4.into(),
-                block_height: 0,
-                r#type: SlashType::DuplicateVote,
-                rate: Dec::one(),
+    let protocol_sk_1 = common_sk_from_simple_seed(0);
+    let protocol_sk_2 = common_sk_from_simple_seed(1);
+
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        [
+            GenesisValidator {
+                address: val1,
+                tokens: stake1,
+                consensus_key: pk1,
+                protocol_key: protocol_sk_1.to_public(),
+                eth_hot_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                eth_cold_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+                max_commission_rate_change: Dec::new(1, 1)
+                    .expect("Dec creation failed"),
+                metadata: Default::default(),
             },
-        )
-        .unwrap();
-    let res = compute_bond_at_epoch(
-        &storage,
-        &params,
-        &bob,
-        12.into(),
-        3.into(),
-        23.into(),
-        Some(&Default::default()),
+            GenesisValidator {
+                address: val2.clone(),
+                tokens: stake2,
+                consensus_key: pk2,
+                protocol_key: protocol_sk_2.to_public(),
+                eth_hot_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                eth_cold_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+                max_commission_rate_change: Dec::new(1, 1)
+                    .expect("Dec creation failed"),
+                metadata: Default::default(),
+            },
+        ]
+        .into_iter(),
+        epoch,
     )
     .unwrap();
 
-    pretty_assertions::assert_eq!(res, 0.into());
+    // A helper to insert a non-genesis validator
+    let insert_validator = |s: &mut TestWlStorage,
+                            addr,
+                            pk: &PublicKey,
+                            stake: token::Amount,
+                            epoch: Epoch| {
+        insert_validator_into_validator_set(
+            s,
+            &params,
+            addr,
+            stake,
+            epoch,
+            params.pipeline_len,
+        )
+        .unwrap();
 
-    // Test 3
-    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
-    let mut redel_bonds = EagerRedelegatedBondsMap::default();
-    redel_bonds.insert(
-        alice.clone(),
-        BTreeMap::from_iter([(Epoch(1), token::Amount::from(5))]),
-    );
-    let res = compute_bond_at_epoch(
-        &storage,
+        update_validator_deltas(s, &params, addr, stake.change(), epoch, None)
+            .unwrap();
+
+        // Set their consensus key (needed for
+        // `validator_set_update_tendermint` fn)
+        validator_consensus_key_handle(addr)
+            .set(s, pk.clone(), epoch, params.pipeline_len)
+            .unwrap();
+    };
+
+    // Advance to EPOCH 1
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
+
+    // Insert another validator with 0 voting power
+    insert_validator(&mut s, &val3, &pk3, stake3, epoch);
+
+    assert_eq!(stake2, stake3);
+
+    // Add 2 bonds, one for val2 and greater one for val3
+    let bonds_epoch_1 = pipeline_epoch;
+    let bond2 = token::Amount::from_uint(1, 0).unwrap();
+    let stake2 = stake2 + bond2;
+    let bond3 = token::Amount::from_uint(4, 0).unwrap();
+    let stake3 = stake3 + bond3;
+
+    assert!(stake2 < stake3);
+    assert_eq!(into_tm_voting_power(params.tm_votes_per_token, stake2), 0);
+    assert_eq!(into_tm_voting_power(params.tm_votes_per_token, stake3), 0);
+
+    update_validator_set(&mut s, &params, &val2, bond2.change(), epoch, None)
+        .unwrap();
+    update_validator_deltas(
+        &mut s,
         &params,
-        &bob,
-        12.into(),
-        3.into(),
-        23.into(),
-        Some(&redel_bonds),
+        &val2,
+        bond2.change(),
+        epoch,
+        None,
     )
     .unwrap();
 
-    pretty_assertions::assert_eq!(res, 23.into());
-
-    // Test 4
-    validator_slashes_handle(&bob)
-        .push(
-            &mut storage,
-            Slash {
-                epoch: 4.into(),
-                block_height: 0,
-                r#type: SlashType::DuplicateVote,
-                rate: Dec::one(),
-            },
-        )
+    update_validator_set(&mut s, &params, &val3, bond3.change(), epoch, None)
         .unwrap();
-    let res = compute_bond_at_epoch(
-        &storage,
+    update_validator_deltas(
+        &mut s,
         &params,
-        &bob,
-        12.into(),
-        3.into(),
-        23.into(),
-        Some(&redel_bonds),
+        &val3,
+        bond3.change(),
+        epoch,
+        None,
     )
     .unwrap();
 
-    pretty_assertions::assert_eq!(res, 0.into());
+    // Advance to EPOCH 2
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
 
-    // Test 5
-    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
-    validator_slashes_handle(&alice)
-        .push(
-            &mut storage,
-            Slash {
-                epoch: 6.into(),
-                block_height: 0,
-                r#type: SlashType::DuplicateVote,
-                rate: Dec::one(),
-            },
-        )
+    // Add 2 more bonds, same amount for `val2` and val3`
+    let bonds_epoch_2 = pipeline_epoch;
+    let bonds = token::Amount::native_whole(1);
+    let stake2 = stake2 + bonds;
+    let stake3 = stake3 + bonds;
+    assert!(stake2 < stake3);
+    assert_eq!(
+        into_tm_voting_power(params.tm_votes_per_token, stake2),
+        into_tm_voting_power(params.tm_votes_per_token, stake3)
+    );
+
+    update_validator_set(&mut s, &params, &val2, bonds.change(), epoch, None)
         .unwrap();
-    let res = compute_bond_at_epoch(
-        &storage,
+    update_validator_deltas(
+        &mut s,
         &params,
-        &bob,
-        12.into(),
-        3.into(),
-        23.into(),
-        Some(&redel_bonds),
+        &val2,
+        bonds.change(),
+        epoch,
+        None,
     )
     .unwrap();
 
-    pretty_assertions::assert_eq!(res, 23.into());
-
-    // Test 6
-    validator_slashes_handle(&alice).pop(&mut storage).unwrap();
-    validator_slashes_handle(&alice)
-        .push(
-            &mut storage,
-            Slash {
-                epoch: 4.into(),
-                block_height: 0,
-                r#type: SlashType::DuplicateVote,
-                rate: Dec::one(),
-            },
-        )
+    update_validator_set(&mut s, &params, &val3, bonds.change(), epoch, None)
         .unwrap();
-    let res = compute_bond_at_epoch(
-        &storage,
+    update_validator_deltas(
+        &mut s,
         &params,
-        &bob,
-        18.into(),
-        9.into(),
-        23.into(),
-        Some(&redel_bonds),
+        &val3,
+        bonds.change(),
+        epoch,
+        None,
     )
     .unwrap();
 
-    pretty_assertions::assert_eq!(res, 18.into());
-}
+    // Advance to EPOCH 3
+    let epoch = advance_epoch(&mut s, &params);
 
-/// `computeSlashBondAtEpochTest`
-#[test]
-fn test_compute_slash_bond_at_epoch() {
-    let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        pipeline_len: 2,
-        unbonding_len: 4,
-        cubic_slashing_window_length: 1,
-        ..Default::default()
-    };
-    let alice = established_address_1();
-    let bob = established_address_2();
+    // Check tendermint validator set updates
+    assert_eq!(bonds_epoch_1, epoch);
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    // `val2` must not be given to tendermint - even though it was in the
+    // consensus set, its voting power was 0, so it wasn't in TM set before the
+    // bond
+    assert!(tm_updates.is_empty());
 
-    let current_epoch = Epoch(20);
-    let infraction_epoch =
-        current_epoch - params.slash_processing_epoch_offset();
+    // Advance to EPOCH 4
+    let epoch = advance_epoch(&mut s, &params);
 
-    let redelegated_bond = BTreeMap::from_iter([(
-        alice,
-        BTreeMap::from_iter([(infraction_epoch - 4, token::Amount::from(10))]),
-    )]);
+    // Check tendermint validator set updates
+    assert_eq!(bonds_epoch_2, epoch);
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    dbg!(&tm_updates);
+    assert_eq!(tm_updates.len(), 1);
+    // `val2` must not be given to tendermint as it was and still is below
+    // capacity
+    assert_eq!(
+        tm_updates[0],
+        ValidatorSetUpdate::Consensus(ConsensusValidator {
+            consensus_key: pk3,
+            bonded_stake: stake3,
+        })
+    );
+}
 
-    // Test 1
-    let res = compute_slash_bond_at_epoch(
-        &storage,
-        &params,
-        &bob,
-        current_epoch.next(),
-        infraction_epoch,
-        infraction_epoch - 2,
-        30.into(),
-        Some(&Default::default()),
-        Dec::one(),
+fn get_tendermint_set_updates(
+    s: &TestWlStorage,
+    params: &PosParams,
+    Epoch(epoch): Epoch,
+) -> Vec<ValidatorSetUpdate> {
+    // Because the `validator_set_update_tendermint` is called 2 blocks before
+    // the start of a new epoch, it expects to receive the epoch that is before
+    // the start of a new one too and so we give it the predecessor of the
+    // current epoch here to actually get the update for the current epoch.
+    let epoch = Epoch(epoch - 1);
+    validator_set_update_tendermint(
+        s,
+        params,
+        epoch,
+        &ConsensusValidatorSetCache::default(),
+        |update| update,
     )
-    .unwrap();
-
-    pretty_assertions::assert_eq!(res, 30.into());
+    .unwrap()
+}
 
-    // Test 2
-    let res = compute_slash_bond_at_epoch(
-        &storage,
-        &params,
-        &bob,
-        current_epoch.next(),
-        infraction_epoch,
-        infraction_epoch - 2,
-        30.into(),
-        Some(&redelegated_bond),
-        Dec::one(),
+/// Advance to the next epoch. Returns the new epoch.
+fn advance_epoch(s: &mut TestWlStorage, params: &PosParams) -> Epoch {
+    s.storage.block.epoch = s.storage.block.epoch.next();
+    let current_epoch = s.storage.block.epoch;
+    compute_and_store_total_consensus_stake(s, current_epoch).unwrap();
+    copy_validator_sets_and_positions(
+        s,
+        params,
+        current_epoch,
+        current_epoch + params.pipeline_len,
     )
     .unwrap();
+    // purge_validator_sets_for_old_epoch(s, current_epoch).unwrap();
+    // process_slashes(s, current_epoch).unwrap();
+    // dbg!(current_epoch);
+    current_epoch
+}
 
-    pretty_assertions::assert_eq!(res, 30.into());
+fn arb_genesis_validators(
+    size: Range<usize>,
+    threshold: Option<token::Amount>,
+) -> impl Strategy<Value = Vec<GenesisValidator>> {
+    let threshold = threshold
+        .unwrap_or_else(|| PosParams::default().validator_stake_threshold);
+    let tokens: Vec<_> = (0..size.end)
+        .map(|ix| {
+            if ix == 0 {
+                // Make sure that at least one validator has at least a stake
+                // greater or equal to the threshold to avoid having an empty
+                // consensus set.
+                threshold.raw_amount().as_u64()..=10_000_000_u64
+            } else {
+                1..=10_000_000_u64
+            }
+            .prop_map(token::Amount::from)
+        })
+        .collect();
+    (size, tokens)
+        .prop_map(|(size, token_amounts)| {
+            // use unique seeds to generate validators' address and consensus
+            // key
+            let seeds = (0_u64..).take(size);
+            seeds
+                .zip(token_amounts)
+                .map(|(seed, tokens)| {
+                    let address = address_from_simple_seed(seed);
+                    let consensus_sk = common_sk_from_simple_seed(seed);
+                    let consensus_key = consensus_sk.to_public();
 
-    // Test 3
-    validator_slashes_handle(&bob)
-        .push(
-            &mut storage,
-            Slash {
-                epoch: infraction_epoch.prev(),
-                block_height: 0,
-                r#type: SlashType::DuplicateVote,
-                rate: Dec::one(),
+                    let protocol_sk = common_sk_from_simple_seed(seed);
+                    let protocol_key = protocol_sk.to_public();
+
+                    let eth_hot_key = key::common::PublicKey::Secp256k1(
+                        key::testing::gen_keypair::<key::secp256k1::SigScheme>(
+                        )
+                        .ref_to(),
+                    );
+                    let eth_cold_key = key::common::PublicKey::Secp256k1(
+                        key::testing::gen_keypair::<key::secp256k1::SigScheme>(
+                        )
+                        .ref_to(),
+                    );
+
+                    let commission_rate = Dec::new(5, 2).expect("Test failed");
+                    let max_commission_rate_change =
+                        Dec::new(1, 2).expect("Test failed");
+                    GenesisValidator {
+                        address,
+                        tokens,
+                        consensus_key,
+                        protocol_key,
+                        eth_hot_key,
+                        eth_cold_key,
+                        commission_rate,
+                        max_commission_rate_change,
+                        metadata: Default::default(),
+                    }
+                })
+                .collect()
+        })
+        .prop_filter(
+            "Must have at least one genesis validator with stake above the \
+             provided threshold, if any.",
+            move |gen_vals: &Vec<GenesisValidator>| {
+                gen_vals.iter().any(|val| val.tokens >= threshold)
             },
         )
-        .unwrap();
-    let res = compute_slash_bond_at_epoch(
-        &storage,
-        &params,
-        &bob,
-        current_epoch.next(),
-        infraction_epoch,
-        infraction_epoch - 2,
-        30.into(),
-        Some(&Default::default()),
-        Dec::one(),
-    )
-    .unwrap();
+}
 
-    pretty_assertions::assert_eq!(res, 0.into());
+fn test_unjail_validator_aux(
+    params: OwnedPosParams,
+    mut validators: Vec<GenesisValidator>,
+) {
+    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    let mut s = TestWlStorage::default();
 
-    // Test 4
-    let res = compute_slash_bond_at_epoch(
-        &storage,
-        &params,
-        &bob,
-        current_epoch.next(),
-        infraction_epoch,
-        infraction_epoch - 2,
-        30.into(),
-        Some(&redelegated_bond),
-        Dec::one(),
+    // Find the validator with the most stake and 100x his stake to keep the
+    // cubic slash rate small
+    let num_vals = validators.len();
+    validators.sort_by_key(|a| a.tokens);
+    validators[num_vals - 1].tokens = 100 * validators[num_vals - 1].tokens;
+
+    // Get second highest stake validator tomisbehave
+    let val_addr = &validators[num_vals - 2].address;
+    let val_tokens = validators[num_vals - 2].tokens;
+    println!(
+        "Validator that will misbehave addr {val_addr}, tokens {}",
+        val_tokens.to_string_native()
+    );
+
+    // Genesis
+    let mut current_epoch = s.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
     )
     .unwrap();
+    s.commit_block().unwrap();
 
-    pretty_assertions::assert_eq!(res, 0.into());
-}
-
-/// `computeNewRedelegatedUnbondsTest`
-#[test]
-fn test_compute_new_redelegated_unbonds() {
-    let mut storage = TestWlStorage::default();
-    let alice = established_address_1();
-    let bob = established_address_2();
+    current_epoch = advance_epoch(&mut s, &params);
+    super::process_slashes(&mut s, current_epoch).unwrap();
 
-    let key = Key::parse("testing").unwrap();
-    let redelegated_bonds = NestedMap::<Epoch, RedelegatedTokens>::open(key);
+    // Discover first slash
+    let slash_0_evidence_epoch = current_epoch;
+    let evidence_block_height = BlockHeight(0); // doesn't matter for slashing logic
+    let slash_0_type = SlashType::DuplicateVote;
+    let ((), changes) =
+        with_state_changes(&mut s, ValidatorStateChangeCause::Slash, |s| {
+            slash(
+                s,
+                &params,
+                current_epoch,
+                slash_0_evidence_epoch,
+                evidence_block_height,
+                slash_0_type,
+                val_addr,
+                current_epoch.next(),
+            )
+        })
+        .unwrap();
 
-    // Populate the lazy and eager maps
-    let (ep1, ep2, ep4, ep5, ep6, ep7) =
-        (Epoch(1), Epoch(2), Epoch(4), Epoch(5), Epoch(6), Epoch(7));
-    let keys_and_values = vec![
-        (ep5, alice.clone(), ep2, 1),
-        (ep5, alice.clone(), ep4, 1),
-        (ep7, alice.clone(), ep2, 1),
-        (ep7, alice.clone(), ep4, 1),
-        (ep5, bob.clone(), ep1, 1),
-        (ep5, bob.clone(), ep4, 2),
-        (ep7, bob.clone(), ep1, 1),
-        (ep7, bob.clone(), ep4, 2),
-    ];
-    let mut eager_map = BTreeMap::<Epoch, EagerRedelegatedBondsMap>::new();
-    for (outer_ep, address, inner_ep, amount) in keys_and_values {
-        redelegated_bonds
-            .at(&outer_ep)
-            .at(&address)
-            .insert(&mut storage, inner_ep, token::Amount::from(amount))
-            .unwrap();
-        eager_map
-            .entry(outer_ep)
-            .or_default()
-            .entry(address.clone())
-            .or_default()
-            .insert(inner_ep, token::Amount::from(amount));
+    assert_eq!(
+        validator_state_handle(val_addr)
+            .get(&s, current_epoch, &params)
+            .unwrap(),
+        Some(ValidatorState::Consensus)
+    );
+
+    // The jailing is recorded for every epoch up to the pipeline epoch
+    let jailed_epochs: Vec<Epoch> = changes
+        .iter()
+        .filter(|change| &change.validator == val_addr)
+        .map(|change| {
+            assert_eq!(change.from, Some(ValidatorState::Consensus));
+            assert_eq!(change.to, ValidatorState::Jailed);
+            assert_eq!(change.cause, ValidatorStateChangeCause::Slash);
+            change.epoch
+        })
+        .collect();
+    assert_eq!(
+        jailed_epochs,
+        Epoch::iter_bounds_inclusive(
+            current_epoch.next(),
+            current_epoch + params.pipeline_len
+        )
+        .collect::<Vec<_>>()
+    );
+
+    for epoch in Epoch::iter_bounds_inclusive(
+        current_epoch.next(),
+        current_epoch + params.pipeline_len,
+    ) {
+        // Check the validator state
+        assert_eq!(
+            validator_state_handle(val_addr)
+                .get(&s, epoch, &params)
+                .unwrap(),
+            Some(ValidatorState::Jailed)
+        );
+        // Check the validator set positions
+        assert!(
+            validator_set_positions_handle()
+                .at(&epoch)
+                .get(&s, val_addr)
+                .unwrap()
+                .is_none(),
+        );
     }
 
-    // Different ModifiedRedelegation objects for testing
-    let empty_mr = ModifiedRedelegation::default();
-    let all_mr = ModifiedRedelegation {
-        epoch: Some(ep7),
-        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
-        validator_to_modify: None,
-        epochs_to_remove: Default::default(),
-        epoch_to_modify: None,
-        new_amount: None,
-    };
-    let mod_val_mr = ModifiedRedelegation {
-        epoch: Some(ep7),
-        validators_to_remove: BTreeSet::from_iter([alice.clone()]),
-        validator_to_modify: None,
-        epochs_to_remove: Default::default(),
-        epoch_to_modify: None,
-        new_amount: None,
-    };
-    let mod_val_partial_mr = ModifiedRedelegation {
-        epoch: Some(ep7),
-        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
-        validator_to_modify: Some(bob.clone()),
-        epochs_to_remove: BTreeSet::from_iter([ep1]),
-        epoch_to_modify: None,
-        new_amount: None,
-    };
-    let mod_epoch_partial_mr = ModifiedRedelegation {
-        epoch: Some(ep7),
-        validators_to_remove: BTreeSet::from_iter([alice, bob.clone()]),
-        validator_to_modify: Some(bob.clone()),
-        epochs_to_remove: BTreeSet::from_iter([ep1, ep4]),
-        epoch_to_modify: Some(ep4),
-        new_amount: Some(token::Amount::from(1)),
-    };
+    // Advance past an epoch in which we can unbond
+    let unfreeze_epoch =
+        slash_0_evidence_epoch + params.slash_processing_epoch_offset();
+    while current_epoch < unfreeze_epoch + 4u64 {
+        current_epoch = advance_epoch(&mut s, &params);
+        super::process_slashes(&mut s, current_epoch).unwrap();
+    }
 
-    // Test case 1
-    let res = compute_new_redelegated_unbonds(
-        &storage,
-        &redelegated_bonds,
-        &Default::default(),
-        &empty_mr,
-    )
-    .unwrap();
-    assert_eq!(res, Default::default());
+    // Unjail the validator
+    let ((), changes) =
+        with_state_changes(&mut s, ValidatorStateChangeCause::Unjail, |s| {
+            unjail_validator(s, val_addr, current_epoch)
+        })
+        .unwrap();
+    assert!(changes.contains(&ValidatorStateChange {
+        validator: val_addr.clone(),
+        epoch: current_epoch + params.pipeline_len,
+        from: Some(ValidatorState::Jailed),
+        to: ValidatorState::Consensus,
+        cause: ValidatorStateChangeCause::Unjail,
+    }));
 
-    let set5 = BTreeSet::<Epoch>::from_iter([ep5]);
-    let set56 = BTreeSet::<Epoch>::from_iter([ep5, ep6]);
+    // Check the validator state
+    for epoch in
+        Epoch::iter_bounds_inclusive(current_epoch, current_epoch.next())
+    {
+        assert_eq!(
+            validator_state_handle(val_addr)
+                .get(&s, epoch, &params)
+                .unwrap(),
+            Some(ValidatorState::Jailed)
+        );
+    }
 
-    // Test case 2
-    let res = compute_new_redelegated_unbonds(
-        &storage,
-        &redelegated_bonds,
-        &set5,
-        &empty_mr,
-    )
-    .unwrap();
-    let mut exp_res = eager_map.clone();
-    exp_res.remove(&ep7);
-    assert_eq!(res, exp_res);
+    assert_eq!(
+        validator_state_handle(val_addr)
+            .get(&s, current_epoch + params.pipeline_len, &params)
+            .unwrap(),
+        Some(ValidatorState::Consensus)
+    );
+    assert!(
+        validator_set_positions_handle()
+            .at(&(current_epoch + params.pipeline_len))
+            .get(&s, val_addr)
+            .unwrap()
+            .is_some(),
+    );
 
-    // Test case 3
-    let res = compute_new_redelegated_unbonds(
-        &storage,
-        &redelegated_bonds,
-        &set56,
-        &empty_mr,
-    )
-    .unwrap();
-    assert_eq!(res, exp_res);
+    // Advance another epoch
+    current_epoch = advance_epoch(&mut s, &params);
+    super::process_slashes(&mut s, current_epoch).unwrap();
 
-    // Test case 4
-    println!("\nTEST CASE 4\n");
-    let res = compute_new_redelegated_unbonds(
+    let second_att = unjail_validator(&mut s, val_addr, current_epoch);
+    assert!(second_att.is_err());
+}
+
+/// `iterateBondsUpToAmountTest`
+#[test]
+fn test_find_bonds_to_remove() {
+    let mut storage = TestWlStorage::default();
+    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
+    gov_params.init_storage(&mut storage).unwrap();
+    write_pos_params(&mut storage, &OwnedPosParams::default()).unwrap();
+
+    let source = established_address_1();
+    let validator = established_address_2();
+    let bond_handle = bond_handle(&source, &validator);
+
+    let (e1, e2, e6) = (Epoch(1), Epoch(2), Epoch(6));
+
+    bond_handle
+        .set(&mut storage, token::Amount::from(5), e1, 0)
+        .unwrap();
+    bond_handle
+        .set(&mut storage, token::Amount::from(3), e2, 0)
+        .unwrap();
+    bond_handle
+        .set(&mut storage, token::Amount::from(8), e6, 0)
+        .unwrap();
+
+    // Test 1
+    let bonds_for_removal = find_bonds_to_remove(
         &storage,
-        &redelegated_bonds,
-        &set56,
-        &all_mr,
+        &bond_handle.get_data_handler(),
+        token::Amount::from(8),
     )
     .unwrap();
-    assert_eq!(res, eager_map);
+    assert_eq!(
+        bonds_for_removal.epochs,
+        vec![e6].into_iter().collect::<BTreeSet<Epoch>>()
+    );
+    assert!(bonds_for_removal.new_entry.is_none());
 
-    // Test case 5
-    let res = compute_new_redelegated_unbonds(
+    // Test 2
+    let bonds_for_removal = find_bonds_to_remove(
         &storage,
-        &redelegated_bonds,
-        &set56,
-        &mod_val_mr,
+        &bond_handle.get_data_handler(),
+        token::Amount::from(10),
     )
     .unwrap();
-    exp_res = eager_map.clone();
-    exp_res.entry(ep7).or_default().remove(&bob);
-    assert_eq!(res, exp_res);
+    assert_eq!(
+        bonds_for_removal.epochs,
+        vec![e6].into_iter().collect::<BTreeSet<Epoch>>()
+    );
+    assert_eq!(
+        bonds_for_removal.new_entry,
+        Some((Epoch(2), token::Amount::from(1)))
+    );
 
-    // Test case 6
-    let res = compute_new_redelegated_unbonds(
+    // Test 3
+    let bonds_for_removal = find_bonds_to_remove(
         &storage,
-        &redelegated_bonds,
-        &set56,
-        &mod_val_partial_mr,
+        &bond_handle.get_data_handler(),
+        token::Amount::from(11),
     )
     .unwrap();
-    exp_res = eager_map.clone();
-    exp_res
-        .entry(ep7)
-        .or_default()
-        .entry(bob.clone())
-        .or_default()
-        .remove(&ep4);
-    assert_eq!(res, exp_res);
+    assert_eq!(
+        bonds_for_removal.epochs,
+        vec![e6, e2].into_iter().collect::<BTreeSet<Epoch>>()
+    );
+    assert!(bonds_for_removal.new_entry.is_none());
 
-    // Test case 7
-    let res = compute_new_redelegated_unbonds(
+    // Test 4
+    let bonds_for_removal = find_bonds_to_remove(
         &storage,
-        &redelegated_bonds,
-        &set56,
-        &mod_epoch_partial_mr,
+        &bond_handle.get_data_handler(),
+        token::Amount::from(12),
     )
     .unwrap();
-    exp_res
-        .entry(ep7)
-        .or_default()
-        .entry(bob)
-        .or_default()
-        .insert(ep4, token::Amount::from(1));
-    assert_eq!(res, exp_res);
+    assert_eq!(
+        bonds_for_removal.epochs,
+        vec![e6, e2].into_iter().collect::<BTreeSet<Epoch>>()
+    );
+    assert_eq!(
+        bonds_for_removal.new_entry,
+        Some((Epoch(1), token::Amount::from(4)))
+    );
 }
 
-/// `applyListSlashesTest`
+/// `computeModifiedRedelegationTest`
 #[test]
-fn test_apply_list_slashes() {
-    let init_epoch = Epoch(2);
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
-    // let unbonding_len = 4u64;
-    // let cubic_offset = 1u64;
+fn test_compute_modified_redelegation() {
+    let mut storage = TestWlStorage::default();
+    let validator1 = established_address_1();
+    let validator2 = established_address_2();
+    let owner = established_address_3();
+    let outer_epoch = Epoch(0);
 
-    let slash1 = Slash {
-        epoch: init_epoch,
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-    let slash2 = Slash {
-        epoch: init_epoch
-            + params.unbonding_len
-            + params.cubic_slashing_window_length
-            + 1u64,
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
+    let mut alice = validator1.clone();
+    let mut bob = validator2.clone();
 
-    let list1 = vec![slash1.clone()];
-    let list2 = vec![slash1.clone(), slash2.clone()];
-    let list3 = vec![slash1.clone(), slash1.clone()];
-    let list4 = vec![slash1.clone(), slash1, slash2];
+    // Ensure a ranking order of alice > bob
+    if bob > alice {
+        alice = validator2;
+        bob = validator1;
+    }
+    println!("\n\nalice = {}\nbob   = {}\n", &alice, &bob);
 
-    let res = apply_list_slashes(&params, &[], token::Amount::from(100));
-    assert_eq!(res, token::Amount::from(100));
+    // Fill redelegated bonds in storage
+    let redelegated_bonds_map = delegator_redelegated_bonds_handle(&owner)
+        .at(&alice)
+        .at(&outer_epoch);
+    redelegated_bonds_map
+        .at(&alice)
+        .insert(&mut storage, Epoch(2), token::Amount::from(6))
+        .unwrap();
+    redelegated_bonds_map
+        .at(&alice)
+        .insert(&mut storage, Epoch(4), token::Amount::from(7))
+        .unwrap();
+    redelegated_bonds_map
+        .at(&bob)
+        .insert(&mut storage, Epoch(1), token::Amount::from(5))
+        .unwrap();
+    redelegated_bonds_map
+        .at(&bob)
+        .insert(&mut storage, Epoch(4), token::Amount::from(7))
+        .unwrap();
 
-    let res = apply_list_slashes(&params, &list1, token::Amount::from(100));
-    assert_eq!(res, token::Amount::zero());
+    // Test cases 1 and 2
+    let mr1 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        token::Amount::from(25),
+    )
+    .unwrap();
+    let mr2 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        token::Amount::from(30),
+    )
+    .unwrap();
 
-    let res = apply_list_slashes(&params, &list2, token::Amount::from(100));
-    assert_eq!(res, token::Amount::zero());
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        ..Default::default()
+    };
 
-    let res = apply_list_slashes(&params, &list3, token::Amount::from(100));
-    assert_eq!(res, token::Amount::zero());
+    assert_eq!(mr1, exp_mr);
+    assert_eq!(mr2, exp_mr);
 
-    let res = apply_list_slashes(&params, &list4, token::Amount::from(100));
-    assert_eq!(res, token::Amount::zero());
-}
+    // Test case 3
+    let mr3 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        token::Amount::from(7),
+    )
+    .unwrap();
 
-/// `computeSlashableAmountTest`
-#[test]
-fn test_compute_slashable_amount() {
-    let init_epoch = Epoch(2);
-    let params = OwnedPosParams {
-        unbonding_len: 4,
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        validators_to_remove: BTreeSet::from_iter([bob.clone()]),
+        validator_to_modify: Some(bob.clone()),
+        epochs_to_remove: BTreeSet::from_iter([Epoch(4)]),
         ..Default::default()
     };
+    assert_eq!(mr3, exp_mr);
 
-    let slash1 = Slash {
-        epoch: init_epoch
-            + params.unbonding_len
-            + params.cubic_slashing_window_length,
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
+    // Test case 4
+    let mr4 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        token::Amount::from(8),
+    )
+    .unwrap();
+
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        validators_to_remove: BTreeSet::from_iter([bob.clone()]),
+        validator_to_modify: Some(bob.clone()),
+        epochs_to_remove: BTreeSet::from_iter([Epoch(1), Epoch(4)]),
+        epoch_to_modify: Some(Epoch(1)),
+        new_amount: Some(4.into()),
     };
+    assert_eq!(mr4, exp_mr);
 
-    let slash2 = Slash {
-        epoch: init_epoch
-            + params.unbonding_len
-            + params.cubic_slashing_window_length
-            + 1u64,
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
+    // Test case 5
+    let mr5 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        12.into(),
+    )
+    .unwrap();
+
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        validators_to_remove: BTreeSet::from_iter([bob.clone()]),
+        ..Default::default()
     };
+    assert_eq!(mr5, exp_mr);
 
-    let test_map = vec![(init_epoch, token::Amount::from(50))]
-        .into_iter()
-        .collect::<BTreeMap<_, _>>();
+    // Test case 6
+    let mr6 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        14.into(),
+    )
+    .unwrap();
 
-    let res = compute_slashable_amount(
-        &params,
-        &slash1,
-        token::Amount::from(100),
-        &BTreeMap::new(),
-    );
-    assert_eq!(res, token::Amount::from(100));
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
+        validator_to_modify: Some(alice.clone()),
+        epochs_to_remove: BTreeSet::from_iter([Epoch(4)]),
+        epoch_to_modify: Some(Epoch(4)),
+        new_amount: Some(5.into()),
+    };
+    assert_eq!(mr6, exp_mr);
 
-    let res = compute_slashable_amount(
-        &params,
-        &slash2,
-        token::Amount::from(100),
-        &test_map,
-    );
-    assert_eq!(res, token::Amount::from(50));
+    // Test case 7
+    let mr7 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        19.into(),
+    )
+    .unwrap();
 
-    let res = compute_slashable_amount(
-        &params,
-        &slash1,
-        token::Amount::from(100),
-        &test_map,
-    );
-    assert_eq!(res, token::Amount::from(100));
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
+        validator_to_modify: Some(alice.clone()),
+        epochs_to_remove: BTreeSet::from_iter([Epoch(4)]),
+        ..Default::default()
+    };
+    assert_eq!(mr7, exp_mr);
+
+    // Test case 8
+    let mr8 = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map,
+        Epoch(5),
+        21.into(),
+    )
+    .unwrap();
+
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(5)),
+        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob]),
+        validator_to_modify: Some(alice),
+        epochs_to_remove: BTreeSet::from_iter([Epoch(2), Epoch(4)]),
+        epoch_to_modify: Some(Epoch(2)),
+        new_amount: Some(4.into()),
+    };
+    assert_eq!(mr8, exp_mr);
 }
 
-/// `foldAndSlashRedelegatedBondsMapTest`
+/// `computeBondAtEpochTest`
 #[test]
-fn test_fold_and_slash_redelegated_bonds() {
+fn test_compute_bond_at_epoch() {
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
+        pipeline_len: 2,
         unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        cubic_slashing_window_length: 1,
         ..Default::default()
     };
-    let start_epoch = Epoch(7);
-
     let alice = established_address_1();
     let bob = established_address_2();
 
-    println!("\n\nAlice: {}", alice);
-    println!("Bob: {}\n", bob);
-
-    let test_slash = Slash {
-        epoch: Default::default(),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-
-    let test_data = vec![
-        (alice.clone(), vec![(2, 1), (4, 1)]),
-        (bob, vec![(1, 1), (4, 2)]),
-    ];
-    let mut eager_redel_bonds = EagerRedelegatedBondsMap::default();
-    for (address, pair) in test_data {
-        for (epoch, amount) in pair {
-            eager_redel_bonds
-                .entry(address.clone())
-                .or_default()
-                .insert(Epoch(epoch), token::Amount::from(amount));
-        }
-    }
-
-    // Test case 1
-    let res = fold_and_slash_redelegated_bonds(
-        &storage,
-        &params,
-        &eager_redel_bonds,
-        start_epoch,
-        &[],
-        |_| true,
-    );
-    assert_eq!(
-        res,
-        FoldRedelegatedBondsResult {
-            total_redelegated: token::Amount::from(5),
-            total_after_slashing: token::Amount::from(5),
-        }
-    );
-
-    // Test case 2
-    let res = fold_and_slash_redelegated_bonds(
-        &storage,
-        &params,
-        &eager_redel_bonds,
-        start_epoch,
-        &[test_slash],
-        |_| true,
-    );
-    assert_eq!(
-        res,
-        FoldRedelegatedBondsResult {
-            total_redelegated: token::Amount::from(5),
-            total_after_slashing: token::Amount::zero(),
-        }
-    );
-
-    // Test case 3
-    let alice_slash = Slash {
-        epoch: Epoch(6),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-    validator_slashes_handle(&alice)
-        .push(&mut storage, alice_slash)
-        .unwrap();
-
-    let res = fold_and_slash_redelegated_bonds(
+    // Test 1
+    let res = compute_bond_at_epoch(
         &storage,
         &params,
-        &eager_redel_bonds,
-        start_epoch,
-        &[],
-        |_| true,
-    );
-    assert_eq!(
-        res,
-        FoldRedelegatedBondsResult {
-            total_redelegated: token::Amount::from(5),
-            total_after_slashing: token::Amount::from(3),
-        }
-    );
-}
+        &bob,
+        12.into(),
+        3.into(),
+        23.into(),
+        Some(&Default::default()),
+    )
+    .unwrap();
 
-/// `slashRedelegationTest`
-#[test]
-fn test_slash_redelegation() {
-    let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
-    let alice = established_address_1();
+    pretty_assertions::assert_eq!(res, 23.into());
 
-    let total_redelegated_unbonded =
-        validator_total_redelegated_unbonded_handle(&alice);
-    total_redelegated_unbonded
-        .at(&Epoch(13))
-        .at(&Epoch(10))
-        .at(&alice)
-        .insert(&mut storage, Epoch(7), token::Amount::from(2))
+    // Test 2
+    validator_slashes_handle(&bob)
+        .push(
+            &mut storage,
+            Slash {
+                epoch: 4.into(),
+                block_height: 0,
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::one(),
+            },
+        )
         .unwrap();
-
-    let slashes = validator_slashes_handle(&alice);
-
-    let mut slashed_amounts_map = BTreeMap::from_iter([
-        (Epoch(15), token::Amount::zero()),
-        (Epoch(16), token::Amount::zero()),
-    ]);
-    let empty_slash_amounts = slashed_amounts_map.clone();
-
-    // Test case 1
-    slash_redelegation(
+    let res = compute_bond_at_epoch(
         &storage,
         &params,
-        token::Amount::from(7),
-        Epoch(7),
-        Epoch(10),
-        &alice,
-        Epoch(14),
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
+        &bob,
+        12.into(),
+        3.into(),
+        23.into(),
+        Some(&Default::default()),
     )
     .unwrap();
-    assert_eq!(
-        slashed_amounts_map,
-        BTreeMap::from_iter([
-            (Epoch(15), token::Amount::from(5)),
-            (Epoch(16), token::Amount::from(5)),
-        ])
-    );
 
-    // Test case 2
-    slashed_amounts_map = empty_slash_amounts.clone();
-    slash_redelegation(
-        &storage,
-        &params,
-        token::Amount::from(7),
-        Epoch(7),
-        Epoch(11),
-        &alice,
-        Epoch(14),
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
-    )
-    .unwrap();
-    assert_eq!(
-        slashed_amounts_map,
-        BTreeMap::from_iter([
-            (Epoch(15), token::Amount::from(7)),
-            (Epoch(16), token::Amount::from(7)),
-        ])
-    );
+    pretty_assertions::assert_eq!(res, 0.into());
 
-    // Test case 3
-    slashed_amounts_map = BTreeMap::from_iter([
-        (Epoch(15), token::Amount::from(2)),
-        (Epoch(16), token::Amount::from(3)),
-    ]);
-    slash_redelegation(
+    // Test 3
+    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
+    let mut redel_bonds = EagerRedelegatedBondsMap::default();
+    redel_bonds.insert(
+        alice.clone(),
+        BTreeMap::from_iter([(Epoch(1), token::Amount::from(5))]),
+    );
+    let res = compute_bond_at_epoch(
         &storage,
         &params,
-        token::Amount::from(7),
-        Epoch(7),
-        Epoch(10),
-        &alice,
-        Epoch(14),
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
+        &bob,
+        12.into(),
+        3.into(),
+        23.into(),
+        Some(&redel_bonds),
     )
     .unwrap();
-    assert_eq!(
-        slashed_amounts_map,
-        BTreeMap::from_iter([
-            (Epoch(15), token::Amount::from(7)),
-            (Epoch(16), token::Amount::from(8)),
-        ])
-    );
 
-    // Test case 4
-    slashes
+    pretty_assertions::assert_eq!(res, 23.into());
+
+    // Test 4
+    validator_slashes_handle(&bob)
         .push(
             &mut storage,
             Slash {
-                epoch: Epoch(8),
-                block_height: Default::default(),
+                epoch: 4.into(),
+                block_height: 0,
                 r#type: SlashType::DuplicateVote,
                 rate: Dec::one(),
             },
         )
         .unwrap();
-    slashed_amounts_map = empty_slash_amounts.clone();
-    slash_redelegation(
+    let res = compute_bond_at_epoch(
         &storage,
         &params,
-        token::Amount::from(7),
-        Epoch(7),
-        Epoch(10),
-        &alice,
-        Epoch(14),
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
+        &bob,
+        12.into(),
+        3.into(),
+        23.into(),
+        Some(&redel_bonds),
     )
     .unwrap();
-    assert_eq!(slashed_amounts_map, empty_slash_amounts);
 
-    // Test case 5
-    slashes.pop(&mut storage).unwrap();
-    slashes
+    pretty_assertions::assert_eq!(res, 0.into());
+
+    // Test 5
+    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
+    validator_slashes_handle(&alice)
         .push(
             &mut storage,
             Slash {
-                epoch: Epoch(9),
-                block_height: Default::default(),
+                epoch: 6.into(),
+                block_height: 0,
                 r#type: SlashType::DuplicateVote,
                 rate: Dec::one(),
             },
         )
         .unwrap();
-    slash_redelegation(
+    let res = compute_bond_at_epoch(
         &storage,
         &params,
-        token::Amount::from(7),
-        Epoch(7),
-        Epoch(10),
-        &alice,
-        Epoch(14),
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
+        &bob,
+        12.into(),
+        3.into(),
+        23.into(),
+        Some(&redel_bonds),
     )
     .unwrap();
-    assert_eq!(slashed_amounts_map, empty_slash_amounts);
 
-    // Test case 6
-    slashes
+    pretty_assertions::assert_eq!(res, 23.into());
+
+    // Test 6
+    validator_slashes_handle(&alice).pop(&mut storage).unwrap();
+    validator_slashes_handle(&alice)
         .push(
             &mut storage,
             Slash {
-                epoch: Epoch(8),
-                block_height: Default::default(),
+                epoch: 4.into(),
+                block_height: 0,
                 r#type: SlashType::DuplicateVote,
                 rate: Dec::one(),
             },
         )
         .unwrap();
-    slash_redelegation(
+    let res = compute_bond_at_epoch(
         &storage,
         &params,
-        token::Amount::from(7),
-        Epoch(7),
-        Epoch(10),
-        &alice,
-        Epoch(14),
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
+        &bob,
+        18.into(),
+        9.into(),
+        23.into(),
+        Some(&redel_bonds),
     )
     .unwrap();
-    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+
+    pretty_assertions::assert_eq!(res, 18.into());
 }
 
-/// `slashValidatorRedelegationTest`
+/// `computeSlashBondAtEpochTest`
 #[test]
-fn test_slash_validator_redelegation() {
+fn test_compute_slash_bond_at_epoch() {
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
+        pipeline_len: 2,
         unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        cubic_slashing_window_length: 1,
         ..Default::default()
     };
-    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
-    gov_params.init_storage(&mut storage).unwrap();
-    write_pos_params(&mut storage, &params).unwrap();
-
     let alice = established_address_1();
     let bob = established_address_2();
 
-    let total_redelegated_unbonded =
-        validator_total_redelegated_unbonded_handle(&alice);
-    total_redelegated_unbonded
-        .at(&Epoch(13))
-        .at(&Epoch(10))
-        .at(&alice)
-        .insert(&mut storage, Epoch(7), token::Amount::from(2))
-        .unwrap();
-
-    let outgoing_redelegations =
-        validator_outgoing_redelegations_handle(&alice).at(&bob);
-
-    let slashes = validator_slashes_handle(&alice);
-
-    let mut slashed_amounts_map = BTreeMap::from_iter([
-        (Epoch(15), token::Amount::zero()),
-        (Epoch(16), token::Amount::zero()),
-    ]);
-    let empty_slash_amounts = slashed_amounts_map.clone();
+    let current_epoch = Epoch(20);
+    let infraction_epoch =
+        current_epoch - params.slash_processing_epoch_offset();
 
-    // Test case 1
-    slash_validator_redelegation(
-        &storage,
-        &params,
-        &alice,
-        Epoch(14),
-        &outgoing_redelegations,
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
-    )
-    .unwrap();
-    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+    let redelegated_bond = BTreeMap::from_iter([(
+        alice,
+        BTreeMap::from_iter([(infraction_epoch - 4, token::Amount::from(10))]),
+    )]);
 
-    // Test case 2
-    total_redelegated_unbonded
-        .remove_all(&mut storage, &Epoch(13))
-        .unwrap();
-    slash_validator_redelegation(
+    // Test 1
+    let res = compute_slash_bond_at_epoch(
         &storage,
         &params,
-        &alice,
-        Epoch(14),
-        &outgoing_redelegations,
-        &slashes,
-        &total_redelegated_unbonded,
+        &bob,
+        current_epoch.next(),
+        infraction_epoch,
+        infraction_epoch - 2,
+        30.into(),
+        Some(&Default::default()),
         Dec::one(),
-        &mut slashed_amounts_map,
     )
     .unwrap();
-    assert_eq!(slashed_amounts_map, empty_slash_amounts);
 
-    // Test case 3
-    total_redelegated_unbonded
-        .at(&Epoch(13))
-        .at(&Epoch(10))
-        .at(&alice)
-        .insert(&mut storage, Epoch(7), token::Amount::from(2))
-        .unwrap();
-    outgoing_redelegations
-        .at(&Epoch(6))
-        .insert(&mut storage, Epoch(8), token::Amount::from(7))
-        .unwrap();
-    slash_validator_redelegation(
-        &storage,
-        &params,
-        &alice,
-        Epoch(14),
-        &outgoing_redelegations,
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
-    )
-    .unwrap();
-    assert_eq!(
-        slashed_amounts_map,
-        BTreeMap::from_iter([
-            (Epoch(15), token::Amount::from(7)),
-            (Epoch(16), token::Amount::from(7)),
-        ])
-    );
+    pretty_assertions::assert_eq!(res, 30.into());
 
-    // Test case 4
-    slashed_amounts_map = empty_slash_amounts.clone();
-    outgoing_redelegations
-        .remove_all(&mut storage, &Epoch(6))
-        .unwrap();
-    outgoing_redelegations
-        .at(&Epoch(7))
-        .insert(&mut storage, Epoch(8), token::Amount::from(7))
-        .unwrap();
-    slash_validator_redelegation(
+    // Test 2
+    let res = compute_slash_bond_at_epoch(
         &storage,
         &params,
-        &alice,
-        Epoch(14),
-        &outgoing_redelegations,
-        &slashes,
-        &total_redelegated_unbonded,
+        &bob,
+        current_epoch.next(),
+        infraction_epoch,
+        infraction_epoch - 2,
+        30.into(),
+        Some(&redelegated_bond),
         Dec::one(),
-        &mut slashed_amounts_map,
     )
     .unwrap();
-    assert_eq!(
-        slashed_amounts_map,
-        BTreeMap::from_iter([
-            (Epoch(15), token::Amount::from(5)),
-            (Epoch(16), token::Amount::from(5)),
-        ])
-    );
 
-    // Test case 5
-    slashed_amounts_map = BTreeMap::from_iter([
-        (Epoch(15), token::Amount::from(2)),
-        (Epoch(16), token::Amount::from(3)),
-    ]);
-    slash_validator_redelegation(
-        &storage,
-        &params,
-        &alice,
-        Epoch(14),
-        &outgoing_redelegations,
-        &slashes,
-        &total_redelegated_unbonded,
-        Dec::one(),
-        &mut slashed_amounts_map,
-    )
-    .unwrap();
-    assert_eq!(
-        slashed_amounts_map,
-        BTreeMap::from_iter([
-            (Epoch(15), token::Amount::from(7)),
-            (Epoch(16), token::Amount::from(8)),
-        ])
-    );
+    pretty_assertions::assert_eq!(res, 30.into());
 
-    // Test case 6
-    slashed_amounts_map = empty_slash_amounts.clone();
-    slashes
+    // Test 3
+    validator_slashes_handle(&bob)
         .push(
             &mut storage,
             Slash {
-                epoch: Epoch(8),
-                block_height: Default::default(),
+                epoch: infraction_epoch.prev(),
+                block_height: 0,
                 r#type: SlashType::DuplicateVote,
                 rate: Dec::one(),
             },
         )
         .unwrap();
-    slash_validator_redelegation(
+    let res = compute_slash_bond_at_epoch(
         &storage,
         &params,
-        &alice,
-        Epoch(14),
-        &outgoing_redelegations,
-        &slashes,
-        &total_redelegated_unbonded,
+        &bob,
+        current_epoch.next(),
+        infraction_epoch,
+        infraction_epoch - 2,
+        30.into(),
+        Some(&Default::default()),
         Dec::one(),
-        &mut slashed_amounts_map,
     )
     .unwrap();
-    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+
+    pretty_assertions::assert_eq!(res, 0.into());
+
+    // Test 4
+    let res = compute_slash_bond_at_epoch(
+        &storage,
+        &params,
+        &bob,
+        current_epoch.next(),
+        infraction_epoch,
+        infraction_epoch - 2,
+        30.into(),
+        Some(&redelegated_bond),
+        Dec::one(),
+    )
+    .unwrap();
+
+    pretty_assertions::assert_eq!(res, 0.into());
 }
 
-/// `slashValidatorTest`
+/// `computeNewRedelegatedUnbondsTest`
 #[test]
-fn test_slash_validator() {
+fn test_compute_new_redelegated_unbonds() {
     let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
-    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
-    gov_params.init_storage(&mut storage).unwrap();
-    write_pos_params(&mut storage, &params).unwrap();
-
     let alice = established_address_1();
     let bob = established_address_2();
 
-    let total_bonded = total_bonded_handle(&bob);
-    let total_unbonded = total_unbonded_handle(&bob);
-    let total_redelegated_bonded =
-        validator_total_redelegated_bonded_handle(&bob);
-    let total_redelegated_unbonded =
-        validator_total_redelegated_unbonded_handle(&bob);
-
-    let infraction_stake = token::Amount::from(23);
+    let key = Key::parse("testing").unwrap();
+    let redelegated_bonds = NestedMap::<Epoch, RedelegatedTokens>::open(key);
 
-    let initial_stakes = BTreeMap::from_iter([
-        (Epoch(11), infraction_stake),
-        (Epoch(12), infraction_stake),
-        (Epoch(13), infraction_stake),
-    ]);
-    let mut exp_res = initial_stakes.clone();
+    // Populate the lazy and eager maps
+    let (ep1, ep2, ep4, ep5, ep6, ep7) =
+        (Epoch(1), Epoch(2), Epoch(4), Epoch(5), Epoch(6), Epoch(7));
+    let keys_and_values = vec![
+        (ep5, alice.clone(), ep2, 1),
+        (ep5, alice.clone(), ep4, 1),
+        (ep7, alice.clone(), ep2, 1),
+        (ep7, alice.clone(), ep4, 1),
+        (ep5, bob.clone(), ep1, 1),
+        (ep5, bob.clone(), ep4, 2),
+        (ep7, bob.clone(), ep1, 1),
+        (ep7, bob.clone(), ep4, 2),
+    ];
+    let mut eager_map = BTreeMap::<Epoch, EagerRedelegatedBondsMap>::new();
+    for (outer_ep, address, inner_ep, amount) in keys_and_values {
+        redelegated_bonds
+            .at(&outer_ep)
+            .at(&address)
+            .insert(&mut storage, inner_ep, token::Amount::from(amount))
+            .unwrap();
+        eager_map
+            .entry(outer_ep)
+            .or_default()
+            .entry(address.clone())
+            .or_default()
+            .insert(inner_ep, token::Amount::from(amount));
+    }
 
-    let current_epoch = Epoch(10);
-    let infraction_epoch =
-        current_epoch - params.slash_processing_epoch_offset();
-    let processing_epoch = current_epoch.next();
-    let slash_rate = Dec::one();
+    // Different ModifiedRedelegation objects for testing
+    let empty_mr = ModifiedRedelegation::default();
+    let all_mr = ModifiedRedelegation {
+        epoch: Some(ep7),
+        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
+        validator_to_modify: None,
+        epochs_to_remove: Default::default(),
+        epoch_to_modify: None,
+        new_amount: None,
+    };
+    let mod_val_mr = ModifiedRedelegation {
+        epoch: Some(ep7),
+        validators_to_remove: BTreeSet::from_iter([alice.clone()]),
+        validator_to_modify: None,
+        epochs_to_remove: Default::default(),
+        epoch_to_modify: None,
+        new_amount: None,
+    };
+    let mod_val_partial_mr = ModifiedRedelegation {
+        epoch: Some(ep7),
+        validators_to_remove: BTreeSet::from_iter([alice.clone(), bob.clone()]),
+        validator_to_modify: Some(bob.clone()),
+        epochs_to_remove: BTreeSet::from_iter([ep1]),
+        epoch_to_modify: None,
+        new_amount: None,
+    };
+    let mod_epoch_partial_mr = ModifiedRedelegation {
+        epoch: Some(ep7),
+        validators_to_remove: BTreeSet::from_iter([alice, bob.clone()]),
+        validator_to_modify: Some(bob.clone()),
+        epochs_to_remove: BTreeSet::from_iter([ep1, ep4]),
+        epoch_to_modify: Some(ep4),
+        new_amount: Some(token::Amount::from(1)),
+    };
 
     // Test case 1
-    println!("\nTEST 1:");
-
-    total_bonded
-        .set(&mut storage, 23.into(), infraction_epoch - 2, 0)
-        .unwrap();
-    let res = slash_validator(
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
+        &redelegated_bonds,
         &Default::default(),
+        &empty_mr,
     )
     .unwrap();
-    assert_eq!(res, exp_res);
+    assert_eq!(res, Default::default());
+
+    let set5 = BTreeSet::<Epoch>::from_iter([ep5]);
+    let set56 = BTreeSet::<Epoch>::from_iter([ep5, ep6]);
 
     // Test case 2
-    println!("\nTEST 2:");
-    total_bonded
-        .set(&mut storage, 17.into(), infraction_epoch - 2, 0)
-        .unwrap();
-    total_unbonded
-        .at(&(current_epoch + params.pipeline_len))
-        .insert(&mut storage, infraction_epoch - 2, 6.into())
-        .unwrap();
-    let res = slash_validator(
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
+        &redelegated_bonds,
+        &set5,
+        &empty_mr,
     )
     .unwrap();
-    exp_res.insert(Epoch(12), 17.into());
-    exp_res.insert(Epoch(13), 17.into());
+    let mut exp_res = eager_map.clone();
+    exp_res.remove(&ep7);
     assert_eq!(res, exp_res);
 
     // Test case 3
-    println!("\nTEST 3:");
-    total_redelegated_bonded
-        .at(&infraction_epoch.prev())
-        .at(&alice)
-        .insert(&mut storage, Epoch(2), 5.into())
-        .unwrap();
-    total_redelegated_bonded
-        .at(&infraction_epoch.prev())
-        .at(&alice)
-        .insert(&mut storage, Epoch(3), 1.into())
-        .unwrap();
-
-    let res = slash_validator(
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
+        &redelegated_bonds,
+        &set56,
+        &empty_mr,
     )
     .unwrap();
     assert_eq!(res, exp_res);
 
     // Test case 4
-    println!("\nTEST 4:");
-    total_unbonded_handle(&bob)
-        .at(&(current_epoch + params.pipeline_len))
-        .remove(&mut storage, &(infraction_epoch - 2))
-        .unwrap();
-    total_unbonded_handle(&bob)
-        .at(&(current_epoch + params.pipeline_len))
-        .insert(&mut storage, infraction_epoch - 1, 6.into())
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&(current_epoch + params.pipeline_len))
-        .at(&infraction_epoch.prev())
-        .at(&alice)
-        .insert(&mut storage, Epoch(2), 5.into())
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&(current_epoch + params.pipeline_len))
-        .at(&infraction_epoch.prev())
-        .at(&alice)
-        .insert(&mut storage, Epoch(3), 1.into())
-        .unwrap();
-    let res = slash_validator(
+    println!("\nTEST CASE 4\n");
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
+        &redelegated_bonds,
+        &set56,
+        &all_mr,
     )
     .unwrap();
-    assert_eq!(res, exp_res);
+    assert_eq!(res, eager_map);
 
     // Test case 5
-    println!("\nTEST 5:");
-    total_bonded_handle(&bob)
-        .set(&mut storage, 19.into(), infraction_epoch - 2, 0)
-        .unwrap();
-    total_unbonded_handle(&bob)
-        .at(&(current_epoch + params.pipeline_len))
-        .insert(&mut storage, infraction_epoch - 1, 4.into())
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, Epoch(2), token::Amount::from(1))
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&(current_epoch + params.pipeline_len))
-        .at(&infraction_epoch.prev())
-        .at(&alice)
-        .remove(&mut storage, &Epoch(3))
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&(current_epoch + params.pipeline_len))
-        .at(&infraction_epoch.prev())
-        .at(&alice)
-        .insert(&mut storage, Epoch(2), 4.into())
-        .unwrap();
-    let res = slash_validator(
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
+        &redelegated_bonds,
+        &set56,
+        &mod_val_mr,
     )
     .unwrap();
-    exp_res.insert(Epoch(12), 19.into());
-    exp_res.insert(Epoch(13), 19.into());
+    exp_res = eager_map.clone();
+    exp_res.entry(ep7).or_default().remove(&bob);
     assert_eq!(res, exp_res);
 
     // Test case 6
-    println!("\nTEST 6:");
-    total_unbonded_handle(&bob)
-        .remove_all(&mut storage, &(current_epoch + params.pipeline_len))
-        .unwrap();
-    total_redelegated_unbonded
-        .remove_all(&mut storage, &(current_epoch + params.pipeline_len))
-        .unwrap();
-    total_redelegated_bonded
-        .remove_all(&mut storage, &current_epoch)
-        .unwrap();
-    total_bonded_handle(&bob)
-        .set(&mut storage, 23.into(), infraction_epoch - 2, 0)
-        .unwrap();
-    total_bonded_handle(&bob)
-        .set(&mut storage, 6.into(), current_epoch, 0)
-        .unwrap();
-
-    let res = slash_validator(
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
+        &redelegated_bonds,
+        &set56,
+        &mod_val_partial_mr,
     )
     .unwrap();
-    exp_res = initial_stakes;
+    exp_res = eager_map.clone();
+    exp_res
+        .entry(ep7)
+        .or_default()
+        .entry(bob.clone())
+        .or_default()
+        .remove(&ep4);
     assert_eq!(res, exp_res);
 
     // Test case 7
-    println!("\nTEST 7:");
-    total_bonded
-        .get_data_handler()
-        .remove(&mut storage, &current_epoch)
-        .unwrap();
-    total_unbonded
-        .at(&current_epoch.next())
-        .insert(&mut storage, current_epoch, 6.into())
-        .unwrap();
-    let res = slash_validator(
+    let res = compute_new_redelegated_unbonds(
         &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
+        &redelegated_bonds,
+        &set56,
+        &mod_epoch_partial_mr,
     )
     .unwrap();
+    exp_res
+        .entry(ep7)
+        .or_default()
+        .entry(bob)
+        .or_default()
+        .insert(ep4, token::Amount::from(1));
     assert_eq!(res, exp_res);
+}
 
-    // Test case 8
-    println!("\nTEST 8:");
-    total_bonded
-        .get_data_handler()
-        .insert(&mut storage, current_epoch, 3.into())
-        .unwrap();
-    total_unbonded
-        .at(&current_epoch.next())
-        .insert(&mut storage, current_epoch, 3.into())
-        .unwrap();
-    let res = slash_validator(
-        &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
-    )
-    .unwrap();
-    assert_eq!(res, exp_res);
+/// `applyListSlashesTest`
+#[test]
+fn test_apply_list_slashes() {
+    let init_epoch = Epoch(2);
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+    // let unbonding_len = 4u64;
+    // let cubic_offset = 1u64;
 
-    // Test case 9
-    println!("\nTEST 9:");
-    total_unbonded
-        .remove_all(&mut storage, &current_epoch.next())
-        .unwrap();
-    total_bonded
-        .set(&mut storage, 6.into(), current_epoch, 0)
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 2.into(), 5.into())
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 3.into(), 1.into())
-        .unwrap();
-    let res = slash_validator(
-        &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
-    )
-    .unwrap();
-    assert_eq!(res, exp_res);
+    let slash1 = Slash {
+        epoch: init_epoch,
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slash2 = Slash {
+        epoch: init_epoch
+            + params.unbonding_len
+            + params.cubic_slashing_window_length
+            + 1u64,
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
 
-    // Test case 10
-    println!("\nTEST 10:");
-    total_redelegated_bonded
-        .remove_all(&mut storage, &current_epoch)
-        .unwrap();
-    total_bonded
-        .get_data_handler()
-        .remove(&mut storage, &current_epoch)
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&current_epoch.next())
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 2.into(), 5.into())
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&current_epoch.next())
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 3.into(), 1.into())
-        .unwrap();
-    let res = slash_validator(
-        &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
-    )
-    .unwrap();
-    assert_eq!(res, exp_res);
+    let list1 = vec![slash1.clone()];
+    let list2 = vec![slash1.clone(), slash2.clone()];
+    let list3 = vec![slash1.clone(), slash1.clone()];
+    let list4 = vec![slash1.clone(), slash1, slash2];
 
-    // Test case 11
-    println!("\nTEST 11:");
-    total_bonded
-        .set(&mut storage, 2.into(), current_epoch, 0)
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&current_epoch.next())
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 2.into(), 4.into())
-        .unwrap();
-    total_redelegated_unbonded
-        .at(&current_epoch.next())
-        .at(&current_epoch)
-        .at(&alice)
-        .remove(&mut storage, &3.into())
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 2.into(), 1.into())
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch)
-        .at(&alice)
-        .insert(&mut storage, 3.into(), 1.into())
-        .unwrap();
-    let res = slash_validator(
-        &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
-    )
-    .unwrap();
-    assert_eq!(res, exp_res);
+    let res = apply_list_slashes(&params, &[], token::Amount::from(100));
+    assert_eq!(res, token::Amount::from(100));
 
-    // Test case 12
-    println!("\nTEST 12:");
-    total_bonded
-        .set(&mut storage, 6.into(), current_epoch, 0)
-        .unwrap();
-    total_bonded
-        .set(&mut storage, 2.into(), current_epoch.next(), 0)
-        .unwrap();
-    total_redelegated_bonded
-        .remove_all(&mut storage, &current_epoch)
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch.next())
-        .at(&alice)
-        .insert(&mut storage, 2.into(), 1.into())
-        .unwrap();
-    total_redelegated_bonded
-        .at(&current_epoch.next())
-        .at(&alice)
-        .insert(&mut storage, 3.into(), 1.into())
-        .unwrap();
-    let res = slash_validator(
-        &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
-    )
-    .unwrap();
-    assert_eq!(res, exp_res);
+    let res = apply_list_slashes(&params, &list1, token::Amount::from(100));
+    assert_eq!(res, token::Amount::zero());
 
-    // Test case 13
-    println!("\nTEST 13:");
-    validator_slashes_handle(&bob)
-        .push(
-            &mut storage,
-            Slash {
-                epoch: infraction_epoch.prev(),
-                block_height: 0,
-                r#type: SlashType::DuplicateVote,
-                rate: Dec::one(),
-            },
-        )
-        .unwrap();
-    total_redelegated_unbonded
-        .remove_all(&mut storage, &current_epoch.next())
-        .unwrap();
-    total_bonded
-        .get_data_handler()
-        .remove(&mut storage, &current_epoch.next())
-        .unwrap();
-    total_redelegated_bonded
-        .remove_all(&mut storage, &current_epoch.next())
-        .unwrap();
-    let res = slash_validator(
-        &storage,
-        &params,
-        &bob,
-        slash_rate,
-        processing_epoch,
-        &Default::default(),
-    )
-    .unwrap();
-    exp_res.insert(Epoch(11), 0.into());
-    exp_res.insert(Epoch(12), 0.into());
-    exp_res.insert(Epoch(13), 0.into());
-    assert_eq!(res, exp_res);
+    let res = apply_list_slashes(&params, &list2, token::Amount::from(100));
+    assert_eq!(res, token::Amount::zero());
+
+    let res = apply_list_slashes(&params, &list3, token::Amount::from(100));
+    assert_eq!(res, token::Amount::zero());
+
+    let res = apply_list_slashes(&params, &list4, token::Amount::from(100));
+    assert_eq!(res, token::Amount::zero());
 }
 
-/// `computeAmountAfterSlashingUnbondTest`
+/// `computeSlashableAmountTest`
 #[test]
-fn compute_amount_after_slashing_unbond_test() {
-    let mut storage = TestWlStorage::default();
+fn test_compute_slashable_amount() {
+    let init_epoch = Epoch(2);
     let params = OwnedPosParams {
         unbonding_len: 4,
+        evidence_max_age_epochs: 4,
         ..Default::default()
     };
 
-    // Test data
-    let alice = established_address_1();
-    let bob = established_address_2();
-    let unbonds: BTreeMap<Epoch, token::Amount> = BTreeMap::from_iter([
-        ((Epoch(2)), token::Amount::from(5)),
-        ((Epoch(4)), token::Amount::from(6)),
-    ]);
-    let redelegated_unbonds: EagerRedelegatedUnbonds = BTreeMap::from_iter([(
-        Epoch(2),
-        BTreeMap::from_iter([(
-            alice.clone(),
-            BTreeMap::from_iter([(Epoch(1), token::Amount::from(1))]),
-        )]),
-    )]);
+    let slash1 = Slash {
+        epoch: init_epoch
+            + params.unbonding_len
+            + params.cubic_slashing_window_length,
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+
+    let slash2 = Slash {
+        epoch: init_epoch
+            + params.unbonding_len
+            + params.cubic_slashing_window_length
+            + 1u64,
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+
+    let test_map = vec![(init_epoch, token::Amount::from(50))]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+    let res = compute_slashable_amount(
+        &params,
+        &slash1,
+        token::Amount::from(100),
+        &BTreeMap::new(),
+    );
+    assert_eq!(res, token::Amount::from(100));
+
+    let res = compute_slashable_amount(
+        &params,
+        &slash2,
+        token::Amount::from(100),
+        &test_map,
+    );
+    assert_eq!(res, token::Amount::from(50));
+
+    let res = compute_slashable_amount(
+        &params,
+        &slash1,
+        token::Amount::from(100),
+        &test_map,
+    );
+    assert_eq!(res, token::Amount::from(100));
+}
+
+/// `foldAndSlashRedelegatedBondsMapTest`
+#[test]
+fn test_fold_and_slash_redelegated_bonds() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+    let start_epoch = Epoch(7);
+
+    let alice = established_address_1();
+    let bob = established_address_2();
+
+    println!("\n\nAlice: {}", alice);
+    println!("Bob: {}\n", bob);
+
+    let test_slash = Slash {
+        epoch: Default::default(),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+
+    let test_data = vec![
+        (alice.clone(), vec![(2, 1), (4, 1)]),
+        (bob, vec![(1, 1), (4, 2)]),
+    ];
+    let mut eager_redel_bonds = EagerRedelegatedBondsMap::default();
+    for (address, pair) in test_data {
+        for (epoch, amount) in pair {
+            eager_redel_bonds
+                .entry(address.clone())
+                .or_default()
+                .insert(Epoch(epoch), token::Amount::from(amount));
+        }
+    }
 
     // Test case 1
-    let slashes = vec![];
-    let result = compute_amount_after_slashing_unbond(
+    let res = fold_and_slash_redelegated_bonds(
         &storage,
         &params,
-        &unbonds,
-        &redelegated_unbonds,
-        slashes,
+        &eager_redel_bonds,
+        start_epoch,
+        &[],
+        |_| true,
     )
     .unwrap();
-    assert_eq!(result.sum, 11.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 5.into()), (4.into(), 6.into())],
+    assert_eq!(
+        res,
+        FoldRedelegatedBondsResult {
+            total_redelegated: token::Amount::from(5),
+            total_after_slashing: token::Amount::from(5),
+        }
     );
 
     // Test case 2
-    let bob_slash = Slash {
-        epoch: Epoch(5),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-    let slashes = vec![bob_slash.clone()];
-    validator_slashes_handle(&bob)
-        .push(&mut storage, bob_slash)
-        .unwrap();
-    let result = compute_amount_after_slashing_unbond(
+    let res = fold_and_slash_redelegated_bonds(
         &storage,
         &params,
-        &unbonds,
-        &redelegated_unbonds,
-        slashes,
+        &eager_redel_bonds,
+        start_epoch,
+        &[test_slash],
+        |_| true,
     )
     .unwrap();
-    assert_eq!(result.sum, 0.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 0.into()), (4.into(), 0.into())],
+    assert_eq!(
+        res,
+        FoldRedelegatedBondsResult {
+            total_redelegated: token::Amount::from(5),
+            total_after_slashing: token::Amount::zero(),
+        }
     );
 
     // Test case 3
     let alice_slash = Slash {
-        epoch: Epoch(0),
+        epoch: Epoch(6),
         block_height: Default::default(),
         r#type: SlashType::DuplicateVote,
         rate: Dec::one(),
     };
-    let slashes = vec![alice_slash.clone()];
     validator_slashes_handle(&alice)
         .push(&mut storage, alice_slash)
         .unwrap();
-    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
-    let result = compute_amount_after_slashing_unbond(
+
+    let res = fold_and_slash_redelegated_bonds(
         &storage,
         &params,
-        &unbonds,
-        &redelegated_unbonds,
-        slashes,
+        &eager_redel_bonds,
+        start_epoch,
+        &[],
+        |_| true,
     )
     .unwrap();
-    assert_eq!(result.sum, 11.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 5.into()), (4.into(), 6.into())],
+    assert_eq!(
+        res,
+        FoldRedelegatedBondsResult {
+            total_redelegated: token::Amount::from(5),
+            total_after_slashing: token::Amount::from(3),
+        }
     );
+}
 
-    // Test case 4
-    let alice_slash = Slash {
-        epoch: Epoch(1),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
+/// `slashRedelegationTest`
+#[test]
+fn test_slash_redelegation() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
     };
-    let slashes = vec![alice_slash.clone()];
-    validator_slashes_handle(&alice).pop(&mut storage).unwrap();
-    validator_slashes_handle(&alice)
-        .push(&mut storage, alice_slash)
+    let alice = established_address_1();
+
+    let total_redelegated_unbonded =
+        validator_total_redelegated_unbonded_handle(&alice);
+    total_redelegated_unbonded
+        .at(&Epoch(13))
+        .at(&Epoch(10))
+        .at(&alice)
+        .insert(&mut storage, Epoch(7), token::Amount::from(2))
         .unwrap();
-    let result = compute_amount_after_slashing_unbond(
+
+    let slashes = validator_slashes_handle(&alice);
+
+    let mut slashed_amounts_map = BTreeMap::from_iter([
+        (Epoch(15), token::Amount::zero()),
+        (Epoch(16), token::Amount::zero()),
+    ]);
+    let empty_slash_amounts = slashed_amounts_map.clone();
+
+    // Test case 1
+    slash_redelegation(
         &storage,
         &params,
-        &unbonds,
-        &redelegated_unbonds,
-        slashes,
+        token::Amount::from(7),
+        Epoch(7),
+        Epoch(10),
+        &alice,
+        Epoch(14),
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(
+        slashed_amounts_map,
+        BTreeMap::from_iter([
+            (Epoch(15), token::Amount::from(5)),
+            (Epoch(16), token::Amount::from(5)),
+        ])
+    );
+
+    // Test case 2
+    slashed_amounts_map = empty_slash_amounts.clone();
+    slash_redelegation(
+        &storage,
+        &params,
+        token::Amount::from(7),
+        Epoch(7),
+        Epoch(11),
+        &alice,
+        Epoch(14),
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(
+        slashed_amounts_map,
+        BTreeMap::from_iter([
+            (Epoch(15), token::Amount::from(7)),
+            (Epoch(16), token::Amount::from(7)),
+        ])
+    );
+
+    // Test case 3
+    slashed_amounts_map = BTreeMap::from_iter([
+        (Epoch(15), token::Amount::from(2)),
+        (Epoch(16), token::Amount::from(3)),
+    ]);
+    slash_redelegation(
+        &storage,
+        &params,
+        token::Amount::from(7),
+        Epoch(7),
+        Epoch(10),
+        &alice,
+        Epoch(14),
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(
+        slashed_amounts_map,
+        BTreeMap::from_iter([
+            (Epoch(15), token::Amount::from(7)),
+            (Epoch(16), token::Amount::from(8)),
+        ])
+    );
+
+    // Test case 4
+    slashes
+        .push(
+            &mut storage,
+            Slash {
+                epoch: Epoch(8),
+                block_height: Default::default(),
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::one(),
+            },
+        )
+        .unwrap();
+    slashed_amounts_map = empty_slash_amounts.clone();
+    slash_redelegation(
+        &storage,
+        &params,
+        token::Amount::from(7),
+        Epoch(7),
+        Epoch(10),
+        &alice,
+        Epoch(14),
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+
+    // Test case 5
+    slashes.pop(&mut storage).unwrap();
+    slashes
+        .push(
+            &mut storage,
+            Slash {
+                epoch: Epoch(9),
+                block_height: Default::default(),
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::one(),
+            },
+        )
+        .unwrap();
+    slash_redelegation(
+        &storage,
+        &params,
+        token::Amount::from(7),
+        Epoch(7),
+        Epoch(10),
+        &alice,
+        Epoch(14),
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+
+    // Test case 6
+    slashes
+        .push(
+            &mut storage,
+            Slash {
+                epoch: Epoch(8),
+                block_height: Default::default(),
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::one(),
+            },
+        )
+        .unwrap();
+    slash_redelegation(
+        &storage,
+        &params,
+        token::Amount::from(7),
+        Epoch(7),
+        Epoch(10),
+        &alice,
+        Epoch(14),
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+}
+
+/// `slashValidatorRedelegationTest`
+#[test]
+fn test_slash_validator_redelegation() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
+    gov_params.init_storage(&mut storage).unwrap();
+    write_pos_params(&mut storage, &params).unwrap();
+
+    let alice = established_address_1();
+    let bob = established_address_2();
+
+    let total_redelegated_unbonded =
+        validator_total_redelegated_unbonded_handle(&alice);
+    total_redelegated_unbonded
+        .at(&Epoch(13))
+        .at(&Epoch(10))
+        .at(&alice)
+        .insert(&mut storage, Epoch(7), token::Amount::from(2))
+        .unwrap();
+
+    let outgoing_redelegations =
+        validator_outgoing_redelegations_handle(&alice).at(&bob);
+
+    let slashes = validator_slashes_handle(&alice);
+
+    let mut slashed_amounts_map = BTreeMap::from_iter([
+        (Epoch(15), token::Amount::zero()),
+        (Epoch(16), token::Amount::zero()),
+    ]);
+    let empty_slash_amounts = slashed_amounts_map.clone();
+
+    // Test case 1
+    slash_validator_redelegation(
+        &storage,
+        &params,
+        &alice,
+        Epoch(14),
+        &outgoing_redelegations,
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+
+    // Test case 2
+    total_redelegated_unbonded
+        .remove_all(&mut storage, &Epoch(13))
+        .unwrap();
+    slash_validator_redelegation(
+        &storage,
+        &params,
+        &alice,
+        Epoch(14),
+        &outgoing_redelegations,
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+
+    // Test case 3
+    total_redelegated_unbonded
+        .at(&Epoch(13))
+        .at(&Epoch(10))
+        .at(&alice)
+        .insert(&mut storage, Epoch(7), token::Amount::from(2))
+        .unwrap();
+    outgoing_redelegations
+        .at(&Epoch(6))
+        .insert(&mut storage, Epoch(8), token::Amount::from(7))
+        .unwrap();
+    slash_validator_redelegation(
+        &storage,
+        &params,
+        &alice,
+        Epoch(14),
+        &outgoing_redelegations,
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(
+        slashed_amounts_map,
+        BTreeMap::from_iter([
+            (Epoch(15), token::Amount::from(7)),
+            (Epoch(16), token::Amount::from(7)),
+        ])
+    );
+
+    // Test case 4
+    slashed_amounts_map = empty_slash_amounts.clone();
+    outgoing_redelegations
+        .remove_all(&mut storage, &Epoch(6))
+        .unwrap();
+    outgoing_redelegations
+        .at(&Epoch(7))
+        .insert(&mut storage, Epoch(8), token::Amount::from(7))
+        .unwrap();
+    slash_validator_redelegation(
+        &storage,
+        &params,
+        &alice,
+        Epoch(14),
+        &outgoing_redelegations,
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(
+        slashed_amounts_map,
+        BTreeMap::from_iter([
+            (Epoch(15), token::Amount::from(5)),
+            (Epoch(16), token::Amount::from(5)),
+        ])
+    );
+
+    // Test case 5
+    slashed_amounts_map = BTreeMap::from_iter([
+        (Epoch(15), token::Amount::from(2)),
+        (Epoch(16), token::Amount::from(3)),
+    ]);
+    slash_validator_redelegation(
+        &storage,
+        &params,
+        &alice,
+        Epoch(14),
+        &outgoing_redelegations,
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(
+        slashed_amounts_map,
+        BTreeMap::from_iter([
+            (Epoch(15), token::Amount::from(7)),
+            (Epoch(16), token::Amount::from(8)),
+        ])
+    );
+
+    // Test case 6
+    slashed_amounts_map = empty_slash_amounts.clone();
+    slashes
+        .push(
+            &mut storage,
+            Slash {
+                epoch: Epoch(8),
+                block_height: Default::default(),
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::one(),
+            },
+        )
+        .unwrap();
+    slash_validator_redelegation(
+        &storage,
+        &params,
+        &alice,
+        Epoch(14),
+        &outgoing_redelegations,
+        &slashes,
+        &total_redelegated_unbonded,
+        Dec::one(),
+        &mut slashed_amounts_map,
+    )
+    .unwrap();
+    assert_eq!(slashed_amounts_map, empty_slash_amounts);
+}
+
+/// `slashValidatorTest`
+#[test]
+fn test_slash_validator() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
+    gov_params.init_storage(&mut storage).unwrap();
+    write_pos_params(&mut storage, &params).unwrap();
+
+    let alice = established_address_1();
+    let bob = established_address_2();
+
+    let total_bonded = total_bonded_handle(&bob);
+    let total_unbonded = total_unbonded_handle(&bob);
+    let total_redelegated_bonded =
+        validator_total_redelegated_bonded_handle(&bob);
+    let total_redelegated_unbonded =
+        validator_total_redelegated_unbonded_handle(&bob);
+
+    let infraction_stake = token::Amount::from(23);
+
+    let initial_stakes = BTreeMap::from_iter([
+        (Epoch(11), infraction_stake),
+        (Epoch(12), infraction_stake),
+        (Epoch(13), infraction_stake),
+    ]);
+    let mut exp_res = initial_stakes.clone();
+
+    let current_epoch = Epoch(10);
+    let infraction_epoch =
+        current_epoch - params.slash_processing_epoch_offset();
+    let processing_epoch = current_epoch.next();
+    let slash_rate = Dec::one();
+
+    // Test case 1
+    println!("\nTEST 1:");
+
+    total_bonded
+        .set(&mut storage, 23.into(), infraction_epoch - 2, 0)
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 2
+    println!("\nTEST 2:");
+    total_bonded
+        .set(&mut storage, 17.into(), infraction_epoch - 2, 0)
+        .unwrap();
+    total_unbonded
+        .at(&(current_epoch + params.pipeline_len))
+        .insert(&mut storage, infraction_epoch - 2, 6.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    exp_res.insert(Epoch(12), 17.into());
+    exp_res.insert(Epoch(13), 17.into());
+    assert_eq!(res, exp_res);
+
+    // Test case 3
+    println!("\nTEST 3:");
+    total_redelegated_bonded
+        .at(&infraction_epoch.prev())
+        .at(&alice)
+        .insert(&mut storage, Epoch(2), 5.into())
+        .unwrap();
+    total_redelegated_bonded
+        .at(&infraction_epoch.prev())
+        .at(&alice)
+        .insert(&mut storage, Epoch(3), 1.into())
+        .unwrap();
+
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 4
+    println!("\nTEST 4:");
+    total_unbonded_handle(&bob)
+        .at(&(current_epoch + params.pipeline_len))
+        .remove(&mut storage, &(infraction_epoch - 2))
+        .unwrap();
+    total_unbonded_handle(&bob)
+        .at(&(current_epoch + params.pipeline_len))
+        .insert(&mut storage, infraction_epoch - 1, 6.into())
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&(current_epoch + params.pipeline_len))
+        .at(&infraction_epoch.prev())
+        .at(&alice)
+        .insert(&mut storage, Epoch(2), 5.into())
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&(current_epoch + params.pipeline_len))
+        .at(&infraction_epoch.prev())
+        .at(&alice)
+        .insert(&mut storage, Epoch(3), 1.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 5
+    println!("\nTEST 5:");
+    total_bonded_handle(&bob)
+        .set(&mut storage, 19.into(), infraction_epoch - 2, 0)
+        .unwrap();
+    total_unbonded_handle(&bob)
+        .at(&(current_epoch + params.pipeline_len))
+        .insert(&mut storage, infraction_epoch - 1, 4.into())
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, Epoch(2), token::Amount::from(1))
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&(current_epoch + params.pipeline_len))
+        .at(&infraction_epoch.prev())
+        .at(&alice)
+        .remove(&mut storage, &Epoch(3))
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&(current_epoch + params.pipeline_len))
+        .at(&infraction_epoch.prev())
+        .at(&alice)
+        .insert(&mut storage, Epoch(2), 4.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    exp_res.insert(Epoch(12), 19.into());
+    exp_res.insert(Epoch(13), 19.into());
+    assert_eq!(res, exp_res);
+
+    // Test case 6
+    println!("\nTEST 6:");
+    total_unbonded_handle(&bob)
+        .remove_all(&mut storage, &(current_epoch + params.pipeline_len))
+        .unwrap();
+    total_redelegated_unbonded
+        .remove_all(&mut storage, &(current_epoch + params.pipeline_len))
+        .unwrap();
+    total_redelegated_bonded
+        .remove_all(&mut storage, &current_epoch)
+        .unwrap();
+    total_bonded_handle(&bob)
+        .set(&mut storage, 23.into(), infraction_epoch - 2, 0)
+        .unwrap();
+    total_bonded_handle(&bob)
+        .set(&mut storage, 6.into(), current_epoch, 0)
+        .unwrap();
+
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    exp_res = initial_stakes;
+    assert_eq!(res, exp_res);
+
+    // Test case 7
+    println!("\nTEST 7:");
+    total_bonded
+        .get_data_handler()
+        .remove(&mut storage, &current_epoch)
+        .unwrap();
+    total_unbonded
+        .at(&current_epoch.next())
+        .insert(&mut storage, current_epoch, 6.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 8
+    println!("\nTEST 8:");
+    total_bonded
+        .get_data_handler()
+        .insert(&mut storage, current_epoch, 3.into())
+        .unwrap();
+    total_unbonded
+        .at(&current_epoch.next())
+        .insert(&mut storage, current_epoch, 3.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 9
+    println!("\nTEST 9:");
+    total_unbonded
+        .remove_all(&mut storage, &current_epoch.next())
+        .unwrap();
+    total_bonded
+        .set(&mut storage, 6.into(), current_epoch, 0)
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 2.into(), 5.into())
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 3.into(), 1.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 10
+    println!("\nTEST 10:");
+    total_redelegated_bonded
+        .remove_all(&mut storage, &current_epoch)
+        .unwrap();
+    total_bonded
+        .get_data_handler()
+        .remove(&mut storage, &current_epoch)
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&current_epoch.next())
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 2.into(), 5.into())
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&current_epoch.next())
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 3.into(), 1.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 11
+    println!("\nTEST 11:");
+    total_bonded
+        .set(&mut storage, 2.into(), current_epoch, 0)
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&current_epoch.next())
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 2.into(), 4.into())
+        .unwrap();
+    total_redelegated_unbonded
+        .at(&current_epoch.next())
+        .at(&current_epoch)
+        .at(&alice)
+        .remove(&mut storage, &3.into())
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 2.into(), 1.into())
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch)
+        .at(&alice)
+        .insert(&mut storage, 3.into(), 1.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 12
+    println!("\nTEST 12:");
+    total_bonded
+        .set(&mut storage, 6.into(), current_epoch, 0)
+        .unwrap();
+    total_bonded
+        .set(&mut storage, 2.into(), current_epoch.next(), 0)
+        .unwrap();
+    total_redelegated_bonded
+        .remove_all(&mut storage, &current_epoch)
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch.next())
+        .at(&alice)
+        .insert(&mut storage, 2.into(), 1.into())
+        .unwrap();
+    total_redelegated_bonded
+        .at(&current_epoch.next())
+        .at(&alice)
+        .insert(&mut storage, 3.into(), 1.into())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(res, exp_res);
+
+    // Test case 13
+    println!("\nTEST 13:");
+    validator_slashes_handle(&bob)
+        .push(
+            &mut storage,
+            Slash {
+                epoch: infraction_epoch.prev(),
+                block_height: 0,
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::one(),
+            },
+        )
+        .unwrap();
+    total_redelegated_unbonded
+        .remove_all(&mut storage, &current_epoch.next())
+        .unwrap();
+    total_bonded
+        .get_data_handler()
+        .remove(&mut storage, &current_epoch.next())
+        .unwrap();
+    total_redelegated_bonded
+        .remove_all(&mut storage, &current_epoch.next())
+        .unwrap();
+    let res = slash_validator(
+        &storage,
+        &params,
+        &bob,
+        slash_rate,
+        processing_epoch,
+        &Default::default(),
+    )
+    .unwrap();
+    exp_res.insert(Epoch(11), 0.into());
+    exp_res.insert(Epoch(12), 0.into());
+    exp_res.insert(Epoch(13), 0.into());
+    assert_eq!(res, exp_res);
+}
+
+/// `computeAmountAfterSlashingUnbondTest`
+#[test]
+fn compute_amount_after_slashing_unbond_test() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    // Test data
+    let alice = established_address_1();
+    let bob = established_address_2();
+    let unbonds: BTreeMap<Epoch, token::Amount> = BTreeMap::from_iter([
+        ((Epoch(2)), token::Amount::from(5)),
+        ((Epoch(4)), token::Amount::from(6)),
+    ]);
+    let redelegated_unbonds: EagerRedelegatedUnbonds = BTreeMap::from_iter([(
+        Epoch(2),
+        BTreeMap::from_iter([(
+            alice.clone(),
+            BTreeMap::from_iter([(Epoch(1), token::Amount::from(1))]),
+        )]),
+    )]);
+
+    // Test case 1
+    let slashes = vec![];
+    let result = compute_amount_after_slashing_unbond(
+        &storage,
+        &params,
+        &unbonds,
+        &redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 11.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 5.into()), (4.into(), 6.into())],
+    );
+
+    // Test case 2
+    let bob_slash = Slash {
+        epoch: Epoch(5),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slashes = vec![bob_slash.clone()];
+    validator_slashes_handle(&bob)
+        .push(&mut storage, bob_slash)
+        .unwrap();
+    let result = compute_amount_after_slashing_unbond(
+        &storage,
+        &params,
+        &unbonds,
+        &redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 0.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 0.into()), (4.into(), 0.into())],
+    );
+
+    // Test case 3
+    let alice_slash = Slash {
+        epoch: Epoch(0),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slashes = vec![alice_slash.clone()];
+    validator_slashes_handle(&alice)
+        .push(&mut storage, alice_slash)
+        .unwrap();
+    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
+    let result = compute_amount_after_slashing_unbond(
+        &storage,
+        &params,
+        &unbonds,
+        &redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 11.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 5.into()), (4.into(), 6.into())],
+    );
+
+    // Test case 4
+    let alice_slash = Slash {
+        epoch: Epoch(1),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slashes = vec![alice_slash.clone()];
+    validator_slashes_handle(&alice).pop(&mut storage).unwrap();
+    validator_slashes_handle(&alice)
+        .push(&mut storage, alice_slash)
+        .unwrap();
+    let result = compute_amount_after_slashing_unbond(
+        &storage,
+        &params,
+        &unbonds,
+        &redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 10.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 4.into()), (4.into(), 6.into())],
+    );
+}
+
+/// `computeAmountAfterSlashingWithdrawTest`
+#[test]
+fn compute_amount_after_slashing_withdraw_test() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    // Test data
+    let alice = established_address_1();
+    let bob = established_address_2();
+    let unbonds_and_redelegated_unbonds: BTreeMap<
+        (Epoch, Epoch),
+        (token::Amount, EagerRedelegatedBondsMap),
+    > = BTreeMap::from_iter([
+        (
+            (Epoch(2), Epoch(20)),
+            (
+                // unbond
+                token::Amount::from(5),
+                // redelegations
+                BTreeMap::from_iter([(
+                    alice.clone(),
+                    BTreeMap::from_iter([(Epoch(1), token::Amount::from(1))]),
+                )]),
+            ),
+        ),
+        (
+            (Epoch(4), Epoch(20)),
+            (
+                // unbond
+                token::Amount::from(6),
+                // redelegations
+                BTreeMap::default(),
+            ),
+        ),
+    ]);
+
+    // Test case 1
+    let slashes = vec![];
+    let result = compute_amount_after_slashing_withdraw(
+        &storage,
+        &params,
+        &unbonds_and_redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 11.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 5.into()), (4.into(), 6.into())],
+    );
+
+    // Test case 2
+    let bob_slash = Slash {
+        epoch: Epoch(5),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slashes = vec![bob_slash.clone()];
+    validator_slashes_handle(&bob)
+        .push(&mut storage, bob_slash)
+        .unwrap();
+    let result = compute_amount_after_slashing_withdraw(
+        &storage,
+        &params,
+        &unbonds_and_redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 0.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 0.into()), (4.into(), 0.into())],
+    );
+
+    // Test case 3
+    let alice_slash = Slash {
+        epoch: Epoch(0),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slashes = vec![alice_slash.clone()];
+    validator_slashes_handle(&alice)
+        .push(&mut storage, alice_slash)
+        .unwrap();
+    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
+    let result = compute_amount_after_slashing_withdraw(
+        &storage,
+        &params,
+        &unbonds_and_redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 11.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 5.into()), (4.into(), 6.into())],
+    );
+
+    // Test case 4
+    let alice_slash = Slash {
+        epoch: Epoch(1),
+        block_height: Default::default(),
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::one(),
+    };
+    let slashes = vec![alice_slash.clone()];
+    validator_slashes_handle(&alice).pop(&mut storage).unwrap();
+    validator_slashes_handle(&alice)
+        .push(&mut storage, alice_slash)
+        .unwrap();
+    let result = compute_amount_after_slashing_withdraw(
+        &storage,
+        &params,
+        &unbonds_and_redelegated_unbonds,
+        slashes,
+    )
+    .unwrap();
+    assert_eq!(result.sum, 10.into());
+    itertools::assert_equal(
+        result.epoch_map,
+        [(2.into(), 4.into()), (4.into(), 6.into())],
+    );
+}
+
+fn arb_redelegation_amounts(
+    max_delegation: u64,
+) -> impl Strategy<Value = (token::Amount, token::Amount, token::Amount)> {
+    let arb_delegation = arb_amount_non_zero_ceiled(max_delegation);
+    let amounts = arb_delegation.prop_flat_map(move |amount_delegate| {
+        let amount_redelegate = arb_amount_non_zero_ceiled(max(
+            1,
+            u64::try_from(amount_delegate.raw_amount()).unwrap() - 1,
+        ));
+        (Just(amount_delegate), amount_redelegate)
+    });
+    amounts.prop_flat_map(move |(amount_delegate, amount_redelegate)| {
+        let amount_unbond = arb_amount_non_zero_ceiled(max(
+            1,
+            u64::try_from(amount_redelegate.raw_amount()).unwrap() - 1,
+        ));
+        (
+            Just(amount_delegate),
+            Just(amount_redelegate),
+            amount_unbond,
+        )
+    })
+}
+
+fn test_simple_redelegation_aux(
+    mut validators: Vec<GenesisValidator>,
+    amount_delegate: token::Amount,
+    amount_redelegate: token::Amount,
+    amount_unbond: token::Amount,
+) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+    let src_validator = validators[0].address.clone();
+    let dest_validator = validators[1].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // Ensure that we cannot redelegate with the same src and dest validator
+    let err = super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &src_validator,
+        &src_validator,
+        current_epoch,
+        amount_redelegate,
+    )
+    .unwrap_err();
+    let err_str = err.to_string();
+    assert_matches!(
+        err.downcast::<RedelegationError>().unwrap().deref(),
+        RedelegationError::RedelegationSrcEqDest,
+        "Redelegation with the same src and dest validator must be rejected, \
+         got {err_str}",
+    );
+
+    for _ in 0..5 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    let init_epoch = current_epoch;
+
+    // Delegate in epoch 1 to src_validator
+    println!(
+        "\nBONDING {} TOKENS TO {}\n",
+        amount_delegate.to_string_native(),
+        &src_validator
+    );
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        amount_delegate,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    println!("\nAFTER DELEGATION\n");
+    let bonds = bond_handle(&delegator, &src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let bonds_dest = bond_handle(&delegator, &dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds = unbond_handle(&delegator, &src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_bonds = total_bonded_handle(&src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds = total_unbonded_handle(&src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    dbg!(&bonds, &bonds_dest, &unbonds, &tot_bonds, &tot_unbonds);
+
+    // Advance three epochs
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Redelegate in epoch 3
+    println!(
+        "\nREDELEGATING {} TOKENS TO {}\n",
+        amount_redelegate.to_string_native(),
+        &dest_validator
+    );
+
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &src_validator,
+        &dest_validator,
+        current_epoch,
+        amount_redelegate,
+    )
+    .unwrap();
+
+    println!("\nAFTER REDELEGATION\n");
+    println!("\nDELEGATOR\n");
+    let bonds_src = bond_handle(&delegator, &src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let bonds_dest = bond_handle(&delegator, &dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_src = unbond_handle(&delegator, &src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+
+    dbg!(
+        &bonds_src,
+        &bonds_dest,
+        &unbonds_src,
+        &unbonds_dest,
+        &redel_bonds,
+        &redel_unbonds
+    );
+
+    // Dest val
+    println!("\nDEST VALIDATOR\n");
+
+    let incoming_redels_dest =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let outgoing_redels_dest =
+        validator_outgoing_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_bonds_dest = total_bonded_handle(&dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_redel_bonds_dest =
+        validator_total_redelegated_bonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_redel_unbonds_dest =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    dbg!(
+        &incoming_redels_dest,
+        &outgoing_redels_dest,
+        &tot_bonds_dest,
+        &tot_unbonds_dest,
+        &tot_redel_bonds_dest,
+        &tot_redel_unbonds_dest
+    );
+
+    // Src val
+    println!("\nSRC VALIDATOR\n");
+
+    let incoming_redels_src =
+        validator_incoming_redelegations_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let outgoing_redels_src =
+        validator_outgoing_redelegations_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_bonds_src = total_bonded_handle(&src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds_src = total_unbonded_handle(&src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_redel_bonds_src =
+        validator_total_redelegated_bonded_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_redel_unbonds_src =
+        validator_total_redelegated_unbonded_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    dbg!(
+        &incoming_redels_src,
+        &outgoing_redels_src,
+        &tot_bonds_src,
+        &tot_unbonds_src,
+        &tot_redel_bonds_src,
+        &tot_redel_unbonds_src
+    );
+
+    // Checks
+    let redelegated = delegator_redelegated_bonds_handle(&delegator)
+        .at(&dest_validator)
+        .at(&(current_epoch + params.pipeline_len))
+        .at(&src_validator)
+        .get(&storage, &(init_epoch + params.pipeline_len))
+        .unwrap()
+        .unwrap();
+    assert_eq!(redelegated, amount_redelegate);
+
+    let redel_start_epoch =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .get(&storage, &delegator)
+            .unwrap()
+            .unwrap();
+    assert_eq!(redel_start_epoch, current_epoch + params.pipeline_len);
+
+    let redelegated = validator_outgoing_redelegations_handle(&src_validator)
+        .at(&dest_validator)
+        .at(&current_epoch.prev())
+        .get(&storage, &current_epoch)
+        .unwrap()
+        .unwrap();
+    assert_eq!(redelegated, amount_redelegate);
+
+    // Advance three epochs
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Unbond in epoch 5 from dest_validator
+    println!(
+        "\nUNBONDING {} TOKENS FROM {}\n",
+        amount_unbond.to_string_native(),
+        &dest_validator
+    );
+    let _ = unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &dest_validator,
+        amount_unbond,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    println!("\nAFTER UNBONDING\n");
+    println!("\nDELEGATOR\n");
+
+    let bonds_src = bond_handle(&delegator, &src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let bonds_dest = bond_handle(&delegator, &dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_src = unbond_handle(&delegator, &src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+
+    dbg!(
+        &bonds_src,
+        &bonds_dest,
+        &unbonds_src,
+        &unbonds_dest,
+        &redel_bonds,
+        &redel_unbonds
+    );
+
+    println!("\nDEST VALIDATOR\n");
+
+    let incoming_redels_dest =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let outgoing_redels_dest =
+        validator_outgoing_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_bonds_dest = total_bonded_handle(&dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_redel_bonds_dest =
+        validator_total_redelegated_bonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_redel_unbonds_dest =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    dbg!(
+        &incoming_redels_dest,
+        &outgoing_redels_dest,
+        &tot_bonds_dest,
+        &tot_unbonds_dest,
+        &tot_redel_bonds_dest,
+        &tot_redel_unbonds_dest
+    );
+
+    let bond_start = init_epoch + params.pipeline_len;
+    let redelegation_end = bond_start + params.pipeline_len + 1u64;
+    let unbond_end =
+        redelegation_end + params.withdrawable_epoch_offset() + 1u64;
+    let unbond_materialized = redelegation_end + params.pipeline_len + 1u64;
+
+    // Checks
+    let redelegated_remaining = delegator_redelegated_bonds_handle(&delegator)
+        .at(&dest_validator)
+        .at(&redelegation_end)
+        .at(&src_validator)
+        .get(&storage, &bond_start)
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(redelegated_remaining, amount_redelegate - amount_unbond);
+
+    let redel_unbonded = delegator_redelegated_unbonds_handle(&delegator)
+        .at(&dest_validator)
+        .at(&redelegation_end)
+        .at(&unbond_end)
+        .at(&src_validator)
+        .get(&storage, &bond_start)
+        .unwrap()
+        .unwrap();
+    assert_eq!(redel_unbonded, amount_unbond);
+
+    dbg!(unbond_materialized, redelegation_end, bond_start);
+    let total_redel_unbonded =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .at(&unbond_materialized)
+            .at(&redelegation_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+            .unwrap();
+    assert_eq!(total_redel_unbonded, amount_unbond);
+
+    // Advance to withdrawal epoch
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+        if current_epoch == unbond_end {
+            break;
+        }
+    }
+
+    // Withdraw
+    withdraw_tokens(
+        &mut storage,
+        Some(&delegator),
+        &dest_validator,
+        current_epoch,
+    )
+    .unwrap();
+
+    assert!(
+        delegator_redelegated_unbonds_handle(&delegator)
+            .at(&dest_validator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+
+    let delegator_balance = storage
+        .read::<token::Amount>(&token::balance_key(&staking_token, &delegator))
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(
+        delegator_balance,
+        del_balance - amount_delegate + amount_unbond
+    );
+}
+
+fn test_redelegation_with_slashing_aux(
+    mut validators: Vec<GenesisValidator>,
+    amount_delegate: token::Amount,
+    amount_redelegate: token::Amount,
+    amount_unbond: token::Amount,
+) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+    let src_validator = validators[0].address.clone();
+    let dest_validator = validators[1].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        // Avoid empty consensus set by removing the threshold
+        validator_stake_threshold: token::Amount::zero(),
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    for _ in 0..5 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    let init_epoch = current_epoch;
+
+    // Delegate in epoch 5 to src_validator
+    println!(
+        "\nBONDING {} TOKENS TO {}\n",
+        amount_delegate.to_string_native(),
+        &src_validator
+    );
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        amount_delegate,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    println!("\nAFTER DELEGATION\n");
+    let bonds = bond_handle(&delegator, &src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let bonds_dest = bond_handle(&delegator, &dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds = unbond_handle(&delegator, &src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_bonds = total_bonded_handle(&src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds = total_unbonded_handle(&src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    dbg!(&bonds, &bonds_dest, &unbonds, &tot_bonds, &tot_unbonds);
+
+    // Advance three epochs
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Redelegate in epoch 8
+    println!(
+        "\nREDELEGATING {} TOKENS TO {}\n",
+        amount_redelegate.to_string_native(),
+        &dest_validator
+    );
+
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &src_validator,
+        &dest_validator,
+        current_epoch,
+        amount_redelegate,
+    )
+    .unwrap();
+
+    println!("\nAFTER REDELEGATION\n");
+    println!("\nDELEGATOR\n");
+    let bonds_src = bond_handle(&delegator, &src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let bonds_dest = bond_handle(&delegator, &dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_src = unbond_handle(&delegator, &src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+
+    dbg!(
+        &bonds_src,
+        &bonds_dest,
+        &unbonds_src,
+        &unbonds_dest,
+        &redel_bonds,
+        &redel_unbonds
+    );
+
+    // Dest val
+    println!("\nDEST VALIDATOR\n");
+
+    let incoming_redels_dest =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let outgoing_redels_dest =
+        validator_outgoing_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_bonds_dest = total_bonded_handle(&dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_redel_bonds_dest =
+        validator_total_redelegated_bonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_redel_unbonds_dest =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    dbg!(
+        &incoming_redels_dest,
+        &outgoing_redels_dest,
+        &tot_bonds_dest,
+        &tot_unbonds_dest,
+        &tot_redel_bonds_dest,
+        &tot_redel_unbonds_dest
+    );
+
+    // Src val
+    println!("\nSRC VALIDATOR\n");
+
+    let incoming_redels_src =
+        validator_incoming_redelegations_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let outgoing_redels_src =
+        validator_outgoing_redelegations_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_bonds_src = total_bonded_handle(&src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds_src = total_unbonded_handle(&src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_redel_bonds_src =
+        validator_total_redelegated_bonded_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_redel_unbonds_src =
+        validator_total_redelegated_unbonded_handle(&src_validator)
+            .collect_map(&storage)
+            .unwrap();
+    dbg!(
+        &incoming_redels_src,
+        &outgoing_redels_src,
+        &tot_bonds_src,
+        &tot_unbonds_src,
+        &tot_redel_bonds_src,
+        &tot_redel_unbonds_src
+    );
+
+    // Checks
+    let redelegated = delegator_redelegated_bonds_handle(&delegator)
+        .at(&dest_validator)
+        .at(&(current_epoch + params.pipeline_len))
+        .at(&src_validator)
+        .get(&storage, &(init_epoch + params.pipeline_len))
+        .unwrap()
+        .unwrap();
+    assert_eq!(redelegated, amount_redelegate);
+
+    let redel_start_epoch =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .get(&storage, &delegator)
+            .unwrap()
+            .unwrap();
+    assert_eq!(redel_start_epoch, current_epoch + params.pipeline_len);
+
+    let redelegated = validator_outgoing_redelegations_handle(&src_validator)
+        .at(&dest_validator)
+        .at(&current_epoch.prev())
+        .get(&storage, &current_epoch)
+        .unwrap()
+        .unwrap();
+    assert_eq!(redelegated, amount_redelegate);
+
+    // Advance three epochs
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Unbond in epoch 11 from dest_validator
+    println!(
+        "\nUNBONDING {} TOKENS FROM {}\n",
+        amount_unbond.to_string_native(),
+        &dest_validator
+    );
+    let _ = unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &dest_validator,
+        amount_unbond,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    println!("\nAFTER UNBONDING\n");
+    println!("\nDELEGATOR\n");
+
+    let bonds_src = bond_handle(&delegator, &src_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let bonds_dest = bond_handle(&delegator, &dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_src = unbond_handle(&delegator, &src_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
+        .collect_map(&storage)
+        .unwrap();
+
+    dbg!(
+        &bonds_src,
+        &bonds_dest,
+        &unbonds_src,
+        &unbonds_dest,
+        &redel_bonds,
+        &redel_unbonds
+    );
+
+    println!("\nDEST VALIDATOR\n");
+
+    let incoming_redels_dest =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let outgoing_redels_dest =
+        validator_outgoing_redelegations_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_bonds_dest = total_bonded_handle(&dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
+        .collect_map(&storage)
+        .unwrap();
+    let tot_redel_bonds_dest =
+        validator_total_redelegated_bonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    let tot_redel_unbonds_dest =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .collect_map(&storage)
+            .unwrap();
+    dbg!(
+        &incoming_redels_dest,
+        &outgoing_redels_dest,
+        &tot_bonds_dest,
+        &tot_unbonds_dest,
+        &tot_redel_bonds_dest,
+        &tot_redel_unbonds_dest
+    );
+
+    // Advance one epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Discover evidence
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        init_epoch + 2 * params.pipeline_len,
+        0u64,
+        SlashType::DuplicateVote,
+        &src_validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    let bond_start = init_epoch + params.pipeline_len;
+    let redelegation_end = bond_start + params.pipeline_len + 1u64;
+    let unbond_end =
+        redelegation_end + params.withdrawable_epoch_offset() + 1u64;
+    let unbond_materialized = redelegation_end + params.pipeline_len + 1u64;
+
+    // Checks
+    let redelegated_remaining = delegator_redelegated_bonds_handle(&delegator)
+        .at(&dest_validator)
+        .at(&redelegation_end)
+        .at(&src_validator)
+        .get(&storage, &bond_start)
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(redelegated_remaining, amount_redelegate - amount_unbond);
+
+    let redel_unbonded = delegator_redelegated_unbonds_handle(&delegator)
+        .at(&dest_validator)
+        .at(&redelegation_end)
+        .at(&unbond_end)
+        .at(&src_validator)
+        .get(&storage, &bond_start)
+        .unwrap()
+        .unwrap();
+    assert_eq!(redel_unbonded, amount_unbond);
+
+    dbg!(unbond_materialized, redelegation_end, bond_start);
+    let total_redel_unbonded =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .at(&unbond_materialized)
+            .at(&redelegation_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+            .unwrap();
+    assert_eq!(total_redel_unbonded, amount_unbond);
+
+    // Advance to withdrawal epoch
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+        if current_epoch == unbond_end {
+            break;
+        }
+    }
+
+    // Withdraw
+    withdraw_tokens(
+        &mut storage,
+        Some(&delegator),
+        &dest_validator,
+        current_epoch,
+    )
+    .unwrap();
+
+    assert!(
+        delegator_redelegated_unbonds_handle(&delegator)
+            .at(&dest_validator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+
+    let delegator_balance = storage
+        .read::<token::Amount>(&token::balance_key(&staking_token, &delegator))
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(delegator_balance, del_balance - amount_delegate);
+}
+
+fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+    let src_validator = validators[0].address.clone();
+    let _init_stake_src = validators[0].tokens;
+    let dest_validator = validators[1].address.clone();
+    let _init_stake_dest = validators[1].tokens;
+    let dest_validator_2 = validators[2].address.clone();
+    let _init_stake_dest_2 = validators[2].tokens;
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // Delegate in epoch 0 to src_validator
+    let bond_amount: token::Amount = 100.into();
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        bond_amount,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    let bond_start = current_epoch + params.pipeline_len;
+
+    // Advance one epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Redelegate in epoch 1 to dest_validator
+    let redel_amount_1: token::Amount = 58.into();
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &src_validator,
+        &dest_validator,
+        current_epoch,
+        redel_amount_1,
+    )
+    .unwrap();
+
+    let redel_start = current_epoch;
+    let redel_end = current_epoch + params.pipeline_len;
+
+    // Checks ----------------
+
+    // Dest validator should have an incoming redelegation
+    let incoming_redelegation =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .get(&storage, &delegator)
+            .unwrap();
+    assert_eq!(incoming_redelegation, Some(redel_end));
+
+    // Src validator should have an outoging redelegation
+    let outgoing_redelegation =
+        validator_outgoing_redelegations_handle(&src_validator)
+            .at(&dest_validator)
+            .at(&bond_start)
+            .get(&storage, &redel_start)
+            .unwrap();
+    assert_eq!(outgoing_redelegation, Some(redel_amount_1));
+
+    // Delegator should have redelegated bonds
+    let del_total_redelegated_bonded =
+        delegator_redelegated_bonds_handle(&delegator)
+            .at(&dest_validator)
+            .at(&redel_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+            .unwrap_or_default();
+    assert_eq!(del_total_redelegated_bonded, redel_amount_1);
+
+    // There should be delegator bonds for both src and dest validators
+    let bonded_src = bond_handle(&delegator, &src_validator);
+    let bonded_dest = bond_handle(&delegator, &dest_validator);
+    assert_eq!(
+        bonded_src
+            .get_delta_val(&storage, bond_start)
+            .unwrap()
+            .unwrap_or_default(),
+        bond_amount - redel_amount_1
+    );
+    assert_eq!(
+        bonded_dest
+            .get_delta_val(&storage, redel_end)
+            .unwrap()
+            .unwrap_or_default(),
+        redel_amount_1
+    );
+
+    // The dest validator should have total redelegated bonded tokens
+    let dest_total_redelegated_bonded =
+        validator_total_redelegated_bonded_handle(&dest_validator)
+            .at(&redel_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+            .unwrap_or_default();
+    assert_eq!(dest_total_redelegated_bonded, redel_amount_1);
+
+    // The dest validator's total bonded should have an entry for the genesis
+    // bond and the redelegation
+    let dest_total_bonded = total_bonded_handle(&dest_validator)
+        .get_data_handler()
+        .collect_map(&storage)
+        .unwrap();
+    assert!(
+        dest_total_bonded.len() == 2
+            && dest_total_bonded.contains_key(&Epoch::default())
+    );
+    assert_eq!(
+        dest_total_bonded
+            .get(&redel_end)
+            .cloned()
+            .unwrap_or_default(),
+        redel_amount_1
+    );
+
+    // The src validator should have a total bonded entry for the original bond
+    // accounting for the redelegation
+    assert_eq!(
+        total_bonded_handle(&src_validator)
+            .get_delta_val(&storage, bond_start)
+            .unwrap()
+            .unwrap_or_default(),
+        bond_amount - redel_amount_1
+    );
+
+    // The src validator should have a total unbonded entry due to the
+    // redelegation
+    let src_total_unbonded = total_unbonded_handle(&src_validator)
+        .at(&redel_end)
+        .get(&storage, &bond_start)
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(src_total_unbonded, redel_amount_1);
+
+    // Attempt to redelegate in epoch 3 to dest_validator
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    let redel_amount_2: token::Amount = 23.into();
+    let redel_att = super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &dest_validator,
+        &dest_validator_2,
+        current_epoch,
+        redel_amount_2,
+    );
+    assert!(redel_att.is_err());
+
+    // Advance to right before the redelegation can be redelegated again
+    assert_eq!(redel_end, current_epoch);
+    let epoch_can_redel =
+        redel_end.prev() + params.slash_processing_epoch_offset();
+    assert_eq!(
+        can_redelegate(&storage, &delegator, &dest_validator, current_epoch)
+            .unwrap(),
+        RedelegationEligibility::ChainedUntil(epoch_can_redel)
+    );
+    assert_eq!(
+        can_redelegate(&storage, &delegator, &src_validator, current_epoch)
+            .unwrap(),
+        RedelegationEligibility::Eligible
+    );
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+        if current_epoch == epoch_can_redel.prev() {
+            break;
+        }
+    }
+
+    // Attempt to redelegate in epoch before we actually are able to
+    let redel_att = super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &dest_validator,
+        &dest_validator_2,
+        current_epoch,
+        redel_amount_2,
+    );
+    assert!(redel_att.is_err());
+
+    // Advance one more epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    assert_eq!(
+        can_redelegate(&storage, &delegator, &dest_validator, current_epoch)
+            .unwrap(),
+        RedelegationEligibility::Eligible
+    );
+
+    // Redelegate from dest_validator to dest_validator_2 now
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &dest_validator,
+        &dest_validator_2,
+        current_epoch,
+        redel_amount_2,
+    )
+    .unwrap();
+
+    let redel_2_start = current_epoch;
+    let redel_2_end = current_epoch + params.pipeline_len;
+
+    // Checks -----------------------------------
+
+    // Both the dest validator and dest validator 2 should have incoming
+    // redelegations
+    let incoming_redelegation_1 =
+        validator_incoming_redelegations_handle(&dest_validator)
+            .get(&storage, &delegator)
+            .unwrap();
+    assert_eq!(incoming_redelegation_1, Some(redel_end));
+    let incoming_redelegation_2 =
+        validator_incoming_redelegations_handle(&dest_validator_2)
+            .get(&storage, &delegator)
+            .unwrap();
+    assert_eq!(incoming_redelegation_2, Some(redel_2_end));
+
+    // Both the src validator and dest validator should have outgoing
+    // redelegations
+    let outgoing_redelegation_1 =
+        validator_outgoing_redelegations_handle(&src_validator)
+            .at(&dest_validator)
+            .at(&bond_start)
+            .get(&storage, &redel_start)
+            .unwrap();
+    assert_eq!(outgoing_redelegation_1, Some(redel_amount_1));
+
+    let outgoing_redelegation_2 =
+        validator_outgoing_redelegations_handle(&dest_validator)
+            .at(&dest_validator_2)
+            .at(&redel_end)
+            .get(&storage, &redel_2_start)
+            .unwrap();
+    assert_eq!(outgoing_redelegation_2, Some(redel_amount_2));
+
+    // All three validators should have bonds
+    let bonded_dest2 = bond_handle(&delegator, &dest_validator_2);
+    assert_eq!(
+        bonded_src
+            .get_delta_val(&storage, bond_start)
+            .unwrap()
+            .unwrap_or_default(),
+        bond_amount - redel_amount_1
+    );
+    assert_eq!(
+        bonded_dest
+            .get_delta_val(&storage, redel_end)
+            .unwrap()
+            .unwrap_or_default(),
+        redel_amount_1 - redel_amount_2
+    );
+    assert_eq!(
+        bonded_dest2
+            .get_delta_val(&storage, redel_2_end)
+            .unwrap()
+            .unwrap_or_default(),
+        redel_amount_2
+    );
+
+    // There should be no unbond entries
+    let unbond_src = unbond_handle(&delegator, &src_validator);
+    let unbond_dest = unbond_handle(&delegator, &dest_validator);
+    assert!(unbond_src.is_empty(&storage).unwrap());
+    assert!(unbond_dest.is_empty(&storage).unwrap());
+
+    // The dest validator should have some total unbonded due to the second
+    // redelegation
+    let dest_total_unbonded = total_unbonded_handle(&dest_validator)
+        .at(&redel_2_end)
+        .get(&storage, &redel_end)
+        .unwrap();
+    assert_eq!(dest_total_unbonded, Some(redel_amount_2));
+
+    // Delegator should have redelegated bonds due to both redelegations
+    let del_redelegated_bonds = delegator_redelegated_bonds_handle(&delegator);
+    assert_eq!(
+        Some(redel_amount_1 - redel_amount_2),
+        del_redelegated_bonds
+            .at(&dest_validator)
+            .at(&redel_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+    );
+    assert_eq!(
+        Some(redel_amount_2),
+        del_redelegated_bonds
+            .at(&dest_validator_2)
+            .at(&redel_2_end)
+            .at(&dest_validator)
+            .get(&storage, &redel_end)
+            .unwrap()
+    );
+
+    // Delegator redelegated unbonds should be empty
+    assert!(
+        delegator_redelegated_unbonds_handle(&delegator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+
+    // Both the dest validator and dest validator 2 should have total
+    // redelegated bonds
+    let dest_redelegated_bonded =
+        validator_total_redelegated_bonded_handle(&dest_validator)
+            .at(&redel_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+            .unwrap_or_default();
+    let dest2_redelegated_bonded =
+        validator_total_redelegated_bonded_handle(&dest_validator_2)
+            .at(&redel_2_end)
+            .at(&dest_validator)
+            .get(&storage, &redel_end)
+            .unwrap()
+            .unwrap_or_default();
+    assert_eq!(dest_redelegated_bonded, redel_amount_1 - redel_amount_2);
+    assert_eq!(dest2_redelegated_bonded, redel_amount_2);
+
+    // Total redelegated unbonded should be empty for src_validator and
+    // dest_validator_2
+    assert!(
+        validator_total_redelegated_unbonded_handle(&dest_validator_2)
+            .is_empty(&storage)
+            .unwrap()
+    );
+    assert!(
+        validator_total_redelegated_unbonded_handle(&src_validator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+
+    // The dest_validator should have total_redelegated unbonded
+    let tot_redel_unbonded =
+        validator_total_redelegated_unbonded_handle(&dest_validator)
+            .at(&redel_2_end)
+            .at(&redel_end)
+            .at(&src_validator)
+            .get(&storage, &bond_start)
+            .unwrap()
+            .unwrap_or_default();
+    assert_eq!(tot_redel_unbonded, redel_amount_2);
+}
+
+/// SM test case 1 from Brent
+#[test]
+fn test_from_sm_case_1() {
+    use namada_core::types::address::testing::established_address_4;
+
+    let mut storage = TestWlStorage::default();
+    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
+    gov_params.init_storage(&mut storage).unwrap();
+    write_pos_params(&mut storage, &OwnedPosParams::default()).unwrap();
+
+    let validator = established_address_1();
+    let redeleg_src_1 = established_address_2();
+    let redeleg_src_2 = established_address_3();
+    let owner = established_address_4();
+    let unbond_amount = token::Amount::from(3130688);
+    println!(
+        "Owner: {owner}\nValidator: {validator}\nRedeleg src 1: \
+         {redeleg_src_1}\nRedeleg src 2: {redeleg_src_2}"
+    );
+
+    // Validator's incoming redelegations
+    let outer_epoch_1 = Epoch(27);
+    // from redeleg_src_1
+    let epoch_1_redeleg_1 = token::Amount::from(8516);
+    // from redeleg_src_2
+    let epoch_1_redeleg_2 = token::Amount::from(5704386);
+    let outer_epoch_2 = Epoch(30);
+    // from redeleg_src_2
+    let epoch_2_redeleg_2 = token::Amount::from(1035191);
+
+    // Insert the data - bonds and redelegated bonds
+    let bonds_handle = bond_handle(&owner, &validator);
+    bonds_handle
+        .add(
+            &mut storage,
+            epoch_1_redeleg_1 + epoch_1_redeleg_2,
+            outer_epoch_1,
+            0,
+        )
+        .unwrap();
+    bonds_handle
+        .add(&mut storage, epoch_2_redeleg_2, outer_epoch_2, 0)
+        .unwrap();
+
+    let redelegated_bonds_map_1 = delegator_redelegated_bonds_handle(&owner)
+        .at(&validator)
+        .at(&outer_epoch_1);
+    redelegated_bonds_map_1
+        .at(&redeleg_src_1)
+        .insert(&mut storage, Epoch(14), epoch_1_redeleg_1)
+        .unwrap();
+    redelegated_bonds_map_1
+        .at(&redeleg_src_2)
+        .insert(&mut storage, Epoch(18), epoch_1_redeleg_2)
+        .unwrap();
+    let redelegated_bonds_map_1 = delegator_redelegated_bonds_handle(&owner)
+        .at(&validator)
+        .at(&outer_epoch_1);
+
+    let redelegated_bonds_map_2 = delegator_redelegated_bonds_handle(&owner)
+        .at(&validator)
+        .at(&outer_epoch_2);
+    redelegated_bonds_map_2
+        .at(&redeleg_src_2)
+        .insert(&mut storage, Epoch(18), epoch_2_redeleg_2)
+        .unwrap();
+
+    // Find the modified redelegation the same way as `unbond_tokens`
+    let bonds_to_unbond = find_bonds_to_remove(
+        &storage,
+        &bonds_handle.get_data_handler(),
+        unbond_amount,
+    )
+    .unwrap();
+    dbg!(&bonds_to_unbond);
+
+    let (new_entry_epoch, new_bond_amount) = bonds_to_unbond.new_entry.unwrap();
+    assert_eq!(outer_epoch_1, new_entry_epoch);
+    // The modified bond should be sum of all redelegations less the unbonded
+    // amouunt
+    assert_eq!(
+        epoch_1_redeleg_1 + epoch_1_redeleg_2 + epoch_2_redeleg_2
+            - unbond_amount,
+        new_bond_amount
+    );
+    // The current bond should be sum of redelegations fom the modified epoch
+    let cur_bond_amount = bonds_handle
+        .get_delta_val(&storage, new_entry_epoch)
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(epoch_1_redeleg_1 + epoch_1_redeleg_2, cur_bond_amount);
+
+    let mr = compute_modified_redelegation(
+        &storage,
+        &redelegated_bonds_map_1,
+        new_entry_epoch,
+        cur_bond_amount - new_bond_amount,
+    )
+    .unwrap();
+
+    let exp_mr = ModifiedRedelegation {
+        epoch: Some(Epoch(27)),
+        validators_to_remove: BTreeSet::from_iter([redeleg_src_2.clone()]),
+        validator_to_modify: Some(redeleg_src_2),
+        epochs_to_remove: BTreeSet::from_iter([Epoch(18)]),
+        epoch_to_modify: Some(Epoch(18)),
+        new_amount: Some(token::Amount::from(3608889)),
+    };
+
+    pretty_assertions::assert_eq!(mr, exp_mr);
+}
+
+/// Test precisely that we are not overslashing, as originally discovered by Tomas in this issue: https://github.com/informalsystems/partnership-heliax/issues/74
+fn test_overslashing_aux(mut validators: Vec<GenesisValidator>) {
+    assert_eq!(validators.len(), 4);
+
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    let offending_stake = token::Amount::native_whole(110);
+    let other_stake = token::Amount::native_whole(100);
+
+    // Set stakes so we know we will get a slashing rate between 0.5 -1.0
+    validators[0].tokens = offending_stake;
+    validators[1].tokens = other_stake;
+    validators[2].tokens = other_stake;
+    validators[3].tokens = other_stake;
+
+    // Get the offending validator
+    let validator = validators[0].address.clone();
+
+    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    let mut storage = TestWlStorage::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    // Get a delegator with some tokens
+    let staking_token = storage.storage.native_token.clone();
+    let delegator = address::testing::gen_implicit_address();
+    let amount_del = token::Amount::native_whole(5);
+    credit_tokens(&mut storage, &staking_token, &delegator, amount_del)
+        .unwrap();
+
+    // Delegate tokens in epoch 0 to validator
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        amount_del,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    let self_bond_epoch = current_epoch;
+    let delegation_epoch = current_epoch + params.pipeline_len;
+
+    // Advance to pipeline epoch
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+    assert_eq!(delegation_epoch, current_epoch);
+
+    // Find a misbehavior committed in epoch 0
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        self_bond_epoch,
+        0_u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    // Find a misbehavior committed in current epoch
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        delegation_epoch,
+        0_u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    let processing_epoch_1 =
+        self_bond_epoch + params.slash_processing_epoch_offset();
+    let processing_epoch_2 =
+        delegation_epoch + params.slash_processing_epoch_offset();
+
+    // Advance to processing epoch 1
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+        if current_epoch == processing_epoch_1 {
+            break;
+        }
+    }
+
+    let total_stake_1 = offending_stake + 3 * other_stake;
+    let stake_frac = Dec::from(offending_stake) / Dec::from(total_stake_1);
+    let slash_rate_1 = Dec::from_str("9.0").unwrap() * stake_frac * stake_frac;
+    dbg!(&slash_rate_1);
+
+    let exp_slashed_1 = offending_stake.mul_ceil(slash_rate_1);
+
+    // Check that the proper amount was slashed
+    let epoch = current_epoch.next();
+    let validator_stake =
+        read_validator_stake(&storage, &params, &validator, epoch).unwrap();
+    let exp_validator_stake = offending_stake - exp_slashed_1 + amount_del;
+    assert_eq!(validator_stake, exp_validator_stake);
+
+    let total_stake = read_total_stake(&storage, &params, epoch).unwrap();
+    let exp_total_stake =
+        offending_stake - exp_slashed_1 + amount_del + 3 * other_stake;
+    assert_eq!(total_stake, exp_total_stake);
+
+    let self_bond_id = BondId {
+        source: validator.clone(),
+        validator: validator.clone(),
+    };
+    let bond_amount =
+        crate::bond_amount(&storage, &self_bond_id, epoch).unwrap();
+    let exp_bond_amount = offending_stake - exp_slashed_1;
+    assert_eq!(bond_amount, exp_bond_amount);
+
+    // Advance to processing epoch 2
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+        if current_epoch == processing_epoch_2 {
+            break;
+        }
+    }
+
+    let total_stake_2 = offending_stake + amount_del + 3 * other_stake;
+    let stake_frac =
+        Dec::from(offending_stake + amount_del) / Dec::from(total_stake_2);
+    let slash_rate_2 = Dec::from_str("9.0").unwrap() * stake_frac * stake_frac;
+    dbg!(&slash_rate_2);
+
+    let exp_slashed_from_delegation = amount_del.mul_ceil(slash_rate_2);
+
+    // Check that the proper amount was slashed. We expect that all of the
+    // validator self-bond has been slashed and some of the delegation has been
+    // slashed due to the second infraction.
+    let epoch = current_epoch.next();
+
+    let validator_stake =
+        read_validator_stake(&storage, &params, &validator, epoch).unwrap();
+    let exp_validator_stake = amount_del - exp_slashed_from_delegation;
+    assert_eq!(validator_stake, exp_validator_stake);
+
+    let total_stake = read_total_stake(&storage, &params, epoch).unwrap();
+    let exp_total_stake =
+        amount_del - exp_slashed_from_delegation + 3 * other_stake;
+    assert_eq!(total_stake, exp_total_stake);
+
+    let delegation_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
+    let delegation_amount =
+        crate::bond_amount(&storage, &delegation_id, epoch).unwrap();
+    let exp_del_amount = amount_del - exp_slashed_from_delegation;
+    assert_eq!(delegation_amount, exp_del_amount);
+
+    let self_bond_amount =
+        crate::bond_amount(&storage, &self_bond_id, epoch).unwrap();
+    let exp_bond_amount = token::Amount::zero();
+    assert_eq!(self_bond_amount, exp_bond_amount);
+}
+
+fn test_simulate_slash_aux(mut validators: Vec<GenesisValidator>) {
+    assert_eq!(validators.len(), 4);
+
+    let offending_stake = token::Amount::native_whole(110);
+    let other_stake = token::Amount::native_whole(100);
+
+    // Set stakes so we know we will get a cubic slashing rate above the
+    // minimum slash rate
+    validators[0].tokens = offending_stake;
+    validators[1].tokens = other_stake;
+    validators[2].tokens = other_stake;
+    validators[3].tokens = other_stake;
+
+    // Get the offending validator
+    let validator = validators[0].address.clone();
+
+    let mut storage = TestWlStorage::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        OwnedPosParams::default(),
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let infraction_epoch = current_epoch;
+    let processing_epoch =
+        infraction_epoch + params.slash_processing_epoch_offset();
+
+    // Simulate an infraction committed in epoch 0
+    let estimate = simulate_slash(
+        &storage,
+        &validator,
+        infraction_epoch,
+        SlashType::DuplicateVote,
+    )
+    .unwrap();
+    let total_stake = offending_stake + 3 * other_stake;
+    let stake_frac = Dec::from(offending_stake) / Dec::from(total_stake);
+    let exp_cubic_rate =
+        Dec::from_str("9.0").unwrap() * stake_frac * stake_frac;
+    assert_eq!(estimate.processing_epoch, processing_epoch);
+    assert_eq!(estimate.cubic_slash_rate, exp_cubic_rate);
+    assert_eq!(estimate.slash_rate, exp_cubic_rate);
+    assert_eq!(estimate.slashed_amount, exp_cubic_rate * offending_stake);
+
+    // The simulation doesn't enqueue any slash
+    assert!(
+        crate::enqueued_slashes_handle()
+            .at(&processing_epoch)
+            .is_empty(&storage)
+            .unwrap()
+    );
+
+    // Find the misbehavior
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        infraction_epoch,
+        0_u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    // The enqueued slash matches the simulated cubic slash rate
+    let cubic_rate =
+        crate::compute_cubic_slash_rate(&storage, &params, infraction_epoch)
+            .unwrap();
+    assert_eq!(cubic_rate, estimate.cubic_slash_rate);
+
+    // Simulating another infraction in the same epoch doesn't count the
+    // validator's stake twice, but both slashes add up
+    let second_estimate = simulate_slash(
+        &storage,
+        &validator,
+        infraction_epoch,
+        SlashType::DuplicateVote,
+    )
+    .unwrap();
+    assert_eq!(second_estimate.cubic_slash_rate, estimate.cubic_slash_rate);
+    assert_eq!(
+        second_estimate.slash_rate,
+        min(Dec::one(), estimate.slash_rate + estimate.slash_rate)
+    );
+
+    // Process the slash and check that the simulated rate was applied
+    while current_epoch < processing_epoch {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+    let slashes = validator_slashes_handle(&validator)
+        .iter(&storage)
+        .unwrap()
+        .collect::<storage_api::Result<Vec<Slash>>>()
+        .unwrap();
+    assert_eq!(slashes.len(), 1);
+    assert_eq!(slashes[0].rate, estimate.slash_rate);
+
+    let validator_stake = read_validator_stake(
+        &storage,
+        &params,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+    assert_eq!(
+        validator_stake,
+        offending_stake - offending_stake.mul_ceil(estimate.slash_rate)
+    );
+}
+
+fn test_find_withdrawable_unbonds_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+    let mut storage = TestWlStorage::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        OwnedPosParams::default(),
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+    let offset = params.withdrawable_epoch_offset();
+
+    // Get a delegator with some tokens and bond to the validator
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1_000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        del_balance,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Nothing is queued before unbonding
+    let queue = find_withdrawable_unbonds(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(queue.is_empty());
+
+    // Unbond in two consecutive epochs once the bond contributes to the stake
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+    let bond_start = current_epoch;
+    let first_amount = token::Amount::native_whole(400);
+    unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        first_amount,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    let first_withdraw = current_epoch + offset;
+
+    current_epoch = advance_epoch(&mut storage, &params);
+    let second_amount = token::Amount::native_whole(200);
+    unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        second_amount,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    let second_withdraw = current_epoch + offset;
+
+    // Without slashes, the whole unbonded amounts are withdrawable
+    let queue = find_withdrawable_unbonds(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(
+        queue,
+        vec![
+            QueuedUnbond {
+                start_epoch: bond_start,
+                withdraw_epoch: first_withdraw,
+                epochs_until_withdrawable: offset - 1,
+                amount: first_amount,
+                withdrawable_amount: first_amount,
+            },
+            QueuedUnbond {
+                start_epoch: bond_start,
+                withdraw_epoch: second_withdraw,
+                epochs_until_withdrawable: offset,
+                amount: second_amount,
+                withdrawable_amount: second_amount,
+            },
+        ]
+    );
+
+    // Slash the validator for an infraction committed while the unbonded
+    // tokens were still contributing to its stake
+    let infraction_epoch = current_epoch;
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        infraction_epoch,
+        0_u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    // Advance until the first unbond becomes withdrawable, processing the
+    // slash on the way
+    while current_epoch < first_withdraw {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    let queue = find_withdrawable_unbonds(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue[0].epochs_until_withdrawable, 0);
+    assert_eq!(queue[1].epochs_until_withdrawable, 1);
+    for unbond in &queue {
+        assert!(unbond.withdrawable_amount < unbond.amount);
+    }
+
+    // The withdrawn amount matches the queried withdrawable amount
+    let withdrawn = withdraw_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(withdrawn, queue[0].withdrawable_amount);
+
+    // Only the second unbond is left in the queue
+    let remaining = find_withdrawable_unbonds(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(remaining, queue[1..].to_vec());
+}
+
+fn test_unslashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let validator1 = validators[0].address.clone();
+    let validator2 = validators[1].address.clone();
+
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        10_000.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Unbond some from validator 1
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        1_342.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        1_875.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        584.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance an epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        384.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Unbond some from validator 1
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        144.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        3_448.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        699.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance an epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        4_384.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        1_008.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        3_500.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Checks
+    let val1_init_stake = validators[0].tokens;
+
+    for epoch in Epoch::iter_bounds_inclusive(
+        Epoch(0),
+        current_epoch + params.pipeline_len,
+    ) {
+        let bond_amount = crate::bond_amount(
+            &storage,
+            &BondId {
+                source: delegator.clone(),
+                validator: validator1.clone(),
+            },
+            epoch,
+        )
+        .unwrap_or_default();
+
+        let val_stake =
+            crate::read_validator_stake(&storage, &params, &validator1, epoch)
+                .unwrap();
+        // dbg!(&bond_amount);
+        assert_eq!(val_stake - val1_init_stake, bond_amount);
+    }
+}
+
+fn test_log_block_rewards_aux(
+    validators: Vec<GenesisValidator>,
+    params: OwnedPosParams,
+) {
+    tracing::info!(
+        "New case with {} validators: {:#?}",
+        validators.len(),
+        validators
+            .iter()
+            .map(|v| (&v.address, v.tokens.to_string_native()))
+            .collect::<Vec<_>>()
+    );
+    let mut s = TestWlStorage::default();
+    // Init genesis
+    let current_epoch = s.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+    let total_stake =
+        crate::get_total_consensus_stake(&s, current_epoch, &params).unwrap();
+    let consensus_set =
+        crate::read_consensus_validator_set_addresses(&s, current_epoch)
+            .unwrap();
+    let proposer_address = consensus_set.iter().next().unwrap().clone();
+
+    tracing::info!(
+            ?params.block_proposer_reward,
+            ?params.block_vote_reward,
+    );
+    tracing::info!(?proposer_address,);
+
+    // Rewards accumulator should be empty at first
+    let rewards_handle = crate::rewards_accumulator_handle();
+    assert!(rewards_handle.is_empty(&s).unwrap());
+
+    let mut last_rewards = BTreeMap::default();
+
+    let num_blocks = 100;
+    // Loop through `num_blocks`, log rewards & check results
+    for i in 0..num_blocks {
+        tracing::info!("");
+        tracing::info!("Block {}", i + 1);
+
+        // A helper closure to prepare minimum required votes
+        let prep_votes = |epoch| {
+            // Ceil of 2/3 of total stake
+            let min_required_votes = total_stake.mul_ceil(Dec::two() / 3);
+
+            let mut total_votes = token::Amount::zero();
+            let mut non_voters = HashSet::<Address>::default();
+            let mut prep_vote = |validator| {
+                // Add validator vote if it's in consensus set and if we don't
+                // yet have min required votes
+                if consensus_set.contains(validator)
+                    && total_votes < min_required_votes
+                {
+                    let stake =
+                        read_validator_stake(&s, &params, validator, epoch)
+                            .unwrap();
+                    total_votes += stake;
+                    let validator_vp =
+                        into_tm_voting_power(params.tm_votes_per_token, stake)
+                            as u64;
+                    tracing::info!("Validator {validator} signed");
+                    Some(VoteInfo {
+                        validator_address: validator.clone(),
+                        validator_vp,
+                    })
+                } else {
+                    non_voters.insert(validator.clone());
+                    None
+                }
+            };
+
+            let votes: Vec<VoteInfo> = validators
+                .iter()
+                .rev()
+                .filter_map(|validator| prep_vote(&validator.address))
+                .collect();
+            (votes, total_votes, non_voters)
+        };
+
+        let (votes, signing_stake, non_voters) = prep_votes(current_epoch);
+        crate::log_block_rewards(
+            &mut s,
+            current_epoch,
+            &proposer_address,
+            votes.clone(),
+            &HashSet::new(),
+            &ConsensusValidatorSetCache::default(),
+        )
+        .unwrap();
+
+        assert!(!rewards_handle.is_empty(&s).unwrap());
+
+        let rewards_calculator = PosRewardsCalculator {
+            proposer_reward: params.block_proposer_reward,
+            signer_reward: params.block_vote_reward,
+            vext_reward: params.block_vext_reward,
+            signing_stake,
+            vext_stake: token::Amount::zero(),
+            total_stake,
+        };
+        let coeffs = rewards_calculator.get_reward_coeffs().unwrap();
+        tracing::info!(?coeffs);
+
+        // Check proposer reward
+        let stake =
+            read_validator_stake(&s, &params, &proposer_address, current_epoch)
+                .unwrap();
+        let proposer_signing_reward = votes.iter().find_map(|vote| {
+            if vote.validator_address == proposer_address {
+                let signing_fraction =
+                    Dec::from(stake) / Dec::from(signing_stake);
+                Some(coeffs.signer_coeff * signing_fraction)
+            } else {
+                None
+            }
+        });
+        let expected_proposer_rewards = last_rewards.get(&proposer_address).copied().unwrap_or_default() +
+        // Proposer reward
+        coeffs.proposer_coeff
+        // Consensus validator reward
+        + (coeffs.active_val_coeff
+            * (Dec::from(stake) / Dec::from(total_stake)))
+        // Signing reward (if proposer voted)
+        + proposer_signing_reward
+            .unwrap_or_default();
+        tracing::info!(
+            "Expected proposer rewards: {expected_proposer_rewards}. Signed \
+             block: {}",
+            proposer_signing_reward.is_some()
+        );
+        assert_eq!(
+            rewards_handle.get(&s, &proposer_address).unwrap(),
+            Some(expected_proposer_rewards)
+        );
+
+        // Check voters rewards
+        for VoteInfo {
+            validator_address, ..
+        } in votes.iter()
+        {
+            // Skip proposer, in case voted - already checked
+            if validator_address == &proposer_address {
+                continue;
+            }
+
+            let stake = read_validator_stake(
+                &s,
+                &params,
+                validator_address,
+                current_epoch,
+            )
+            .unwrap();
+            let signing_fraction = Dec::from(stake) / Dec::from(signing_stake);
+            let expected_signer_rewards = last_rewards
+                .get(validator_address)
+                .copied()
+                .unwrap_or_default()
+                + coeffs.signer_coeff * signing_fraction
+                + (coeffs.active_val_coeff
+                    * (Dec::from(stake) / Dec::from(total_stake)));
+            tracing::info!(
+                "Expected signer {validator_address} rewards: \
+                 {expected_signer_rewards}"
+            );
+            assert_eq!(
+                rewards_handle.get(&s, validator_address).unwrap(),
+                Some(expected_signer_rewards)
+            );
+        }
+
+        // Check non-voters rewards, if any
+        for address in non_voters {
+            // Skip proposer, in case it didn't vote - already checked
+            if address == proposer_address {
+                continue;
+            }
+
+            if consensus_set.contains(&address) {
+                let stake =
+                    read_validator_stake(&s, &params, &address, current_epoch)
+                        .unwrap();
+                let expected_non_signer_rewards =
+                    last_rewards.get(&address).copied().unwrap_or_default()
+                        + coeffs.active_val_coeff
+                            * (Dec::from(stake) / Dec::from(total_stake));
+                tracing::info!(
+                    "Expected non-signer {address} rewards: \
+                     {expected_non_signer_rewards}"
+                );
+                assert_eq!(
+                    rewards_handle.get(&s, &address).unwrap(),
+                    Some(expected_non_signer_rewards)
+                );
+            } else {
+                let last_reward = last_rewards.get(&address).copied();
+                assert_eq!(
+                    rewards_handle.get(&s, &address).unwrap(),
+                    last_reward
+                );
+            }
+        }
+        s.commit_block().unwrap();
+
+        last_rewards =
+            crate::rewards_accumulator_handle().collect_map(&s).unwrap();
+
+        let rewards_sum: Dec = last_rewards.values().copied().sum();
+        let expected_sum = Dec::one() * (i as u64 + 1);
+        let err_tolerance = Dec::new(1, 9).unwrap();
+        let fail_msg = format!(
+            "Expected rewards sum at block {} to be {expected_sum}, got \
+             {rewards_sum}. Error tolerance {err_tolerance}.",
+            i + 1
+        );
+        assert!(expected_sum <= rewards_sum + err_tolerance, "{fail_msg}");
+        assert!(rewards_sum <= expected_sum, "{fail_msg}");
+    }
+}
+
+fn test_update_rewards_products_aux(validators: Vec<GenesisValidator>) {
+    tracing::info!(
+        "New case with {} validators: {:#?}",
+        validators.len(),
+        validators
+            .iter()
+            .map(|v| (&v.address, v.tokens.to_string_native()))
+            .collect::<Vec<_>>()
+    );
+    let mut s = TestWlStorage::default();
+    // Init genesis
+    let current_epoch = s.storage.block.epoch;
+    let params = OwnedPosParams::default();
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.into_iter(),
+        current_epoch,
     )
     .unwrap();
-    assert_eq!(result.sum, 10.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 4.into()), (4.into(), 6.into())],
-    );
-}
+    s.commit_block().unwrap();
 
-/// `computeAmountAfterSlashingWithdrawTest`
-#[test]
-fn compute_amount_after_slashing_withdraw_test() {
-    let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
+    let staking_token = staking_token_address(&s);
+    let consensus_set =
+        crate::read_consensus_validator_set_addresses(&s, current_epoch)
+            .unwrap();
 
-    // Test data
-    let alice = established_address_1();
-    let bob = established_address_2();
-    let unbonds_and_redelegated_unbonds: BTreeMap<
-        (Epoch, Epoch),
-        (token::Amount, EagerRedelegatedBondsMap),
-    > = BTreeMap::from_iter([
-        (
-            (Epoch(2), Epoch(20)),
-            (
-                // unbond
-                token::Amount::from(5),
-                // redelegations
-                BTreeMap::from_iter([(
-                    alice.clone(),
-                    BTreeMap::from_iter([(Epoch(1), token::Amount::from(1))]),
-                )]),
-            ),
-        ),
-        (
-            (Epoch(4), Epoch(20)),
-            (
-                // unbond
-                token::Amount::from(6),
-                // redelegations
-                BTreeMap::default(),
-            ),
-        ),
-    ]);
+    // Start a new epoch
+    let current_epoch = advance_epoch(&mut s, &params);
 
-    // Test case 1
-    let slashes = vec![];
-    let result = compute_amount_after_slashing_withdraw(
-        &storage,
+    // Read some data before applying rewards
+    let pos_balance_pre =
+        read_balance(&s, &staking_token, &address::POS).unwrap();
+    let gov_balance_pre =
+        read_balance(&s, &staking_token, &address::GOV).unwrap();
+
+    let num_consensus_validators = consensus_set.len() as u64;
+    let accum_val = Dec::one() / num_consensus_validators;
+    let num_blocks_in_last_epoch = 1000;
+
+    // Assign some reward accumulator values to consensus validator
+    for validator in &consensus_set {
+        crate::rewards_accumulator_handle()
+            .insert(
+                &mut s,
+                validator.clone(),
+                accum_val * num_blocks_in_last_epoch,
+            )
+            .unwrap();
+    }
+
+    // Distribute inflation into rewards
+    let last_epoch = current_epoch.prev();
+    let inflation = token::Amount::native_whole(10_000_000);
+    crate::update_rewards_products_and_mint_inflation(
+        &mut s,
         &params,
-        &unbonds_and_redelegated_unbonds,
-        slashes,
+        last_epoch,
+        num_blocks_in_last_epoch,
+        inflation,
+        &staking_token,
     )
     .unwrap();
-    assert_eq!(result.sum, 11.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 5.into()), (4.into(), 6.into())],
+
+    let pos_balance_post =
+        read_balance(&s, &staking_token, &address::POS).unwrap();
+    let gov_balance_post =
+        read_balance(&s, &staking_token, &address::GOV).unwrap();
+
+    assert_eq!(
+        pos_balance_pre + gov_balance_pre + inflation,
+        pos_balance_post + gov_balance_post,
+        "Expected inflation to be minted to PoS and left-over amount to Gov"
     );
 
-    // Test case 2
-    let bob_slash = Slash {
-        epoch: Epoch(5),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-    let slashes = vec![bob_slash.clone()];
-    validator_slashes_handle(&bob)
-        .push(&mut storage, bob_slash)
-        .unwrap();
-    let result = compute_amount_after_slashing_withdraw(
-        &storage,
-        &params,
-        &unbonds_and_redelegated_unbonds,
-        slashes,
-    )
-    .unwrap();
-    assert_eq!(result.sum, 0.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 0.into()), (4.into(), 0.into())],
+    let pos_credit = pos_balance_post - pos_balance_pre;
+    let gov_credit = gov_balance_post - gov_balance_pre;
+    assert!(
+        pos_credit > gov_credit,
+        "PoS must receive more tokens than Gov, but got {} in PoS and {} in \
+         Gov",
+        pos_credit.to_string_native(),
+        gov_credit.to_string_native()
     );
 
-    // Test case 3
-    let alice_slash = Slash {
-        epoch: Epoch(0),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-    let slashes = vec![alice_slash.clone()];
-    validator_slashes_handle(&alice)
-        .push(&mut storage, alice_slash)
-        .unwrap();
-    validator_slashes_handle(&bob).pop(&mut storage).unwrap();
-    let result = compute_amount_after_slashing_withdraw(
-        &storage,
-        &params,
-        &unbonds_and_redelegated_unbonds,
-        slashes,
-    )
-    .unwrap();
-    assert_eq!(result.sum, 11.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 5.into()), (4.into(), 6.into())],
+    // Rewards accumulator must be cleared out
+    let rewards_handle = crate::rewards_accumulator_handle();
+    assert!(rewards_handle.is_empty(&s).unwrap());
+
+    // The applied inflation must be recorded for the last epoch only
+    assert_eq!(
+        read_inflation_amount(&s, last_epoch).unwrap(),
+        Some(inflation)
     );
+    assert_eq!(read_inflation_amount(&s, current_epoch).unwrap(), None);
+}
 
-    // Test case 4
-    let alice_slash = Slash {
-        epoch: Epoch(1),
-        block_height: Default::default(),
-        r#type: SlashType::DuplicateVote,
-        rate: Dec::one(),
-    };
-    let slashes = vec![alice_slash.clone()];
-    validator_slashes_handle(&alice).pop(&mut storage).unwrap();
-    validator_slashes_handle(&alice)
-        .push(&mut storage, alice_slash)
-        .unwrap();
-    let result = compute_amount_after_slashing_withdraw(
-        &storage,
-        &params,
-        &unbonds_and_redelegated_unbonds,
-        slashes,
+fn test_rewards_dust_reconciliation_aux(validators: Vec<GenesisValidator>) {
+    let mut s = TestWlStorage::default();
+    // Genesis
+    let current_epoch = s.storage.block.epoch;
+    let params = OwnedPosParams::default();
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.into_iter(),
+        current_epoch,
     )
     .unwrap();
-    assert_eq!(result.sum, 10.into());
-    itertools::assert_equal(
-        result.epoch_map,
-        [(2.into(), 4.into()), (4.into(), 6.into())],
-    );
-}
+    s.commit_block().unwrap();
 
-fn arb_redelegation_amounts(
-    max_delegation: u64,
-) -> impl Strategy<Value = (token::Amount, token::Amount, token::Amount)> {
-    let arb_delegation = arb_amount_non_zero_ceiled(max_delegation);
-    let amounts = arb_delegation.prop_flat_map(move |amount_delegate| {
-        let amount_redelegate = arb_amount_non_zero_ceiled(max(
-            1,
-            u64::try_from(amount_delegate.raw_amount()).unwrap() - 1,
-        ));
-        (Just(amount_delegate), amount_redelegate)
-    });
-    amounts.prop_flat_map(move |(amount_delegate, amount_redelegate)| {
-        let amount_unbond = arb_amount_non_zero_ceiled(max(
-            1,
-            u64::try_from(amount_redelegate.raw_amount()).unwrap() - 1,
-        ));
-        (
-            Just(amount_delegate),
-            Just(amount_redelegate),
-            amount_unbond,
-        )
-    })
-}
+    let staking_token = staking_token_address(&s);
+    let consensus_set =
+        crate::read_consensus_validator_set_addresses(&s, current_epoch)
+            .unwrap();
+
+    // Send the dust to a custom recipient
+    let recipient = established_address_3();
+    assert_eq!(read_rewards_dust_recipient(&s).unwrap(), address::GOV);
+    write_rewards_dust_recipient(&mut s, &recipient).unwrap();
+    assert_eq!(read_rewards_dust_recipient(&s).unwrap(), recipient);
+
+    // Start a new epoch
+    let current_epoch = advance_epoch(&mut s, &params);
+    let pos_balance_pre =
+        read_balance(&s, &staking_token, &address::POS).unwrap();
+    let recipient_balance_pre =
+        read_balance(&s, &staking_token, &recipient).unwrap();
+
+    // Assign rewards accumulator values that can't be represented exactly
+    let num_consensus_validators = consensus_set.len() as u64;
+    let num_blocks_in_last_epoch = 7;
+    let accum_val = Dec::from(num_blocks_in_last_epoch)
+        / Dec::from(num_consensus_validators * 3);
+    for validator in &consensus_set {
+        crate::rewards_accumulator_handle()
+            .insert(&mut s, validator.clone(), accum_val)
+            .unwrap();
+    }
+
+    // Distribute an inflation amount that doesn't divide evenly
+    let last_epoch = current_epoch.prev();
+    let inflation = token::Amount::from_uint(1_000_003_u64, 0).unwrap();
+    let distribution = crate::update_rewards_products_and_mint_inflation(
+        &mut s,
+        &params,
+        last_epoch,
+        num_blocks_in_last_epoch,
+        inflation,
+        &staking_token,
+    )
+    .unwrap();
 
-fn test_simple_redelegation_aux(
-    mut validators: Vec<GenesisValidator>,
-    amount_delegate: token::Amount,
-    amount_redelegate: token::Amount,
-    amount_unbond: token::Amount,
-) {
-    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    // The minted tokens exactly equal the inflation
+    let pos_credit = read_balance(&s, &staking_token, &address::POS).unwrap()
+        - pos_balance_pre;
+    let dust_credit = read_balance(&s, &staking_token, &recipient).unwrap()
+        - recipient_balance_pre;
+    assert_eq!(pos_credit + dust_credit, inflation);
+    assert_eq!(pos_credit, distribution.pos_reward_tokens);
+    assert_eq!(dust_credit, distribution.dust_tokens);
+    assert_eq!(distribution.dust_recipient, recipient);
+    // Only a third of the inflation is attributed to the validators
+    assert!(dust_credit > pos_credit);
 
-    let src_validator = validators[0].address.clone();
-    let dest_validator = validators[1].address.clone();
+    // The PoS account receives exactly the claimable rewards
+    let mut claimable = token::Amount::zero();
+    for rewards in &distribution.validators {
+        let stake =
+            read_validator_stake(&s, &params, &rewards.validator, last_epoch)
+                .unwrap();
+        let validator_claimable = rewards.commissions + rewards.product * stake;
+        assert_eq!(validator_claimable + rewards.dust, rewards.reward_tokens);
+        claimable += validator_claimable;
+    }
+    assert_eq!(claimable, pos_credit);
+
+    // Distributing more than the inflation is rejected
+    assert!(
+        reconcile_rewards_dust(
+            &mut s,
+            &staking_token,
+            inflation,
+            inflation + token::Amount::from(1_u64),
+        )
+        .is_err()
+    );
+}
 
+fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
         unbonding_len: 4,
+        evidence_max_age_epochs: 4,
         ..Default::default()
     };
 
+    let init_tot_stake = validators
+        .clone()
+        .into_iter()
+        .fold(token::Amount::zero(), |acc, v| acc + v.tokens);
+    let val1_init_stake = validators[0].tokens;
+
+    let mut validators = validators;
+    validators[0].tokens = (init_tot_stake - val1_init_stake) / 30;
+
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
     let params = test_init_genesis(
@@ -4444,6 +7455,9 @@ fn test_simple_redelegation_aux(
     .unwrap();
     storage.commit_block().unwrap();
 
+    let validator1 = validators[0].address.clone();
+    let validator2 = validators[1].address.clone();
+
     // Get a delegator with some tokens
     let staking_token = staking_token_address(&storage);
     let delegator = address::testing::gen_implicit_address();
@@ -4451,399 +7465,576 @@ fn test_simple_redelegation_aux(
     credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
         .unwrap();
 
-    // Ensure that we cannot redelegate with the same src and dest validator
-    let err = super::redelegate_tokens(
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        10_000.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Unbond some from validator 1
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        1_342.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
         &mut storage,
         &delegator,
-        &src_validator,
-        &src_validator,
+        &validator1,
+        &validator2,
         current_epoch,
-        amount_redelegate,
+        1_875.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        584.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance an epoch to 1
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        384.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Unbond some from validator 1
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        144.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        3_448.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        699.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance an epoch to ep 2
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        4_384.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        1_008.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        3_500.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance two epochs to ep 4
+    for _ in 0..2 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    // Find some slashes committed in various epochs
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(1),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(2),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(2),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(3),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+
+    // Advance such that these slashes are all processed
+    for _ in 0..params.slash_processing_epoch_offset() {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let del_bond_amount = crate::bond_amount(
+        &storage,
+        &BondId {
+            source: delegator.clone(),
+            validator: validator1.clone(),
+        },
+        pipeline_epoch,
+    )
+    .unwrap_or_default();
+
+    let self_bond_amount = crate::bond_amount(
+        &storage,
+        &BondId {
+            source: validator1.clone(),
+            validator: validator1.clone(),
+        },
+        pipeline_epoch,
+    )
+    .unwrap_or_default();
+
+    let val_stake = crate::read_validator_stake(
+        &storage,
+        &params,
+        &validator1,
+        pipeline_epoch,
     )
-    .unwrap_err();
-    let err_str = err.to_string();
-    assert_matches!(
-        err.downcast::<RedelegationError>().unwrap().deref(),
-        RedelegationError::RedelegationSrcEqDest,
-        "Redelegation with the same src and dest validator must be rejected, \
-         got {err_str}",
+    .unwrap();
+    // dbg!(&val_stake);
+    // dbg!(&del_bond_amount);
+    // dbg!(&self_bond_amount);
+
+    let diff = val_stake - self_bond_amount - del_bond_amount;
+    assert!(diff <= 2.into());
+
+    // The cumulative slash rate only includes the processed slashes
+    let first_processing_epoch =
+        Epoch(1) + params.slash_processing_epoch_offset();
+    assert!(
+        read_validator_cumulative_slash(
+            &storage,
+            &validator1,
+            first_processing_epoch.prev()
+        )
+        .unwrap()
+        .is_zero()
+    );
+    let cumulative_rate =
+        read_validator_cumulative_slash(&storage, &validator1, current_epoch)
+            .unwrap();
+    assert!(cumulative_rate > Dec::zero());
+    assert!(
+        read_validator_cumulative_slash(&storage, &validator2, current_epoch)
+            .unwrap()
+            .is_zero()
     );
 
-    for _ in 0..5 {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
+    // The self-bond was made at genesis, before all the infractions, so it's
+    // slashed by the cumulative rate, give or take the rounding of every slash
+    let genesis_self_bond = validators[0].tokens;
+    let expected_self_bond =
+        genesis_self_bond - genesis_self_bond.mul_ceil(cumulative_rate);
+    let self_bond_diff = if expected_self_bond > self_bond_amount {
+        expected_self_bond - self_bond_amount
+    } else {
+        self_bond_amount - expected_self_bond
+    };
+    assert!(self_bond_diff <= 4.into());
+
+    // The checkpointed bond amounts match the amounts computed from the whole
+    // history of the bonds
+    let bond_ids = [
+        BondId {
+            source: delegator.clone(),
+            validator: validator1.clone(),
+        },
+        BondId {
+            source: delegator.clone(),
+            validator: validator2.clone(),
+        },
+        BondId {
+            source: validator1.clone(),
+            validator: validator1.clone(),
+        },
+    ];
+    for bond_id in &bond_ids {
+        for epoch in Epoch::iter_bounds_inclusive(Epoch(0), pipeline_epoch) {
+            assert_eq!(
+                crate::bond_amount(&storage, bond_id, epoch).unwrap(),
+                crate::compute_bond_amount(&storage, &params, bond_id, epoch)
+                    .unwrap(),
+                "Bond amount mismatch for {bond_id:?} in epoch {epoch}"
+            );
+        }
     }
 
-    let init_epoch = current_epoch;
+    // The checkpoints recorded before the slashes were applied are stale, so
+    // they're ignored by the look-ups above
+    let bond_id = &bond_ids[0];
+    let slashes = validator_slashes_handle(&validator1);
+    let num_slashes = slashes.len(&storage).unwrap();
+    let last_slash = slashes.get(&storage, num_slashes - 1).unwrap();
+    assert!(last_slash.is_some());
+    let checkpoints = bond_amount_checkpoint_handle(&delegator, &validator1);
+    for res in checkpoints.get_data_handler().iter(&storage).unwrap() {
+        let (_epoch, checkpoint) = res.unwrap();
+        assert_ne!(checkpoint.last_slash, last_slash);
+    }
 
-    // Delegate in epoch 1 to src_validator
-    println!(
-        "\nBONDING {} TOKENS TO {}\n",
-        amount_delegate.to_string_native(),
-        &src_validator
-    );
+    // A new bond updates the checkpoint at the pipeline epoch
     super::bond_tokens(
         &mut storage,
         Some(&delegator),
-        &src_validator,
-        amount_delegate,
+        &validator1,
+        100.into(),
         current_epoch,
         None,
     )
     .unwrap();
-
-    println!("\nAFTER DELEGATION\n");
-    let bonds = bond_handle(&delegator, &src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let bonds_dest = bond_handle(&delegator, &dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds = unbond_handle(&delegator, &src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let tot_bonds = total_bonded_handle(&src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds = total_unbonded_handle(&src_validator)
-        .collect_map(&storage)
+    let checkpoint = checkpoints
+        .get(&storage, pipeline_epoch, &params)
+        .unwrap()
         .unwrap();
-    dbg!(&bonds, &bonds_dest, &unbonds, &tot_bonds, &tot_unbonds);
+    assert_eq!(
+        checkpoint.amount,
+        crate::compute_bond_amount(&storage, &params, bond_id, pipeline_epoch)
+            .unwrap()
+    );
+    assert_eq!(checkpoint.last_slash, last_slash);
+    assert_eq!(
+        crate::bond_amount(&storage, bond_id, pipeline_epoch).unwrap(),
+        checkpoint.amount
+    );
+}
 
-    // Advance three epochs
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
+    assert_eq!(validators.len(), 1);
 
-    // Redelegate in epoch 3
-    println!(
-        "\nREDELEGATING {} TOKENS TO {}\n",
-        amount_redelegate.to_string_native(),
-        &dest_validator
-    );
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+    let validator = validators[0].address.clone();
 
-    super::redelegate_tokens(
+    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    let mut storage = TestWlStorage::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        &delegator,
-        &src_validator,
-        &dest_validator,
+        params,
+        validators.into_iter(),
         current_epoch,
-        amount_redelegate,
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    println!("\nAFTER REDELEGATION\n");
-    println!("\nDELEGATOR\n");
-    let bonds_src = bond_handle(&delegator, &src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let bonds_dest = bond_handle(&delegator, &dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_src = unbond_handle(&delegator, &src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
-        .collect_map(&storage)
+    // Check that there is one consensus key in the network
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 1);
+    let ck = consensus_keys.first().cloned().unwrap();
+    let og_ck = validator_consensus_key_handle(&validator)
+        .get(&storage, current_epoch, &params)
+        .unwrap()
         .unwrap();
+    assert_eq!(ck, og_ck);
 
-    dbg!(
-        &bonds_src,
-        &bonds_dest,
-        &unbonds_src,
-        &unbonds_dest,
-        &redel_bonds,
-        &redel_unbonds
-    );
-
-    // Dest val
-    println!("\nDEST VALIDATOR\n");
+    // Attempt to change to a new secp256k1 consensus key (disallowed)
+    let secp_ck = gen_keypair::<key::secp256k1::SigScheme>();
+    let secp_ck = key::common::SecretKey::Secp256k1(secp_ck).ref_to();
+    let res =
+        change_consensus_key(&mut storage, &validator, &secp_ck, current_epoch);
+    assert!(res.is_err());
 
-    let incoming_redels_dest =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let outgoing_redels_dest =
-        validator_outgoing_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_bonds_dest = total_bonded_handle(&dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
-        .collect_map(&storage)
+    // Change consensus keys
+    let ck_2 = common_sk_from_simple_seed(1).ref_to();
+    change_consensus_key(&mut storage, &validator, &ck_2, current_epoch)
         .unwrap();
-    let tot_redel_bonds_dest =
-        validator_total_redelegated_bonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_redel_unbonds_dest =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    dbg!(
-        &incoming_redels_dest,
-        &outgoing_redels_dest,
-        &tot_bonds_dest,
-        &tot_unbonds_dest,
-        &tot_redel_bonds_dest,
-        &tot_redel_unbonds_dest
-    );
 
-    // Src val
-    println!("\nSRC VALIDATOR\n");
+    // Check that there is a new consensus key
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 2);
 
-    let incoming_redels_src =
-        validator_incoming_redelegations_handle(&src_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let outgoing_redels_src =
-        validator_outgoing_redelegations_handle(&src_validator)
-            .collect_map(&storage)
+    for epoch in current_epoch.iter_range(params.pipeline_len) {
+        let ck = validator_consensus_key_handle(&validator)
+            .get(&storage, epoch, &params)
+            .unwrap()
             .unwrap();
-    let tot_bonds_src = total_bonded_handle(&src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds_src = total_unbonded_handle(&src_validator)
-        .collect_map(&storage)
+        assert_eq!(ck, og_ck);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let ck = validator_consensus_key_handle(&validator)
+        .get(&storage, pipeline_epoch, &params)
+        .unwrap()
         .unwrap();
-    let tot_redel_bonds_src =
-        validator_total_redelegated_bonded_handle(&src_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_redel_unbonds_src =
-        validator_total_redelegated_unbonded_handle(&src_validator)
-            .collect_map(&storage)
+    assert_eq!(ck, ck_2);
+
+    // Advance to the pipeline epoch
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        if current_epoch == pipeline_epoch {
+            break;
+        }
+    }
+
+    // Check the consensus keys again
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 2);
+
+    for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
+        let ck = validator_consensus_key_handle(&validator)
+            .get(&storage, epoch, &params)
+            .unwrap()
             .unwrap();
-    dbg!(
-        &incoming_redels_src,
-        &outgoing_redels_src,
-        &tot_bonds_src,
-        &tot_unbonds_src,
-        &tot_redel_bonds_src,
-        &tot_redel_unbonds_src
-    );
+        assert_eq!(ck, ck_2);
+    }
 
-    // Checks
-    let redelegated = delegator_redelegated_bonds_handle(&delegator)
-        .at(&dest_validator)
-        .at(&(current_epoch + params.pipeline_len))
-        .at(&src_validator)
-        .get(&storage, &(init_epoch + params.pipeline_len))
-        .unwrap()
+    // Now change the consensus key again and bond in the same epoch
+    let ck_3 = common_sk_from_simple_seed(3).ref_to();
+    change_consensus_key(&mut storage, &validator, &ck_3, current_epoch)
         .unwrap();
-    assert_eq!(redelegated, amount_redelegate);
 
-    let redel_start_epoch =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .get(&storage, &delegator)
+    let staking_token = storage.storage.native_token.clone();
+    let amount_del = token::Amount::native_whole(5);
+    credit_tokens(&mut storage, &staking_token, &validator, amount_del)
+        .unwrap();
+    bond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        token::Amount::native_whole(1),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Check consensus keys again
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 3);
+
+    for epoch in current_epoch.iter_range(params.pipeline_len) {
+        let ck = validator_consensus_key_handle(&validator)
+            .get(&storage, epoch, &params)
             .unwrap()
             .unwrap();
-    assert_eq!(redel_start_epoch, current_epoch + params.pipeline_len);
-
-    let redelegated = validator_outgoing_redelegations_handle(&src_validator)
-        .at(&dest_validator)
-        .at(&current_epoch.prev())
-        .get(&storage, &current_epoch)
+        assert_eq!(ck, ck_2);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let ck = validator_consensus_key_handle(&validator)
+        .get(&storage, pipeline_epoch, &params)
         .unwrap()
         .unwrap();
-    assert_eq!(redelegated, amount_redelegate);
+    assert_eq!(ck, ck_3);
 
-    // Advance three epochs
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    // Advance to the pipeline epoch to ensure that the validator set updates to
+    // tendermint will work
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        if current_epoch == pipeline_epoch {
+            break;
+        }
+    }
+    assert_eq!(current_epoch.0, 2 * params.pipeline_len);
+}
 
-    // Unbond in epoch 5 from dest_validator
-    println!(
-        "\nUNBONDING {} TOKENS FROM {}\n",
-        amount_unbond.to_string_native(),
-        &dest_validator
-    );
-    let _ = unbond_tokens(
+fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+    let validator1 = validators[0].address.clone();
+    let validator2 = validators[1].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        Some(&delegator),
-        &dest_validator,
-        amount_unbond,
+        params,
+        validators.clone().into_iter(),
         current_epoch,
-        false,
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    println!("\nAFTER UNBONDING\n");
-    println!("\nDELEGATOR\n");
-
-    let bonds_src = bond_handle(&delegator, &src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let bonds_dest = bond_handle(&delegator, &dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_src = unbond_handle(&delegator, &src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
-        .collect_map(&storage)
+    // Get delegators with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator1 = address::testing::gen_implicit_address();
+    let delegator2 = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator1, del_balance)
         .unwrap();
-    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
-        .collect_map(&storage)
+    credit_tokens(&mut storage, &staking_token, &delegator2, del_balance)
         .unwrap();
 
-    dbg!(
-        &bonds_src,
-        &bonds_dest,
-        &unbonds_src,
-        &unbonds_dest,
-        &redel_bonds,
-        &redel_unbonds
-    );
+    // Advance to epoch 1
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
 
-    println!("\nDEST VALIDATOR\n");
+    // Delegate in epoch 1 to validator1
+    let del1_epoch = current_epoch;
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator1),
+        &validator1,
+        1000.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
 
-    let incoming_redels_dest =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let outgoing_redels_dest =
-        validator_outgoing_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_bonds_dest = total_bonded_handle(&dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let tot_redel_bonds_dest =
-        validator_total_redelegated_bonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_redel_unbonds_dest =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    dbg!(
-        &incoming_redels_dest,
-        &outgoing_redels_dest,
-        &tot_bonds_dest,
-        &tot_unbonds_dest,
-        &tot_redel_bonds_dest,
-        &tot_redel_unbonds_dest
-    );
+    // Advance to epoch 2
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
 
-    let bond_start = init_epoch + params.pipeline_len;
-    let redelegation_end = bond_start + params.pipeline_len + 1u64;
-    let unbond_end =
-        redelegation_end + params.withdrawable_epoch_offset() + 1u64;
-    let unbond_materialized = redelegation_end + params.pipeline_len + 1u64;
+    // Delegate in epoch 2 to validator2
+    let del2_epoch = current_epoch;
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator2),
+        &validator2,
+        1000.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
 
     // Checks
-    let redelegated_remaining = delegator_redelegated_bonds_handle(&delegator)
-        .at(&dest_validator)
-        .at(&redelegation_end)
-        .at(&src_validator)
-        .get(&storage, &bond_start)
-        .unwrap()
-        .unwrap_or_default();
-    assert_eq!(redelegated_remaining, amount_redelegate - amount_unbond);
-
-    let redel_unbonded = delegator_redelegated_unbonds_handle(&delegator)
-        .at(&dest_validator)
-        .at(&redelegation_end)
-        .at(&unbond_end)
-        .at(&src_validator)
-        .get(&storage, &bond_start)
-        .unwrap()
-        .unwrap();
-    assert_eq!(redel_unbonded, amount_unbond);
+    assert!(super::is_validator(&storage, &validator1).unwrap());
+    assert!(super::is_validator(&storage, &validator2).unwrap());
+    assert!(!super::is_delegator(&storage, &validator1, None).unwrap());
+    assert!(!super::is_delegator(&storage, &validator2, None).unwrap());
 
-    dbg!(unbond_materialized, redelegation_end, bond_start);
-    let total_redel_unbonded =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .at(&unbond_materialized)
-            .at(&redelegation_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
-            .unwrap();
-    assert_eq!(total_redel_unbonded, amount_unbond);
+    assert!(!super::is_validator(&storage, &delegator1).unwrap());
+    assert!(!super::is_validator(&storage, &delegator2).unwrap());
+    assert!(super::is_delegator(&storage, &delegator1, None).unwrap());
+    assert!(super::is_delegator(&storage, &delegator2, None).unwrap());
 
-    // Advance to withdrawal epoch
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-        if current_epoch == unbond_end {
-            break;
-        }
+    for epoch in Epoch::default().iter_range(del1_epoch.0 + params.pipeline_len)
+    {
+        assert!(
+            !super::is_delegator(&storage, &delegator1, Some(epoch)).unwrap()
+        );
+    }
+    assert!(
+        super::is_delegator(
+            &storage,
+            &delegator1,
+            Some(del1_epoch + params.pipeline_len)
+        )
+        .unwrap()
+    );
+    for epoch in Epoch::default().iter_range(del2_epoch.0 + params.pipeline_len)
+    {
+        assert!(
+            !super::is_delegator(&storage, &delegator2, Some(epoch)).unwrap()
+        );
     }
-
-    // Withdraw
-    withdraw_tokens(
-        &mut storage,
-        Some(&delegator),
-        &dest_validator,
-        current_epoch,
-    )
-    .unwrap();
-
     assert!(
-        delegator_redelegated_unbonds_handle(&delegator)
-            .at(&dest_validator)
-            .is_empty(&storage)
-            .unwrap()
-    );
-
-    let delegator_balance = storage
-        .read::<token::Amount>(&token::balance_key(&staking_token, &delegator))
+        super::is_delegator(
+            &storage,
+            &delegator2,
+            Some(del2_epoch + params.pipeline_len)
+        )
         .unwrap()
-        .unwrap_or_default();
-    assert_eq!(
-        delegator_balance,
-        del_balance - amount_delegate + amount_unbond
     );
 }
 
-fn test_redelegation_with_slashing_aux(
-    mut validators: Vec<GenesisValidator>,
-    amount_delegate: token::Amount,
-    amount_redelegate: token::Amount,
-    amount_unbond: token::Amount,
-) {
-    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
-
-    let src_validator = validators[0].address.clone();
-    let dest_validator = validators[1].address.clone();
+fn test_purge_old_epoched_data_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
 
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
         unbonding_len: 4,
-        // Avoid empty consensus set by removing the threshold
-        validator_stake_threshold: token::Amount::zero(),
+        evidence_max_age_epochs: 4,
+        past_data_retention_len: 1,
         ..Default::default()
     };
 
@@ -4852,405 +8043,379 @@ fn test_redelegation_with_slashing_aux(
     let params = test_init_genesis(
         &mut storage,
         params,
-        validators.clone().into_iter(),
+        validators.into_iter(),
         current_epoch,
     )
     .unwrap();
     storage.commit_block().unwrap();
 
-    // Get a delegator with some tokens
-    let staking_token = staking_token_address(&storage);
-    let delegator = address::testing::gen_implicit_address();
-    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
-    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
-        .unwrap();
-
-    for _ in 0..5 {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-    }
-
-    let init_epoch = current_epoch;
-
-    // Delegate in epoch 5 to src_validator
-    println!(
-        "\nBONDING {} TOKENS TO {}\n",
-        amount_delegate.to_string_native(),
-        &src_validator
-    );
-    super::bond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &src_validator,
-        amount_delegate,
-        current_epoch,
-        None,
-    )
-    .unwrap();
-
-    println!("\nAFTER DELEGATION\n");
-    let bonds = bond_handle(&delegator, &src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let bonds_dest = bond_handle(&delegator, &dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds = unbond_handle(&delegator, &src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let tot_bonds = total_bonded_handle(&src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds = total_unbonded_handle(&src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    dbg!(&bonds, &bonds_dest, &unbonds, &tot_bonds, &tot_unbonds);
-
-    // Advance three epochs
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    // Unbond some of the validator's self-bond in epoch 1
     current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Redelegate in epoch 8
-    println!(
-        "\nREDELEGATING {} TOKENS TO {}\n",
-        amount_redelegate.to_string_native(),
-        &dest_validator
-    );
-
-    super::redelegate_tokens(
+    super::unbond_tokens(
         &mut storage,
-        &delegator,
-        &src_validator,
-        &dest_validator,
+        None,
+        &validator,
+        1.into(),
         current_epoch,
-        amount_redelegate,
+        false,
     )
     .unwrap();
-
-    println!("\nAFTER REDELEGATION\n");
-    println!("\nDELEGATOR\n");
-    let bonds_src = bond_handle(&delegator, &src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let bonds_dest = bond_handle(&delegator, &dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_src = unbond_handle(&delegator, &src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
-        .collect_map(&storage)
-        .unwrap();
-
-    dbg!(
-        &bonds_src,
-        &bonds_dest,
-        &unbonds_src,
-        &unbonds_dest,
-        &redel_bonds,
-        &redel_unbonds
-    );
-
-    // Dest val
-    println!("\nDEST VALIDATOR\n");
-
-    let incoming_redels_dest =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let outgoing_redels_dest =
-        validator_outgoing_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_bonds_dest = total_bonded_handle(&dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let tot_redel_bonds_dest =
-        validator_total_redelegated_bonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_redel_unbonds_dest =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    dbg!(
-        &incoming_redels_dest,
-        &outgoing_redels_dest,
-        &tot_bonds_dest,
-        &tot_unbonds_dest,
-        &tot_redel_bonds_dest,
-        &tot_redel_unbonds_dest
+    let unbond_epoch = current_epoch + params.pipeline_len;
+    assert!(
+        !total_unbonded_handle(&validator)
+            .at(&unbond_epoch)
+            .is_empty(&storage)
+            .unwrap()
     );
 
-    // Src val
-    println!("\nSRC VALIDATOR\n");
-
-    let incoming_redels_src =
-        validator_incoming_redelegations_handle(&src_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let outgoing_redels_src =
-        validator_outgoing_redelegations_handle(&src_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_bonds_src = total_bonded_handle(&src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds_src = total_unbonded_handle(&src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let tot_redel_bonds_src =
-        validator_total_redelegated_bonded_handle(&src_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_redel_unbonds_src =
-        validator_total_redelegated_unbonded_handle(&src_validator)
-            .collect_map(&storage)
-            .unwrap();
-    dbg!(
-        &incoming_redels_src,
-        &outgoing_redels_src,
-        &tot_bonds_src,
-        &tot_unbonds_src,
-        &tot_redel_bonds_src,
-        &tot_redel_unbonds_src
+    // Nothing gets purged until the unbond is past the retention horizon
+    while params.oldest_epoch_to_retain(current_epoch) <= unbond_epoch {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::prune_all(&mut storage, current_epoch).unwrap();
+        if params.oldest_epoch_to_retain(current_epoch) <= unbond_epoch {
+            assert!(
+                !total_unbonded_handle(&validator)
+                    .at(&unbond_epoch)
+                    .is_empty(&storage)
+                    .unwrap()
+            );
+        }
+    }
+    assert!(
+        total_unbonded_handle(&validator)
+            .at(&unbond_epoch)
+            .is_empty(&storage)
+            .unwrap()
     );
+}
 
-    // Checks
-    let redelegated = delegator_redelegated_bonds_handle(&delegator)
-        .at(&dest_validator)
-        .at(&(current_epoch + params.pipeline_len))
-        .at(&src_validator)
-        .get(&storage, &(init_epoch + params.pipeline_len))
-        .unwrap()
-        .unwrap();
-    assert_eq!(redelegated, amount_redelegate);
+fn test_compute_validator_set_diff_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+    let genesis_stake = validators[0].tokens;
 
-    let redel_start_epoch =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .get(&storage, &delegator)
-            .unwrap()
-            .unwrap();
-    assert_eq!(redel_start_epoch, current_epoch + params.pipeline_len);
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 4,
+        ..Default::default()
+    };
 
-    let redelegated = validator_outgoing_redelegations_handle(&src_validator)
-        .at(&dest_validator)
-        .at(&current_epoch.prev())
-        .get(&storage, &current_epoch)
-        .unwrap()
-        .unwrap();
-    assert_eq!(redelegated, amount_redelegate);
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+    let cache = ConsensusValidatorSetCache::default();
 
-    // Advance three epochs
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    // No changes between the same epochs
+    let diff = compute_validator_set_diff(
+        &storage,
+        &params,
+        current_epoch,
+        current_epoch,
+        &cache,
+    )
+    .unwrap();
+    assert!(diff.is_empty());
 
-    // Unbond in epoch 11 from dest_validator
-    println!(
-        "\nUNBONDING {} TOKENS FROM {}\n",
-        amount_unbond.to_string_native(),
-        &dest_validator
-    );
-    let _ = unbond_tokens(
+    // Self-bond some more tokens, which takes effect at the pipeline epoch
+    let staking_token = staking_token_address(&storage);
+    let amount = token::Amount::native_whole(10);
+    credit_tokens(&mut storage, &staking_token, &validator, amount).unwrap();
+    super::bond_tokens(
         &mut storage,
-        Some(&delegator),
-        &dest_validator,
-        amount_unbond,
+        None,
+        &validator,
+        amount,
         current_epoch,
-        false,
+        None,
     )
     .unwrap();
 
-    println!("\nAFTER UNBONDING\n");
-    println!("\nDELEGATOR\n");
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let diff = compute_validator_set_diff(
+        &storage,
+        &params,
+        current_epoch,
+        pipeline_epoch,
+        &cache,
+    )
+    .unwrap();
+    assert_eq!(diff.len(), 1);
+    match &diff[0] {
+        ValidatorSetDiff::Updated {
+            address,
+            consensus_key,
+            prev_consensus_key,
+            bonded_stake,
+            prev_bonded_stake,
+        } => {
+            assert_eq!(address, &validator);
+            assert_eq!(consensus_key, prev_consensus_key);
+            assert_eq!(*prev_bonded_stake, genesis_stake);
+            assert_eq!(*bonded_stake, genesis_stake + amount);
+        }
+        other => panic!("Unexpected validator set diff {other:?}"),
+    }
+}
 
-    let bonds_src = bond_handle(&delegator, &src_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let bonds_dest = bond_handle(&delegator, &dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_src = unbond_handle(&delegator, &src_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let unbonds_dest = unbond_handle(&delegator, &dest_validator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_bonds = delegator_redelegated_bonds_handle(&delegator)
-        .collect_map(&storage)
-        .unwrap();
-    let redel_unbonds = delegator_redelegated_unbonds_handle(&delegator)
-        .collect_map(&storage)
-        .unwrap();
+fn test_rebalance_validator_sets_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        max_validator_slots: 3,
+        ..Default::default()
+    };
 
-    dbg!(
-        &bonds_src,
-        &bonds_dest,
-        &unbonds_src,
-        &unbonds_dest,
-        &redel_bonds,
-        &redel_unbonds
-    );
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let mut params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
 
-    println!("\nDEST VALIDATOR\n");
+    let num_eligible = validators
+        .iter()
+        .filter(|validator| {
+            validator.tokens >= params.validator_stake_threshold
+        })
+        .count() as u64;
+    assert_eq!(
+        get_num_consensus_validators(&storage, current_epoch).unwrap(),
+        std::cmp::min(3, num_eligible)
+    );
 
-    let incoming_redels_dest =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let outgoing_redels_dest =
-        validator_outgoing_redelegations_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_bonds_dest = total_bonded_handle(&dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
-        .unwrap();
-    let tot_unbonds_dest = total_unbonded_handle(&dest_validator)
-        .collect_map(&storage)
+    // Check the validator sets at the pipeline epoch against the given number
+    // of slots
+    let check_sets = |storage: &TestWlStorage,
+                      params: &PosParams,
+                      current_epoch: Epoch,
+                      slots: u64| {
+        let pipeline_epoch = current_epoch + params.pipeline_len;
+        let consensus = read_consensus_validator_set_addresses_with_stake(
+            storage,
+            pipeline_epoch,
+        )
         .unwrap();
-    let tot_redel_bonds_dest =
-        validator_total_redelegated_bonded_handle(&dest_validator)
-            .collect_map(&storage)
-            .unwrap();
-    let tot_redel_unbonds_dest =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .collect_map(&storage)
+        let below_capacity =
+            read_below_capacity_validator_set_addresses_with_stake(
+                storage,
+                pipeline_epoch,
+            )
             .unwrap();
-    dbg!(
-        &incoming_redels_dest,
-        &outgoing_redels_dest,
-        &tot_bonds_dest,
-        &tot_unbonds_dest,
-        &tot_redel_bonds_dest,
-        &tot_redel_unbonds_dest
-    );
+        assert_eq!(consensus.len() as u64, std::cmp::min(slots, num_eligible));
+        assert_eq!(
+            (consensus.len() + below_capacity.len()) as u64,
+            num_eligible
+        );
+        let min_consensus = consensus.iter().map(|v| v.bonded_stake).min();
+        let max_below_capacity =
+            below_capacity.iter().map(|v| v.bonded_stake).max();
+        if let (Some(min_consensus), Some(max_below_capacity)) =
+            (min_consensus, max_below_capacity)
+        {
+            assert!(min_consensus >= max_below_capacity);
+        }
+        for validator in &consensus {
+            assert_eq!(
+                validator_state_handle(&validator.address)
+                    .get(storage, pipeline_epoch, params)
+                    .unwrap(),
+                Some(ValidatorState::Consensus)
+            );
+        }
+        for validator in &below_capacity {
+            assert_eq!(
+                validator_state_handle(&validator.address)
+                    .get(storage, pipeline_epoch, params)
+                    .unwrap(),
+                Some(ValidatorState::BelowCapacity)
+            );
+        }
+    };
 
-    // Advance one epoch
+    // Shrink the number of slots
+    params.owned.max_validator_slots = 1;
+    write_pos_params(&mut storage, &params.owned).unwrap();
     current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    rebalance_validator_sets(&mut storage, &params, current_epoch).unwrap();
+    check_sets(&storage, &params, current_epoch, 1);
 
-    // Discover evidence
-    slash(
+    // Rebalancing again without a change in the slots is a no-op
+    rebalance_validator_sets(&mut storage, &params, current_epoch).unwrap();
+    check_sets(&storage, &params, current_epoch, 1);
+
+    // Grow the number of slots
+    params.owned.max_validator_slots = 5;
+    write_pos_params(&mut storage, &params.owned).unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    rebalance_validator_sets(&mut storage, &params, current_epoch).unwrap();
+    check_sets(&storage, &params, current_epoch, 5);
+}
+
+fn test_estimate_staking_apr_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        &params,
+        params,
+        validators.clone().into_iter(),
         current_epoch,
-        init_epoch + 2 * params.pipeline_len,
-        0u64,
-        SlashType::DuplicateVote,
-        &src_validator,
-        current_epoch.next(),
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    let bond_start = init_epoch + params.pipeline_len;
-    let redelegation_end = bond_start + params.pipeline_len + 1u64;
-    let unbond_end =
-        redelegation_end + params.withdrawable_epoch_offset() + 1u64;
-    let unbond_materialized = redelegation_end + params.pipeline_len + 1u64;
+    let epochs_per_year = 365_u64;
+    let inflation = token::Amount::native_whole(1_000);
+    storage
+        .write(&params_storage::get_epochs_per_year_key(), epochs_per_year)
+        .unwrap();
+    storage
+        .write(&params_storage::get_pos_inflation_amount_key(), inflation)
+        .unwrap();
 
-    // Checks
-    let redelegated_remaining = delegator_redelegated_bonds_handle(&delegator)
-        .at(&dest_validator)
-        .at(&redelegation_end)
-        .at(&src_validator)
-        .get(&storage, &bond_start)
-        .unwrap()
-        .unwrap_or_default();
-    assert_eq!(redelegated_remaining, amount_redelegate - amount_unbond);
+    let total_stake =
+        crate::get_total_consensus_stake(&storage, current_epoch, &params)
+            .unwrap();
+    let gross_apr =
+        Dec::from(inflation) * epochs_per_year / Dec::from(total_stake);
 
-    let redel_unbonded = delegator_redelegated_unbonds_handle(&delegator)
-        .at(&dest_validator)
-        .at(&redelegation_end)
-        .at(&unbond_end)
-        .at(&src_validator)
-        .get(&storage, &bond_start)
-        .unwrap()
-        .unwrap();
-    assert_eq!(redel_unbonded, amount_unbond);
+    // All the genesis validators have the same commission rate
+    let commission_rate = validators[0].commission_rate;
+    let expected_apr = gross_apr * (Dec::one() - commission_rate);
+    assert_eq!(
+        estimate_staking_apr(&storage, current_epoch).unwrap(),
+        expected_apr
+    );
 
-    dbg!(unbond_materialized, redelegation_end, bond_start);
-    let total_redel_unbonded =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .at(&unbond_materialized)
-            .at(&redelegation_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
+    for validator in &validators {
+        let state = validator_state_handle(&validator.address)
+            .get(&storage, current_epoch, &params)
             .unwrap();
-    assert_eq!(total_redel_unbonded, amount_unbond);
+        let apr = estimate_validator_staking_apr(
+            &storage,
+            &validator.address,
+            current_epoch,
+        )
+        .unwrap();
+        if state == Some(ValidatorState::Consensus) {
+            assert_eq!(apr, expected_apr);
+        } else {
+            assert!(apr.is_zero());
+        }
+    }
+}
 
-    // Advance to withdrawal epoch
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-        if current_epoch == unbond_end {
-            break;
+/// A storage wrapper that counts the number of storage accesses, used to
+/// measure the cost of queries.
+struct CountingStorage<'a> {
+    inner: &'a TestWlStorage,
+    accesses: Cell<u64>,
+}
+
+impl<'a> CountingStorage<'a> {
+    fn new(inner: &'a TestWlStorage) -> Self {
+        Self {
+            inner,
+            accesses: Cell::new(0),
         }
     }
 
-    // Withdraw
-    withdraw_tokens(
-        &mut storage,
-        Some(&delegator),
-        &dest_validator,
-        current_epoch,
-    )
-    .unwrap();
+    fn count(&self) {
+        self.accesses.set(self.accesses.get() + 1);
+    }
+}
 
-    assert!(
-        delegator_redelegated_unbonds_handle(&delegator)
-            .at(&dest_validator)
-            .is_empty(&storage)
-            .unwrap()
-    );
+impl StorageRead for CountingStorage<'_> {
+    type PrefixIter<'iter> = <TestWlStorage as StorageRead>::PrefixIter<'iter>
+    where
+        Self: 'iter;
+
+    fn read_bytes(&self, key: &Key) -> storage_api::Result<Option<Vec<u8>>> {
+        self.count();
+        self.inner.read_bytes(key)
+    }
+
+    fn has_key(&self, key: &Key) -> storage_api::Result<bool> {
+        self.count();
+        self.inner.has_key(key)
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        prefix: &Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        self.count();
+        self.inner.iter_prefix(prefix)
+    }
+
+    fn iter_prefix_from<'iter>(
+        &'iter self,
+        prefix: &Key,
+        start: &Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        self.count();
+        self.inner.iter_prefix_from(prefix, start)
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        iter: &mut Self::PrefixIter<'iter>,
+    ) -> storage_api::Result<Option<(String, Vec<u8>)>> {
+        self.count();
+        self.inner.iter_next(iter)
+    }
+
+    fn get_chain_id(&self) -> storage_api::Result<String> {
+        self.inner.get_chain_id()
+    }
+
+    fn get_block_height(&self) -> storage_api::Result<BlockHeight> {
+        self.inner.get_block_height()
+    }
+
+    fn get_block_header(
+        &self,
+        height: BlockHeight,
+    ) -> storage_api::Result<Option<Header>> {
+        self.inner.get_block_header(height)
+    }
+
+    fn get_block_hash(&self) -> storage_api::Result<BlockHash> {
+        self.inner.get_block_hash()
+    }
+
+    fn get_block_epoch(&self) -> storage_api::Result<Epoch> {
+        self.inner.get_block_epoch()
+    }
+
+    fn get_tx_index(&self) -> storage_api::Result<TxIndex> {
+        self.inner.get_tx_index()
+    }
 
-    let delegator_balance = storage
-        .read::<token::Amount>(&token::balance_key(&staking_token, &delegator))
-        .unwrap()
-        .unwrap_or_default();
-    assert_eq!(delegator_balance, del_balance - amount_delegate);
+    fn get_native_token(&self) -> storage_api::Result<Address> {
+        self.inner.get_native_token()
+    }
 }
 
-fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
-    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
-
-    let src_validator = validators[0].address.clone();
-    let _init_stake_src = validators[0].tokens;
-    let dest_validator = validators[1].address.clone();
-    let _init_stake_dest = validators[1].tokens;
-    let dest_validator_2 = validators[2].address.clone();
-    let _init_stake_dest_2 = validators[2].tokens;
+fn test_bond_amount_query_cost_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
 
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
         unbonding_len: 4,
+        evidence_max_age_epochs: 4,
         ..Default::default()
     };
 
@@ -5259,7 +8424,7 @@ fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
     let params = test_init_genesis(
         &mut storage,
         params,
-        validators.clone().into_iter(),
+        validators.into_iter(),
         current_epoch,
     )
     .unwrap();
@@ -5268,473 +8433,339 @@ fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
     // Get a delegator with some tokens
     let staking_token = staking_token_address(&storage);
     let delegator = address::testing::gen_implicit_address();
-    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
+    let del_balance = token::Amount::native_whole(1_000);
     credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
         .unwrap();
 
-    // Delegate in epoch 0 to src_validator
-    let bond_amount: token::Amount = 100.into();
-    super::bond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &src_validator,
-        bond_amount,
-        current_epoch,
-        None,
-    )
-    .unwrap();
-
-    let bond_start = current_epoch + params.pipeline_len;
-
-    // Advance one epoch
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Redelegate in epoch 1 to dest_validator
-    let redel_amount_1: token::Amount = 58.into();
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &src_validator,
-        &dest_validator,
-        current_epoch,
-        redel_amount_1,
-    )
-    .unwrap();
-
-    let redel_start = current_epoch;
-    let redel_end = current_epoch + params.pipeline_len;
-
-    // Checks ----------------
-
-    // Dest validator should have an incoming redelegation
-    let incoming_redelegation =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .get(&storage, &delegator)
-            .unwrap();
-    assert_eq!(incoming_redelegation, Some(redel_end));
-
-    // Src validator should have an outoging redelegation
-    let outgoing_redelegation =
-        validator_outgoing_redelegations_handle(&src_validator)
-            .at(&dest_validator)
-            .at(&bond_start)
-            .get(&storage, &redel_start)
-            .unwrap();
-    assert_eq!(outgoing_redelegation, Some(redel_amount_1));
-
-    // Delegator should have redelegated bonds
-    let del_total_redelegated_bonded =
-        delegator_redelegated_bonds_handle(&delegator)
-            .at(&dest_validator)
-            .at(&redel_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
-            .unwrap_or_default();
-    assert_eq!(del_total_redelegated_bonded, redel_amount_1);
-
-    // There should be delegator bonds for both src and dest validators
-    let bonded_src = bond_handle(&delegator, &src_validator);
-    let bonded_dest = bond_handle(&delegator, &dest_validator);
-    assert_eq!(
-        bonded_src
-            .get_delta_val(&storage, bond_start)
-            .unwrap()
-            .unwrap_or_default(),
-        bond_amount - redel_amount_1
-    );
-    assert_eq!(
-        bonded_dest
-            .get_delta_val(&storage, redel_end)
-            .unwrap()
-            .unwrap_or_default(),
-        redel_amount_1
-    );
-
-    // The dest validator should have total redelegated bonded tokens
-    let dest_total_redelegated_bonded =
-        validator_total_redelegated_bonded_handle(&dest_validator)
-            .at(&redel_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
-            .unwrap_or_default();
-    assert_eq!(dest_total_redelegated_bonded, redel_amount_1);
-
-    // The dest validator's total bonded should have an entry for the genesis
-    // bond and the redelegation
-    let dest_total_bonded = total_bonded_handle(&dest_validator)
-        .get_data_handler()
-        .collect_map(&storage)
+    // Build up a long history of bonds and unbonds
+    let num_epochs = 30;
+    for _ in 0..num_epochs {
+        super::bond_tokens(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            10.into(),
+            current_epoch,
+            None,
+        )
+        .unwrap();
+        super::unbond_tokens(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            3.into(),
+            current_epoch,
+            false,
+        )
         .unwrap();
-    assert!(
-        dest_total_bonded.len() == 2
-            && dest_total_bonded.contains_key(&Epoch::default())
-    );
-    assert_eq!(
-        dest_total_bonded
-            .get(&redel_end)
-            .cloned()
-            .unwrap_or_default(),
-        redel_amount_1
-    );
-
-    // The src validator should have a total bonded entry for the original bond
-    // accounting for the redelegation
-    assert_eq!(
-        total_bonded_handle(&src_validator)
-            .get_delta_val(&storage, bond_start)
-            .unwrap()
-            .unwrap_or_default(),
-        bond_amount - redel_amount_1
-    );
-
-    // The src validator should have a total unbonded entry due to the
-    // redelegation
-    let src_total_unbonded = total_unbonded_handle(&src_validator)
-        .at(&redel_end)
-        .get(&storage, &bond_start)
-        .unwrap()
-        .unwrap_or_default();
-    assert_eq!(src_total_unbonded, redel_amount_1);
-
-    // Attempt to redelegate in epoch 3 to dest_validator
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    let redel_amount_2: token::Amount = 23.into();
-    let redel_att = super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &dest_validator,
-        &dest_validator_2,
-        current_epoch,
-        redel_amount_2,
-    );
-    assert!(redel_att.is_err());
-
-    // Advance to right before the redelegation can be redelegated again
-    assert_eq!(redel_end, current_epoch);
-    let epoch_can_redel =
-        redel_end.prev() + params.slash_processing_epoch_offset();
-    loop {
         current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-        if current_epoch == epoch_can_redel.prev() {
-            break;
-        }
     }
-
-    // Attempt to redelegate in epoch before we actually are able to
-    let redel_att = super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &dest_validator,
-        &dest_validator_2,
-        current_epoch,
-        redel_amount_2,
-    );
-    assert!(redel_att.is_err());
-
-    // Advance one more epoch
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Redelegate from dest_validator to dest_validator_2 now
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &dest_validator,
-        &dest_validator_2,
-        current_epoch,
-        redel_amount_2,
-    )
-    .unwrap();
-
-    let redel_2_start = current_epoch;
-    let redel_2_end = current_epoch + params.pipeline_len;
-
-    // Checks -----------------------------------
-
-    // Both the dest validator and dest validator 2 should have incoming
-    // redelegations
-    let incoming_redelegation_1 =
-        validator_incoming_redelegations_handle(&dest_validator)
-            .get(&storage, &delegator)
-            .unwrap();
-    assert_eq!(incoming_redelegation_1, Some(redel_end));
-    let incoming_redelegation_2 =
-        validator_incoming_redelegations_handle(&dest_validator_2)
-            .get(&storage, &delegator)
-            .unwrap();
-    assert_eq!(incoming_redelegation_2, Some(redel_2_end));
-
-    // Both the src validator and dest validator should have outgoing
-    // redelegations
-    let outgoing_redelegation_1 =
-        validator_outgoing_redelegations_handle(&src_validator)
-            .at(&dest_validator)
-            .at(&bond_start)
-            .get(&storage, &redel_start)
-            .unwrap();
-    assert_eq!(outgoing_redelegation_1, Some(redel_amount_1));
-
-    let outgoing_redelegation_2 =
-        validator_outgoing_redelegations_handle(&dest_validator)
-            .at(&dest_validator_2)
-            .at(&redel_end)
-            .get(&storage, &redel_2_start)
-            .unwrap();
-    assert_eq!(outgoing_redelegation_2, Some(redel_amount_2));
-
-    // All three validators should have bonds
-    let bonded_dest2 = bond_handle(&delegator, &dest_validator_2);
-    assert_eq!(
-        bonded_src
-            .get_delta_val(&storage, bond_start)
-            .unwrap()
-            .unwrap_or_default(),
-        bond_amount - redel_amount_1
-    );
-    assert_eq!(
-        bonded_dest
-            .get_delta_val(&storage, redel_end)
-            .unwrap()
-            .unwrap_or_default(),
-        redel_amount_1 - redel_amount_2
+
+    let bond_id = BondId {
+        source: delegator,
+        validator,
+    };
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let counting = CountingStorage::new(&storage);
+    let amount =
+        crate::bond_amount(&counting, &bond_id, pipeline_epoch).unwrap();
+    let checkpoint_cost = counting.accesses.get();
+
+    let counting = CountingStorage::new(&storage);
+    let computed_amount = crate::compute_bond_amount(
+        &counting,
+        &params,
+        &bond_id,
+        pipeline_epoch,
+    )
+    .unwrap();
+    let full_cost = counting.accesses.get();
+
+    assert_eq!(amount, computed_amount);
+    assert_eq!(amount, token::Amount::from(7_u64 * num_epochs));
+    // The cost of the query with checkpoints is bounded by the number of the
+    // retained checkpoints, regardless of the length of the bonds history
+    let max_checkpoints = params.slash_processing_epoch_offset()
+        + params.past_data_retention_len
+        + params.pipeline_len
+        + 1;
+    assert!(
+        checkpoint_cost <= 2 * max_checkpoints + 4,
+        "Query with checkpoints took {checkpoint_cost} storage accesses"
     );
-    assert_eq!(
-        bonded_dest2
-            .get_delta_val(&storage, redel_2_end)
-            .unwrap()
-            .unwrap_or_default(),
-        redel_amount_2
+    assert!(
+        checkpoint_cost < full_cost,
+        "Query with checkpoints took {checkpoint_cost} storage accesses, \
+         without {full_cost}"
     );
+}
 
-    // There should be no unbond entries
-    let unbond_src = unbond_handle(&delegator, &src_validator);
-    let unbond_dest = unbond_handle(&delegator, &dest_validator);
-    assert!(unbond_src.is_empty(&storage).unwrap());
-    assert!(unbond_dest.is_empty(&storage).unwrap());
+fn test_read_all_validator_keys_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
-    // The dest validator should have some total unbonded due to the second
-    // redelegation
-    let dest_total_unbonded = total_unbonded_handle(&dest_validator)
-        .at(&redel_2_end)
-        .get(&storage, &redel_end)
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let keys = read_all_validator_keys(&storage, current_epoch).unwrap();
+    assert_eq!(keys.len(), validators.len());
+    for validator in &validators {
+        let expected = ValidatorKeys {
+            consensus_key: validator.consensus_key.clone(),
+            eth_hot_key: validator.eth_hot_key.clone(),
+            eth_cold_key: validator.eth_cold_key.clone(),
+            protocol_key: validator.protocol_key.clone(),
+        };
+        assert_eq!(keys.get(&validator.address), Some(&expected));
+    }
+
+    // Change a consensus key, it should only be returned from the pipeline
+    // epoch
+    let validator = validators[0].address.clone();
+    let new_ck = common_sk_from_simple_seed(42).ref_to();
+    change_consensus_key(&mut storage, &validator, &new_ck, current_epoch)
         .unwrap();
-    assert_eq!(dest_total_unbonded, Some(redel_amount_2));
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    for _ in 0..params.pipeline_len {
+        let keys = read_all_validator_keys(&storage, current_epoch).unwrap();
+        assert_eq!(keys[&validator].consensus_key, validators[0].consensus_key);
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+    assert_eq!(current_epoch, pipeline_epoch);
+    let keys = read_all_validator_keys(&storage, current_epoch).unwrap();
+    assert_eq!(keys[&validator].consensus_key, new_ck);
+    assert_eq!(keys[&validator].protocol_key, validators[0].protocol_key);
+}
 
-    // Delegator should have redelegated bonds due to both redelegations
-    let del_redelegated_bonds = delegator_redelegated_bonds_handle(&delegator);
-    assert_eq!(
-        Some(redel_amount_1 - redel_amount_2),
-        del_redelegated_bonds
-            .at(&dest_validator)
-            .at(&redel_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
-    );
-    assert_eq!(
-        Some(redel_amount_2),
-        del_redelegated_bonds
-            .at(&dest_validator_2)
-            .at(&redel_2_end)
-            .at(&dest_validator)
-            .get(&storage, &redel_end)
-            .unwrap()
-    );
+fn test_validator_sets_retention_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        validator_sets_retention_len: 5,
+        ..Default::default()
+    };
 
-    // Delegator redelegated unbonds should be empty
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let consensus_retention =
+        params.max_proposal_period + params.validator_sets_retention_len;
+    while current_epoch.0 <= consensus_retention + 2 {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+
+    // The validator positions are kept for the configured number of past
+    // epochs
+    let oldest_kept = current_epoch - params.validator_sets_retention_len;
     assert!(
-        delegator_redelegated_unbonds_handle(&delegator)
+        !validator_set_positions_handle()
+            .at(&oldest_kept)
             .is_empty(&storage)
             .unwrap()
     );
-
-    // Both the dest validator and dest validator 2 should have total
-    // redelegated bonds
-    let dest_redelegated_bonded =
-        validator_total_redelegated_bonded_handle(&dest_validator)
-            .at(&redel_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
-            .unwrap_or_default();
-    let dest2_redelegated_bonded =
-        validator_total_redelegated_bonded_handle(&dest_validator_2)
-            .at(&redel_2_end)
-            .at(&dest_validator)
-            .get(&storage, &redel_end)
+    assert!(
+        validator_set_positions_handle()
+            .at(&oldest_kept.prev())
+            .is_empty(&storage)
             .unwrap()
-            .unwrap_or_default();
-    assert_eq!(dest_redelegated_bonded, redel_amount_1 - redel_amount_2);
-    assert_eq!(dest2_redelegated_bonded, redel_amount_2);
+    );
 
-    // Total redelegated unbonded should be empty for src_validator and
-    // dest_validator_2
+    // The consensus validator sets are kept for the max proposal period on
+    // top
+    let oldest_kept = current_epoch - consensus_retention;
     assert!(
-        validator_total_redelegated_unbonded_handle(&dest_validator_2)
+        !consensus_validator_set_handle()
+            .at(&oldest_kept)
             .is_empty(&storage)
             .unwrap()
     );
     assert!(
-        validator_total_redelegated_unbonded_handle(&src_validator)
+        consensus_validator_set_handle()
+            .at(&oldest_kept.prev())
             .is_empty(&storage)
             .unwrap()
     );
-
-    // The dest_validator should have total_redelegated unbonded
-    let tot_redel_unbonded =
-        validator_total_redelegated_unbonded_handle(&dest_validator)
-            .at(&redel_2_end)
-            .at(&redel_end)
-            .at(&src_validator)
-            .get(&storage, &bond_start)
-            .unwrap()
-            .unwrap_or_default();
-    assert_eq!(tot_redel_unbonded, redel_amount_2);
 }
 
-/// SM test case 1 from Brent
-#[test]
-fn test_from_sm_case_1() {
-    use namada_core::types::address::testing::established_address_4;
-
+fn test_validator_set_snapshot_aux(validators: Vec<GenesisValidator>) {
     let mut storage = TestWlStorage::default();
-    let gov_params = namada_core::ledger::governance::parameters::GovernanceParameters::default();
-    gov_params.init_storage(&mut storage).unwrap();
-    write_pos_params(&mut storage, &OwnedPosParams::default()).unwrap();
-
-    let validator = established_address_1();
-    let redeleg_src_1 = established_address_2();
-    let redeleg_src_2 = established_address_3();
-    let owner = established_address_4();
-    let unbond_amount = token::Amount::from(3130688);
-    println!(
-        "Owner: {owner}\nValidator: {validator}\nRedeleg src 1: \
-         {redeleg_src_1}\nRedeleg src 2: {redeleg_src_2}"
-    );
-
-    // Validator's incoming redelegations
-    let outer_epoch_1 = Epoch(27);
-    // from redeleg_src_1
-    let epoch_1_redeleg_1 = token::Amount::from(8516);
-    // from redeleg_src_2
-    let epoch_1_redeleg_2 = token::Amount::from(5704386);
-    let outer_epoch_2 = Epoch(30);
-    // from redeleg_src_2
-    let epoch_2_redeleg_2 = token::Amount::from(1035191);
-
-    // Insert the data - bonds and redelegated bonds
-    let bonds_handle = bond_handle(&owner, &validator);
-    bonds_handle
-        .add(
-            &mut storage,
-            epoch_1_redeleg_1 + epoch_1_redeleg_2,
-            outer_epoch_1,
-            0,
-        )
-        .unwrap();
-    bonds_handle
-        .add(&mut storage, epoch_2_redeleg_2, outer_epoch_2, 0)
-        .unwrap();
-
-    let redelegated_bonds_map_1 = delegator_redelegated_bonds_handle(&owner)
-        .at(&validator)
-        .at(&outer_epoch_1);
-    redelegated_bonds_map_1
-        .at(&redeleg_src_1)
-        .insert(&mut storage, Epoch(14), epoch_1_redeleg_1)
-        .unwrap();
-    redelegated_bonds_map_1
-        .at(&redeleg_src_2)
-        .insert(&mut storage, Epoch(18), epoch_1_redeleg_2)
-        .unwrap();
-    let redelegated_bonds_map_1 = delegator_redelegated_bonds_handle(&owner)
-        .at(&validator)
-        .at(&outer_epoch_1);
-
-    let redelegated_bonds_map_2 = delegator_redelegated_bonds_handle(&owner)
-        .at(&validator)
-        .at(&outer_epoch_2);
-    redelegated_bonds_map_2
-        .at(&redeleg_src_2)
-        .insert(&mut storage, Epoch(18), epoch_2_redeleg_2)
-        .unwrap();
+    let params = OwnedPosParams::default();
 
-    // Find the modified redelegation the same way as `unbond_tokens`
-    let bonds_to_unbond = find_bonds_to_remove(
-        &storage,
-        &bonds_handle.get_data_handler(),
-        unbond_amount,
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
     )
     .unwrap();
-    dbg!(&bonds_to_unbond);
+    storage.commit_block().unwrap();
 
-    let (new_entry_epoch, new_bond_amount) = bonds_to_unbond.new_entry.unwrap();
-    assert_eq!(outer_epoch_1, new_entry_epoch);
-    // The modified bond should be sum of all redelegations less the unbonded
-    // amouunt
+    // The genesis snapshot holds the consensus validators from the highest
+    // stake with their consensus keys
+    let genesis_snapshot = read_validator_set_snapshot(&storage, current_epoch)
+        .unwrap()
+        .unwrap();
+    assert_eq!(genesis_snapshot.epoch, current_epoch);
+    let consensus_set: Vec<WeightedValidator> =
+        read_consensus_validator_set_addresses_with_stake(
+            &storage,
+            current_epoch,
+        )
+        .unwrap()
+        .into_iter()
+        .rev()
+        .collect();
+    assert_eq!(genesis_snapshot.validators.len(), consensus_set.len());
+    for (snapshot, validator) in
+        genesis_snapshot.validators.iter().zip(&consensus_set)
+    {
+        assert_eq!(snapshot.address, validator.address);
+        assert_eq!(snapshot.bonded_stake, validator.bonded_stake);
+        let genesis_validator = validators
+            .iter()
+            .find(|genesis| genesis.address == validator.address)
+            .unwrap();
+        assert_eq!(snapshot.consensus_key, genesis_validator.consensus_key);
+    }
     assert_eq!(
-        epoch_1_redeleg_1 + epoch_1_redeleg_2 + epoch_2_redeleg_2
-            - unbond_amount,
-        new_bond_amount
+        genesis_snapshot.total_stake(),
+        get_total_consensus_stake(&storage, current_epoch, &params).unwrap()
     );
-    // The current bond should be sum of redelegations fom the modified epoch
-    let cur_bond_amount = bonds_handle
-        .get_delta_val(&storage, new_entry_epoch)
-        .unwrap()
-        .unwrap_or_default();
-    assert_eq!(epoch_1_redeleg_1 + epoch_1_redeleg_2, cur_bond_amount);
 
-    let mr = compute_modified_redelegation(
-        &storage,
-        &redelegated_bonds_map_1,
-        new_entry_epoch,
-        cur_bond_amount - new_bond_amount,
-    )
-    .unwrap();
+    // The snapshot is stored under a deterministic key
+    let key = crate::storage::validator_set_snapshot_key(current_epoch);
+    assert_eq!(
+        crate::storage::is_validator_set_snapshot_key(&key),
+        Some(current_epoch)
+    );
 
-    let exp_mr = ModifiedRedelegation {
-        epoch: Some(Epoch(27)),
-        validators_to_remove: BTreeSet::from_iter([redeleg_src_2.clone()]),
-        validator_to_modify: Some(redeleg_src_2),
-        epochs_to_remove: BTreeSet::from_iter([Epoch(18)]),
-        epoch_to_modify: Some(Epoch(18)),
-        new_amount: Some(token::Amount::from(3608889)),
-    };
+    // Change a consensus key, it should only be in the snapshot from the
+    // pipeline epoch
+    let validator = genesis_snapshot.validators[0].address.clone();
+    let new_ck = common_sk_from_simple_seed(42).ref_to();
+    change_consensus_key(&mut storage, &validator, &new_ck, current_epoch)
+        .unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    while current_epoch < pipeline_epoch {
+        current_epoch = advance_epoch(&mut storage, &params);
+        store_validator_set_snapshot(&mut storage, &params, current_epoch)
+            .unwrap();
+    }
+    let snapshot = read_validator_set_snapshot(&storage, current_epoch)
+        .unwrap()
+        .unwrap();
+    assert_eq!(snapshot.validators[0].consensus_key, new_ck);
+    assert_ne!(snapshot.commitment(), genesis_snapshot.commitment());
 
-    pretty_assertions::assert_eq!(mr, exp_mr);
+    // Old snapshots are pruned
+    let last_kept_epoch = Epoch(params.withdrawable_epoch_offset() + 1);
+    while current_epoch < last_kept_epoch {
+        current_epoch = advance_epoch(&mut storage, &params);
+        store_validator_set_snapshot(&mut storage, &params, current_epoch)
+            .unwrap();
+    }
+    assert!(
+        read_validator_set_snapshot(&storage, Epoch::default())
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        read_validator_set_snapshot(&storage, Epoch(1)).unwrap().is_some()
+    );
 }
 
-/// Test precisely that we are not overslashing, as originally discovered by Tomas in this issue: https://github.com/informalsystems/partnership-heliax/issues/74
-fn test_overslashing_aux(mut validators: Vec<GenesisValidator>) {
-    assert_eq!(validators.len(), 4);
+fn test_validator_from_protocol_pk_cached_aux(
+    validators: Vec<GenesisValidator>,
+) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
 
-    let offending_stake = token::Amount::native_whole(110);
-    let other_stake = token::Amount::native_whole(100);
+    let consensus_set =
+        read_consensus_validator_set_addresses(&storage, current_epoch)
+            .unwrap();
+    let mut cache = ProtocolKeyCache::default();
+    for validator in &validators {
+        let uncached = storage.pos_queries().get_validator_from_protocol_pk(
+            &validator.protocol_key,
+            Some(current_epoch),
+        );
+        let cached =
+            storage.pos_queries().get_validator_from_protocol_pk_cached(
+                &validator.protocol_key,
+                Some(current_epoch),
+                &mut cache,
+            );
+        if consensus_set.contains(&validator.address) {
+            let cached = cached.unwrap();
+            assert_eq!(cached.address, validator.address);
+            assert_eq!(cached, uncached.unwrap());
+        } else {
+            assert!(cached.is_err());
+            assert!(uncached.is_err());
+        }
+    }
+    assert!(cache.contains_epoch(current_epoch));
 
-    // Set stakes so we know we will get a slashing rate between 0.5 -1.0
-    validators[0].tokens = offending_stake;
-    validators[1].tokens = other_stake;
-    validators[2].tokens = other_stake;
-    validators[3].tokens = other_stake;
+    // An unknown protocol key is not found
+    let unknown_pk = common_sk_from_simple_seed(42).ref_to();
+    assert_matches!(
+        storage.pos_queries().get_validator_from_protocol_pk_cached(
+            &unknown_pk,
+            Some(current_epoch),
+            &mut cache,
+        ),
+        Err(PosQueriesError::NotValidatorKey(_, _))
+    );
 
-    // Get the offending validator
-    let validator = validators[0].address.clone();
+    // Only the most recently looked up epochs are kept in the cache
+    let genesis_epoch = current_epoch;
+    for _ in 0..2 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        let _ = storage.pos_queries().get_validator_from_protocol_pk_cached(
+            &validators[0].protocol_key,
+            Some(current_epoch),
+            &mut cache,
+        );
+        assert!(cache.contains_epoch(current_epoch));
+    }
+    assert!(!cache.contains_epoch(genesis_epoch));
 
-    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    cache.invalidate();
+    assert!(!cache.contains_epoch(current_epoch));
+}
+
+fn test_change_validator_eth_keys_aux(validators: Vec<GenesisValidator>) {
     let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
@@ -5747,566 +8778,748 @@ fn test_overslashing_aux(mut validators: Vec<GenesisValidator>) {
     .unwrap();
     storage.commit_block().unwrap();
 
-    // Get a delegator with some tokens
-    let staking_token = storage.storage.native_token.clone();
-    let delegator = address::testing::gen_implicit_address();
-    let amount_del = token::Amount::native_whole(5);
-    credit_tokens(&mut storage, &staking_token, &delegator, amount_del)
-        .unwrap();
+    let validator = validators[0].address.clone();
+    let new_hot_key = gen_keypair::<key::secp256k1::SigScheme>();
+    let new_hot_key = key::common::SecretKey::Secp256k1(new_hot_key).ref_to();
+    let new_cold_key = gen_keypair::<key::secp256k1::SigScheme>();
+    let new_cold_key = key::common::SecretKey::Secp256k1(new_cold_key).ref_to();
 
-    // Delegate tokens in epoch 0 to validator
-    bond_tokens(
+    // The keys must be Secp256k1 keys
+    let ed_key = common_sk_from_simple_seed(0).ref_to();
+    assert!(
+        change_validator_eth_keys(
+            &mut storage,
+            &validator,
+            &ed_key,
+            &new_cold_key,
+            current_epoch,
+        )
+        .is_err()
+    );
+    // The hot and cold keys must be different
+    assert!(
+        change_validator_eth_keys(
+            &mut storage,
+            &validator,
+            &new_cold_key,
+            &new_cold_key,
+            current_epoch,
+        )
+        .is_err()
+    );
+    // Only a validator can change their keys
+    assert!(
+        change_validator_eth_keys(
+            &mut storage,
+            &established_address_2(),
+            &new_hot_key,
+            &new_cold_key,
+            current_epoch,
+        )
+        .is_err()
+    );
+
+    change_validator_eth_keys(
         &mut storage,
-        Some(&delegator),
         &validator,
-        amount_del,
+        &new_hot_key,
+        &new_cold_key,
         current_epoch,
-        None,
     )
     .unwrap();
 
-    let self_bond_epoch = current_epoch;
-    let delegation_epoch = current_epoch + params.pipeline_len;
-
-    // Advance to pipeline epoch
-    for _ in 0..params.pipeline_len {
+    // The new keys only take effect at the pipeline epoch
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    while current_epoch < pipeline_epoch {
+        let keys = read_all_validator_keys(&storage, current_epoch).unwrap();
+        assert_eq!(keys[&validator].eth_hot_key, validators[0].eth_hot_key);
+        assert_eq!(keys[&validator].eth_cold_key, validators[0].eth_cold_key);
         current_epoch = advance_epoch(&mut storage, &params);
     }
-    assert_eq!(delegation_epoch, current_epoch);
+    let keys = read_all_validator_keys(&storage, current_epoch).unwrap();
+    assert_eq!(keys[&validator].eth_hot_key, new_hot_key);
+    assert_eq!(keys[&validator].eth_cold_key, new_cold_key);
+}
 
-    // Find a misbehavior committed in epoch 0
-    slash(
-        &mut storage,
-        &params,
-        current_epoch,
-        self_bond_epoch,
-        0_u64,
-        SlashType::DuplicateVote,
-        &validator,
-        current_epoch.next(),
-    )
-    .unwrap();
+fn test_liveness_reports_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
-    // Find a misbehavior committed in current epoch
-    slash(
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        &params,
+        params,
+        validators.into_iter(),
         current_epoch,
-        delegation_epoch,
-        0_u64,
-        SlashType::DuplicateVote,
-        &validator,
-        current_epoch.next(),
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    let processing_epoch_1 =
-        self_bond_epoch + params.slash_processing_epoch_offset();
-    let processing_epoch_2 =
-        delegation_epoch + params.slash_processing_epoch_offset();
+    let consensus_validators =
+        read_consensus_validator_set_addresses(&storage, current_epoch)
+            .unwrap()
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+    let offline_validator = consensus_validators.first().unwrap().clone();
+    let offline = BTreeSet::from([offline_validator.clone()]);
+    let reporters = consensus_validators
+        .iter()
+        .filter(|&validator| validator != &offline_validator)
+        .cloned()
+        .collect::<Vec<_>>();
 
-    // Advance to processing epoch 1
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-        if current_epoch == processing_epoch_1 {
-            break;
-        }
+    // A validator can't report itself
+    let changed_keys =
+        record_liveness_report(&mut storage, &offline_validator, &offline)
+            .unwrap();
+    assert!(changed_keys.is_empty());
+
+    for reporter in &reporters {
+        let changed_keys =
+            record_liveness_report(&mut storage, reporter, &offline).unwrap();
+        assert_eq!(changed_keys.len(), 1);
     }
+    // The reports of validators outside of the consensus set are ignored
+    record_liveness_report(&mut storage, &established_address_3(), &offline)
+        .unwrap();
 
-    let total_stake_1 = offending_stake + 3 * other_stake;
-    let stake_frac = Dec::from(offending_stake) / Dec::from(total_stake_1);
-    let slash_rate_1 = Dec::from_str("9.0").unwrap() * stake_frac * stake_frac;
-    dbg!(&slash_rate_1);
+    let mut reported_stake = token::Amount::zero();
+    for reporter in &reporters {
+        reported_stake +=
+            read_validator_stake(&storage, &params, reporter, current_epoch)
+                .unwrap();
+    }
+    let total_consensus_stake =
+        get_total_consensus_stake(&storage, current_epoch, &params).unwrap();
+    let quorum_reached =
+        reported_stake > total_consensus_stake * (2_u64, 3_u64);
 
-    let exp_slashed_1 = offending_stake.mul_ceil(slash_rate_1);
+    let votes_height = BlockHeight(1);
+    apply_liveness_reports(&mut storage, &params, current_epoch, votes_height)
+        .unwrap();
 
-    // Check that the proper amount was slashed
-    let epoch = current_epoch.next();
-    let validator_stake =
-        read_validator_stake(&storage, &params, &validator, epoch).unwrap();
-    let exp_validator_stake = offending_stake - exp_slashed_1 + amount_del;
-    assert_eq!(validator_stake, exp_validator_stake);
+    let missed_vote = liveness_missed_votes_handle()
+        .at(&offline_validator)
+        .contains(&storage, &votes_height.0)
+        .unwrap();
+    assert_eq!(missed_vote, quorum_reached);
+    let missed_votes = liveness_sum_missed_votes_handle()
+        .get(&storage, &offline_validator)
+        .unwrap();
+    assert_eq!(missed_votes, quorum_reached.then_some(1));
+    // The reports are cleared once applied
+    assert!(liveness_reports_handle().is_empty(&storage).unwrap());
 
-    let total_stake = read_total_stake(&storage, &params, epoch).unwrap();
-    let exp_total_stake =
-        offending_stake - exp_slashed_1 + amount_del + 3 * other_stake;
-    assert_eq!(total_stake, exp_total_stake);
+    // A missed vote is only counted once per block height
+    for reporter in &reporters {
+        record_liveness_report(&mut storage, reporter, &offline).unwrap();
+    }
+    apply_liveness_reports(&mut storage, &params, current_epoch, votes_height)
+        .unwrap();
+    let missed_votes = liveness_sum_missed_votes_handle()
+        .get(&storage, &offline_validator)
+        .unwrap();
+    assert_eq!(missed_votes, quorum_reached.then_some(1));
+}
 
-    let self_bond_id = BondId {
-        source: validator.clone(),
-        validator: validator.clone(),
-    };
-    let bond_amount =
-        crate::bond_amount(&storage, &self_bond_id, epoch).unwrap();
-    let exp_bond_amount = offending_stake - exp_slashed_1;
-    assert_eq!(bond_amount, exp_bond_amount);
+fn test_read_bonded_ratio_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
-    // Advance to processing epoch 2
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-        if current_epoch == processing_epoch_2 {
-            break;
-        }
-    }
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
 
-    let total_stake_2 = offending_stake + amount_del + 3 * other_stake;
-    let stake_frac =
-        Dec::from(offending_stake + amount_del) / Dec::from(total_stake_2);
-    let slash_rate_2 = Dec::from_str("9.0").unwrap() * stake_frac * stake_frac;
-    dbg!(&slash_rate_2);
+    // The whole genesis supply of the staking token is bonded
+    assert_eq!(
+        read_bonded_ratio(&storage, current_epoch).unwrap(),
+        Dec::one()
+    );
 
-    let exp_slashed_from_delegation = amount_del.mul_ceil(slash_rate_2);
+    // Minting as many unbonded tokens as there are staked halves the ratio
+    let staking_token = staking_token_address(&storage);
+    let total_stake =
+        read_total_stake(&storage, &params, current_epoch).unwrap();
+    credit_tokens(
+        &mut storage,
+        &staking_token,
+        &established_address_1(),
+        total_stake,
+    )
+    .unwrap();
+    assert_eq!(
+        read_bonded_ratio(&storage, current_epoch).unwrap(),
+        Dec::new(5, 1).unwrap()
+    );
+}
 
-    // Check that the proper amount was slashed. We expect that all of the
-    // validator self-bond has been slashed and some of the delegation has been
-    // slashed due to the second infraction.
-    let epoch = current_epoch.next();
+fn test_slash_stale_evidence_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        evidence_max_age_epochs: 2,
+        ..Default::default()
+    };
+    let validator = validators[0].address.clone();
 
-    let validator_stake =
-        read_validator_stake(&storage, &params, &validator, epoch).unwrap();
-    let exp_validator_stake = amount_del - exp_slashed_from_delegation;
-    assert_eq!(validator_stake, exp_validator_stake);
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
 
-    let total_stake = read_total_stake(&storage, &params, epoch).unwrap();
-    let exp_total_stake =
-        amount_del - exp_slashed_from_delegation + 3 * other_stake;
-    assert_eq!(total_stake, exp_total_stake);
+    for _ in 0..=params.evidence_max_age_epochs {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+    let state_pre = validator_state_handle(&validator)
+        .get(&storage, current_epoch.next(), &params)
+        .unwrap();
 
-    let delegation_id = BondId {
-        source: delegator.clone(),
-        validator: validator.clone(),
-    };
-    let delegation_amount =
-        crate::bond_amount(&storage, &delegation_id, epoch).unwrap();
-    let exp_del_amount = amount_del - exp_slashed_from_delegation;
-    assert_eq!(delegation_amount, exp_del_amount);
+    // Evidence older than the max age must be rejected
+    let stale_epoch = current_epoch - (params.evidence_max_age_epochs + 1);
+    let err = slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        stale_epoch,
+        0_u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap_err();
+    assert_matches!(
+        err.downcast::<SlashError>().unwrap().deref(),
+        SlashError::EvidenceTooOld(epoch, current, max_age)
+            if *epoch == stale_epoch
+                && *current == current_epoch
+                && *max_age == params.evidence_max_age_epochs
+    );
 
-    let self_bond_amount =
-        crate::bond_amount(&storage, &self_bond_id, epoch).unwrap();
-    let exp_bond_amount = token::Amount::zero();
-    assert_eq!(self_bond_amount, exp_bond_amount);
+    // Nothing must have been enqueued and the validator is not jailed
+    let stale_processing_epoch =
+        stale_epoch + params.slash_processing_epoch_offset();
+    assert!(
+        crate::enqueued_slashes_handle()
+            .at(&stale_processing_epoch)
+            .is_empty(&storage)
+            .unwrap()
+    );
+    assert_eq!(
+        validator_state_handle(&validator)
+            .get(&storage, current_epoch.next(), &params)
+            .unwrap(),
+        state_pre
+    );
+
+    // Evidence at the max age is still slashable
+    let infraction_epoch = current_epoch - params.evidence_max_age_epochs;
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        infraction_epoch,
+        0_u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+    let processing_epoch =
+        infraction_epoch + params.slash_processing_epoch_offset();
+    assert!(
+        !crate::enqueued_slashes_handle()
+            .at(&processing_epoch)
+            .is_empty(&storage)
+            .unwrap()
+    );
+    assert_eq!(
+        validator_state_handle(&validator)
+            .get(&storage, current_epoch.next(), &params)
+            .unwrap(),
+        Some(ValidatorState::Jailed)
+    );
 }
 
-fn test_unslashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
+fn test_get_voting_power_at_aux(validators: Vec<GenesisValidator>) {
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
         unbonding_len: 4,
+        evidence_max_age_epochs: 4,
         ..Default::default()
     };
+    let validator = validators[0].address.clone();
+    let validator_stake = validators[0].tokens;
 
     // Genesis
-    let mut current_epoch = storage.storage.block.epoch;
+    let genesis_epoch = storage.storage.block.epoch;
     let params = test_init_genesis(
         &mut storage,
         params,
-        validators.clone().into_iter(),
-        current_epoch,
+        validators.into_iter(),
+        genesis_epoch,
     )
     .unwrap();
     storage.commit_block().unwrap();
 
-    let validator1 = validators[0].address.clone();
-    let validator2 = validators[1].address.clone();
-
-    // Get a delegator with some tokens
+    // Delegate to the validator
     let staking_token = staking_token_address(&storage);
     let delegator = address::testing::gen_implicit_address();
-    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
-    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+    let amount_del = token::Amount::native_whole(1_000);
+    credit_tokens(&mut storage, &staking_token, &delegator, amount_del)
         .unwrap();
-
-    // Bond to validator 1
-    super::bond_tokens(
+    bond_tokens(
         &mut storage,
         Some(&delegator),
-        &validator1,
-        10_000.into(),
-        current_epoch,
+        &validator,
+        amount_del,
+        genesis_epoch,
         None,
     )
     .unwrap();
 
-    // Unbond some from validator 1
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator1,
-        1_342.into(),
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    let mut current_epoch = genesis_epoch;
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
-        current_epoch,
-        1_875.into(),
-    )
-    .unwrap();
+    // The voting power at a past epoch doesn't include the later delegation
+    let pos_queries = storage.pos_queries();
+    assert_eq!(
+        pos_queries
+            .get_voting_power_at(&validator, genesis_epoch)
+            .unwrap(),
+        validator_stake
+    );
+    assert_eq!(
+        pos_queries
+            .get_voting_power_at(&delegator, genesis_epoch)
+            .unwrap(),
+        token::Amount::zero()
+    );
+    // The voting power of a validator includes its delegations
+    assert_eq!(
+        pos_queries
+            .get_voting_power_at(&validator, current_epoch)
+            .unwrap(),
+        validator_stake + amount_del
+    );
+    assert_eq!(
+        pos_queries
+            .get_voting_power_at(&delegator, current_epoch)
+            .unwrap(),
+        amount_del
+    );
 
-    // Unbond some from validator 2
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator2,
-        584.into(),
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    // The stake of epochs past the retention can no longer be queried
+    let retention =
+        OffsetMaxProposalPeriodOrSlashProcessingLenPlus::value(&params);
+    for _ in 0..retention {
+        advance_epoch(&mut storage, &params);
+    }
+    let err = storage
+        .pos_queries()
+        .get_voting_power_at(&validator, genesis_epoch)
+        .unwrap_err();
+    assert_matches!(
+        err,
+        PosQueriesError::EpochNotRetained(epoch, _) if epoch == genesis_epoch
+    );
+}
 
-    // Advance an epoch
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+fn test_sample_validators_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
-    // Bond to validator 1
-    super::bond_tokens(
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    test_init_genesis(
         &mut storage,
-        Some(&delegator),
-        &validator1,
-        384.into(),
+        params,
+        validators.into_iter(),
         current_epoch,
-        None,
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    // Unbond some from validator 1
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator1,
-        144.into(),
+    let consensus_set = read_consensus_validator_set_addresses_with_stake(
+        &storage,
         current_epoch,
-        false,
     )
     .unwrap();
+    let seed = Hash::sha256(b"seed");
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
+    // Asking for more validators than there are samples the whole set, once
+    let sampled = sample_validators(
+        &storage,
         current_epoch,
-        3_448.into(),
+        &seed,
+        consensus_set.len() + 1,
     )
     .unwrap();
+    assert_eq!(sampled.len(), consensus_set.len());
+    assert_eq!(
+        sampled.iter().cloned().collect::<BTreeSet<_>>(),
+        consensus_set
+    );
 
-    // Unbond some from validator 2
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator2,
-        699.into(),
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    // The sampling is reproducible
+    assert_eq!(
+        sample_validators(
+            &storage,
+            current_epoch,
+            &seed,
+            consensus_set.len() + 1
+        )
+        .unwrap(),
+        sampled
+    );
+    // A smaller sample with the same seed is a prefix of the larger one
+    assert_eq!(
+        sample_validators(&storage, current_epoch, &seed, 1).unwrap(),
+        sampled[..1]
+    );
+    assert!(
+        sample_validators(&storage, current_epoch, &seed, 0)
+            .unwrap()
+            .is_empty()
+    );
+}
 
-    // Advance an epoch
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+fn test_sample_validators_by_stake_aux(mut validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
-    // Bond to validator 1
-    super::bond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator1,
-        4_384.into(),
-        current_epoch,
-        None,
-    )
-    .unwrap();
+    // Give the second validator 3 times the stake of the first one
+    validators[0].tokens = token::Amount::native_whole(1_000);
+    validators[1].tokens = token::Amount::native_whole(3_000);
+    let heavy_validator = validators[1].address.clone();
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    test_init_genesis(
         &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
+        params,
+        validators.into_iter(),
         current_epoch,
-        1_008.into(),
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    // Unbond some from validator 2
-    super::unbond_tokens(
+    // The validator with more stake is sampled first about 3/4 of the time
+    let num_samples = 1_000_u64;
+    let heavy_count = (0..num_samples)
+        .filter(|ix| {
+            let seed = Hash::sha256(ix.to_be_bytes());
+            let sampled =
+                sample_validators(&storage, current_epoch, &seed, 1).unwrap();
+            sampled[0].address == heavy_validator
+        })
+        .count() as u64;
+    assert!(
+        (700..=800).contains(&heavy_count),
+        "Expected the heavier validator to be sampled first about 750 times \
+         out of {num_samples}, got {heavy_count}"
+    );
+}
+
+fn test_read_stake_range_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+    let validator = validators[0].address.clone();
+
+    // Genesis
+    let genesis_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        Some(&delegator),
-        &validator2,
-        3_500.into(),
-        current_epoch,
-        false,
+        params,
+        validators.into_iter(),
+        genesis_epoch,
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    // Checks
-    let val1_init_stake = validators[0].tokens;
-
-    for epoch in Epoch::iter_bounds_inclusive(
-        Epoch(0),
-        current_epoch + params.pipeline_len,
-    ) {
-        let bond_amount = crate::bond_amount(
-            &storage,
-            &BondId {
-                source: delegator.clone(),
-                validator: validator1.clone(),
-            },
-            epoch,
+    // Change the validator's stake in a few epochs
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let amount_del = token::Amount::native_whole(1_000);
+    credit_tokens(&mut storage, &staking_token, &delegator, amount_del * 3_u64)
+        .unwrap();
+    let mut current_epoch = genesis_epoch;
+    for _ in 0..3 {
+        bond_tokens(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            amount_del,
+            current_epoch,
+            None,
         )
-        .unwrap_or_default();
-
-        let val_stake =
-            crate::read_validator_stake(&storage, &params, &validator1, epoch)
-                .unwrap();
-        // dbg!(&bond_amount);
-        assert_eq!(val_stake - val1_init_stake, bond_amount);
+        .unwrap();
+        current_epoch = advance_epoch(&mut storage, &params);
+        current_epoch = advance_epoch(&mut storage, &params);
     }
-}
 
-fn test_log_block_rewards_aux(
-    validators: Vec<GenesisValidator>,
-    params: OwnedPosParams,
-) {
-    tracing::info!(
-        "New case with {} validators: {:#?}",
-        validators.len(),
-        validators
-            .iter()
-            .map(|v| (&v.address, v.tokens.to_string_native()))
+    // The stakes in a range match the stakes read one epoch at a time
+    let to_epoch = current_epoch + params.pipeline_len;
+    let stakes = read_validator_stake_range(
+        &storage,
+        &params,
+        &validator,
+        genesis_epoch,
+        to_epoch,
+    )
+    .unwrap();
+    let total_stakes =
+        read_total_stake_range(&storage, &params, genesis_epoch, to_epoch)
+            .unwrap();
+    assert_eq!(
+        stakes.keys().copied().collect::<Vec<_>>(),
+        Epoch::iter_bounds_inclusive(genesis_epoch, to_epoch)
             .collect::<Vec<_>>()
     );
-    let mut s = TestWlStorage::default();
-    // Init genesis
-    let current_epoch = s.storage.block.epoch;
-    let params = test_init_genesis(
-        &mut s,
-        params,
-        validators.clone().into_iter(),
+    assert_eq!(
+        total_stakes.keys().copied().collect::<Vec<_>>(),
+        Epoch::iter_bounds_inclusive(genesis_epoch, to_epoch)
+            .collect::<Vec<_>>()
+    );
+    for epoch in Epoch::iter_bounds_inclusive(genesis_epoch, to_epoch) {
+        assert_eq!(
+            stakes[&epoch],
+            read_validator_stake(&storage, &params, &validator, epoch).unwrap()
+        );
+        assert_eq!(
+            total_stakes[&epoch],
+            read_total_stake(&storage, &params, epoch).unwrap()
+        );
+    }
+    assert_eq!(
+        stakes[&to_epoch],
+        stakes[&genesis_epoch] + amount_del * 3_u64
+    );
+
+    // A sub-range only contains the requested epochs
+    let from_epoch = genesis_epoch + 2;
+    let stakes_sub = read_validator_stake_range(
+        &storage,
+        &params,
+        &validator,
+        from_epoch,
         current_epoch,
     )
     .unwrap();
-    s.commit_block().unwrap();
-    let total_stake =
-        crate::get_total_consensus_stake(&s, current_epoch, &params).unwrap();
-    let consensus_set =
-        crate::read_consensus_validator_set_addresses(&s, current_epoch)
-            .unwrap();
-    let proposer_address = consensus_set.iter().next().unwrap().clone();
-
-    tracing::info!(
-            ?params.block_proposer_reward,
-            ?params.block_vote_reward,
+    assert_eq!(
+        stakes_sub,
+        stakes
+            .range(from_epoch..=current_epoch)
+            .map(|(epoch, stake)| (*epoch, *stake))
+            .collect::<BTreeMap<_, _>>()
     );
-    tracing::info!(?proposer_address,);
+}
 
-    // Rewards accumulator should be empty at first
-    let rewards_handle = crate::rewards_accumulator_handle();
-    assert!(rewards_handle.is_empty(&s).unwrap());
+/// Test that a reserved consensus key can only be taken by the account that
+/// reserved it, until the reservation expires.
+fn test_reserve_consensus_key_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+    let used_key = validators[0].consensus_key.clone();
 
-    let mut last_rewards = BTreeMap::default();
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
 
-    let num_blocks = 100;
-    // Loop through `num_blocks`, log rewards & check results
-    for i in 0..num_blocks {
-        tracing::info!("");
-        tracing::info!("Block {}", i + 1);
+    let operator = established_address_1();
+    let other = established_address_2();
+    let consensus_key = key::common::PublicKey::Ed25519(
+        gen_keypair::<key::ed25519::SigScheme>().ref_to(),
+    );
+    let reservation_error = |err: storage_api::Error| {
+        err.downcast::<ConsensusKeyReservationError>().unwrap()
+    };
 
-        // A helper closure to prepare minimum required votes
-        let prep_votes = |epoch| {
-            // Ceil of 2/3 of total stake
-            let min_required_votes = total_stake.mul_ceil(Dec::two() / 3);
+    // A key used by a validator or a non-Ed25519 key cannot be reserved
+    let err = reserve_consensus_key(
+        &mut storage,
+        &operator,
+        &used_key,
+        current_epoch,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        *reservation_error(err),
+        ConsensusKeyReservationError::AlreadyUsed
+    ));
+    let secp_key = key::common::PublicKey::Secp256k1(
+        gen_keypair::<key::secp256k1::SigScheme>().ref_to(),
+    );
+    let err = reserve_consensus_key(
+        &mut storage,
+        &operator,
+        &secp_key,
+        current_epoch,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        *reservation_error(err),
+        ConsensusKeyReservationError::MustBeEd25519
+    ));
 
-            let mut total_votes = token::Amount::zero();
-            let mut non_voters = HashSet::<Address>::default();
-            let mut prep_vote = |validator| {
-                // Add validator vote if it's in consensus set and if we don't
-                // yet have min required votes
-                if consensus_set.contains(validator)
-                    && total_votes < min_required_votes
-                {
-                    let stake =
-                        read_validator_stake(&s, &params, validator, epoch)
-                            .unwrap();
-                    total_votes += stake;
-                    let validator_vp =
-                        into_tm_voting_power(params.tm_votes_per_token, stake)
-                            as u64;
-                    tracing::info!("Validator {validator} signed");
-                    Some(VoteInfo {
-                        validator_address: validator.clone(),
-                        validator_vp,
-                    })
-                } else {
-                    non_voters.insert(validator.clone());
-                    None
-                }
-            };
+    // Reserve the key and renew the reservation in the next epoch
+    reserve_consensus_key(
+        &mut storage,
+        &operator,
+        &consensus_key,
+        current_epoch,
+    )
+    .unwrap();
+    current_epoch = advance_epoch(&mut storage, &params);
+    reserve_consensus_key(
+        &mut storage,
+        &operator,
+        &consensus_key,
+        current_epoch,
+    )
+    .unwrap();
+    let reserved_at = current_epoch;
+    assert_eq!(
+        find_consensus_key_reservation(&storage, &consensus_key, current_epoch)
+            .unwrap(),
+        Some((operator.clone(), reserved_at))
+    );
 
-            let votes: Vec<VoteInfo> = validators
-                .iter()
-                .rev()
-                .filter_map(|validator| prep_vote(&validator.address))
-                .collect();
-            (votes, total_votes, non_voters)
-        };
+    // Nobody else can reserve or take the key until the reservation expires
+    let expiry_epoch = reserved_at + params.pipeline_len;
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::prune_all(&mut storage, current_epoch).unwrap();
 
-        let (votes, signing_stake, non_voters) = prep_votes(current_epoch);
-        crate::log_block_rewards(
-            &mut s,
+        let err = reserve_consensus_key(
+            &mut storage,
+            &other,
+            &consensus_key,
             current_epoch,
-            &proposer_address,
-            votes.clone(),
         )
-        .unwrap();
-
-        assert!(!rewards_handle.is_empty(&s).unwrap());
-
-        let rewards_calculator = PosRewardsCalculator {
-            proposer_reward: params.block_proposer_reward,
-            signer_reward: params.block_vote_reward,
-            signing_stake,
-            total_stake,
-        };
-        let coeffs = rewards_calculator.get_reward_coeffs().unwrap();
-        tracing::info!(?coeffs);
-
-        // Check proposer reward
-        let stake =
-            read_validator_stake(&s, &params, &proposer_address, current_epoch)
-                .unwrap();
-        let proposer_signing_reward = votes.iter().find_map(|vote| {
-            if vote.validator_address == proposer_address {
-                let signing_fraction =
-                    Dec::from(stake) / Dec::from(signing_stake);
-                Some(coeffs.signer_coeff * signing_fraction)
-            } else {
-                None
-            }
-        });
-        let expected_proposer_rewards = last_rewards.get(&proposer_address).copied().unwrap_or_default() +
-        // Proposer reward
-        coeffs.proposer_coeff
-        // Consensus validator reward
-        + (coeffs.active_val_coeff
-            * (Dec::from(stake) / Dec::from(total_stake)))
-        // Signing reward (if proposer voted)
-        + proposer_signing_reward
-            .unwrap_or_default();
-        tracing::info!(
-            "Expected proposer rewards: {expected_proposer_rewards}. Signed \
-             block: {}",
-            proposer_signing_reward.is_some()
-        );
-        assert_eq!(
-            rewards_handle.get(&s, &proposer_address).unwrap(),
-            Some(expected_proposer_rewards)
-        );
-
-        // Check voters rewards
-        for VoteInfo {
-            validator_address, ..
-        } in votes.iter()
-        {
-            // Skip proposer, in case voted - already checked
-            if validator_address == &proposer_address {
-                continue;
-            }
-
-            let stake = read_validator_stake(
-                &s,
+        .unwrap_err();
+        assert!(matches!(
+            *reservation_error(err),
+            ConsensusKeyReservationError::ReservedByOther(owner, epoch)
+                if owner == operator && epoch == expiry_epoch
+        ));
+        let err = become_validator(
+            &mut storage,
+            become_validator_args(
                 &params,
-                validator_address,
+                &other,
+                &consensus_key,
                 current_epoch,
-            )
-            .unwrap();
-            let signing_fraction = Dec::from(stake) / Dec::from(signing_stake);
-            let expected_signer_rewards = last_rewards
-                .get(validator_address)
-                .copied()
-                .unwrap_or_default()
-                + coeffs.signer_coeff * signing_fraction
-                + (coeffs.active_val_coeff
-                    * (Dec::from(stake) / Dec::from(total_stake)));
-            tracing::info!(
-                "Expected signer {validator_address} rewards: \
-                 {expected_signer_rewards}"
-            );
-            assert_eq!(
-                rewards_handle.get(&s, validator_address).unwrap(),
-                Some(expected_signer_rewards)
-            );
-        }
-
-        // Check non-voters rewards, if any
-        for address in non_voters {
-            // Skip proposer, in case it didn't vote - already checked
-            if address == proposer_address {
-                continue;
-            }
+            ),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            *reservation_error(err),
+            ConsensusKeyReservationError::ReservedByOther(..)
+        ));
+        assert!(!is_validator(&storage, &other).unwrap());
+    }
 
-            if consensus_set.contains(&address) {
-                let stake =
-                    read_validator_stake(&s, &params, &address, current_epoch)
-                        .unwrap();
-                let expected_non_signer_rewards =
-                    last_rewards.get(&address).copied().unwrap_or_default()
-                        + coeffs.active_val_coeff
-                            * (Dec::from(stake) / Dec::from(total_stake));
-                tracing::info!(
-                    "Expected non-signer {address} rewards: \
-                     {expected_non_signer_rewards}"
-                );
-                assert_eq!(
-                    rewards_handle.get(&s, &address).unwrap(),
-                    Some(expected_non_signer_rewards)
-                );
-            } else {
-                let last_reward = last_rewards.get(&address).copied();
-                assert_eq!(
-                    rewards_handle.get(&s, &address).unwrap(),
-                    last_reward
-                );
-            }
-        }
-        s.commit_block().unwrap();
+    // Once expired, the reservation is pruned and the key can be reserved by
+    // another account, which then takes it
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::prune_all(&mut storage, current_epoch).unwrap();
+    assert_eq!(
+        find_consensus_key_reservation(&storage, &consensus_key, current_epoch)
+            .unwrap(),
+        None
+    );
+    assert!(
+        consensus_key_reservations_handle()
+            .at(&reserved_at)
+            .is_empty(&storage)
+            .unwrap()
+    );
+    reserve_consensus_key(&mut storage, &other, &consensus_key, current_epoch)
+        .unwrap();
+    assert!(
+        become_validator(
+            &mut storage,
+            become_validator_args(
+                &params,
+                &operator,
+                &consensus_key,
+                current_epoch
+            ),
+        )
+        .is_err()
+    );
+    become_validator(
+        &mut storage,
+        become_validator_args(&params, &other, &consensus_key, current_epoch),
+    )
+    .unwrap();
+    assert!(is_validator(&storage, &other).unwrap());
+    assert!(is_consensus_key_used(&storage, &consensus_key).unwrap());
 
-        last_rewards =
-            crate::rewards_accumulator_handle().collect_map(&s).unwrap();
+    // The reservation is released once the key is used
+    assert_eq!(
+        find_consensus_key_reservation(&storage, &consensus_key, current_epoch)
+            .unwrap(),
+        None
+    );
+}
 
-        let rewards_sum: Dec = last_rewards.values().copied().sum();
-        let expected_sum = Dec::one() * (i as u64 + 1);
-        let err_tolerance = Dec::new(1, 9).unwrap();
-        let fail_msg = format!(
-            "Expected rewards sum at block {} to be {expected_sum}, got \
-             {rewards_sum}. Error tolerance {err_tolerance}.",
-            i + 1
-        );
-        assert!(expected_sum <= rewards_sum + err_tolerance, "{fail_msg}");
-        assert!(rewards_sum <= expected_sum, "{fail_msg}");
+/// Arguments to initialize a validator with the given consensus key and
+/// arbitrary other keys.
+fn become_validator_args<'a>(
+    params: &'a PosParams,
+    address: &'a Address,
+    consensus_key: &'a PublicKey,
+    current_epoch: Epoch,
+) -> BecomeValidator<'a> {
+    BecomeValidator {
+        params,
+        address,
+        consensus_key,
+        protocol_key: consensus_key,
+        eth_cold_key: consensus_key,
+        eth_hot_key: consensus_key,
+        current_epoch,
+        commission_rate: Dec::new(5, 2).expect("Dec creation failed"),
+        max_commission_rate_change: Dec::new(5, 2)
+            .expect("Dec creation failed"),
+        metadata: Default::default(),
+        offset_opt: None,
     }
 }
 
-fn test_update_rewards_products_aux(validators: Vec<GenesisValidator>) {
-    tracing::info!(
-        "New case with {} validators: {:#?}",
-        validators.len(),
-        validators
-            .iter()
-            .map(|v| (&v.address, v.tokens.to_string_native()))
-            .collect::<Vec<_>>()
-    );
+/// Test that the validators whose Ethereum bridge vote extensions were
+/// included in a block share the vote extensions reward.
+fn test_log_vext_rewards_aux(validators: Vec<GenesisValidator>) {
     let mut s = TestWlStorage::default();
-    // Init genesis
-    let current_epoch = s.storage.block.epoch;
     let params = OwnedPosParams::default();
+
+    // Genesis
+    let current_epoch = s.storage.block.epoch;
     let params = test_init_genesis(
         &mut s,
         params,
@@ -6316,89 +9529,282 @@ fn test_update_rewards_products_aux(validators: Vec<GenesisValidator>) {
     .unwrap();
     s.commit_block().unwrap();
 
-    let staking_token = staking_token_address(&s);
     let consensus_set =
-        crate::read_consensus_validator_set_addresses(&s, current_epoch)
+        read_consensus_validator_set_addresses_with_stake(&s, current_epoch)
             .unwrap();
+    let total_stake =
+        crate::get_total_consensus_stake(&s, current_epoch, &params).unwrap();
+    let proposer_address = consensus_set.first().unwrap().address.clone();
 
-    // Start a new epoch
-    let current_epoch = advance_epoch(&mut s, &params);
-
-    // Read some data before applying rewards
-    let pos_balance_pre =
-        read_balance(&s, &staking_token, &address::POS).unwrap();
-    let gov_balance_pre =
-        read_balance(&s, &staking_token, &address::GOV).unwrap();
+    // Every consensus validator votes, but only the last one contributed
+    // vote extensions
+    let votes = consensus_set
+        .iter()
+        .map(|validator| VoteInfo {
+            validator_address: validator.address.clone(),
+            validator_vp: into_tm_voting_power(
+                params.tm_votes_per_token,
+                validator.bonded_stake,
+            ) as u64,
+        })
+        .collect::<Vec<_>>();
+    let vext_signer = consensus_set.last().unwrap();
+    let vext_signers = HashSet::from([vext_signer.address.clone()]);
+    crate::log_block_rewards(
+        &mut s,
+        current_epoch,
+        &proposer_address,
+        votes,
+        &vext_signers,
+        &ConsensusValidatorSetCache::default(),
+    )
+    .unwrap();
 
-    let num_consensus_validators = consensus_set.len() as u64;
-    let accum_val = Dec::one() / num_consensus_validators;
-    let num_blocks_in_last_epoch = 1000;
+    let coeffs = PosRewardsCalculator {
+        proposer_reward: params.block_proposer_reward,
+        signer_reward: params.block_vote_reward,
+        vext_reward: params.block_vext_reward,
+        signing_stake: total_stake,
+        vext_stake: vext_signer.bonded_stake,
+        total_stake,
+    }
+    .get_reward_coeffs()
+    .unwrap();
+    assert_eq!(coeffs.vext_coeff, params.block_vext_reward);
 
-    // Assign some reward accumulator values to consensus validator
+    // The only vote extensions contributor gets the whole vote extensions
+    // reward on top of its other rewards
+    let rewards_handle = crate::rewards_accumulator_handle();
     for validator in &consensus_set {
-        crate::rewards_accumulator_handle()
-            .insert(
-                &mut s,
-                validator.clone(),
-                accum_val * num_blocks_in_last_epoch,
+        let stake_frac =
+            Dec::from(validator.bonded_stake) / Dec::from(total_stake);
+        let mut expected = coeffs.signer_coeff * stake_frac
+            + coeffs.active_val_coeff * stake_frac;
+        if validator.address == proposer_address {
+            expected += coeffs.proposer_coeff;
+        }
+        if validator.address == vext_signer.address {
+            expected += coeffs.vext_coeff;
+        }
+        assert_eq!(
+            rewards_handle.get(&s, &validator.address).unwrap(),
+            Some(expected)
+        );
+    }
+}
+
+/// Test that the consensus set reward fractions with a concentration penalty
+/// conserve the reward, i.e. that they add up to one, and that they only move
+/// rewards from the validators above the threshold to the ones below it
+fn test_concentration_penalty_aux(stakes: Vec<u64>, threshold: Dec, rate: Dec) {
+    let params = OwnedPosParams {
+        concentration_penalty_threshold: threshold,
+        concentration_penalty_rate: rate,
+        ..Default::default()
+    };
+    let params = PosParams {
+        owned: params,
+        max_proposal_period: 100,
+    };
+    let validators: Vec<(Address, token::Amount)> = stakes
+        .into_iter()
+        .enumerate()
+        .map(|(ix, stake)| {
+            (
+                address_from_simple_seed(ix as u64),
+                token::Amount::native_whole(stake),
             )
-            .unwrap();
+        })
+        .collect();
+    let total_stake = validators
+        .iter()
+        .fold(token::Amount::zero(), |acc, (_, stake)| acc + *stake);
+    let fracs =
+        consensus_reward_fractions(&params, validators.clone(), total_stake);
+
+    if total_stake.is_zero() {
+        assert!(fracs.is_empty());
+        return;
+    }
+    // Allow for the rounding of the `Dec` divisions
+    let tolerance = Dec::new(1, 9).unwrap();
+    let sum = fracs.values().fold(Dec::zero(), |acc, frac| acc + *frac);
+    assert!(
+        sum.abs_diff(&Dec::one()) <= tolerance,
+        "The reward fractions must add up to one, got {sum}"
+    );
+    for (address, stake) in validators {
+        let frac = fracs.get(&address).copied();
+        if stake.is_zero() {
+            assert!(frac.is_none());
+            continue;
+        }
+        let frac = frac.unwrap();
+        assert!(!frac.is_negative());
+        let share = Dec::from(stake) / Dec::from(total_stake);
+        if share > threshold {
+            assert!(frac <= share);
+            assert!(frac >= threshold.min(share));
+        } else {
+            assert!(frac >= share);
+        }
     }
+}
 
-    // Distribute inflation into rewards
-    let last_epoch = current_epoch.prev();
-    let inflation = token::Amount::native_whole(10_000_000);
-    crate::update_rewards_products_and_mint_inflation(
-        &mut s,
+/// Test the consensus set reward fractions with a concentration penalty on a
+/// concrete example
+#[test]
+fn test_concentration_penalty_example() {
+    let params = PosParams {
+        owned: OwnedPosParams {
+            concentration_penalty_threshold: Dec::new(5, 1).unwrap(),
+            concentration_penalty_rate: Dec::new(5, 1).unwrap(),
+            ..Default::default()
+        },
+        max_proposal_period: 100,
+    };
+    let [v1, v2, v3] = [0, 1, 2].map(address_from_simple_seed);
+    let validators = [
+        (v1.clone(), token::Amount::native_whole(60)),
+        (v2.clone(), token::Amount::native_whole(30)),
+        (v3.clone(), token::Amount::native_whole(10)),
+    ];
+    let fracs = consensus_reward_fractions(
         &params,
-        last_epoch,
-        num_blocks_in_last_epoch,
-        inflation,
-        &staking_token,
+        validators.clone(),
+        token::Amount::native_whole(100),
+    );
+    // The 0.1 share of `v1` above the threshold is penalized at half, so 0.05
+    // is redistributed to `v2` and `v3` with 3:1 ratio
+    assert_eq!(fracs[&v1], Dec::new(55, 2).unwrap());
+    assert_eq!(fracs[&v2], Dec::new(3375, 4).unwrap());
+    assert_eq!(fracs[&v3], Dec::new(1125, 4).unwrap());
+
+    // Without the penalty, the fractions are the validators' shares of stake
+    let fracs = consensus_reward_fractions(
+        &PosParams::default(),
+        validators,
+        token::Amount::native_whole(100),
+    );
+    assert_eq!(fracs[&v1], Dec::new(6, 1).unwrap());
+    assert_eq!(fracs[&v2], Dec::new(3, 1).unwrap());
+    assert_eq!(fracs[&v3], Dec::new(1, 1).unwrap());
+}
+
+fn test_simulate_bond_action_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
     )
     .unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
 
-    let pos_balance_post =
-        read_balance(&s, &staking_token, &address::POS).unwrap();
-    let gov_balance_post =
-        read_balance(&s, &staking_token, &address::GOV).unwrap();
+    // Fund a delegator and commit, so that the simulation sees the balance
+    let delegator = established_address_1();
+    let staking_token = staking_token_address(&storage);
+    let amount = token::Amount::native_whole(100);
+    credit_tokens(&mut storage, &staking_token, &delegator, amount).unwrap();
+    storage.commit_block().unwrap();
+
+    let validator = &validators[0];
+    let stake_pre = read_validator_stake(
+        &storage,
+        &params,
+        &validator.address,
+        pipeline_epoch,
+    )
+    .unwrap();
+    assert_eq!(stake_pre, validator.tokens);
 
+    // Simulate a delegation
+    let action = BondAction::Bond {
+        source: Some(delegator.clone()),
+        validator: validator.address.clone(),
+        amount,
+    };
+    let mut temp_storage = TempWlStorage::new(&storage.storage);
+    let simulation =
+        simulate_bond_action(&mut temp_storage, &action, current_epoch)
+            .unwrap();
+    assert_eq!(simulation.pipeline_epoch, pipeline_epoch);
+    assert!(simulation.result_slashing.is_none());
     assert_eq!(
-        pos_balance_pre + gov_balance_pre + inflation,
-        pos_balance_post + gov_balance_post,
-        "Expected inflation to be minted to PoS and left-over amount to Gov"
+        simulation.stake_changes,
+        BTreeMap::from([(
+            validator.address.clone(),
+            (stake_pre, stake_pre + amount)
+        )])
     );
+    assert!(simulation.state_transitions.is_empty());
 
-    let pos_credit = pos_balance_post - pos_balance_pre;
-    let gov_credit = gov_balance_post - gov_balance_pre;
-    assert!(
-        pos_credit > gov_credit,
-        "PoS must receive more tokens than Gov, but got {} in PoS and {} in \
-         Gov",
-        pos_credit.to_string_native(),
-        gov_credit.to_string_native()
+    // Nothing has been applied to the actual state
+    assert_eq!(
+        read_validator_stake(
+            &storage,
+            &params,
+            &validator.address,
+            pipeline_epoch
+        )
+        .unwrap(),
+        stake_pre
+    );
+    assert_eq!(
+        read_balance(&storage, &staking_token, &delegator).unwrap(),
+        amount
     );
 
-    // Rewards accumulator must be cleared out
-    let rewards_handle = crate::rewards_accumulator_handle();
-    assert!(rewards_handle.is_empty(&s).unwrap());
-}
-
-fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
-    let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
+    // Simulate unbonding the whole self-bond, which takes the validator out
+    // of the consensus set
+    let action = BondAction::Unbond {
+        source: None,
+        validator: validator.address.clone(),
+        amount: validator.tokens,
     };
+    let mut temp_storage = TempWlStorage::new(&storage.storage);
+    let simulation =
+        simulate_bond_action(&mut temp_storage, &action, current_epoch)
+            .unwrap();
+    let result_slashing = simulation.result_slashing.unwrap();
+    assert_eq!(result_slashing.sum, validator.tokens);
+    assert_eq!(
+        simulation.stake_changes,
+        BTreeMap::from([(
+            validator.address.clone(),
+            (stake_pre, token::Amount::zero())
+        )])
+    );
+    assert_eq!(
+        simulation.state_transitions,
+        vec![ValidatorStateTransition {
+            validator: validator.address.clone(),
+            from: Some(ValidatorState::Consensus),
+            to: Some(ValidatorState::BelowThreshold),
+        }]
+    );
 
-    let init_tot_stake = validators
-        .clone()
-        .into_iter()
-        .fold(token::Amount::zero(), |acc, v| acc + v.tokens);
-    let val1_init_stake = validators[0].tokens;
+    // Simulating an unbond of more than is bonded fails
+    let action = BondAction::Unbond {
+        source: Some(delegator),
+        validator: validator.address.clone(),
+        amount,
+    };
+    let mut temp_storage = TempWlStorage::new(&storage.storage);
+    assert!(
+        simulate_bond_action(&mut temp_storage, &action, current_epoch)
+            .is_err()
+    );
+}
 
-    let mut validators = validators;
-    validators[0].tokens = (init_tot_stake - val1_init_stake) / 30;
+fn test_scheduled_withdrawal_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
@@ -6411,382 +9817,615 @@ fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
     .unwrap();
     storage.commit_block().unwrap();
 
-    let validator1 = validators[0].address.clone();
-    let validator2 = validators[1].address.clone();
-
-    // Get a delegator with some tokens
+    // Delegate and wait for the bond to become active
+    let validator = validators[0].address.clone();
+    let delegator = established_address_1();
     let staking_token = staking_token_address(&storage);
-    let delegator = address::testing::gen_implicit_address();
-    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
-    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
-        .unwrap();
-
-    // Bond to validator 1
-    super::bond_tokens(
+    let amount = token::Amount::native_whole(100);
+    credit_tokens(&mut storage, &staking_token, &delegator, amount).unwrap();
+    bond_tokens(
         &mut storage,
         Some(&delegator),
-        &validator1,
-        10_000.into(),
+        &validator,
+        amount,
         current_epoch,
         None,
     )
     .unwrap();
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
 
-    // Unbond some from validator 1
-    super::unbond_tokens(
+    // Unbond with an automatic withdrawal
+    unbond_tokens(
         &mut storage,
         Some(&delegator),
-        &validator1,
-        1_342.into(),
+        &validator,
+        amount,
         current_epoch,
         false,
     )
     .unwrap();
-
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
-        current_epoch,
-        1_875.into(),
-    )
-    .unwrap();
-
-    // Unbond some from validator 2
-    super::unbond_tokens(
+    let withdrawable_epoch = schedule_withdrawal(
         &mut storage,
         Some(&delegator),
-        &validator2,
-        584.into(),
+        &validator,
         current_epoch,
-        false,
     )
     .unwrap();
+    assert_eq!(
+        withdrawable_epoch,
+        current_epoch + params.withdrawable_epoch_offset()
+    );
+    assert_eq!(
+        read_balance(&storage, &staking_token, &delegator).unwrap(),
+        token::Amount::zero()
+    );
 
-    // Advance an epoch to 1
+    // Nothing is withdrawn before the unbond matures
+    while current_epoch < withdrawable_epoch - 1 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_scheduled_actions(&mut storage, current_epoch).unwrap();
+        assert_eq!(
+            read_balance(&storage, &staking_token, &delegator).unwrap(),
+            token::Amount::zero()
+        );
+    }
+    assert!(
+        !unbond_handle(&delegator, &validator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+
+    // The unbond is withdrawn automatically in the withdrawable epoch and the
+    // executed action is removed from the queue
     current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    assert_eq!(current_epoch, withdrawable_epoch);
+    process_scheduled_actions(&mut storage, current_epoch).unwrap();
+    assert_eq!(
+        read_balance(&storage, &staking_token, &delegator).unwrap(),
+        amount
+    );
+    assert!(
+        unbond_handle(&delegator, &validator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+    assert!(
+        scheduled_actions_handle()
+            .at(&withdrawable_epoch)
+            .is_empty(&storage)
+            .unwrap()
+    );
 
-    // Bond to validator 1
-    super::bond_tokens(
+    // A scheduled withdrawal of unbonds that have already been withdrawn is
+    // skipped
+    let self_bond = validators[0].tokens;
+    unbond_tokens(
         &mut storage,
-        Some(&delegator),
-        &validator1,
-        384.into(),
-        current_epoch,
         None,
-    )
-    .unwrap();
-
-    // Unbond some from validator 1
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator1,
-        144.into(),
+        &validator,
+        self_bond,
         current_epoch,
         false,
     )
     .unwrap();
+    let withdrawable_epoch =
+        schedule_withdrawal(&mut storage, None, &validator, current_epoch)
+            .unwrap();
+    while current_epoch < withdrawable_epoch {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+    let withdrawn =
+        withdraw_tokens(&mut storage, None, &validator, current_epoch).unwrap();
+    assert_eq!(withdrawn, self_bond);
+    let balance_pre =
+        read_balance(&storage, &staking_token, &validator).unwrap();
+    process_scheduled_actions(&mut storage, current_epoch).unwrap();
+    assert_eq!(
+        read_balance(&storage, &staking_token, &validator).unwrap(),
+        balance_pre
+    );
+}
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
+fn test_commission_history_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
+        params,
+        validators.clone().into_iter(),
         current_epoch,
-        3_448.into(),
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    // Unbond some from validator 2
-    super::unbond_tokens(
+    let validator = &validators[0].address;
+    let initial_rate = validators[0].commission_rate;
+    assert!(
+        read_commission_history(&storage, validator)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Change the rate twice in the same epoch
+    let rate_1 = initial_rate + Dec::new(5, 3).unwrap();
+    let rate_2 = initial_rate + Dec::new(1, 2).unwrap();
+    change_validator_commission_rate(
         &mut storage,
-        Some(&delegator),
-        &validator2,
-        699.into(),
+        validator,
+        rate_1,
         current_epoch,
-        false,
     )
     .unwrap();
-
-    // Advance an epoch to ep 2
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Bond to validator 1
-    super::bond_tokens(
+    change_validator_commission_rate(
         &mut storage,
-        Some(&delegator),
-        &validator1,
-        4_384.into(),
+        validator,
+        rate_2,
         current_epoch,
-        None,
     )
     .unwrap();
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
+    // Neither setting the same rate nor a rejected change is recorded
+    change_validator_commission_rate(
         &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
+        validator,
+        rate_2,
         current_epoch,
-        1_008.into(),
     )
     .unwrap();
+    assert!(
+        change_validator_commission_rate(
+            &mut storage,
+            validator,
+            Dec::one(),
+            current_epoch,
+        )
+        .is_err()
+    );
 
-    // Unbond some from validator 2
-    super::unbond_tokens(
+    // Change the rate in a later epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    change_validator_commission_rate(
         &mut storage,
-        Some(&delegator),
-        &validator2,
-        3_500.into(),
+        validator,
+        initial_rate,
         current_epoch,
-        false,
     )
     .unwrap();
 
-    // Advance two epochs to ep 4
-    for _ in 0..2 {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    assert_eq!(
+        read_commission_history(&storage, validator).unwrap(),
+        vec![
+            CommissionRateChange {
+                epoch: pipeline_epoch.prev(),
+                old_rate: initial_rate,
+                new_rate: rate_1,
+            },
+            CommissionRateChange {
+                epoch: pipeline_epoch.prev(),
+                old_rate: rate_1,
+                new_rate: rate_2,
+            },
+            CommissionRateChange {
+                epoch: pipeline_epoch,
+                old_rate: rate_2,
+                new_rate: initial_rate,
+            },
+        ]
+    );
+
+    // Other validators' history is unaffected
+    for other in &validators[1..] {
+        assert!(
+            read_commission_history(&storage, &other.address)
+                .unwrap()
+                .is_empty()
+        );
     }
+}
 
-    // Find some slashes committed in various epochs
-    super::slash(
+fn test_commission_rate_change_cap_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(1),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        params,
+        validators.clone().into_iter(),
         current_epoch,
     )
     .unwrap();
-    super::slash(
+    storage.commit_block().unwrap();
+
+    let validator = &validators[0].address;
+    let initial_rate = validators[0].commission_rate;
+    let max_change = validators[0].max_commission_rate_change;
+    let half_max_change = max_change / Dec::two();
+    let rate_error = |err: storage_api::Error| {
+        err.downcast::<CommissionRateChangeError>().unwrap()
+    };
+
+    // Each of these changes is within the maximum change, but not their sum
+    change_validator_commission_rate(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(2),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        validator,
+        initial_rate + half_max_change,
         current_epoch,
     )
     .unwrap();
-    super::slash(
+    change_validator_commission_rate(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(2),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        validator,
+        initial_rate,
         current_epoch,
     )
     .unwrap();
-    super::slash(
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    assert_eq!(
+        read_commission_rate_change_in_epoch(
+            &storage,
+            validator,
+            pipeline_epoch
+        )
+        .unwrap(),
+        max_change
+    );
+
+    // The cap is exhausted, so any other change in the same epoch is rejected
+    // and the rate at pipeline is left unchanged
+    let err = change_validator_commission_rate(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(3),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        validator,
+        initial_rate - half_max_change,
         current_epoch,
     )
-    .unwrap();
-
-    // Advance such that these slashes are all processed
-    for _ in 0..params.slash_processing_epoch_offset() {
-        current_epoch = advance_epoch(&mut storage, &params);
-        super::process_slashes(&mut storage, current_epoch).unwrap();
-    }
+    .unwrap_err();
+    assert!(matches!(
+        *rate_error(err),
+        CommissionRateChangeError::RateChangeTooLarge(change, _)
+            if change == max_change + half_max_change
+    ));
+    assert_eq!(
+        validator_commission_rate_handle(validator)
+            .get(&storage, pipeline_epoch, &params)
+            .unwrap(),
+        Some(initial_rate)
+    );
 
+    // The cap is available again in the next epoch
+    current_epoch = advance_epoch(&mut storage, &params);
     let pipeline_epoch = current_epoch + params.pipeline_len;
-
-    let del_bond_amount = crate::bond_amount(
-        &storage,
-        &BondId {
-            source: delegator.clone(),
-            validator: validator1.clone(),
-        },
-        pipeline_epoch,
-    )
-    .unwrap_or_default();
-
-    let self_bond_amount = crate::bond_amount(
-        &storage,
-        &BondId {
-            source: validator1.clone(),
-            validator: validator1.clone(),
-        },
-        pipeline_epoch,
-    )
-    .unwrap_or_default();
-
-    let val_stake = crate::read_validator_stake(
-        &storage,
-        &params,
-        &validator1,
-        pipeline_epoch,
+    assert_eq!(
+        read_commission_rate_change_in_epoch(
+            &storage,
+            validator,
+            pipeline_epoch
+        )
+        .unwrap(),
+        Dec::zero()
+    );
+    change_validator_commission_rate(
+        &mut storage,
+        validator,
+        initial_rate - max_change,
+        current_epoch,
     )
     .unwrap();
-    // dbg!(&val_stake);
-    // dbg!(&del_bond_amount);
-    // dbg!(&self_bond_amount);
-
-    let diff = val_stake - self_bond_amount - del_bond_amount;
-    assert!(diff <= 2.into());
+    assert!(
+        change_validator_commission_rate(
+            &mut storage,
+            validator,
+            initial_rate - max_change - half_max_change,
+            current_epoch,
+        )
+        .is_err()
+    );
+    assert_eq!(
+        validator_commission_rate_handle(validator)
+            .get(&storage, pipeline_epoch, &params)
+            .unwrap(),
+        Some(initial_rate - max_change)
+    );
 }
 
-fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
-    assert_eq!(validators.len(), 1);
-
+fn test_chunked_slash_processing_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    // Process a single slashed validator per block
     let params = OwnedPosParams {
-        unbonding_len: 4,
+        max_slashed_validators_per_block: 1,
         ..Default::default()
     };
-    let validator = validators[0].address.clone();
-
-    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
-    let mut storage = TestWlStorage::default();
 
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
     let params = test_init_genesis(
         &mut storage,
         params,
-        validators.into_iter(),
+        validators.clone().into_iter(),
         current_epoch,
     )
     .unwrap();
     storage.commit_block().unwrap();
 
-    // Check that there is one consensus key in the network
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 1);
-    let ck = consensus_keys.first().cloned().unwrap();
-    let og_ck = validator_consensus_key_handle(&validator)
-        .get(&storage, current_epoch, &params)
-        .unwrap()
-        .unwrap();
-    assert_eq!(ck, og_ck);
-
-    // Attempt to change to a new secp256k1 consensus key (disallowed)
-    let secp_ck = gen_keypair::<key::secp256k1::SigScheme>();
-    let secp_ck = key::common::SecretKey::Secp256k1(secp_ck).ref_to();
-    let res =
-        change_consensus_key(&mut storage, &validator, &secp_ck, current_epoch);
-    assert!(res.is_err());
-
-    // Change consensus keys
-    let ck_2 = common_sk_from_simple_seed(1).ref_to();
-    change_consensus_key(&mut storage, &validator, &ck_2, current_epoch)
+    // Delegate to one of the validators that get slashed
+    let delegator = address::testing::gen_implicit_address();
+    let amount_del = token::Amount::native_whole(1);
+    let staking_token = staking_token_address(&storage);
+    credit_tokens(&mut storage, &staking_token, &delegator, amount_del)
         .unwrap();
-
-    // Check that there is a new consensus key
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 2);
-
-    for epoch in current_epoch.iter_range(params.pipeline_len) {
-        let ck = validator_consensus_key_handle(&validator)
-            .get(&storage, epoch, &params)
-            .unwrap()
-            .unwrap();
-        assert_eq!(ck, og_ck);
-    }
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-    let ck = validator_consensus_key_handle(&validator)
-        .get(&storage, pipeline_epoch, &params)
-        .unwrap()
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validators[0].address,
+        amount_del,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Slash two validators for an infraction in the same epoch
+    let slashed = [&validators[0].address, &validators[1].address];
+    let evidence_epoch = current_epoch;
+    for validator in slashed {
+        slash(
+            &mut storage,
+            &params,
+            current_epoch,
+            evidence_epoch,
+            0_u64,
+            SlashType::DuplicateVote,
+            validator,
+            current_epoch.next(),
+        )
         .unwrap();
-    assert_eq!(ck, ck_2);
+    }
 
-    // Advance to the pipeline epoch
-    loop {
+    // Advance to the epoch in which the slashes are processed
+    let processing_epoch =
+        evidence_epoch + params.slash_processing_epoch_offset();
+    while current_epoch < processing_epoch {
         current_epoch = advance_epoch(&mut storage, &params);
-        if current_epoch == pipeline_epoch {
-            break;
+        let status =
+            process_slashes_chunk(&mut storage, current_epoch, true).unwrap();
+        if current_epoch < processing_epoch {
+            assert_eq!(status, SlashProcessingStatus::Idle);
+        } else {
+            assert_eq!(status, SlashProcessingStatus::InProgress);
         }
     }
+    let stakes_pre = slashed.map(|validator| {
+        read_validator_stake(&storage, &params, validator, current_epoch)
+            .unwrap()
+    });
+    // The inflation of the last epoch is applied in the first block of the
+    // epoch, while the slashes are still being processed, from the stakes in
+    // the last epoch
+    let last_epoch = current_epoch.prev();
+    let last_stakes_pre = slashed.map(|validator| {
+        read_validator_stake(&storage, &params, validator, last_epoch).unwrap()
+    });
+    let last_total_stake_pre =
+        read_total_stake(&storage, &params, last_epoch).unwrap();
 
-    // Check the consensus keys again
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 2);
+    // Only one of the validators has been processed in the first block, so
+    // nothing is applied yet
+    assert!(is_slash_processing_in_progress(&storage).unwrap());
+    for validator in slashed {
+        assert!(
+            validator_slashes_handle(validator)
+                .is_empty(&storage)
+                .unwrap()
+        );
+    }
 
-    for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
-        let ck = validator_consensus_key_handle(&validator)
-            .get(&storage, epoch, &params)
-            .unwrap()
-            .unwrap();
-        assert_eq!(ck, ck_2);
+    // The slashed validators are frozen until the slashes are applied, so the
+    // delegation can neither be unbonded nor redelegated
+    for validator in slashed {
+        assert!(
+            is_validator_frozen(&storage, validator, current_epoch, &params)
+                .unwrap()
+        );
     }
+    let err = unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validators[0].address,
+        amount_del,
+        current_epoch,
+        false,
+    )
+    .unwrap_err();
+    assert_matches!(
+        err.downcast::<UnbondError>().unwrap().deref(),
+        UnbondError::ValidatorIsFrozen(validator)
+            if validator == &validators[0].address
+    );
+    let err = redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validators[0].address,
+        &validators[1].address,
+        current_epoch,
+        amount_del,
+    )
+    .unwrap_err();
+    assert_matches!(
+        err.downcast::<UnbondError>().unwrap().deref(),
+        UnbondError::ValidatorIsFrozen(validator)
+            if validator == &validators[0].address
+    );
 
-    // Now change the consensus key again and bond in the same epoch
-    let ck_3 = common_sk_from_simple_seed(3).ref_to();
-    change_consensus_key(&mut storage, &validator, &ck_3, current_epoch)
-        .unwrap();
+    // The other validator is processed in the next block and the slashes are
+    // applied
+    let status =
+        process_slashes_chunk(&mut storage, current_epoch, false).unwrap();
+    assert_eq!(status, SlashProcessingStatus::Completed);
+    assert!(!is_slash_processing_in_progress(&storage).unwrap());
+    for (validator, stake_pre) in slashed.into_iter().zip(stakes_pre) {
+        assert_eq!(
+            validator_slashes_handle(validator).len(&storage).unwrap(),
+            1
+        );
+        let stake =
+            read_validator_stake(&storage, &params, validator, current_epoch)
+                .unwrap();
+        assert!(stake < stake_pre);
+    }
+    // The slashes don't change the stakes in the last epoch, so the inflation
+    // would have been the same if it was applied after the slashes
+    for (validator, last_stake_pre) in slashed.into_iter().zip(last_stakes_pre)
+    {
+        assert_eq!(
+            read_validator_stake(&storage, &params, validator, last_epoch)
+                .unwrap(),
+            last_stake_pre
+        );
+    }
+    assert_eq!(
+        read_total_stake(&storage, &params, last_epoch).unwrap(),
+        last_total_stake_pre
+    );
 
-    let staking_token = storage.storage.native_token.clone();
-    let amount_del = token::Amount::native_whole(5);
-    credit_tokens(&mut storage, &staking_token, &validator, amount_del)
-        .unwrap();
-    bond_tokens(
+    // Once the slashes are applied, the delegation can be unbonded again
+    for validator in slashed {
+        assert!(
+            !is_validator_frozen(&storage, validator, current_epoch, &params)
+                .unwrap()
+        );
+    }
+    unbond_tokens(
         &mut storage,
-        None,
-        &validator,
-        token::Amount::native_whole(1),
+        Some(&delegator),
+        &validators[0].address,
+        amount_del,
         current_epoch,
-        None,
+        false,
     )
     .unwrap();
 
-    // Check consensus keys again
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 3);
+    // There's nothing left to process in the following blocks
+    let status =
+        process_slashes_chunk(&mut storage, current_epoch, false).unwrap();
+    assert_eq!(status, SlashProcessingStatus::Idle);
+}
 
-    for epoch in current_epoch.iter_range(params.pipeline_len) {
-        let ck = validator_consensus_key_handle(&validator)
-            .get(&storage, epoch, &params)
-            .unwrap()
+#[test]
+fn test_iter_slashes() {
+    let mut storage = TestWlStorage::default();
+    let validator1 = established_address_1();
+    let validator2 = established_address_2();
+    let slash_at = |epoch: u64| Slash {
+        epoch: Epoch(epoch),
+        block_height: 0,
+        r#type: SlashType::DuplicateVote,
+        rate: Dec::new(1, 2).unwrap(),
+    };
+
+    // Processed slashes
+    let processed = [
+        (validator1.clone(), slash_at(1)),
+        (validator1.clone(), slash_at(4)),
+        (validator2.clone(), slash_at(2)),
+    ];
+    for (validator, slash) in &processed {
+        validator_slashes_handle(validator)
+            .push(&mut storage, slash.clone())
             .unwrap();
-        assert_eq!(ck, ck_2);
     }
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-    let ck = validator_consensus_key_handle(&validator)
-        .get(&storage, pipeline_epoch, &params)
-        .unwrap()
-        .unwrap();
-    assert_eq!(ck, ck_3);
-
-    // Advance to the pipeline epoch to ensure that the validator set updates to
-    // tendermint will work
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        if current_epoch == pipeline_epoch {
-            break;
-        }
+    // Enqueued slashes, one of which has a processing epoch that's not after
+    // the current epoch and is therefore skipped
+    let current_epoch = Epoch(5);
+    for (processing_epoch, slash_epoch) in [(5, 3), (7, 5), (8, 6)] {
+        enqueued_slashes_handle()
+            .get_data_handler()
+            .at(&Epoch(processing_epoch))
+            .at(&validator2)
+            .push(&mut storage, slash_at(slash_epoch))
+            .unwrap();
     }
-    assert_eq!(current_epoch.0, 2 * params.pipeline_len);
-}
 
-fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
-    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let collect = |filter: SlashFilter| {
+        iter_slashes(&storage, filter, current_epoch)
+            .unwrap()
+            .collect::<storage_api::Result<Vec<SlashRecord>>>()
+            .unwrap()
+    };
 
-    let validator1 = validators[0].address.clone();
-    let validator2 = validators[1].address.clone();
+    // Without a filter, the processed slashes come first
+    let all = collect(SlashFilter::default());
+    assert_eq!(all.len(), 5);
+    assert!(
+        all[..3]
+            .iter()
+            .all(|record| record.status == SlashStatus::Processed
+                && record.processing_epoch.is_none())
+    );
+    assert_eq!(
+        all[3..]
+            .iter()
+            .map(|record| (record.status, record.processing_epoch))
+            .collect::<Vec<_>>(),
+        vec![
+            (SlashStatus::Enqueued, Some(Epoch(7))),
+            (SlashStatus::Enqueued, Some(Epoch(8)))
+        ]
+    );
 
-    let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        unbonding_len: 4,
+    // Filter by validator
+    let records = collect(SlashFilter {
+        validator: Some(validator1.clone()),
         ..Default::default()
-    };
+    });
+    assert_eq!(
+        records
+            .into_iter()
+            .map(|record| (record.validator, record.slash))
+            .collect::<Vec<_>>(),
+        processed[..2].to_vec()
+    );
+
+    // Filter by infraction epoch range
+    let records = collect(SlashFilter {
+        from_epoch: Some(Epoch(2)),
+        to_epoch: Some(Epoch(5)),
+        ..Default::default()
+    });
+    let epochs = records
+        .iter()
+        .map(|record| record.slash.epoch)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(epochs, BTreeSet::from([Epoch(2), Epoch(4), Epoch(5)]));
+
+    // Filter by status
+    let records = collect(SlashFilter {
+        validator: Some(validator2.clone()),
+        status: Some(SlashStatus::Enqueued),
+        ..Default::default()
+    });
+    assert_eq!(records.len(), 2);
+    let records = collect(SlashFilter {
+        validator: Some(validator2.clone()),
+        status: Some(SlashStatus::Processed),
+        ..Default::default()
+    });
+    assert_eq!(records.len(), 1);
+
+    // All the processed slashes are found by validator
+    let all_slashes = find_all_slashes(&storage).unwrap();
+    assert_eq!(all_slashes[&validator1].len(), 2);
+    assert_eq!(all_slashes[&validator2].len(), 1);
+}
+
+fn test_vesting_account_delegation_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
@@ -6799,85 +10438,86 @@ fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
     .unwrap();
     storage.commit_block().unwrap();
 
-    // Get delegators with some tokens
+    // Give the delegator a vesting account whose tokens are locked for longer
+    // than it takes to bond, unbond and withdraw
+    let validator = validators[0].address.clone();
+    let funder = established_address_1();
+    let delegator = established_address_2();
     let staking_token = staking_token_address(&storage);
-    let delegator1 = address::testing::gen_implicit_address();
-    let delegator2 = address::testing::gen_implicit_address();
-    let del_balance = token::Amount::native_whole(1000);
-    credit_tokens(&mut storage, &staking_token, &delegator1, del_balance)
-        .unwrap();
-    credit_tokens(&mut storage, &staking_token, &delegator2, del_balance)
-        .unwrap();
-
-    // Advance to epoch 1
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    let amount = token::Amount::native_whole(100);
+    credit_tokens(&mut storage, &staking_token, &funder, amount).unwrap();
+    let schedule = VestingSchedule {
+        tranches: BTreeMap::from([(Epoch(1000), amount)]),
+    };
+    vesting::init_vesting(&mut storage, &funder, &delegator, schedule).unwrap();
 
-    // Delegate in epoch 1 to validator1
-    let del1_epoch = current_epoch;
-    super::bond_tokens(
+    // The locked tokens are bonded from the vesting address
+    let bond_amount = token::Amount::native_whole(60);
+    bond_tokens(
         &mut storage,
-        Some(&delegator1),
-        &validator1,
-        1000.into(),
+        Some(&delegator),
+        &validator,
+        bond_amount,
         current_epoch,
         None,
     )
     .unwrap();
+    assert_eq!(
+        vesting::read_locked_amount(&storage, &delegator).unwrap(),
+        amount - bond_amount
+    );
+    assert_eq!(
+        read_balance(&storage, &staking_token, &delegator).unwrap(),
+        token::Amount::zero()
+    );
+    // More than the held tokens cannot be bonded
+    assert!(
+        bond_tokens(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            amount,
+            current_epoch,
+            None,
+        )
+        .is_err()
+    );
 
-    // Advance to epoch 2
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Delegate in epoch 2 to validator2
-    let del2_epoch = current_epoch;
-    super::bond_tokens(
+    // The withdrawn tokens are returned to the vesting address
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+    }
+    unbond_tokens(
         &mut storage,
-        Some(&delegator2),
-        &validator2,
-        1000.into(),
+        Some(&delegator),
+        &validator,
+        bond_amount,
         current_epoch,
-        None,
+        false,
     )
     .unwrap();
-
-    // Checks
-    assert!(super::is_validator(&storage, &validator1).unwrap());
-    assert!(super::is_validator(&storage, &validator2).unwrap());
-    assert!(!super::is_delegator(&storage, &validator1, None).unwrap());
-    assert!(!super::is_delegator(&storage, &validator2, None).unwrap());
-
-    assert!(!super::is_validator(&storage, &delegator1).unwrap());
-    assert!(!super::is_validator(&storage, &delegator2).unwrap());
-    assert!(super::is_delegator(&storage, &delegator1, None).unwrap());
-    assert!(super::is_delegator(&storage, &delegator2, None).unwrap());
-
-    for epoch in Epoch::default().iter_range(del1_epoch.0 + params.pipeline_len)
-    {
-        assert!(
-            !super::is_delegator(&storage, &delegator1, Some(epoch)).unwrap()
-        );
+    for _ in 0..params.withdrawable_epoch_offset() {
+        current_epoch = advance_epoch(&mut storage, &params);
     }
-    assert!(
-        super::is_delegator(
-            &storage,
-            &delegator1,
-            Some(del1_epoch + params.pipeline_len)
-        )
-        .unwrap()
+    let withdrawn = withdraw_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(withdrawn, bond_amount);
+    assert_eq!(
+        vesting::read_locked_amount(&storage, &delegator).unwrap(),
+        amount
+    );
+    assert_eq!(
+        read_balance(&storage, &staking_token, &delegator).unwrap(),
+        token::Amount::zero()
     );
-    for epoch in Epoch::default().iter_range(del2_epoch.0 + params.pipeline_len)
-    {
-        assert!(
-            !super::is_delegator(&storage, &delegator2, Some(epoch)).unwrap()
-        );
-    }
     assert!(
-        super::is_delegator(
-            &storage,
-            &delegator2,
-            Some(del2_epoch + params.pipeline_len)
-        )
-        .unwrap()
+        vesting::claimable_amount(&storage, &delegator, current_epoch)
+            .unwrap()
+            .is_zero()
     );
 }
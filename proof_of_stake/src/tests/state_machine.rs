@@ -2907,11 +2907,11 @@ impl ReferenceStateMachine for AbstractPosState {
                     state.is_validator(address, *infraction_epoch);
 
                 // The infraction epoch cannot be in the future or more than
-                // unbonding_len epochs in the past
+                // evidence_max_age_epochs epochs in the past
                 let current_epoch = state.epoch;
                 let valid_epoch = *infraction_epoch <= current_epoch
                     && current_epoch.0 - infraction_epoch.0
-                        <= state.params.unbonding_len;
+                        <= state.params.evidence_max_age_epochs;
 
                 // Only misbehave when there is more than 3 validators that's
                 // not jailed or about to be slashed, so there's always at least
@@ -5207,7 +5207,7 @@ fn arb_slash(state: &AbstractPosState) -> impl Strategy<Value = Transition> {
         vec![SlashType::LightClientAttack, SlashType::DuplicateVote];
     let arb_type = prop::sample::select(slash_types);
     let arb_epoch = (current_epoch
-        .checked_sub(state.params.unbonding_len)
+        .checked_sub(state.params.evidence_max_age_epochs)
         .unwrap_or_default()..=current_epoch)
         .prop_map(Epoch::from);
     (arb_validator, arb_type, arb_epoch).prop_map(
@@ -43,8 +43,8 @@ use crate::{
     consensus_validator_set_handle, delegator_redelegated_bonds_handle,
     enqueued_slashes_handle, find_slashes_in_range,
     read_below_threshold_validator_set_addresses, read_pos_params,
-    redelegate_tokens, validator_deltas_handle, validator_slashes_handle,
-    validator_state_handle, RedelegationError,
+    redelegate_tokens, total_deltas_handle, validator_deltas_handle,
+    validator_slashes_handle, validator_state_handle, RedelegationError,
 };
 
 prop_state_machine! {
@@ -58,6 +58,17 @@ prop_state_machine! {
     fn pos_state_machine_test_v2(sequential 1000 => ConcretePosState);
 }
 
+prop_state_machine! {
+    #![proptest_config(Config {
+        cases: 2,
+        .. Config::default()
+    })]
+    #[test]
+    /// A shorter run of [`pos_state_machine_test_v2`] to check the invariants
+    /// of redelegation chains on every test run
+    fn pos_state_machine_test_v2_short(sequential 200 => ConcretePosState);
+}
+
 /// Abstract representation of a state of PoS system
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -1862,6 +1873,10 @@ struct ConcretePosState {
     /// Last reference state in debug format to print changes after transitions
     #[derivative(Debug = "ignore")]
     last_state_diff: DbgPrintDiff<AbstractPosState>,
+    /// The sum of all the tokens ever bonded, including genesis bonds
+    total_bonded: token::Amount,
+    /// The sum of all the tokens ever withdrawn
+    total_withdrawn: token::Amount,
 }
 
 /// State machine transitions
@@ -1937,7 +1952,16 @@ impl StateMachineTest for ConcretePosState {
         )
         .unwrap();
         let last_state_diff = DbgPrintDiff::new().store(initial_state);
-        Self { s, last_state_diff }
+        let total_bonded = initial_state
+            .genesis_validators
+            .iter()
+            .fold(token::Amount::zero(), |acc, val| acc + val.tokens);
+        Self {
+            s,
+            last_state_diff,
+            total_bonded,
+            total_withdrawn: token::Amount::zero(),
+        }
     }
 
     fn apply(
@@ -2073,6 +2097,7 @@ impl StateMachineTest for ConcretePosState {
                     None,
                 )
                 .unwrap();
+                state.total_bonded += amount;
 
                 let params = read_pos_params(&state.s).unwrap();
                 state.check_bond_post_conditions(
@@ -2212,6 +2237,7 @@ impl StateMachineTest for ConcretePosState {
                     current_epoch,
                 )
                 .unwrap();
+                state.total_withdrawn += withdrawn;
 
                 let src_balance_post =
                     token::read_balance(&state.s, &native_token, &source)
@@ -3565,6 +3591,87 @@ impl ConcretePosState {
                 }
             }
         }
+
+        self.check_global_token_invariants(params, current_epoch);
+    }
+
+    /// Check the invariants relating the validator deltas, the total deltas
+    /// and the balance of the PoS account, which must hold after any
+    /// sequence of transitions.
+    fn check_global_token_invariants(
+        &self,
+        params: &PosParams,
+        current_epoch: Epoch,
+    ) {
+        for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
+            let mut sum_validator_deltas = token::Change::zero();
+            for validator in
+                crate::read_all_validator_addresses(&self.s, epoch).unwrap()
+            {
+                let deltas_stake = validator_deltas_handle(&validator)
+                    .get_sum(&self.s, epoch, params)
+                    .unwrap()
+                    .unwrap_or_default();
+                assert!(
+                    !deltas_stake.is_negative(),
+                    "Negative stake {} of validator {validator} in epoch \
+                     {epoch}",
+                    deltas_stake.to_string_native()
+                );
+                sum_validator_deltas += deltas_stake;
+            }
+            let total_deltas = total_deltas_handle()
+                .get_sum(&self.s, epoch, params)
+                .unwrap()
+                .unwrap_or_default();
+            assert_eq!(
+                total_deltas,
+                sum_validator_deltas,
+                "The total deltas {} don't match the sum of validator deltas \
+                 {} in epoch {epoch}",
+                total_deltas.to_string_native(),
+                sum_validator_deltas.to_string_native()
+            );
+        }
+
+        let pos_balance = read_balance(
+            &self.s,
+            &self.s.storage.native_token,
+            &crate::ADDRESS,
+        )
+        .unwrap();
+        // The slashed tokens are not moved out of the PoS account, so its
+        // balance only changes with bonds and withdrawals
+        assert_eq!(
+            pos_balance,
+            self.total_bonded - self.total_withdrawn,
+            "The PoS balance {} doesn't match the bonded {} minus withdrawn \
+             {} tokens",
+            pos_balance.to_string_native(),
+            self.total_bonded.to_string_native(),
+            self.total_withdrawn.to_string_native()
+        );
+        // The PoS account holds at least the bonded tokens remaining after
+        // slashing
+        let total_stake =
+            crate::read_all_validator_addresses(&self.s, current_epoch)
+                .unwrap()
+                .into_iter()
+                .fold(token::Amount::zero(), |acc, validator| {
+                    acc + crate::read_validator_stake(
+                        &self.s,
+                        params,
+                        &validator,
+                        current_epoch,
+                    )
+                    .unwrap()
+                });
+        assert!(
+            total_stake <= pos_balance,
+            "The total stake {} exceeds the PoS balance {}",
+            total_stake.to_string_native(),
+            pos_balance.to_string_native()
+        );
     }
 }
 
@@ -4386,11 +4493,11 @@ impl ReferenceStateMachine for AbstractPosState {
                     state.is_validator(address, *infraction_epoch);
 
                 // The infraction epoch cannot be in the future or more than
-                // unbonding_len epochs in the past
+                // evidence_max_age_epochs epochs in the past
                 let current_epoch = state.epoch;
                 let valid_epoch = *infraction_epoch <= current_epoch
                     && current_epoch.0 - infraction_epoch.0
-                        <= state.params.unbonding_len;
+                        <= state.params.evidence_max_age_epochs;
 
                 // Only misbehave when there is more than 3 validators that's
                 // not jailed, so there's always at least one honest left
@@ -4589,7 +4696,7 @@ fn arb_slash(state: &AbstractPosState) -> impl Strategy<Value = Transition> {
         vec![SlashType::LightClientAttack, SlashType::DuplicateVote];
     let arb_type = prop::sample::select(slash_types);
     let arb_epoch = (current_epoch
-        .checked_sub(state.params.unbonding_len)
+        .checked_sub(state.params.evidence_max_age_epochs)
         .unwrap_or_default()..=current_epoch)
         .prop_map(Epoch::from);
     (arb_validator, arb_type, arb_epoch).prop_map(
@@ -8,6 +8,7 @@ use std::convert::TryFrom;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::ops::Sub;
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::ledger::storage_api::collections::lazy_map::NestedMap;
@@ -16,55 +17,83 @@ use namada_core::ledger::storage_api::collections::{
 };
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
+use namada_core::types::hash::Hash;
 use namada_core::types::key::common;
-use namada_core::types::storage::{Epoch, KeySeg};
+use namada_core::types::storage::{BlockHeight, Epoch, KeySeg};
 use namada_core::types::token;
 use namada_core::types::token::Amount;
 pub use rev_order::ReverseOrdTokenAmount;
 use serde::{Deserialize, Serialize};
 
 use crate::parameters::PosParams;
+use crate::SlashError;
 
 /// Stored positions of validators in validator sets
 pub type ValidatorSetPositions = crate::epoched::NestedEpoched<
     LazyMap<Address, Position>,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetDefaultNumPastEpochs,
+    crate::epoched::KeepLastN<crate::epoched::OffsetValidatorSetsRetentionLen>,
 >;
 
 /// Epoched validator's consensus key.
 pub type ValidatorConsensusKeys = crate::epoched::Epoched<
     common::PublicKey,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetDefaultNumPastEpochs,
+    crate::epoched::KeepLastN<crate::epoched::OffsetDefaultNumPastEpochs>,
 >;
 
 /// Epoched validator's protocol key.
 pub type ValidatorProtocolKeys = crate::epoched::Epoched<
     common::PublicKey,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxProposalPeriodPlus,
+    crate::epoched::KeepLastN<crate::epoched::OffsetMaxProposalPeriodPlus>,
 >;
 
 /// Epoched validator's eth hot key.
 pub type ValidatorEthHotKeys = crate::epoched::Epoched<
     common::PublicKey,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxProposalPeriodPlus,
+    crate::epoched::KeepLastN<crate::epoched::OffsetMaxProposalPeriodPlus>,
 >;
 
 /// Epoched validator's eth cold key.
 pub type ValidatorEthColdKeys = crate::epoched::Epoched<
     common::PublicKey,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxProposalPeriodPlus,
+    crate::epoched::KeepLastN<crate::epoched::OffsetMaxProposalPeriodPlus>,
 >;
 
+/// Consensus keys reserved ahead of becoming a validator. The outer key is the
+/// epoch in which the reservation was made and the value is the address of
+/// the reserving account.
+pub type ConsensusKeyReservations =
+    NestedMap<Epoch, LazyMap<common::PublicKey, Address>>;
+
+/// Pruning strategy of [`ConsensusKeyReservations`], keyed by the reservation
+/// epoch. A reservation expires once it's older than the pipeline length.
+pub type ConsensusKeyReservationsPruning =
+    crate::epoched::KeepLastN<crate::epoched::OffsetPipelineLen>;
+
+/// Actions scheduled to be executed automatically at the beginning of an
+/// epoch, keyed by the epoch in which they are executed.
+pub type ScheduledActions = NestedMap<Epoch, LazyVec<ScheduledAction>>;
+
+/// The validators that have had no stake and no bonds at the epoch
+/// transitions, keyed by address, with the first epoch from which they have
+/// been seen without stake. These validators get deregistered once they have
+/// been without stake for longer than the `validator_deregistration_len`
+/// parameter.
+pub type ZeroStakeValidators = LazyMap<Address, Epoch>;
+
+/// The proposers of the last blocks, in a ring buffer keyed by the block
+/// height modulo [`crate::proposer_history::PROPOSER_HISTORY_LEN`].
+pub type ProposerHistory = LazyMap<u64, ProposerRecord>;
+
 /// Epoched validator's state.
 pub type ValidatorStates = crate::epoched::Epoched<
     ValidatorState,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetDefaultNumPastEpochs,
+    crate::epoched::KeepLastN<crate::epoched::OffsetDefaultNumPastEpochs>,
 >;
 
 /// A map from a position to an address in a Validator Set
@@ -82,56 +111,76 @@ pub type BelowCapacityValidatorSet =
 pub type ConsensusValidatorSets = crate::epoched::NestedEpoched<
     ConsensusValidatorSet,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxProposalPeriodPlus,
+    crate::epoched::KeepLastN<
+        crate::epoched::OffsetConsensusValidatorSetsRetentionLen,
+    >,
 >;
 
 /// Epoched below-capacity validator sets.
 pub type BelowCapacityValidatorSets = crate::epoched::NestedEpoched<
     BelowCapacityValidatorSet,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetDefaultNumPastEpochs,
+    crate::epoched::KeepLastN<crate::epoched::OffsetValidatorSetsRetentionLen>,
+>;
+
+/// Below-threshold validator set, i.e. the validators whose stake is below the
+/// `validator_stake_threshold` parameter
+pub type BelowThresholdValidatorSet = LazySet<Address>;
+
+/// Epoched below-threshold validator sets.
+pub type BelowThresholdValidatorSets = crate::epoched::NestedEpoched<
+    BelowThresholdValidatorSet,
+    crate::epoched::OffsetPipelineLen,
+    crate::epoched::KeepLastN<crate::epoched::OffsetValidatorSetsRetentionLen>,
 >;
 
 /// Epoched total consensus validator set stake
 pub type TotalConsensusStakes = crate::epoched::Epoched<
     Amount,
     crate::epoched::OffsetZero,
-    crate::epoched::OffsetMaxU64,
+    crate::epoched::NeverPrune,
 >;
 
 /// Epoched validator's deltas.
 pub type ValidatorDeltas = crate::epoched::EpochedDelta<
     token::Change,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxProposalPeriodOrSlashProcessingLenPlus,
+    crate::epoched::KeepLastN<
+        crate::epoched::OffsetMaxProposalPeriodOrSlashProcessingLenPlus,
+    >,
 >;
 
 /// Epoched total deltas.
 pub type TotalDeltas = crate::epoched::EpochedDelta<
     token::Change,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxProposalPeriodOrSlashProcessingLenPlus,
+    crate::epoched::KeepLastN<
+        crate::epoched::OffsetMaxProposalPeriodOrSlashProcessingLenPlus,
+    >,
 >;
 
 /// Epoched validator commission rate
 pub type CommissionRates = crate::epoched::Epoched<
     Dec,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetDefaultNumPastEpochs,
+    crate::epoched::KeepLastN<crate::epoched::OffsetDefaultNumPastEpochs>,
 >;
 
+/// Append-only history of a validator's commission rate changes
+pub type CommissionRateHistory = LazyVec<CommissionRateChange>;
+
 /// Epoched validator's bonds
 pub type Bonds = crate::epoched::EpochedDelta<
     token::Amount,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetMaxU64,
+    crate::epoched::NeverPrune,
 >;
 
 /// An epoched lazy set of all known validator addresses
 pub type ValidatorAddresses = crate::epoched::NestedEpoched<
     LazySet<Address>,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetDefaultNumPastEpochs,
+    crate::epoched::KeepLastN<crate::epoched::OffsetDefaultNumPastEpochs>,
 >;
 
 /// Slashes indexed by validator address and then block height (for easier
@@ -145,7 +194,7 @@ pub type ValidatorSlashes = NestedMap<Address, Slashes>;
 pub type EpochedSlashes = crate::epoched::NestedEpoched<
     ValidatorSlashes,
     crate::epoched::OffsetPipelineLen,
-    crate::epoched::OffsetSlashProcessingLenPlus,
+    crate::epoched::KeepLastN<crate::epoched::OffsetSlashProcessingLenPlus>,
 >;
 
 /// Epoched validator's unbonds
@@ -166,10 +215,17 @@ pub type ConsensusKeys = LazySet<common::PublicKey>;
 pub type ValidatorTotalUnbonded =
     NestedMap<Epoch, LazyMap<Epoch, token::Amount>>;
 
+/// Pruning strategy of [`ValidatorTotalUnbonded`], keyed by the unbond epoch
+pub type ValidatorTotalUnbondedPruning = crate::epoched::KeepUntilProcessed;
+
 /// A validator's incoming redelegations, where the key is the bond owner
 /// address and the value is the redelegation end epoch
 pub type IncomingRedelegations = LazyMap<Address, Epoch>;
 
+/// Pruning strategy of [`IncomingRedelegations`], applied to the redelegation
+/// end epoch delayed by the slash processing offset
+pub type IncomingRedelegationsPruning = crate::epoched::KeepUntilProcessed;
+
 /// A validator's outgoing redelegations, where the validator in question is a
 /// source validator.
 ///
@@ -183,6 +239,10 @@ pub type IncomingRedelegations = LazyMap<Address, Epoch>;
 pub type OutgoingRedelegations =
     NestedMap<Address, NestedMap<Epoch, LazyMap<Epoch, token::Amount>>>;
 
+/// Pruning strategy of [`OutgoingRedelegations`], applied to the redelegation
+/// end epoch
+pub type OutgoingRedelegationsPruning = crate::epoched::KeepUntilProcessed;
+
 /// A validator's total redelegated unbonded tokens for any delegator.
 /// The map keys from outside in are:
 ///
@@ -202,6 +262,9 @@ pub type TotalRedelegatedBonded = NestedMap<Epoch, RedelegatedTokens>;
 /// - bond start epoch
 pub type TotalRedelegatedUnbonded = NestedMap<Epoch, RedelegatedBondsOrUnbonds>;
 
+/// Pruning strategy of [`TotalRedelegatedUnbonded`], keyed by the unbond epoch
+pub type TotalRedelegatedUnbondedPruning = crate::epoched::KeepUntilProcessed;
+
 /// Map of redelegated tokens.
 /// The map keys from outside in are:
 ///
@@ -262,6 +325,24 @@ pub type LivenessMissedVotes = NestedMap<Address, LazySet<u64>>;
 /// elements in the correspoding inner LazySet of [`LivenessMissedVotes`].
 pub type LivenessSumMissedVotes = LazyMap<Address, u64>;
 
+/// Liveness reports received from the vote extensions of consensus
+/// validators. Maps each validator reported as offline to the set of
+/// validators who reported it.
+pub type LivenessReports = NestedMap<Address, LazySet<Address>>;
+
+/// The validators that the staking pool spreads its bonds across.
+pub type StakingPoolValidators = LazySet<Address>;
+
+/// The staking pool shares held by the pool's depositors.
+pub type StakingPoolShares = LazyMap<Address, token::Amount>;
+
+/// The pooled unbonds of the staking pool's depositors. The map keys from
+/// outside in are:
+///
+/// - depositor address
+/// - epoch from which the unbonded tokens can be withdrawn
+pub type StakingPoolUnbonds = NestedMap<Address, LazyMap<Epoch, token::Amount>>;
+
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Eq, Hash, PartialEq,
 )]
@@ -282,9 +363,54 @@ pub struct CommissionPair {
     pub max_commission_change_per_epoch: Dec,
 }
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+)]
+/// A change of a validator's commission rate
+pub struct CommissionRateChange {
+    /// The epoch from which the new rate applies
+    pub epoch: Epoch,
+    /// The commission rate before the change
+    pub old_rate: Dec,
+    /// The new commission rate
+    pub new_rate: Dec,
+}
+
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+/// The proposer of a block, recorded in the [`ProposerHistory`]
+pub struct ProposerRecord {
+    /// The height of the block
+    pub height: BlockHeight,
+    /// The address of the validator that proposed the block
+    pub proposer: Address,
+}
+
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+/// All the keys of a validator in some epoch
+pub struct ValidatorKeys {
+    /// Consensus key used to sign block votes
+    pub consensus_key: common::PublicKey,
+    /// Ethereum bridge hot key
+    pub eth_hot_key: common::PublicKey,
+    /// Ethereum bridge cold key
+    pub eth_cold_key: common::PublicKey,
+    /// Protocol key used to sign protocol txs
+    pub protocol_key: common::PublicKey,
+}
+
 /// Epoched rewards products
 pub type RewardsProducts = LazyMap<Epoch, Dec>;
 
+/// PoS inflation amounts minted for the rewards distribution of each epoch
+pub type InflationAmounts = LazyMap<Epoch, token::Amount>;
+
 /// Consensus validator rewards accumulator (for tracking the fractional block
 /// rewards owed over the course of an epoch)
 pub type RewardsAccumulator = LazyMap<Address, Dec>;
@@ -386,6 +512,45 @@ pub enum ValidatorSetUpdate {
     Deactivated(common::PublicKey),
 }
 
+/// A change of a validator in the consensus validator set between two epochs.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub enum ValidatorSetDiff {
+    /// A validator that joined the consensus validator set
+    Added {
+        /// Validator's address
+        address: Address,
+        /// Validator's consensus key in the later epoch
+        consensus_key: common::PublicKey,
+        /// Validator's bonded stake in the later epoch
+        bonded_stake: token::Amount,
+    },
+    /// A validator that stayed in the consensus validator set, but whose
+    /// bonded stake or consensus key has changed
+    Updated {
+        /// Validator's address
+        address: Address,
+        /// Validator's consensus key in the later epoch
+        consensus_key: common::PublicKey,
+        /// Validator's consensus key in the earlier epoch
+        prev_consensus_key: common::PublicKey,
+        /// Validator's bonded stake in the later epoch
+        bonded_stake: token::Amount,
+        /// Validator's bonded stake in the earlier epoch
+        prev_bonded_stake: token::Amount,
+    },
+    /// A validator that left the consensus validator set
+    Removed {
+        /// Validator's address
+        address: Address,
+        /// Validator's consensus key in the earlier epoch
+        prev_consensus_key: common::PublicKey,
+        /// Validator's bonded stake in the earlier epoch
+        prev_bonded_stake: token::Amount,
+    },
+}
+
 /// Consensus validator's consensus key and its bonded stake.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConsensusValidator {
@@ -530,6 +695,85 @@ pub enum ValidatorState {
     Jailed,
 }
 
+impl Display for ValidatorState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidatorState::Consensus => write!(f, "consensus"),
+            ValidatorState::BelowCapacity => write!(f, "below_capacity"),
+            ValidatorState::BelowThreshold => write!(f, "below_threshold"),
+            ValidatorState::Inactive => write!(f, "inactive"),
+            ValidatorState::Jailed => write!(f, "jailed"),
+        }
+    }
+}
+
+/// The cause of a [`ValidatorStateChange`]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+)]
+pub enum ValidatorStateChangeCause {
+    /// The validator has become a validator
+    Registration,
+    /// A change of the stake of the validator, or of another validator that
+    /// has swapped places with it in the validator sets
+    StakeChange,
+    /// A rebalancing of the validator sets, e.g. after a change of the number
+    /// of consensus validator slots
+    Rebalance,
+    /// The validator was jailed for a misbehavior
+    Slash,
+    /// The validator was jailed for missing too many votes
+    Liveness,
+    /// The validator was unjailed
+    Unjail,
+    /// The deactivation of the validator, or of a consensus validator whose
+    /// slot the validator has taken
+    Deactivation,
+    /// The reactivation of the validator
+    Reactivation,
+}
+
+impl Display for ValidatorStateChangeCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Registration => write!(f, "registration"),
+            Self::StakeChange => write!(f, "stake_change"),
+            Self::Rebalance => write!(f, "rebalance"),
+            Self::Slash => write!(f, "slash"),
+            Self::Liveness => write!(f, "liveness"),
+            Self::Unjail => write!(f, "unjail"),
+            Self::Deactivation => write!(f, "deactivation"),
+            Self::Reactivation => write!(f, "reactivation"),
+        }
+    }
+}
+
+/// A change of a validator's state in some epoch. The changes made in a block
+/// are recorded in memory for the ledger to emit them as events at the end of
+/// the block (see [`crate::state_changes`]).
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub struct ValidatorStateChange {
+    /// The validator's address
+    pub validator: Address,
+    /// The epoch from which the new state applies
+    pub epoch: Epoch,
+    /// The validator's previous state in the epoch
+    pub from: Option<ValidatorState>,
+    /// The validator's new state in the epoch
+    pub to: ValidatorState,
+    /// What caused the change
+    pub cause: ValidatorStateChangeCause,
+}
+
 /// A slash applied to validator, to punish byzantine behavior by removing
 /// their staked tokens at and before the epoch of the slash.
 #[derive(
@@ -559,6 +803,103 @@ pub struct Slash {
 /// their staked tokens at and before the epoch of the slash.
 pub type Slashes = LazyVec<Slash>;
 
+/// Pruning strategy of [`Slashes`]. The slashes are needed to compute any bond
+/// amount, so they are never pruned.
+pub type SlashesPruning = crate::epoched::NeverPrune;
+
+/// The processing status of a slash
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+pub enum SlashStatus {
+    /// The slash has been processed and applied to the validator's bonds
+    Processed,
+    /// The slash is enqueued to be processed in a future epoch
+    Enqueued,
+}
+
+/// A slash of a validator with its processing status
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq, Eq,
+)]
+pub struct SlashRecord {
+    /// The slashed validator
+    pub validator: Address,
+    /// The processing status of the slash
+    pub status: SlashStatus,
+    /// The epoch in which an enqueued slash is going to be processed.
+    /// `None` for processed slashes.
+    pub processing_epoch: Option<Epoch>,
+    /// The slash
+    pub slash: Slash,
+}
+
+/// A filter of the slashes to iterate. The epoch range applies to the
+/// infraction epochs of the slashes and is inclusive on both ends.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlashFilter {
+    /// Only include the slashes of this validator
+    pub validator: Option<Address>,
+    /// Only include the slashes of infractions at or after this epoch
+    pub from_epoch: Option<Epoch>,
+    /// Only include the slashes of infractions at or before this epoch
+    pub to_epoch: Option<Epoch>,
+    /// Only include the slashes with this processing status
+    pub status: Option<SlashStatus>,
+}
+
+impl SlashFilter {
+    /// Does the filter include slashes with the given status?
+    pub fn includes_status(&self, status: SlashStatus) -> bool {
+        self.status.map_or(true, |filter| filter == status)
+    }
+
+    /// Does the filter include the given slash of the validator?
+    pub fn matches(&self, validator: &Address, slash: &Slash) -> bool {
+        self.validator
+            .as_ref()
+            .map_or(true, |filter| filter == validator)
+            && self.from_epoch.map_or(true, |from| slash.epoch >= from)
+            && self.to_epoch.map_or(true, |to| slash.epoch <= to)
+    }
+}
+
+/// A bond amount after slashing in the epoch of the checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct BondAmountCheckpoint {
+    /// The bond amount after applying the slashes processed so far
+    pub amount: token::Amount,
+    /// The last of the validator's slashes applied to the amount, if any. The
+    /// checkpoint is stale once another slash has been applied.
+    pub last_slash: Option<Slash>,
+}
+
+/// Checkpoints of a bond amount, keyed by the epochs in which the bond has
+/// changed. The amount in a checkpoint holds until the next checkpoint. The
+/// checkpoints older than the data retention horizon are pruned, except for
+/// the latest of them which is moved to the horizon, so that a look-up only
+/// visits the retained checkpoints.
+pub type BondAmountCheckpoints = crate::epoched::Epoched<
+    BondAmountCheckpoint,
+    crate::epoched::OffsetPipelineLen,
+    crate::epoched::KeepUntilProcessed,
+>;
+
+/// The cumulative rate of a validator's slashes, keyed by the epoch in which
+/// the slashes were processed. The rate at an epoch is the fraction of a bond
+/// that contributed to the validator's stake since before any of its
+/// infractions that has been slashed by the slashes processed up to and
+/// including that epoch.
+pub type CumulativeSlashRates = LazyMap<Epoch, Dec>;
+
 /// A type of slashable event.
 #[derive(
     Debug,
@@ -580,6 +921,199 @@ pub enum SlashType {
     LightClientAttack,
 }
 
+/// An estimate of the slash that an infraction of a validator would incur once
+/// processed
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct SlashEstimate {
+    /// The epoch in which the slash would be processed
+    pub processing_epoch: Epoch,
+    /// The cubic slash rate of the infraction epoch, including the infraction
+    pub cubic_slash_rate: Dec,
+    /// The combined rate of the validator's slashes for the infraction epoch,
+    /// capped at 1
+    pub slash_rate: Dec,
+    /// The estimated amount of the validator's stake that would be slashed
+    pub slashed_amount: token::Amount,
+}
+
+/// Whether a delegator may redelegate its bonds from a source validator
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+)]
+pub enum RedelegationEligibility {
+    /// A redelegation is allowed in the current epoch
+    Eligible,
+    /// A redelegation would be chained, because the source validator holds
+    /// tokens of the delegator redelegated to it that may still be slashed.
+    /// A redelegation is allowed from the given epoch.
+    ChainedUntil(Epoch),
+}
+
+/// An action scheduled in [`ScheduledActions`]
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub enum ScheduledAction {
+    /// Withdraw the matured unbonds of the bond, as with
+    /// [`crate::withdraw_tokens`]
+    Withdraw(BondId),
+}
+
+/// The progress of the processing of an epoch's slashes that is spread over
+/// several blocks, when the number of slashed validators processed in a block
+/// is limited by the `max_slashed_validators_per_block` PoS parameter
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct SlashProcessingCursor {
+    /// The epoch in which the slashes are being processed
+    pub epoch: Epoch,
+    /// The processed slashes with their final rates, to be written into the
+    /// validators' slashes once all the slashed validators are processed
+    pub slashes: BTreeMap<Address, Vec<Slash>>,
+    /// The slashed validators that remain to be processed, with their
+    /// cumulative slash rates
+    pub pending: BTreeMap<Address, Dec>,
+    /// The slashed amounts per epoch of the validators processed so far
+    pub slashed_amounts: EagerRedelegatedBondsMap,
+}
+
+impl SlashProcessingCursor {
+    /// Returns whether all the slashed validators have been processed
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// The status of the processing of the slashes of the current epoch, returned
+/// from [`crate::process_slashes_chunk`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlashProcessingStatus {
+    /// There are no slashes being processed
+    Idle,
+    /// Some of the slashed validators remain to be processed in the
+    /// following blocks
+    InProgress,
+    /// The slashes have been processed and applied in this call
+    Completed,
+}
+
+/// A bond, unbond or redelegation to simulate with
+/// [`crate::simulate_bond_action`]
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum BondAction {
+    /// Bond tokens to a validator
+    Bond {
+        /// The source of the bond, or `None` for a self-bond
+        source: Option<Address>,
+        /// The validator to bond to
+        validator: Address,
+        /// The amount of tokens to bond
+        amount: token::Amount,
+    },
+    /// Unbond tokens from a validator
+    Unbond {
+        /// The source of the bond, or `None` for a self-bond
+        source: Option<Address>,
+        /// The validator to unbond from
+        validator: Address,
+        /// The amount of tokens to unbond
+        amount: token::Amount,
+    },
+    /// Redelegate bonded tokens from a source validator to a destination
+    /// validator
+    Redelegate {
+        /// The owner of the redelegated bonds
+        delegator: Address,
+        /// The validator to redelegate from
+        src_validator: Address,
+        /// The validator to redelegate to
+        dest_validator: Address,
+        /// The amount of tokens to redelegate
+        amount: token::Amount,
+    },
+}
+
+/// A change of a validator's state at the pipeline epoch that would be caused
+/// by a simulated [`BondAction`]
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub struct ValidatorStateTransition {
+    /// The validator's address
+    pub validator: Address,
+    /// The validator's state without the action
+    pub from: Option<ValidatorState>,
+    /// The validator's state with the action applied
+    pub to: Option<ValidatorState>,
+}
+
+/// The outcome of a simulated [`BondAction`]
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct BondActionSimulation {
+    /// The pipeline epoch, in which the action takes effect
+    pub pipeline_epoch: Epoch,
+    /// For an unbond, the unbonded tokens after accounting for slashes
+    pub result_slashing: Option<crate::ResultSlashing>,
+    /// The stake at the pipeline epoch of the validators the action is
+    /// applied to, without and with the action
+    pub stake_changes: BTreeMap<Address, (token::Amount, token::Amount)>,
+    /// The validators whose state at the pipeline epoch would change,
+    /// including any validators moved between the validator sets
+    pub state_transitions: Vec<ValidatorStateTransition>,
+}
+
+/// A consensus validator in a [`ValidatorSetSnapshot`]
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq, Eq,
+)]
+pub struct ValidatorSnapshot {
+    /// The validator's address
+    pub address: Address,
+    /// The validator's bonded stake
+    pub bonded_stake: token::Amount,
+    /// The validator's consensus key
+    pub consensus_key: common::PublicKey,
+}
+
+/// A snapshot of the consensus validator set of an epoch. It's stored under a
+/// deterministic key so that light clients can verify it with a proof against
+/// the storage Merkle tree.
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq, Eq,
+)]
+pub struct ValidatorSetSnapshot {
+    /// The epoch of the validator set
+    pub epoch: Epoch,
+    /// The consensus validators, ordered by descending stake and then by
+    /// descending address
+    pub validators: Vec<ValidatorSnapshot>,
+}
+
+impl ValidatorSetSnapshot {
+    /// The SHA-256 hash of the encoded snapshot, which can be used as a
+    /// compact commitment to the validator set
+    pub fn commitment(&self) -> Hash {
+        Hash::sha256(
+            borsh::to_vec(self)
+                .expect("Encoding a validator set snapshot shouldn't fail"),
+        )
+    }
+
+    /// The total bonded stake of the validators in the snapshot
+    pub fn total_stake(&self) -> token::Amount {
+        self.validators
+            .iter()
+            .map(|validator| validator.bonded_stake)
+            .sum()
+    }
+}
+
 /// VoteInfo inspired from tendermint for validators whose signature was
 /// included in the last block
 #[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
@@ -636,6 +1170,27 @@ pub struct UnbondDetails {
     pub slashed_amount: Option<token::Amount>,
 }
 
+/// An unbond in the unbonding queue of a bond, with the number of epochs left
+/// until it can be withdrawn
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq,
+)]
+pub struct QueuedUnbond {
+    /// The first epoch in which the source bond of this unbond contributed to
+    /// a stake
+    pub start_epoch: Epoch,
+    /// The first epoch in which this unbond can be withdrawn
+    pub withdraw_epoch: Epoch,
+    /// The number of epochs left until the unbond can be withdrawn, zero if
+    /// it is already withdrawable
+    pub epochs_until_withdrawable: u64,
+    /// The unbonded amount, before the application of slashes
+    pub amount: token::Amount,
+    /// The amount that can be withdrawn after the application of the slashes
+    /// known so far
+    pub withdrawable_amount: token::Amount,
+}
+
 impl Display for BondId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -668,6 +1223,39 @@ impl Display for SlashType {
     }
 }
 
+impl FromStr for SlashType {
+    type Err = SlashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Duplicate vote" => Ok(SlashType::DuplicateVote),
+            "Light client attack" => Ok(SlashType::LightClientAttack),
+            _ => Err(SlashError::UnknownSlashType(s.to_string())),
+        }
+    }
+}
+
+impl Display for SlashStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlashStatus::Processed => write!(f, "processed"),
+            SlashStatus::Enqueued => write!(f, "enqueued"),
+        }
+    }
+}
+
+impl FromStr for SlashStatus {
+    type Err = SlashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "processed" => Ok(SlashStatus::Processed),
+            "enqueued" => Ok(SlashStatus::Enqueued),
+            _ => Err(SlashError::UnknownSlashStatus(s.to_string())),
+        }
+    }
+}
+
 /// Calculate voting power in the tendermint context (which is stored as i64)
 /// from the number of tokens
 pub fn into_tm_voting_power(votes_per_token: Dec, tokens: Amount) -> i64 {
@@ -0,0 +1,1024 @@
+//! Validation of the changes made by a transaction to the PoS storage, used by
+//! the PoS native validity predicate.
+//!
+//! Rather than checking every PoS storage key individually, [`validate`]
+//! checks that the changes as a whole are one of the allowed transitions:
+//! - the PoS parameters are only changed by an accepted governance proposal and
+//!   remain valid
+//! - the total deltas change as much as the sum of the validator deltas
+//! - no validator ends up with a negative stake
+//! - an increase of stake is backed by tokens transferred to the PoS account,
+//!   and the tokens taken out of it don't exceed the withdrawn unbonds and
+//!   claimed rewards
+//! - the validator sets at the pipeline epoch are consistent with the states
+//!   and stakes of their validators
+//! - the staking pool's validators are only changed by an accepted governance
+//!   proposal, its shares and pooled unbonds add up and its share price doesn't
+//!   decrease
+//!
+//! Additionally, [`find_operator_actions`] finds the changes that a
+//! validator's operator key may authorize and [`find_staking_pool_redeemers`]
+//! the depositors whose staking pool shares or pooled unbonds are spent, which
+//! the native validity predicate checks against the tx signatures.
+
+use std::collections::BTreeSet;
+
+use namada_core::ledger::storage_api::{self, governance, token, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::storage::{Epoch, Key};
+use thiserror::Error;
+
+use crate::parameters::{ParamError, PosParams};
+use crate::staking_pool::{
+    read_staking_pool_reserved, read_staking_pool_total_shares,
+    staking_pool_value,
+};
+use crate::storage::{
+    is_below_capacity_validator_set_key, is_bond_key,
+    is_consensus_validator_set_key, is_last_pos_reward_claim_epoch_key,
+    is_params_key, is_rewards_dust_recipient_key, is_staking_pool_key,
+    is_staking_pool_shares_key, is_staking_pool_unbond_key,
+    is_staking_pool_validators_key, is_unbond_key,
+    is_validator_commission_rate_key, is_validator_deltas_key,
+    is_validator_metadata_key, is_validator_state_key,
+};
+use crate::types::{ValidatorState, WeightedValidator};
+use crate::{
+    query_reward_tokens,
+    read_below_capacity_validator_set_addresses_with_stake,
+    read_consensus_validator_set_addresses_with_stake, read_pos_params,
+    read_validator_stake, staking_token_address, total_deltas_handle,
+    validator_deltas_handle, validator_state_handle, ADDRESS,
+    STAKING_POOL_ADDRESS,
+};
+
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error(
+        "The PoS parameters can only be changed by an accepted governance \
+         proposal"
+    )]
+    ParamsChangedOutsideGovernance,
+    #[error(
+        "The recipient of the rewards dust can only be changed by an accepted \
+         governance proposal"
+    )]
+    RewardsDustRecipientChangedOutsideGovernance,
+    #[error("The new PoS parameters are invalid: {0}")]
+    InvalidParams(ParamError),
+    #[error(
+        "The total deltas changed by {} at the pipeline epoch, but the \
+         validator deltas by {} in sum",
+        .total.to_string_native(),
+        .validators.to_string_native()
+    )]
+    TotalDeltasMismatch {
+        total: token::Change,
+        validators: token::Change,
+    },
+    #[error(
+        "The stake of validator {0} is negative at the pipeline epoch: {}",
+        .1.to_string_native()
+    )]
+    NegativeStake(Address, token::Change),
+    #[error(
+        "The stake increased by {} at the pipeline epoch, but only {} tokens \
+         were transferred to PoS",
+        .stake.to_string_native(),
+        .transferred.to_string_native()
+    )]
+    UnbackedStake {
+        stake: token::Change,
+        transferred: token::Change,
+    },
+    #[error(
+        "The PoS balance changed by {}, but at most {} tokens can be \
+         withdrawn or claimed",
+        .transferred.to_string_native(),
+        .allowed.to_string_native()
+    )]
+    ExcessiveOutflow {
+        transferred: token::Change,
+        allowed: token::Amount,
+    },
+    #[error(
+        "The consensus validator set has {size} validators, more than the \
+         {capacity} slots"
+    )]
+    ConsensusSetOverCapacity { size: u64, capacity: u64 },
+    #[error(
+        "Expected validator {validator} to be in state {expected:?} in epoch \
+         {epoch}, got {got:?}"
+    )]
+    UnexpectedValidatorState {
+        validator: Address,
+        epoch: Epoch,
+        expected: ValidatorState,
+        got: Option<ValidatorState>,
+    },
+    #[error(
+        "Validator {validator} is in a validator set with stake {}, but its \
+         stake is {}",
+        .set_stake.to_string_native(),
+        .stake.to_string_native()
+    )]
+    ValidatorSetStakeMismatch {
+        validator: Address,
+        set_stake: token::Amount,
+        stake: token::Amount,
+    },
+    #[error(
+        "The consensus validator with the least stake {} has less stake than \
+         the below-capacity validator with the most stake {}",
+        .consensus.to_string_native(),
+        .below_capacity.to_string_native()
+    )]
+    ValidatorSetsOrder {
+        consensus: token::Amount,
+        below_capacity: token::Amount,
+    },
+    #[error(
+        "The changes to validator {0} must be signed by the validator or its \
+         operator key"
+    )]
+    UnauthorizedOperatorAction(Address),
+    #[error(
+        "The staking pool validators can only be changed by an accepted \
+         governance proposal"
+    )]
+    StakingPoolValidatorsChangedOutsideGovernance,
+    #[error(
+        "The total staking pool shares changed by {}, but the shares of the \
+         depositors by {} in sum",
+        .total.to_string_native(),
+        .owners.to_string_native()
+    )]
+    StakingPoolSharesMismatch {
+        total: token::Change,
+        owners: token::Change,
+    },
+    #[error(
+        "The staking pool reserved amount changed by {}, but the pooled \
+         unbonds by {} in sum",
+        .reserved.to_string_native(),
+        .unbonds.to_string_native()
+    )]
+    StakingPoolReservedMismatch {
+        reserved: token::Change,
+        unbonds: token::Change,
+    },
+    #[error(
+        "Invalid change of the pooled unbond of {owner} withdrawable from \
+         epoch {epoch}"
+    )]
+    InvalidPooledUnbond { owner: Address, epoch: Epoch },
+    #[error(
+        "The staking pool's bonds can only be changed by a redemption of \
+         shares"
+    )]
+    StakingPoolBondsChangedOutsideRedemption,
+    #[error(
+        "The staking pool share price decreased from {} / {} to {} / {}",
+        .pre_value.to_string_native(),
+        .pre_shares.to_string_native(),
+        .post_value.to_string_native(),
+        .post_shares.to_string_native()
+    )]
+    StakingPoolSharePriceDecreased {
+        pre_value: token::Amount,
+        pre_shares: token::Amount,
+        post_value: token::Amount,
+        post_shares: token::Amount,
+    },
+    #[error(
+        "The redemption of the staking pool shares or pooled unbonds of {0} \
+         must be signed by the depositor"
+    )]
+    UnauthorizedStakingPoolAction(Address),
+}
+
+/// Validate the changes of the given keys from the `pre` to the `post` state
+/// made by a tx with the given data. Returns the invalid changes, if any.
+pub fn validate<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    keys_changed: &BTreeSet<Key>,
+    tx_data: Option<&[u8]>,
+) -> storage_api::Result<Vec<ValidationError>>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let mut errors = vec![];
+
+    let is_accepted_proposal = || match tx_data {
+        Some(data) => governance::is_proposal_accepted(pre, data),
+        None => Ok(false),
+    };
+    if keys_changed.iter().any(is_params_key) {
+        if !is_accepted_proposal()? {
+            errors.push(ValidationError::ParamsChangedOutsideGovernance);
+        }
+        let new_params = read_pos_params(post)?;
+        errors.extend(
+            new_params
+                .owned
+                .validate()
+                .into_iter()
+                .map(ValidationError::InvalidParams),
+        );
+    }
+    if keys_changed.iter().any(is_rewards_dust_recipient_key)
+        && !is_accepted_proposal()?
+    {
+        errors.push(
+            ValidationError::RewardsDustRecipientChangedOutsideGovernance,
+        );
+    }
+
+    let params = read_pos_params(pre)?;
+    let current_epoch = pre.get_block_epoch()?;
+    validate_token_flows(
+        pre,
+        post,
+        &params,
+        current_epoch,
+        keys_changed,
+        &mut errors,
+    )?;
+
+    let validator_sets_changed = keys_changed.iter().any(|key| {
+        is_consensus_validator_set_key(key)
+            || is_below_capacity_validator_set_key(key)
+            || is_validator_state_key(key).is_some()
+            || is_validator_deltas_key(key).is_some()
+    });
+    if validator_sets_changed {
+        let params = read_pos_params(post)?;
+        validate_validator_sets(post, &params, current_epoch, &mut errors)?;
+    }
+
+    if keys_changed.iter().any(is_staking_pool_validators_key) {
+        let is_accepted_proposal = match tx_data {
+            Some(data) => governance::is_proposal_accepted(pre, data)?,
+            None => false,
+        };
+        if !is_accepted_proposal {
+            errors.push(
+                ValidationError::StakingPoolValidatorsChangedOutsideGovernance,
+            );
+        }
+    }
+
+    let staking_token = staking_token_address(pre);
+    let staking_pool_changed = keys_changed.iter().any(|key| {
+        is_staking_pool_key(key)
+            || token::is_balance_key(&staking_token, key)
+                == Some(&STAKING_POOL_ADDRESS)
+            || is_bond_key(key)
+                .map(|(bond_id, _)| bond_id.source == STAKING_POOL_ADDRESS)
+                .unwrap_or_default()
+    });
+    if staking_pool_changed {
+        validate_staking_pool(
+            pre,
+            post,
+            &params,
+            current_epoch,
+            keys_changed,
+            &mut errors,
+        )?;
+    }
+
+    Ok(errors)
+}
+
+/// Find the validators whose commission rate or metadata are changed by the
+/// given keys, or who are unjailed. These are the actions that a validator's
+/// operator key may authorize in place of the validator's account keys.
+pub fn find_operator_actions<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    keys_changed: &BTreeSet<Key>,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let mut validators = BTreeSet::new();
+    for key in keys_changed {
+        if let Some((validator, _epoch)) = is_validator_commission_rate_key(key)
+        {
+            validators.insert(validator.clone());
+        } else if let Some(validator) = is_validator_metadata_key(key) {
+            validators.insert(validator.clone());
+        } else if let Some((validator, epoch)) = is_validator_state_key(key) {
+            let state_pre = validator_state_handle(validator).get(
+                pre,
+                epoch,
+                &read_pos_params(pre)?,
+            )?;
+            let state_post = validator_state_handle(validator).get(
+                post,
+                epoch,
+                &read_pos_params(post)?,
+            )?;
+            let is_unjailed = state_pre == Some(ValidatorState::Jailed)
+                && matches!(
+                    state_post,
+                    Some(
+                        ValidatorState::Consensus
+                            | ValidatorState::BelowCapacity
+                            | ValidatorState::BelowThreshold
+                    )
+                );
+            if is_unjailed {
+                validators.insert(validator.clone());
+            }
+        }
+    }
+    Ok(validators)
+}
+
+/// Find the depositors whose staking pool shares or pooled unbonds are
+/// decreased by the given keys. The tx must be signed by these depositors.
+pub fn find_staking_pool_redeemers<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    keys_changed: &BTreeSet<Key>,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let mut owners = BTreeSet::new();
+    for key in keys_changed {
+        let owner = match is_staking_pool_shares_key(key) {
+            Some(owner) => owner,
+            None => match is_staking_pool_unbond_key(key) {
+                Some((owner, _epoch)) => owner,
+                None => continue,
+            },
+        };
+        let pre_amount: token::Amount = pre.read(key)?.unwrap_or_default();
+        let post_amount: token::Amount = post.read(key)?.unwrap_or_default();
+        if post_amount < pre_amount {
+            owners.insert(owner.clone());
+        }
+    }
+    Ok(owners)
+}
+
+/// Check that the changes of stake at the pipeline epoch add up and that
+/// they're backed by the tokens transferred to or from the PoS account
+fn validate_token_flows<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    params: &PosParams,
+    current_epoch: Epoch,
+    keys_changed: &BTreeSet<Key>,
+    errors: &mut Vec<ValidationError>,
+) -> storage_api::Result<()>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let pipeline = current_epoch + params.pipeline_len;
+
+    let total_deltas = total_deltas_handle();
+    let total_delta = total_deltas
+        .get_sum(post, pipeline, params)?
+        .unwrap_or_default()
+        - total_deltas
+            .get_sum(pre, pipeline, params)?
+            .unwrap_or_default();
+
+    let validators: BTreeSet<&Address> = keys_changed
+        .iter()
+        .filter_map(is_validator_deltas_key)
+        .collect();
+    let mut validators_delta = token::Change::zero();
+    for validator in validators {
+        let deltas = validator_deltas_handle(validator);
+        let post_stake =
+            deltas.get_sum(post, pipeline, params)?.unwrap_or_default();
+        let pre_stake =
+            deltas.get_sum(pre, pipeline, params)?.unwrap_or_default();
+        if post_stake.is_negative() {
+            errors.push(ValidationError::NegativeStake(
+                validator.clone(),
+                post_stake,
+            ));
+        }
+        validators_delta += post_stake - pre_stake;
+    }
+    if total_delta != validators_delta {
+        errors.push(ValidationError::TotalDeltasMismatch {
+            total: total_delta,
+            validators: validators_delta,
+        });
+    }
+
+    // Tokens may only leave the PoS account for withdrawn unbonds and
+    // claimed rewards
+    let mut allowed_outflow = token::Amount::zero();
+    for key in keys_changed {
+        if is_unbond_key(key).is_some() {
+            let pre_unbond: token::Amount = pre.read(key)?.unwrap_or_default();
+            let post_unbond: token::Amount =
+                post.read(key)?.unwrap_or_default();
+            if pre_unbond > post_unbond {
+                allowed_outflow += pre_unbond - post_unbond;
+            }
+        } else if let Some((delegator, validator)) =
+            is_last_pos_reward_claim_epoch_key(key)
+        {
+            allowed_outflow += query_reward_tokens(
+                pre,
+                Some(delegator),
+                validator,
+                current_epoch,
+            )?;
+        }
+    }
+
+    let staking_token = staking_token_address(pre);
+    let transferred = token::read_balance(post, &staking_token, &ADDRESS)?
+        .change()
+        - token::read_balance(pre, &staking_token, &ADDRESS)?.change();
+    if (transferred + allowed_outflow.change()).is_negative() {
+        errors.push(ValidationError::ExcessiveOutflow {
+            transferred,
+            allowed: allowed_outflow,
+        });
+    }
+    if total_delta > transferred + allowed_outflow.change() {
+        errors.push(ValidationError::UnbackedStake {
+            stake: total_delta,
+            transferred,
+        });
+    }
+    Ok(())
+}
+
+/// Check that the changes of the staking pool's shares and pooled unbonds add
+/// up, that the pooled unbonds are only withdrawn once they mature and that
+/// the value of a share doesn't decrease
+fn validate_staking_pool<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    params: &PosParams,
+    current_epoch: Epoch,
+    keys_changed: &BTreeSet<Key>,
+    errors: &mut Vec<ValidationError>,
+) -> storage_api::Result<()>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let mut owners_shares = token::Change::zero();
+    let mut unbonds = token::Change::zero();
+    let mut bonds_changed = false;
+    for key in keys_changed {
+        if is_staking_pool_shares_key(key).is_some() {
+            let pre_shares: token::Amount = pre.read(key)?.unwrap_or_default();
+            let post_shares: token::Amount =
+                post.read(key)?.unwrap_or_default();
+            owners_shares += post_shares.change() - pre_shares.change();
+        } else if let Some((owner, epoch)) = is_staking_pool_unbond_key(key) {
+            let pre_unbond: token::Amount = pre.read(key)?.unwrap_or_default();
+            let post_unbond: token::Amount =
+                post.read(key)?.unwrap_or_default();
+            // A pooled unbond can only be withdrawn once it matures and only
+            // be added with the withdrawable epoch of a new unbond
+            let is_valid = if post_unbond < pre_unbond {
+                epoch <= current_epoch
+            } else {
+                epoch >= current_epoch + params.withdrawable_epoch_offset()
+            };
+            if !is_valid {
+                errors.push(ValidationError::InvalidPooledUnbond {
+                    owner: owner.clone(),
+                    epoch,
+                });
+            }
+            unbonds += post_unbond.change() - pre_unbond.change();
+        } else if let Some((bond_id, _epoch)) = is_bond_key(key) {
+            bonds_changed |= bond_id.source == STAKING_POOL_ADDRESS;
+        }
+    }
+
+    let pre_shares = read_staking_pool_total_shares(pre)?;
+    let post_shares = read_staking_pool_total_shares(post)?;
+    let total_shares = post_shares.change() - pre_shares.change();
+    if total_shares != owners_shares {
+        errors.push(ValidationError::StakingPoolSharesMismatch {
+            total: total_shares,
+            owners: owners_shares,
+        });
+    }
+    let reserved = read_staking_pool_reserved(post)?.change()
+        - read_staking_pool_reserved(pre)?.change();
+    if reserved != unbonds {
+        errors.push(ValidationError::StakingPoolReservedMismatch {
+            reserved,
+            unbonds,
+        });
+    }
+    // The pool's bonds are only rebalanced by the protocol, outside of txs
+    if bonds_changed && post_shares >= pre_shares {
+        errors.push(ValidationError::StakingPoolBondsChangedOutsideRedemption);
+    }
+
+    // Compare the share prices `post_value / post_shares` and `pre_value /
+    // pre_shares` by cross-multiplying. The first shares must not be issued
+    // for less than their value.
+    let pre_value = staking_pool_value(pre, current_epoch)?;
+    let post_value = staking_pool_value(post, current_epoch)?;
+    let is_price_decreased = if pre_shares.is_zero() {
+        post_shares > post_value
+    } else {
+        match (
+            post_value.checked_mul(pre_shares),
+            pre_value.checked_mul(post_shares),
+        ) {
+            (Some(post), Some(pre)) => post < pre,
+            _ => true,
+        }
+    };
+    if is_price_decreased {
+        errors.push(ValidationError::StakingPoolSharePriceDecreased {
+            pre_value,
+            pre_shares,
+            post_value,
+            post_shares,
+        });
+    }
+    Ok(())
+}
+
+/// Check that the validators in the validator sets at the pipeline epoch
+/// have the matching states and stakes and that the sets are ordered
+fn validate_validator_sets<S>(
+    storage: &S,
+    params: &PosParams,
+    current_epoch: Epoch,
+    errors: &mut Vec<ValidationError>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead,
+{
+    let pipeline = current_epoch + params.pipeline_len;
+
+    let consensus_set =
+        read_consensus_validator_set_addresses_with_stake(storage, pipeline)?;
+    let below_capacity_set =
+        read_below_capacity_validator_set_addresses_with_stake(
+            storage, pipeline,
+        )?;
+
+    let size = consensus_set.len() as u64;
+    if size > params.max_validator_slots {
+        errors.push(ValidationError::ConsensusSetOverCapacity {
+            size,
+            capacity: params.max_validator_slots,
+        });
+    }
+
+    for (set, expected) in [
+        (&consensus_set, ValidatorState::Consensus),
+        (&below_capacity_set, ValidatorState::BelowCapacity),
+    ] {
+        for WeightedValidator {
+            bonded_stake,
+            address,
+        } in set
+        {
+            let state = validator_state_handle(address)
+                .get(storage, pipeline, params)?;
+            if state != Some(expected) {
+                errors.push(ValidationError::UnexpectedValidatorState {
+                    validator: address.clone(),
+                    epoch: pipeline,
+                    expected,
+                    got: state,
+                });
+            }
+            let stake =
+                read_validator_stake(storage, params, address, pipeline)?;
+            if stake != *bonded_stake {
+                errors.push(ValidationError::ValidatorSetStakeMismatch {
+                    validator: address.clone(),
+                    set_stake: *bonded_stake,
+                    stake,
+                });
+            }
+        }
+    }
+
+    let min_consensus = consensus_set.iter().map(|val| val.bonded_stake).min();
+    let max_below_capacity =
+        below_capacity_set.iter().map(|val| val.bonded_stake).max();
+    if let (Some(consensus), Some(below_capacity)) =
+        (min_consensus, max_below_capacity)
+    {
+        if consensus < below_capacity {
+            errors.push(ValidationError::ValidatorSetsOrder {
+                consensus,
+                below_capacity,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_validation {
+    use std::collections::{BTreeMap, HashMap};
+
+    use assert_matches::assert_matches;
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::ledger::storage::TempWlStorage;
+    use namada_core::ledger::storage_api::token::credit_tokens;
+    use namada_core::ledger::storage_api::StorageWrite;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada_core::types::dec::Dec;
+    use namada_core::types::key::testing::{keypair_1, keypair_2};
+    use namada_core::types::key::{self, RefTo};
+
+    use super::*;
+    use crate::parameters::{OwnedPosParams, MIN_VALIDATOR_SETS_RETENTION_LEN};
+    use crate::staking_pool::{
+        deposit_into_staking_pool, read_staking_pool_unbonds,
+        rebalance_staking_pool, redeem_staking_pool_shares,
+        staking_pool_share_price, staking_pool_shares_handle,
+        withdraw_from_staking_pool, write_staking_pool_validators,
+    };
+    use crate::storage::{params_key, staking_pool_total_shares_key};
+    use crate::test_utils::test_init_genesis;
+    use crate::types::GenesisValidator;
+    use crate::{
+        bond_tokens, change_validator_commission_rate,
+        change_validator_operator_key, find_delegations,
+        read_validator_operator_key, unbond_tokens, update_total_deltas,
+        update_validator_deltas, withdraw_tokens, write_pos_params,
+        write_rewards_dust_recipient, write_validator_metadata,
+    };
+
+    /// Init a storage with a single genesis validator, committed to the
+    /// storage, so that any following change is only in the write log
+    fn init_storage() -> (TestWlStorage, PosParams, Address) {
+        let mut s = TestWlStorage::default();
+        let validator = established_address_1();
+        let genesis_validator = GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::native_whole(1_000),
+            consensus_key: keypair_1().ref_to(),
+            protocol_key: keypair_2().ref_to(),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+            max_commission_rate_change: Dec::new(1, 1)
+                .expect("Dec creation failed"),
+            metadata: Default::default(),
+        };
+        let params = test_init_genesis(
+            &mut s,
+            OwnedPosParams::default(),
+            [genesis_validator].into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+        s.commit_block().unwrap();
+        (s, params, validator)
+    }
+
+    /// Validate the changes in the write log of the given storage
+    fn validate_write_log(
+        s: &TestWlStorage,
+        tx_data: Option<&[u8]>,
+    ) -> Vec<ValidationError> {
+        let pre = TempWlStorage::new(&s.storage);
+        validate(&pre, s, &s.write_log.get_keys(), tx_data).unwrap()
+    }
+
+    /// Test that the changes of bonds, unbonds and withdrawals are valid
+    #[test]
+    fn test_validate_bond_unbond_withdraw() {
+        let (mut s, params, validator) = init_storage();
+        let delegator = established_address_2();
+        let staking_token = staking_token_address(&s);
+        let amount = token::Amount::native_whole(100);
+        credit_tokens(&mut s, &staking_token, &delegator, amount).unwrap();
+        s.commit_block().unwrap();
+
+        let current_epoch = s.storage.block.epoch;
+        bond_tokens(
+            &mut s,
+            Some(&delegator),
+            &validator,
+            amount,
+            current_epoch,
+            None,
+        )
+        .unwrap();
+        assert_eq!(validate_write_log(&s, None), vec![]);
+        s.commit_block().unwrap();
+
+        unbond_tokens(
+            &mut s,
+            Some(&delegator),
+            &validator,
+            amount,
+            current_epoch,
+            false,
+        )
+        .unwrap();
+        assert_eq!(validate_write_log(&s, None), vec![]);
+        s.commit_block().unwrap();
+
+        let withdrawable_epoch =
+            current_epoch + params.withdrawable_epoch_offset();
+        s.storage.block.epoch = withdrawable_epoch;
+        s.commit_block().unwrap();
+        let withdrawn = withdraw_tokens(
+            &mut s,
+            Some(&delegator),
+            &validator,
+            withdrawable_epoch,
+        )
+        .unwrap();
+        assert_eq!(withdrawn, amount);
+        assert_eq!(validate_write_log(&s, None), vec![]);
+    }
+
+    /// Test that stake can't be added without a transfer to PoS, nor with
+    /// inconsistent deltas or validator sets
+    #[test]
+    fn test_validate_unbacked_stake() {
+        let (mut s, params, validator) = init_storage();
+        let current_epoch = s.storage.block.epoch;
+        let amount = token::Amount::native_whole(100);
+
+        update_validator_deltas(
+            &mut s,
+            &params,
+            &validator,
+            amount.change(),
+            current_epoch,
+            None,
+        )
+        .unwrap();
+        let errors = validate_write_log(&s, None);
+        assert_matches!(
+            &errors[..],
+            [
+                ValidationError::TotalDeltasMismatch { .. },
+                ValidationError::ValidatorSetStakeMismatch { .. },
+            ]
+        );
+
+        update_total_deltas(
+            &mut s,
+            &params,
+            amount.change(),
+            current_epoch,
+            None,
+        )
+        .unwrap();
+        let errors = validate_write_log(&s, None);
+        assert_matches!(
+            &errors[..],
+            [
+                ValidationError::UnbackedStake { .. },
+                ValidationError::ValidatorSetStakeMismatch { .. },
+            ]
+        );
+
+        let staking_token = staking_token_address(&s);
+        credit_tokens(&mut s, &staking_token, &ADDRESS, amount).unwrap();
+        let errors = validate_write_log(&s, None);
+        assert_matches!(
+            &errors[..],
+            [ValidationError::ValidatorSetStakeMismatch { .. }]
+        );
+    }
+
+    /// Test that the PoS parameters can only be changed by an accepted
+    /// governance proposal
+    #[test]
+    fn test_validate_params_change() {
+        let (mut s, params, _validator) = init_storage();
+        let mut new_params = params.owned.clone();
+        new_params.max_validator_slots += 1;
+        write_pos_params(&mut s, &new_params).unwrap();
+
+        assert_eq!(
+            validate_write_log(&s, None),
+            vec![ValidationError::ParamsChangedOutsideGovernance]
+        );
+    }
+
+    /// Test that the recipient of the rewards dust can only be changed by an
+    /// accepted governance proposal
+    #[test]
+    fn test_validate_rewards_dust_recipient_change() {
+        let (mut s, _params, _validator) = init_storage();
+        write_rewards_dust_recipient(&mut s, &established_address_2()).unwrap();
+
+        assert_eq!(
+            validate_write_log(&s, None),
+            vec![ValidationError::RewardsDustRecipientChangedOutsideGovernance]
+        );
+    }
+
+    /// Test that the PoS parameters can't be changed to invalid values, such
+    /// as a validator sets retention shorter than the safe minimum
+    #[test]
+    fn test_validate_invalid_params_change() {
+        let (mut s, params, _validator) = init_storage();
+        let mut new_params = params.owned.clone();
+        new_params.validator_sets_retention_len =
+            MIN_VALIDATOR_SETS_RETENTION_LEN - 1;
+        // Invalid params are rejected by `write_pos_params`, so write them
+        // directly as a tx would
+        s.write(&params_key(), &new_params).unwrap();
+
+        assert_eq!(
+            validate_write_log(&s, None),
+            vec![
+                ValidationError::ParamsChangedOutsideGovernance,
+                ValidationError::InvalidParams(
+                    ParamError::ValidatorSetsRetentionLenTooShort(
+                        MIN_VALIDATOR_SETS_RETENTION_LEN - 1
+                    )
+                ),
+            ]
+        );
+    }
+
+    /// Test that the operator key can be set and removed only for validators
+    /// and that the actions it may authorize are found
+    #[test]
+    fn test_operator_key_and_actions() {
+        let (mut s, _params, validator) = init_storage();
+        let current_epoch = s.storage.block.epoch;
+        let operator_key = key::testing::common_sk_from_simple_seed(0).ref_to();
+
+        // Only validators can have an operator key
+        assert!(
+            change_validator_operator_key(
+                &mut s,
+                &established_address_2(),
+                Some(&operator_key),
+            )
+            .is_err()
+        );
+        change_validator_operator_key(&mut s, &validator, Some(&operator_key))
+            .unwrap();
+        assert_eq!(
+            read_validator_operator_key(&s, &validator).unwrap(),
+            Some(operator_key)
+        );
+        // Changing the operator key isn't an operator action
+        let pre = TempWlStorage::new(&s.storage);
+        let keys_changed = s.write_log.get_keys();
+        assert!(
+            find_operator_actions(&pre, &s, &keys_changed)
+                .unwrap()
+                .is_empty()
+        );
+        s.commit_block().unwrap();
+
+        change_validator_commission_rate(
+            &mut s,
+            &validator,
+            Dec::new(5, 2).unwrap(),
+            current_epoch,
+        )
+        .unwrap();
+        let pre = TempWlStorage::new(&s.storage);
+        let keys_changed = s.write_log.get_keys();
+        assert_eq!(
+            find_operator_actions(&pre, &s, &keys_changed).unwrap(),
+            BTreeSet::from([validator.clone()])
+        );
+        s.commit_block().unwrap();
+
+        let metadata = crate::types::ValidatorMetaData {
+            email: "operator@example.com".to_string(),
+            ..Default::default()
+        };
+        write_validator_metadata(&mut s, &validator, &metadata).unwrap();
+        let pre = TempWlStorage::new(&s.storage);
+        let keys_changed = s.write_log.get_keys();
+        assert_eq!(
+            find_operator_actions(&pre, &s, &keys_changed).unwrap(),
+            BTreeSet::from([validator.clone()])
+        );
+        s.commit_block().unwrap();
+
+        change_validator_operator_key(&mut s, &validator, None).unwrap();
+        assert_eq!(read_validator_operator_key(&s, &validator).unwrap(), None);
+    }
+
+    /// Test the staking pool's deposits, rebalancing, redemptions and
+    /// withdrawals, and that its shares can't be issued without backing
+    #[test]
+    fn test_validate_staking_pool() {
+        let (mut s, params, validator) = init_storage();
+        let depositor = established_address_2();
+        let staking_token = staking_token_address(&s);
+        let amount = token::Amount::native_whole(100);
+        credit_tokens(&mut s, &staking_token, &depositor, amount).unwrap();
+        s.commit_block().unwrap();
+
+        // The pool's validators can only be set by governance
+        write_staking_pool_validators(
+            &mut s,
+            &BTreeSet::from([validator.clone()]),
+        )
+        .unwrap();
+        assert_eq!(
+            validate_write_log(&s, None),
+            vec![
+                ValidationError::StakingPoolValidatorsChangedOutsideGovernance
+            ]
+        );
+        s.commit_block().unwrap();
+
+        let current_epoch = s.storage.block.epoch;
+        let shares = deposit_into_staking_pool(
+            &mut s,
+            &depositor,
+            amount,
+            current_epoch,
+        )
+        .unwrap();
+        assert_eq!(shares, amount);
+        assert_eq!(validate_write_log(&s, None), vec![]);
+        s.commit_block().unwrap();
+
+        // The deposit is bonded by the rebalancing
+        rebalance_staking_pool(&mut s, current_epoch).unwrap();
+        s.commit_block().unwrap();
+        let pipeline_epoch = current_epoch + params.pipeline_len;
+        assert_eq!(
+            find_delegations(&s, &STAKING_POOL_ADDRESS, &pipeline_epoch)
+                .unwrap(),
+            HashMap::from([(validator.clone(), amount)])
+        );
+        assert_eq!(
+            staking_pool_share_price(&s, current_epoch).unwrap(),
+            Some(Dec::one())
+        );
+
+        // Without idle tokens, the redeemed value is unbonded
+        let half = token::Amount::native_whole(50);
+        let value =
+            redeem_staking_pool_shares(&mut s, &depositor, half, current_epoch)
+                .unwrap();
+        assert_eq!(value, half);
+        assert_eq!(validate_write_log(&s, None), vec![]);
+        s.commit_block().unwrap();
+        let withdrawable_epoch =
+            current_epoch + params.withdrawable_epoch_offset();
+        assert_eq!(
+            read_staking_pool_unbonds(&s, &depositor).unwrap(),
+            BTreeMap::from([(withdrawable_epoch, half)])
+        );
+        assert!(
+            withdraw_from_staking_pool(&mut s, &depositor, current_epoch)
+                .unwrap()
+                .is_zero()
+        );
+
+        s.storage.block.epoch = withdrawable_epoch;
+        s.commit_block().unwrap();
+        let withdrawn =
+            withdraw_from_staking_pool(&mut s, &depositor, withdrawable_epoch)
+                .unwrap();
+        assert_eq!(withdrawn, half);
+        assert_eq!(validate_write_log(&s, None), vec![]);
+        s.commit_block().unwrap();
+
+        // Shares issued without a deposit dilute the share price
+        staking_pool_shares_handle()
+            .insert(&mut s, depositor.clone(), amount)
+            .unwrap();
+        s.write(&staking_pool_total_shares_key(), amount).unwrap();
+        assert_matches!(
+            &validate_write_log(&s, None)[..],
+            [ValidationError::StakingPoolSharePriceDecreased { .. }]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! In-memory cache of the consensus validator sets. The cache is meant to be
+//! populated once per block by the ledger and invalidated whenever the
+//! validator sets get written to, so that the consensus validator set lazy
+//! maps don't have to be iterated several times per block.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use namada_core::ledger::storage_api::collections::lazy_map::{
+    NestedSubKey, SubKey,
+};
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+
+use crate::consensus_validator_set_handle;
+use crate::types::{Position, WeightedValidator};
+
+/// A consensus validator, as found in the consensus validator set of some
+/// epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedConsensusValidator {
+    /// The validator's address
+    pub address: Address,
+    /// The validator's bonded stake at the epoch of the set
+    pub bonded_stake: token::Amount,
+    /// The validator's position among the validators with the same stake
+    pub position: Position,
+}
+
+impl From<&CachedConsensusValidator> for WeightedValidator {
+    fn from(validator: &CachedConsensusValidator) -> Self {
+        WeightedValidator {
+            bonded_stake: validator.bonded_stake,
+            address: validator.address.clone(),
+        }
+    }
+}
+
+/// In-memory view of the consensus validator sets of some epochs, in the
+/// same order as they are stored (ascending by stake, then by position).
+#[derive(Debug, Clone, Default)]
+pub struct ConsensusValidatorSetCache {
+    sets: BTreeMap<Epoch, Vec<CachedConsensusValidator>>,
+}
+
+impl ConsensusValidatorSetCache {
+    /// Read the consensus validator set of the given epoch into the cache,
+    /// unless it's already cached.
+    pub fn populate<S>(
+        &mut self,
+        storage: &S,
+        epoch: Epoch,
+    ) -> storage_api::Result<()>
+    where
+        S: StorageRead,
+    {
+        if !self.sets.contains_key(&epoch) {
+            let set = read_consensus_validator_set(storage, epoch)?;
+            self.sets.insert(epoch, set);
+        }
+        Ok(())
+    }
+
+    /// Get the cached consensus validator set of the given epoch, if any.
+    pub fn get(&self, epoch: Epoch) -> Option<&[CachedConsensusValidator]> {
+        self.sets.get(&epoch).map(Vec::as_slice)
+    }
+
+    /// Get the consensus validator set of the given epoch from the cache, or
+    /// read it from storage if it's not cached.
+    pub fn read<S>(
+        &self,
+        storage: &S,
+        epoch: Epoch,
+    ) -> storage_api::Result<Cow<'_, [CachedConsensusValidator]>>
+    where
+        S: StorageRead,
+    {
+        match self.get(epoch) {
+            Some(set) => Ok(Cow::Borrowed(set)),
+            None => {
+                read_consensus_validator_set(storage, epoch).map(Cow::Owned)
+            }
+        }
+    }
+
+    /// Find a validator in the cached consensus validator set of the given
+    /// epoch. Returns `None` if the set is not cached or if the validator is
+    /// not in it.
+    pub fn find_validator(
+        &self,
+        epoch: Epoch,
+        address: &Address,
+    ) -> Option<&CachedConsensusValidator> {
+        self.get(epoch)?
+            .iter()
+            .find(|validator| &validator.address == address)
+    }
+
+    /// Drop all the cached validator sets.
+    pub fn invalidate(&mut self) {
+        self.sets.clear();
+    }
+
+    /// Drop the cached validator sets of the given epoch and all the epochs
+    /// after it.
+    pub fn invalidate_from(&mut self, epoch: Epoch) {
+        let _ = self.sets.split_off(&epoch);
+    }
+}
+
+/// Read the consensus validator set of the given epoch from storage.
+pub fn read_consensus_validator_set<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<CachedConsensusValidator>>
+where
+    S: StorageRead,
+{
+    consensus_validator_set_handle()
+        .at(&epoch)
+        .iter(storage)?
+        .map(|res| {
+            let (
+                NestedSubKey::Data {
+                    key: bonded_stake,
+                    nested_sub_key: SubKey::Data(position),
+                },
+                address,
+            ) = res?;
+            Ok(CachedConsensusValidator {
+                address,
+                bonded_stake,
+                position,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_consensus_validator_set_cache() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let epoch = Epoch::default();
+        let handle = consensus_validator_set_handle().at(&epoch);
+        let validator_1 = established_address_1();
+        let validator_2 = established_address_2();
+        handle.at(&token::Amount::from(1_u64)).insert(
+            &mut storage,
+            Position(0),
+            validator_1.clone(),
+        )?;
+        handle.at(&token::Amount::from(2_u64)).insert(
+            &mut storage,
+            Position(0),
+            validator_2.clone(),
+        )?;
+
+        let mut cache = ConsensusValidatorSetCache::default();
+        assert!(cache.get(epoch).is_none());
+        cache.populate(&storage, epoch)?;
+        let set = cache.get(epoch).unwrap();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set[0].address, validator_1);
+        assert_eq!(set[1].address, validator_2);
+        assert_eq!(
+            cache
+                .find_validator(epoch, &validator_2)
+                .unwrap()
+                .bonded_stake,
+            token::Amount::from(2_u64)
+        );
+
+        // A write to storage is not visible until the cache is invalidated
+        handle
+            .at(&token::Amount::from(1_u64))
+            .remove(&mut storage, &Position(0))?;
+        assert_eq!(cache.read(&storage, epoch)?.len(), 2);
+        cache.invalidate_from(epoch);
+        assert!(cache.get(epoch).is_none());
+        assert_eq!(cache.read(&storage, epoch)?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! In-memory batching of token amount updates to lazy maps. Unbonding,
+//! redelegation and slashing processing update many lazy map entries with
+//! small additions and subtractions, often touching the same entries several
+//! times. The batch accumulates these changes in memory and writes each
+//! touched entry only once when flushed.
+//!
+//! Note that the changes are not visible in storage until the batch is
+//! flushed, so the batched lazy maps must not be read in the meantime.
+
+use std::collections::BTreeMap;
+
+use namada_core::ledger::storage_api::collections::LazyMap;
+use namada_core::ledger::storage_api::{self, StorageRead, StorageWrite};
+use namada_core::types::storage::{Key, KeySeg};
+use namada_core::types::token;
+
+use crate::ArithmeticError;
+
+/// The accumulated change of a single lazy map entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct AmountUpdate {
+    added: token::Amount,
+    subtracted: token::Amount,
+}
+
+/// Batch of updates to token amounts stored in lazy maps, keyed by the
+/// storage keys of the updated entries.
+#[derive(Debug, Clone, Default)]
+pub struct AmountWriteBatch {
+    updates: BTreeMap<Key, AmountUpdate>,
+}
+
+impl AmountWriteBatch {
+    /// Add the given amount to the value at the given key of the map. A
+    /// missing value is treated as zero.
+    pub fn add<K>(
+        &mut self,
+        map: &LazyMap<K, token::Amount>,
+        key: &K,
+        amount: token::Amount,
+    ) -> storage_api::Result<()>
+    where
+        K: KeySeg,
+    {
+        let update = self.updates.entry(map.get_data_key(key)).or_default();
+        update.added = update
+            .added
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow("batched amount"))?;
+        Ok(())
+    }
+
+    /// Subtract the given amount from the value at the given key of the map.
+    /// A missing value is treated as zero.
+    pub fn sub<K>(
+        &mut self,
+        map: &LazyMap<K, token::Amount>,
+        key: &K,
+        amount: token::Amount,
+    ) -> storage_api::Result<()>
+    where
+        K: KeySeg,
+    {
+        let update = self.updates.entry(map.get_data_key(key)).or_default();
+        update.subtracted = update
+            .subtracted
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow("batched amount"))?;
+        Ok(())
+    }
+
+    /// Get the number of distinct entries that will be written on flush.
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Returns whether the batch has no pending updates.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Apply all the accumulated updates to storage, with a single read and
+    /// write per updated entry.
+    pub fn flush<S>(self, storage: &mut S) -> storage_api::Result<()>
+    where
+        S: StorageRead + StorageWrite,
+    {
+        for (key, AmountUpdate { added, subtracted }) in self.updates {
+            let current =
+                storage.read::<token::Amount>(&key)?.unwrap_or_default();
+            let new = current
+                .checked_add(added)
+                .ok_or(ArithmeticError::Overflow("batched amount"))?
+                .checked_sub(subtracted)
+                .ok_or(ArithmeticError::Underflow("batched amount"))?;
+            storage.write(&key, new)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::ledger::storage::testing::TestWlStorage;
+    use namada_core::ledger::storage_api::collections::LazyCollection;
+    use namada_core::types::storage::Epoch;
+
+    use super::*;
+
+    #[test]
+    fn test_amount_write_batch() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let map =
+            LazyMap::<Epoch, token::Amount>::open(Key::parse("test").unwrap());
+        map.insert(&mut storage, Epoch(0), token::Amount::from(10_u64))?;
+
+        let mut batch = AmountWriteBatch::default();
+        assert!(batch.is_empty());
+        batch.add(&map, &Epoch(0), token::Amount::from(5_u64))?;
+        batch.sub(&map, &Epoch(0), token::Amount::from(12_u64))?;
+        batch.add(&map, &Epoch(1), token::Amount::from(3_u64))?;
+        batch.add(&map, &Epoch(1), token::Amount::from(4_u64))?;
+        assert_eq!(batch.len(), 2);
+
+        // Nothing is written before the batch is flushed
+        assert_eq!(
+            map.get(&storage, &Epoch(0))?,
+            Some(token::Amount::from(10_u64))
+        );
+        assert_eq!(map.get(&storage, &Epoch(1))?, None);
+
+        batch.flush(&mut storage)?;
+        assert_eq!(
+            map.get(&storage, &Epoch(0))?,
+            Some(token::Amount::from(3_u64))
+        );
+        assert_eq!(
+            map.get(&storage, &Epoch(1))?,
+            Some(token::Amount::from(7_u64))
+        );
+
+        // Subtracting more than the stored amount fails on flush
+        let mut batch = AmountWriteBatch::default();
+        batch.sub(&map, &Epoch(1), token::Amount::from(8_u64))?;
+        assert!(batch.flush(&mut storage).is_err());
+
+        Ok(())
+    }
+}
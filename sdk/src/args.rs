@@ -968,6 +968,9 @@ pub struct Unbond<C: NamadaTypes = SdkTypes> {
     /// Source address for unbonding from delegations. For unbonding from
     /// self-bonds, the validator is also the source
     pub source: Option<C::Address>,
+    /// Withdraw the unbonded tokens automatically once they become
+    /// withdrawable
+    pub auto_withdraw: bool,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -1019,6 +1022,15 @@ impl<C: NamadaTypes> Unbond<C> {
         }
     }
 
+    /// Withdraw the unbonded tokens automatically once they become
+    /// withdrawable
+    pub fn auto_withdraw(self, auto_withdraw: bool) -> Self {
+        Self {
+            auto_withdraw,
+            ..self
+        }
+    }
+
     /// Path to the TX WASM code file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -1335,6 +1347,15 @@ pub struct QueryValidatorState<C: NamadaTypes = SdkTypes> {
     pub epoch: Option<Epoch>,
 }
 
+/// Check the setup of a validator at the pipeline epoch
+#[derive(Clone, Debug)]
+pub struct QueryValidatorSetup<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of a validator
+    pub validator: C::Address,
+}
+
 #[derive(Clone, Debug)]
 /// Commission rate change args
 pub struct CommissionRateChange<C: NamadaTypes = SdkTypes> {
@@ -1874,6 +1895,12 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub gas_limit: GasLimit,
     /// The optional expiration of the transaction
     pub expiration: Option<DateTimeUtc>,
+    /// The optional block height after which the transaction is expected to
+    /// expire
+    pub expiration_height: Option<storage::BlockHeight>,
+    /// The optional memo to attach to the transaction, of at most
+    /// [`crate::proto::MAX_MEMO_LEN`] bytes
+    pub tx_memo: Option<String>,
     /// Generate an ephimeral signing key to be used only once to sign a
     /// wrapper tx
     pub disposable_signing_key: bool,
@@ -1992,6 +2019,24 @@ pub trait TxBuilder<C: NamadaTypes>: Sized {
             ..x
         })
     }
+    /// The optional block height after which the transaction is expected to
+    /// expire
+    fn expiration_height(
+        self,
+        expiration_height: storage::BlockHeight,
+    ) -> Self {
+        self.tx(|x| Tx {
+            expiration_height: Some(expiration_height),
+            ..x
+        })
+    }
+    /// The optional memo to attach to the transaction
+    fn tx_memo(self, tx_memo: String) -> Self {
+        self.tx(|x| Tx {
+            tx_memo: Some(tx_memo),
+            ..x
+        })
+    }
     /// Generate an ephimeral signing key to be used only once to sign a
     /// wrapper tx
     fn disposable_signing_key(self, disposable_signing_key: bool) -> Self {
@@ -328,6 +328,18 @@ pub async fn sleep(dur: Duration) {
     internal_sleep(dur).await;
 }
 
+/// Run a future to completion, unless it takes longer than the given
+/// duration.
+#[inline]
+pub async fn timeout<F: Future>(
+    dur: Duration,
+    future: F,
+) -> Result<F::Output, Error> {
+    internal_timeout_at(Instant::now() + dur, future)
+        .await
+        .map_err(|_| Error::Elapsed)
+}
+
 #[cfg(target_family = "wasm")]
 #[allow(missing_docs)]
 mod internal {
@@ -4,8 +4,10 @@ use namada_core::proto::Tx;
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::ethereum_events::EthAddress;
+use namada_core::types::key::common;
 use namada_core::types::storage;
 use namada_core::types::storage::Epoch;
+use namada_core::types::transaction::TxErrorPayload;
 use prost::EncodeError;
 use tendermint_rpc::Error as RpcError;
 use thiserror::Error;
@@ -139,6 +141,24 @@ pub enum TxError {
     /// Error during broadcasting a transaction
     #[error("Encountered error while broadcasting transaction: {0}")]
     TxBroadcast(RpcError),
+    /// The expiration height of a tx has already been reached
+    #[error(
+        "The expiration height {0} must be above the last committed block \
+         height {1}"
+    )]
+    ExpirationHeightReached(storage::BlockHeight, storage::BlockHeight),
+    /// The memo of a tx is too long
+    #[error("The memo is {0} bytes long, but at most {1} bytes are allowed")]
+    MemoTooLong(usize, usize),
+    /// A tx without expiration can't be safely submitted again
+    #[error(
+        "The transaction has no expiration, so it can't be submitted again \
+         without risking that both copies get applied"
+    )]
+    MissingExpiration,
+    /// The transaction failed with a machine-readable error
+    #[error("The transaction failed with {}: {}", .0.kind, .0.message)]
+    Failed(TxErrorPayload),
     /// Invalid comission rate set
     #[error("Invalid new commission rate, received {0}")]
     InvalidCommissionRate(Dec),
@@ -321,6 +341,9 @@ pub enum TxError {
          still subject to possible slashing"
     )]
     IncomingRedelIsStillSlashable(Address, Address),
+    /// The target weights of a delegation rebalancing are invalid
+    #[error("Invalid delegation rebalancing target weights: {0}")]
+    InvalidRebalanceWeights(String),
     /// An empty string was provided as a new email
     #[error("An empty string cannot be provided as a new email")]
     InvalidEmail,
@@ -330,6 +353,24 @@ pub enum TxError {
     /// The consensus key is not unique
     #[error("The consensus key has already been registered and is not unique")]
     ConsensusKeyNotUnique,
+    /// The key is not one of the keys expected to sign the tx
+    #[error("The key {0} is not expected to sign the transaction")]
+    UnexpectedSigner(common::PublicKey),
+    /// The key is not that of the fee payer of the tx
+    #[error("The key {0} is not that of the transaction's fee payer")]
+    UnexpectedFeePayer(common::PublicKey),
+    /// The threshold of an account is out of range
+    #[error(
+        "The account threshold {0} must be between 1 and the number of public \
+         keys, {1}"
+    )]
+    InvalidAccountThreshold(u8, usize),
+    /// The same public key was given more than once for an account
+    #[error("The public key {0} was given more than once")]
+    DuplicateAccountKey(common::PublicKey),
+    /// A signature of the tx is invalid
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
     /// Other Errors that may show up when using the interface
     #[error("{0}")]
     Other(String),
@@ -353,9 +394,15 @@ pub enum EthereumBridgeError {
     /// Error querying transfer to Ethereum progress.
     #[error("Failed to query transfer to Ethereum progress: {0}")]
     TransferToEthProgress(String),
+    /// Error querying the status of a transfer to Ethereum.
+    #[error("Failed to query the status of a transfer to Ethereum: {0}")]
+    TransferToEthStatus(String),
     /// Error querying Ethereum voting powers.
     #[error("Failed to query Ethereum voting powers: {0}")]
     QueryVotingPowers(String),
+    /// Error querying the status of the Ethereum oracle.
+    #[error("Failed to query the status of the Ethereum oracle: {0}")]
+    QueryOracleStatus(String),
     /// Ethereum node timeout error.
     #[error(
         "Timed out while attempting to communicate with the Ethereum node"
@@ -385,6 +432,12 @@ pub enum EthereumBridgeError {
     /// Transfer already in pool error.
     #[error("An identical transfer is already present in the Bridge pool")]
     TransferAlreadyInPool,
+    /// Error reading the ERC20 token whitelist.
+    #[error("Failed to read the ERC20 token whitelist: {0}")]
+    ReadErc20Whitelist(String),
+    /// Empty ERC20 token whitelist update error.
+    #[error("No ERC20 token whitelist update was provided")]
+    EmptyWhitelistUpdate,
 }
 
 /// Checks if the given error is an invalid viewing key
@@ -36,7 +36,7 @@ use crate::io::Io;
 use crate::proto::Tx;
 use crate::queries::{
     Client, GenBridgePoolProofReq, GenBridgePoolProofRsp, TransferToErcArgs,
-    RPC,
+    TransferToEthereumStatus, RPC,
 };
 use crate::rpc::{query_storage_value, query_wasm_code_hash, validate_amount};
 use crate::signing::aux_signing_data;
@@ -416,6 +416,23 @@ pub async fn query_relay_progress(
     Ok(())
 }
 
+/// Query the status of the transfer to Ethereum with the given hash,
+/// along its lifecycle in the Bridge pool.
+pub async fn query_transfer_status(
+    client: &(impl Client + Sync),
+    hash: KeccakHash,
+) -> Result<TransferToEthereumStatus, Error> {
+    RPC.shell()
+        .eth_bridge()
+        .transfer_to_ethereum_status(client, &hash)
+        .await
+        .map_err(|e| {
+            Error::EthereumBridge(EthereumBridgeError::TransferToEthStatus(
+                e.to_string(),
+            ))
+        })
+}
+
 /// Internal methdod to construct a proof that a set of transfers are in the
 /// bridge pool.
 async fn construct_bridge_pool_proof(
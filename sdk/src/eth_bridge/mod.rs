@@ -2,6 +2,7 @@
 
 pub mod bridge_pool;
 pub mod validator_set;
+pub mod whitelist;
 
 use std::ops::ControlFlow;
 
@@ -21,6 +22,7 @@ use crate::control_flow::time::{
 };
 use crate::error::{Error, EthereumBridgeError};
 use crate::io::Io;
+use crate::queries::{Client, EthereumOracleStatus, RPC};
 use crate::{display_line, edisplay_line};
 
 const DEFAULT_BACKOFF: Duration = std::time::Duration::from_millis(500);
@@ -184,3 +186,19 @@ where
     .await?;
     Ok(())
 }
+
+/// Query the health of the Ethereum oracle of the node the client is
+/// connected to. Returns [`None`] if the node is not running an oracle.
+pub async fn query_oracle_status(
+    client: &(impl Client + Sync),
+) -> Result<Option<EthereumOracleStatus>, Error> {
+    RPC.shell()
+        .eth_bridge()
+        .read_oracle_status(client)
+        .await
+        .map_err(|e| {
+            Error::EthereumBridge(EthereumBridgeError::QueryOracleStatus(
+                e.to_string(),
+            ))
+        })
+}
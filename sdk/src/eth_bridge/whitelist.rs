@@ -0,0 +1,77 @@
+//! Management of the ERC20 token whitelist of the Ethereum bridge.
+//!
+//! The whitelist can only be updated by governance: a default proposal
+//! whose code is the [`TX_UPDATE_ERC20_WHITELIST_WASM`] tx applies the
+//! [`WhitelistUpdate`]s carried by the proposal content once it passes.
+
+pub use namada_core::ledger::eth_bridge::storage::whitelist::WhitelistUpdate;
+use namada_core::ledger::eth_bridge::storage::whitelist::PROPOSAL_CONTENT_KEY;
+use namada_core::ledger::governance::cli::onchain::{
+    DefaultProposal, OnChainProposal,
+};
+use namada_core::types::ethereum_events::EthAddress;
+use namada_core::types::token::DenominatedAmount;
+
+use crate::error::{EncodingError, Error, EthereumBridgeError};
+pub use crate::queries::Erc20WhitelistStatus;
+use crate::queries::{Client, RPC};
+
+/// The proposal code applying the ERC20 token whitelist updates carried by
+/// the content of a governance proposal.
+pub const TX_UPDATE_ERC20_WHITELIST_WASM: &str =
+    "tx_update_erc20_whitelist.wasm";
+
+/// Whitelist an ERC20 token with the given cap, whose denomination is that
+/// of the token. The default minimum number of confirmations of the bridge
+/// applies to its transfers.
+pub fn add_erc20(token: EthAddress, cap: DenominatedAmount) -> WhitelistUpdate {
+    WhitelistUpdate::Add {
+        token,
+        cap: cap.amount,
+        denom: cap.denom,
+        min_confirmations: None,
+    }
+}
+
+/// Remove an ERC20 token from the whitelist.
+pub fn remove_erc20(token: EthAddress) -> WhitelistUpdate {
+    WhitelistUpdate::Remove { token }
+}
+
+/// Build a default governance proposal applying the given whitelist
+/// updates once it passes. The `proposal_code` must be the contents of the
+/// [`TX_UPDATE_ERC20_WHITELIST_WASM`] file, and the updates are added to
+/// the proposal content, such that voters can review them.
+pub fn build_whitelist_proposal(
+    mut proposal: OnChainProposal,
+    updates: &[WhitelistUpdate],
+    proposal_code: Vec<u8>,
+) -> Result<DefaultProposal, Error> {
+    if updates.is_empty() {
+        return Err(EthereumBridgeError::EmptyWhitelistUpdate.into());
+    }
+    let updates = serde_json::to_string(updates)
+        .map_err(|e| EncodingError::Serde(e.to_string()))?;
+    proposal
+        .content
+        .insert(PROPOSAL_CONTENT_KEY.to_string(), updates);
+    Ok(DefaultProposal {
+        proposal,
+        data: Some(proposal_code),
+    })
+}
+
+/// Query the state of every ERC20 token ever added to the whitelist,
+/// including its cap and the supply of its wrapped asset. The removed
+/// tokens are reported as not whitelisted.
+pub async fn query_whitelist(
+    client: &(impl Client + Sync),
+) -> Result<Vec<Erc20WhitelistStatus>, Error> {
+    RPC.shell()
+        .eth_bridge()
+        .read_erc20_whitelist(client)
+        .await
+        .map_err(|e| {
+            Error::from(EthereumBridgeError::ReadErc20Whitelist(e.to_string()))
+        })
+}
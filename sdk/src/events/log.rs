@@ -33,7 +33,7 @@ impl Default for Params {
 
 /// Represents a log of [`Event`] instances emitted by
 /// `FinalizeBlock` calls, in the ledger.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EventLog {
     queue: CircularQueue<Event>,
 }
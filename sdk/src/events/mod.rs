@@ -52,6 +52,14 @@ pub enum EventType {
     Proposal,
     /// The pgf payment
     PgfPayment,
+    /// The PoS inflation minted for an epoch
+    PosInflation,
+    /// The PoS rewards of a validator for an epoch
+    PosRewards,
+    /// The storage I/O of a PoS operation in a block
+    PosIo,
+    /// A change of a validator's state
+    ValidatorState,
 }
 
 impl Display for EventType {
@@ -62,6 +70,10 @@ impl Display for EventType {
             EventType::Ibc(t) => write!(f, "{}", t),
             EventType::Proposal => write!(f, "proposal"),
             EventType::PgfPayment => write!(f, "pgf_payment"),
+            EventType::PosInflation => write!(f, "pos_inflation"),
+            EventType::PosRewards => write!(f, "pos_rewards"),
+            EventType::PosIo => write!(f, "pos_io"),
+            EventType::ValidatorState => write!(f, "validator_state"),
         }?;
         Ok(())
     }
@@ -76,6 +88,10 @@ impl FromStr for EventType {
             "applied" => Ok(EventType::Applied),
             "proposal" => Ok(EventType::Proposal),
             "pgf_payments" => Ok(EventType::PgfPayment),
+            "pos_inflation" => Ok(EventType::PosInflation),
+            "pos_rewards" => Ok(EventType::PosRewards),
+            "pos_io" => Ok(EventType::PosIo),
+            "validator_state" => Ok(EventType::ValidatorState),
             // IBC
             "update_client" => Ok(EventType::Ibc("update_client".to_string())),
             "send_packet" => Ok(EventType::Ibc("send_packet".to_string())),
@@ -88,8 +104,8 @@ impl FromStr for EventType {
 }
 
 impl Event {
-    /// Creates a new event with the hash and height of the transaction
-    /// already filled in
+    /// Creates a new event with the hash, height and memo, if any, of the
+    /// transaction already filled in
     pub fn new_tx_event(tx: &crate::proto::Tx, height: u64) -> Self {
         let mut event = match tx.header().tx_type {
             TxType::Wrapper(_) => {
@@ -127,6 +143,9 @@ impl Event {
         };
         event["height"] = height.to_string();
         event["log"] = "".to_string();
+        if let Some(memo) = tx.memo() {
+            event["memo"] = memo.to_string();
+        }
         event
     }
 
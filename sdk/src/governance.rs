@@ -0,0 +1,378 @@
+//! A high-level client for the lifecycle of governance proposals.
+//!
+//! The [`GovernanceClient`] builds the arguments of the txs submitting
+//! default and PGF proposals and voting on them, then constructs, signs and
+//! submits them through the [`crate::tx`] functions. Each tx can be
+//! customized before submission with the builder methods of
+//! [`GovernanceTx`]. The client can also query the state of a proposal and
+//! tally its votes.
+
+use std::collections::HashMap;
+
+use namada_core::ledger::governance::cli::onchain::{
+    DefaultProposal, PgfFundingProposal, PgfStewardProposal, ProposalVote,
+};
+use namada_core::ledger::governance::storage::keys as governance_storage;
+use namada_core::ledger::governance::storage::proposal::StorageProposal;
+use namada_core::ledger::governance::utils::{
+    compute_proposal_result, ProposalResult, ProposalStatus, ProposalVotes,
+    TallyVote, VotePower,
+};
+use namada_core::types::address::Address;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use serde::Serialize;
+
+use crate::args::{self, SdkTypes, TxBuilder};
+use crate::error::{EncodingError, Error, Result};
+use crate::staking::{is_applied, sign_and_submit};
+use crate::tx::ProcessTxResponse;
+use crate::{rpc, Namada};
+
+/// A client submitting governance txs on behalf of the wallet of a
+/// [`Namada`] context
+pub struct GovernanceClient<'a, N: Namada> {
+    namada: &'a N,
+}
+
+impl<'a, N: Namada> GovernanceClient<'a, N> {
+    /// Create a governance client using the given context
+    pub fn new(namada: &'a N) -> Self {
+        Self { namada }
+    }
+
+    /// Submit a default proposal, optionally carrying the wasm code to
+    /// execute if it passes
+    pub fn propose(
+        &self,
+        proposal: &DefaultProposal,
+    ) -> Result<GovernanceTx<'a, N, args::InitProposal>> {
+        Ok(GovernanceTx::new(
+            self.namada,
+            self.namada.new_init_proposal(encode_proposal(proposal)?),
+        ))
+    }
+
+    /// Submit a proposal updating the set of PGF stewards
+    pub fn propose_pgf_stewards(
+        &self,
+        proposal: &PgfStewardProposal,
+    ) -> Result<GovernanceTx<'a, N, args::InitProposal>> {
+        let args = self
+            .namada
+            .new_init_proposal(encode_proposal(proposal)?)
+            .is_pgf_stewards(true);
+        Ok(GovernanceTx::new(self.namada, args))
+    }
+
+    /// Submit a proposal funding PGF targets, continuously or retroactively
+    pub fn propose_pgf_funding(
+        &self,
+        proposal: &PgfFundingProposal,
+    ) -> Result<GovernanceTx<'a, N, args::InitProposal>> {
+        let args = self
+            .namada
+            .new_init_proposal(encode_proposal(proposal)?)
+            .is_pgf_funding(true);
+        Ok(GovernanceTx::new(self.namada, args))
+    }
+
+    /// Vote on the proposal with the given id. The vote of a delegator is
+    /// weighted by its delegations at the start of the voting period, that
+    /// of a validator by its stake.
+    pub fn vote(
+        &self,
+        proposal_id: u64,
+        vote: ProposalVote,
+        voter: Address,
+    ) -> GovernanceTx<'a, N, args::VoteProposal> {
+        let args = self
+            .namada
+            .new_vote_prposal(vote_to_string(&vote), voter)
+            .proposal_id(proposal_id);
+        GovernanceTx::new(self.namada, args)
+    }
+
+    /// Query the proposal with the given id along with its status. The
+    /// result of the proposal is included once its voting period has ended.
+    pub async fn query_proposal(
+        &self,
+        proposal_id: u64,
+    ) -> Result<Option<ProposalState>> {
+        let client = self.namada.client();
+        let Some(proposal) =
+            rpc::query_proposal_by_id(client, proposal_id).await?
+        else {
+            return Ok(None);
+        };
+        let current_epoch = rpc::query_epoch(client).await?;
+        let status = proposal.get_status(current_epoch);
+        let result = match status {
+            ProposalStatus::Ended => {
+                Some(query_proposal_result(client, &proposal).await?)
+            }
+            ProposalStatus::Pending | ProposalStatus::OnGoing => None,
+        };
+        Ok(Some(ProposalState {
+            proposal,
+            status,
+            result,
+        }))
+    }
+
+    /// Tally the votes of the proposal with the given id. The result of an
+    /// ended proposal is read from storage if it was already tallied by the
+    /// ledger, otherwise the votes cast so far are tallied against the stake
+    /// at the end of the voting period, or at the current epoch if it is
+    /// still on-going.
+    pub async fn tally(
+        &self,
+        proposal_id: u64,
+    ) -> Result<Option<ProposalResult>> {
+        let client = self.namada.client();
+        let Some(proposal) =
+            rpc::query_proposal_by_id(client, proposal_id).await?
+        else {
+            return Ok(None);
+        };
+        query_proposal_result(client, &proposal).await.map(Some)
+    }
+
+    /// Query the voting power `voter` would vote on the given proposal with,
+    /// i.e. its stake if it is a validator or the sum of its delegations
+    /// otherwise, at the start of the voting period
+    pub async fn voting_power(
+        &self,
+        proposal_id: u64,
+        voter: &Address,
+    ) -> Result<VotePower> {
+        let client = self.namada.client();
+        let proposal = rpc::query_proposal_by_id(client, proposal_id)
+            .await?
+            .ok_or_else(|| {
+                Error::from(crate::error::TxError::ProposalDoesNotExist(
+                    proposal_id,
+                ))
+            })?;
+        voting_power_at(client, voter, proposal.voting_start_epoch).await
+    }
+}
+
+/// A proposal as stored on chain, along with its current status
+pub struct ProposalState {
+    /// The proposal
+    pub proposal: StorageProposal,
+    /// The status of the proposal at the current epoch
+    pub status: ProposalStatus,
+    /// The result of the proposal, if its voting period has ended
+    pub result: Option<ProposalResult>,
+}
+
+/// A governance tx ready to be customized and submitted
+pub struct GovernanceTx<'a, N: Namada, A> {
+    namada: &'a N,
+    args: A,
+}
+
+impl<'a, N: Namada, A> GovernanceTx<'a, N, A> {
+    fn new(namada: &'a N, args: A) -> Self {
+        Self { namada, args }
+    }
+
+    /// Apply the given function to the arguments of the tx
+    pub fn with<F>(self, func: F) -> Self
+    where
+        F: FnOnce(A) -> A,
+    {
+        Self {
+            args: func(self.args),
+            ..self
+        }
+    }
+
+    /// The arguments the tx will be built from
+    pub fn args(&self) -> &A {
+        &self.args
+    }
+}
+
+impl<'a, N: Namada, A> TxBuilder<SdkTypes> for GovernanceTx<'a, N, A>
+where
+    A: TxBuilder<SdkTypes>,
+{
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(args::Tx<SdkTypes>) -> args::Tx<SdkTypes>,
+    {
+        self.with(|args| args.tx(func))
+    }
+}
+
+/// The result of submitting a proposal
+#[derive(Debug)]
+pub struct ProposeResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+}
+
+/// The result of submitting a vote
+#[derive(Debug)]
+pub struct VoteResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+    /// The voting power of the vote. Only known if the tx was applied.
+    pub voting_power: Option<VotePower>,
+}
+
+impl<'a, N: Namada> GovernanceTx<'a, N, args::InitProposal> {
+    /// Validate, build, sign and submit the proposal tx
+    pub async fn submit(self) -> Result<ProposeResult> {
+        let (tx, signing_data, _epoch) = self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+        Ok(ProposeResult { response })
+    }
+}
+
+impl<'a, N: Namada> GovernanceTx<'a, N, args::VoteProposal> {
+    /// Build, sign and submit the vote tx. The delegations of the voter at
+    /// the start of the voting period are looked up to be voted with.
+    pub async fn submit(self) -> Result<VoteResult> {
+        let (tx, signing_data, _epoch) = self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+
+        let voting_power = match self.args.proposal_id {
+            Some(proposal_id) if is_applied(&response) => Some(
+                GovernanceClient::new(self.namada)
+                    .voting_power(proposal_id, &self.args.voter)
+                    .await?,
+            ),
+            _ => None,
+        };
+        Ok(VoteResult {
+            response,
+            voting_power,
+        })
+    }
+}
+
+/// Query the result of the given proposal, or tally its votes if the ledger
+/// hasn't done so yet
+async fn query_proposal_result<C: crate::queries::Client + Sync>(
+    client: &C,
+    proposal: &StorageProposal,
+) -> Result<ProposalResult> {
+    let result_key = governance_storage::get_proposal_result_key(proposal.id);
+    if let Ok(result) = rpc::query_storage_value(client, &result_key).await {
+        return Ok(result);
+    }
+    let current_epoch = rpc::query_epoch(client).await?;
+    let tally_epoch = proposal.voting_end_epoch.min(current_epoch);
+    let is_author_steward = rpc::is_steward(client, &proposal.author).await;
+    let tally_type = proposal.get_tally_type(is_author_steward);
+    let total_voting_power =
+        rpc::get_total_staked_tokens(client, tally_epoch).await?;
+    let votes = query_proposal_votes(client, proposal.id, tally_epoch).await?;
+    Ok(compute_proposal_result(
+        votes,
+        total_voting_power,
+        tally_type,
+    ))
+}
+
+/// Query the votes cast on the given proposal, weighted by the stake of the
+/// voters at the given epoch
+pub async fn query_proposal_votes<C: crate::queries::Client + Sync>(
+    client: &C,
+    proposal_id: u64,
+    epoch: Epoch,
+) -> Result<ProposalVotes> {
+    let mut validators_vote: HashMap<Address, TallyVote> = HashMap::default();
+    let mut validator_voting_power: HashMap<Address, VotePower> =
+        HashMap::default();
+    let mut delegators_vote: HashMap<Address, TallyVote> = HashMap::default();
+    let mut delegator_voting_power: HashMap<
+        Address,
+        HashMap<Address, VotePower>,
+    > = HashMap::default();
+
+    for vote in rpc::query_proposal_votes(client, proposal_id).await? {
+        if vote.is_validator() {
+            let validator_stake =
+                rpc::get_validator_stake(client, epoch, &vote.validator)
+                    .await?;
+            validators_vote.insert(vote.validator.clone(), vote.data.into());
+            validator_voting_power.insert(vote.validator, validator_stake);
+        } else {
+            let delegator_stake = rpc::get_bond_amount_at(
+                client,
+                &vote.delegator,
+                &vote.validator,
+                epoch,
+            )
+            .await?;
+            delegators_vote.insert(vote.delegator.clone(), vote.data.into());
+            delegator_voting_power
+                .entry(vote.delegator)
+                .or_default()
+                .insert(vote.validator, delegator_stake);
+        }
+    }
+
+    Ok(ProposalVotes {
+        validators_vote,
+        validator_voting_power,
+        delegators_vote,
+        delegator_voting_power,
+    })
+}
+
+/// The voting power of `voter` at the given epoch
+async fn voting_power_at<C: crate::queries::Client + Sync>(
+    client: &C,
+    voter: &Address,
+    epoch: Epoch,
+) -> Result<VotePower> {
+    if rpc::is_validator(client, voter).await? {
+        rpc::get_validator_stake(client, epoch, voter).await
+    } else {
+        Ok(rpc::get_delegators_delegation_at(client, voter, epoch)
+            .await?
+            .into_values()
+            .fold(token::Amount::zero(), |sum, amount| sum + amount))
+    }
+}
+
+/// Encode a proposal as the JSON data expected by [`args::InitProposal`]
+fn encode_proposal(proposal: &impl Serialize) -> Result<Vec<u8>> {
+    serde_json::to_vec(proposal)
+        .map_err(|err| Error::from(EncodingError::Serde(err.to_string())))
+}
+
+/// The representation of a vote expected by [`args::VoteProposal`]
+fn vote_to_string(vote: &ProposalVote) -> String {
+    match vote {
+        ProposalVote::Yay => "yay",
+        ProposalVote::Nay => "nay",
+        ProposalVote::Abstain => "abstain",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod test_governance {
+    use super::*;
+
+    /// Test that the votes are encoded the way the vote tx parses them
+    #[test]
+    fn test_vote_roundtrip() {
+        for vote in
+            [ProposalVote::Yay, ProposalVote::Nay, ProposalVote::Abstain]
+        {
+            assert_eq!(ProposalVote::try_from(vote_to_string(&vote)), Ok(vote));
+        }
+    }
+}
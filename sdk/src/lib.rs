@@ -13,8 +13,11 @@ pub mod eth_bridge;
 pub mod rpc;
 
 pub mod args;
+pub mod governance;
 pub mod masp;
+pub mod multisig;
 pub mod signing;
+pub mod staking;
 #[allow(clippy::result_large_err)]
 pub mod tx;
 
@@ -136,6 +139,8 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             fee_unshield: None,
             gas_limit: GasLimit::from(20_000),
             expiration: None,
+            expiration_height: None,
+            tx_memo: None,
             disposable_signing_key: false,
             chain_id: None,
             signing_keys: vec![],
@@ -214,6 +219,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             validator,
             amount,
             source: None,
+            auto_withdraw: false,
             tx: self.tx_builder(),
             tx_code_path: PathBuf::from(TX_UNBOND_WASM),
         }
@@ -653,6 +659,8 @@ where
                 fee_unshield: None,
                 gas_limit: GasLimit::from(20_000),
                 expiration: None,
+                expiration_height: None,
+                tx_memo: None,
                 disposable_signing_key: false,
                 chain_id: None,
                 signing_keys: vec![],
@@ -3,7 +3,7 @@
 use std::collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
 use std::fmt::Debug;
-use std::ops::Deref;
+use std::ops::{Deref, Range, RangeBounds};
 use std::path::PathBuf;
 
 // use async_std::io::prelude::WriteExt;
@@ -50,6 +50,7 @@ use masp_proofs::bls12_381::Bls12;
 use masp_proofs::prover::LocalTxProver;
 use masp_proofs::sapling::SaplingVerificationContext;
 use namada_core::types::address::{Address, MASP};
+use namada_core::types::hash::Hash;
 use namada_core::types::masp::{
     BalanceOwner, ExtendedViewingKey, PaymentAddress, TransferSource,
     TransferTarget,
@@ -98,6 +99,10 @@ const NETWORK: MainNetwork = MainNetwork;
 #[cfg(not(feature = "mainnet"))]
 const NETWORK: TestNetwork = TestNetwork;
 
+/// The number of shielded transactions fetched and scanned between two
+/// checkpoints of the shielded context
+const FETCH_CHECKPOINT_INTERVAL: u64 = 100;
+
 // TODO these could be exported from masp_proof crate
 /// Spend circuit name
 pub const SPEND_NAME: &str = "masp-spend.params";
@@ -148,6 +153,7 @@ pub struct PVKs {
     output_vk: PreparedVerifyingKey<Bls12>,
 }
 
+#[cfg(not(target_family = "wasm"))]
 lazy_static! {
     /// MASP verifying keys load from parameters
     static ref VERIFIYING_KEYS: PVKs =
@@ -187,14 +193,52 @@ lazy_static! {
 }
 
 /// Make sure the MASP params are present and load verifying keys into memory
+#[cfg(not(target_family = "wasm"))]
 pub fn preload_verifying_keys() -> &'static PVKs {
     &VERIFIYING_KEYS
 }
 
+#[cfg(not(target_family = "wasm"))]
 fn load_pvks() -> &'static PVKs {
     &VERIFIYING_KEYS
 }
 
+/// MASP verifying keys, loaded from parameters supplied by the host since
+/// there is no filesystem on wasm targets
+#[cfg(target_family = "wasm")]
+static VERIFIYING_KEYS: std::sync::OnceLock<PVKs> = std::sync::OnceLock::new();
+
+/// Load the verifying keys into memory from the bytes of the spend, output
+/// and convert MASP params, e.g. fetched by a browser. This must be done
+/// before any shielded tx is verified. The params are only parsed once.
+#[cfg(target_family = "wasm")]
+pub fn preload_verifying_keys(
+    spend_params: &[u8],
+    output_params: &[u8],
+    convert_params: &[u8],
+) -> &'static PVKs {
+    VERIFIYING_KEYS.get_or_init(|| {
+        // blake2b checked here
+        let params = masp_proofs::parse_parameters(
+            spend_params,
+            output_params,
+            convert_params,
+        );
+        PVKs {
+            spend_vk: params.spend_vk,
+            convert_vk: params.convert_vk,
+            output_vk: params.output_vk,
+        }
+    })
+}
+
+#[cfg(target_family = "wasm")]
+fn load_pvks() -> &'static PVKs {
+    VERIFIYING_KEYS
+        .get()
+        .expect("The MASP verifying keys must be preloaded on wasm targets")
+}
+
 /// check_spend wrapper
 pub fn check_spend(
     spend: &SpendDescription<<Authorized as Authorization>::SaplingAuth>,
@@ -585,12 +629,86 @@ impl From<MaspAmount> for I128Sum {
 pub type Conversions =
     BTreeMap<AssetType, (AllowedConversion, MerklePath<Node>, i128)>;
 
+/// An allowed conversion of the MASP conversion tree, with its asset type
+/// decoded
+#[derive(Debug, Clone)]
+pub struct AssetConversion {
+    /// The asset type converted by this conversion
+    pub asset_type: AssetType,
+    /// The token of the converted asset type
+    pub token: Address,
+    /// The epoch of the converted asset type
+    pub epoch: Epoch,
+    /// The components of the conversion, which always sum up to zero: the
+    /// converted asset type with a negative value, the asset type of the
+    /// latest epoch and the reward asset types with positive values
+    pub conversion: I128Sum,
+}
+
+/// Query the conversions of the MASP conversion tree, optionally restricted
+/// to the given token and epoch. The conversions that merely decode an asset
+/// type (i.e. with no components) are left out.
+pub async fn query_conversion_tree<C: Client + Sync>(
+    client: &C,
+    token: Option<&Address>,
+    epoch: Option<Epoch>,
+) -> Result<Vec<AssetConversion>, Error> {
+    let conversions = rpc::query_conversions(client).await?;
+    Ok(conversions
+        .into_iter()
+        .filter(|(_, (addr, conv_epoch, conv))| {
+            !conv.is_zero()
+                && token.map_or(true, |token| token == addr)
+                && epoch.map_or(true, |epoch| epoch == *conv_epoch)
+        })
+        .map(|(asset_type, (token, epoch, conversion))| AssetConversion {
+            asset_type,
+            token,
+            epoch,
+            conversion,
+        })
+        .collect())
+}
+
 /// Represents the changes that were made to a list of transparent accounts
 pub type TransferDelta = HashMap<Address, MaspChange>;
 
 /// Represents the changes that were made to a list of shielded accounts
 pub type TransactionDelta = HashMap<ViewingKey, MaspAmount>;
 
+/// Whether a note was received or spent by a viewing key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteDirection {
+    /// The note was sent to the viewing key
+    Incoming,
+    /// The note was spent by the viewing key
+    Outgoing,
+}
+
+/// A note received or spent by a viewing key, as found in its shielded
+/// history
+#[derive(Debug, Clone)]
+pub struct ShieldedHistoryEntry {
+    /// The height of the block containing the transaction
+    pub height: BlockHeight,
+    /// The index of the transaction in its block
+    pub index: TxIndex,
+    /// The epoch in which the transaction was applied
+    pub epoch: Epoch,
+    /// The hash of the shielded section of the transaction, if known
+    pub tx_hash: Option<Hash>,
+    /// Whether the note was received or spent
+    pub direction: NoteDirection,
+    /// The position of the note in the commitment tree
+    pub note_pos: usize,
+    /// The raw asset type of the note
+    pub asset_type: AssetType,
+    /// The value of the note, decoded into token amounts
+    pub amount: MaspAmount,
+    /// The memo attached to the note
+    pub memo: Option<MemoBytes>,
+}
+
 /// Represents the current state of the shielded pool from the perspective of
 /// the chosen viewing keys.
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -612,7 +730,8 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub memo_map: HashMap<usize, MemoBytes>,
     /// Maps note positions to the diversifier of their payment address
     pub div_map: HashMap<usize, Diversifier>,
-    /// Maps note positions to their witness (used to make merkle paths)
+    /// Maps the positions of our unspent notes to their witness (used to
+    /// make merkle paths)
     pub witness_map: HashMap<usize, IncrementalWitness<Node>>,
     /// Tracks what each transaction does to various account balances
     pub delta_map: BTreeMap<
@@ -625,6 +744,13 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub asset_types: HashMap<AssetType, (Address, MaspDenom, Epoch)>,
     /// Maps note positions to their corresponding viewing keys
     pub vk_map: HashMap<usize, ViewingKey>,
+    /// Maps note positions to the transaction that created them
+    pub note_tx_map: HashMap<usize, (BlockHeight, TxIndex)>,
+    /// Maps the positions of spent notes to the transaction that spent them
+    pub spent_tx_map: HashMap<usize, (BlockHeight, TxIndex)>,
+    /// Maps the transactions affecting our notes to the hash of their
+    /// shielded section
+    pub tx_hash_map: HashMap<(BlockHeight, TxIndex), Hash>,
 }
 
 /// Default implementation to ease construction of TxContexts. Derive cannot be
@@ -645,6 +771,9 @@ impl<U: ShieldedUtils + Default> Default for ShieldedContext<U> {
             delta_map: BTreeMap::default(),
             asset_types: HashMap::default(),
             vk_map: HashMap::default(),
+            note_tx_map: HashMap::default(),
+            spent_tx_map: HashMap::default(),
+            tx_hash_map: HashMap::default(),
         }
     }
 }
@@ -677,6 +806,9 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         self.spents.extend(new_ctx.spents);
         self.asset_types.extend(new_ctx.asset_types);
         self.vk_map.extend(new_ctx.vk_map);
+        self.note_tx_map.extend(new_ctx.note_tx_map);
+        self.spent_tx_map.extend(new_ctx.spent_tx_map);
+        self.tx_hash_map.extend(new_ctx.tx_hash_map);
         // The deltas are the exception because different keys can reveal
         // different parts of the same transaction. Hence each delta needs to be
         // merged separately.
@@ -716,11 +848,13 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
 
         // If unknown keys are being used, we need to scan older transactions
         // for any unspent notes
-        let (txs, mut tx_iter);
         if !unknown_keys.is_empty() {
-            // Load all transactions accepted until this point
-            txs = Self::fetch_shielded_transfers(client, 0).await?;
-            tx_iter = txs.iter();
+            // Load all transactions scanned by this context until this point
+            let txs = Self::fetch_shielded_transfers_range(
+                client,
+                0..self.last_txidx,
+            )
+            .await?;
             // Do this by constructing a shielding context only for unknown keys
             let mut tx_ctx = Self {
                 utils: self.utils.clone(),
@@ -730,33 +864,58 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                 tx_ctx.pos_map.entry(vk).or_insert_with(BTreeSet::new);
             }
             // Update this unknown shielded context until it is level with self
-            while tx_ctx.last_txidx != self.last_txidx {
-                if let Some(((height, idx), (epoch, tx, stx))) = tx_iter.next()
-                {
-                    tx_ctx
-                        .scan_tx(client, *height, *idx, *epoch, tx, stx)
-                        .await?;
-                } else {
-                    break;
-                }
+            for ((height, idx), (epoch, tx, stx)) in &txs {
+                tx_ctx
+                    .scan_tx(client, *height, *idx, *epoch, tx, stx)
+                    .await?;
             }
             // Merge the context data originating from the unknown keys into the
             // current context
             self.merge(tx_ctx);
-        } else {
-            // Load only transactions accepted from last_txid until this point
-            txs =
-                Self::fetch_shielded_transfers(client, self.last_txidx).await?;
-            tx_iter = txs.iter();
         }
         // Now that we possess the unspent notes corresponding to both old and
-        // new keys up until tx_pos, proceed to scan the new transactions.
-        for ((height, idx), (epoch, tx, stx)) in &mut tx_iter {
-            self.scan_tx(client, *height, *idx, *epoch, tx, stx).await?;
+        // new keys up until tx_pos, proceed to scan the new transactions. This
+        // is done in batches, checkpointing the context after each of them so
+        // that an interrupted fetch resumes from the last checkpoint.
+        let head_txidx = Self::query_head_txidx(client).await?;
+        while self.last_txidx < head_txidx {
+            let batch_end =
+                head_txidx.min(self.last_txidx + FETCH_CHECKPOINT_INTERVAL);
+            let txs = Self::fetch_shielded_transfers_range(
+                client,
+                self.last_txidx..batch_end,
+            )
+            .await?;
+            if txs.is_empty() {
+                break;
+            }
+            for ((height, idx), (epoch, tx, stx)) in &txs {
+                self.scan_tx(client, *height, *idx, *epoch, tx, stx).await?;
+            }
+            let _ = self.save().await;
         }
         Ok(())
     }
 
+    /// Query the index that the next accepted shielded transaction will be
+    /// stored at, i.e. the number of accepted shielded transactions.
+    pub async fn query_head_txidx<C: Client + Sync>(
+        client: &C,
+    ) -> Result<u64, Error> {
+        // The address of the MASP account
+        let masp_addr = MASP;
+        // Construct the key where last transaction pointer is stored
+        let head_tx_key = Key::from(masp_addr.to_db_key())
+            .push(&HEAD_TX_KEY.to_owned())
+            .map_err(|k| {
+                Error::Other(format!("Cannot obtain a storage key: {}", k))
+            })?;
+        // Query for the index of the last accepted transaction
+        Ok(query_storage_value::<C, u64>(client, &head_tx_key)
+            .await
+            .unwrap_or(0))
+    }
+
     /// Obtain a chronologically-ordered list of all accepted shielded
     /// transactions from the ledger. The ledger conceptually stores
     /// transactions as a vector. More concretely, the HEAD_TX_KEY location
@@ -768,22 +927,26 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
     ) -> Result<
         BTreeMap<(BlockHeight, TxIndex), (Epoch, Transfer, Transaction)>,
         Error,
+    > {
+        let head_txidx = Self::query_head_txidx(client).await?;
+        Self::fetch_shielded_transfers_range(client, last_txidx..head_txidx)
+            .await
+    }
+
+    /// Obtain a chronologically-ordered list of the accepted shielded
+    /// transactions whose indices fall in the given range.
+    pub async fn fetch_shielded_transfers_range<C: Client + Sync>(
+        client: &C,
+        txidx_range: Range<u64>,
+    ) -> Result<
+        BTreeMap<(BlockHeight, TxIndex), (Epoch, Transfer, Transaction)>,
+        Error,
     > {
         // The address of the MASP account
         let masp_addr = MASP;
-        // Construct the key where last transaction pointer is stored
-        let head_tx_key = Key::from(masp_addr.to_db_key())
-            .push(&HEAD_TX_KEY.to_owned())
-            .map_err(|k| {
-                Error::Other(format!("Cannot obtain a storage key: {}", k))
-            })?;
-        // Query for the index of the last accepted transaction
-        let head_txidx = query_storage_value::<C, u64>(client, &head_tx_key)
-            .await
-            .unwrap_or(0);
         let mut shielded_txs = BTreeMap::new();
-        // Fetch all the transactions we do not have yet
-        for i in last_txidx..head_txidx {
+        // Fetch all the transactions in the given range
+        for i in txidx_range {
             // Construct the key for where the current transaction is stored
             let current_tx_key = Key::from(masp_addr.to_db_key())
                 .push(&(TX_KEY_PREFIX.to_owned() + &i.to_string()))
@@ -843,10 +1006,6 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             self.tree.append(node).map_err(|()| {
                 Error::Other("note commitment tree is full".to_string())
             })?;
-            // Finally, make it easier to construct merkle paths to this new
-            // note
-            let witness = IncrementalWitness::<Node>::from_tree(&self.tree);
-            self.witness_map.insert(note_pos, witness);
             // Let's try to see if any of our viewing keys can decrypt latest
             // note
             let mut pos_map = HashMap::new();
@@ -898,6 +1057,14 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                         .await;
 
                     self.vk_map.insert(note_pos, *vk);
+                    self.note_tx_map.insert(note_pos, (height, index));
+                    // Finally, make it easier to construct merkle paths to
+                    // this new note. Witnesses are only maintained for our
+                    // own notes, since they must all be updated with each
+                    // new note.
+                    let witness =
+                        IncrementalWitness::<Node>::from_tree(&self.tree);
+                    self.witness_map.insert(note_pos, witness);
                     break;
                 }
             }
@@ -912,6 +1079,9 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             // is rendered unusable
             if let Some(note_pos) = self.nf_map.get(&ss.nullifier) {
                 self.spents.insert(*note_pos);
+                self.spent_tx_map.insert(*note_pos, (height, index));
+                // A spent note's merkle path is no longer needed
+                self.witness_map.remove(note_pos);
                 // Note the account changes
                 let balance = transaction_delta
                     .entry(self.vk_map[note_pos])
@@ -946,6 +1116,12 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         );
         self.last_txidx += 1;
 
+        // Remember the hash of the transactions affecting our notes
+        if !transaction_delta.is_empty() {
+            if let Some(hash) = tx.shielded {
+                self.tx_hash_map.insert((height, index), hash);
+            }
+        }
         self.delta_map.insert(
             (height, index),
             (epoch, transfer_delta, transaction_delta),
@@ -1004,6 +1180,106 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         Ok(Some(self.decode_all_amounts(client, val_acc).await))
     }
 
+    /// List the notes received and spent by the viewing key in transactions
+    /// applied in the given range of epochs, in chronological order. If the
+    /// key is not in the context, then we do not know its history and hence
+    /// we return None.
+    pub async fn shielded_history<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        vk: &ViewingKey,
+        epochs: impl RangeBounds<Epoch>,
+    ) -> Result<Option<Vec<ShieldedHistoryEntry>>, Error> {
+        let Some(notes) = self.pos_map.get(vk) else {
+            return Ok(None);
+        };
+        // Collect the transactions that created or spent each of the notes
+        let mut events = Vec::new();
+        for note_pos in notes {
+            if let Some(tx) = self.note_tx_map.get(note_pos) {
+                events.push((*tx, *note_pos, NoteDirection::Incoming));
+            }
+            if let Some(tx) = self.spent_tx_map.get(note_pos) {
+                events.push((*tx, *note_pos, NoteDirection::Outgoing));
+            }
+        }
+        events.sort_by_key(|(tx, note_pos, _)| (*tx, *note_pos));
+        let mut history = Vec::new();
+        for ((height, index), note_pos, direction) in events {
+            let Some((epoch, _, _)) = self.delta_map.get(&(height, index))
+            else {
+                continue;
+            };
+            let epoch = *epoch;
+            if !epochs.contains(&epoch) {
+                continue;
+            }
+            let note = *self.note_map.get(&note_pos).ok_or_else(|| {
+                Error::Other(format!("Unable to get note {note_pos}"))
+            })?;
+            let value =
+                I128Sum::from_nonnegative(note.asset_type, note.value as i128)
+                    .map_err(|()| {
+                        Error::Other(
+                            "found note with invalid value or asset type"
+                                .to_string(),
+                        )
+                    })?;
+            history.push(ShieldedHistoryEntry {
+                height,
+                index,
+                epoch,
+                tx_hash: self.tx_hash_map.get(&(height, index)).copied(),
+                direction,
+                note_pos,
+                asset_type: note.asset_type,
+                amount: self.decode_all_amounts(client, value).await,
+                memo: self.memo_map.get(&note_pos).cloned(),
+            });
+        }
+        Ok(Some(history))
+    }
+
+    /// Compute the total of the notes held by the viewing key at the end of
+    /// the given epoch, i.e. those received in transactions applied up to
+    /// that epoch and not yet spent by then. If the key is not in the
+    /// context, then we do not know the balance and hence we return None.
+    pub async fn shielded_balance_at_epoch<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        vk: &ViewingKey,
+        epoch: Epoch,
+    ) -> Result<Option<MaspAmount>, Error> {
+        let Some(notes) = self.pos_map.get(vk) else {
+            return Ok(None);
+        };
+        // Check if the given transaction was applied by the end of the epoch
+        let applied_by_epoch = |tx: Option<&(BlockHeight, TxIndex)>| {
+            tx.and_then(|tx| self.delta_map.get(tx))
+                .map_or(false, |(tx_epoch, _, _)| *tx_epoch <= epoch)
+        };
+        let mut val_acc = I128Sum::zero();
+        for note_pos in notes {
+            if !applied_by_epoch(self.note_tx_map.get(note_pos))
+                || applied_by_epoch(self.spent_tx_map.get(note_pos))
+            {
+                continue;
+            }
+            let note = self.note_map.get(note_pos).ok_or_else(|| {
+                Error::Other(format!("Unable to get note {note_pos}"))
+            })?;
+            val_acc +=
+                I128Sum::from_nonnegative(note.asset_type, note.value as i128)
+                    .map_err(|()| {
+                        Error::Other(
+                            "found note with invalid value or asset type"
+                                .to_string(),
+                        )
+                    })?;
+        }
+        Ok(Some(self.decode_all_amounts(client, val_acc).await))
+    }
+
     /// Query the ledger for the decoding of the given asset type and cache it
     /// if it is found.
     pub async fn decode_asset_type<C: Client + Sync>(
@@ -1085,6 +1361,39 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         }
     }
 
+    /// Compute the rewards that the notes of the given viewing key can claim
+    /// by being converted to the asset types of the target epoch, summed per
+    /// token. The conversions themselves can be obtained with
+    /// [`Self::compute_exchanged_amount`] and included in a transfer. If the
+    /// key is not in the context, then we return None.
+    pub async fn compute_conversion_rewards(
+        &mut self,
+        client: &(impl Client + Sync),
+        io: &impl Io,
+        vk: &ViewingKey,
+        target_epoch: Epoch,
+    ) -> Result<Option<BTreeMap<Address, Change>>, Error> {
+        let balance = match self.compute_shielded_balance(client, vk).await? {
+            Some(balance) => balance,
+            None => return Ok(None),
+        };
+        let unexchanged = self
+            .decode_all_amounts(client, I128Sum::from(&balance))
+            .await;
+        let exchanged = self
+            .compute_exchanged_balance(client, io, vk, target_epoch)
+            .await?
+            .unwrap_or_default();
+        // Conversions preserve the amount of each converted token, so any
+        // increase of a token's total is a reward
+        let mut rewards: BTreeMap<Address, Change> = BTreeMap::new();
+        for ((_, token), change) in (exchanged - unexchanged).iter() {
+            *rewards.entry(token.clone()).or_default() += *change;
+        }
+        rewards.retain(|_, change| change.is_positive());
+        Ok(Some(rewards))
+    }
+
     /// Try to convert as much of the given asset type-value pair using the
     /// given allowed conversion. usage is incremented by the amount of the
     /// conversion used, the conversions are applied to the given input, and
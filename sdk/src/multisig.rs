@@ -0,0 +1,305 @@
+//! Helpers for multisig accounts, i.e. established accounts controlled by
+//! several public keys, a threshold of which must sign each transaction.
+//!
+//! The signatures of the raw header of a transaction, and of its memo if any,
+//! can be collected from each signer, online or offline, with
+//! [`MultisigSignatures`], and then assembled into a signature section of the
+//! transaction. The checks
+//! performed by the ledger on these signatures are mirrored by
+//! [`verify_partial_signature`] and [`verify_multisig`].
+
+use std::collections::{BTreeMap, HashSet};
+
+use namada_core::ledger::parameters::storage as parameter_storage;
+use namada_core::proto::{Signature, SignatureIndex, Signer, Tx};
+use namada_core::types::account::AccountPublicKeysMap;
+use namada_core::types::address::Address;
+use namada_core::types::hash::Hash;
+use namada_core::types::key::{common, RefTo, SigScheme};
+
+use crate::error::{Error, Result, TxError};
+use crate::{args, rpc, Namada};
+
+/// Make a builder for a transaction initializing an established account
+/// controlled by the given public keys, any `threshold` of which must sign
+/// the transactions of the account
+pub fn new_multisig_account(
+    context: &impl Namada,
+    public_keys: Vec<common::PublicKey>,
+    threshold: u8,
+) -> Result<args::TxInitAccount> {
+    if threshold == 0 || usize::from(threshold) > public_keys.len() {
+        return Err(Error::from(TxError::InvalidAccountThreshold(
+            threshold,
+            public_keys.len(),
+        )));
+    }
+    let mut unique_keys = HashSet::new();
+    if let Some(duplicate) =
+        public_keys.iter().find(|pk| !unique_keys.insert(*pk))
+    {
+        return Err(Error::from(TxError::DuplicateAccountKey(
+            duplicate.clone(),
+        )));
+    }
+    Ok(context.new_init_account(public_keys, Some(threshold)))
+}
+
+/// The signatures of the raw header of a transaction made on behalf of a
+/// multisig account, collected from its signers
+#[derive(Debug, Clone)]
+pub struct MultisigSignatures {
+    /// The hashes of the raw header and of the memo being signed
+    targets: Vec<Hash>,
+    /// The multisig account
+    owner: Address,
+    /// The public keys of the account, indexed
+    public_keys_map: AccountPublicKeysMap,
+    /// The number of signatures required by the account
+    threshold: u8,
+    /// The valid signatures collected so far, by public key index
+    signatures: BTreeMap<u8, SignatureIndex>,
+}
+
+impl MultisigSignatures {
+    /// Start collecting signatures of the given transaction on behalf of
+    /// `owner`, controlled by the given public keys and threshold
+    pub fn new(
+        tx: &Tx,
+        owner: Address,
+        public_keys_map: AccountPublicKeysMap,
+        threshold: u8,
+    ) -> Self {
+        Self {
+            targets: tx.raw_sechashes(),
+            owner,
+            public_keys_map,
+            threshold,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    /// Start collecting signatures of the given transaction on behalf of
+    /// `owner`, with the public keys and threshold of the account on chain
+    pub async fn for_account(
+        context: &impl Namada,
+        tx: &Tx,
+        owner: Address,
+    ) -> Result<Self> {
+        let account = rpc::get_account_info(context.client(), &owner)
+            .await?
+            .ok_or_else(|| {
+                Error::from(TxError::InvalidAccount(owner.encode()))
+            })?;
+        Ok(Self::new(
+            tx,
+            owner,
+            account.public_keys_map,
+            account.threshold,
+        ))
+    }
+
+    /// Sign the raw header with the given secret key of one of the signers
+    pub fn sign(&mut self, secret_key: &common::SecretKey) -> Result<()> {
+        let public_key = secret_key.ref_to();
+        let index = self
+            .public_keys_map
+            .get_index_from_public_key(&public_key)
+            .ok_or_else(|| {
+                Error::from(TxError::UnexpectedSigner(public_key.clone()))
+            })?;
+        let signature = common::SigScheme::sign(
+            secret_key,
+            signature_target(&self.targets),
+        );
+        self.signatures.insert(
+            index,
+            SignatureIndex {
+                pubkey: public_key,
+                index: Some((self.owner.clone(), index)),
+                signature,
+            },
+        );
+        Ok(())
+    }
+
+    /// Add a signature produced by one of the signers, e.g. on an offline
+    /// host. The signature is verified before being added.
+    pub fn add(&mut self, signature: SignatureIndex) -> Result<()> {
+        let index = self
+            .public_keys_map
+            .get_index_from_public_key(&signature.pubkey)
+            .ok_or_else(|| {
+                Error::from(TxError::UnexpectedSigner(signature.pubkey.clone()))
+            })?;
+        match &signature.index {
+            Some((owner, signature_index))
+                if owner == &self.owner && *signature_index == index => {}
+            _ => {
+                return Err(Error::from(TxError::InvalidSignature(format!(
+                    "the signature by {} is not indexed as the key {} of \
+                     account {}",
+                    signature.pubkey, index, self.owner
+                ))));
+            }
+        }
+        verify_partial_signature(&self.targets, &signature)?;
+        self.signatures.insert(index, signature);
+        Ok(())
+    }
+
+    /// Check if enough signatures were collected to meet the threshold of
+    /// the account
+    pub fn is_complete(&self) -> bool {
+        self.signatures.len() >= usize::from(self.threshold)
+    }
+
+    /// The signatures collected so far
+    pub fn signatures(&self) -> Vec<SignatureIndex> {
+        self.signatures.values().cloned().collect()
+    }
+
+    /// Add the collected signatures to the given transaction, as a single
+    /// signature section. Fails if the threshold of the account isn't met
+    /// or if the transaction isn't the one that was signed.
+    pub fn assemble(self, tx: &mut Tx) -> Result<()> {
+        if !self.is_complete() {
+            return Err(Error::from(TxError::MissingSigningKeys(
+                self.threshold,
+                self.signatures.len() as u8,
+            )));
+        }
+        if tx.raw_sechashes() != self.targets {
+            return Err(Error::from(TxError::InvalidSignature(
+                "the signatures were made over another transaction".to_string(),
+            )));
+        }
+        tx.add_signatures(self.signatures.into_values().collect());
+        Ok(())
+    }
+}
+
+/// The hash committed to by the signatures of a section targeting the
+/// sections with the given hashes
+fn signature_target(targets: &[Hash]) -> Hash {
+    Signature {
+        targets: targets.to_vec(),
+        signer: Signer::PubKeys(vec![]),
+        signatures: BTreeMap::new(),
+    }
+    .get_raw_hash()
+}
+
+/// Verify a single signature of the sections with the given hashes, i.e. the
+/// [`Tx::raw_sechashes`] of a transaction, as the ledger would when verifying
+/// the signature section containing it
+pub fn verify_partial_signature(
+    targets: &[Hash],
+    signature: &SignatureIndex,
+) -> Result<()> {
+    common::SigScheme::verify_signature(
+        &signature.pubkey,
+        &signature_target(targets),
+        &signature.signature,
+    )
+    .map_err(|err| Error::from(TxError::InvalidSignature(err.to_string())))
+}
+
+/// Verify that the raw header of the given transaction carries enough valid
+/// signatures on behalf of `owner`, as the ledger would. At most
+/// `max_signatures` signatures are allowed per signature section, which is
+/// a protocol parameter (see [`query_max_signatures_per_transaction`]).
+pub fn verify_multisig(
+    tx: &Tx,
+    owner: &Address,
+    public_keys_map: AccountPublicKeysMap,
+    threshold: u8,
+    max_signatures: Option<u8>,
+) -> Result<()> {
+    tx.verify_signatures(
+        &[tx.raw_header_hash()],
+        public_keys_map,
+        &Some(owner.clone()),
+        threshold,
+        max_signatures,
+        || Ok(()),
+    )
+    .map(|_witnesses| ())
+    .map_err(|err| Error::from(TxError::InvalidSignature(err.to_string())))
+}
+
+/// Query the maximum number of signatures allowed per signature section of a
+/// transaction
+pub async fn query_max_signatures_per_transaction<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+) -> Result<u8> {
+    let key = parameter_storage::get_max_signatures_per_transaction_key();
+    rpc::query_storage_value::<C, u8>(client, &key).await
+}
+
+#[cfg(test)]
+mod test_multisig {
+    use namada_core::types::address::testing::established_address_1;
+    use namada_core::types::chain::ChainId;
+    use namada_core::types::key::testing::{keypair_1, keypair_2, keypair_3};
+
+    use super::*;
+
+    /// Test that the signatures collected from several signers, both online
+    /// and offline, are assembled into a tx passing the ledger's checks
+    #[test]
+    fn test_collect_and_assemble_signatures() {
+        let owner = established_address_1();
+        let keys = [keypair_1(), keypair_2(), keypair_3()];
+        let public_keys_map: AccountPublicKeysMap =
+            keys.iter().map(RefTo::ref_to).collect();
+        let mut tx = Tx::new(ChainId::default(), None);
+        tx.add_data(42u64);
+
+        let mut signatures = MultisigSignatures::new(
+            &tx,
+            owner.clone(),
+            public_keys_map.clone(),
+            2,
+        );
+        signatures.sign(&keys[0]).unwrap();
+        assert!(!signatures.is_complete());
+        assert!(signatures.clone().assemble(&mut tx.clone()).is_err());
+
+        // a signature made on another host
+        let mut offline = MultisigSignatures::new(
+            &tx,
+            owner.clone(),
+            public_keys_map.clone(),
+            2,
+        );
+        offline.sign(&keys[2]).unwrap();
+        let offline_signature = offline.signatures().pop().unwrap();
+        signatures.add(offline_signature).unwrap();
+        assert!(signatures.is_complete());
+
+        // a signature over another tx is rejected
+        let mut other_tx = Tx::new(ChainId::default(), None);
+        other_tx.add_data(43u64);
+        let mut other = MultisigSignatures::new(
+            &other_tx,
+            owner.clone(),
+            public_keys_map.clone(),
+            2,
+        );
+        other.sign(&keys[1]).unwrap();
+        assert!(signatures.add(other.signatures().pop().unwrap()).is_err());
+
+        assert!(
+            verify_multisig(&tx, &owner, public_keys_map.clone(), 2, None)
+                .is_err()
+        );
+        signatures.assemble(&mut tx).unwrap();
+        verify_multisig(&tx, &owner, public_keys_map.clone(), 2, None).unwrap();
+        assert!(
+            verify_multisig(&tx, &owner, public_keys_map, 2, Some(1)).is_err()
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! An RPC client that retries failed requests and fails over between several
+//! endpoints, for long-running services relying on public RPC nodes.
+//!
+//! Since [`FailoverClient`] implements [`tendermint_rpc::client::Client`], it
+//! can be used anywhere a [`super::Client`] is expected, e.g. with the
+//! functions of [`crate::rpc`].
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tendermint_rpc::{Error as RpcError, SimpleRequest};
+
+use crate::control_flow::time::{self, Duration};
+
+/// The policy used to retry failed RPC requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of times a failed request is retried
+    pub max_retries: u32,
+    /// The delay before the first retry
+    pub initial_backoff: Duration,
+    /// The factor the delay is multiplied by on each subsequent retry
+    pub backoff_multiplier: u32,
+    /// The maximum delay between two retries
+    pub max_backoff: Duration,
+    /// The time after which a single request is considered failed, if any
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            backoff_multiplier: 2,
+            max_backoff: Duration::from_secs(10),
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries failed requests, nor times them out
+    pub fn no_retries() -> Self {
+        Self {
+            max_retries: 0,
+            timeout: None,
+            ..Self::default()
+        }
+    }
+
+    /// The delay before the given retry, counting from zero
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.backoff_multiplier
+            .checked_pow(retry)
+            .and_then(|factor| self.initial_backoff.checked_mul(factor))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// A client forwarding requests to one of several RPC endpoints. Failed
+/// requests are retried according to a [`RetryPolicy`], moving on to the
+/// next endpoint after each failure.
+pub struct FailoverClient<C> {
+    /// The clients of each endpoint
+    clients: Vec<C>,
+    /// The index of the endpoint currently in use
+    current: AtomicUsize,
+    /// How failed requests are retried
+    policy: RetryPolicy,
+}
+
+impl<C> FailoverClient<C> {
+    /// Create a client rotating between the given endpoint clients, in
+    /// order. Panics if no client is given.
+    pub fn new(clients: Vec<C>, policy: RetryPolicy) -> Self {
+        assert!(
+            !clients.is_empty(),
+            "At least one RPC endpoint must be given"
+        );
+        Self {
+            clients,
+            current: AtomicUsize::new(0),
+            policy,
+        }
+    }
+
+    /// The index of the endpoint currently in use
+    pub fn current_endpoint(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    /// Move on to the endpoint after `failed`, unless another request
+    /// already did so
+    fn rotate(&self, failed: usize) {
+        let next = (failed + 1) % self.clients.len();
+        _ = self.current.compare_exchange(
+            failed,
+            next,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+#[async_trait::async_trait]
+impl<C> tendermint_rpc::client::Client for FailoverClient<C>
+where
+    C: tendermint_rpc::client::Client + Send + Sync,
+{
+    async fn perform<R>(&self, request: R) -> Result<R::Output, RpcError>
+    where
+        R: SimpleRequest,
+    {
+        let mut retry = 0;
+        loop {
+            let endpoint = self.current_endpoint();
+            let client = &self.clients[endpoint];
+            let attempt = clone_request(&request)?;
+            let result = match self.policy.timeout {
+                Some(timeout) => {
+                    time::timeout(timeout, client.perform(attempt))
+                        .await
+                        .unwrap_or_else(|_| {
+                            Err(RpcError::client_internal(format!(
+                                "The request timed out after {timeout:?}"
+                            )))
+                        })
+                }
+                None => client.perform(attempt).await,
+            };
+            match result {
+                Ok(output) => break Ok(output),
+                Err(err) if retry < self.policy.max_retries => {
+                    let backoff = self.policy.backoff(retry);
+                    tracing::warn!(
+                        %err,
+                        endpoint,
+                        retry,
+                        ?backoff,
+                        "RPC request failed, retrying with the next endpoint"
+                    );
+                    self.rotate(endpoint);
+                    time::sleep(backoff).await;
+                    retry += 1;
+                }
+                Err(err) => break Err(err),
+            }
+        }
+    }
+}
+
+/// Clone a request, so it can be sent more than once
+fn clone_request<R: SimpleRequest>(request: &R) -> Result<R, RpcError> {
+    serde_json::to_value(request)
+        .and_then(serde_json::from_value)
+        .map_err(|err| RpcError::client_internal(err.to_string()))
+}
+
+#[cfg(test)]
+mod test_failover {
+    use super::*;
+
+    /// Test that the delay between retries grows exponentially, up to the
+    /// maximum backoff.
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            backoff_multiplier: 3,
+            max_backoff: Duration::from_secs(1),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(300));
+        assert_eq!(policy.backoff(2), Duration::from_millis(900));
+        assert_eq!(policy.backoff(3), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,122 @@
+//! An RPC client for wasm targets, sending its requests with the `fetch` API
+//! of the host, e.g. a browser or a web worker.
+//!
+//! The HTTP clients of [`tendermint_rpc`] are not available on wasm targets,
+//! so [`FetchClient`] implements [`super::Client`] directly instead.
+
+use namada_core::types::storage::BlockHeight;
+use tendermint_rpc::{Error as RpcError, Response, SimpleRequest};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Headers, RequestInit, RequestMode};
+
+use super::{Client, EncodedResponseQuery, Error};
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `fetch` function, available both in windows and workers
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(input: &web_sys::Request) -> js_sys::Promise;
+}
+
+/// A client sending JSON-RPC requests to a single RPC endpoint over `fetch`
+#[derive(Debug, Clone)]
+pub struct FetchClient {
+    /// The URL of the RPC endpoint
+    url: String,
+}
+
+impl FetchClient {
+    /// Create a client for the RPC endpoint at the given URL
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    /// The URL of the RPC endpoint
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// POST the given JSON-RPC request body, returning the response body
+    async fn post(&self, body: String) -> Result<String, RpcError> {
+        let headers = Headers::new().map_err(js_error)?;
+        headers
+            .set("Content-Type", "application/json")
+            .map_err(js_error)?;
+        let mut init = RequestInit::new();
+        init.method("POST")
+            .mode(RequestMode::Cors)
+            .headers(&headers)
+            .body(Some(&JsValue::from_str(&body)));
+        let request = web_sys::Request::new_with_str_and_init(&self.url, &init)
+            .map_err(js_error)?;
+
+        let response: web_sys::Response =
+            JsFuture::from(fetch_with_request(&request))
+                .await
+                .and_then(|response| response.dyn_into())
+                .map_err(js_error)?;
+        if !response.ok() {
+            return Err(RpcError::client_internal(format!(
+                "The RPC endpoint {} responded with status {}",
+                self.url,
+                response.status()
+            )));
+        }
+        JsFuture::from(response.text().map_err(js_error)?)
+            .await
+            .map_err(js_error)?
+            .as_string()
+            .ok_or_else(|| {
+                RpcError::client_internal(
+                    "The response body is not a string".to_string(),
+                )
+            })
+    }
+}
+
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+impl Client for FetchClient {
+    type Error = Error;
+
+    async fn request(
+        &self,
+        path: String,
+        data: Option<Vec<u8>>,
+        height: Option<BlockHeight>,
+        prove: bool,
+    ) -> Result<EncodedResponseQuery, Self::Error> {
+        let data = data.unwrap_or_default();
+        let height = height
+            .map(|height| {
+                crate::tendermint::block::Height::try_from(height.0)
+                    .map_err(|_err| Error::InvalidHeight(height))
+            })
+            .transpose()?;
+        let response = self.abci_query(Some(path), data, height, prove).await?;
+        use crate::tendermint::abci::Code;
+        match response.code {
+            Code::Ok => Ok(EncodedResponseQuery {
+                data: response.value,
+                info: response.info,
+                proof: response.proof,
+            }),
+            Code::Err(code) => Err(Error::Query(response.info, code.into())),
+        }
+    }
+
+    async fn perform<R>(&self, request: R) -> Result<R::Output, RpcError>
+    where
+        R: SimpleRequest,
+    {
+        let response = self.post(request.into_json()).await?;
+        R::Response::from_string(response).map(Into::into)
+    }
+}
+
+/// Convert an exception thrown by the host into an RPC error
+fn js_error(err: JsValue) -> RpcError {
+    RpcError::client_internal(format!("{err:?}"))
+}
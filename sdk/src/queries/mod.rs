@@ -15,13 +15,18 @@ pub use types::{
 use vp::{Vp, VP};
 
 pub use self::shell::eth_bridge::{
-    Erc20FlowControl, GenBridgePoolProofReq, GenBridgePoolProofRsp,
-    TransferToErcArgs,
+    Erc20FlowControl, Erc20WhitelistStatus, EthereumOracleStatus,
+    GenBridgePoolProofReq, GenBridgePoolProofRsp, OracleConnectionState,
+    OracleHeartbeat, TransferToErcArgs, TransferToEthereumStatus,
 };
 use crate::{MaybeSend, MaybeSync};
 
 #[macro_use]
 mod router;
+#[cfg(not(target_family = "wasm"))]
+pub mod failover;
+#[cfg(all(target_family = "wasm", feature = "async-client"))]
+pub mod fetch;
 mod shell;
 mod types;
 pub mod vp;
@@ -1,12 +1,15 @@
 //! Ethereum bridge related shell queries.
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
-use namada_core::ledger::eth_bridge::storage::bridge_pool::get_key_from_hash;
+use namada_core::ledger::eth_bridge::storage::bridge_pool::{
+    get_key_from_hash, get_transfer_outcome_key,
+};
+use namada_core::ledger::eth_bridge::storage::whitelist;
 use namada_core::ledger::storage::merkle_tree::StoreRef;
 use namada_core::ledger::storage::{DBIter, StorageHasher, StoreType, DB};
 use namada_core::ledger::storage_api::{
@@ -15,7 +18,7 @@ use namada_core::ledger::storage_api::{
 use namada_core::types::address::Address;
 use namada_core::types::eth_abi::{Encode, EncodeCell};
 use namada_core::types::eth_bridge_pool::{
-    PendingTransfer, PendingTransferAppendix,
+    PendingTransfer, PendingTransferAppendix, TransferToEthereumOutcome,
 };
 use namada_core::types::ethereum_events::{
     EthAddress, EthereumEvent, TransferToEthereum,
@@ -24,12 +27,15 @@ use namada_core::types::ethereum_structs;
 use namada_core::types::keccak::KeccakHash;
 use namada_core::types::storage::MembershipProof::BridgePool;
 use namada_core::types::storage::{BlockHeight, DbKeySeg, Epoch, Key};
-use namada_core::types::token::Amount;
+use namada_core::types::time::DateTimeUtc;
+use namada_core::types::token::{Amount, Denomination};
 use namada_core::types::vote_extensions::validator_set_update::{
     ValidatorSetArgs, VotingPowersMap,
 };
 use namada_core::types::voting_power::FractionalVotingPower;
-use namada_ethereum_bridge::parameters::UpgradeableContract;
+use namada_ethereum_bridge::parameters::{
+    read_erc20_min_confirmations, MinimumConfirmations, UpgradeableContract,
+};
 use namada_ethereum_bridge::protocol::transactions::votes::{
     EpochedVotingPower, EpochedVotingPowerExt,
 };
@@ -40,6 +46,7 @@ use namada_ethereum_bridge::storage::{
     bridge_contract_key, native_erc20_key, vote_tallies,
 };
 use namada_proof_of_stake::pos_queries::PosQueries;
+use num256::Uint256;
 
 use crate::eth_bridge::ethers::abi::AbiDecode;
 use crate::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
@@ -67,6 +74,129 @@ impl Erc20FlowControl {
     }
 }
 
+/// The state of an ERC20 asset in the token whitelist.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct Erc20WhitelistStatus {
+    /// The address of the ERC20 asset.
+    pub asset: EthAddress,
+    /// The flow control of the wrapped asset.
+    pub flow_control: Erc20FlowControl,
+    /// The denomination of the ERC20 asset.
+    pub denom: Option<Denomination>,
+    /// The minimum number of confirmations of the Ethereum events
+    /// transferring the asset, if different from the bridge's default.
+    pub min_confirmations: Option<MinimumConfirmations>,
+}
+
+/// The state of the connection between the Ethereum oracle of a
+/// validator and its Ethereum full node.
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub enum OracleConnectionState {
+    /// The oracle is waiting to be configured by the ledger.
+    #[default]
+    AwaitingConfig,
+    /// The oracle is connected to a synced Ethereum full node.
+    Connected,
+    /// The Ethereum full node is syncing, or it has fallen behind
+    /// the blocks already processed by the oracle.
+    Syncing,
+    /// The oracle is failing to communicate with the Ethereum full
+    /// node, but it may still recover.
+    Unreachable,
+    /// The Ethereum bridge is deactivated, and the oracle is idle
+    /// until it gets reactivated.
+    Deactivated,
+    /// The oracle has stopped processing Ethereum blocks.
+    Halted,
+}
+
+/// A heartbeat published by the Ethereum oracle of a validator,
+/// after each attempt at processing an Ethereum block.
+#[derive(
+    Debug, Default, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct OracleHeartbeat {
+    /// The state of the connection to the Ethereum full node.
+    pub connection: OracleConnectionState,
+    /// The latest Ethereum block height reported by the full node.
+    pub latest_block: Option<ethereum_structs::BlockHeight>,
+    /// The time at which the heartbeat was published, if any was
+    /// published yet.
+    pub timestamp: Option<DateTimeUtc>,
+}
+
+/// The health of the Ethereum oracle of a validator node.
+#[derive(Debug, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct EthereumOracleStatus {
+    /// The latest heartbeat published by the oracle.
+    pub heartbeat: OracleHeartbeat,
+    /// The latest Ethereum block fully processed by the oracle.
+    pub last_processed_block: Option<ethereum_structs::BlockHeight>,
+    /// The number of Ethereum events queued up by the node, to be
+    /// included in its next vote extensions.
+    pub queued_events: u64,
+}
+
+impl EthereumOracleStatus {
+    /// Return the number of Ethereum blocks the oracle still has to
+    /// process to catch up with its full node, if known.
+    pub fn blocks_behind(&self) -> Option<Uint256> {
+        let latest: &Uint256 = self.heartbeat.latest_block.as_ref()?.into();
+        let processed: &Uint256 = self.last_processed_block.as_ref()?.into();
+        Some(if latest > processed {
+            latest.clone() - processed.clone()
+        } else {
+            Uint256::from(0u64)
+        })
+    }
+}
+
+/// The status of a transfer to Ethereum, along the lifecycle of
+/// a Bridge pool transfer.
+#[derive(Debug, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize)]
+pub enum TransferToEthereumStatus {
+    /// No transfer with the given hash was ever known to be in the
+    /// Bridge pool.
+    Unknown,
+    /// The transfer is in the Bridge pool, but it is not covered by
+    /// the latest signed Bridge pool root.
+    Pending,
+    /// The transfer is covered by the latest signed Bridge pool root,
+    /// and it can be relayed to Ethereum.
+    Signed,
+    /// The transfer was relayed to Ethereum, and its execution is
+    /// being voted on by Namada validators.
+    Relayed {
+        /// The voting power behind the Ethereum event containing the
+        /// transfer.
+        voting_power: FractionalVotingPower,
+    },
+    /// The transfer was executed on Ethereum, and removed from the
+    /// Bridge pool at the given height.
+    Confirmed {
+        /// The height at which the transfer was removed from the pool.
+        height: BlockHeight,
+    },
+    /// The transfer timed out in the Bridge pool, and its assets
+    /// were refunded at the given height.
+    Expired {
+        /// The height at which the transfer was refunded.
+        height: BlockHeight,
+    },
+}
+
+impl TransferToEthereumStatus {
+    /// Check if the transfer has reached the end of its lifecycle.
+    #[inline]
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Confirmed { .. } | Self::Expired { .. })
+    }
+}
+
 /// Request data to pass to `generate_bridge_pool_proof`.
 #[derive(Debug, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize)]
 pub struct GenBridgePoolProofReq<'transfers, 'relayer> {
@@ -129,6 +259,12 @@ router! {ETH_BRIDGE,
         -> HashMap<PendingTransfer, FractionalVotingPower>
         = transfer_to_ethereum_progress,
 
+    // Get the status of the transfer to Ethereum with the given hash,
+    // from its inclusion in the Bridge pool to its execution on
+    // Ethereum.
+    ( "pool" / "transfer_status" / [hash: KeccakHash] )
+        -> TransferToEthereumStatus = transfer_to_ethereum_status,
+
     // Request a proof of a validator set signed off for
     // the given epoch.
     //
@@ -173,6 +309,64 @@ router! {ETH_BRIDGE,
     // ERC20 token in Namada.
     ( "erc20" / "flow_control" / [asset: EthAddress] )
         -> Erc20FlowControl = get_erc20_flow_control,
+
+    // Read the state of every ERC20 asset ever added to the token
+    // whitelist, including the removed ones.
+    ( "erc20" / "whitelist" )
+        -> Vec<Erc20WhitelistStatus> = read_erc20_whitelist,
+
+    // Read the health of the node's Ethereum oracle. Returns `None`
+    // if the node is not running an oracle.
+    //
+    // This query is served by the shell of the node, which owns the
+    // channels to its oracle.
+    ( "oracle" / "status" )
+        -> Option<EthereumOracleStatus> = read_oracle_status,
+}
+
+/// Read the state of every ERC20 asset ever added to the token
+/// whitelist.
+fn read_erc20_whitelist<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Vec<Erc20WhitelistStatus>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut assets = BTreeSet::new();
+    for entry in
+        storage_api::iter_prefix_bytes(ctx.wl_storage, &whitelist::prefix())?
+    {
+        let (key, _) = entry?;
+        assets.extend(whitelist::key_asset(&key));
+    }
+    let min_confirmations = read_erc20_min_confirmations(ctx.wl_storage)?;
+    let ethbridge_queries = ctx.wl_storage.ethbridge_queries();
+    assets
+        .into_iter()
+        .map(|asset| {
+            let flow_control = Erc20FlowControl {
+                whitelisted: ethbridge_queries.is_token_whitelisted(&asset),
+                supply: ethbridge_queries
+                    .get_token_supply(&asset)
+                    .unwrap_or_default(),
+                cap: ethbridge_queries
+                    .get_token_cap(&asset)
+                    .unwrap_or_default(),
+            };
+            let denom_key = whitelist::Key {
+                asset,
+                suffix: whitelist::KeyType::Denomination,
+            };
+            let denom = StorageRead::read(ctx.wl_storage, &denom_key.into())?;
+            Ok(Erc20WhitelistStatus {
+                asset,
+                flow_control,
+                denom,
+                min_confirmations: min_confirmations.get(&asset).copied(),
+            })
+        })
+        .collect()
 }
 
 /// Read the total supply and respective cap of some wrapped
@@ -200,6 +394,22 @@ where
     })
 }
 
+/// Read the health of the node's Ethereum oracle.
+///
+/// The shell of the node serves this query on its own, so reaching
+/// this handler means the query was not issued against a node.
+fn read_oracle_status<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Option<EthereumOracleStatus>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Err(storage_api::Error::SimpleMessage(
+        "The status of the Ethereum oracle can only be queried from a node",
+    ))
+}
+
 /// Helper function to read a smart contract from storage.
 fn read_contract<T, D, H, V, U>(
     key: &Key,
@@ -524,6 +734,63 @@ where
     Ok(pending_events)
 }
 
+/// Read the status of the transfer to Ethereum with the given hash.
+fn transfer_to_ethereum_status<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    hash: KeccakHash,
+) -> storage_api::Result<TransferToEthereumStatus>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let wl_storage = ctx.wl_storage;
+
+    // transfers removed from the pool have their outcome recorded
+    let outcome: Option<TransferToEthereumOutcome> =
+        wl_storage.read(&get_transfer_outcome_key(&hash))?;
+    match outcome {
+        Some(TransferToEthereumOutcome::Relayed(height)) => {
+            return Ok(TransferToEthereumStatus::Confirmed { height });
+        }
+        Some(TransferToEthereumOutcome::Expired(height)) => {
+            return Ok(TransferToEthereumStatus::Expired { height });
+        }
+        None => {}
+    }
+
+    let key = get_key_from_hash(&hash);
+    let Some(transfer) = wl_storage.read::<PendingTransfer>(&key)? else {
+        return Ok(TransferToEthereumStatus::Unknown);
+    };
+
+    // the transfer can only be relayed with the latest signed root,
+    // once it has caught up with the Bridge pool nonce
+    let ethbridge_queries = wl_storage.ethbridge_queries();
+    let is_signed = match ethbridge_queries.get_signed_bridge_pool_root() {
+        Some((signed_root, height))
+            if signed_root.data.1
+                == ethbridge_queries.get_bridge_pool_nonce() =>
+        {
+            wl_storage
+                .storage
+                .get_merkle_tree(height, Some(StoreType::BridgePool))
+                .into_storage_result()?
+                .has_key(&key)
+                .into_storage_result()?
+        }
+        _ => false,
+    };
+
+    let progress = transfer_to_ethereum_progress(ctx)?;
+    Ok(match progress.get(&transfer) {
+        Some(voting_power) => TransferToEthereumStatus::Relayed {
+            voting_power: *voting_power,
+        },
+        None if is_signed => TransferToEthereumStatus::Signed,
+        None => TransferToEthereumStatus::Pending,
+    })
+}
+
 /// Read a validator set update proof from storage.
 ///
 /// This method may fail if a complete proof (i.e. with more than
@@ -1388,6 +1655,122 @@ mod test_ethbridge_router {
         assert_eq!(expected, resp);
     }
 
+    /// Test that we can follow the status of a transfer to Ethereum
+    /// along its lifecycle in the Bridge pool.
+    #[tokio::test]
+    async fn test_transfer_to_eth_status() {
+        let mut client = TestClient::new(RPC);
+        let transfer = PendingTransfer {
+            transfer: TransferToEthereum {
+                kind: TransferToEthereumKind::Erc20,
+                asset: EthAddress([0; 20]),
+                recipient: EthAddress([0; 20]),
+                sender: bertha_address(),
+                amount: 0.into(),
+            },
+            gas_fee: GasFee {
+                token: nam(),
+                amount: 0.into(),
+                payer: bertha_address(),
+            },
+        };
+        // write validator to storage
+        test_utils::init_default_storage(&mut client.wl_storage);
+
+        // write a transfer into the bridge pool
+        client
+            .wl_storage
+            .write_bytes(
+                &get_pending_key(&transfer),
+                transfer.serialize_to_vec(),
+            )
+            .expect("Test failed");
+
+        // create a signed Merkle root for this pool
+        let signed_root = BridgePoolRootProof {
+            signatures: Default::default(),
+            data: (transfer.keccak256(), 0.into()),
+        };
+        let written_height = client.wl_storage.storage.block.height;
+
+        // commit the changes and increase block height
+        client.wl_storage.commit_block().expect("Test failed");
+        client.wl_storage.storage.block.height += 1;
+
+        // update the pool, with a transfer not covered by the root
+        let mut transfer2 = transfer.clone();
+        transfer2.transfer.amount = 1.into();
+        client
+            .wl_storage
+            .write_bytes(
+                &get_pending_key(&transfer2),
+                transfer2.serialize_to_vec(),
+            )
+            .expect("Test failed");
+
+        // add the signature for the pool at the previous block height
+        client
+            .wl_storage
+            .write_bytes(
+                &get_signed_root_key(),
+                (signed_root, written_height).serialize_to_vec(),
+            )
+            .expect("Test failed");
+
+        // record the outcome of transfers removed from the pool
+        let mut relayed = transfer.clone();
+        relayed.transfer.amount = 2.into();
+        let relayed_height = client.wl_storage.storage.block.height;
+        client
+            .wl_storage
+            .write(
+                &get_transfer_outcome_key(&relayed.keccak256()),
+                TransferToEthereumOutcome::Relayed(relayed_height),
+            )
+            .expect("Test failed");
+        let mut expired = transfer.clone();
+        expired.transfer.amount = 3.into();
+        client
+            .wl_storage
+            .write(
+                &get_transfer_outcome_key(&expired.keccak256()),
+                TransferToEthereumOutcome::Expired(relayed_height),
+            )
+            .expect("Test failed");
+
+        // commit the changes and increase block height
+        client.wl_storage.commit_block().expect("Test failed");
+        client.wl_storage.storage.block.height += 1;
+
+        let mut unknown = transfer.clone();
+        unknown.transfer.amount = 4.into();
+        for (transfer, expected) in [
+            (transfer, TransferToEthereumStatus::Signed),
+            (transfer2, TransferToEthereumStatus::Pending),
+            (
+                relayed,
+                TransferToEthereumStatus::Confirmed {
+                    height: relayed_height,
+                },
+            ),
+            (
+                expired,
+                TransferToEthereumStatus::Expired {
+                    height: relayed_height,
+                },
+            ),
+            (unknown, TransferToEthereumStatus::Unknown),
+        ] {
+            let resp = RPC
+                .shell()
+                .eth_bridge()
+                .transfer_to_ethereum_status(&client, &transfer.keccak256())
+                .await
+                .unwrap();
+            assert_eq!(resp, expected);
+        }
+    }
+
     /// Test if the a transfer has been removed from the
     /// pool (either because it was transferred or timed out),
     /// a proof is not generated for it, even if it was
@@ -5,6 +5,10 @@ pub use governance::Gov;
 use governance::GOV;
 pub use pos::Pos;
 use pos::POS;
+#[cfg(feature = "pos-state-export")]
+pub use pos_state::PosState;
+#[cfg(feature = "pos-state-export")]
+use pos_state::POS_STATE;
 pub use token::Token;
 use token::TOKEN;
 mod governance;
@@ -13,12 +17,25 @@ use pgf::PGF;
 mod pgf;
 
 pub mod pos;
+#[cfg(feature = "pos-state-export")]
+pub mod pos_state;
 mod token;
 
 // Validity predicate queries
+#[cfg(not(feature = "pos-state-export"))]
 router! {VP,
     ( "pos" ) = (sub POS),
     ( "token" ) = (sub TOKEN),
     ( "governance" ) = (sub GOV),
     ( "pgf" ) = (sub PGF),
 }
+
+// Validity predicate queries, including the export of the whole PoS state
+#[cfg(feature = "pos-state-export")]
+router! {VP,
+    ( "pos" ) = (sub POS),
+    ( "pos_state" ) = (sub POS_STATE),
+    ( "token" ) = (sub TOKEN),
+    ( "governance" ) = (sub GOV),
+    ( "pgf" ) = (sub PGF),
+}
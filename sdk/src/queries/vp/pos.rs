@@ -3,34 +3,54 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+use borsh_ext::BorshSerializeExt;
+use namada_core::ledger::storage::{DBIter, StorageHasher, TempWlStorage, DB};
 use namada_core::ledger::storage_api;
 use namada_core::ledger::storage_api::collections::lazy_map;
-use namada_core::ledger::storage_api::OptionExt;
+use namada_core::ledger::storage_api::{OptionExt, ResultExt};
 use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
 use namada_core::types::key::common;
-use namada_core::types::storage::Epoch;
+use namada_core::types::storage::{BlockHeight, Epoch};
 use namada_core::types::token;
 use namada_proof_of_stake::parameters::PosParams;
+use namada_proof_of_stake::proposer_history::{
+    proposer_stats, read_block_proposer, ProposerStats,
+};
+use namada_proof_of_stake::rewards::{
+    estimate_staking_apr, estimate_validator_staking_apr,
+};
+use namada_proof_of_stake::setup_check::{
+    check_validator_setup, ValidatorSetupReport,
+};
 use namada_proof_of_stake::types::{
-    BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails, CommissionPair,
-    Slash, ValidatorMetaData, ValidatorState, WeightedValidator,
+    BondAction, BondActionSimulation, BondId, BondsAndUnbondsDetail,
+    BondsAndUnbondsDetails, CommissionPair, CommissionRateChange, QueuedUnbond,
+    RedelegationEligibility, Slash, SlashEstimate, SlashFilter, SlashRecord,
+    SlashStatus, SlashType, ValidatorKeys, ValidatorMetaData, ValidatorSetDiff,
+    ValidatorSetSnapshot, ValidatorState, WeightedValidator,
 };
+use namada_proof_of_stake::validator_set_cache::ConsensusValidatorSetCache;
 use namada_proof_of_stake::{
-    self, bond_amount, bond_handle, find_all_enqueued_slashes,
-    find_all_slashes, find_delegation_validators, find_delegations,
-    query_reward_tokens, read_all_validator_addresses,
+    self, bond_amount, bond_handle, can_redelegate, compute_cubic_slash_rate,
+    compute_validator_set_diff, find_all_enqueued_slashes, find_all_slashes,
+    find_delegation_validators, find_delegations, find_withdrawable_unbonds,
+    iter_slashes, liveness_sum_missed_votes_handle, query_reward_tokens,
+    read_all_validator_addresses, read_all_validator_keys,
     read_below_capacity_validator_set_addresses_with_stake,
-    read_consensus_validator_set_addresses_with_stake, read_pos_params,
-    read_total_stake, read_validator_description,
-    read_validator_discord_handle, read_validator_email,
-    read_validator_last_slash_epoch, read_validator_max_commission_rate_change,
-    read_validator_stake, read_validator_website, unbond_handle,
-    validator_commission_rate_handle, validator_incoming_redelegations_handle,
-    validator_slashes_handle, validator_state_handle,
+    read_below_threshold_validator_set_addresses, read_bonded_ratio,
+    read_commission_history, read_consensus_validator_set_addresses_with_stake,
+    read_inflation_amount, read_pos_params, read_total_stake,
+    read_total_stake_range, read_validator_cumulative_slash,
+    read_validator_description, read_validator_discord_handle,
+    read_validator_email, read_validator_last_slash_epoch,
+    read_validator_set_snapshot, read_validator_stake,
+    read_validator_stake_range, read_validator_website, simulate_slash,
+    staking_pool, unbond_handle, validator_incoming_redelegations_handle,
+    validator_slashes_handle, PosQueryCtx,
 };
 
-use crate::queries::types::RequestCtx;
+use crate::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
 
 // PoS validity predicate queries
 router! {POS,
@@ -40,15 +60,27 @@ router! {POS,
         ( "addresses" / [epoch: opt Epoch] )
             -> HashSet<Address> = validator_addresses,
 
+        ( "keys" / [epoch: opt Epoch] )
+            -> HashMap<Address, ValidatorKeys> = validator_keys,
+
         ( "stake" / [validator: Address] / [epoch: opt Epoch] )
             -> Option<token::Amount> = validator_stake,
 
+        ( "stake_range" / [validator: Address] / [from_epoch: Epoch] / [to_epoch: Epoch] )
+            -> Option<BTreeMap<Epoch, token::Amount>> = validator_stake_range,
+
         ( "slashes" / [validator: Address] )
             -> Vec<Slash> = validator_slashes,
 
+        ( "cumulative_slash_rate" / [validator: Address] / [epoch: opt Epoch] )
+            -> Dec = validator_cumulative_slash_rate,
+
         ( "commission" / [validator: Address] / [epoch: opt Epoch] )
             -> Option<CommissionPair> = validator_commission,
 
+        ( "commission_history" / [validator: Address] )
+            -> Vec<CommissionRateChange> = validator_commission_history,
+
         ( "metadata" / [validator: Address] )
             -> Option<ValidatorMetaData> = validator_metadata,
 
@@ -58,8 +90,25 @@ router! {POS,
         ( "incoming_redelegation" / [src_validator: Address] / [delegator: Address] )
             -> Option<Epoch> = validator_incoming_redelegation,
 
+        ( "redelegation_eligibility" / [src_validator: Address] / [delegator: Address] )
+            -> RedelegationEligibility = validator_redelegation_eligibility,
+
         ( "last_infraction_epoch" / [validator: Address] )
             -> Option<Epoch> = validator_last_infraction_epoch,
+
+        ( "staking_apr" / [validator: Address] / [epoch: opt Epoch] )
+            -> Dec = validator_staking_apr,
+
+        ( "simulate_slash" / [validator: Address] / [slash_type: SlashType] / [infraction_epoch: opt Epoch] )
+            -> Option<SlashEstimate> = validator_simulate_slash,
+
+        ( "setup_check" / [validator: Address] )
+            -> ValidatorSetupReport = validator_setup_check,
+    },
+
+    ( "validators" ) = {
+        ( "detailed" / [offset: u64] / [limit: u64] / [epoch: opt Epoch] )
+            -> ValidatorsPage = validators_detailed,
     },
 
     ( "validator_set" ) = {
@@ -69,7 +118,14 @@ router! {POS,
         ( "below_capacity" / [epoch: opt Epoch] )
             -> BTreeSet<WeightedValidator> = below_capacity_validator_set,
 
-        // TODO: add "below_threshold"
+        ( "diff" / [from_epoch: Epoch] / [to_epoch: Epoch] )
+            -> Vec<ValidatorSetDiff> = consensus_validator_set_diff,
+
+        ( "snapshot" / [epoch: opt Epoch] )
+            -> Option<ValidatorSetSnapshot> = validator_set_snapshot,
+
+        ( "below_threshold" / [epoch: opt Epoch] )
+            -> HashSet<Address> = below_threshold_validator_set,
     },
 
     ( "pos_params") -> PosParams = pos_params,
@@ -77,6 +133,18 @@ router! {POS,
     ( "total_stake" / [epoch: opt Epoch] )
         -> token::Amount = total_stake,
 
+    ( "total_stake_range" / [from_epoch: Epoch] / [to_epoch: Epoch] )
+        -> BTreeMap<Epoch, token::Amount> = total_stake_range,
+
+    ( "staking_apr" / [epoch: opt Epoch] )
+        -> Dec = staking_apr,
+
+    ( "bonded_ratio" / [epoch: opt Epoch] )
+        -> Dec = bonded_ratio,
+
+    ( "inflation" / [epoch: Epoch] )
+        -> Option<token::Amount> = inflation_amount,
+
     ( "delegations" / [owner: Address] )
         -> HashSet<Address> = delegation_validators,
 
@@ -104,6 +172,12 @@ router! {POS,
     ( "withdrawable_tokens" / [source: Address] / [validator: Address] / [epoch: opt Epoch] )
         -> token::Amount = withdrawable_tokens,
 
+    ( "withdrawable_unbonds" / [source: Address] / [epoch: opt Epoch] )
+        -> Vec<WithdrawableUnbond> = withdrawable_unbonds,
+
+    ( "unbonding_queue" / [source: Address] / [validator: Address] / [epoch: opt Epoch] )
+        -> Vec<QueuedUnbond> = unbonding_queue,
+
     ( "bonds_and_unbonds" / [source: opt Address] / [validator: opt Address] )
         -> BondsAndUnbondsDetails = bonds_and_unbonds,
 
@@ -112,6 +186,12 @@ router! {POS,
 
     ( "all_slashes" ) -> HashMap<Address, Vec<Slash>> = slashes,
 
+    ( "slashes_page" / [from_epoch: Epoch] / [to_epoch: Epoch] / [offset: u64] / [limit: u64] / [validator: opt Address] / [status: opt SlashStatus] )
+        -> SlashesPage = slashes_page,
+
+    ( "cubic_slash_rate" / [infraction_epoch: opt Epoch] )
+        -> Dec = cubic_slash_rate,
+
     ( "is_delegator" / [addr: Address ] / [epoch: opt Epoch] ) -> bool = is_delegator,
 
     ( "validator_by_tm_addr" / [tm_addr: String] )
@@ -122,6 +202,30 @@ router! {POS,
     ( "has_bonds" / [source: Address] )
         -> bool = has_bonds,
 
+    ( "proposer_history" ) = {
+        ( "block_proposer" / [height: BlockHeight] )
+            -> Option<Address> = block_proposer,
+
+        ( "stats" / [from_height: BlockHeight] / [to_height: opt BlockHeight] )
+            -> ProposerStats = proposer_history_stats,
+    },
+
+    ( "staking_pool" ) = {
+        ( "share_price" ) -> Option<Dec> = staking_pool_share_price,
+
+        ( "value" ) -> token::Amount = staking_pool_value,
+
+        ( "validators" ) -> BTreeSet<Address> = staking_pool_validators,
+
+        ( "shares" / [owner: Address] ) -> token::Amount = staking_pool_shares,
+
+        ( "unbonds" / [owner: Address] )
+            -> BTreeMap<Epoch, token::Amount> = staking_pool_unbonds,
+    },
+
+    ( "simulate_bond_action" )
+        -> BondActionSimulation = (with_options simulate_bond_action),
+
 }
 
 /// Enriched bonds data with extra information calculated from the data queried
@@ -164,6 +268,64 @@ impl<T> Enriched<T> {
     }
 }
 
+/// The maximum number of validators returned by a single page of the
+/// detailed validators query
+pub const MAX_VALIDATORS_PAGE_SIZE: u64 = 100;
+
+/// The details of a validator at some epoch
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct ValidatorDetails {
+    /// The address of the validator
+    pub address: Address,
+    /// The state of the validator
+    pub state: Option<ValidatorState>,
+    /// The bonded stake of the validator
+    pub stake: token::Amount,
+    /// The commission rate and max commission rate change per epoch
+    pub commission: Option<CommissionPair>,
+    /// Whether the validator is jailed
+    pub is_jailed: bool,
+    /// The number of blocks the validator didn't sign within the last
+    /// liveness window, at the last committed block. Only tracked for
+    /// consensus validators.
+    pub missed_votes: Option<u64>,
+}
+
+/// A page of the validators known at some epoch, ordered by address
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct ValidatorsPage {
+    /// The details of the validators in this page
+    pub validators: Vec<ValidatorDetails>,
+    /// The total number of validators, across all pages
+    pub total: u64,
+}
+
+/// The maximum number of slashes returned by a single page of the slashes
+/// query
+pub const MAX_SLASHES_PAGE_SIZE: u64 = 100;
+
+/// A page of the slashes matching a filter
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct SlashesPage {
+    /// The slashes in this page
+    pub slashes: Vec<SlashRecord>,
+    /// The offset of the next page, if there are more matching slashes
+    pub next_offset: Option<u64>,
+}
+
+/// An unbond that is withdrawable at some epoch
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct WithdrawableUnbond {
+    /// The validator the tokens were unbonded from
+    pub validator: Address,
+    /// The epoch from which the unbonded bond contributed to the stake
+    pub start_epoch: Epoch,
+    /// The epoch from which the unbond is withdrawable
+    pub withdraw_epoch: Epoch,
+    /// The unbonded amount, before the application of slashes
+    pub amount: token::Amount,
+}
+
 // Handlers that implement the functions via `trait StorageRead`:
 
 /// Get the PoS parameters
@@ -216,6 +378,20 @@ where
     read_all_validator_addresses(ctx.wl_storage, epoch)
 }
 
+/// Get the consensus, eth hot, eth cold and protocol keys of all the known
+/// validators.
+fn validator_keys<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<HashMap<Address, ValidatorKeys>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_all_validator_keys(ctx.wl_storage, epoch)
+}
+
 /// Get the validator commission rate and max commission rate change per epoch
 fn validator_commission<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -227,24 +403,66 @@ where
     H: 'static + StorageHasher + Sync,
 {
     let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
-    let params = read_pos_params(ctx.wl_storage)?;
-    let commission_rate = validator_commission_rate_handle(&validator).get(
-        ctx.wl_storage,
-        epoch,
-        &params,
-    )?;
-    let max_commission_change_per_epoch =
-        read_validator_max_commission_rate_change(ctx.wl_storage, &validator)?;
-
-    match (commission_rate, max_commission_change_per_epoch) {
-        (Some(commission_rate), Some(max_commission_change_per_epoch)) => {
-            Ok(Some(CommissionPair {
-                commission_rate,
-                max_commission_change_per_epoch,
-            }))
-        }
-        _ => Ok(None),
-    }
+    PosQueryCtx::new(ctx.wl_storage)?.validator_commission(&validator, epoch)
+}
+
+/// Get all the commission rate changes of a validator, from the oldest to the
+/// most recent one
+fn validator_commission_history<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+) -> storage_api::Result<Vec<CommissionRateChange>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_commission_history(ctx.wl_storage, &validator)
+}
+
+/// Get a page of the details of all the known validators, in any state, at
+/// the given epoch or current when `None`. At most
+/// [`MAX_VALIDATORS_PAGE_SIZE`] validators are returned, starting from
+/// `offset` in the order of their addresses.
+fn validators_detailed<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    offset: u64,
+    limit: u64,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<ValidatorsPage>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    let pos = PosQueryCtx::new(ctx.wl_storage)?;
+    let addresses: BTreeSet<Address> =
+        read_all_validator_addresses(ctx.wl_storage, epoch)?
+            .into_iter()
+            .collect();
+    let total = addresses.len() as u64;
+    let missed_votes = liveness_sum_missed_votes_handle();
+
+    let validators = addresses
+        .into_iter()
+        .skip(offset.try_into().unwrap_or(usize::MAX))
+        .take(limit.min(MAX_VALIDATORS_PAGE_SIZE) as usize)
+        .map(|address| {
+            let state = pos.validator_state(&address, epoch)?;
+            let stake = pos.read_validator_stake(&address, epoch)?;
+            let commission = pos.validator_commission(&address, epoch)?;
+            let missed_votes = missed_votes.get(ctx.wl_storage, &address)?;
+            Ok(ValidatorDetails {
+                is_jailed: state == Some(ValidatorState::Jailed),
+                address,
+                state,
+                stake,
+                commission,
+                missed_votes,
+            })
+        })
+        .collect::<storage_api::Result<Vec<_>>>()?;
+
+    Ok(ValidatorsPage { validators, total })
 }
 
 /// Get the validator metadata
@@ -285,13 +503,7 @@ where
     H: 'static + StorageHasher + Sync,
 {
     let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
-    let params = read_pos_params(ctx.wl_storage)?;
-    let state = validator_state_handle(&validator).get(
-        ctx.wl_storage,
-        epoch,
-        &params,
-    )?;
-    Ok(state)
+    PosQueryCtx::new(ctx.wl_storage)?.validator_state(&validator, epoch)
 }
 
 /// Get the validator state
@@ -331,6 +543,34 @@ where
     }
 }
 
+/// Get the total stake of a validator in every epoch of the given inclusive
+/// range, in a single pass. Returns `Ok(None)` if the given address is not a
+/// validator address.
+fn validator_stake_range<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> storage_api::Result<Option<BTreeMap<Epoch, token::Amount>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    if namada_proof_of_stake::is_validator(ctx.wl_storage, &validator)? {
+        let stakes = read_validator_stake_range(
+            ctx.wl_storage,
+            &params,
+            &validator,
+            from_epoch,
+            to_epoch,
+        )?;
+        Ok(Some(stakes))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Get the incoming redelegation epoch for a source validator - delegator pair,
 /// if there is any.
 fn validator_incoming_redelegation<D, H, V, T>(
@@ -346,6 +586,21 @@ where
     handle.get(ctx.wl_storage, &delegator)
 }
 
+/// Check whether a delegator may redelegate its bonds from a source validator
+/// in the current epoch and if not, from which epoch it may.
+fn validator_redelegation_eligibility<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    src_validator: Address,
+    delegator: Address,
+) -> storage_api::Result<RedelegationEligibility>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    can_redelegate(ctx.wl_storage, &delegator, &src_validator, current_epoch)
+}
+
 /// Get all the validator in the consensus set with their bonded stake.
 fn consensus_validator_set<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -359,6 +614,22 @@ where
     read_consensus_validator_set_addresses_with_stake(ctx.wl_storage, epoch)
 }
 
+/// Get the snapshot of the consensus validator set at the given epoch or
+/// current when `None`. Returns `None` when there's no snapshot stored for the
+/// epoch. To get the snapshot with a proof, query its storage key with
+/// `storage_value`.
+fn validator_set_snapshot<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Option<ValidatorSetSnapshot>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_validator_set_snapshot(ctx.wl_storage, epoch)
+}
+
 /// Get all the validator in the below-capacity set with their bonded stake.
 fn below_capacity_validator_set<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -375,6 +646,40 @@ where
     )
 }
 
+/// Get all the validators in the below-threshold set.
+fn below_threshold_validator_set<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<HashSet<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_below_threshold_validator_set_addresses(ctx.wl_storage, epoch)
+}
+
+/// Get the changes in the consensus validator set from `from_epoch` to
+/// `to_epoch`.
+fn consensus_validator_set_diff<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> storage_api::Result<Vec<ValidatorSetDiff>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    compute_validator_set_diff(
+        ctx.wl_storage,
+        &params,
+        from_epoch,
+        to_epoch,
+        &ConsensusValidatorSetCache::default(),
+    )
+}
+
 /// Get the total stake in PoS system at the given epoch or current when `None`.
 fn total_stake<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -389,6 +694,77 @@ where
     read_total_stake(ctx.wl_storage, &params, epoch)
 }
 
+/// Get the total stake in PoS system in every epoch of the given inclusive
+/// range, in a single pass.
+fn total_stake_range<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> storage_api::Result<BTreeMap<Epoch, token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_total_stake_range(ctx.wl_storage, &params, from_epoch, to_epoch)
+}
+
+/// Get the estimated nominal APR of staking with the consensus validators at
+/// the given epoch or current when `None`.
+fn staking_apr<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Dec>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    estimate_staking_apr(ctx.wl_storage, epoch)
+}
+
+/// Get the ratio of the total stake to the total supply of the staking token
+/// at the given epoch or current when `None`.
+fn bonded_ratio<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Dec>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_bonded_ratio(ctx.wl_storage, epoch)
+}
+
+/// Get the PoS inflation amount that was applied for the rewards of the given
+/// epoch. This is `None` until the epoch has ended.
+fn inflation_amount<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> storage_api::Result<Option<token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_inflation_amount(ctx.wl_storage, epoch)
+}
+
+/// Get the estimated nominal APR of staking with the given validator at the
+/// given epoch or current when `None`.
+fn validator_staking_apr<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Dec>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    estimate_validator_staking_apr(ctx.wl_storage, &validator, epoch)
+}
+
 fn bond_deltas<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     source: Address,
@@ -519,6 +895,66 @@ where
     Ok(total)
 }
 
+/// Find the unbonding queue of the bond between the given `source` and
+/// `validator`, with the number of epochs left until each unbond becomes
+/// withdrawable as of the given epoch or current when `None`.
+fn unbonding_queue<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    source: Address,
+    validator: Address,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Vec<QueuedUnbond>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    find_withdrawable_unbonds(ctx.wl_storage, Some(&source), &validator, epoch)
+}
+
+/// Find all the unbonds of the given `source` address, from any validator,
+/// that are withdrawable at the given epoch or current when `None`. The
+/// unbonds are ordered by validator, then start and withdraw epochs.
+fn withdrawable_unbonds<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    source: Address,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Vec<WithdrawableUnbond>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+
+    let prefix =
+        namada_proof_of_stake::storage::unbonds_for_source_prefix(&source);
+    let mut unbonds = vec![];
+    for result in storage_api::iter_prefix(ctx.wl_storage, &prefix)? {
+        let (key, amount): (_, token::Amount) = result?;
+        let Some((bond_id, start_epoch, withdraw_epoch)) =
+            namada_proof_of_stake::storage::is_unbond_key(&key)
+        else {
+            continue;
+        };
+        if withdraw_epoch <= epoch {
+            unbonds.push(WithdrawableUnbond {
+                validator: bond_id.validator,
+                start_epoch,
+                withdraw_epoch,
+                amount,
+            });
+        }
+    }
+    unbonds.sort_by(|a, b| {
+        (&a.validator, a.start_epoch, a.withdraw_epoch).cmp(&(
+            &b.validator,
+            b.start_epoch,
+            b.withdraw_epoch,
+        ))
+    });
+    Ok(unbonds)
+}
+
 fn rewards<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     validator: Address,
@@ -546,7 +982,7 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    namada_proof_of_stake::bonds_and_unbonds(ctx.wl_storage, source, validator)
+    PosQueryCtx::new(ctx.wl_storage)?.bonds_and_unbonds(source, validator)
 }
 
 /// Find all the validator addresses to whom the given `owner` address has
@@ -590,6 +1026,21 @@ where
     slash_handle.iter(ctx.wl_storage)?.collect()
 }
 
+/// Get the cumulative rate of the validator's slashes processed at or before
+/// the given epoch or current when `None`.
+fn validator_cumulative_slash_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Dec>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_validator_cumulative_slash(ctx.wl_storage, &validator, epoch)
+}
+
 /// All slashes
 fn slashes<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -601,6 +1052,156 @@ where
     find_all_slashes(ctx.wl_storage)
 }
 
+/// Get a page of the processed and enqueued slashes of infractions committed
+/// in the given inclusive epoch range, optionally only of the given validator
+/// or with the given status. At most [`MAX_SLASHES_PAGE_SIZE`] slashes are
+/// returned, starting from `offset` in the order of
+/// [`namada_proof_of_stake::iter_slashes`].
+fn slashes_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+    offset: u64,
+    limit: u64,
+    validator: Option<Address>,
+    status: Option<SlashStatus>,
+) -> storage_api::Result<SlashesPage>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    let filter = SlashFilter {
+        validator,
+        from_epoch: Some(from_epoch),
+        to_epoch: Some(to_epoch),
+        status,
+    };
+    let limit = limit.min(MAX_SLASHES_PAGE_SIZE) as usize;
+    // Read one more slash than requested to find out if there's a next page
+    let mut slashes = iter_slashes(ctx.wl_storage, filter, current_epoch)?
+        .skip(offset.try_into().unwrap_or(usize::MAX))
+        .take(limit + 1)
+        .collect::<storage_api::Result<Vec<_>>>()?;
+    let next_offset = if slashes.len() > limit {
+        slashes.truncate(limit);
+        Some(offset + limit as u64)
+    } else {
+        None
+    };
+    Ok(SlashesPage {
+        slashes,
+        next_offset,
+    })
+}
+
+/// The cubic slash rate of the given infraction epoch or current when `None`,
+/// considering the slashes that are currently enqueued.
+fn cubic_slash_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    infraction_epoch: Option<Epoch>,
+) -> storage_api::Result<Dec>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let infraction_epoch =
+        infraction_epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    let params = read_pos_params(ctx.wl_storage)?;
+    compute_cubic_slash_rate(ctx.wl_storage, &params, infraction_epoch)
+}
+
+/// Estimate the slash that an infraction of the validator committed at the
+/// given epoch or current when `None` would incur once processed.
+/// Returns `None` when the given address is not a validator address.
+fn validator_simulate_slash<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    slash_type: SlashType,
+    infraction_epoch: Option<Epoch>,
+) -> storage_api::Result<Option<SlashEstimate>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let infraction_epoch =
+        infraction_epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    if namada_proof_of_stake::is_validator(ctx.wl_storage, &validator)? {
+        simulate_slash(ctx.wl_storage, &validator, infraction_epoch, slash_type)
+            .map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Check the setup of a validator at the pipeline epoch, to find out if it
+/// will enter the consensus validator set
+fn validator_setup_check<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+) -> storage_api::Result<ValidatorSetupReport>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_validator_setup(ctx.wl_storage, &validator)
+}
+
+/// Find the proposer of the block at the given height, if it's still in the
+/// proposer history
+fn block_proposer<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    height: BlockHeight,
+) -> storage_api::Result<Option<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_block_proposer(ctx.wl_storage, height)
+}
+
+/// Count the blocks proposed by each validator from `from_height` up to and
+/// including `to_height`, or the last committed block if not specified
+fn proposer_history_stats<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from_height: BlockHeight,
+    to_height: Option<BlockHeight>,
+) -> storage_api::Result<ProposerStats>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let to_height = to_height
+        .unwrap_or_else(|| ctx.wl_storage.storage.get_last_block_height());
+    proposer_stats(ctx.wl_storage, from_height, to_height)
+}
+
+/// Simulate the bond, unbond or redelegation encoded in the request data as
+/// if it was submitted in the current epoch. The action is applied on top of
+/// the latest state in a temporary write log, which is then discarded.
+fn simulate_bond_action<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let action =
+        BondAction::try_from_slice(&request.data).into_storage_result()?;
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    let mut temp_wl_storage = TempWlStorage::new(&ctx.wl_storage.storage);
+    let simulation = namada_proof_of_stake::simulate_bond_action(
+        &mut temp_wl_storage,
+        &action,
+        current_epoch,
+    )?;
+    Ok(EncodedResponseQuery {
+        data: simulation.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
 /// Enqueued slashes
 fn enqueued_slashes<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -648,6 +1249,69 @@ where
     namada_proof_of_stake::has_bonds(ctx.wl_storage, &source)
 }
 
+/// Get the value of a single staking pool share in the staking token, if any
+/// shares have been issued.
+fn staking_pool_share_price<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Option<Dec>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    staking_pool::staking_pool_share_price(ctx.wl_storage, current_epoch)
+}
+
+/// Get the value of the staking pool that backs its shares.
+fn staking_pool_value<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    staking_pool::staking_pool_value(ctx.wl_storage, current_epoch)
+}
+
+/// Get the validators that the staking pool delegates to.
+fn staking_pool_validators<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    staking_pool::read_staking_pool_validators(ctx.wl_storage)
+}
+
+/// Get the staking pool shares of the given depositor.
+fn staking_pool_shares<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+) -> storage_api::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let shares = staking_pool::staking_pool_shares_handle()
+        .get(ctx.wl_storage, &owner)?;
+    Ok(shares.unwrap_or_default())
+}
+
+/// Get the pooled unbonds of the given depositor, keyed by the epoch from
+/// which they can be withdrawn.
+fn staking_pool_unbonds<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+) -> storage_api::Result<BTreeMap<Epoch, token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    staking_pool::read_staking_pool_unbonds(ctx.wl_storage, &owner)
+}
+
 /// Client-only methods for the router type are composed from router functions.
 #[cfg(any(test, feature = "async-client"))]
 pub mod client_only_methods {
@@ -0,0 +1,191 @@
+//! Queries router and handlers for the export of the whole PoS state, enabled
+//! with the `pos-state-export` feature. The export can be large, so it is not
+//! meant to be served by public nodes.
+
+use std::collections::BTreeMap;
+
+use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+use namada_core::ledger::storage_api::{self, ResultExt};
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use namada_proof_of_stake::export::{
+    export_pos_state, PosStateExport, ValidatorExport,
+};
+use namada_proof_of_stake::types::{Slash, WeightedValidator};
+use serde_json::{json, Value};
+
+use crate::queries::RequestCtx;
+
+// PoS state export queries
+router! {POS_STATE,
+    ( "borsh" / [epoch: opt Epoch] )
+        -> PosStateExport = pos_state_borsh,
+
+    ( "json" / [epoch: opt Epoch] )
+        -> String = pos_state_json,
+}
+
+/// Export the whole PoS state at the given epoch or current when `None`
+fn pos_state_borsh<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<PosStateExport>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    export_pos_state(ctx.wl_storage, epoch)
+}
+
+/// Export the whole PoS state at the given epoch or current when `None`,
+/// rendered as pretty-printed JSON
+fn pos_state_json<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<String>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    let export = export_pos_state(ctx.wl_storage, epoch)?;
+    serde_json::to_string_pretty(&pos_state_to_json(&export))
+        .into_storage_result()
+}
+
+/// Render a PoS state export as JSON. The token amounts and changes are
+/// rendered as strings in the native denomination and the maps keyed by
+/// epochs are rendered as objects keyed by the epochs' numbers.
+pub fn pos_state_to_json(export: &PosStateExport) -> Value {
+    let validators: serde_json::Map<String, Value> = export
+        .validators
+        .iter()
+        .map(|(address, validator)| {
+            (address.to_string(), validator_to_json(validator))
+        })
+        .collect();
+    let bonds: Vec<Value> = export
+        .bonds
+        .iter()
+        .map(|(bond_id, deltas)| {
+            json!({
+                "source": bond_id.source.to_string(),
+                "validator": bond_id.validator.to_string(),
+                "deltas": epoch_map_to_json(deltas, amount_to_json),
+            })
+        })
+        .collect();
+    let unbonds: Vec<Value> = export
+        .unbonds
+        .iter()
+        .map(|(bond_id, unbonds)| {
+            let unbonds: Vec<Value> = unbonds
+                .iter()
+                .map(|((start_epoch, withdraw_epoch), amount)| {
+                    json!({
+                        "start_epoch": start_epoch.0,
+                        "withdraw_epoch": withdraw_epoch.0,
+                        "amount": amount.to_string_native(),
+                    })
+                })
+                .collect();
+            json!({
+                "source": bond_id.source.to_string(),
+                "validator": bond_id.validator.to_string(),
+                "unbonds": unbonds,
+            })
+        })
+        .collect();
+    let enqueued_slashes: serde_json::Map<String, Value> = export
+        .enqueued_slashes
+        .iter()
+        .map(|(address, slashes)| {
+            let slashes = epoch_map_to_json(slashes, |slashes| {
+                slashes.iter().map(slash_to_json).collect()
+            });
+            (address.to_string(), slashes)
+        })
+        .collect();
+
+    json!({
+        "epoch": export.epoch.0,
+        "params": format!("{:?}", export.params),
+        "total_stake": export.total_stake.to_string_native(),
+        "total_deltas": epoch_map_to_json(&export.total_deltas, change_to_json),
+        "consensus_set": validator_set_to_json(&export.consensus_set),
+        "below_capacity_set":
+            validator_set_to_json(&export.below_capacity_set),
+        "below_threshold_set": export
+            .below_threshold_set
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        "validators": validators,
+        "bonds": bonds,
+        "unbonds": unbonds,
+        "enqueued_slashes": enqueued_slashes,
+    })
+}
+
+fn validator_to_json(validator: &ValidatorExport) -> Value {
+    json!({
+        "state": validator.state.as_ref().map(|state| format!("{:?}", state)),
+        "stake": validator.stake.to_string_native(),
+        "deltas": epoch_map_to_json(&validator.deltas, change_to_json),
+        "commission_rate": validator
+            .commission_rate
+            .as_ref()
+            .map(ToString::to_string),
+        "max_commission_rate_change": validator
+            .max_commission_rate_change
+            .as_ref()
+            .map(ToString::to_string),
+        "slashes": validator
+            .slashes
+            .iter()
+            .map(slash_to_json)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn validator_set_to_json<'a>(
+    set: impl IntoIterator<Item = &'a WeightedValidator>,
+) -> Value {
+    set.into_iter()
+        .map(|validator| {
+            json!({
+                "address": validator.address.to_string(),
+                "bonded_stake": validator.bonded_stake.to_string_native(),
+            })
+        })
+        .collect()
+}
+
+fn slash_to_json(slash: &Slash) -> Value {
+    json!({
+        "epoch": slash.epoch.0,
+        "block_height": slash.block_height,
+        "type": slash.r#type.to_string(),
+        "rate": slash.rate.to_string(),
+    })
+}
+
+fn epoch_map_to_json<V>(
+    map: &BTreeMap<Epoch, V>,
+    to_json: impl Fn(&V) -> Value,
+) -> Value {
+    Value::Object(
+        map.iter()
+            .map(|(epoch, value)| (epoch.0.to_string(), to_json(value)))
+            .collect(),
+    )
+}
+
+fn amount_to_json(amount: &token::Amount) -> Value {
+    Value::String(amount.to_string_native())
+}
+
+fn change_to_json(change: &token::Change) -> Value {
+    Value::String(change.to_string_native())
+}
@@ -5,18 +5,22 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::ControlFlow;
 
 use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
+use namada_core::ledger::gas::Gas;
 use namada_core::ledger::governance::parameters::GovernanceParameters;
 use namada_core::ledger::governance::storage::proposal::StorageProposal;
 use namada_core::ledger::governance::utils::Vote;
 use namada_core::ledger::ibc::storage::{
     ibc_denom_key, ibc_denom_key_prefix, is_ibc_denom_key,
 };
+use namada_core::ledger::parameters::storage as parameter_storage;
 use namada_core::ledger::storage::LastBlock;
 use namada_core::types::account::Account;
 use namada_core::types::address::{Address, InternalAddress};
+use namada_core::types::dec::Dec;
 use namada_core::types::hash::Hash;
 use namada_core::types::key::common;
 use namada_core::types::storage::{
@@ -25,10 +29,16 @@ use namada_core::types::storage::{
 use namada_core::types::token::{
     Amount, DenominatedAmount, Denomination, MaspDenom,
 };
+use namada_core::types::transaction::{GasLimit, TxErrorPayload};
 use namada_core::types::{storage, token};
 use namada_proof_of_stake::parameters::PosParams;
+use namada_proof_of_stake::proposer_history::ProposerStats;
+use namada_proof_of_stake::setup_check::ValidatorSetupReport;
 use namada_proof_of_stake::types::{
-    BondsAndUnbondsDetails, CommissionPair, ValidatorMetaData, ValidatorState,
+    BondAction, BondActionSimulation, BondsAndUnbondsDetails, CommissionPair,
+    CommissionRateChange, QueuedUnbond, RedelegationEligibility, Slash,
+    SlashEstimate, SlashStatus, SlashType, ValidatorKeys, ValidatorMetaData,
+    ValidatorSetDiff, ValidatorSetSnapshot, ValidatorState,
 };
 use serde::Serialize;
 
@@ -38,8 +48,11 @@ use crate::error::{EncodingError, Error, QueryError, TxError};
 use crate::events::Event;
 use crate::internal_macros::echo_error;
 use crate::io::Io;
-use crate::proto::Tx;
-use crate::queries::vp::pos::EnrichedBondsAndUnbondsDetails;
+use crate::proto::{Memo, Tx};
+use crate::queries::vp::pos::{
+    EnrichedBondsAndUnbondsDetails, SlashesPage, ValidatorsPage,
+    WithdrawableUnbond,
+};
 use crate::queries::{Client, RPC};
 use crate::tendermint::block::Height;
 use crate::tendermint::merkle::proof::ProofOps;
@@ -477,14 +490,157 @@ pub async fn dry_run_tx<N: Namada>(
     tx_bytes: Vec<u8>,
 ) -> Result<namada_core::types::transaction::TxResult, Error> {
     let (data, height, prove) = (Some(tx_bytes), None, false);
-    let result = convert_response::<N::Client, _>(
+    let result = RPC
+        .shell()
+        .dry_run_tx(context.client(), data, height, prove)
+        .await
+        .map_err(|err| {
+            // Decode the error the tx failed with, if it reported any
+            let err = err.to_string();
+            match TxErrorPayload::from_info(&err) {
+                Some(payload) => Error::from(TxError::Failed(payload)),
+                None => Error::from(QueryError::NoResponse(err)),
+            }
+        })?
+        .data;
+    display_line!(context.io(), "Dry-run result: {}", result);
+    Ok(result)
+}
+
+/// Query the conversion rates of the tokens whitelisted by governance for fee
+/// payment
+pub async fn query_fee_token_conversion_rates<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+) -> Result<BTreeMap<Address, Dec>, Error> {
+    let key = parameter_storage::get_fee_token_conversion_rates_key();
+    if query_has_storage_key(client, &key).await? {
+        query_storage_value(client, &key).await
+    } else {
+        Ok(BTreeMap::new())
+    }
+}
+
+/// Query the cost per unit of gas of every token accepted for fee payment,
+/// including the tokens whitelisted with a conversion rate to the native token
+pub async fn query_fee_gas_costs<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<BTreeMap<Address, token::Amount>, Error> {
+    let gas_cost_key = parameter_storage::get_gas_cost_key();
+    let gas_cost_table = query_storage_value::<
+        C,
+        BTreeMap<Address, token::Amount>,
+    >(client, &gas_cost_key)
+    .await?;
+    let conversion_rates = query_fee_token_conversion_rates(client).await?;
+    let native_token = query_native_token(client).await?;
+    Ok(namada_core::ledger::parameters::fee_gas_costs(
+        gas_cost_table,
+        &conversion_rates,
+        &native_token,
+    ))
+}
+
+/// A token that can be used to pay the fees of a transaction
+#[derive(Debug, Clone)]
+pub struct FeeOption {
+    /// The fee token
+    pub token: Address,
+    /// The minimum price per unit of gas in this token
+    pub gas_cost: token::Amount,
+    /// The minimum fee to pay for the gas limit in this token
+    pub minimum_fee: token::Amount,
+    /// The balance of the fee payer in this token
+    pub balance: token::Amount,
+}
+
+impl FeeOption {
+    /// Whether the fee payer can afford the minimum fee in this token
+    pub fn is_affordable(&self) -> bool {
+        self.balance >= self.minimum_fee
+    }
+}
+
+/// Query the fee options of a fee payer for the given gas limit, one for
+/// every token accepted for fee payment
+pub async fn query_fee_options<C: crate::queries::Client + Sync>(
+    client: &C,
+    fee_payer: &Address,
+    gas_limit: GasLimit,
+) -> Result<Vec<FeeOption>, Error> {
+    let gas_costs = query_fee_gas_costs(client).await?;
+    let mut options = Vec::with_capacity(gas_costs.len());
+    for (token, gas_cost) in gas_costs {
+        let minimum_fee = gas_cost
+            .checked_mul(token::Amount::from(u64::from(gas_limit)))
+            .ok_or_else(|| {
+                Error::Other(
+                    "Overflow in the computation of the minimum fee"
+                        .to_string(),
+                )
+            })?;
+        let balance_key = token::balance_key(&token, fee_payer);
+        let balance =
+            query_storage_value::<C, token::Amount>(client, &balance_key)
+                .await
+                .unwrap_or_default();
+        options.push(FeeOption {
+            token,
+            gas_cost,
+            minimum_fee,
+            balance,
+        });
+    }
+    Ok(options)
+}
+
+/// The estimated cost of a transaction
+#[derive(Debug, Clone)]
+pub struct GasEstimate {
+    /// The gas used by the dry run of the transaction, including the gas
+    /// used by its wrapper (if any)
+    pub gas_used: Gas,
+    /// The smallest gas limit covering the gas used
+    pub gas_limit: GasLimit,
+    /// The minimum fee to pay for the gas limit in each of the tokens
+    /// accepted for fee payment
+    pub minimum_fees: BTreeMap<Address, token::Amount>,
+}
+
+/// Estimate the gas used by a transaction by dry running it against the last
+/// committed state, along with the minimum fee given the current gas prices
+pub async fn estimate_gas<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx: &Tx,
+) -> Result<GasEstimate, Error> {
+    let gas_used = convert_response::<C, _>(
         RPC.shell()
-            .dry_run_tx(context.client(), data, height, prove)
+            .estimate_gas(client, Some(tx.to_bytes()), None, false)
             .await,
     )?
     .data;
-    display_line!(context.io(), "Dry-run result: {}", result);
-    Ok(result)
+    let gas_limit = GasLimit::from(gas_used.get_whole_gas_units());
+    let gas_costs = query_fee_gas_costs(client).await?;
+    let minimum_fees = gas_costs
+        .into_iter()
+        .map(|(token, gas_cost)| {
+            gas_cost
+                .checked_mul(token::Amount::from(u64::from(gas_limit)))
+                .map(|fee| (token, fee))
+                .ok_or_else(|| {
+                    Error::Other(
+                        "Overflow in the computation of the minimum fee"
+                            .to_string(),
+                    )
+                })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(GasEstimate {
+        gas_used,
+        gas_limit,
+        minimum_fees,
+    })
 }
 
 /// Data needed for broadcasting a tx and
@@ -525,6 +681,10 @@ pub struct TxResponse {
     pub gas_used: String,
     /// Initialized accounts
     pub initialized_accounts: Vec<Address>,
+    /// The machine-readable error of a failed tx, if any
+    pub error: Option<TxErrorPayload>,
+    /// The memo attached to the tx, if any
+    pub memo: Option<Memo>,
 }
 
 impl TryFrom<Event> for TxResponse {
@@ -568,6 +728,11 @@ impl TryFrom<Event> for TxResponse {
                 serde_json::from_str(initialized_accounts)
                     .map_err(|err| format!("JSON decode error: {err}"))
             })?;
+        let error = tx_error_payload(event.get("error"), &info);
+        let memo = event
+            .get("memo")
+            .map(|memo| memo.parse::<Memo>())
+            .transpose()?;
 
         Ok(TxResponse {
             hash,
@@ -577,6 +742,8 @@ impl TryFrom<Event> for TxResponse {
             code,
             gas_used,
             initialized_accounts,
+            error,
+            memo,
         })
     }
 }
@@ -590,6 +757,17 @@ impl TxResponse {
     }
 }
 
+/// Decode the error payload of a tx event from its `error` attribute, or
+/// failing that, from its info
+fn tx_error_payload(
+    error: Option<impl AsRef<str>>,
+    info: &str,
+) -> Option<TxErrorPayload> {
+    error
+        .and_then(|error| serde_json::from_str(error.as_ref()).ok())
+        .or_else(|| TxErrorPayload::from_info(info))
+}
+
 /// Lookup the full response accompanying the specified transaction event
 // TODO: maybe remove this in favor of `query_tx_status`
 pub async fn query_tx_response<C: crate::queries::Client + Sync>(
@@ -657,10 +835,35 @@ pub async fn query_tx_response<C: crate::queries::Client + Sync>(
             event_map["initialized_accounts"],
         )
         .unwrap_or_default(),
+        error: tx_error_payload(event_map.get("error"), event_map["info"]),
+        memo: event_map
+            .get("memo")
+            .and_then(|memo| memo.parse::<Memo>().ok()),
     };
     Ok(result)
 }
 
+/// Query the memo attached to the applied transaction with the given hash, as
+/// recorded in the event log of the node. Returns `None` if the transaction
+/// carries no memo, and fails if the node has no record of the transaction
+/// having been applied.
+pub async fn query_tx_memo<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx_hash: &str,
+) -> Result<Option<Memo>, error::Error> {
+    let event = query_tx_events(client, TxEventQuery::Applied(tx_hash))
+        .await
+        .map_err(|err| Error::from(QueryError::General(err.to_string())))?
+        .ok_or_else(|| {
+            Error::from(QueryError::General(format!(
+                "No applied transaction found with hash {tx_hash}"
+            )))
+        })?;
+    let response = TxResponse::try_from(event)
+        .map_err(|err| Error::from(EncodingError::Decoding(err)))?;
+    Ok(response.memo)
+}
+
 /// Get the PoS parameters
 pub async fn get_pos_params<C: crate::queries::Client + Sync>(
     client: &C,
@@ -681,6 +884,51 @@ pub async fn get_all_validators<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get the validators whose stake is below the validator stake threshold in
+/// the given epoch
+pub async fn get_below_threshold_validators<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<HashSet<Address>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .below_threshold_validator_set(client, &Some(epoch))
+            .await,
+    )
+}
+
+/// Get a page of the details of all the validators in the given epoch, i.e.
+/// their state, stake, commission and liveness, in the order of their
+/// addresses. At most [`crate::queries::vp::pos::MAX_VALIDATORS_PAGE_SIZE`]
+/// validators are returned per page.
+pub async fn get_validators_detailed<C: crate::queries::Client + Sync>(
+    client: &C,
+    offset: u64,
+    limit: u64,
+    epoch: Option<Epoch>,
+) -> Result<ValidatorsPage, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validators_detailed(client, &offset, &limit, &epoch)
+            .await,
+    )
+}
+
+/// Get the consensus, eth hot, eth cold and protocol keys of all validators in
+/// the given epoch
+pub async fn get_all_validator_keys<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<HashMap<Address, ValidatorKeys>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().validator_keys(client, &Some(epoch)).await,
+    )
+}
+
 /// Get the total staked tokens in the given epoch
 pub async fn get_total_staked_tokens<C: crate::queries::Client + Sync>(
     client: &C,
@@ -691,6 +939,201 @@ pub async fn get_total_staked_tokens<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get the total staked tokens in every epoch of the given inclusive range
+pub async fn get_total_staked_tokens_range<C: crate::queries::Client + Sync>(
+    client: &C,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> Result<BTreeMap<Epoch, token::Amount>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .total_stake_range(client, &from_epoch, &to_epoch)
+            .await,
+    )
+}
+
+/// Get the estimated nominal APR of staking at the given epoch, net of the
+/// validators' average commission
+pub async fn get_staking_apr<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Option<Epoch>,
+) -> Result<Dec, error::Error> {
+    convert_response::<C, _>(RPC.vp().pos().staking_apr(client, &epoch).await)
+}
+
+/// Get the ratio of the total staked tokens to the total supply of the
+/// staking token at the given epoch
+pub async fn get_bonded_ratio<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Option<Epoch>,
+) -> Result<Dec, error::Error> {
+    convert_response::<C, _>(RPC.vp().pos().bonded_ratio(client, &epoch).await)
+}
+
+/// Get the PoS inflation amount that was applied for the rewards of the given
+/// epoch, if the epoch has ended
+pub async fn get_pos_inflation_amount<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<Option<token::Amount>, error::Error> {
+    convert_response::<C, _>(RPC.vp().pos().inflation(client, &epoch).await)
+}
+
+/// Get the value of a single PoS staking pool share in the staking token, if
+/// any shares have been issued
+pub async fn get_staking_pool_share_price<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Option<Dec>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().staking_pool_share_price(client).await,
+    )
+}
+
+/// Get the PoS staking pool shares of the given depositor
+pub async fn get_staking_pool_shares<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().staking_pool_shares(client, owner).await,
+    )
+}
+
+/// Get the pooled unbonds of the given PoS staking pool depositor, keyed by
+/// the epoch from which they can be withdrawn
+pub async fn get_staking_pool_unbonds<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<BTreeMap<Epoch, token::Amount>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().staking_pool_unbonds(client, owner).await,
+    )
+}
+
+/// Get the estimated nominal APR of staking with the given validator at the
+/// given epoch, net of its commission
+pub async fn get_validator_staking_apr<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    epoch: Option<Epoch>,
+) -> Result<Dec, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validator_staking_apr(client, validator, &epoch)
+            .await,
+    )
+}
+
+/// Get the cumulative rate of the given validator's slashes processed at or
+/// before the given epoch
+pub async fn get_validator_cumulative_slash_rate<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    validator: &Address,
+    epoch: Option<Epoch>,
+) -> Result<Dec, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validator_cumulative_slash_rate(client, validator, &epoch)
+            .await,
+    )
+}
+
+/// Get a page of the processed and enqueued slashes of infractions committed
+/// in the given inclusive epoch range, optionally only of the given validator
+/// or with the given status. At most
+/// [`crate::queries::vp::pos::MAX_SLASHES_PAGE_SIZE`] slashes are returned per
+/// page and the next page, if any, starts from the returned `next_offset`.
+pub async fn get_slashes_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+    offset: u64,
+    limit: u64,
+    validator: Option<Address>,
+    status: Option<SlashStatus>,
+) -> Result<SlashesPage, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .slashes_page(
+                client,
+                &from_epoch,
+                &to_epoch,
+                &offset,
+                &limit,
+                &validator,
+                &status,
+            )
+            .await,
+    )
+}
+
+/// Get the cubic slash rate of the given infraction epoch, considering the
+/// slashes that are currently enqueued
+pub async fn get_cubic_slash_rate<C: crate::queries::Client + Sync>(
+    client: &C,
+    infraction_epoch: Option<Epoch>,
+) -> Result<Dec, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .cubic_slash_rate(client, &infraction_epoch)
+            .await,
+    )
+}
+
+/// Estimate the slash that an infraction of the given validator committed at
+/// the given epoch would incur once processed. Returns `None` when the given
+/// address is not a validator address.
+pub async fn simulate_validator_slash<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    slash_type: SlashType,
+    infraction_epoch: Option<Epoch>,
+) -> Result<Option<SlashEstimate>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validator_simulate_slash(
+                client,
+                validator,
+                &slash_type,
+                &infraction_epoch,
+            )
+            .await,
+    )
+}
+
+/// Query the snapshot of the consensus validator set of the given epoch with a
+/// proof of its inclusion in the storage Merkle tree at the given block
+/// height, or the last committed height when `None`. The snapshot is `None`
+/// when there's none stored for the epoch, in which case the proof is a
+/// non-existence proof.
+pub async fn query_validator_set_snapshot_with_proof<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    epoch: Epoch,
+    height: Option<BlockHeight>,
+) -> Result<(Option<ValidatorSetSnapshot>, Option<ProofOps>), error::Error> {
+    let key = namada_proof_of_stake::storage::validator_set_snapshot_key(epoch);
+    let (value, proof) =
+        query_storage_value_bytes(client, &key, height, true).await?;
+    let snapshot = value
+        .map(|bytes| {
+            ValidatorSetSnapshot::try_from_slice(&bytes).map_err(|err| {
+                Error::from(EncodingError::Decoding(err.to_string()))
+            })
+        })
+        .transpose()?;
+    Ok((snapshot, proof))
+}
+
 /// Get the given validator's stake at the given epoch
 pub async fn get_validator_stake<C: crate::queries::Client + Sync>(
     client: &C,
@@ -706,6 +1149,22 @@ pub async fn get_validator_stake<C: crate::queries::Client + Sync>(
     .map(|t| t.unwrap_or_default())
 }
 
+/// Get the total stake of a validator in every epoch of the given inclusive
+/// range, or `None` if the address is not a validator
+pub async fn get_validator_stake_range<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> Result<Option<BTreeMap<Epoch, token::Amount>>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validator_stake_range(client, validator, &from_epoch, &to_epoch)
+            .await,
+    )
+}
+
 /// Query and return a validator's state
 pub async fn get_validator_state<C: crate::queries::Client + Sync>(
     client: &C,
@@ -720,6 +1179,63 @@ pub async fn get_validator_state<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Check the setup of a validator at the pipeline epoch, to find out if it
+/// will enter the consensus validator set
+pub async fn check_validator_setup<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<ValidatorSetupReport, error::Error> {
+    convert_response::<C, ValidatorSetupReport>(
+        RPC.vp()
+            .pos()
+            .validator_setup_check(client, validator)
+            .await,
+    )
+}
+
+/// Get the proposer of the block at the given height, if it's still in the
+/// proposer history
+pub async fn get_block_proposer<C: crate::queries::Client + Sync>(
+    client: &C,
+    height: BlockHeight,
+) -> Result<Option<Address>, error::Error> {
+    convert_response::<C, Option<Address>>(
+        RPC.vp().pos().block_proposer(client, &height).await,
+    )
+}
+
+/// Get the number of blocks proposed by each validator from `from_height` up
+/// to and including `to_height`, or the last committed block if `None`
+pub async fn get_proposer_stats<C: crate::queries::Client + Sync>(
+    client: &C,
+    from_height: BlockHeight,
+    to_height: Option<BlockHeight>,
+) -> Result<ProposerStats, error::Error> {
+    convert_response::<C, ProposerStats>(
+        RPC.vp()
+            .pos()
+            .proposer_history_stats(client, &from_height, &to_height)
+            .await,
+    )
+}
+
+/// Get the changes in the consensus validator set from `from_epoch` to
+/// `to_epoch`
+pub async fn get_consensus_validator_set_diff<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> Result<Vec<ValidatorSetDiff>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .consensus_validator_set_diff(client, &from_epoch, &to_epoch)
+            .await,
+    )
+}
+
 /// Get the delegator's delegation
 pub async fn get_delegators_delegation<C: crate::queries::Client + Sync>(
     client: &C,
@@ -769,6 +1285,20 @@ pub async fn query_commission_rate<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query all the commission rate changes of a validator, from the oldest to
+/// the most recent one
+pub async fn query_commission_history<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<Vec<CommissionRateChange>, Error> {
+    convert_response::<C, Vec<CommissionRateChange>>(
+        RPC.vp()
+            .pos()
+            .validator_commission_history(client, validator)
+            .await,
+    )
+}
+
 /// Query and return validator's metadata, including the commission rate and max
 /// commission rate change
 pub async fn query_metadata<C: crate::queries::Client + Sync>(
@@ -803,6 +1333,44 @@ pub async fn query_incoming_redelegations<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query whether the given delegator may redelegate its bonds from the source
+/// validator in the current epoch and if not, from which epoch it may.
+pub async fn query_redelegation_eligibility<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    src_validator: &Address,
+    delegator: &Address,
+) -> Result<RedelegationEligibility, Error> {
+    convert_response::<C, RedelegationEligibility>(
+        RPC.vp()
+            .pos()
+            .validator_redelegation_eligibility(
+                client,
+                src_validator,
+                delegator,
+            )
+            .await,
+    )
+}
+
+/// Dry run a bond, unbond or redelegation against the latest state, as if it
+/// was submitted in the current epoch, without broadcasting any transaction.
+pub async fn simulate_bond_action<C: crate::queries::Client + Sync>(
+    client: &C,
+    action: &BondAction,
+) -> Result<BondActionSimulation, Error> {
+    let data = Some(action.serialize_to_vec());
+    let simulation = convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .simulate_bond_action(client, data, None, false)
+            .await,
+    )?
+    .data;
+    Ok(simulation)
+}
+
 /// Query a validator's bonds for a given epoch
 pub async fn query_bond<C: crate::queries::Client + Sync>(
     client: &C,
@@ -868,19 +1436,29 @@ pub async fn query_and_print_unbonds(
     source: &Address,
     validator: &Address,
 ) -> Result<(), error::Error> {
-    let unbonds =
-        query_unbond_with_slashing(context.client(), source, validator).await?;
     let current_epoch = query_epoch(context.client()).await?;
+    let unbonds = query_unbonding_queue(
+        context.client(),
+        source,
+        validator,
+        Some(current_epoch),
+    )
+    .await?;
 
     let mut total_withdrawable = token::Amount::zero();
-    let mut not_yet_withdrawable = HashMap::<Epoch, token::Amount>::new();
-    for ((_start_epoch, withdraw_epoch), amount) in unbonds.into_iter() {
-        if withdraw_epoch <= current_epoch {
-            total_withdrawable += amount;
+    let mut not_yet_withdrawable =
+        BTreeMap::<Epoch, (u64, token::Amount)>::new();
+    for unbond in unbonds {
+        if unbond.epochs_until_withdrawable == 0 {
+            total_withdrawable += unbond.withdrawable_amount;
         } else {
-            let withdrawable_amount =
-                not_yet_withdrawable.entry(withdraw_epoch).or_default();
-            *withdrawable_amount += amount;
+            let (_, withdrawable_amount) = not_yet_withdrawable
+                .entry(unbond.withdraw_epoch)
+                .or_insert((
+                    unbond.epochs_until_withdrawable,
+                    token::Amount::zero(),
+                ));
+            *withdrawable_amount += unbond.withdrawable_amount;
         }
     }
     if !total_withdrawable.is_zero() {
@@ -893,16 +1471,35 @@ pub async fn query_and_print_unbonds(
     if !not_yet_withdrawable.is_empty() {
         display_line!(context.io(), "Current epoch: {current_epoch}.")
     }
-    for (withdraw_epoch, amount) in not_yet_withdrawable {
+    for (withdraw_epoch, (epochs_left, amount)) in not_yet_withdrawable {
         display_line!(
             context.io(),
-            "Amount {} withdrawable starting from epoch {withdraw_epoch}.",
+            "Amount {} withdrawable starting from epoch {withdraw_epoch} (in \
+             {epochs_left} epochs).",
             amount.to_string_native()
         );
     }
     Ok(())
 }
 
+/// Query the unbonding queue of a bond, i.e. its unbonds with the number of
+/// epochs left until each becomes withdrawable and the amount withdrawable
+/// after the application of slashes, as of the given epoch or current when
+/// `None`
+pub async fn query_unbonding_queue<C: crate::queries::Client + Sync>(
+    client: &C,
+    source: &Address,
+    validator: &Address,
+    epoch: Option<Epoch>,
+) -> Result<Vec<QueuedUnbond>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .unbonding_queue(client, source, validator, &epoch)
+            .await,
+    )
+}
+
 /// Query withdrawable tokens in a validator account for a given epoch
 pub async fn query_withdrawable_tokens<C: crate::queries::Client + Sync>(
     client: &C,
@@ -918,6 +1515,33 @@ pub async fn query_withdrawable_tokens<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query the unbonds of `bond_source` from any validator that are withdrawable
+/// at the given epoch, or at the current epoch if `None`
+pub async fn query_withdrawable_unbonds<C: crate::queries::Client + Sync>(
+    client: &C,
+    bond_source: &Address,
+    epoch: Option<Epoch>,
+) -> Result<Vec<WithdrawableUnbond>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .withdrawable_unbonds(client, bond_source, &epoch)
+            .await,
+    )
+}
+
+/// Query the reward tokens available to claim for the bonds of `source`
+/// (or the self-bonds of the validator, if `None`) to the given validator
+pub async fn query_rewards<C: crate::queries::Client + Sync>(
+    client: &C,
+    source: &Option<Address>,
+    validator: &Address,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, token::Amount>(
+        RPC.vp().pos().rewards(client, validator, source).await,
+    )
+}
+
 /// Query all unbonds for a validator, applying slashes
 pub async fn query_unbond_with_slashing<C: crate::queries::Client + Sync>(
     client: &C,
@@ -1002,6 +1626,74 @@ pub async fn enriched_bonds_and_unbonds<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Summary of the delegations of some owner to a single validator. All the
+/// amounts are after the application of slashes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DelegationSummary {
+    /// Bonded tokens contributing to the validator's stake at the epoch
+    pub bonded: token::Amount,
+    /// Bonded tokens that will only contribute to the stake after the epoch
+    pub pending: token::Amount,
+    /// Unbonded tokens that are not yet withdrawable at the epoch
+    pub unbonding: token::Amount,
+    /// Unbonded tokens that are withdrawable at the epoch
+    pub withdrawable: token::Amount,
+    /// Reward tokens available to claim as of the last committed epoch
+    pub rewards: token::Amount,
+    /// Slashes applied to any of the bonds and/or unbonds
+    pub slashes: Vec<Slash>,
+}
+
+/// Get a summary of the bonds, unbonds, rewards and slashes of the
+/// delegations of `owner`, per validator, at the given epoch or at the last
+/// committed one. The underlying queries are issued concurrently.
+pub async fn get_delegation_summary<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+    epoch: Option<Epoch>,
+) -> Result<BTreeMap<Address, DelegationSummary>, error::Error> {
+    let source = Some(owner.clone());
+    let epoch = async {
+        match epoch {
+            Some(epoch) => Ok(epoch),
+            None => query_epoch(client).await,
+        }
+    };
+    let (epoch, details) =
+        futures::try_join!(epoch, bonds_and_unbonds(client, &source, &None))?;
+    let details: Vec<_> = details.into_iter().collect();
+    let rewards =
+        futures::future::try_join_all(details.iter().map(|(bond_id, _)| {
+            query_rewards(client, &source, &bond_id.validator)
+        }))
+        .await?;
+
+    let mut summaries = BTreeMap::<Address, DelegationSummary>::new();
+    for ((bond_id, detail), rewards) in details.into_iter().zip(rewards) {
+        let summary = summaries.entry(bond_id.validator).or_default();
+        for bond in detail.bonds {
+            let amount = bond.amount - bond.slashed_amount.unwrap_or_default();
+            if bond.start <= epoch {
+                summary.bonded += amount;
+            } else {
+                summary.pending += amount;
+            }
+        }
+        for unbond in detail.unbonds {
+            let amount =
+                unbond.amount - unbond.slashed_amount.unwrap_or_default();
+            if unbond.withdraw <= epoch {
+                summary.withdrawable += amount;
+            } else {
+                summary.unbonding += amount;
+            }
+        }
+        summary.rewards += rewards;
+        summary.slashes.extend(detail.slashes);
+    }
+    Ok(summaries)
+}
+
 /// Get the correct representation of the amount given the token type.
 pub async fn validate_amount<N: Namada>(
     context: &N,
@@ -1,5 +1,5 @@
 //! Functions to sign transactions
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 
 use borsh::BorshDeserialize;
@@ -28,7 +28,7 @@ use namada_core::types::transaction::governance::{
     InitProposalData, VoteProposalData,
 };
 use namada_core::types::transaction::pos::BecomeValidator;
-use namada_core::types::transaction::{pos, Fee};
+use namada_core::types::transaction::{pos, Fee, GasLimit};
 use prost::Message;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
@@ -82,6 +82,62 @@ pub struct SigningTxData {
     pub fee_payer: common::PublicKey,
 }
 
+/// A fully-formed but unsigned transaction, along with the data needed to
+/// sign it, in a portable format that can be carried to an offline host
+/// holding the signing keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineTx {
+    /// The transaction, including its wrapper header
+    pub tx: Tx,
+    /// The address owning the transaction
+    pub owner: Option<Address>,
+    /// The public keys associated to an account
+    pub public_keys: Vec<common::PublicKey>,
+    /// The threshold associated to an account
+    pub threshold: u8,
+    /// The public keys to index map associated to an account
+    pub account_public_keys_map: Option<AccountPublicKeysMap>,
+    /// The public keys of the fee payer
+    pub fee_payer: common::PublicKey,
+}
+
+impl OfflineTx {
+    /// Bundle the given transaction with its signing data
+    pub fn new(tx: Tx, signing_data: SigningTxData) -> Self {
+        Self {
+            tx,
+            owner: signing_data.owner,
+            public_keys: signing_data.public_keys,
+            threshold: signing_data.threshold,
+            account_public_keys_map: signing_data.account_public_keys_map,
+            fee_payer: signing_data.fee_payer,
+        }
+    }
+
+    /// The data needed to sign the transaction
+    pub fn signing_data(&self) -> SigningTxData {
+        SigningTxData {
+            owner: self.owner.clone(),
+            public_keys: self.public_keys.clone(),
+            threshold: self.threshold,
+            account_public_keys_map: self.account_public_keys_map.clone(),
+            fee_payer: self.fee_payer.clone(),
+        }
+    }
+
+    /// Serialize to a JSON string
+    pub fn serialize(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("Serializing an offline tx shouldn't fail")
+    }
+
+    /// Deserialize from a JSON string
+    pub fn deserialize(data: &[u8]) -> Result<Self, Error> {
+        serde_json::from_slice(data)
+            .map_err(|err| Error::from(EncodingError::Serde(err.to_string())))
+    }
+}
+
 /// Find the public key for the given address and try to load the keypair
 /// for it from the wallet. If the keypair is encrypted but a password is not
 /// supplied, then it is interactively prompted. Errors if the key cannot be
@@ -309,6 +365,66 @@ where
     Ok(())
 }
 
+/// Sign the raw header of the given offline transaction with the given secret
+/// keys, without access to a wallet or to the ledger. The returned signatures
+/// can be attached to the transaction with [`attach_signatures`].
+pub fn sign_offline(
+    offline_tx: &OfflineTx,
+    secret_keys: &[common::SecretKey],
+) -> Result<Vec<SignatureIndex>, Error> {
+    let Some(account_public_keys_map) = &offline_tx.account_public_keys_map
+    else {
+        // The raw header of this transaction need not be signed
+        return Ok(vec![]);
+    };
+    for secret_key in secret_keys {
+        let public_key = secret_key.ref_to();
+        if !offline_tx.public_keys.contains(&public_key) {
+            return Err(Error::from(TxError::UnexpectedSigner(public_key)));
+        }
+    }
+    Ok(offline_tx.tx.compute_section_signature(
+        secret_keys,
+        account_public_keys_map,
+        offline_tx.owner.clone(),
+    ))
+}
+
+/// Merge the signatures of the raw header of a transaction that were
+/// produced externally, e.g. with [`sign_offline`], into the transaction.
+pub fn attach_signatures(
+    tx: &mut Tx,
+    signing_data: &SigningTxData,
+    signatures: Vec<SignatureIndex>,
+) -> Result<(), Error> {
+    if let Some(signature) = signatures
+        .iter()
+        .find(|signature| !signing_data.public_keys.contains(&signature.pubkey))
+    {
+        return Err(Error::from(TxError::UnexpectedSigner(
+            signature.pubkey.clone(),
+        )));
+    }
+    tx.add_signatures(signatures);
+    Ok(())
+}
+
+/// Sign the wrapper header of a transaction with the secret key of its fee
+/// payer. This must be done after all the signatures of the raw header have
+/// been attached, as the wrapper signature commits to them.
+pub fn sign_wrapper_offline(
+    tx: &mut Tx,
+    signing_data: &SigningTxData,
+    fee_payer_key: common::SecretKey,
+) -> Result<(), Error> {
+    let public_key = fee_payer_key.ref_to();
+    if public_key != signing_data.fee_payer {
+        return Err(Error::from(TxError::UnexpectedFeePayer(public_key)));
+    }
+    tx.sign_wrapper(fee_payer_key);
+    Ok(())
+}
+
 /// Return the necessary data regarding an account to be able to generate a
 /// multisignature section
 pub async fn aux_signing_data(
@@ -436,6 +552,51 @@ pub struct TxSourcePostBalance {
     pub token: Address,
 }
 
+/// Display the other tokens accepted for fee payment in which the fee payer
+/// can afford the minimum fee, so that they can pick one of them instead
+async fn display_fee_alternatives<N: Namada>(
+    context: &N,
+    fee_payer: &Address,
+    fee_token: &Address,
+    gas_limit: GasLimit,
+) {
+    let options =
+        match rpc::query_fee_options(context.client(), fee_payer, gas_limit)
+            .await
+        {
+            Ok(options) => options,
+            Err(_) => return,
+        };
+    let affordable: Vec<_> = options
+        .into_iter()
+        .filter(|option| &option.token != fee_token && option.is_affordable())
+        .collect();
+    if affordable.is_empty() {
+        return;
+    }
+    display_line!(
+        context.io(),
+        "The fee payer {} can instead pay the fees in one of the following \
+         tokens (with --gas-token):",
+        fee_payer
+    );
+    for option in affordable {
+        let minimum_fee = context
+            .format_amount(&option.token, option.minimum_fee)
+            .await;
+        let balance =
+            context.format_amount(&option.token, option.balance).await;
+        display_line!(
+            context.io(),
+            "{:4}{}: minimum fee {}, balance {}",
+            "",
+            option.token,
+            minimum_fee,
+            balance
+        );
+    }
+}
+
 /// Create a wrapper tx from a normal tx. Get the hash of the
 /// wrapper and its payload which is needed for monitoring its
 /// progress on chain.
@@ -450,22 +611,19 @@ pub async fn wrap_tx<N: Namada>(
 ) -> Result<Option<Epoch>, Error> {
     let fee_payer_address = Address::from(&fee_payer);
     // Validate fee amount and token
-    let gas_cost_key = parameter_storage::get_gas_cost_key();
-    let minimum_fee = match rpc::query_storage_value::<
-        _,
-        BTreeMap<Address, Amount>,
-    >(context.client(), &gas_cost_key)
-    .await
-    .and_then(|map| {
-        map.get(&args.fee_token)
-            .map(ToOwned::to_owned)
-            .ok_or_else(|| {
-                Error::Other(format!(
-                    "Could not retrieve from storage the gas cost for token {}",
-                    args.fee_token
-                ))
-            })
-    }) {
+    let minimum_fee = match rpc::query_fee_gas_costs(context.client())
+        .await
+        .and_then(|map| {
+            map.get(&args.fee_token)
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| {
+                    Error::Other(format!(
+                        "Could not retrieve from storage the gas cost for \
+                         token {}",
+                        args.fee_token
+                    ))
+                })
+        }) {
         Ok(amount) => amount,
         Err(e) => {
             if !args.force {
@@ -627,6 +785,13 @@ pub async fn wrap_tx<N: Namada>(
             } else {
                 let token_addr = args.fee_token.clone();
                 if !args.force {
+                    display_fee_alternatives(
+                        context,
+                        &fee_payer_address,
+                        &token_addr,
+                        args.gas_limit,
+                    )
+                    .await;
                     let fee_amount =
                         context.format_amount(&token_addr, total_fee).await;
 
@@ -0,0 +1,590 @@
+//! A high-level client for the staking txs of the proof-of-stake system.
+//!
+//! The [`StakingClient`] builds the arguments of the bond, unbond, withdraw,
+//! redelegate and claim rewards txs, then constructs, signs and submits them
+//! through the [`crate::tx`] functions. Each tx can be customized before
+//! submission with the builder methods of [`StakingTx`]. The delegations of
+//! an owner can be spread across validators by target weights with
+//! [`rebalance`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use namada_core::types::address::{Address, ImplicitAddress};
+use namada_core::types::dec::Dec;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use namada_proof_of_stake::types::RedelegationEligibility;
+
+use crate::args::{self, SdkTypes, TxBuilder};
+use crate::error::{Error, Result, TxError};
+use crate::signing::{default_sign, SigningTxData};
+use crate::tx::{self, ProcessTxResponse};
+use crate::{display_line, rpc, Namada};
+
+/// A client submitting staking txs on behalf of the wallet of a [`Namada`]
+/// context
+pub struct StakingClient<'a, N: Namada> {
+    namada: &'a N,
+}
+
+impl<'a, N: Namada> StakingClient<'a, N> {
+    /// Create a staking client using the given context
+    pub fn new(namada: &'a N) -> Self {
+        Self { namada }
+    }
+
+    /// Bond `amount` native tokens to `validator`. The source of the bond
+    /// defaults to the validator itself, i.e. a self-bond.
+    pub fn bond(
+        &self,
+        validator: Address,
+        amount: token::Amount,
+    ) -> StakingTx<'a, N, args::Bond> {
+        StakingTx::new(self.namada, self.namada.new_bond(validator, amount))
+    }
+
+    /// Unbond `amount` native tokens from `validator`. The source of the
+    /// bond defaults to the validator itself, i.e. a self-bond.
+    pub fn unbond(
+        &self,
+        validator: Address,
+        amount: token::Amount,
+    ) -> StakingTx<'a, N, args::Unbond> {
+        StakingTx::new(self.namada, self.namada.new_unbond(validator, amount))
+    }
+
+    /// Withdraw the tokens unbonded from `validator` that have become
+    /// withdrawable
+    pub fn withdraw(
+        &self,
+        validator: Address,
+    ) -> StakingTx<'a, N, args::Withdraw> {
+        StakingTx::new(self.namada, self.namada.new_withdraw(validator))
+    }
+
+    /// Redelegate `amount` tokens bonded by `owner` from `src_validator` to
+    /// `dest_validator`
+    pub fn redelegate(
+        &self,
+        owner: Address,
+        src_validator: Address,
+        dest_validator: Address,
+        amount: token::Amount,
+    ) -> StakingTx<'a, N, args::Redelegate> {
+        StakingTx::new(
+            self.namada,
+            self.namada.new_redelegation(
+                owner,
+                src_validator,
+                dest_validator,
+                amount,
+            ),
+        )
+    }
+
+    /// Claim the rewards of the bonds to `validator`
+    pub fn claim_rewards(
+        &self,
+        validator: Address,
+    ) -> StakingTx<'a, N, args::ClaimRewards> {
+        StakingTx::new(self.namada, self.namada.new_claim_rewards(validator))
+    }
+}
+
+/// A staking tx ready to be customized and submitted
+pub struct StakingTx<'a, N: Namada, A> {
+    namada: &'a N,
+    args: A,
+}
+
+impl<'a, N: Namada, A> StakingTx<'a, N, A> {
+    fn new(namada: &'a N, args: A) -> Self {
+        Self { namada, args }
+    }
+
+    /// Apply the given function to the arguments of the tx, e.g. to set
+    /// the source of a bond
+    pub fn with<F>(self, func: F) -> Self
+    where
+        F: FnOnce(A) -> A,
+    {
+        Self {
+            args: func(self.args),
+            ..self
+        }
+    }
+
+    /// The arguments the tx will be built from
+    pub fn args(&self) -> &A {
+        &self.args
+    }
+}
+
+impl<'a, N: Namada, A> TxBuilder<SdkTypes> for StakingTx<'a, N, A>
+where
+    A: TxBuilder<SdkTypes>,
+{
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(args::Tx<SdkTypes>) -> args::Tx<SdkTypes>,
+    {
+        self.with(|args| args.tx(func))
+    }
+}
+
+/// The result of submitting a bond tx
+#[derive(Debug)]
+pub struct BondResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+}
+
+/// The result of submitting an unbond tx
+#[derive(Debug)]
+pub struct UnbondResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+    /// The epoch from which the unbonded tokens can be withdrawn, along
+    /// with their amount after the application of slashes. Only known if
+    /// the tx was applied.
+    pub withdrawal: Option<(Epoch, token::Amount)>,
+}
+
+/// The result of submitting a withdraw tx
+#[derive(Debug)]
+pub struct WithdrawResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+    /// The amount of tokens that were withdrawable when the tx was built
+    pub amount: token::Amount,
+}
+
+/// The result of submitting a redelegation tx
+#[derive(Debug)]
+pub struct RedelegateResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+}
+
+/// The result of submitting a claim rewards tx
+#[derive(Debug)]
+pub struct ClaimRewardsResult {
+    /// The response of the ledger to the tx
+    pub response: ProcessTxResponse,
+    /// The amount of reward tokens that were claimable when the tx was built
+    pub amount: token::Amount,
+}
+
+impl<'a, N: Namada> StakingTx<'a, N, args::Bond> {
+    /// Build, sign and submit the bond tx. The public key of an implicit
+    /// source is revealed first, if needed.
+    pub async fn submit(self) -> Result<BondResult> {
+        let source = self.args.source.as_ref().unwrap_or(&self.args.validator);
+        reveal_pk_if_needed(self.namada, &self.args.tx, source).await?;
+
+        let (tx, signing_data, _epoch) = self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+        Ok(BondResult { response })
+    }
+}
+
+impl<'a, N: Namada> StakingTx<'a, N, args::Unbond> {
+    /// Build, sign and submit the unbond tx
+    pub async fn submit(self) -> Result<UnbondResult> {
+        let (tx, signing_data, _epoch, latest_withdrawal_pre) =
+            self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+
+        let withdrawal = if is_applied(&response) {
+            let source =
+                self.args.source.as_ref().unwrap_or(&self.args.validator);
+            let unbonds = rpc::query_unbond_with_slashing(
+                self.namada.client(),
+                source,
+                &self.args.validator,
+            )
+            .await?;
+            let mut withdrawable = BTreeMap::<Epoch, token::Amount>::new();
+            for ((_start_epoch, withdraw_epoch), amount) in unbonds {
+                *withdrawable.entry(withdraw_epoch).or_default() += amount;
+            }
+            withdrawable.into_iter().last().map(|(epoch, amount_post)| {
+                match latest_withdrawal_pre {
+                    // unbonds withdrawable at the same epoch are merged
+                    Some((epoch_pre, amount_pre)) if epoch_pre == epoch => {
+                        (epoch, amount_post - amount_pre)
+                    }
+                    _ => (epoch, amount_post),
+                }
+            })
+        } else {
+            None
+        };
+        Ok(UnbondResult {
+            response,
+            withdrawal,
+        })
+    }
+}
+
+impl<'a, N: Namada> StakingTx<'a, N, args::Withdraw> {
+    /// Build, sign and submit the withdraw tx
+    pub async fn submit(self) -> Result<WithdrawResult> {
+        let source = self.args.source.as_ref().unwrap_or(&self.args.validator);
+        let amount = rpc::query_withdrawable_tokens(
+            self.namada.client(),
+            source,
+            &self.args.validator,
+            None,
+        )
+        .await?;
+
+        let (tx, signing_data, _epoch) = self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+        Ok(WithdrawResult { response, amount })
+    }
+}
+
+impl<'a, N: Namada> StakingTx<'a, N, args::Redelegate> {
+    /// Build, sign and submit the redelegation tx
+    pub async fn submit(self) -> Result<RedelegateResult> {
+        let (tx, signing_data) = self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+        Ok(RedelegateResult { response })
+    }
+}
+
+impl<'a, N: Namada> StakingTx<'a, N, args::ClaimRewards> {
+    /// Build, sign and submit the claim rewards tx
+    pub async fn submit(self) -> Result<ClaimRewardsResult> {
+        let amount = rpc::query_rewards(
+            self.namada.client(),
+            &self.args.source,
+            &self.args.validator,
+        )
+        .await?;
+
+        let (tx, signing_data, _epoch) = self.args.build(self.namada).await?;
+        let response =
+            sign_and_submit(self.namada, &self.args.tx, tx, signing_data)
+                .await?;
+        Ok(ClaimRewardsResult { response, amount })
+    }
+}
+
+/// A redelegation of a delegation rebalancing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebalanceMove {
+    /// The validator to redelegate from
+    pub src_validator: Address,
+    /// The validator to redelegate to
+    pub dest_validator: Address,
+    /// The amount of tokens to redelegate
+    pub amount: token::Amount,
+}
+
+/// The redelegations that bring the delegations of an owner to the target
+/// weights
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RebalancePlan {
+    /// The redelegations, from the largest to the smallest
+    pub moves: Vec<RebalanceMove>,
+    /// The validators holding more than their target whose tokens can't be
+    /// redelegated yet, because the owner's tokens were redelegated to them
+    /// and may still be slashed. The redelegations from these validators
+    /// would be chained and are allowed from the given epochs.
+    pub deferred: BTreeMap<Address, Epoch>,
+}
+
+/// The result of a delegation rebalancing
+#[derive(Debug)]
+pub struct RebalanceResult {
+    /// The planned redelegations
+    pub plan: RebalancePlan,
+    /// The responses of the ledger to the submitted redelegation txs, in the
+    /// order of the moves of the plan. The submission stops at the first tx
+    /// that fails. Empty in dry-run mode.
+    pub responses: Vec<ProcessTxResponse>,
+}
+
+/// Rebalance the delegations of `owner` to match the `target_weights` of the
+/// validators, which are relative to each other. The delegations to the
+/// validators missing from the targets are moved entirely. The plan moves
+/// each token at most once and uses the fewest redelegations it can by
+/// matching the largest surpluses with the largest deficits. When the
+/// `dry_run` tx argument of the context is set, the plan is returned without
+/// submitting any tx.
+pub async fn rebalance<N: Namada>(
+    namada: &N,
+    owner: &Address,
+    target_weights: &BTreeMap<Address, Dec>,
+) -> Result<RebalanceResult> {
+    let client = namada.client();
+    for validator in target_weights.keys() {
+        if !rpc::is_validator(client, validator).await? {
+            return Err(Error::from(TxError::InvalidRebalanceWeights(
+                format!("{validator} is not a validator"),
+            )));
+        }
+    }
+    // The redelegations take effect at the pipeline epoch, so they have to
+    // account for the bonds and unbonds that are still pending
+    let params = rpc::get_pos_params(client).await?;
+    let current_epoch = rpc::query_epoch(client).await?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let delegations =
+        rpc::get_delegators_delegation_at(client, owner, pipeline_epoch)
+            .await?;
+    let mut chained = BTreeMap::new();
+    for validator in delegations.keys() {
+        if let RedelegationEligibility::ChainedUntil(epoch) =
+            rpc::query_redelegation_eligibility(client, validator, owner)
+                .await?
+        {
+            chained.insert(validator.clone(), epoch);
+        }
+    }
+    let delegations = delegations.into_iter().collect();
+    let plan = plan_rebalance(&delegations, target_weights, &chained)?;
+
+    let mut responses = vec![];
+    if !namada.tx_builder().dry_run {
+        let staking = StakingClient::new(namada);
+        for RebalanceMove {
+            src_validator,
+            dest_validator,
+            amount,
+        } in plan.moves.iter().cloned()
+        {
+            let response = staking
+                .redelegate(
+                    owner.clone(),
+                    src_validator,
+                    dest_validator,
+                    amount,
+                )
+                .submit()
+                .await?
+                .response;
+            let is_failed = matches!(response, ProcessTxResponse::Applied(_))
+                && !is_applied(&response);
+            responses.push(response);
+            if is_failed {
+                break;
+            }
+        }
+    }
+    Ok(RebalanceResult { plan, responses })
+}
+
+/// Plan the redelegations that bring the `delegations` of an owner to the
+/// `target_weights` of the validators. The validators in `chained` can't be
+/// redelegated from until the given epochs.
+pub fn plan_rebalance(
+    delegations: &BTreeMap<Address, token::Amount>,
+    target_weights: &BTreeMap<Address, Dec>,
+    chained: &BTreeMap<Address, Epoch>,
+) -> Result<RebalancePlan> {
+    let invalid_weights = |reason: &str| {
+        Error::from(TxError::InvalidRebalanceWeights(reason.into()))
+    };
+    if target_weights.values().any(Dec::is_negative) {
+        return Err(invalid_weights("the weights must not be negative"));
+    }
+    let total_weight = target_weights
+        .values()
+        .try_fold(Dec::zero(), |acc, weight| acc.checked_add(weight))
+        .ok_or_else(|| invalid_weights("the sum of the weights overflows"))?;
+    let Some((heaviest, _)) = target_weights
+        .iter()
+        .filter(|(_, weight)| !weight.is_zero())
+        .max_by_key(|(_, weight)| **weight)
+    else {
+        return Err(invalid_weights("at least one weight must be positive"));
+    };
+
+    // The rounding remainder of the targets goes to the heaviest validator
+    let total: token::Amount = delegations.values().copied().sum();
+    let mut targets = BTreeMap::new();
+    for (validator, weight) in target_weights {
+        let share = weight
+            .trunc_div(&total_weight)
+            .ok_or_else(|| invalid_weights("the weights can't be divided"))?;
+        targets.insert(validator, share * total);
+    }
+    let assigned: token::Amount = targets.values().copied().sum();
+    *targets.entry(heaviest).or_default() += total - assigned;
+
+    let mut surpluses = vec![];
+    let mut deficits = vec![];
+    let mut deferred = BTreeMap::new();
+    let validators: BTreeSet<&Address> =
+        delegations.keys().chain(target_weights.keys()).collect();
+    for validator in validators {
+        let current = delegations.get(validator).copied().unwrap_or_default();
+        let target = targets.get(validator).copied().unwrap_or_default();
+        if current > target {
+            match chained.get(validator) {
+                Some(epoch) => {
+                    deferred.insert(validator.clone(), *epoch);
+                }
+                None => surpluses.push((validator, current - target)),
+            }
+        } else if target > current {
+            deficits.push((validator, target - current));
+        }
+    }
+    surpluses.sort_by(|(_, a), (_, b)| b.cmp(a));
+    deficits.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    let mut moves = vec![];
+    let (mut src_ix, mut dest_ix) = (0, 0);
+    while src_ix < surpluses.len() && dest_ix < deficits.len() {
+        let (src, surplus) = &mut surpluses[src_ix];
+        let (dest, deficit) = &mut deficits[dest_ix];
+        let amount = std::cmp::min(*surplus, *deficit);
+        moves.push(RebalanceMove {
+            src_validator: (*src).clone(),
+            dest_validator: (*dest).clone(),
+            amount,
+        });
+        *surplus -= amount;
+        *deficit -= amount;
+        if surplus.is_zero() {
+            src_ix += 1;
+        }
+        if deficit.is_zero() {
+            dest_ix += 1;
+        }
+    }
+    Ok(RebalancePlan { moves, deferred })
+}
+
+/// Sign the given tx with the keys in the wallet and submit it, unless it
+/// is to be dumped
+pub(crate) async fn sign_and_submit(
+    namada: &impl Namada,
+    args: &args::Tx,
+    mut tx: crate::proto::Tx,
+    signing_data: SigningTxData,
+) -> Result<ProcessTxResponse> {
+    if args.dump_tx {
+        tx::dump_tx(namada.io(), args, tx);
+        return Ok(ProcessTxResponse::Dump);
+    }
+    namada
+        .sign(&mut tx, args, signing_data, default_sign, ())
+        .await?;
+    namada.submit(tx, args).await
+}
+
+/// Submit a tx revealing the public key of the given address, if it is an
+/// implicit address whose public key is not yet revealed
+async fn reveal_pk_if_needed(
+    namada: &impl Namada,
+    args: &args::Tx,
+    address: &Address,
+) -> Result<()> {
+    let Address::Implicit(ImplicitAddress(pkh)) = address else {
+        return Ok(());
+    };
+    if args.dump_tx
+        || !tx::is_reveal_pk_needed(namada.client(), address, args.force)
+            .await?
+    {
+        return Ok(());
+    }
+    let public_key = namada
+        .wallet()
+        .await
+        .find_public_key_by_pkh(pkh)
+        .map_err(|err| crate::error::Error::Other(err.to_string()))?;
+    display_line!(
+        namada.io(),
+        "Submitting a tx to reveal the public key for address {address}..."
+    );
+    let (tx, signing_data, _epoch) =
+        tx::build_reveal_pk(namada, args, &public_key).await?;
+    sign_and_submit(namada, args, tx, signing_data).await?;
+    Ok(())
+}
+
+/// Check if the given response is that of a tx that was applied
+/// successfully
+pub(crate) fn is_applied(response: &ProcessTxResponse) -> bool {
+    matches!(response, ProcessTxResponse::Applied(resp) if resp.code == 0.to_string())
+}
+
+#[cfg(test)]
+mod test_staking {
+    use assert_matches::assert_matches;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+
+    use super::*;
+
+    /// Test that the rebalancing plan moves the surpluses to the deficits,
+    /// largest first, and defers the chained redelegations
+    #[test]
+    fn test_plan_rebalance() {
+        let (val1, val2, val3) = (
+            established_address_1(),
+            established_address_2(),
+            established_address_3(),
+        );
+        let delegations = BTreeMap::from([
+            (val1.clone(), token::Amount::from(100)),
+            (val3.clone(), token::Amount::from(50)),
+        ]);
+        let weights = BTreeMap::from([
+            (val1.clone(), Dec::one()),
+            (val2.clone(), Dec::one()),
+        ]);
+
+        let plan =
+            plan_rebalance(&delegations, &weights, &BTreeMap::new()).unwrap();
+        assert_eq!(
+            plan.moves,
+            vec![
+                RebalanceMove {
+                    src_validator: val3.clone(),
+                    dest_validator: val2.clone(),
+                    amount: token::Amount::from(50),
+                },
+                RebalanceMove {
+                    src_validator: val1.clone(),
+                    dest_validator: val2.clone(),
+                    amount: token::Amount::from(25),
+                },
+            ]
+        );
+        assert!(plan.deferred.is_empty());
+
+        let chained = BTreeMap::from([(val3.clone(), Epoch(5))]);
+        let plan = plan_rebalance(&delegations, &weights, &chained).unwrap();
+        assert_eq!(
+            plan.moves,
+            vec![RebalanceMove {
+                src_validator: val1,
+                dest_validator: val2,
+                amount: token::Amount::from(25),
+            }]
+        );
+        assert_eq!(plan.deferred, chained);
+
+        let zero_weights = BTreeMap::from([(val3, Dec::zero())]);
+        assert_matches!(
+            plan_rebalance(&delegations, &zero_weights, &BTreeMap::new()),
+            Err(Error::Tx(TxError::InvalidRebalanceWeights(_)))
+        );
+    }
+}
@@ -2,6 +2,7 @@
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -30,15 +31,17 @@ use namada_core::ledger::governance::cli::onchain::{
 use namada_core::ledger::governance::storage::proposal::ProposalType;
 use namada_core::ledger::governance::storage::vote::StorageProposalVote;
 use namada_core::ledger::ibc::storage::channel_key;
+use namada_core::ledger::parameters::storage as parameter_storage;
 use namada_core::ledger::pgf::cli::steward::Commission;
+use namada_core::ledger::vesting::storage as vesting_storage;
 use namada_core::types::address::{Address, InternalAddress, MASP};
 use namada_core::types::dec::Dec;
 use namada_core::types::hash::Hash;
 use namada_core::types::ibc::IbcShieldedTransfer;
 use namada_core::types::key::*;
 use namada_core::types::masp::{TransferSource, TransferTarget};
-use namada_core::types::storage::Epoch;
-use namada_core::types::time::DateTimeUtc;
+use namada_core::types::storage::{BlockHeight, Epoch};
+use namada_core::types::time::{DateTimeUtc, DurationSecs};
 use namada_core::types::token::MaspDenom;
 use namada_core::types::transaction::account::{InitAccount, UpdateAccount};
 use namada_core::types::transaction::governance::{
@@ -48,7 +51,9 @@ use namada_core::types::transaction::pgf::UpdateStewardCommission;
 use namada_core::types::transaction::pos;
 use namada_core::types::{storage, token};
 use namada_proof_of_stake::parameters::PosParams;
-use namada_proof_of_stake::types::{CommissionPair, ValidatorState};
+use namada_proof_of_stake::types::{
+    CommissionPair, RedelegationEligibility, ValidatorState,
+};
 
 use crate::args::{self, InputAmount};
 use crate::control_flow::time;
@@ -56,12 +61,12 @@ use crate::error::{EncodingError, Error, QueryError, Result, TxError};
 use crate::io::Io;
 use crate::masp::TransferErr::Build;
 use crate::masp::{make_asset_type, ShieldedContext, ShieldedTransfer};
-use crate::proto::{MaspBuilder, Tx};
+use crate::proto::{MaspBuilder, Memo, Tx, MAX_MEMO_LEN};
 use crate::queries::Client;
 use crate::rpc::{
     self, query_wasm_code_hash, validate_amount, TxBroadcastData, TxResponse,
 };
-use crate::signing::{self, SigningTxData, TxSourcePostBalance};
+use crate::signing::{self, OfflineTx, SigningTxData, TxSourcePostBalance};
 use crate::tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 use crate::tendermint_rpc::error::Error as RpcError;
 use crate::wallet::WalletIo;
@@ -106,6 +111,10 @@ pub const TX_CHANGE_COMMISSION_WASM: &str =
     "tx_change_validator_commission.wasm";
 /// Change consensus key WASM path
 pub const TX_CHANGE_CONSENSUS_KEY_WASM: &str = "tx_change_consensus_key.wasm";
+/// Change Ethereum bridge keys WASM path
+pub const TX_CHANGE_ETH_KEYS_WASM: &str = "tx_change_eth_keys.wasm";
+/// Change validator operator key WASM path
+pub const TX_CHANGE_OPERATOR_KEY_WASM: &str = "tx_change_operator_key.wasm";
 /// Change validator metadata WASM path
 pub const TX_CHANGE_METADATA_WASM: &str = "tx_change_validator_metadata.wasm";
 /// Resign steward WASM path
@@ -115,6 +124,16 @@ pub const TX_UPDATE_STEWARD_COMMISSION: &str =
     "tx_update_steward_commission.wasm";
 /// Redelegate transaction WASM path
 pub const TX_REDELEGATE_WASM: &str = "tx_redelegate.wasm";
+/// Initialize vesting account WASM path
+pub const TX_INIT_VESTING_WASM: &str = "tx_init_vesting.wasm";
+/// Claim vested tokens WASM path
+pub const TX_CLAIM_VESTED_WASM: &str = "tx_claim_vested.wasm";
+/// Deposit into the staking pool WASM path
+pub const TX_POOL_DEPOSIT_WASM: &str = "tx_pool_deposit.wasm";
+/// Redeem staking pool shares WASM path
+pub const TX_POOL_REDEEM_WASM: &str = "tx_pool_redeem.wasm";
+/// Withdraw from the staking pool WASM path
+pub const TX_POOL_WITHDRAW_WASM: &str = "tx_pool_withdraw.wasm";
 
 /// Default timeout in seconds for requests to the `/accepted`
 /// and `/applied` ABCI query endpoints.
@@ -167,8 +186,39 @@ pub fn dump_tx<IO: Io>(io: &IO, args: &args::Tx, tx: Tx) {
     }
 }
 
-/// Prepare a transaction for signing and submission by adding a wrapper header
-/// to it.
+/// Dump a transaction, along with the data needed to sign it, either to file
+/// or to screen, so that it can be signed on an offline host. The transaction
+/// must have been built with its wrapper header, i.e. not in dry-run mode.
+pub fn dump_offline_tx<IO: Io>(
+    io: &IO,
+    args: &args::Tx,
+    tx: Tx,
+    signing_data: SigningTxData,
+) {
+    let tx_id = tx.header_hash();
+    let offline_tx = OfflineTx::new(tx, signing_data);
+    match args.output_folder.to_owned() {
+        Some(path) => {
+            let tx_filename = format!("{}.offline.json", tx_id);
+            let tx_path = path.join(tx_filename);
+            let out = File::create(&tx_path).unwrap();
+            serde_json::to_writer_pretty(out, &offline_tx)
+                .expect("Should be able to write to file.");
+            display_line!(
+                io,
+                "Offline transaction serialized to {}.",
+                tx_path.to_string_lossy()
+            );
+        }
+        None => {
+            display_line!(io, "Below the offline transaction: \n");
+            display_line!(io, "{}", offline_tx.serialize())
+        }
+    }
+}
+
+/// Prepare a transaction for signing and submission by attaching the memo, if
+/// any, and adding a wrapper header to it.
 #[allow(clippy::too_many_arguments)]
 pub async fn prepare_tx(
     context: &impl Namada,
@@ -177,8 +227,22 @@ pub async fn prepare_tx(
     fee_payer: common::PublicKey,
     tx_source_balance: Option<TxSourcePostBalance>,
 ) -> Result<Option<Epoch>> {
+    if let Some(memo) = &args.tx_memo {
+        let memo = Memo::new(memo.as_bytes()).ok_or_else(|| {
+            Error::from(TxError::MemoTooLong(memo.len(), MAX_MEMO_LEN))
+        })?;
+        tx.add_memo(memo);
+    }
     if !args.dry_run {
         let epoch = rpc::query_epoch(context.client()).await?;
+        if let Some(height) = args.expiration_height {
+            let expiration =
+                query_expiration_at_height(context, height).await?;
+            tx.header.expiration = Some(match tx.header.expiration {
+                Some(other) => other.min(expiration),
+                None => expiration,
+            });
+        }
 
         signing::wrap_tx(context, tx, args, tx_source_balance, epoch, fee_payer)
             .await
@@ -187,6 +251,33 @@ pub async fn prepare_tx(
     }
 }
 
+/// Estimate the time by which the chain reaches the given block height at the
+/// latest, from the time of the last committed block and the maximum expected
+/// time per block. If blocks are produced faster than that, a tx expiring at
+/// this time can still be included after the given height.
+pub async fn query_expiration_at_height(
+    context: &impl Namada,
+    height: BlockHeight,
+) -> Result<DateTimeUtc> {
+    let last_block = rpc::query_block(context.client()).await?;
+    let (last_height, last_time) = last_block
+        .map(|block| (block.height, block.time))
+        .unwrap_or_else(|| (BlockHeight::default(), DateTimeUtc::now()));
+    if height <= last_height {
+        return Err(Error::from(TxError::ExpirationHeightReached(
+            height,
+            last_height,
+        )));
+    }
+    let max_time_per_block: DurationSecs = rpc::query_storage_value(
+        context.client(),
+        &parameter_storage::get_max_expected_time_per_block_key(),
+    )
+    .await?;
+    let blocks = height.0 - last_height.0;
+    Ok(last_time + DurationSecs(max_time_per_block.0.saturating_mul(blocks)))
+}
+
 /// Submit transaction and wait for result. Returns a list of addresses
 /// initialized in the transaction if any. In dry run, this is always empty.
 pub async fn process_tx(
@@ -424,6 +515,226 @@ pub async fn submit_tx(
     parsed
 }
 
+/// The policy of [`submit_until_final`] for the transactions that expire
+/// before making it on chain
+#[derive(Debug, Clone, Default)]
+pub struct ResubmitPolicy {
+    /// The maximum number of times an expired transaction is rebuilt and
+    /// submitted again. With zero, an expired transaction is not retried.
+    pub max_resubmissions: u32,
+    /// The fraction by which the fee per gas unit is raised at each
+    /// re-submission, e.g. 0.1 for 10%. The fee is left unchanged if `None`.
+    pub fee_increase: Option<Dec>,
+}
+
+/// The final status of a transaction submitted with [`submit_until_final`]
+#[derive(Debug)]
+pub enum TxFinalStatus {
+    /// The transaction was applied successfully
+    Applied {
+        /// The result of the application
+        response: TxResponse,
+        /// The number of submissions, including the re-submissions
+        submissions: u32,
+    },
+    /// The transaction made it on chain, but it was rejected
+    Rejected {
+        /// The result of the wrapper or of the inner transaction
+        response: TxResponse,
+        /// The number of submissions, including the re-submissions
+        submissions: u32,
+    },
+    /// The last submission of the transaction expired before making it on
+    /// chain
+    Expired {
+        /// The expiration of the last submission
+        expiration: DateTimeUtc,
+        /// The number of submissions, including the re-submissions
+        submissions: u32,
+    },
+}
+
+/// The outcome of a single submission of a transaction
+enum SubmissionOutcome {
+    /// The transaction was applied or rejected
+    Final(TxResponse),
+    /// The transaction can't make it on chain anymore
+    Expired,
+}
+
+/// Submit a signed transaction and follow it until it's applied, rejected or
+/// expired. Per the `policy`, an expired transaction is rebuilt with `rebuild`
+/// and submitted again. `rebuild` is given the transaction arguments with a
+/// fresh expiration, as far in the future as that of the expired transaction
+/// was from its timestamp, and with the raised fee, if any. It must return the
+/// transaction built and signed again, which gives it a fresh timestamp.
+///
+/// A transaction is only considered expired once the chain has committed a
+/// block past its expiration, after which it can't be included anymore. This
+/// way, the expired transaction and its re-submission can't both be applied.
+pub async fn submit_until_final<N, F, Fut>(
+    context: &N,
+    args: &args::Tx,
+    mut tx: Tx,
+    policy: &ResubmitPolicy,
+    mut rebuild: F,
+) -> Result<TxFinalStatus>
+where
+    N: Namada,
+    F: FnMut(args::Tx) -> Fut,
+    Fut: std::future::Future<Output = Result<Tx>>,
+{
+    if args.dry_run || args.dry_run_wrapper {
+        return Err(Error::from(TxError::ExpectLiveRun(tx)));
+    }
+    let mut args = args.clone();
+    let mut submissions = 0;
+    loop {
+        let header = tx.header();
+        let expiration = header
+            .expiration
+            .ok_or_else(|| Error::from(TxError::MissingExpiration))?;
+        submissions += 1;
+        let response = match submit_and_monitor(context, tx, expiration).await?
+        {
+            SubmissionOutcome::Final(response) => response,
+            SubmissionOutcome::Expired
+                if submissions > policy.max_resubmissions =>
+            {
+                return Ok(TxFinalStatus::Expired {
+                    expiration,
+                    submissions,
+                });
+            }
+            SubmissionOutcome::Expired => {
+                display_line!(
+                    context.io(),
+                    "Transaction expired at {}, submitting it again.",
+                    expiration
+                );
+                let validity =
+                    expiration.0.signed_duration_since(header.timestamp.0);
+                args.expiration = Some(DateTimeUtc::now() + validity);
+                args.expiration_height = None;
+                let fee_increase = policy
+                    .fee_increase
+                    .filter(|increase| !increase.is_negative());
+                if let (Some(increase), Some(wrapper)) =
+                    (fee_increase, header.wrapper())
+                {
+                    let fee = wrapper
+                        .fee
+                        .amount_per_gas_unit
+                        .mul_ceil(Dec::one() + increase);
+                    let fee = rpc::denominate_amount(
+                        context.client(),
+                        context.io(),
+                        &wrapper.fee.token,
+                        fee,
+                    )
+                    .await;
+                    args.fee_amount = Some(InputAmount::Validated(fee));
+                }
+                tx = rebuild(args.clone()).await?;
+                continue;
+            }
+        };
+        save_initialized_accounts(
+            context,
+            &args,
+            response.initialized_accounts.clone(),
+        )
+        .await;
+        return Ok(if response.code == 0.to_string() {
+            TxFinalStatus::Applied {
+                response,
+                submissions,
+            }
+        } else {
+            TxFinalStatus::Rejected {
+                response,
+                submissions,
+            }
+        });
+    }
+}
+
+/// Broadcast a transaction and wait until it's applied or rejected, or until
+/// the chain commits a block past its expiration without including it
+async fn submit_and_monitor(
+    context: &impl Namada,
+    tx: Tx,
+    expiration: DateTimeUtc,
+) -> Result<SubmissionOutcome> {
+    if is_expired(context, expiration).await? {
+        return Ok(SubmissionOutcome::Expired);
+    }
+    let wrapper_hash = tx.header_hash().to_string();
+    let decrypted_hash = tx.raw_header_hash().to_string();
+    let to_broadcast = TxBroadcastData::Live {
+        tx,
+        wrapper_hash: wrapper_hash.clone(),
+        decrypted_hash: decrypted_hash.clone(),
+    };
+    broadcast_tx(context, &to_broadcast).await?;
+
+    // Stop waiting if the chain doesn't commit a block past the expiration
+    let validity = expiration
+        .0
+        .signed_duration_since(DateTimeUtc::now().0)
+        .to_std()
+        .unwrap_or_default();
+    let deadline = time::Instant::now()
+        + validity
+        + time::Duration::from_secs(
+            DEFAULT_NAMADA_EVENTS_MAX_WAIT_TIME_SECONDS,
+        );
+    let accepted = time::Sleep {
+        strategy: time::LinearBackoff {
+            delta: time::Duration::from_secs(1),
+        },
+    }
+    .timeout(deadline, || async {
+        // Check the expiration before the events, so that a tx included in
+        // a block committed in between isn't taken for an expired one
+        let Ok(expired) = is_expired(context, expiration).await else {
+            return ControlFlow::Continue(());
+        };
+        let query = rpc::TxEventQuery::Accepted(wrapper_hash.as_str());
+        match rpc::query_tx_events(context.client(), query).await {
+            Ok(Some(event)) => ControlFlow::Break(Some(event)),
+            Ok(None) if expired => ControlFlow::Break(None),
+            _ => ControlFlow::Continue(()),
+        }
+    })
+    .await
+    .map_err(|_| Error::from(TxError::AcceptTimeout))?;
+    let Some(event) = accepted else {
+        return Ok(SubmissionOutcome::Expired);
+    };
+    let response = TxResponse::from_event(event);
+    if response.code != 0.to_string() {
+        return Ok(SubmissionOutcome::Final(response));
+    }
+
+    let deadline = time::Instant::now()
+        + time::Duration::from_secs(
+            DEFAULT_NAMADA_EVENTS_MAX_WAIT_TIME_SECONDS,
+        );
+    let query = rpc::TxEventQuery::Applied(decrypted_hash.as_str());
+    let event = rpc::query_tx_status(context, query, deadline).await?;
+    Ok(SubmissionOutcome::Final(TxResponse::from_event(event)))
+}
+
+/// Check if the chain has committed a block past the given expiration
+async fn is_expired(
+    context: &impl Namada,
+    expiration: DateTimeUtc,
+) -> Result<bool> {
+    let last_block = rpc::query_block(context.client()).await?;
+    Ok(last_block.map_or(false, |block| block.time > expiration))
+}
+
 /// decode components of a masp note
 pub fn decode_component<K, F>(
     (addr, denom, epoch): (Address, MaspDenom, Epoch),
@@ -1147,29 +1458,21 @@ pub async fn build_redelegation(
     }
 
     // Prohibit chained redelegations
-    let params = rpc::get_pos_params(context.client()).await?;
-    let incoming_redel_epoch = rpc::query_incoming_redelegations(
+    let eligibility = rpc::query_redelegation_eligibility(
         context.client(),
         &src_validator,
         &owner,
     )
     .await?;
-    let current_epoch = rpc::query_epoch(context.client()).await?;
-    let is_not_chained = if let Some(redel_end_epoch) = incoming_redel_epoch {
-        let last_contrib_epoch = redel_end_epoch.prev();
-        last_contrib_epoch + params.slash_processing_epoch_offset()
-            <= current_epoch
-    } else {
-        true
-    };
-    if !is_not_chained {
+    if let RedelegationEligibility::ChainedUntil(eligible_epoch) = eligibility {
         edisplay_line!(
             context.io(),
             "The source validator {} has an incoming redelegation from the \
              delegator {} that may still be subject to future slashing. \
-             Redelegation is not allowed until this is no longer the case.",
+             Redelegation is not allowed until epoch {}.",
             &src_validator,
-            &owner
+            &owner,
+            eligible_epoch
         );
         if !tx_args.force {
             return Err(Error::from(TxError::IncomingRedelIsStillSlashable(
@@ -1181,6 +1484,8 @@ pub async fn build_redelegation(
 
     // Give a redelegation warning based on the pipeline state of the dest
     // validator
+    let params = rpc::get_pos_params(context.client()).await?;
+    let current_epoch = rpc::query_epoch(context.client()).await?;
     let pipeline_epoch = current_epoch + params.pipeline_len;
     let dest_validator_state_at_pipeline = rpc::get_validator_state(
         context.client(),
@@ -1404,6 +1709,7 @@ pub async fn build_unbond(
         validator,
         amount,
         source,
+        auto_withdraw,
         tx_code_path,
     }: &args::Unbond,
 ) -> Result<(
@@ -1488,6 +1794,7 @@ pub async fn build_unbond(
         validator: validator.clone(),
         amount: *amount,
         source: source.clone(),
+        auto_withdraw: *auto_withdraw,
     };
 
     let (tx, epoch) = build(
@@ -1649,9 +1956,17 @@ pub async fn build_bond(
     .await?;
 
     // Check bond's source (source for delegation or validator for self-bonds)
-    // balance
+    // balance. Vesting accounts bond the tokens held by the vesting address.
     let bond_source = source.as_ref().unwrap_or(&validator);
-    let balance_key = token::balance_key(native_token, bond_source);
+    let schedule_key =
+        vesting_storage::schedules_handle().get_data_key(bond_source);
+    let is_vesting_account =
+        rpc::query_has_storage_key(context.client(), &schedule_key).await?;
+    let balance_key = if is_vesting_account {
+        vesting_storage::locked_handle().get_data_key(bond_source)
+    } else {
+        token::balance_key(native_token, bond_source)
+    };
 
     // TODO Should we state the same error message for the native token?
     let post_balance = check_balance_too_low_err(
@@ -1663,11 +1978,13 @@ pub async fn build_bond(
         context,
     )
     .await?;
-    let tx_source_balance = Some(TxSourcePostBalance {
-        post_balance,
-        source: bond_source.clone(),
-        token: native_token.clone(),
-    });
+    // The source's own balance is unaffected by the bonds of vesting accounts
+    let tx_source_balance =
+        (!is_vesting_account).then(|| TxSourcePostBalance {
+            post_balance,
+            source: bond_source.clone(),
+            token: native_token.clone(),
+        });
 
     let data = pos::Bond {
         validator,
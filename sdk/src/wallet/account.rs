@@ -0,0 +1,74 @@
+//! Typed views of the accounts in the wallet. A [`SigningAccount`] has its
+//! secret key in the wallet and can sign txs. A [`WatchOnlyAccount`] only has
+//! an address, a public key or a viewing key. It can be used to query
+//! balances and bonds and to build unsigned txs, but never to sign.
+
+use namada_core::types::address::Address;
+use namada_core::types::key::common;
+use namada_core::types::masp::ExtendedViewingKey;
+
+use super::alias::Alias;
+
+/// An account without a secret key in the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOnlyAccount {
+    /// The alias of the account
+    pub alias: Alias,
+    /// The address of the account, if known
+    pub address: Option<Address>,
+    /// The public key of the account, if known
+    pub public_key: Option<common::PublicKey>,
+    /// The viewing key of the account, if known
+    pub viewing_key: Option<ExtendedViewingKey>,
+}
+
+/// An account whose secret key is in the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningAccount {
+    /// The alias of the account
+    pub alias: Alias,
+    /// The address of the account
+    pub address: Address,
+    /// The public key of the account's secret key
+    pub public_key: common::PublicKey,
+}
+
+/// An account in the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletAccount {
+    /// An account that can't be used for signing
+    WatchOnly(WatchOnlyAccount),
+    /// An account that can be used for signing
+    Signing(SigningAccount),
+}
+
+impl WalletAccount {
+    /// The alias of the account
+    pub fn alias(&self) -> &Alias {
+        match self {
+            WalletAccount::WatchOnly(account) => &account.alias,
+            WalletAccount::Signing(account) => &account.alias,
+        }
+    }
+
+    /// The address of the account, if known
+    pub fn address(&self) -> Option<&Address> {
+        match self {
+            WalletAccount::WatchOnly(account) => account.address.as_ref(),
+            WalletAccount::Signing(account) => Some(&account.address),
+        }
+    }
+
+    /// The public key of the account, if known
+    pub fn public_key(&self) -> Option<&common::PublicKey> {
+        match self {
+            WalletAccount::WatchOnly(account) => account.public_key.as_ref(),
+            WalletAccount::Signing(account) => Some(&account.public_key),
+        }
+    }
+
+    /// Check if the account can be used for signing
+    pub fn is_signing(&self) -> bool {
+        matches!(self, WalletAccount::Signing(_))
+    }
+}
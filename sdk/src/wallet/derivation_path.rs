@@ -23,6 +23,40 @@ pub enum DerivationPathError {
 #[derive(Clone, Debug)]
 pub struct DerivationPath(DerivationPathInner);
 
+/// The keys of a validator that can be derived from a HD seed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidatorKeyKind {
+    /// The consensus key
+    Consensus,
+    /// The key signing protocol txs
+    Protocol,
+    /// The hot key signing Ethereum bridge txs
+    EthHot,
+    /// The cold key of the Ethereum bridge
+    EthCold,
+}
+
+impl ValidatorKeyKind {
+    /// The signature scheme of the key
+    pub fn scheme(&self) -> SchemeType {
+        match self {
+            Self::Consensus | Self::Protocol => SchemeType::Ed25519,
+            Self::EthHot | Self::EthCold => SchemeType::Secp256k1,
+        }
+    }
+
+    /// The BIP44 change index the key is derived at. Indexes 0 and 1 are
+    /// left to the external and internal chains of transparent keys.
+    fn change_index(&self) -> u32 {
+        match self {
+            Self::Consensus => 2,
+            Self::Protocol => 3,
+            Self::EthHot => 4,
+            Self::EthCold => 5,
+        }
+    }
+}
+
 impl DerivationPath {
     fn new<P>(path: P) -> Self
     where
@@ -85,6 +119,14 @@ impl DerivationPath {
         path.hardened(scheme)
     }
 
+    /// The path of the given validator key of the given account, following
+    /// BIP44 with a change index specific to each kind of key
+    pub fn validator(kind: ValidatorKeyKind, account: u32) -> Self {
+        let scheme = kind.scheme();
+        let path = Self::bip44(scheme, account, kind.change_index(), 0);
+        path.hardened(scheme)
+    }
+
     pub fn from_path_str(
         scheme: SchemeType,
         path: &str,
@@ -139,7 +181,7 @@ impl IntoHDeriveDerivationPath for DerivationPath {
 mod tests {
     use namada_core::types::key::SchemeType;
 
-    use super::DerivationPath;
+    use super::{DerivationPath, ValidatorKeyKind};
 
     #[test]
     fn path_is_compatible() {
@@ -180,4 +222,16 @@ mod tests {
         assert!(!path_nam.is_compatible(SchemeType::Secp256k1));
         assert!(path_nam.is_compatible(SchemeType::Common));
     }
+
+    #[test]
+    fn validator_paths() {
+        let consensus =
+            DerivationPath::validator(ValidatorKeyKind::Consensus, 1);
+        assert_eq!(consensus.to_string(), "m/44'/877'/1'/2'/0'");
+        assert!(consensus.is_compatible(SchemeType::Ed25519));
+
+        let eth_hot = DerivationPath::validator(ValidatorKeyKind::EthHot, 1);
+        assert_eq!(eth_hot.to_string(), "m/44'/60'/1'/4/0");
+        assert!(eth_hot.is_compatible(SchemeType::Secp256k1));
+    }
 }
@@ -4,10 +4,14 @@ use std::fmt::Display;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
 use orion::{aead, kdf};
+use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zeroize::Zeroizing;
@@ -16,6 +20,17 @@ use crate::wallet::WalletIo;
 
 const ENCRYPTED_KEY_PREFIX: &str = "encrypted:";
 const UNENCRYPTED_KEY_PREFIX: &str = "unencrypted:";
+/// Prefix of the keypairs encrypted with [`KeyEncryption::Argon2idAesGcm`]
+const ARGON2ID_AES_GCM_PREFIX: &str = "argon2id:";
+
+/// Length of the salt of the Argon2id key derivation
+const ARGON2ID_SALT_LEN: usize = 16;
+/// Length of the AES-GCM nonce
+const AES_GCM_NONCE_LEN: usize = 12;
+/// Memory cost of the Argon2id key derivation, in KiB
+const ARGON2ID_MEMORY_COST: u32 = 1 << 17;
+/// Number of iterations of the Argon2id key derivation
+const ARGON2ID_ITERATIONS: u32 = 3;
 
 /// A keypair stored in a wallet
 #[derive(Debug)]
@@ -112,16 +127,40 @@ pub enum DeserializeStoredKeypairError {
     MissingPrefix,
 }
 
+/// The scheme used to encrypt a keypair stored in a wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEncryption {
+    /// Argon2i key derivation and XChaCha20-Poly1305 encryption, used by
+    /// older wallets. Keypairs encrypted with it can still be decrypted, but
+    /// new keypairs are never encrypted with it.
+    Legacy,
+    /// Argon2id key derivation and AES-256-GCM encryption
+    Argon2idAesGcm,
+}
+
 /// An encrypted keypair stored in a wallet
 #[derive(Debug)]
-pub struct EncryptedKeypair<T: BorshSerialize + BorshDeserialize>(
-    Vec<u8>,
-    PhantomData<T>,
-);
+pub struct EncryptedKeypair<T: BorshSerialize + BorshDeserialize> {
+    /// The encryption scheme
+    encryption: KeyEncryption,
+    /// The salt of the key derivation, followed by the encrypted keypair
+    data: Vec<u8>,
+    phantom: PhantomData<T>,
+}
 
 impl<T: BorshSerialize + BorshDeserialize> Display for EncryptedKeypair<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", HEXLOWER.encode(self.0.as_ref()))
+        match self.encryption {
+            KeyEncryption::Legacy => {
+                write!(f, "{}", HEXLOWER.encode(self.data.as_ref()))
+            }
+            KeyEncryption::Argon2idAesGcm => write!(
+                f,
+                "{}{}",
+                ARGON2ID_AES_GCM_PREFIX,
+                HEXLOWER.encode(self.data.as_ref())
+            ),
+        }
     }
 }
 
@@ -129,7 +168,15 @@ impl<T: BorshSerialize + BorshDeserialize> FromStr for EncryptedKeypair<T> {
     type Err = data_encoding::DecodeError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        HEXLOWER.decode(s.as_ref()).map(|x| Self(x, PhantomData))
+        let (encryption, hex) = match s.strip_prefix(ARGON2ID_AES_GCM_PREFIX) {
+            Some(hex) => (KeyEncryption::Argon2idAesGcm, hex),
+            None => (KeyEncryption::Legacy, s),
+        };
+        HEXLOWER.decode(hex.as_ref()).map(|data| Self {
+            encryption,
+            data,
+            phantom: PhantomData,
+        })
     }
 }
 
@@ -194,22 +241,62 @@ where
             StoredKeypair::Raw(_) => false,
         }
     }
+
+    /// Indicates whether this key is stored raw or encrypted with the legacy
+    /// scheme, and should hence be migrated
+    pub fn needs_migration(&self) -> bool {
+        match self {
+            StoredKeypair::Encrypted(encrypted) => {
+                encrypted.encryption() == KeyEncryption::Legacy
+            }
+            StoredKeypair::Raw(_) => true,
+        }
+    }
+
+    /// Encrypt this key with the current encryption scheme and the given
+    /// password. A key encrypted with the legacy scheme must have been
+    /// encrypted with the same password.
+    pub fn migrate(
+        &self,
+        password: Zeroizing<String>,
+    ) -> Result<Self, DecryptionError> {
+        let keypair = match self {
+            StoredKeypair::Encrypted(encrypted) => {
+                encrypted.decrypt(password.clone())?
+            }
+            StoredKeypair::Raw(keypair) => keypair.clone(),
+        };
+        Ok(Self::Encrypted(EncryptedKeypair::new(&keypair, password)))
+    }
 }
 
 impl<T: BorshSerialize + BorshDeserialize> EncryptedKeypair<T> {
-    /// Encrypt a keypair and store it with its salt.
+    /// Encrypt a keypair with Argon2id and AES-256-GCM and store it with its
+    /// salt and nonce.
     pub fn new(keypair: &T, password: Zeroizing<String>) -> Self {
-        let salt = encryption_salt();
-        let encryption_key = encryption_key(&salt, &password);
+        let mut salt = [0; ARGON2ID_SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0; AES_GCM_NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let encryption_key = argon2id_encryption_key(&salt, &password);
 
-        let data = keypair.serialize_to_vec();
+        let data = Zeroizing::new(keypair.serialize_to_vec());
 
-        let encrypted_keypair = aead::seal(&encryption_key, &data)
-            .expect("Encryption of data shouldn't fail");
+        let encrypted_keypair =
+            Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&encryption_key[..]))
+                .encrypt(Nonce::from_slice(&nonce), data.as_slice())
+                .expect("Encryption of data shouldn't fail");
 
-        let encrypted_data = [salt.as_ref(), &encrypted_keypair].concat();
+        Self {
+            encryption: KeyEncryption::Argon2idAesGcm,
+            data: [&salt[..], &nonce[..], &encrypted_keypair[..]].concat(),
+            phantom: PhantomData,
+        }
+    }
 
-        Self(encrypted_data, PhantomData)
+    /// The scheme this keypair was encrypted with
+    pub fn encryption(&self) -> KeyEncryption {
+        self.encryption
     }
 
     /// Decrypt an encrypted keypair
@@ -217,30 +304,152 @@ impl<T: BorshSerialize + BorshDeserialize> EncryptedKeypair<T> {
         &self,
         password: Zeroizing<String>,
     ) -> Result<T, DecryptionError> {
-        let salt_len = encryption_salt().len();
-        let (raw_salt, cipher) = self.0.split_at(salt_len);
+        let decrypted_data = match self.encryption {
+            KeyEncryption::Legacy => {
+                let salt_len = legacy_encryption_salt().len();
+                if self.data.len() < salt_len {
+                    return Err(DecryptionError::BadSalt);
+                }
+                let (raw_salt, cipher) = self.data.split_at(salt_len);
 
-        let salt = kdf::Salt::from_slice(raw_salt)
-            .map_err(|_| DecryptionError::BadSalt)?;
+                let salt = kdf::Salt::from_slice(raw_salt)
+                    .map_err(|_| DecryptionError::BadSalt)?;
 
-        let encryption_key = encryption_key(&salt, &password);
+                let encryption_key = legacy_encryption_key(&salt, &password);
 
-        let decrypted_data = aead::open(&encryption_key, cipher)
-            .map_err(|_| DecryptionError::DecryptionError)?;
+                Zeroizing::new(
+                    aead::open(&encryption_key, cipher)
+                        .map_err(|_| DecryptionError::DecryptionError)?,
+                )
+            }
+            KeyEncryption::Argon2idAesGcm => {
+                if self.data.len() < ARGON2ID_SALT_LEN + AES_GCM_NONCE_LEN {
+                    return Err(DecryptionError::BadSalt);
+                }
+                let (salt, rest) = self.data.split_at(ARGON2ID_SALT_LEN);
+                let (nonce, cipher) = rest.split_at(AES_GCM_NONCE_LEN);
+
+                let encryption_key = argon2id_encryption_key(salt, &password);
+
+                Zeroizing::new(
+                    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(
+                        &encryption_key[..],
+                    ))
+                    .decrypt(Nonce::from_slice(nonce), cipher)
+                    .map_err(|_| DecryptionError::DecryptionError)?,
+                )
+            }
+        };
 
         T::try_from_slice(&decrypted_data)
             .map_err(|_| DecryptionError::DeserializingError)
     }
 }
 
-/// Keypair encryption salt
-fn encryption_salt() -> kdf::Salt {
+/// Make an AES-256 encryption key from a password with Argon2id.
+fn argon2id_encryption_key(salt: &[u8], password: &str) -> Zeroizing<[u8; 32]> {
+    let params =
+        Params::new(ARGON2ID_MEMORY_COST, ARGON2ID_ITERATIONS, 1, Some(32))
+            .expect("The Argon2id parameters should be valid");
+    let mut key = Zeroizing::new([0; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key[..])
+        .expect("Generation of encryption secret key shouldn't fail");
+    key
+}
+
+/// Keypair encryption salt of the legacy scheme
+fn legacy_encryption_salt() -> kdf::Salt {
     kdf::Salt::default()
 }
 
-/// Make encryption secret key from a password.
-fn encryption_key(salt: &kdf::Salt, password: &str) -> kdf::SecretKey {
+/// Make encryption secret key from a password with the legacy scheme.
+fn legacy_encryption_key(salt: &kdf::Salt, password: &str) -> kdf::SecretKey {
     kdf::Password::from_slice(password.as_bytes())
         .and_then(|password| kdf::derive_key(&password, salt, 3, 1 << 17, 32))
         .expect("Generation of encryption secret key shouldn't fail")
 }
+
+#[cfg(test)]
+mod test_keys {
+    use namada_core::types::key::testing::keypair_1;
+    use namada_core::types::key::{common, RefTo};
+
+    use super::*;
+
+    /// Test that keypairs encrypted with the current scheme can be decrypted
+    /// after a round-trip through their string encoding
+    #[test]
+    fn test_encrypted_keypair_roundtrip() {
+        let keypair = keypair_1();
+        let password = Zeroizing::new("password".to_string());
+        let (stored, _) =
+            StoredKeypair::new(keypair.clone(), Some(password.clone()));
+        assert!(!stored.needs_migration());
+
+        let encoded = serde_json::to_string(&stored).unwrap();
+        let decoded: StoredKeypair<common::SecretKey> =
+            serde_json::from_str(&encoded).unwrap();
+        let StoredKeypair::Encrypted(encrypted) = decoded else {
+            panic!("The keypair should be encrypted");
+        };
+        assert_eq!(encrypted.encryption(), KeyEncryption::Argon2idAesGcm);
+        assert_eq!(
+            encrypted.decrypt(password).unwrap().ref_to(),
+            keypair.ref_to()
+        );
+        assert!(matches!(
+            encrypted.decrypt(Zeroizing::new("wrong".to_string())),
+            Err(DecryptionError::DecryptionError)
+        ));
+    }
+
+    /// Test that raw keypairs and keypairs encrypted with the legacy scheme
+    /// are migrated to the current scheme
+    #[test]
+    fn test_keypair_migration() {
+        let keypair = keypair_1();
+        let password = Zeroizing::new("password".to_string());
+
+        let salt = legacy_encryption_salt();
+        let cipher = aead::seal(
+            &legacy_encryption_key(&salt, &password),
+            &keypair.serialize_to_vec(),
+        )
+        .unwrap();
+        let legacy = StoredKeypair::Encrypted(
+            EncryptedKeypair::<common::SecretKey>::from_str(
+                &HEXLOWER.encode(&[salt.as_ref(), &cipher].concat()),
+            )
+            .unwrap(),
+        );
+        let raw = StoredKeypair::Raw(keypair.clone());
+
+        for stored in [legacy, raw] {
+            assert!(stored.needs_migration());
+            assert_eq!(
+                stored
+                    .get::<TestIo>(true, Some(password.clone()))
+                    .unwrap()
+                    .ref_to(),
+                keypair.ref_to()
+            );
+            let migrated = stored.migrate(password.clone()).unwrap();
+            assert!(!migrated.needs_migration());
+            assert_eq!(
+                migrated
+                    .get::<TestIo>(true, Some(password.clone()))
+                    .unwrap()
+                    .ref_to(),
+                keypair.ref_to()
+            );
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestIo;
+
+    impl WalletIo for TestIo {
+        type Rng = rand_core::OsRng;
+    }
+}
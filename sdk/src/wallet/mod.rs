@@ -1,4 +1,5 @@
 //! Provides functionality for managing keys and addresses for a user
+pub mod account;
 pub mod alias;
 mod derivation_path;
 mod keys;
@@ -24,8 +25,11 @@ pub use store::{AddressVpType, Store};
 use thiserror::Error;
 use zeroize::Zeroizing;
 
-pub use self::derivation_path::{DerivationPath, DerivationPathError};
-pub use self::keys::{DecryptionError, StoredKeypair};
+pub use self::account::{SigningAccount, WalletAccount, WatchOnlyAccount};
+pub use self::derivation_path::{
+    DerivationPath, DerivationPathError, ValidatorKeyKind,
+};
+pub use self::keys::{DecryptionError, KeyEncryption, StoredKeypair};
 pub use self::store::{ConfirmationResponse, ValidatorData, ValidatorKeys};
 use crate::wallet::store::derive_hd_secret_key;
 
@@ -226,6 +230,221 @@ pub mod fs {
     }
 }
 
+/// Implementation of wallet functionality keeping the wallet store in memory,
+/// for clients that have no filesystem or persist the encoded store by their
+/// own means
+pub mod memory {
+    use std::sync::{Arc, Mutex};
+
+    use rand_core::OsRng;
+
+    use super::*;
+
+    /// For a non-interactive wallet whose encoded store is kept in memory.
+    /// Clones share the same memory.
+    #[derive(Debug, Clone, Default)]
+    pub struct MemoryWalletUtils {
+        data: Arc<Mutex<Option<Vec<u8>>>>,
+    }
+
+    impl MemoryWalletUtils {
+        /// Initialize an empty wallet
+        pub fn new() -> Wallet<Self> {
+            Wallet::new(Self::default(), Store::default())
+        }
+
+        /// Initialize a wallet whose storage holds the given encoded store,
+        /// to be loaded with [`Wallet::load`]
+        pub fn from_encoded(data: Vec<u8>) -> Wallet<Self> {
+            let utils = Self {
+                data: Arc::new(Mutex::new(Some(data))),
+            };
+            Wallet::new(utils, Store::default())
+        }
+
+        /// The encoded store, if the wallet was saved
+        pub fn encoded(&self) -> Option<Vec<u8>> {
+            self.data.lock().unwrap().clone()
+        }
+    }
+
+    impl WalletStorage for MemoryWalletUtils {
+        fn save<U>(&self, wallet: &Wallet<U>) -> Result<(), LoadStoreError> {
+            *self.data.lock().unwrap() = Some(wallet.store.encode());
+            Ok(())
+        }
+
+        fn load<U>(
+            &self,
+            wallet: &mut Wallet<U>,
+        ) -> Result<(), LoadStoreError> {
+            let data = self.encoded().ok_or_else(|| {
+                LoadStoreError::ReadWallet(
+                    "memory".to_string(),
+                    "no wallet was saved".to_string(),
+                )
+            })?;
+            wallet.store =
+                Store::decode(data).map_err(LoadStoreError::Decode)?;
+            Ok(())
+        }
+    }
+
+    impl WalletIo for MemoryWalletUtils {
+        type Rng = OsRng;
+    }
+
+    #[cfg(test)]
+    mod test_memory_wallet {
+        use namada_core::types::address::testing::established_address_1;
+        use namada_core::types::address::ImplicitAddress;
+        use namada_core::types::key::testing::keypair_1;
+
+        use super::*;
+
+        /// Test that a wallet saved in memory can be loaded back, including
+        /// from its encoding
+        #[test]
+        fn test_memory_wallet_roundtrip() {
+            let mut wallet = MemoryWalletUtils::new();
+            assert!(wallet.load().is_err());
+
+            let address = established_address_1();
+            wallet.insert_address("alias", address.clone(), false);
+            wallet.save().unwrap();
+
+            let mut loaded = MemoryWalletUtils::from_encoded(
+                wallet.utils.encoded().unwrap(),
+            );
+            loaded.load().unwrap();
+            assert_eq!(loaded.find_address("alias").as_deref(), Some(&address));
+        }
+
+        /// Test that a watch-only account survives a save and load and can't
+        /// be used for signing
+        #[test]
+        fn test_watch_only_account() {
+            let mut wallet = MemoryWalletUtils::new();
+            let pk = keypair_1().ref_to();
+            let alias = wallet
+                .insert_watch_only(
+                    "watched".to_string(),
+                    None,
+                    Some(pk.clone()),
+                    None,
+                    false,
+                )
+                .unwrap();
+            wallet.save().unwrap();
+
+            let mut loaded = MemoryWalletUtils::from_encoded(
+                wallet.utils.encoded().unwrap(),
+            );
+            loaded.load().unwrap();
+            let account = loaded.find_account(&alias).unwrap();
+            assert!(!account.is_signing());
+            assert_eq!(account.public_key(), Some(&pk));
+            assert_eq!(
+                account.address(),
+                Some(&Address::Implicit(ImplicitAddress((&pk).into())))
+            );
+            assert_eq!(loaded.get_watch_only_accounts().len(), 1);
+            assert!(matches!(
+                loaded.find_signing_account(&alias),
+                Err(FindKeyError::WatchOnly(_))
+            ));
+            assert!(matches!(
+                loaded.find_secret_key(&alias, None),
+                Err(FindKeyError::WatchOnly(_))
+            ));
+            assert!(matches!(
+                loaded.find_key_by_pk(&pk, None),
+                Err(FindKeyError::WatchOnly(_))
+            ));
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+/// Implementation of wallet functionality keeping the wallet store in the
+/// local storage of a browser
+pub mod local_storage {
+    use rand_core::OsRng;
+
+    use super::*;
+
+    /// For a non-interactive wallet whose encoded store is kept in the local
+    /// storage of the browser, under a given key
+    #[derive(Debug, Clone)]
+    pub struct LocalStorageWalletUtils {
+        key: String,
+    }
+
+    impl LocalStorageWalletUtils {
+        /// Initialize a wallet stored under the given local storage key
+        pub fn new(key: impl Into<String>) -> Wallet<Self> {
+            Wallet::new(Self { key: key.into() }, Store::default())
+        }
+
+        /// The local storage of the browser window
+        fn local_storage(
+            &self,
+        ) -> Result<web_sys::Storage, Option<wasm_bindgen::JsValue>> {
+            web_sys::window()
+                .ok_or(None)?
+                .local_storage()
+                .map_err(Some)?
+                .ok_or(None)
+        }
+    }
+
+    /// Describe an error raised by the local storage API
+    fn describe(err: Option<wasm_bindgen::JsValue>) -> String {
+        match err {
+            Some(err) => format!("{err:?}"),
+            None => "local storage is not available".to_string(),
+        }
+    }
+
+    impl WalletStorage for LocalStorageWalletUtils {
+        fn save<U>(&self, wallet: &Wallet<U>) -> Result<(), LoadStoreError> {
+            // The store is encoded as TOML, hence valid UTF-8
+            let data = String::from_utf8(wallet.store.encode())
+                .expect("The encoded wallet store should be valid UTF-8");
+            self.local_storage()
+                .and_then(|storage| {
+                    storage.set_item(&self.key, &data).map_err(Some)
+                })
+                .map_err(|err| LoadStoreError::StoreNewWallet(describe(err)))
+        }
+
+        fn load<U>(
+            &self,
+            wallet: &mut Wallet<U>,
+        ) -> Result<(), LoadStoreError> {
+            let data = self
+                .local_storage()
+                .and_then(|storage| storage.get_item(&self.key).map_err(Some))
+                .map_err(|err| {
+                    LoadStoreError::ReadWallet(self.key.clone(), describe(err))
+                })?
+                .ok_or_else(|| {
+                    LoadStoreError::ReadWallet(
+                        self.key.clone(),
+                        "no wallet was saved".to_string(),
+                    )
+                })?;
+            wallet.store = Store::decode(data.into_bytes())
+                .map_err(LoadStoreError::Decode)?;
+            Ok(())
+        }
+    }
+
+    impl WalletIo for LocalStorageWalletUtils {
+        type Rng = OsRng;
+    }
+}
+
 /// Generate a new secret key.
 pub fn gen_secret_key(
     scheme: SchemeType,
@@ -259,6 +478,11 @@ pub enum FindKeyError {
     /// Could not decrypt a given key in the wallet
     #[error("{0}")]
     KeyDecryptionError(keys::DecryptionError),
+    /// The key belongs to a watch-only account
+    #[error(
+        "The account {0} is watch-only, its secret key is not in the wallet"
+    )]
+    WatchOnly(String),
 }
 
 /// Represents a collection of keys and addresses while caching key decryptions
@@ -477,6 +701,75 @@ impl<U> Wallet<U> {
             .map(|(alias, value)| (alias.into(), value))
             .collect()
     }
+
+    /// Decrypt all the secret and spending keys encrypted with the given
+    /// password and store them in the decrypted key caches, so that they can
+    /// be used without prompting for a password. Keys encrypted with another
+    /// password are left locked. Returns the number of unlocked keys.
+    pub fn unlock(
+        &mut self,
+        password: Zeroizing<String>,
+    ) -> Result<usize, FindKeyError> {
+        let mut unlocked = 0;
+        for (alias, (stored_key, _pkh)) in self.store.get_secret_keys() {
+            if let Some(key) =
+                Self::try_unlock_key(stored_key, password.clone())?
+            {
+                self.decrypted_key_cache.insert(alias, key);
+                unlocked += 1;
+            }
+        }
+        for (alias, stored_key) in self.store.get_spending_keys() {
+            if let Some(key) =
+                Self::try_unlock_key(stored_key, password.clone())?
+            {
+                self.decrypted_spendkey_cache.insert(alias.clone(), key);
+                unlocked += 1;
+            }
+        }
+        Ok(unlocked)
+    }
+
+    /// Decrypt the given stored key if it is encrypted with the given
+    /// password
+    fn try_unlock_key<
+        T: FromStr + Display + BorshSerialize + BorshDeserialize + Clone,
+    >(
+        stored_key: &StoredKeypair<T>,
+        password: Zeroizing<String>,
+    ) -> Result<Option<T>, FindKeyError>
+    where
+        <T as std::str::FromStr>::Err: Display,
+    {
+        match stored_key {
+            StoredKeypair::Encrypted(encrypted) => {
+                match encrypted.decrypt(password) {
+                    Ok(key) => Ok(Some(key)),
+                    Err(DecryptionError::DecryptionError) => Ok(None),
+                    Err(err) => Err(FindKeyError::KeyDecryptionError(err)),
+                }
+            }
+            StoredKeypair::Raw(_) => Ok(None),
+        }
+    }
+
+    /// Forget all the decrypted keys, so that a password is needed again to
+    /// use the encrypted keys
+    pub fn lock(&mut self) {
+        self.decrypted_key_cache.clear();
+        self.decrypted_spendkey_cache.clear();
+    }
+
+    /// Encrypt all the keys stored raw or with the legacy encryption scheme
+    /// with the current scheme and the given password. The wallet must be
+    /// saved for the migration to persist. Returns the number of migrated
+    /// keys.
+    pub fn migrate_keys(
+        &mut self,
+        password: Zeroizing<String>,
+    ) -> Result<usize, DecryptionError> {
+        self.store.migrate_keys(password)
+    }
 }
 
 impl<U: WalletStorage> Wallet<U> {
@@ -689,12 +982,10 @@ impl<U: WalletIo> Wallet<U> {
             return Ok(cached_key.clone());
         }
         // If not cached, look-up in store
-        let stored_key = self
-            .store
-            .find_secret_key(alias_pkh_or_pk.as_ref())
-            .ok_or_else(|| {
-            FindKeyError::KeyNotFound(alias_pkh_or_pk.as_ref().to_string())
-        })?;
+        let stored_key =
+            self.store
+                .find_secret_key(alias_pkh_or_pk.as_ref())
+                .ok_or_else(|| self.key_not_found(alias_pkh_or_pk.as_ref()))?;
         Self::decrypt_stored_key::<_>(
             &mut self.decrypted_key_cache,
             stored_key,
@@ -805,7 +1096,7 @@ impl<U: WalletIo> Wallet<U> {
         let stored_key = self
             .store
             .find_key_by_pkh(pkh)
-            .ok_or_else(|| FindKeyError::KeyNotFound(pkh.to_string()))?;
+            .ok_or_else(|| self.key_not_found(pkh.to_string()))?;
         Self::decrypt_stored_key(
             &mut self.decrypted_key_cache,
             stored_key,
@@ -814,6 +1105,79 @@ impl<U: WalletIo> Wallet<U> {
         )
     }
 
+    /// Find the account with the given alias
+    pub fn find_account(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Result<WalletAccount, FindKeyError> {
+        self.store.find_account(alias.as_ref()).ok_or_else(|| {
+            FindKeyError::KeyNotFound(alias.as_ref().to_string())
+        })
+    }
+
+    /// Find the account with the given alias, if it can be used for signing
+    pub fn find_signing_account(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Result<SigningAccount, FindKeyError> {
+        match self.find_account(alias.as_ref())? {
+            WalletAccount::Signing(account) => Ok(account),
+            WalletAccount::WatchOnly(_) => {
+                Err(FindKeyError::WatchOnly(alias.as_ref().to_string()))
+            }
+        }
+    }
+
+    /// Get the secret key of a signing account. If the key is encrypted and
+    /// password not supplied, then password will be interactively prompted.
+    pub fn find_signing_key(
+        &mut self,
+        account: &SigningAccount,
+        password: Option<Zeroizing<String>>,
+    ) -> Result<common::SecretKey, FindKeyError> {
+        self.find_secret_key(&account.alias, password)
+    }
+
+    /// Get all the accounts with an address, a public key or a viewing key
+    pub fn get_accounts(&self) -> Vec<WalletAccount> {
+        self.store.get_accounts()
+    }
+
+    /// Get all the accounts that can't be used for signing
+    pub fn get_watch_only_accounts(&self) -> Vec<WatchOnlyAccount> {
+        self.get_accounts()
+            .into_iter()
+            .filter_map(|account| match account {
+                WalletAccount::WatchOnly(account) => Some(account),
+                WalletAccount::Signing(_) => None,
+            })
+            .collect()
+    }
+
+    /// The error for a secret key that's not in the wallet, distinguishing
+    /// the keys of watch-only accounts
+    fn key_not_found(&self, alias_pkh_or_pk: impl AsRef<str>) -> FindKeyError {
+        let alias_pkh_or_pk = alias_pkh_or_pk.as_ref();
+        let alias = self
+            .store
+            .find_account(alias_pkh_or_pk)
+            .map(|account| account.alias().clone())
+            .or_else(|| {
+                let pkh = PublicKeyHash::from_str(alias_pkh_or_pk)
+                    .ok()
+                    .or_else(|| {
+                        common::PublicKey::from_str(alias_pkh_or_pk)
+                            .ok()
+                            .map(|pk| PublicKeyHash::from(&pk))
+                    })?;
+                self.store.find_alias_by_pkh(&pkh)
+            });
+        match alias {
+            Some(alias) => FindKeyError::WatchOnly(alias.to_string()),
+            None => FindKeyError::KeyNotFound(alias_pkh_or_pk.to_string()),
+        }
+    }
+
     /// Decrypt stored key, if it's not stored un-encrypted.
     /// If a given storage key needs to be decrypted and password is not
     /// supplied, then interactively prompt for password and if successfully
@@ -909,6 +1273,29 @@ impl<U: WalletIo> Wallet<U> {
             .map(Into::into)
     }
 
+    /// Insert a watch-only account into the wallet under the given alias,
+    /// made of an address, a public key and a viewing key, any of which may be
+    /// missing, but not all. If the alias is already used, then display a
+    /// prompt for overwrite confirmation.
+    pub fn insert_watch_only(
+        &mut self,
+        alias: String,
+        address: Option<Address>,
+        pubkey: Option<common::PublicKey>,
+        view_key: Option<ExtendedViewingKey>,
+        force_alias: bool,
+    ) -> Option<String> {
+        self.store
+            .insert_watch_only::<U>(
+                alias.into(),
+                address,
+                pubkey,
+                view_key,
+                force_alias,
+            )
+            .map(Into::into)
+    }
+
     /// Insert a viewing key into the wallet under the given alias
     pub fn insert_viewing_key(
         &mut self,
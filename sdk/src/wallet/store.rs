@@ -5,6 +5,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 use bimap::BiBTreeMap;
+use borsh::{BorshDeserialize, BorshSerialize};
 use itertools::Itertools;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use namada_core::types::address::{Address, ImplicitAddress};
@@ -16,10 +17,11 @@ use serde::{Deserialize, Serialize};
 use slip10_ed25519;
 use zeroize::Zeroizing;
 
+use super::account::{SigningAccount, WalletAccount, WatchOnlyAccount};
 use super::alias::{self, Alias};
-use super::derivation_path::DerivationPath;
+use super::derivation_path::{DerivationPath, ValidatorKeyKind};
 use super::pre_genesis;
-use crate::wallet::{StoredKeypair, WalletIo};
+use crate::wallet::{DecryptionError, StoredKeypair, WalletIo};
 
 /// Actions that can be taken when there is an alias conflict
 pub enum ConfirmationResponse {
@@ -45,6 +47,21 @@ impl ValidatorKeys {
     pub fn get_protocol_keypair(&self) -> &common::SecretKey {
         &self.protocol_keypair
     }
+
+    /// Derive the keys of the given validator account from a HD seed
+    pub fn derive(seed: &[u8], account: u32) -> Self {
+        let derive = |kind: ValidatorKeyKind| {
+            derive_hd_secret_key(
+                kind.scheme(),
+                seed,
+                DerivationPath::validator(kind, account),
+            )
+        };
+        Self {
+            protocol_keypair: derive(ValidatorKeyKind::Protocol),
+            eth_bridge_keypair: derive(ValidatorKeyKind::EthHot),
+        }
+    }
 }
 
 /// Special data associated with a validator
@@ -200,6 +217,49 @@ impl Store {
         self.addresses.get_by_right(address)
     }
 
+    /// Find the account with the given alias. The account can be used for
+    /// signing only if its secret key is in the wallet.
+    pub fn find_account(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Option<WalletAccount> {
+        let alias: Alias = alias.as_ref().into();
+        let address = self.addresses.get_by_left(&alias).cloned();
+        let public_key = self.public_keys.get(&alias).cloned();
+        if self.secret_keys.contains_key(&alias) {
+            if let (Some(address), Some(public_key)) =
+                (address.clone(), public_key.clone())
+            {
+                return Some(WalletAccount::Signing(SigningAccount {
+                    alias,
+                    address,
+                    public_key,
+                }));
+            }
+        }
+        let viewing_key = self.view_keys.get(&alias).cloned();
+        if address.is_none() && public_key.is_none() && viewing_key.is_none() {
+            return None;
+        }
+        Some(WalletAccount::WatchOnly(WatchOnlyAccount {
+            alias,
+            address,
+            public_key,
+            viewing_key,
+        }))
+    }
+
+    /// Get all the accounts with an address, a public key or a viewing key
+    pub fn get_accounts(&self) -> Vec<WalletAccount> {
+        self.addresses
+            .left_values()
+            .chain(self.public_keys.keys())
+            .chain(self.view_keys.keys())
+            .unique()
+            .filter_map(|alias| self.find_account(alias))
+            .collect()
+    }
+
     /// Get all known keys by their alias, paired with PKH, if known.
     pub fn get_secret_keys(
         &self,
@@ -475,6 +535,87 @@ impl Store {
         Some(alias)
     }
 
+    /// Insert a watch-only account made of an address, a public key and a
+    /// viewing key, any of which may be missing, but not all. The address
+    /// defaults to the implicit address of the public key. No secret key is
+    /// stored for the account, so it can't be used for signing.
+    pub fn insert_watch_only<U: WalletIo>(
+        &mut self,
+        mut alias: Alias,
+        address: Option<Address>,
+        pubkey: Option<common::PublicKey>,
+        viewkey: Option<ExtendedViewingKey>,
+        force: bool,
+    ) -> Option<Alias> {
+        let pkh = pubkey.as_ref().map(PublicKeyHash::from);
+        let address = address.or_else(|| {
+            pkh.clone()
+                .map(|pkh| Address::Implicit(ImplicitAddress(pkh)))
+        });
+        if address.is_none() && viewkey.is_none() {
+            eprintln!(
+                "A watch-only account needs an address, a public key or a \
+                 viewing key."
+            );
+            return None;
+        }
+        if !force {
+            if matches!(&pkh, Some(pkh) if self.pkhs.contains_key(pkh)) {
+                println!("The key already exists.");
+                return None;
+            }
+            if let Some(address) = address.as_ref() {
+                if let Some(alias) = self.addresses.get_by_right(address) {
+                    println!(
+                        "Address {} already exists in the wallet with alias {}",
+                        address.encode(),
+                        alias,
+                    );
+                    return None;
+                }
+            }
+        }
+
+        // abort if the alias is reserved
+        if Alias::is_reserved(&alias).is_some() {
+            println!("The alias {} is reserved", alias);
+            return None;
+        }
+
+        if alias.is_empty() {
+            let Some(address) = address.as_ref() else {
+                eprintln!("Empty alias given.");
+                return None;
+            };
+            alias = address.encode().into();
+            println!("Empty alias given, defaulting to {}.", alias);
+        }
+        if self.contains_alias(&alias) && !force {
+            match U::show_overwrite_confirmation(&alias, "a watch-only account")
+            {
+                ConfirmationResponse::Replace => {}
+                ConfirmationResponse::Reselect(new_alias) => {
+                    return self.insert_watch_only::<U>(
+                        new_alias, address, pubkey, viewkey, false,
+                    );
+                }
+                ConfirmationResponse::Skip => return None,
+            }
+        }
+        self.remove_alias(&alias);
+        if let Some(address) = address {
+            self.addresses.insert(alias.clone(), address);
+        }
+        if let (Some(pubkey), Some(pkh)) = (pubkey, pkh) {
+            self.public_keys.insert(alias.clone(), pubkey);
+            self.pkhs.insert(pkh, alias.clone());
+        }
+        if let Some(viewkey) = viewkey {
+            self.view_keys.insert(alias.clone(), viewkey);
+        }
+        Some(alias)
+    }
+
     /// Insert payment addresses similarly to how it's done for keypairs
     pub fn insert_payment_addr<U: WalletIo>(
         &mut self,
@@ -676,6 +817,24 @@ impl Store {
             .insert(address);
     }
 
+    /// Encrypt all the secret and spending keys that are stored raw or
+    /// encrypted with the legacy scheme with the current encryption scheme
+    /// and the given password. The keys encrypted with the legacy scheme must
+    /// have been encrypted with the same password. If any key cannot be
+    /// migrated, the store is left unchanged. Returns the number of migrated
+    /// keys.
+    pub fn migrate_keys(
+        &mut self,
+        password: Zeroizing<String>,
+    ) -> Result<usize, DecryptionError> {
+        let secret_keys = migrate_stored_keys(&self.secret_keys, &password)?;
+        let spend_keys = migrate_stored_keys(&self.spend_keys, &password)?;
+        let migrated = secret_keys.len() + spend_keys.len();
+        self.secret_keys.extend(secret_keys);
+        self.spend_keys.extend(spend_keys);
+        Ok(migrated)
+    }
+
     /// Decode a Store from the given bytes
     pub fn decode(data: Vec<u8>) -> Result<Self, toml::de::Error> {
         toml::from_slice(&data)
@@ -687,6 +846,25 @@ impl Store {
     }
 }
 
+/// Migrate the given stored keys that need it, see
+/// [`Store::migrate_keys`]
+fn migrate_stored_keys<T>(
+    keys: &BTreeMap<Alias, StoredKeypair<T>>,
+    password: &Zeroizing<String>,
+) -> Result<Vec<(Alias, StoredKeypair<T>)>, DecryptionError>
+where
+    T: BorshSerialize + BorshDeserialize + Display + FromStr + Clone,
+    <T as FromStr>::Err: Display,
+{
+    keys.iter()
+        .filter(|(_alias, key)| key.needs_migration())
+        .map(|(alias, key)| {
+            key.migrate(password.clone())
+                .map(|migrated| (alias.clone(), migrated))
+        })
+        .collect()
+}
+
 /// Generate a new secret key from the seed.
 pub fn derive_hd_secret_key(
     scheme: SchemeType,
@@ -889,6 +1067,36 @@ mod test_wallet {
         }
     }
 
+    #[test]
+    fn derive_validator_keys() {
+        const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+        let seed = HEXLOWER.decode(SEED.as_bytes()).unwrap();
+
+        let keys = ValidatorKeys::derive(&seed, 0);
+        let protocol_path =
+            DerivationPath::validator(ValidatorKeyKind::Protocol, 0);
+        assert_eq!(
+            keys.protocol_keypair.ref_to(),
+            derive_hd_secret_key(SchemeType::Ed25519, &seed, protocol_path)
+                .ref_to()
+        );
+        assert!(matches!(
+            keys.eth_bridge_keypair,
+            common::SecretKey::Secp256k1(_)
+        ));
+
+        // each account has its own keys
+        let other_keys = ValidatorKeys::derive(&seed, 1);
+        assert_ne!(
+            keys.protocol_keypair.ref_to(),
+            other_keys.protocol_keypair.ref_to()
+        );
+        assert_ne!(
+            keys.eth_bridge_keypair.ref_to(),
+            other_keys.eth_bridge_keypair.ref_to()
+        );
+    }
+
     #[test]
     fn gen_sk_from_seed_ed25519() {
         const SCHEME: SchemeType = SchemeType::Ed25519;
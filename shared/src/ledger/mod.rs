@@ -13,15 +13,17 @@ pub mod storage;
 pub mod vp_host_fns;
 
 #[cfg(feature = "wasm-runtime")]
-pub use dry_run_tx::dry_run_tx;
+pub use dry_run_tx::{dry_run_tx, estimate_gas};
 pub use namada_core::ledger::{
     gas, parameters, replay_protection, storage_api, tx_env, vp_env,
 };
 
 #[cfg(feature = "wasm-runtime")]
 mod dry_run_tx {
+    use borsh_ext::BorshSerializeExt;
     use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
     use namada_core::ledger::storage_api::ResultExt;
+    use namada_core::types::transaction::TxResult;
     use namada_sdk::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
 
     use super::{protocol, storage_api};
@@ -30,7 +32,26 @@ mod dry_run_tx {
 
     /// Dry run a transaction
     pub fn dry_run_tx<D, H, CA>(
-        mut ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        request: &RequestQuery,
+    ) -> storage_api::Result<EncodedResponseQuery>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
+        let data = simulate_tx(ctx, request)?.serialize_to_vec();
+        Ok(EncodedResponseQuery {
+            data,
+            proof: None,
+            info: Default::default(),
+        })
+    }
+
+    /// Estimate the gas used by a transaction, including the gas used by its
+    /// wrapper (if any), by dry running it
+    pub fn estimate_gas<D, H, CA>(
+        ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
         request: &RequestQuery,
     ) -> storage_api::Result<EncodedResponseQuery>
     where
@@ -38,7 +59,25 @@ mod dry_run_tx {
         H: 'static + StorageHasher + Sync,
         CA: 'static + WasmCacheAccess + Sync,
     {
-        use borsh_ext::BorshSerializeExt;
+        let data = simulate_tx(ctx, request)?.gas_used.serialize_to_vec();
+        Ok(EncodedResponseQuery {
+            data,
+            proof: None,
+            info: Default::default(),
+        })
+    }
+
+    /// Execute the transaction in the request against a temporary copy of
+    /// the storage, whose changes are then dropped
+    fn simulate_tx<D, H, CA>(
+        mut ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        request: &RequestQuery,
+    ) -> storage_api::Result<TxResult>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
         use namada_core::ledger::gas::{Gas, GasMetering, TxGasMeter};
         use namada_core::ledger::storage::TempWlStorage;
         use namada_core::proto::Tx;
@@ -123,12 +162,7 @@ mod dry_run_tx {
         data.gas_used = cumulated_gas;
         // NOTE: the keys changed by the wrapper transaction (if any) are not
         // returned from this function
-        let data = data.serialize_to_vec();
-        Ok(EncodedResponseQuery {
-            data,
-            proof: None,
-            info: Default::default(),
-        })
+        Ok(data)
     }
 }
 
@@ -255,6 +289,8 @@ mod test {
             // really permit error types other than [`std::io::Error`]
             if request.path == "/shell/dry_run_tx" {
                 super::dry_run_tx(ctx, &request)
+            } else if request.path == "/shell/estimate_gas" {
+                super::estimate_gas(ctx, &request)
             } else {
                 self.rpc.handle(ctx, &request)
             }
@@ -302,11 +338,19 @@ mod test {
         let tx_bytes = outer_tx.to_bytes();
         let result = RPC
             .shell()
-            .dry_run_tx(&client, Some(tx_bytes), None, false)
+            .dry_run_tx(&client, Some(tx_bytes.clone()), None, false)
             .await
             .unwrap();
         assert!(result.data.is_accepted());
 
+        // Request the gas estimation of the same tx
+        let gas_used = RPC
+            .shell()
+            .estimate_gas(&client, Some(tx_bytes), None, false)
+            .await
+            .unwrap();
+        assert_eq!(gas_used.data, result.data.gas_used);
+
         // Request storage value for a balance key ...
         let token_addr = address::testing::established_address_1();
         let owner = address::testing::established_address_2();
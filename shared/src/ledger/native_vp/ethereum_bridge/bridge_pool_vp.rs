@@ -663,6 +663,7 @@ mod test_bridge_pool_vp {
     use crate::types::hash::Hash;
     use crate::types::storage::TxIndex;
     use crate::types::transaction::TxType;
+    use crate::types::voting_power::FractionalVotingPower;
     use crate::vm::wasm::VpCache;
     use crate::vm::WasmCacheRwAccess;
 
@@ -905,6 +906,7 @@ mod test_bridge_pool_vp {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: Default::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
             contracts: Contracts {
                 native_erc20: wnam(),
                 bridge: UpgradeableContract {
@@ -2,13 +2,14 @@
 use std::collections::{BTreeSet, HashSet};
 
 use eyre::{eyre, Result};
-use namada_core::ledger::eth_bridge::storage::{self, escrow_key};
+use namada_core::ledger::eth_bridge::storage::{self, escrow_key, whitelist};
 use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::{eth_bridge, storage as ledger_storage};
 use namada_core::types::address::Address;
 use namada_core::types::storage::Key;
 use namada_core::types::token::{balance_key, is_balance_key, Amount};
 
+use crate::core::ledger::storage_api::governance;
 use crate::ledger::native_vp::{Ctx, NativeVp, StorageReader};
 use crate::proto::Tx;
 use crate::vm::WasmCacheAccess;
@@ -35,6 +36,32 @@ where
     H: 'static + StorageHasher,
     CA: 'static + WasmCacheAccess,
 {
+    /// The ERC20 token whitelist may only be updated by an accepted
+    /// governance proposal, without touching any other key of the
+    /// Ethereum bridge.
+    fn validate_whitelist_update(
+        &self,
+        tx: &Tx,
+        keys_changed: &BTreeSet<Key>,
+    ) -> Result<bool, Error> {
+        let only_whitelist_changed = keys_changed
+            .iter()
+            .filter(|key| storage::has_eth_addr_segment(key))
+            .all(whitelist::is_cap_or_whitelisted_key);
+        if !only_whitelist_changed {
+            tracing::debug!(
+                "Rejecting tx updating the ERC20 whitelist along with other \
+                 Ethereum bridge keys"
+            );
+            return Ok(false);
+        }
+        let Some(data) = tx.data() else {
+            return Ok(false);
+        };
+        Ok(governance::is_proposal_accepted(&self.ctx.pre(), &data)
+            .unwrap_or(false))
+    }
+
     /// If the Ethereum bridge's escrow key was written to, we check
     /// that the NAM balance increased and that the Bridge pool VP has
     /// been triggered.
@@ -101,7 +128,7 @@ where
     /// no wasm transactions should be able to modify those keys.
     fn validate_tx(
         &self,
-        _: &Tx,
+        tx: &Tx,
         keys_changed: &BTreeSet<Key>,
         verifiers: &BTreeSet<Address>,
     ) -> Result<bool, Self::Error> {
@@ -111,6 +138,13 @@ where
             "Ethereum Bridge VP triggered",
         );
 
+        if keys_changed
+            .iter()
+            .any(whitelist::is_cap_or_whitelisted_key)
+        {
+            return self.validate_whitelist_update(tx, keys_changed);
+        }
+
         if !validate_changed_keys(&self.ctx.storage.native_token, keys_changed)?
         {
             return Ok(false);
@@ -176,12 +210,13 @@ mod tests {
     use rand::Rng;
 
     use super::*;
+    use crate::core::ledger::governance::storage::keys as governance_keys;
     use crate::ledger::gas::VpGasMeter;
     use crate::ledger::storage::mockdb::MockDB;
     use crate::ledger::storage::traits::Sha256Hasher;
     use crate::ledger::storage::write_log::WriteLog;
     use crate::ledger::storage::{Storage, WlStorage};
-    use crate::proto::Tx;
+    use crate::proto::{Data, Tx};
     use crate::types::address::testing::established_address_1;
     use crate::types::address::{nam, wnam};
     use crate::types::ethereum_events;
@@ -189,6 +224,7 @@ mod tests {
     use crate::types::storage::TxIndex;
     use crate::types::token::minted_balance_key;
     use crate::types::transaction::TxType;
+    use crate::types::voting_power::FractionalVotingPower;
     use crate::vm::wasm::VpCache;
     use crate::vm::WasmCacheRwAccess;
 
@@ -229,6 +265,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: Default::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
             contracts: Contracts {
                 native_erc20: wnam(),
                 bridge: UpgradeableContract {
@@ -498,4 +535,47 @@ mod tests {
         let res = vp.validate_tx(&tx, &keys_changed, &verifiers);
         assert!(!res.expect("Test failed"));
     }
+
+    /// Test that the ERC20 whitelist can only be updated by an accepted
+    /// governance proposal.
+    #[test]
+    fn test_whitelist_update_requires_accepted_proposal() {
+        let mut wl_storage = setup_storage();
+        let proposal_id = 0u64;
+        let updates = [whitelist::WhitelistUpdate::Add {
+            token: ethereum_events::testing::DAI_ERC20_ETH_ADDRESS,
+            cap: Amount::from(1_000),
+            denom: 18.into(),
+        }];
+        whitelist::apply_updates(&mut wl_storage, &updates)
+            .expect("Test failed");
+        let keys_changed = wl_storage.write_log.get_keys();
+        let verifiers = BTreeSet::new();
+
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_data(Data::new(proposal_id.serialize_to_vec()));
+        let validate = |wl_storage: &WlStorage<MockDB, Sha256Hasher>| {
+            let vp = EthBridge {
+                ctx: setup_ctx(
+                    &tx,
+                    &wl_storage.storage,
+                    &wl_storage.write_log,
+                    &keys_changed,
+                    &verifiers,
+                ),
+            };
+            vp.validate_tx(&tx, &keys_changed, &verifiers)
+                .expect("Test failed")
+        };
+        assert!(!validate(&wl_storage));
+
+        // the proposal is being executed
+        let execution_key =
+            governance_keys::get_proposal_execution_key(proposal_id);
+        wl_storage
+            .storage
+            .write(&execution_key, ().serialize_to_vec())
+            .expect("Test failed");
+        assert!(validate(&wl_storage));
+    }
 }
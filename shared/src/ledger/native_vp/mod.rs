@@ -6,14 +6,17 @@ pub mod ibc;
 pub mod masp;
 pub mod multitoken;
 pub mod parameters;
+pub mod vesting;
 
 use std::cell::RefCell;
 use std::collections::BTreeSet;
 
 use borsh::BorshDeserialize;
 use eyre::WrapErr;
-use namada_core::ledger::gas::GasMetering;
+use namada_core::ledger::gas::{GasMetering, VERIFY_TX_SIG_GAS};
+use namada_core::ledger::parameters;
 pub use namada_core::ledger::vp_env::VpEnv;
+use namada_core::types::account::AccountPublicKeysMap;
 use namada_core::types::validity_predicate::VpSentinel;
 
 use super::storage_api::{self, ResultExt, StorageRead};
@@ -168,6 +171,35 @@ where
     ) -> CtxPostStorageRead<'view, 'a, DB, H, CA> {
         CtxPostStorageRead { ctx: self }
     }
+
+    /// Check that the tx is signed by at least `threshold` of the given
+    /// public keys on behalf of the `signer` account. The signatures are
+    /// verified against the prior storage's max signatures per tx parameter.
+    pub fn verify_signatures(
+        &self,
+        public_keys_index_map: AccountPublicKeysMap,
+        signer: &Address,
+        threshold: u8,
+    ) -> Result<bool, storage_api::Error> {
+        let max_signatures =
+            parameters::max_signatures_per_transaction(&self.pre())?;
+        let mut gas_meter = self.gas_meter.borrow_mut();
+        match self.tx.verify_signatures(
+            &[self.tx.raw_header_hash()],
+            public_keys_index_map,
+            &Some(signer.clone()),
+            threshold,
+            max_signatures,
+            || gas_meter.consume(VERIFY_TX_SIG_GAS),
+        ) {
+            Ok(_) => Ok(true),
+            Err(crate::proto::Error::OutOfGas(err)) => {
+                self.sentinel.borrow_mut().set_out_of_gas();
+                Err(err).into_storage_result()
+            }
+            Err(_) => Ok(false),
+        }
+    }
 }
 
 impl<'view, 'a: 'view, DB, H, CA> StorageRead
@@ -0,0 +1,389 @@
+//! Vesting native VP. The vesting address holds the locked native tokens of
+//! the vesting accounts. The VP checks that these tokens only leave the
+//! vesting address when they're unlocked or when they're delegated by their
+//! owner, in which case they must be credited to the PoS address.
+
+use std::cmp;
+use std::collections::BTreeSet;
+
+use namada_core::ledger::storage;
+use namada_core::ledger::storage_api::{account, vesting, StorageRead};
+use namada_core::ledger::vesting::{storage as vesting_storage, ADDRESS};
+use namada_core::ledger::vp_env::VpEnv;
+use namada_core::proto::Tx;
+use namada_core::types::address::{Address, POS};
+use namada_core::types::storage::Key;
+use namada_core::types::token::{self, Amount};
+use namada_proof_of_stake::storage::is_bond_key;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Vesting VP result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Vesting VP
+pub struct VestingVp<'a, DB, H, CA>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: storage::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for VestingVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        _tx: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        _verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let native_token = self.ctx.pre().get_native_token()?;
+        let mut owners = BTreeSet::new();
+        for key in keys_changed {
+            if let Some(owner) = vesting_storage::is_schedule_key(key)
+                .or_else(|| vesting_storage::is_locked_key(key))
+            {
+                owners.insert(owner.clone());
+            } else if vesting_storage::is_vesting_key(key) {
+                tracing::info!("Unexpected change of vesting key {key}");
+                return Ok(false);
+            } else if let Some([token, owner]) =
+                token::is_any_token_balance_key(key)
+            {
+                // Only native tokens can be held by the vesting address
+                if owner == &ADDRESS && token != &native_token {
+                    return Ok(false);
+                }
+            }
+        }
+
+        let current_epoch = self.ctx.get_block_epoch()?;
+        let mut held_change = token::Change::zero();
+        let mut delegated = Amount::zero();
+        for owner in owners {
+            let pre_held =
+                vesting::read_locked_amount(&self.ctx.pre(), &owner)?;
+            let post_held =
+                vesting::read_locked_amount(&self.ctx.post(), &owner)?;
+            held_change += post_held.change() - pre_held.change();
+
+            let pre_schedule = vesting::read_schedule(&self.ctx.pre(), &owner)?;
+            let post_schedule =
+                vesting::read_schedule(&self.ctx.post(), &owner)?;
+            let is_valid = match (pre_schedule, post_schedule) {
+                // A new vesting account must be funded with the total of its
+                // schedule
+                (None, Some(schedule)) => {
+                    schedule.is_valid()
+                        && pre_held.is_zero()
+                        && schedule.total() == Some(post_held)
+                        && self.is_signed_by(&owner)?
+                }
+                (Some(pre_schedule), Some(post_schedule))
+                    if pre_schedule == post_schedule =>
+                {
+                    if post_held >= pre_held {
+                        true
+                    } else {
+                        // The tokens that are still locked can only leave the
+                        // vesting address to be delegated by the owner
+                        let locked = post_schedule.locked_at(current_epoch);
+                        let locked_outflow = cmp::min(
+                            pre_held - post_held,
+                            locked.checked_sub(post_held).unwrap_or_default(),
+                        );
+                        delegated += locked_outflow;
+                        self.is_signed_by(&owner)?
+                            && (locked_outflow.is_zero()
+                                || self.is_delegated(
+                                    keys_changed,
+                                    &native_token,
+                                    &owner,
+                                    locked_outflow,
+                                )?)
+                    }
+                }
+                // The schedules cannot be changed or removed
+                _ => false,
+            };
+            if !is_valid {
+                tracing::info!(
+                    "Invalid change of the vesting account of {owner}"
+                );
+                return Ok(false);
+            }
+        }
+
+        // The delegated locked tokens must be credited to the PoS address,
+        // rather than to the owners, which could otherwise bond the same
+        // amount of their liquid tokens in their place
+        if !delegated.is_zero() {
+            let pos_balance_key = token::balance_key(&native_token, &POS);
+            let pre_pos_balance: Amount =
+                self.ctx.read_pre(&pos_balance_key)?.unwrap_or_default();
+            let post_pos_balance: Amount =
+                self.ctx.read_post(&pos_balance_key)?.unwrap_or_default();
+            if post_pos_balance.change() - pre_pos_balance.change()
+                < delegated.change()
+            {
+                tracing::info!(
+                    "The delegated locked tokens must be credited to PoS"
+                );
+                return Ok(false);
+            }
+        }
+
+        // The tokens held by the vesting address must match the sum of the
+        // amounts held on behalf of the vesting accounts
+        let balance_key = token::balance_key(&native_token, &ADDRESS);
+        let pre_balance: Amount =
+            self.ctx.read_pre(&balance_key)?.unwrap_or_default();
+        let post_balance: Amount =
+            self.ctx.read_post(&balance_key)?.unwrap_or_default();
+        Ok(post_balance.change() - pre_balance.change() == held_change)
+    }
+}
+
+impl<'a, DB, H, CA> VestingVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that the tx is signed by the owner of a vesting account
+    fn is_signed_by(&self, owner: &Address) -> Result<bool> {
+        let pre = self.ctx.pre();
+        let public_keys = account::public_keys_index_map(&pre, owner)?;
+        let threshold = account::threshold(&pre, owner)?.unwrap_or(1);
+        Ok(self.ctx.verify_signatures(public_keys, owner, threshold)?)
+    }
+
+    /// Check that the locked tokens that left the vesting address on behalf
+    /// of the `owner` have been delegated, i.e. that the owner's bonds have
+    /// grown by at least the same amount and that the tokens weren't credited
+    /// to the owner instead
+    fn is_delegated(
+        &self,
+        keys_changed: &BTreeSet<Key>,
+        native_token: &Address,
+        owner: &Address,
+        locked_outflow: Amount,
+    ) -> Result<bool> {
+        let balance_key = token::balance_key(native_token, owner);
+        let pre_balance: Amount =
+            self.ctx.read_pre(&balance_key)?.unwrap_or_default();
+        let post_balance: Amount =
+            self.ctx.read_post(&balance_key)?.unwrap_or_default();
+        Ok(post_balance <= pre_balance
+            && self.bonded_amount(keys_changed, owner)?
+                >= locked_outflow.change())
+    }
+
+    /// The sum of the changes of the bonds delegated by the `source`
+    fn bonded_amount(
+        &self,
+        keys_changed: &BTreeSet<Key>,
+        source: &Address,
+    ) -> Result<token::Change> {
+        let mut bonded = token::Change::zero();
+        for key in keys_changed {
+            match is_bond_key(key) {
+                Some((bond_id, _)) if &bond_id.source == source => {
+                    let pre: Amount =
+                        self.ctx.read_pre(key)?.unwrap_or_default();
+                    let post: Amount =
+                        self.ctx.read_post(key)?.unwrap_or_default();
+                    bonded += post.change() - pre.change();
+                }
+                _ => {}
+            }
+        }
+        Ok(bonded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use namada_core::ledger::gas::TxGasMeter;
+    use namada_core::ledger::storage_api::token::credit_tokens;
+    use namada_core::ledger::vesting::VestingSchedule;
+    use namada_core::types::account::AccountPublicKeysMap;
+    use namada_core::types::storage::Epoch;
+    use namada_proof_of_stake::bond_handle;
+
+    use super::*;
+    use crate::core::ledger::storage::testing::TestWlStorage;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+    use crate::ledger::gas::VpGasMeter;
+    use crate::proto::{Code, Data, Section, Signature, Tx};
+    use crate::types::key::testing::keypair_1;
+    use crate::types::key::RefTo;
+    use crate::types::storage::TxIndex;
+    use crate::types::transaction::TxType;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    /// Set up a vesting account of 100 tokens that unlock in epoch 5
+    fn init_storage() -> (TestWlStorage, Address) {
+        let mut wl_storage = TestWlStorage::default();
+        let native_token = wl_storage.storage.native_token.clone();
+        let funder = established_address_1();
+        let owner = established_address_2();
+        account::init_account_storage(
+            &mut wl_storage,
+            &owner,
+            &[keypair_1().ref_to()],
+            1,
+        )
+        .unwrap();
+        credit_tokens(
+            &mut wl_storage,
+            &native_token,
+            &funder,
+            Amount::from(100_u64),
+        )
+        .unwrap();
+        let schedule = VestingSchedule {
+            tranches: BTreeMap::from_iter([(Epoch(5), Amount::from(100_u64))]),
+        };
+        vesting::init_vesting(&mut wl_storage, &funder, &owner, schedule)
+            .unwrap();
+        wl_storage.commit_block().unwrap();
+        (wl_storage, owner)
+    }
+
+    fn signed_tx(wl_storage: &TestWlStorage) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        let pks_map = AccountPublicKeysMap::from_iter([keypair_1().ref_to()]);
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair_1()]),
+            None,
+        )));
+        tx
+    }
+
+    fn validate(wl_storage: &TestWlStorage) -> bool {
+        let keys_changed = wl_storage.write_log.get_keys();
+        let verifiers = BTreeSet::new();
+        let tx_index = TxIndex::default();
+        let tx = signed_tx(wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        let vp = VestingVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    #[test]
+    fn test_locked_tokens_cannot_be_transferred() {
+        let (mut wl_storage, owner) = init_storage();
+        vesting::debit_locked(
+            &mut wl_storage,
+            &owner,
+            &owner,
+            Amount::from(10_u64),
+        )
+        .unwrap();
+        assert!(!validate(&wl_storage));
+    }
+
+    #[test]
+    fn test_locked_tokens_can_be_delegated() {
+        let (mut wl_storage, owner) = init_storage();
+        let amount = Amount::from(10_u64);
+        vesting::debit_locked(&mut wl_storage, &owner, &POS, amount).unwrap();
+        // Without a matching bond, the tokens cannot leave
+        assert!(!validate(&wl_storage));
+
+        bond_handle(&owner, &established_address_3())
+            .get_data_handler()
+            .insert(&mut wl_storage, Epoch(2), amount)
+            .unwrap();
+        assert!(validate(&wl_storage));
+    }
+
+    #[test]
+    fn test_locked_tokens_must_be_delegated_to_pos() {
+        let (mut wl_storage, owner) = init_storage();
+        let native_token = wl_storage.storage.native_token.clone();
+        let amount = Amount::from(10_u64);
+        // The locked tokens are moved to the owner, who bonds the same amount
+        // of liquid tokens in their place
+        vesting::debit_locked(&mut wl_storage, &owner, &owner, amount).unwrap();
+        bond_handle(&owner, &established_address_3())
+            .get_data_handler()
+            .insert(&mut wl_storage, Epoch(2), amount)
+            .unwrap();
+        assert!(!validate(&wl_storage));
+
+        // Even if PoS is credited from elsewhere
+        credit_tokens(&mut wl_storage, &native_token, &POS, amount).unwrap();
+        assert!(!validate(&wl_storage));
+    }
+
+    #[test]
+    fn test_unlocked_tokens_can_be_claimed() {
+        let (mut wl_storage, owner) = init_storage();
+        let claimed =
+            vesting::claim_vested(&mut wl_storage, &owner, Epoch(0)).unwrap();
+        assert!(claimed.is_zero());
+
+        wl_storage.storage.block.epoch = Epoch(5);
+        let claimed =
+            vesting::claim_vested(&mut wl_storage, &owner, Epoch(5)).unwrap();
+        assert_eq!(claimed, Amount::from(100_u64));
+        assert!(validate(&wl_storage));
+    }
+
+    #[test]
+    fn test_unaccounted_balance_change_rejected() {
+        let (mut wl_storage, _owner) = init_storage();
+        let native_token = wl_storage.storage.native_token.clone();
+        credit_tokens(
+            &mut wl_storage,
+            &native_token,
+            &ADDRESS,
+            Amount::from(1_u64),
+        )
+        .unwrap();
+        assert!(!validate(&wl_storage));
+    }
+}
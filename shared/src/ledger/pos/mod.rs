@@ -1,5 +1,6 @@
 //! Proof-of-Stake integration as a native validity predicate
 
+pub mod utils;
 pub mod vp;
 
 use std::convert::TryFrom;
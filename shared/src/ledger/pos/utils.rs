@@ -0,0 +1,207 @@
+//! Ledger events reporting the PoS inflation and rewards distribution, the
+//! storage I/O of the PoS operations and the changes of the validators' states
+
+use std::collections::HashMap;
+
+use namada_core::types::storage::BlockHeight;
+use namada_proof_of_stake::io_stats::{PosIoStats, PosOp, PosOpIoStats};
+use namada_proof_of_stake::rewards::{InflationDistribution, ValidatorRewards};
+use namada_proof_of_stake::types::ValidatorStateChange;
+
+use crate::ledger::events::{Event, EventLevel, EventType};
+
+/// PoS rewards event definition
+pub struct RewardsEvent {
+    /// Rewards event type
+    pub event_type: EventType,
+    /// Rewards event attributes
+    pub attributes: HashMap<String, String>,
+}
+
+impl From<RewardsEvent> for Event {
+    fn from(rewards_event: RewardsEvent) -> Self {
+        Self {
+            event_type: rewards_event.event_type,
+            level: EventLevel::Block,
+            attributes: rewards_event.attributes,
+        }
+    }
+}
+
+impl RewardsEvent {
+    /// Create a new event for the PoS inflation minted for an epoch
+    pub fn inflation(
+        distribution: &InflationDistribution,
+        height: BlockHeight,
+    ) -> Self {
+        let attributes = HashMap::from([
+            ("height".to_string(), height.to_string()),
+            ("epoch".to_string(), distribution.epoch.to_string()),
+            (
+                "num_blocks".to_string(),
+                distribution.num_blocks.to_string(),
+            ),
+            (
+                "inflation".to_string(),
+                distribution.inflation.to_string_native(),
+            ),
+            (
+                "pos_reward_tokens".to_string(),
+                distribution.pos_reward_tokens.to_string_native(),
+            ),
+            (
+                "dust_recipient".to_string(),
+                distribution.dust_recipient.to_string(),
+            ),
+            (
+                "dust_tokens".to_string(),
+                distribution.dust_tokens.to_string_native(),
+            ),
+            (
+                "num_validators".to_string(),
+                distribution.validators.len().to_string(),
+            ),
+        ]);
+        Self {
+            event_type: EventType::PosInflation,
+            attributes,
+        }
+    }
+
+    /// Create a new event for the updated rewards product of a validator
+    pub fn validator_rewards(
+        distribution: &InflationDistribution,
+        rewards: &ValidatorRewards,
+        height: BlockHeight,
+    ) -> Self {
+        let attributes = HashMap::from([
+            ("height".to_string(), height.to_string()),
+            ("epoch".to_string(), distribution.epoch.to_string()),
+            ("validator".to_string(), rewards.validator.to_string()),
+            (
+                "reward_fraction".to_string(),
+                rewards.reward_fraction.to_string(),
+            ),
+            (
+                "reward_tokens".to_string(),
+                rewards.reward_tokens.to_string_native(),
+            ),
+            ("rewards_product".to_string(), rewards.product.to_string()),
+            (
+                "commissions".to_string(),
+                rewards.commissions.to_string_native(),
+            ),
+            ("dust".to_string(), rewards.dust.to_string_native()),
+        ]);
+        Self {
+            event_type: EventType::PosRewards,
+            attributes,
+        }
+    }
+
+    /// Create the events for the whole distribution of the PoS inflation of an
+    /// epoch: one for the minted inflation, followed by one per validator
+    pub fn distribution_events(
+        distribution: &InflationDistribution,
+        height: BlockHeight,
+    ) -> Vec<Event> {
+        std::iter::once(Self::inflation(distribution, height))
+            .chain(distribution.validators.iter().map(|rewards| {
+                Self::validator_rewards(distribution, rewards, height)
+            }))
+            .map(Event::from)
+            .collect()
+    }
+}
+
+/// PoS storage I/O event definition
+pub struct PosIoEvent {
+    /// PoS storage I/O event attributes
+    pub attributes: HashMap<String, String>,
+}
+
+impl From<PosIoEvent> for Event {
+    fn from(io_event: PosIoEvent) -> Self {
+        Self {
+            event_type: EventType::PosIo,
+            level: EventLevel::Block,
+            attributes: io_event.attributes,
+        }
+    }
+}
+
+impl PosIoEvent {
+    /// Create a new event for the storage I/O of all the calls of a PoS
+    /// operation in a block
+    pub fn new(op: PosOp, stats: &PosOpIoStats, height: BlockHeight) -> Self {
+        let io = &stats.io;
+        let max_call = &stats.max_call;
+        let attributes = HashMap::from([
+            ("height".to_string(), height.to_string()),
+            ("op".to_string(), op.to_string()),
+            ("calls".to_string(), stats.calls.to_string()),
+            ("reads".to_string(), io.reads.to_string()),
+            ("bytes_read".to_string(), io.bytes_read.to_string()),
+            ("iters".to_string(), io.iters.to_string()),
+            ("iter_steps".to_string(), io.iter_steps.to_string()),
+            ("writes".to_string(), io.writes.to_string()),
+            ("bytes_written".to_string(), io.bytes_written.to_string()),
+            ("deletes".to_string(), io.deletes.to_string()),
+            ("max_call_reads".to_string(), max_call.reads.to_string()),
+            (
+                "max_call_iter_steps".to_string(),
+                max_call.iter_steps.to_string(),
+            ),
+            ("max_call_writes".to_string(), max_call.writes.to_string()),
+            ("max_call_deletes".to_string(), max_call.deletes.to_string()),
+        ]);
+        Self { attributes }
+    }
+
+    /// Create the events for the storage I/O of the PoS operations of a
+    /// block, one per operation
+    pub fn block_events(stats: &PosIoStats, height: BlockHeight) -> Vec<Event> {
+        stats
+            .iter()
+            .map(|(op, op_stats)| Self::new(*op, op_stats, height).into())
+            .collect()
+    }
+}
+
+/// Validator state change event definition
+pub struct ValidatorStateEvent {
+    /// Validator state change event attributes
+    pub attributes: HashMap<String, String>,
+}
+
+impl From<ValidatorStateEvent> for Event {
+    fn from(state_event: ValidatorStateEvent) -> Self {
+        Self {
+            event_type: EventType::ValidatorState,
+            level: EventLevel::Block,
+            attributes: state_event.attributes,
+        }
+    }
+}
+
+impl ValidatorStateEvent {
+    /// Create a new event for a change of a validator's state made in the
+    /// block at the given height. A validator that has no state in the epoch
+    /// before the change, i.e. a new validator, is reported with the previous
+    /// state `none`.
+    pub fn new(change: &ValidatorStateChange, height: BlockHeight) -> Self {
+        let from = change
+            .from
+            .map(|state| state.to_string())
+            .unwrap_or_else(|| "none".to_string());
+        let attributes = HashMap::from([
+            ("height".to_string(), height.to_string()),
+            ("validator".to_string(), change.validator.to_string()),
+            ("epoch".to_string(), change.epoch.to_string()),
+            ("from".to_string(), from),
+            ("to".to_string(), change.to.to_string()),
+            ("cause".to_string(), change.cause.to_string()),
+        ]);
+        Self { attributes }
+    }
+}
@@ -2,26 +2,23 @@
 
 use std::collections::BTreeSet;
 
-use namada_core::ledger::storage_api::governance;
-// use borsh::BorshDeserialize;
-pub use namada_proof_of_stake;
+use itertools::Itertools;
+use namada_core::types::account::AccountPublicKeysMap;
 pub use namada_proof_of_stake::parameters::PosParams;
-// use namada_proof_of_stake::validation::validate;
-use namada_proof_of_stake::read_pos_params;
-pub use namada_proof_of_stake::types;
+use namada_proof_of_stake::read_validator_operator_key;
+use namada_proof_of_stake::validation::{
+    find_operator_actions, find_staking_pool_redeemers, validate,
+    ValidationError,
+};
+pub use namada_proof_of_stake::{self, types};
 use thiserror::Error;
 
-use super::is_params_key;
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
-// use crate::ledger::pos::{
-//     is_validator_address_raw_hash_key,
-//     is_validator_max_commission_rate_change_key,
-// };
 use crate::ledger::storage::{self as ledger_storage, StorageHasher};
-use crate::ledger::storage_api::StorageRead;
+use crate::ledger::storage_api::account;
 use crate::proto::Tx;
-use crate::types::address::{Address, InternalAddress};
-use crate::types::storage::{Key, KeySeg};
+use crate::types::address::Address;
+use crate::types::storage::Key;
 use crate::vm::WasmCacheAccess;
 
 #[allow(missing_docs)]
@@ -55,6 +52,38 @@ where
     pub fn new(ctx: Ctx<'a, DB, H, CA>) -> Self {
         Self { ctx }
     }
+
+    /// Is the tx signed by the validator's account keys or by its operator
+    /// key? Without an operator key, the authorization is left to the
+    /// validator's own VP.
+    fn is_signed_by_validator_or_operator(
+        &self,
+        validator: &Address,
+    ) -> Result<bool> {
+        let pre = self.ctx.pre();
+        let operator_key = match read_validator_operator_key(&pre, validator)? {
+            Some(operator_key) => operator_key,
+            None => return Ok(true),
+        };
+        let public_keys = account::public_keys_index_map(&pre, validator)?;
+        let threshold = account::threshold(&pre, validator)?.unwrap_or(1);
+        Ok(self
+            .ctx
+            .verify_signatures(public_keys, validator, threshold)?
+            || self.ctx.verify_signatures(
+                AccountPublicKeysMap::from_iter([operator_key]),
+                validator,
+                1,
+            )?)
+    }
+
+    /// Is the tx signed by the account keys of the given owner?
+    fn is_signed_by_owner(&self, owner: &Address) -> Result<bool> {
+        let pre = self.ctx.pre();
+        let public_keys = account::public_keys_index_map(&pre, owner)?;
+        let threshold = account::threshold(&pre, owner)?.unwrap_or(1);
+        Ok(self.ctx.verify_signatures(public_keys, owner, threshold)?)
+    }
 }
 
 impl<'a, DB, H, CA> NativeVp for PosVP<'a, DB, H, CA>
@@ -71,55 +100,46 @@ where
         keys_changed: &BTreeSet<Key>,
         _verifiers: &BTreeSet<Address>,
     ) -> Result<bool> {
-        // use validation::Data;
-        // use validation::DataUpdate::{self, *};
-        // use validation::ValidatorUpdate::*;
-
-        let addr = Address::Internal(InternalAddress::PoS);
-        // let mut changes: Vec<DataUpdate> = vec![];
-        let _current_epoch = self.ctx.pre().get_block_epoch()?;
-
         tracing::debug!("\nValidating PoS Tx\n");
 
-        for key in keys_changed {
-            if is_params_key(key) {
-                let data = if let Some(data) = tx_data.data() {
-                    data
-                } else {
-                    return Ok(false);
-                };
-                if !governance::is_proposal_accepted(&self.ctx.pre(), &data)
-                    .map_err(Error::NativeVpError)?
-                {
-                    return Ok(false);
-                }
-            } else if key.segments.get(0) == Some(&addr.to_db_key()) {
-                // Unknown changes to this address space are disallowed
-                // tracing::info!("PoS unrecognized key change {} rejected",
-                // key);
-                tracing::debug!(
-                    "PoS key change {} - No action is taken currently.",
-                    key
-                );
-                // return Ok(false);
-            } else {
-                tracing::debug!("PoS unrecognized key change {}", key);
-                // Unknown changes anywhere else are permitted
+        let tx_data = tx_data.data();
+        let mut errors = validate(
+            &self.ctx.pre(),
+            &self.ctx.post(),
+            keys_changed,
+            tx_data.as_deref(),
+        )?;
+        for validator in find_operator_actions(
+            &self.ctx.pre(),
+            &self.ctx.post(),
+            keys_changed,
+        )? {
+            if !self.is_signed_by_validator_or_operator(&validator)? {
+                errors.push(ValidationError::UnauthorizedOperatorAction(
+                    validator,
+                ));
             }
         }
-
-        let _params = read_pos_params(&self.ctx.pre())?;
-        // let errors = validate(&params, changes, current_epoch);
-        // Ok(if errors.is_empty() {
-        //     true
-        // } else {
-        //     tracing::info!(
-        //         "PoS validation errors:\n - {}",
-        //         errors.iter().format("\n - ")
-        //     );
-        //     false
-        // })
-        Ok(true)
+        for owner in find_staking_pool_redeemers(
+            &self.ctx.pre(),
+            &self.ctx.post(),
+            keys_changed,
+        )? {
+            if !self.is_signed_by_owner(&owner)? {
+                errors.push(ValidationError::UnauthorizedStakingPoolAction(
+                    owner,
+                ));
+            }
+        }
+        Ok(if errors.is_empty() {
+            true
+        } else {
+            tracing::info!(
+                "PoS validation errors:\n - {}",
+                errors.iter().format("\n - ")
+            );
+            false
+        })
     }
 }
 
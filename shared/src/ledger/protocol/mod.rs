@@ -25,6 +25,7 @@ use crate::ledger::native_vp::ibc::Ibc;
 use crate::ledger::native_vp::masp::MaspVp;
 use crate::ledger::native_vp::multitoken::MultitokenVp;
 use crate::ledger::native_vp::parameters::{self, ParametersVp};
+use crate::ledger::native_vp::vesting::VestingVp;
 use crate::ledger::native_vp::{self, NativeVp};
 use crate::ledger::pgf::PgfVp;
 use crate::ledger::pos::{self, PosVP};
@@ -93,6 +94,8 @@ pub enum Error {
     NutNativeVpError(native_vp::ethereum_bridge::nut::Error),
     #[error("MASP native VP error: {0}")]
     MaspNativeVpError(native_vp::masp::Error),
+    #[error("Vesting native VP error: {0}")]
+    VestingNativeVpError(native_vp::vesting::Error),
     #[error("Access to an internal address {0:?} is forbidden")]
     AccessForbidden(InternalAddress),
 }
@@ -243,7 +246,7 @@ where
     // Write wrapper tx hash to storage
     shell_params
         .wl_storage
-        .write_tx_hash(tx.header_hash())
+        .write_tx_hash(tx.header_hash(), tx.header.expiration)
         .expect("Error while writing tx hash to storage");
 
     // Charge fee before performing any fallible operations
@@ -674,9 +677,27 @@ where
             )
             .map_err(Error::ProtocolTxError)
         }
+        EthereumTxData::LivenessVext(ext) => {
+            let changed_keys = namada_proof_of_stake::record_liveness_report(
+                storage,
+                &ext.data.validator_addr,
+                &ext.data.offline_validators,
+            )
+            .map_err(|err| Error::ProtocolTxError(err.into()))?;
+            Ok(TxResult {
+                changed_keys,
+                ..Default::default()
+            })
+        }
+        EthereumTxData::ConsensusValidatorSetUpdates(_) => {
+            // The updates have already been applied to the consensus
+            // validator set, the tx only records them in the block
+            Ok(TxResult::default())
+        }
         EthereumTxData::EthereumEvents(_)
         | EthereumTxData::BridgePool(_)
-        | EthereumTxData::ValidatorSetUpdate(_) => {
+        | EthereumTxData::ValidatorSetUpdate(_)
+        | EthereumTxData::Liveness(_) => {
             // TODO(namada#198): implement this
             tracing::warn!(
                 "Attempt made to apply an unimplemented protocol transaction, \
@@ -1006,6 +1027,29 @@ where
                                 gas_meter = masp.ctx.gas_meter.into_inner();
                                 (result, masp.ctx.sentinel.into_inner())
                             }
+                            InternalAddress::Vesting => {
+                                let vesting = VestingVp { ctx };
+                                let result = vesting
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::VestingNativeVpError);
+                                // Take the gas meter and the sentinel back out
+                                // of the context
+                                gas_meter = vesting.ctx.gas_meter.into_inner();
+                                (result, vesting.ctx.sentinel.into_inner())
+                            }
+                            InternalAddress::StakingPool => {
+                                // The staking pool is validated by the PoS VP
+                                // Take the gas meter and the sentinel
+                                // back
+                                // out of the context
+                                gas_meter = ctx.gas_meter.into_inner();
+                                (
+                                    Ok(verifiers.contains(&Address::Internal(
+                                        InternalAddress::PoS,
+                                    ))),
+                                    ctx.sentinel.into_inner(),
+                                )
+                            }
                         };
 
                     accepted.map_err(|err| {
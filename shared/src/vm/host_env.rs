@@ -13,7 +13,7 @@ use namada_core::ledger::gas::{
 use namada_core::types::address::{ESTABLISHED_ADDRESS_BYTES_LEN, MASP};
 use namada_core::types::internal::KeyVal;
 use namada_core::types::storage::TX_INDEX_LENGTH;
-use namada_core::types::transaction::TxSentinel;
+use namada_core::types::transaction::{TxErrorPayload, TxSentinel};
 use namada_core::types::validity_predicate::VpSentinel;
 use thiserror::Error;
 
@@ -2091,6 +2091,31 @@ where
     sentinel.set_invalid_commitment();
 }
 
+/// Set the sentinel for a tx that failed with the given Borsh-encoded
+/// [`TxErrorPayload`], reported by the tx before aborting
+pub fn tx_set_error<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    payload_ptr: u64,
+    payload_len: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let (payload, gas) =
+        env.memory
+            .read_bytes(payload_ptr, payload_len as _)
+            .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+    let payload: TxErrorPayload = BorshDeserialize::try_from_slice(&payload)
+        .map_err(TxRuntimeError::EncodingError)?;
+    let sentinel = unsafe { env.ctx.sentinel.get() };
+    sentinel.set_failed(payload);
+    Ok(())
+}
+
 /// Verify a transaction signature
 #[allow(clippy::too_many_arguments)]
 pub fn tx_verify_tx_section_signature<MEM, DB, H, CA>(
@@ -6,7 +6,7 @@ use std::marker::PhantomData;
 use borsh::BorshDeserialize;
 use namada_core::ledger::gas::{GasMetering, TxGasMeter, WASM_MEMORY_PAGE_GAS};
 use namada_core::ledger::storage::write_log::StorageModification;
-use namada_core::types::transaction::TxSentinel;
+use namada_core::types::transaction::{TxErrorPayload, TxSentinel};
 use namada_core::types::validity_predicate::VpSentinel;
 use parity_wasm::elements;
 use thiserror::Error;
@@ -58,6 +58,8 @@ pub enum Error {
     MissingModuleEntrypoint(wasmer::ExportError),
     #[error("Failed running wasm with: {0}")]
     RuntimeError(wasmer::RuntimeError),
+    #[error("Transaction failed with: {0}")]
+    TxFailed(TxErrorPayload),
     #[error("Failed instantiating wasm module with: {0}")]
     // Boxed cause it's 128b
     InstantiationError(Box<wasmer::InstantiationError>),
@@ -209,6 +211,7 @@ where
             TxSentinel::InvalidCommitment => {
                 Error::MissingSection(err.to_string())
             }
+            TxSentinel::Failed(payload) => Error::TxFailed(payload),
         }
     })?;
 
@@ -18,6 +18,7 @@ use namada::types::control_flow::time::{Constant, Sleep};
 use namada::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
 use namada::types::ethereum_events::EthAddress;
 use namada::types::storage::{self, Epoch};
+use namada::types::voting_power::FractionalVotingPower;
 use namada::types::{address, token};
 use namada_apps::config::ethereum_bridge;
 use namada_core::ledger::eth_bridge::ADDRESS as BRIDGE_ADDRESS;
@@ -298,6 +299,7 @@ async fn test_bridge_pool_e2e() {
             genesis.ethereum_bridge_params = Some(EthereumBridgeParams {
                 eth_start_height: Default::default(),
                 min_confirmations: Default::default(),
+                vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
                 contracts: Contracts {
                     native_erc20: wnam(),
                     bridge: UpgradeableContract {
@@ -493,6 +495,7 @@ async fn test_wnam_transfer() -> Result<()> {
             // be violated is if we construct values
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
+        vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
         }),
         contracts: Contracts {
             native_erc20: wnam(),
@@ -600,6 +603,7 @@ fn test_configure_oracle_from_storage() -> Result<()> {
             // be violated is if we construct values
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
+        vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
         }),
         contracts: Contracts {
             native_erc20: EthAddress([1; 20]),
@@ -13,6 +13,7 @@ use namada::ledger::eth_bridge::{
 };
 use namada::types::address::{wnam, Address};
 use namada::types::ethereum_events::{EthAddress, Uint};
+use namada::types::voting_power::FractionalVotingPower;
 use namada_apps::config::ethereum_bridge;
 use namada_core::types::ethereum_events::{EthereumEvent, TransferToNamada};
 use namada_core::types::token;
@@ -87,6 +88,7 @@ pub fn setup_single_validator_test() -> Result<(Test, NamadaBgCmd)> {
             // be violated is if we construct values
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
+        vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
         }),
         contracts: Contracts {
             native_erc20: wnam(),
@@ -1471,7 +1471,7 @@ All unbonds total withdrawable: 412.000000\r",
 /// 7. Check the new validator's bonded stake
 #[test]
 fn pos_init_validator() -> Result<()> {
-    let pipeline_len = 1;
+    let pipeline_len = 2;
     let validator_stake = token::Amount::native_whole(100000_u64);
     let test = setup::network(
         |mut genesis, base_dir: &_| {
@@ -1479,7 +1479,7 @@ fn pos_init_validator() -> Result<()> {
             genesis.parameters.parameters.epochs_per_year = 31_536_000;
             genesis.parameters.parameters.max_expected_time_per_block = 1;
             genesis.parameters.pos_params.pipeline_len = pipeline_len;
-            genesis.parameters.pos_params.unbonding_len = 2;
+            genesis.parameters.pos_params.unbonding_len = 3;
             let genesis = setup::set_validators(
                 1,
                 genesis,
@@ -1,2 +1,3 @@
 mod masp;
+mod mock_node;
 mod setup;
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+use namada::ledger::parameters::EpochDuration;
+use namada::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
+use namada::types::time::DurationSecs;
+use test_log::test;
+
+use super::setup;
+
+/// In this test we verify that an epoch whose minimum number of blocks was
+/// reached only ends once its minimum duration has passed on the clock of
+/// the node.
+#[test]
+fn mock_node_time_travel() -> Result<()> {
+    let (mut node, _services) = setup::setup()?;
+    // Wait till epoch boundary
+    let epoch = node.next_epoch();
+
+    node.set_epoch_duration(EpochDuration {
+        min_num_of_blocks: 1,
+        min_duration: DurationSecs(3600),
+    });
+    for _ in 0..5 {
+        node.finalize_and_commit();
+    }
+    assert_eq!(node.current_epoch(), epoch);
+
+    node.advance_time(Duration::from_secs(3600));
+    // The epoch switch is delayed by a few blocks after its duration passed
+    for _ in 0..=EPOCH_SWITCH_BLOCKS_DELAY {
+        node.finalize_and_commit();
+    }
+    assert_eq!(node.current_epoch(), epoch.next());
+
+    Ok(())
+}
+
+/// In this test we verify that the blocks of the node are timestamped
+/// deterministically.
+#[test]
+fn mock_node_deterministic_clock() -> Result<()> {
+    let (node, _services) = setup::setup()?;
+
+    let block_time = node.block_time();
+    node.finalize_and_commit();
+    assert_eq!(node.block_time(), block_time + DurationSecs(1));
+
+    Ok(())
+}
+
+/// In this test we verify that the state of the node can be restored from a
+/// snapshot, several times, e.g. to run different scenarios from the same
+/// chain.
+#[test]
+fn mock_node_snapshot_restore() -> Result<()> {
+    let (mut node, _services) = setup::setup()?;
+    let epoch = node.next_epoch();
+    let snapshot = node.snapshot();
+
+    let next_epoch = node.next_epoch();
+    assert_eq!(next_epoch, epoch.next());
+
+    node.restore(&snapshot);
+    assert_eq!(node.current_epoch(), epoch);
+    // The restored node keeps producing blocks from the snapshot
+    assert_eq!(node.next_epoch(), next_epoch);
+
+    node.restore(&snapshot);
+    assert_eq!(node.current_epoch(), epoch);
+
+    Ok(())
+}
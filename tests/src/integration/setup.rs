@@ -2,6 +2,7 @@ use std::mem::ManuallyDrop;
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use color_eyre::eyre::{eyre, Result};
 use namada_apps::cli::args;
@@ -14,8 +15,8 @@ use namada_apps::config::TendermintMode;
 use namada_apps::facade::tendermint::Timeout;
 use namada_apps::facade::tendermint_proto::google::protobuf::Timestamp;
 use namada_apps::node::ledger::shell::testing::node::{
-    mock_services, MockNode, MockServicesCfg, MockServicesController,
-    MockServicesPackage,
+    mock_services, MockClock, MockNode, MockServicesCfg,
+    MockServicesController, MockServicesPackage,
 };
 use namada_apps::node::ledger::shell::testing::utils::TestDir;
 use namada_apps::node::ledger::shell::Shell;
@@ -28,6 +29,10 @@ use crate::e2e::setup::{copy_wasm_to_chain_dir, SINGLE_NODE_NET_GENESIS};
 /// Env. var for keeping temporary files created by the integration tests
 const ENV_VAR_KEEP_TEMP: &str = "NAMADA_INT_KEEP_TEMP";
 
+/// Env. var for seeding the randomness of the integration tests, e.g. to
+/// reproduce a failed run
+const ENV_VAR_RNG_SEED: &str = "NAMADA_INT_RNG_SEED";
+
 /// Setup a network with a single genesis validator node.
 pub fn setup() -> Result<(MockNode, MockServicesController)> {
     initialize_genesis()
@@ -102,9 +107,16 @@ pub fn initialize_genesis() -> Result<(MockNode, MockServicesController)> {
         // Ethereum bridge is enabled at genesis
         eth_bridge_params.is_some()
     };
+    let rng_seed = std::env::var(ENV_VAR_RNG_SEED).ok().map(|seed| {
+        seed.parse()
+            .unwrap_or_else(|_| panic!("{ENV_VAR_RNG_SEED} must be a u64"))
+    });
     let services_cfg = MockServicesCfg {
         auto_drive_services,
         enable_eth_oracle,
+        // Blocks are timestamped deterministically from the genesis time
+        clock: MockClock::fixed(genesis_time, Duration::from_secs(1)),
+        rng_seed,
     };
     finalize_wallet(&template_dir, &global_args, genesis);
     create_node(test_dir, global_args, keep_temp, services_cfg)
@@ -186,11 +198,14 @@ fn create_node(
             50 * 1024 * 1024, // 50 kiB
             50 * 1024 * 1024, // 50 kiB
         ))),
+        peers: vec![],
+        offline_validators: Default::default(),
         test_dir: ManuallyDrop::new(test_dir),
         keep_temp,
         services: Arc::new(services),
         results: Arc::new(Mutex::new(vec![])),
         auto_drive_services,
+        time_offset: Arc::new(Mutex::new(Default::default())),
     };
     let init_req =
         namada_apps::facade::tendermint::v0_37::abci::request::InitChain {
@@ -15,6 +15,7 @@ mod test_bridge_pool_vp {
     use namada::types::ethereum_events::EthAddress;
     use namada::types::key::{common, ed25519, SecretKey};
     use namada::types::token::Amount;
+    use namada::types::voting_power::FractionalVotingPower;
     use namada_apps::wallet::defaults::{albert_address, bertha_address};
     use namada_apps::wasm_loader;
     use namada_sdk::eth_bridge::{
@@ -68,9 +69,11 @@ mod test_bridge_pool_vp {
             erc20_whitelist: vec![Erc20WhitelistEntry {
                 token_address: wnam(),
                 token_cap: Amount::from_u64(TOKEN_CAP).native_denominated(),
+                min_confirmations: None,
             }],
             eth_start_height: Default::default(),
             min_confirmations: Default::default(),
+            vext_digest_quorum: FractionalVotingPower::TWO_THIRDS,
             contracts: Contracts {
                 native_erc20: wnam(),
                 bridge: UpgradeableContract {
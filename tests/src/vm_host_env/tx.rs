@@ -511,6 +511,7 @@ mod native_tx_host_env {
     native_host_fn!(tx_log_string(str_ptr: u64, str_len: u64));
     native_host_fn!(tx_charge_gas(used_gas: u64));
     native_host_fn!("non-result", tx_set_commitment_sentinel());
+    native_host_fn!(tx_set_error(payload_ptr: u64, payload_len: u64));
     native_host_fn!(tx_verify_tx_section_signature(
         hash_list_ptr: u64,
         hash_list_len: u64,
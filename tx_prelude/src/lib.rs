@@ -12,6 +12,7 @@ pub mod key;
 pub mod pgf;
 pub mod proof_of_stake;
 pub mod token;
+pub mod vesting;
 
 use core::slice;
 use std::marker::PhantomData;
@@ -372,6 +373,15 @@ pub fn tx_ibc_execute() {
     unsafe { namada_tx_ibc_execute() }
 }
 
+/// Report the error that made the tx fail to the host, so that it's surfaced
+/// with a machine-readable payload in the tx result
+pub fn set_tx_error(err: &Error) {
+    let payload = namada_proof_of_stake::tx_error_payload(err)
+        .unwrap_or_else(|| transaction::TxErrorPayload::new("Other", err));
+    let payload = payload.serialize_to_vec();
+    unsafe { namada_tx_set_error(payload.as_ptr() as _, payload.len() as _) }
+}
+
 /// Verify section signatures against the given list of keys
 pub fn verify_signatures_of_pks(
     ctx: &Ctx,
@@ -1,17 +1,36 @@
 //! Proof of Stake system integration with functions for transactions
 
+use std::collections::BTreeSet;
+
 use namada_core::types::dec::Dec;
 use namada_core::types::key::common;
 use namada_core::types::transaction::pos::BecomeValidator;
 use namada_core::types::{key, token};
+use namada_proof_of_stake::io_stats::{
+    with_io_stats, IoCounter, PosIoStats, PosOp,
+};
 pub use namada_proof_of_stake::parameters::PosParams;
-use namada_proof_of_stake::types::ValidatorMetaData;
+use namada_proof_of_stake::staking_pool::{
+    deposit_into_staking_pool, redeem_staking_pool_shares,
+    withdraw_from_staking_pool, write_staking_pool_validators,
+};
+use namada_proof_of_stake::state_changes::{
+    with_state_changes, StateChangeRecorder,
+};
+use namada_proof_of_stake::storage::{
+    io_stats_key, validator_state_changes_key,
+};
+use namada_proof_of_stake::types::{
+    ValidatorMetaData, ValidatorStateChange, ValidatorStateChangeCause,
+};
 use namada_proof_of_stake::{
     become_validator, bond_tokens, change_consensus_key,
-    change_validator_commission_rate, change_validator_metadata,
+    change_validator_commission_rate, change_validator_eth_keys,
+    change_validator_metadata, change_validator_operator_key,
     claim_reward_tokens, deactivate_validator, reactivate_validator,
-    read_pos_params, redelegate_tokens, unbond_tokens, unjail_validator,
-    withdraw_tokens,
+    read_pos_params, redelegate_tokens, reserve_consensus_key,
+    schedule_withdrawal, unbond_tokens, unjail_validator,
+    validator_eth_cold_key_handle, withdraw_tokens,
 };
 pub use namada_proof_of_stake::{parameters, types, ResultSlashing};
 
@@ -28,7 +47,9 @@ impl Ctx {
         amount: token::Amount,
     ) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
-        bond_tokens(self, source, validator, amount, current_epoch, None)
+        self.with_pos_io_stats(PosOp::Bond, |storage| {
+            bond_tokens(storage, source, validator, amount, current_epoch, None)
+        })
     }
 
     /// Unbond self-bonded tokens from a validator when `source` is `None`
@@ -41,7 +62,29 @@ impl Ctx {
         amount: token::Amount,
     ) -> EnvResult<ResultSlashing> {
         let current_epoch = self.get_block_epoch()?;
-        unbond_tokens(self, source, validator, amount, current_epoch, false)
+        self.with_pos_io_stats(PosOp::Unbond, |storage| {
+            unbond_tokens(
+                storage,
+                source,
+                validator,
+                amount,
+                current_epoch,
+                false,
+            )
+        })
+    }
+
+    /// Schedule the tokens unbonded in the current epoch from a self-bond to
+    /// a validator when `source` is `None` or equal to the `validator`
+    /// address, or from the `source` delegation to the `validator`, to be
+    /// withdrawn automatically once they become withdrawable.
+    pub fn schedule_withdrawal(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+    ) -> EnvResult<Epoch> {
+        let current_epoch = self.get_block_epoch()?;
+        schedule_withdrawal(self, source, validator, current_epoch)
     }
 
     /// Withdraw unbonded tokens from a self-bond to a validator when
@@ -53,7 +96,9 @@ impl Ctx {
         validator: &Address,
     ) -> EnvResult<token::Amount> {
         let current_epoch = self.get_block_epoch()?;
-        withdraw_tokens(self, source, validator, current_epoch)
+        self.with_pos_io_stats(PosOp::Withdraw, |storage| {
+            withdraw_tokens(storage, source, validator, current_epoch)
+        })
     }
 
     /// Change validator consensus key.
@@ -66,6 +111,57 @@ impl Ctx {
         change_consensus_key(self, validator, consensus_key, current_epoch)
     }
 
+    /// Reserve an unused consensus key for the `owner` account, which can then
+    /// use it to become a validator.
+    pub fn reserve_consensus_key(
+        &mut self,
+        owner: &Address,
+        consensus_key: &common::PublicKey,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        reserve_consensus_key(self, owner, consensus_key, current_epoch)
+    }
+
+    /// Change validator Ethereum bridge hot and cold keys.
+    pub fn change_validator_eth_keys(
+        &mut self,
+        validator: &Address,
+        eth_hot_key: &common::PublicKey,
+        eth_cold_key: &common::PublicKey,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        change_validator_eth_keys(
+            self,
+            validator,
+            eth_hot_key,
+            eth_cold_key,
+            current_epoch,
+        )
+    }
+
+    /// Set the validator's operator key, or remove it when `None`.
+    pub fn change_validator_operator_key(
+        &mut self,
+        validator: &Address,
+        operator_key: Option<&common::PublicKey>,
+    ) -> TxResult {
+        change_validator_operator_key(self, validator, operator_key)
+    }
+
+    /// Read the validator's Ethereum bridge cold key in the current epoch.
+    pub fn read_validator_eth_cold_key(
+        &self,
+        validator: &Address,
+    ) -> EnvResult<Option<common::PublicKey>> {
+        let current_epoch = self.get_block_epoch()?;
+        let params = read_pos_params(self)?;
+        validator_eth_cold_key_handle(validator).get(
+            self,
+            current_epoch,
+            &params,
+        )
+    }
+
     /// Change validator commission rate.
     pub fn change_validator_commission_rate(
         &mut self,
@@ -79,7 +175,10 @@ impl Ctx {
     /// Unjail a jailed validator and re-enter the validator sets.
     pub fn unjail_validator(&mut self, validator: &Address) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
-        unjail_validator(self, validator, current_epoch)
+        self.with_pos_state_changes(
+            ValidatorStateChangeCause::Unjail,
+            |storage| unjail_validator(storage, validator, current_epoch),
+        )
     }
 
     /// Redelegate bonded tokens from one validator to another one.
@@ -91,14 +190,16 @@ impl Ctx {
         amount: token::Amount,
     ) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
-        redelegate_tokens(
-            self,
-            owner,
-            src_validator,
-            dest_validator,
-            current_epoch,
-            amount,
-        )
+        self.with_pos_io_stats(PosOp::Redelegate, |storage| {
+            redelegate_tokens(
+                storage,
+                owner,
+                src_validator,
+                dest_validator,
+                current_epoch,
+                amount,
+            )
+        })
     }
 
     /// Claim available reward tokens
@@ -134,25 +235,30 @@ impl Ctx {
         let eth_hot_key = key::common::PublicKey::Secp256k1(eth_hot_key);
         let params = read_pos_params(self)?;
 
-        become_validator(
-            self,
-            namada_proof_of_stake::BecomeValidator {
-                params: &params,
-                address: &address,
-                consensus_key: &consensus_key,
-                protocol_key: &protocol_key,
-                eth_cold_key: &eth_cold_key,
-                eth_hot_key: &eth_hot_key,
-                current_epoch,
-                commission_rate,
-                max_commission_rate_change,
-                metadata: ValidatorMetaData {
-                    email,
-                    description,
-                    website,
-                    discord_handle,
-                },
-                offset_opt: None,
+        self.with_pos_state_changes(
+            ValidatorStateChangeCause::Registration,
+            |storage| {
+                become_validator(
+                    storage,
+                    namada_proof_of_stake::BecomeValidator {
+                        params: &params,
+                        address: &address,
+                        consensus_key: &consensus_key,
+                        protocol_key: &protocol_key,
+                        eth_cold_key: &eth_cold_key,
+                        eth_hot_key: &eth_hot_key,
+                        current_epoch,
+                        commission_rate,
+                        max_commission_rate_change,
+                        metadata: ValidatorMetaData {
+                            email,
+                            description,
+                            website,
+                            discord_handle,
+                        },
+                        offset_opt: None,
+                    },
+                )
             },
         )?;
 
@@ -162,13 +268,19 @@ impl Ctx {
     /// Deactivate validator
     pub fn deactivate_validator(&mut self, validator: &Address) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
-        deactivate_validator(self, validator, current_epoch)
+        self.with_pos_state_changes(
+            ValidatorStateChangeCause::Deactivation,
+            |storage| deactivate_validator(storage, validator, current_epoch),
+        )
     }
 
     /// Reactivate validator
     pub fn reactivate_validator(&mut self, validator: &Address) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
-        reactivate_validator(self, validator, current_epoch)
+        self.with_pos_state_changes(
+            ValidatorStateChangeCause::Reactivation,
+            |storage| reactivate_validator(storage, validator, current_epoch),
+        )
     }
 
     /// Change validator metadata.
@@ -194,4 +306,100 @@ impl Ctx {
             current_epoch,
         )
     }
+
+    /// Deposit tokens of the `owner` into the staking pool. Returns the number
+    /// of issued pool shares.
+    pub fn deposit_into_staking_pool(
+        &mut self,
+        owner: &Address,
+        amount: token::Amount,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        self.with_pos_state_changes(
+            ValidatorStateChangeCause::StakeChange,
+            |storage| {
+                deposit_into_staking_pool(storage, owner, amount, current_epoch)
+            },
+        )
+    }
+
+    /// Redeem staking pool shares of the `owner`. Returns the redeemed value.
+    pub fn redeem_staking_pool_shares(
+        &mut self,
+        owner: &Address,
+        shares: token::Amount,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        self.with_pos_state_changes(
+            ValidatorStateChangeCause::StakeChange,
+            |storage| {
+                redeem_staking_pool_shares(
+                    storage,
+                    owner,
+                    shares,
+                    current_epoch,
+                )
+            },
+        )
+    }
+
+    /// Withdraw the matured pooled unbonds of the `owner`. Returns the
+    /// withdrawn amount.
+    pub fn withdraw_from_staking_pool(
+        &mut self,
+        owner: &Address,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        withdraw_from_staking_pool(self, owner, current_epoch)
+    }
+
+    /// Replace the validators that the staking pool delegates to. Only allowed
+    /// in the code of an accepted governance proposal.
+    pub fn write_staking_pool_validators(
+        &mut self,
+        validators: &BTreeSet<Address>,
+    ) -> TxResult {
+        write_staking_pool_validators(self, validators)
+    }
+
+    /// Run a PoS operation that changes the stakes and add its storage I/O to
+    /// the stats of the tx, which are written to temporary storage for the
+    /// ledger to collect. The changes of the validators' states that the
+    /// operation makes are recorded too.
+    fn with_pos_io_stats<T>(
+        &mut self,
+        op: PosOp,
+        f: impl FnOnce(
+            &mut IoCounter<'_, StateChangeRecorder<'_, Self>>,
+        ) -> EnvResult<T>,
+    ) -> EnvResult<T> {
+        let (res, io) = self.with_pos_state_changes(
+            ValidatorStateChangeCause::StakeChange,
+            |storage| with_io_stats(storage, f),
+        )?;
+        let key = io_stats_key();
+        let mut stats: PosIoStats = self.read(&key)?.unwrap_or_default();
+        stats.entry(op).or_default().record(&io);
+        self.write_temp(&key, stats)?;
+        Ok(res)
+    }
+
+    /// Run a PoS operation and add the changes of the validators' states that
+    /// it makes to the changes of the tx, which are written to temporary
+    /// storage for the ledger to emit them as events.
+    fn with_pos_state_changes<T>(
+        &mut self,
+        cause: ValidatorStateChangeCause,
+        f: impl FnOnce(&mut StateChangeRecorder<'_, Self>) -> EnvResult<T>,
+    ) -> EnvResult<T> {
+        let (res, changes) = with_state_changes(self, cause, f)?;
+        if !changes.is_empty() {
+            let key = validator_state_changes_key();
+            let mut tx_changes: Vec<ValidatorStateChange> =
+                self.read(&key)?.unwrap_or_default();
+            tx_changes.extend(changes);
+            self.write_temp(&key, tx_changes)?;
+        }
+        Ok(res)
+    }
 }
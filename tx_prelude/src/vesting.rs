@@ -0,0 +1,28 @@
+//! Vesting accounts integration with functions for transactions
+
+use namada_core::ledger::storage_api::vesting;
+use namada_core::ledger::vesting::VestingSchedule;
+use namada_core::types::token;
+
+use super::*;
+
+impl Ctx {
+    /// Give the `owner` account a vesting schedule, funded by the `source`.
+    pub fn init_vesting(
+        &mut self,
+        source: &Address,
+        owner: &Address,
+        schedule: VestingSchedule,
+    ) -> TxResult {
+        vesting::init_vesting(self, source, owner, schedule)
+    }
+
+    /// Claim the unlocked tokens of the `owner` vesting account.
+    pub fn claim_vested(
+        &mut self,
+        owner: &Address,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        vesting::claim_vested(self, owner, current_epoch)
+    }
+}
@@ -122,6 +122,9 @@ pub mod tx {
         /// Set the sentinel for a wrong tx section commitment
         pub fn namada_tx_set_commitment_sentinel();
 
+        /// Report the Borsh-encoded error payload of a failing tx
+        pub fn namada_tx_set_error(payload_ptr: u64, payload_len: u64);
+
         // Verify the signatures of a tx
         pub fn namada_tx_verify_tx_section_signature(
             hash_list_ptr: u64,
@@ -6,12 +6,18 @@ pub mod tx_bond;
 pub mod tx_bridge_pool;
 #[cfg(feature = "tx_change_consensus_key")]
 pub mod tx_change_consensus_key;
+#[cfg(feature = "tx_change_eth_keys")]
+pub mod tx_change_eth_keys;
+#[cfg(feature = "tx_change_operator_key")]
+pub mod tx_change_operator_key;
 #[cfg(feature = "tx_change_validator_commission")]
 pub mod tx_change_validator_commission;
 #[cfg(feature = "tx_change_validator_metadata")]
 pub mod tx_change_validator_metadata;
 #[cfg(feature = "tx_claim_rewards")]
 pub mod tx_claim_rewards;
+#[cfg(feature = "tx_claim_vested")]
+pub mod tx_claim_vested;
 #[cfg(feature = "tx_deactivate_validator")]
 pub mod tx_deactivate_validator;
 #[cfg(feature = "tx_ibc")]
@@ -20,6 +26,14 @@ pub mod tx_ibc;
 pub mod tx_init_account;
 #[cfg(feature = "tx_init_proposal")]
 pub mod tx_init_proposal;
+#[cfg(feature = "tx_init_vesting")]
+pub mod tx_init_vesting;
+#[cfg(feature = "tx_pool_deposit")]
+pub mod tx_pool_deposit;
+#[cfg(feature = "tx_pool_redeem")]
+pub mod tx_pool_redeem;
+#[cfg(feature = "tx_pool_withdraw")]
+pub mod tx_pool_withdraw;
 #[cfg(feature = "tx_reactivate_validator")]
 pub mod tx_reactivate_validator;
 #[cfg(feature = "tx_redelegate")]
@@ -36,6 +50,8 @@ pub mod tx_unbond;
 pub mod tx_unjail_validator;
 #[cfg(feature = "tx_update_account")]
 pub mod tx_update_account;
+#[cfg(feature = "tx_update_erc20_whitelist")]
+pub mod tx_update_erc20_whitelist;
 #[cfg(feature = "tx_update_steward_commission")]
 pub mod tx_update_steward_commission;
 #[cfg(feature = "tx_vote_proposal")]
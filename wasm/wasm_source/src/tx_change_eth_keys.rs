@@ -0,0 +1,34 @@
+//! A tx for a validator to change their Ethereum bridge hot and cold keys.
+
+use namada_tx_prelude::transaction::pos::EthKeysChange;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 220000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let EthKeysChange {
+        validator,
+        eth_hot_key,
+        eth_cold_key,
+    } = transaction::pos::EthKeysChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode EthKeysChange")?;
+    let eth_hot_key = key::common::PublicKey::Secp256k1(eth_hot_key);
+    let eth_cold_key = key::common::PublicKey::Secp256k1(eth_cold_key);
+
+    // Check that the change is authorized by the current cold key and that
+    // the tx has been signed with both of the new keys
+    let current_cold_key = ctx
+        .read_validator_eth_cold_key(&validator)?
+        .ok_or_err_msg("Missing the validator's Ethereum cold key")?;
+    let mut all_pks = vec![eth_hot_key.clone(), eth_cold_key.clone()];
+    if current_cold_key != eth_cold_key {
+        all_pks.push(current_cold_key);
+    }
+    if !matches!(verify_signatures_of_pks(ctx, &signed, all_pks), Ok(true)) {
+        debug_log!("Ethereum keys signature verification failed");
+        panic!()
+    }
+
+    ctx.change_validator_eth_keys(&validator, &eth_hot_key, &eth_cold_key)
+}
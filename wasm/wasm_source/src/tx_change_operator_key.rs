@@ -0,0 +1,16 @@
+//! A tx for a validator to set or remove their operator key.
+
+use namada_tx_prelude::transaction::pos::OperatorKeyChange;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 220000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let OperatorKeyChange {
+        validator,
+        operator_key,
+    } = transaction::pos::OperatorKeyChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode OperatorKeyChange")?;
+    ctx.change_validator_operator_key(&validator, operator_key.as_ref())
+}
@@ -0,0 +1,13 @@
+//! A tx for the owner of a vesting account to claim its unlocked tokens.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let owner = Address::try_from_slice(&data[..])
+        .wrap_err("failed to decode an Address")?;
+    ctx.claim_vested(&owner)?;
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! A tx to give an account a vesting schedule, funded by the source.
+
+use namada_tx_prelude::transaction::vesting::InitVesting;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let InitVesting {
+        source,
+        owner,
+        schedule,
+    } = InitVesting::try_from_slice(&data[..])
+        .wrap_err("failed to decode InitVesting")?;
+    ctx.init_vesting(&source, &owner, schedule)
+}
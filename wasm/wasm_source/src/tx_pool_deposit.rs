@@ -0,0 +1,15 @@
+//! A tx to deposit tokens into the PoS staking pool.
+
+use namada_tx_prelude::transaction::pos::PoolDeposit;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let PoolDeposit { owner, amount } =
+        transaction::pos::PoolDeposit::try_from_slice(&data[..])
+            .wrap_err("failed to decode PoolDeposit")?;
+    ctx.deposit_into_staking_pool(&owner, amount)?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+//! A tx to redeem PoS staking pool shares.
+
+use namada_tx_prelude::transaction::pos::PoolRedeem;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 2645941)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let PoolRedeem { owner, shares } =
+        transaction::pos::PoolRedeem::try_from_slice(&data[..])
+            .wrap_err("failed to decode PoolRedeem")?;
+    ctx.redeem_staking_pool_shares(&owner, shares)?;
+    Ok(())
+}
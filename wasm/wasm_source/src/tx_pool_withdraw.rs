@@ -0,0 +1,13 @@
+//! A tx to withdraw the matured pooled unbonds from the PoS staking pool.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1119469)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let owner = Address::try_from_slice(&data[..])
+        .wrap_err("failed to decode an Address")?;
+    ctx.withdraw_from_staking_pool(&owner)?;
+    Ok(())
+}
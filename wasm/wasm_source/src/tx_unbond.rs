@@ -1,5 +1,6 @@
 //! A tx for a PoS unbond that removes staked tokens from a self-bond or a
-//! delegation to be withdrawn in or after unbonding epoch.
+//! delegation to be withdrawn in or after unbonding epoch. The withdrawal can
+//! also be scheduled to be executed automatically.
 
 use namada_tx_prelude::*;
 
@@ -18,6 +19,9 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
         &unbond.validator,
         unbond.amount,
     )?;
+    if unbond.auto_withdraw {
+        ctx.schedule_withdrawal(unbond.source.as_ref(), &unbond.validator)?;
+    }
     // TODO: would using debug_log! be useful?
 
     Ok(())
@@ -383,6 +387,7 @@ mod tests {
                     validator,
                     amount,
                     source,
+                    auto_withdraw: false,
                 }
             })
     }
@@ -0,0 +1,27 @@
+//! A governance proposal code to update the ERC20 token whitelist of the
+//! Ethereum bridge, with the updates carried by the proposal content
+
+use std::collections::BTreeMap;
+
+use namada_tx_prelude::eth_bridge::storage::whitelist;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let proposal_id = u64::try_from_slice(&data[..])
+        .wrap_err("failed to decode a proposal id")?;
+
+    let content_key = gov_storage::keys::get_content_key(proposal_id);
+    let content: BTreeMap<String, String> = ctx
+        .read(&content_key)?
+        .ok_or_err_msg("Missing proposal content")?;
+    let updates = whitelist::updates_from_proposal_content(&content)
+        .ok_or_err_msg("Missing or malformed ERC20 whitelist updates")?;
+
+    whitelist::apply_updates(ctx, &updates)
+}
@@ -7,7 +7,10 @@
 //! valid signature(s).
 //!
 //! For validator a tx to change a validator's commission rate or metadata
-//! requires a valid signature(s) only from the validator.
+//! requires a valid signature(s) only from the validator. If the validator has
+//! set an operator key, these changes and unjailing may instead be signed with
+//! the operator key, which is then checked by the PoS native VP. Setting the
+//! operator key itself requires a valid signature(s) from the validator.
 //!
 //! Any other storage key changes are allowed only with a valid signature.
 
@@ -69,6 +72,13 @@ fn validate_tx(
     let valid_sig = Lazy::new(|| {
         matches!(verify_signatures(ctx, &tx_data, &addr), Ok(true))
     });
+    // The signature of the operator key, if any, is verified by the PoS VP
+    let has_operator_key = Lazy::new(|| {
+        matches!(
+            proof_of_stake::read_validator_operator_key(&ctx.pre(), &addr),
+            Ok(Some(_))
+        )
+    });
 
     if !is_valid_tx(ctx, &tx_data)? {
         return reject();
@@ -133,7 +143,7 @@ fn validate_tx(
                     );
                 let valid_commission_rate_change = match comm {
                     Some((validator, _epoch)) => {
-                        *validator == addr && *valid_sig
+                        *validator == addr && (*valid_sig || *has_operator_key)
                     }
                     None => true,
                 };
@@ -142,6 +152,15 @@ fn validate_tx(
                 let metadata =
                     proof_of_stake::storage::is_validator_metadata_key(key);
                 let valid_metadata_change = match metadata {
+                    Some(address) => {
+                        *address == addr && (*valid_sig || *has_operator_key)
+                    }
+                    None => true,
+                };
+                // Operator key changes must be signed by the validator
+                let operator_key =
+                    proof_of_stake::storage::is_validator_operator_key_key(key);
+                let valid_operator_key_change = match operator_key {
                     Some(address) => *address == addr && *valid_sig,
                     None => true,
                 };
@@ -177,8 +196,11 @@ fn validate_tx(
                                 // Reactivation case
                                 || pre == ValidatorState::Inactive
                                     && post != ValidatorState::Inactive
+                                {
+                                    *address == addr && *valid_sig
+                                } else if
                                 // Unjail case
-                                || pre == ValidatorState::Jailed
+                                pre == ValidatorState::Jailed
                                     && matches!(
                                         post,
                                         ValidatorState::Consensus
@@ -186,7 +208,8 @@ fn validate_tx(
                                             | ValidatorState::BelowThreshold
                                     )
                                 {
-                                    *address == addr && *valid_sig
+                                    *address == addr
+                                        && (*valid_sig || *has_operator_key)
                                 } else {
                                     true
                                 }
@@ -205,6 +228,7 @@ fn validate_tx(
                     && valid_commission_rate_change
                     && valid_state_change
                     && valid_metadata_change
+                    && valid_operator_key_change
             }
             KeyType::GovernanceVote(voter) => {
                 if voter == &addr {
@@ -1023,6 +1047,152 @@ mod tests {
         );
     }
 
+    /// Test that the validator PoS actions that may be authorized by the
+    /// validator's operator key are accepted without the validator's
+    /// signature. The operator key signature is checked by the PoS VP.
+    #[test]
+    fn test_operator_validator_pos_action_accepted() {
+        // Init PoS genesis
+        let pos_params = PosParams::default();
+        let validator = address::testing::established_address_3();
+        let initial_stake = token::Amount::from_uint(10_098_123, 0).unwrap();
+        let consensus_key = key::testing::keypair_2().ref_to();
+        let protocol_key = key::testing::keypair_1().ref_to();
+        let commission_rate = Dec::new(5, 2).unwrap();
+        let max_commission_rate_change = Dec::new(1, 2).unwrap();
+
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: initial_stake,
+            consensus_key,
+            protocol_key,
+            commission_rate,
+            max_commission_rate_change,
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            metadata: Default::default(),
+        }];
+
+        init_pos(&genesis_validators[..], &pos_params, Epoch(0));
+
+        // Initialize a tx environment with the validator's account and
+        // operator keys committed
+        let mut tx_env = tx_host_env::take();
+        let public_key = key::testing::keypair_1().ref_to();
+        let operator_key = key::testing::keypair_4().ref_to();
+        tx_env.init_account_storage(&validator, vec![public_key], 1);
+        proof_of_stake::change_validator_operator_key(
+            &mut tx_env.wl_storage,
+            &validator,
+            Some(&operator_key),
+        )
+        .unwrap();
+        tx_env.commit_tx_and_block();
+
+        // Validator PoS actions that the operator may authorize
+        vp_host_env::init_from_tx(validator.clone(), tx_env, |_address| {
+            tx::ctx()
+                .change_validator_metadata(
+                    &validator,
+                    Some("email".to_owned()),
+                    Some("desc".to_owned()),
+                    Some("website".to_owned()),
+                    Some("discord".to_owned()),
+                    Some(Dec::new(6, 2).unwrap()),
+                )
+                .unwrap();
+        });
+
+        let vp_env = vp_host_env::take();
+        let mut tx_data = Tx::from_type(TxType::Raw);
+        tx_data.set_data(Data::new(vec![]));
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            validate_tx(&CTX, tx_data, validator, keys_changed, verifiers)
+                .unwrap()
+        );
+    }
+
+    /// Test that a change of the validator's operator key is rejected without
+    /// the validator's signature.
+    #[test]
+    fn test_unsigned_operator_key_change_rejected() {
+        // Init PoS genesis
+        let pos_params = PosParams::default();
+        let validator = address::testing::established_address_3();
+        let initial_stake = token::Amount::from_uint(10_098_123, 0).unwrap();
+        let consensus_key = key::testing::keypair_2().ref_to();
+        let protocol_key = key::testing::keypair_1().ref_to();
+        let commission_rate = Dec::new(5, 2).unwrap();
+        let max_commission_rate_change = Dec::new(1, 2).unwrap();
+
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: initial_stake,
+            consensus_key,
+            protocol_key,
+            commission_rate,
+            max_commission_rate_change,
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            metadata: Default::default(),
+        }];
+
+        init_pos(&genesis_validators[..], &pos_params, Epoch(0));
+
+        // Initialize a tx environment with the validator's account and
+        // operator keys committed
+        let mut tx_env = tx_host_env::take();
+        let public_key = key::testing::keypair_1().ref_to();
+        let operator_key = key::testing::keypair_4().ref_to();
+        tx_env.init_account_storage(&validator, vec![public_key], 1);
+        proof_of_stake::change_validator_operator_key(
+            &mut tx_env.wl_storage,
+            &validator,
+            Some(&operator_key),
+        )
+        .unwrap();
+        tx_env.commit_tx_and_block();
+
+        // Replace the operator key
+        vp_host_env::init_from_tx(validator.clone(), tx_env, |_address| {
+            let new_operator_key = key::testing::keypair_2().ref_to();
+            tx::ctx()
+                .change_validator_operator_key(
+                    &validator,
+                    Some(&new_operator_key),
+                )
+                .unwrap();
+        });
+
+        let vp_env = vp_host_env::take();
+        let mut tx_data = Tx::from_type(TxType::Raw);
+        tx_data.set_data(Data::new(vec![]));
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            !validate_tx(&CTX, tx_data, validator, keys_changed, verifiers)
+                .unwrap()
+        );
+    }
+
     /// Test that a transfer on with accounts other than self is accepted.
     #[test]
     fn test_transfer_between_other_parties_accepted() {